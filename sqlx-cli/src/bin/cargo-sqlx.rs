@@ -1,6 +1,6 @@
 use clap::Parser;
 use console::style;
-use sqlx_cli::Opt;
+use sqlx_cli::{Config, DriftFound, Opt, StaleQueryData};
 use std::process;
 
 // cargo invokes this binary as `cargo-sqlx sqlx <args>`
@@ -14,10 +14,35 @@ enum Cli {
 #[tokio::main]
 async fn main() {
     dotenvy::dotenv().ok();
-    let Cli::Sqlx(opt) = Cli::parse();
 
-    if let Err(error) = sqlx_cli::run(opt).await {
-        println!("{} {}", style("error:").bold().red(), error);
+    let config = Config::discover().unwrap_or_else(|error| {
+        println!("{} {:#}", style("error:").bold().red(), error);
+        process::exit(1);
+    });
+
+    let Cli::Sqlx(opt) = sqlx_cli::parse::<Cli>(&config);
+    let json = opt.json;
+
+    if let Err(error) = sqlx_cli::run(opt, &config).await {
+        if json {
+            println!("{}", sqlx_cli::error_json(&error));
+        }
+
+        // the differences were printed already
+        if let Some(drift) = error.downcast_ref::<DriftFound>() {
+            process::exit(drift.exit_code());
+        }
+
+        if let Some(stale) = error.downcast_ref::<StaleQueryData>() {
+            process::exit(stale.exit_code());
+        }
+
+        if json {
+            eprintln!("{} {}", style("error:").bold().red(), error);
+        } else {
+            println!("{} {}", style("error:").bold().red(), error);
+        }
+
         process::exit(1);
     }
 }
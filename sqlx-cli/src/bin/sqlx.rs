@@ -1,13 +1,39 @@
-use clap::Parser;
 use console::style;
-use sqlx_cli::Opt;
+use sqlx_cli::{Config, DriftFound, Opt, StaleQueryData};
 
 #[tokio::main]
 async fn main() {
     dotenvy::dotenv().ok();
+
+    let config = Config::discover().unwrap_or_else(|error| {
+        println!("{} {:#}", style("error:").bold().red(), error);
+        std::process::exit(1);
+    });
+
     // no special handling here
-    if let Err(error) = sqlx_cli::run(Opt::parse()).await {
-        println!("{} {}", style("error:").bold().red(), error);
+    let opt = sqlx_cli::parse::<Opt>(&config);
+    let json = opt.json;
+
+    if let Err(error) = sqlx_cli::run(opt, &config).await {
+        if json {
+            println!("{}", sqlx_cli::error_json(&error));
+        }
+
+        // the differences were printed already
+        if let Some(drift) = error.downcast_ref::<DriftFound>() {
+            std::process::exit(drift.exit_code());
+        }
+
+        if let Some(stale) = error.downcast_ref::<StaleQueryData>() {
+            std::process::exit(stale.exit_code());
+        }
+
+        if json {
+            eprintln!("{} {}", style("error:").bold().red(), error);
+        } else {
+            println!("{} {}", style("error:").bold().red(), error);
+        }
+
         std::process::exit(1);
     }
 }
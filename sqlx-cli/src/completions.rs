@@ -0,0 +1,117 @@
+//! `sqlx completions` and `sqlx man`, generated from the definition of the command line, for
+//! packaging.
+
+use anyhow::Context;
+use clap::CommandFactory;
+use clap_complete::Shell;
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+
+use crate::opt::Opt;
+
+/// The command line, without the defaults of `sqlx.toml`, which are only applied when parsing
+/// (see [`crate::parse`]), so that the generated scripts and pages don't depend on the current
+/// directory.
+pub fn command() -> clap::Command<'static> {
+    let mut cmd = Opt::command().name("sqlx");
+    cmd.build();
+    cmd
+}
+
+/// Writes the completion script of `shell` to `out`.
+///
+/// The paths to migrations, seeds and templates, and the flags of every subcommand, are
+/// completed by the shell.
+pub fn completions(shell: Shell, out: &mut dyn Write) {
+    clap_complete::generate(shell, &mut command(), "sqlx", out);
+}
+
+/// Writes the man page of `sqlx` to `out`, or with `out_dir`, the man pages of `sqlx` and of each
+/// of its subcommands to `sqlx.1`, `sqlx-migrate.1`, `sqlx-migrate-run.1`, etc. in it.
+pub fn man(out_dir: Option<&Path>, out: &mut dyn Write) -> anyhow::Result<()> {
+    let cmd = command();
+
+    match out_dir {
+        Some(out_dir) => {
+            fs::create_dir_all(out_dir)
+                .with_context(|| format!("failed to create `{}`", out_dir.display()))?;
+
+            write_man_pages(&cmd, out_dir)
+        }
+        None => Ok(clap_mangen::Man::new(cmd).render(out)?),
+    }
+}
+
+fn write_man_pages(cmd: &clap::Command<'static>, out_dir: &Path) -> anyhow::Result<()> {
+    let path = out_dir.join(format!("{}.1", cmd.get_name()));
+
+    let mut page = Vec::new();
+    clap_mangen::Man::new(cmd.clone()).render(&mut page)?;
+    fs::write(&path, page).with_context(|| format!("failed to write `{}`", path.display()))?;
+
+    for subcommand in cmd
+        .get_subcommands()
+        .filter(|subcommand| !subcommand.is_hide_set() && subcommand.get_name() != "help")
+    {
+        let name = format!("{}-{}", cmd.get_name(), subcommand.get_name());
+        write_man_pages(&subcommand.clone().name(name), out_dir)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn completions_mention_the_flags() {
+        for shell in [Shell::Bash, Shell::Zsh, Shell::Fish, Shell::PowerShell] {
+            let mut script = Vec::new();
+            completions(shell, &mut script);
+            let script = String::from_utf8(script).unwrap();
+
+            // fish spells the flags `-l database-url`
+            for flag in ["database-url", "source", "json", "migrate"] {
+                assert!(script.contains(flag), "{} completions lack {}", shell, flag);
+            }
+        }
+
+        // the migrations are completed as directories
+        let mut script = Vec::new();
+        completions(Shell::Zsh, &mut script);
+        let script = String::from_utf8(script).unwrap();
+        assert!(script.contains("--source=[Path to folder containing migrations"));
+        assert!(script.contains(":_files -/"));
+    }
+
+    #[test]
+    fn man_pages_are_written() -> anyhow::Result<()> {
+        let mut page = Vec::new();
+        man(None, &mut page)?;
+        let page = String::from_utf8(page)?;
+        assert!(page.contains(".TH sqlx 1"));
+        assert!(page.contains("migrate"));
+
+        let out_dir = std::env::temp_dir().join(format!("sqlx-man-{}", std::process::id()));
+        man(Some(&out_dir), &mut Vec::new())?;
+
+        for page in [
+            "sqlx.1",
+            "sqlx-migrate.1",
+            "sqlx-migrate-run.1",
+            "sqlx-database-drop.1",
+        ] {
+            assert!(out_dir.join(page).is_file(), "{} is missing", page);
+        }
+        assert!(!out_dir.join("sqlx-man.1").exists());
+
+        let page = fs::read_to_string(out_dir.join("sqlx-migrate-run.1"))?;
+        assert!(page.contains("database\\-url"));
+
+        fs::remove_dir_all(out_dir)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,482 @@
+use crate::output;
+use anyhow::{bail, Context};
+use console::style;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The name of the configuration file, found in the current directory or its closest ancestor
+/// which has one.
+pub const CONFIG_FILE: &str = "sqlx.toml";
+
+/// Defaults of the command-line arguments, read from `sqlx.toml`.
+///
+/// The arguments given on the command line, and the environment variables such as `DATABASE_URL`,
+/// override these.
+#[derive(Debug, Default, serde::Deserialize)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub struct Config {
+    /// The file the configuration was read from, if any
+    #[serde(skip)]
+    path: Option<PathBuf>,
+
+    /// The environment selected by `SQLX_ENV`
+    #[serde(skip)]
+    environment: Option<String>,
+
+    database_url: Option<String>,
+    environments: BTreeMap<String, EnvironmentConfig>,
+    migrate: MigrateConfig,
+    prepare: PrepareConfig,
+
+    /// Read by the query macros when they're expanded, e.g. by `prepare`
+    macros: Option<toml::Value>,
+}
+
+#[derive(Debug, Default, serde::Deserialize)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+struct EnvironmentConfig {
+    database_url: Option<String>,
+}
+
+#[derive(Debug, Default, serde::Deserialize)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+struct MigrateConfig {
+    source: Option<Sources>,
+    table: Option<String>,
+}
+
+#[derive(Debug, Default, serde::Deserialize)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub struct PrepareConfig {
+    pub workspace: Option<bool>,
+    pub incremental: Option<bool>,
+    pub features: Option<Vec<String>>,
+    pub all_features: Option<bool>,
+    pub no_default_features: Option<bool>,
+    pub profile: Option<String>,
+    pub target: Option<String>,
+}
+
+/// A migrations source, or a list of them.
+#[derive(Debug, serde::Deserialize)]
+#[serde(untagged)]
+enum Sources {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl Config {
+    /// Reads `sqlx.toml` from the current directory or its closest ancestor which has one, or
+    /// returns an empty configuration if there is none.
+    pub fn discover() -> anyhow::Result<Config> {
+        let current_dir = env::current_dir()?;
+
+        match current_dir
+            .ancestors()
+            .map(|dir| dir.join(CONFIG_FILE))
+            .find(|path| path.is_file())
+        {
+            Some(path) => Config::load(&path),
+            None => Ok(Config::default()),
+        }
+    }
+
+    pub fn load(path: &Path) -> anyhow::Result<Config> {
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+
+        let mut config: Config =
+            toml::from_str(&contents).with_context(|| format!("invalid {}", path.display()))?;
+
+        // relative migrations sources are relative to the configuration file
+        if let (Some(sources), Some(dir)) = (&mut config.migrate.source, path.parent()) {
+            match sources {
+                Sources::One(source) => *source = resolve_relative(dir, source),
+                Sources::Many(sources) => {
+                    for source in sources {
+                        *source = resolve_relative(dir, source);
+                    }
+                }
+            }
+        }
+
+        if let Ok(environment) = env::var("SQLX_ENV") {
+            if !config.environments.contains_key(&environment) {
+                bail!(
+                    "environment `{}` selected by `SQLX_ENV` isn't defined in {}",
+                    environment,
+                    path.display()
+                );
+            }
+
+            config.environment = Some(environment);
+        }
+
+        config.path = Some(path.to_path_buf());
+
+        Ok(config)
+    }
+
+    /// The database URL of the selected environment, or else of the file.
+    pub fn database_url(&self) -> Option<&str> {
+        self.environment
+            .as_ref()
+            .and_then(|environment| self.environments[environment].database_url.as_deref())
+            .or(self.database_url.as_deref())
+    }
+
+    pub fn migrations_sources(&self) -> Option<Vec<&str>> {
+        match self.migrate.source.as_ref()? {
+            Sources::One(source) => Some(vec![source]),
+            Sources::Many(sources) => Some(sources.iter().map(String::as_str).collect()),
+        }
+    }
+
+    pub fn migrations_table(&self) -> Option<&str> {
+        self.migrate.table.as_deref()
+    }
+
+    pub fn prepare(&self) -> &PrepareConfig {
+        &self.prepare
+    }
+
+    /// Sets the values of the file as the defaults of the arguments of `cmd` and its subcommands,
+    /// so the command line and the environment variables still override them.
+    pub fn apply_defaults<'a>(&'a self, mut cmd: clap::Command<'a>) -> clap::Command<'a> {
+        let ids: Vec<&str> = cmd.get_arguments().map(|arg| arg.get_id()).collect();
+
+        for id in ids {
+            match id {
+                "database-url" => {
+                    // clap checks the required arguments before applying the defaults
+                    if let Some(url) = self.database_url() {
+                        cmd = cmd.mut_arg(id, |arg| arg.default_value(url).required(false));
+                    }
+                }
+
+                // `--source` of `migrate`, and of `migrate add`
+                "source" => {
+                    if let Some(sources) = self.migrations_sources() {
+                        cmd = cmd.mut_arg(id, |arg| arg.default_value(sources[0]));
+                    }
+                }
+
+                "sources" => {
+                    if let Some(sources) = self.migrations_sources() {
+                        cmd = cmd.mut_arg(id, |arg| arg.default_values(&sources));
+                    }
+                }
+
+                "table" => {
+                    if let Some(table) = self.migrations_table() {
+                        cmd = cmd.mut_arg(id, |arg| arg.default_value(table));
+                    }
+                }
+
+                "features" => {
+                    if let Some(features) = &self.prepare.features {
+                        let features: Vec<_> = features.iter().map(String::as_str).collect();
+                        cmd = cmd.mut_arg(id, |arg| arg.default_values(&features));
+                    }
+                }
+
+                "profile" => {
+                    if let Some(profile) = &self.prepare.profile {
+                        cmd = cmd.mut_arg(id, |arg| arg.default_value(profile));
+                    }
+                }
+
+                "target" => {
+                    if let Some(target) = &self.prepare.target {
+                        cmd = cmd.mut_arg(id, |arg| arg.default_value(target));
+                    }
+                }
+
+                _ => {}
+            }
+        }
+
+        let subcommands: Vec<String> = cmd
+            .get_subcommands()
+            .map(|subcommand| subcommand.get_name().to_owned())
+            .collect();
+
+        for name in subcommands {
+            cmd = cmd.mut_subcommand(&*name, |subcommand| self.apply_defaults(subcommand));
+        }
+
+        cmd
+    }
+}
+
+fn resolve_relative(dir: &Path, path: &str) -> String {
+    dir.join(path).to_string_lossy().into_owned()
+}
+
+/// Prints the effective configuration, with where each value comes from.
+pub fn show(config: &Config) {
+    let file = || CONFIG_FILE.to_owned();
+    let default = || "default".to_owned();
+
+    let mut values: Vec<(&str, Value, String)> = Vec::new();
+
+    let environment_url = config
+        .environment
+        .as_ref()
+        .and_then(|environment| config.environments[environment].database_url.as_ref());
+
+    let database_url = if let Ok(url) = env::var("DATABASE_URL") {
+        Some((url, "environment variable `DATABASE_URL`".to_owned()))
+    } else if let Some(url) = environment_url {
+        Some((
+            url.clone(),
+            format!(
+                "[environments.{}] in {}",
+                config.environment.as_deref().unwrap_or_default(),
+                CONFIG_FILE
+            ),
+        ))
+    } else {
+        config.database_url.clone().map(|url| (url, file()))
+    };
+
+    values.push(match database_url {
+        Some((url, origin)) => ("database-url", redact_password(&url).into(), origin),
+        None => ("database-url", Value::Null, default()),
+    });
+
+    values.push(match config.migrations_sources() {
+        Some(sources) => ("migrate.source", sources.into(), file()),
+        None => ("migrate.source", vec!["migrations"].into(), default()),
+    });
+
+    values.push(match config.migrations_table() {
+        Some(table) => ("migrate.table", table.into(), file()),
+        None => (
+            "migrate.table",
+            sqlx::migrate::DEFAULT_MIGRATIONS_TABLE.into(),
+            default(),
+        ),
+    });
+
+    let prepare = &config.prepare;
+
+    for (key, value) in [
+        ("prepare.workspace", prepare.workspace),
+        ("prepare.incremental", prepare.incremental),
+        ("prepare.all-features", prepare.all_features),
+        ("prepare.no-default-features", prepare.no_default_features),
+    ] {
+        values.push(match value {
+            Some(value) => (key, value.into(), file()),
+            None => (key, false.into(), default()),
+        });
+    }
+
+    values.push(match &prepare.features {
+        Some(features) => ("prepare.features", features.clone().into(), file()),
+        None => ("prepare.features", Value::Array(Vec::new()), default()),
+    });
+
+    for (key, value) in [
+        ("prepare.profile", &prepare.profile),
+        ("prepare.target", &prepare.target),
+    ] {
+        values.push(match value {
+            Some(value) => (key, value.clone().into(), file()),
+            None => (key, Value::Null, default()),
+        });
+    }
+
+    if output::is_json() {
+        let values: serde_json::Map<_, _> = values
+            .into_iter()
+            .map(|(key, value, origin)| {
+                (
+                    key.to_owned(),
+                    serde_json::json!({ "value": value, "origin": origin }),
+                )
+            })
+            .collect();
+
+        output::print_json(&serde_json::json!({
+            "path": config.path,
+            "environment": config.environment,
+            "values": values,
+        }));
+
+        return;
+    }
+
+    match (&config.path, &config.environment) {
+        (Some(path), Some(environment)) => println!(
+            "# {}, environment `{}` (from `SQLX_ENV`)",
+            path.display(),
+            environment
+        ),
+        (Some(path), None) => println!("# {}", path.display()),
+        (None, _) => println!("# no {} found", CONFIG_FILE),
+    }
+
+    let values: Vec<_> = values
+        .into_iter()
+        .map(|(key, value, origin)| (key, display(&value), origin))
+        .collect();
+
+    let width = values
+        .iter()
+        .map(|(key, value, _)| key.len() + value.len())
+        .max()
+        .unwrap_or_default();
+
+    for (key, value, origin) in values {
+        println!(
+            "{} = {}{:pad$}  {}",
+            key,
+            value,
+            "",
+            style(format!("# {}", origin)).dim(),
+            pad = width - key.len() - value.len()
+        );
+    }
+}
+
+// TOML-like, e.g. `["a", "b"]`
+fn display(value: &Value) -> String {
+    match value {
+        Value::Null => "(not set)".to_owned(),
+        Value::Array(values) => {
+            let values: Vec<_> = values.iter().map(display).collect();
+            format!("[{}]", values.join(", "))
+        }
+        value => value.to_string(),
+    }
+}
+
+// don't print the password of the database URL
+pub(crate) fn redact_password(url: &str) -> String {
+    match url::Url::parse(url) {
+        Ok(mut parsed) if parsed.password().is_some() => {
+            let _ = parsed.set_password(Some("****"));
+            parsed.to_string()
+        }
+        _ => url.to_owned(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::opt::{Command, DatabaseCommand, MigrateCommand};
+    use crate::Opt;
+    use clap::{CommandFactory, FromArgMatches};
+
+    const CONFIG: &str = r#"
+database-url = "sqlite://dev.db"
+
+[environments.test]
+database-url = "sqlite://test.db"
+
+[migrate]
+source = ["migrations", "seeds"]
+table = "_migrations"
+
+[prepare]
+workspace = true
+features = ["postgres"]
+
+[[macros.type-overrides]]
+type = "INT8"
+column = "*user_id"
+rust = "crate::ids::UserId"
+"#;
+
+    fn parse(config: &Config, args: &[&str]) -> Opt {
+        let matches = config
+            .apply_defaults(Opt::command())
+            .try_get_matches_from(args)
+            .unwrap();
+
+        Opt::from_arg_matches(&matches).unwrap()
+    }
+
+    #[test]
+    fn it_parses_the_config() {
+        let config: Config = toml::from_str(CONFIG).unwrap();
+
+        assert_eq!(config.database_url(), Some("sqlite://dev.db"));
+        assert_eq!(
+            config.migrations_sources(),
+            Some(vec!["migrations", "seeds"])
+        );
+        assert_eq!(config.migrations_table(), Some("_migrations"));
+        assert_eq!(config.prepare().workspace, Some(true));
+        assert_eq!(config.prepare().incremental, None);
+
+        let config: Config = toml::from_str("[migrate]\nsource = \"db\"").unwrap();
+        assert_eq!(config.migrations_sources(), Some(vec!["db"]));
+        assert_eq!(config.database_url(), None);
+
+        assert!(toml::from_str::<Config>("database_url = \"sqlite://dev.db\"").is_err());
+    }
+
+    #[test]
+    fn it_selects_the_environment() {
+        let mut config: Config = toml::from_str(CONFIG).unwrap();
+        config.environment = Some("test".to_owned());
+
+        assert_eq!(config.database_url(), Some("sqlite://test.db"));
+    }
+
+    #[test]
+    fn it_applies_the_defaults() {
+        let config: Config = toml::from_str(CONFIG).unwrap();
+
+        let opt = parse(&config, &["sqlx", "migrate", "add", "first"]);
+        match opt.command {
+            Command::Migrate(migrate) => match migrate.command {
+                MigrateCommand::Add { source, .. } => {
+                    assert_eq!(source.resolve("default"), "migrations")
+                }
+                command => panic!("unexpected command {:?}", command),
+            },
+            command => panic!("unexpected command {:?}", command),
+        }
+
+        let opt = parse(&config, &["sqlx", "database", "setup"]);
+        match opt.command {
+            Command::Database(database) => match database.command {
+                DatabaseCommand::Setup {
+                    source,
+                    table,
+                    connect_opts,
+                    ..
+                } => {
+                    assert_eq!(source.resolve(), ["migrations", "seeds"]);
+                    assert_eq!(&*table, "_migrations");
+                    assert_eq!(connect_opts.database_url(), "sqlite://dev.db");
+                }
+                command => panic!("unexpected command {:?}", command),
+            },
+            command => panic!("unexpected command {:?}", command),
+        }
+
+        // the command line overrides the file
+        let opt = parse(
+            &config,
+            &["sqlx", "database", "create", "-D", "sqlite://other.db"],
+        );
+        match opt.command {
+            Command::Database(database) => match database.command {
+                DatabaseCommand::Create { connect_opts, .. } => {
+                    assert_eq!(connect_opts.database_url(), "sqlite://other.db")
+                }
+                command => panic!("unexpected command {:?}", command),
+            },
+            command => panic!("unexpected command {:?}", command),
+        }
+    }
+}
@@ -1,11 +1,17 @@
 use crate::migrate;
 use crate::opt::ConnectOpts;
+use crate::output;
 use console::style;
 use promptly::{prompt, ReadlineError};
 use sqlx::any::Any;
-use sqlx::migrate::MigrateDatabase;
+use sqlx::migrate::{CreateDatabaseOptions, MigrateDatabase};
+use sqlx::Connection;
+use std::time::Instant;
 
-pub async fn create(connect_opts: &ConnectOpts) -> anyhow::Result<()> {
+pub async fn create(
+    connect_opts: &ConnectOpts,
+    create_options: &CreateDatabaseOptions,
+) -> anyhow::Result<()> {
     // NOTE: only retry the idempotent action.
     // We're assuming that if this succeeds, then any following operations should also succeed.
     let exists = crate::retry_connect_errors(connect_opts, Any::database_exists).await?;
@@ -17,14 +23,44 @@ pub async fn create(connect_opts: &ConnectOpts) -> anyhow::Result<()> {
             std::sync::atomic::Ordering::Release,
         );
 
-        Any::create_database(&connect_opts.database_url).await?;
+        Any::create_database_with(connect_opts.database_url(), create_options).await?;
+    }
+
+    if output::is_json() {
+        output::print_json(&serde_json::json!({ "created": !exists }));
+    }
+
+    Ok(())
+}
+
+pub async fn ping(connect_opts: &ConnectOpts) -> anyhow::Result<()> {
+    let start = Instant::now();
+
+    let mut conn = crate::connect(connect_opts).await?;
+    conn.ping().await?;
+    conn.close().await?;
+
+    let elapsed = start.elapsed();
+
+    if output::is_json() {
+        output::print_json(&serde_json::json!({ "duration_ns": elapsed.as_nanos() as u64 }));
+    } else {
+        println!(
+            "Connected to the database {}",
+            style(format!("({:?})", elapsed)).dim()
+        );
     }
 
     Ok(())
 }
 
-pub async fn drop(connect_opts: &ConnectOpts, confirm: bool) -> anyhow::Result<()> {
-    if confirm && !ask_to_continue(connect_opts) {
+pub async fn drop(connect_opts: &ConnectOpts, confirm: bool, force: bool) -> anyhow::Result<()> {
+    if confirm
+        && !ask_to_continue(&format!(
+            "Drop database at {}?",
+            style(connect_opts.database_url()).cyan()
+        ))?
+    {
         return Ok(());
     }
 
@@ -33,38 +69,86 @@ pub async fn drop(connect_opts: &ConnectOpts, confirm: bool) -> anyhow::Result<(
     let exists = crate::retry_connect_errors(connect_opts, Any::database_exists).await?;
 
     if exists {
-        Any::drop_database(&connect_opts.database_url).await?;
+        if force {
+            Any::force_drop_database(connect_opts.database_url()).await?;
+        } else {
+            Any::drop_database(connect_opts.database_url()).await?;
+        }
+    }
+
+    if output::is_json() {
+        output::print_json(&serde_json::json!({ "dropped": exists }));
     }
 
     Ok(())
 }
 
 pub async fn reset(
-    migration_source: &str,
+    migration_sources: &[&str],
     connect_opts: &ConnectOpts,
+    create_options: &CreateDatabaseOptions,
+    table_name: &str,
     confirm: bool,
+    force: bool,
+    seed_paths: &[String],
+) -> anyhow::Result<()> {
+    drop(connect_opts, confirm, force).await?;
+    setup(
+        migration_sources,
+        connect_opts,
+        create_options,
+        table_name,
+        seed_paths,
+    )
+    .await
+}
+
+pub async fn setup(
+    migration_sources: &[&str],
+    connect_opts: &ConnectOpts,
+    create_options: &CreateDatabaseOptions,
+    table_name: &str,
+    seed_paths: &[String],
 ) -> anyhow::Result<()> {
-    drop(connect_opts, confirm).await?;
-    setup(migration_source, connect_opts).await
+    create(connect_opts, create_options).await?;
+    migrate::run(
+        migration_sources,
+        connect_opts,
+        table_name,
+        migrate::RunOptions {
+            seed_paths,
+            ..Default::default()
+        },
+    )
+    .await
 }
 
-pub async fn setup(migration_source: &str, connect_opts: &ConnectOpts) -> anyhow::Result<()> {
-    create(connect_opts).await?;
-    migrate::run(migration_source, connect_opts, false, false).await
+/// A command needs to be confirmed, but it can't be asked with `--json`.
+#[derive(Debug)]
+pub struct ConfirmationRequired;
+
+impl std::fmt::Display for ConfirmationRequired {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("confirmation can't be asked with `--json`; pass `-y` to skip it")
+    }
 }
 
-fn ask_to_continue(connect_opts: &ConnectOpts) -> bool {
+impl std::error::Error for ConfirmationRequired {}
+
+pub(crate) fn ask_to_continue(question: &str) -> anyhow::Result<bool> {
+    // the prompt would be mixed with the JSON documents
+    if output::is_json() {
+        return Err(ConfirmationRequired.into());
+    }
+
     loop {
-        let r: Result<String, ReadlineError> = prompt(format!(
-            "Drop database at {}? (y/n)",
-            style(&connect_opts.database_url).cyan()
-        ));
+        let r: Result<String, ReadlineError> = prompt(format!("{} (y/n)", question));
         match r {
             Ok(response) => {
                 if response == "n" || response == "N" {
-                    return false;
+                    return Ok(false);
                 } else if response == "y" || response == "Y" {
-                    return true;
+                    return Ok(true);
                 } else {
                     println!(
                         "Response not recognized: {}\nPlease type 'y' or 'n' and press enter.",
@@ -74,7 +158,7 @@ fn ask_to_continue(connect_opts: &ConnectOpts) -> bool {
             }
             Err(e) => {
                 println!("{}", e);
-                return false;
+                return Ok(false);
             }
         }
     }
@@ -4,107 +4,345 @@ use sqlx::{AnyConnection, Connection};
 use std::io;
 use std::time::Duration;
 
-use crate::opt::{Command, ConnectOpts, DatabaseCommand, MigrateCommand};
+use crate::opt::{
+    Command, ConfigCommand, ConnectOpts, DatabaseCommand, MigrateCommand, SchemaCommand,
+};
 
+mod completions;
+mod config;
 mod database;
 mod metadata;
 // mod migration;
 // mod migrator;
 mod migrate;
 mod opt;
+mod output;
 mod prepare;
+mod schema;
 
+pub use crate::completions::command;
+pub use crate::config::Config;
+pub use crate::migrate::DriftFound;
 pub use crate::opt::Opt;
+pub use crate::output::error_json;
+pub use crate::prepare::StaleQueryData;
+
+/// Parses the command line, with the defaults of `config`.
+pub fn parse<P: clap::Parser>(config: &Config) -> P {
+    let matches = config.apply_defaults(P::command()).get_matches();
+
+    P::from_arg_matches(&matches).unwrap_or_else(|e| e.exit())
+}
+
+pub async fn run(mut opt: Opt, config: &Config) -> Result<()> {
+    output::set_json(opt.json);
+
+    opt.command.resolve_connect_opts()?;
 
-pub async fn run(opt: Opt) -> Result<()> {
     match opt.command {
         Command::Migrate(migrate) => match migrate.command {
             MigrateCommand::Add {
                 source,
                 description,
                 reversible,
-            } => migrate::add(source.resolve(&migrate.source), &description, reversible).await?,
+                no_tx,
+                template,
+                down_template,
+                sequential,
+                timestamp,
+                width,
+            } => {
+                let versioning = if sequential {
+                    Some(migrate::Versioning::Sequential)
+                } else if timestamp {
+                    Some(migrate::Versioning::Timestamp)
+                } else {
+                    None
+                };
+
+                migrate::add(
+                    source.resolve(&migrate.source),
+                    &description,
+                    reversible,
+                    no_tx,
+                    template.as_deref(),
+                    down_template.as_deref(),
+                    versioning,
+                    width,
+                )
+                .await?
+            }
             MigrateCommand::Run {
                 source,
                 dry_run,
                 ignore_missing,
+                allow_out_of_order,
+                seeds,
+                table,
+                database_urls_file: _,
+                jobs,
+                keep_going,
+                dump_schema,
+                no_pg_dump,
                 connect_opts,
             } => {
-                migrate::run(
-                    source.resolve(&migrate.source),
+                let options = migrate::RunOptions {
+                    dry_run,
+                    ignore_missing: *ignore_missing,
+                    allow_out_of_order,
+                    seed_paths: &seeds,
+                };
+
+                if connect_opts.database_urls.len() > 1 {
+                    migrate::run_targets(
+                        &source.resolve(&migrate.source),
+                        &connect_opts,
+                        &table,
+                        options,
+                        jobs,
+                        keep_going,
+                    )
+                    .await?
+                } else {
+                    migrate::run(
+                        &source.resolve(&migrate.source),
+                        &connect_opts,
+                        &table,
+                        options,
+                    )
+                    .await?
+                }
+
+                // the databases have the same schema once migrated, so the first one is dumped
+                if let Some(file) = dump_schema {
+                    schema::dump(&connect_opts, &table, Some(&file), false, !no_pg_dump).await?
+                }
+            }
+            MigrateCommand::Revert {
+                source,
+                dry_run,
+                target_version,
+                all,
+                ignore_missing,
+                table,
+                connect_opts,
+            } => {
+                let target = if all {
+                    migrate::RevertTarget::All
+                } else {
+                    target_version.map_or(migrate::RevertTarget::Latest, |version| {
+                        migrate::RevertTarget::Version(version)
+                    })
+                };
+
+                migrate::revert(
+                    &source.resolve(&migrate.source),
                     &connect_opts,
+                    &table,
                     dry_run,
+                    target,
                     *ignore_missing,
                 )
                 .await?
             }
-            MigrateCommand::Revert {
+            MigrateCommand::Redo {
                 source,
                 dry_run,
                 ignore_missing,
+                table,
                 connect_opts,
             } => {
-                migrate::revert(
-                    source.resolve(&migrate.source),
+                migrate::redo(
+                    &source.resolve(&migrate.source),
                     &connect_opts,
+                    &table,
                     dry_run,
                     *ignore_missing,
                 )
                 .await?
             }
+            MigrateCommand::Repair {
+                source,
+                dry_run,
+                confirmation,
+                table,
+                connect_opts,
+            } => {
+                migrate::repair(
+                    &source.resolve(&migrate.source),
+                    &connect_opts,
+                    &table,
+                    dry_run,
+                    !confirmation.yes,
+                )
+                .await?
+            }
+            MigrateCommand::Baseline {
+                source,
+                version,
+                dry_run,
+                confirmation,
+                table,
+                connect_opts,
+            } => {
+                migrate::baseline(
+                    &source.resolve(&migrate.source),
+                    &connect_opts,
+                    &table,
+                    version,
+                    dry_run,
+                    !confirmation.yes,
+                )
+                .await?
+            }
             MigrateCommand::Info {
                 source,
+                table,
                 connect_opts,
-            } => migrate::info(source.resolve(&migrate.source), &connect_opts).await?,
+            } => migrate::info(&source.resolve(&migrate.source), &connect_opts, &table).await?,
+            MigrateCommand::Verify {
+                source,
+                allowed,
+                ignore_missing,
+                table,
+                connect_opts,
+            } => {
+                migrate::verify(
+                    &source.resolve(&migrate.source),
+                    &connect_opts,
+                    &table,
+                    &allowed,
+                    *ignore_missing,
+                )
+                .await?
+            }
             MigrateCommand::BuildScript { source, force } => {
                 migrate::build_script(source.resolve(&migrate.source), force)?
             }
         },
 
         Command::Database(database) => match database.command {
-            DatabaseCommand::Create { connect_opts } => database::create(&connect_opts).await?,
+            DatabaseCommand::Create {
+                create_opts,
+                connect_opts,
+            } => database::create(&connect_opts, &create_opts.options()).await?,
+            DatabaseCommand::Ping { connect_opts } => database::ping(&connect_opts).await?,
             DatabaseCommand::Drop {
                 confirmation,
+                force,
                 connect_opts,
-            } => database::drop(&connect_opts, !confirmation.yes).await?,
+            } => database::drop(&connect_opts, !confirmation.yes, force).await?,
             DatabaseCommand::Reset {
                 confirmation,
+                force,
                 source,
+                table,
+                seeds,
+                create_opts,
                 connect_opts,
-            } => database::reset(&source, &connect_opts, !confirmation.yes).await?,
+            } => {
+                database::reset(
+                    &source.resolve(),
+                    &connect_opts,
+                    &create_opts.options(),
+                    &table,
+                    !confirmation.yes,
+                    force,
+                    &seeds,
+                )
+                .await?
+            }
             DatabaseCommand::Setup {
                 source,
+                table,
+                seeds,
+                create_opts,
                 connect_opts,
-            } => database::setup(&source, &connect_opts).await?,
+            } => {
+                database::setup(
+                    &source.resolve(),
+                    &connect_opts,
+                    &create_opts.options(),
+                    &table,
+                    &seeds,
+                )
+                .await?
+            }
         },
 
         Command::Prepare {
-            check: false,
-            merged,
+            check,
+            workspace,
+            incremental,
+            force,
+            prune,
+            mut build_opts,
             args,
             connect_opts,
-        } => prepare::run(&connect_opts, merged, args).await?,
+        } => {
+            // the flags can only turn on what `sqlx.toml` leaves off
+            let prepare = config.prepare();
+            let workspace = workspace || prepare.workspace == Some(true);
+            let incremental = workspace && (incremental || prepare.incremental == Some(true));
+            build_opts.all_features |= prepare.all_features == Some(true);
+            build_opts.no_default_features |= prepare.no_default_features == Some(true);
 
-        Command::Prepare {
-            check: true,
-            merged,
-            args,
-            connect_opts,
-        } => prepare::check(&connect_opts, merged, args).await?,
+            if check {
+                prepare::check(
+                    &connect_opts,
+                    workspace,
+                    incremental,
+                    force,
+                    &build_opts,
+                    args,
+                )
+                .await?
+            } else {
+                prepare::run(
+                    &connect_opts,
+                    workspace,
+                    incremental,
+                    force,
+                    prune,
+                    &build_opts,
+                    args,
+                )
+                .await?
+            }
+        }
+
+        Command::Schema(opt) => match opt.command {
+            SchemaCommand::Dump {
+                file,
+                check,
+                no_pg_dump,
+                table,
+                connect_opts,
+            } => schema::dump(&connect_opts, &table, file.as_deref(), check, !no_pg_dump).await?,
+        },
+
+        Command::Config(opt) => match opt.command {
+            ConfigCommand::Show => config::show(config),
+        },
+
+        Command::Completions { shell } => completions::completions(shell, &mut io::stdout()),
+
+        Command::Man { out_dir } => completions::man(out_dir.as_deref(), &mut io::stdout())?,
     };
 
     Ok(())
 }
 
-/// Attempt to connect to the database server, retrying up to `ops.connect_timeout`.
+/// Attempt to connect to the database server, retrying up to `ops.connect_timeout`, and at most
+/// `ops.connect_retries` times.
 async fn connect(opts: &ConnectOpts) -> sqlx::Result<AnyConnection> {
     retry_connect_errors(opts, AnyConnection::connect).await
 }
 
 /// Attempt an operation that may return errors like `ConnectionRefused`,
-/// retrying up until `ops.connect_timeout`.
+/// retrying up until `ops.connect_timeout`, and at most `ops.connect_retries` times.
 ///
-/// The closure is passed `&ops.database_url` for easy composition.
+/// The closure is passed the database URL for easy composition.
 async fn retry_connect_errors<'a, F, Fut, T>(
     opts: &'a ConnectOpts,
     mut connect: F,
@@ -113,14 +351,22 @@ where
     F: FnMut(&'a str) -> Fut,
     Fut: Future<Output = sqlx::Result<T>> + 'a,
 {
+    let mut retries = 0;
+
     backoff::future::retry(
         backoff::ExponentialBackoffBuilder::new()
             .with_max_elapsed_time(Some(Duration::from_secs(opts.connect_timeout)))
             .build(),
         || {
-            connect(&opts.database_url).map_err(|e| -> backoff::Error<sqlx::Error> {
+            let can_retry = match opts.connect_retries {
+                Some(max_retries) => retries < max_retries,
+                None => true,
+            };
+            retries += 1;
+
+            connect(opts.database_url()).map_err(move |e| -> backoff::Error<sqlx::Error> {
                 match e {
-                    sqlx::Error::Io(ref ioe) => match ioe.kind() {
+                    sqlx::Error::Io(ref ioe) if can_retry => match ioe.kind() {
                         io::ErrorKind::ConnectionRefused
                         | io::ErrorKind::ConnectionReset
                         | io::ErrorKind::ConnectionAborted => {
@@ -12,11 +12,14 @@ use std::{
 /// The minimal amount of package information we care about
 ///
 /// The package's `name` is used to `cargo clean -p` specific crates while the `src_paths` are
-/// are used to trigger recompiles of packages within the workspace
+/// are used to trigger recompiles of packages within the workspace, and with the `manifest_path`
+/// to fingerprint their sources, together with the `features` resolved for the build
 #[derive(Debug)]
 pub struct Package {
     name: String,
     src_paths: Vec<PathBuf>,
+    manifest_path: PathBuf,
+    features: Vec<String>,
 }
 
 impl Package {
@@ -27,6 +30,14 @@ impl Package {
     pub fn src_paths(&self) -> &[PathBuf] {
         &self.src_paths
     }
+
+    pub fn manifest_path(&self) -> &Path {
+        &self.manifest_path
+    }
+
+    pub fn features(&self) -> &[String] {
+        &self.features
+    }
 }
 
 impl From<&MetadataPackage> for Package {
@@ -37,8 +48,15 @@ impl From<&MetadataPackage> for Package {
             .iter()
             .map(|target| target.src_path.clone().into_std_path_buf())
             .collect();
-
-        Self { name, src_paths }
+        let manifest_path = package.manifest_path.clone().into_std_path_buf();
+
+        Self {
+            name,
+            src_paths,
+            manifest_path,
+            // filled in from the resolved dependency graph
+            features: Vec::new(),
+        }
     }
 }
 
@@ -56,6 +74,8 @@ pub struct Metadata {
     ///
     /// Typically `target` at the workspace root, but can be overridden
     target_directory: PathBuf,
+    /// The root directory of the workspace, or of the package outside of a workspace
+    workspace_root: PathBuf,
 }
 
 impl Metadata {
@@ -75,6 +95,10 @@ impl Metadata {
         &self.target_directory
     }
 
+    pub fn workspace_root(&self) -> &Path {
+        &self.workspace_root
+    }
+
     /// Gets all dependents (direct and transitive) of `id`
     pub fn all_dependents_of(&self, id: &MetadataId) -> BTreeSet<&MetadataId> {
         let mut dependents = BTreeSet::new();
@@ -106,6 +130,7 @@ impl FromStr for Metadata {
             workspace_members,
             resolve,
             target_directory,
+            workspace_root,
             ..
         } = serde_json::from_str(s)?;
 
@@ -119,6 +144,10 @@ impl FromStr for Metadata {
         let resolve =
             resolve.context("Resolving the dependency graph failed (old version of cargo)")?;
         for node in resolve.nodes {
+            if let Some(package) = packages.get_mut(&node.id) {
+                package.features = node.features;
+            }
+
             for dep in node.deps {
                 let dependent = node.id.clone();
                 let dependency = dep.pkg;
@@ -130,12 +159,14 @@ impl FromStr for Metadata {
         }
 
         let target_directory = target_directory.into_std_path_buf();
+        let workspace_root = workspace_root.into_std_path_buf();
 
         Ok(Self {
             packages,
             workspace_members,
             reverse_deps,
             target_directory,
+            workspace_root,
         })
     }
 }
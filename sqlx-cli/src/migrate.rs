@@ -1,23 +1,167 @@
+use crate::config::redact_password;
+use crate::database::ask_to_continue;
 use crate::opt::ConnectOpts;
+use crate::output::{self, say};
 use anyhow::{bail, Context};
-use chrono::Utc;
-use console::style;
-use sqlx::migrate::{AppliedMigration, Migrate, MigrateError, MigrationType, Migrator};
-use std::borrow::Cow;
+use chrono::{SecondsFormat, Utc};
+use console::{style, Term};
+use futures::{stream, StreamExt};
+use sqlx::any::AnyKind;
+use sqlx::migrate::{
+    AppliedMigration, ChecksumRepair, DriftKind, Migrate, MigrateError, Migration, MigrationEvent,
+    MigrationPlan, MigrationProblem, MigrationType, Migrator, Seed, NO_TRANSACTION,
+};
+use sqlx::AnyConnection;
 use std::collections::{HashMap, HashSet};
 use std::fmt::Write;
 use std::fs::{self, File};
-use std::path::Path;
-use std::time::Duration;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// How the versions of new migrations are numbered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Versioning {
+    /// The current time, e.g. `20220801120000`.
+    Timestamp,
+
+    /// One after the latest migration, e.g. `0042`.
+    Sequential,
+}
+
+impl Versioning {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Versioning::Timestamp => "timestamp",
+            Versioning::Sequential => "sequential",
+        }
+    }
+}
+
+/// The file of a migrations directory which saves how new migrations are numbered.
+const CONFIG_FILE: &str = ".sqlx-config";
+
+/// The width sequential versions are zero-padded to, unless another one is saved.
+const DEFAULT_WIDTH: usize = 4;
+
+/// How new migrations are numbered, as saved in the config file of a migrations directory.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+struct MigrationsConfig {
+    versioning: Option<Versioning>,
+
+    /// The width sequential versions are zero-padded to.
+    width: Option<usize>,
+}
+
+/// Read how new migrations are numbered from the config file of the migrations directory, if
+/// it was saved.
+fn read_config(migration_source: &str) -> anyhow::Result<MigrationsConfig> {
+    let path = Path::new(migration_source).join(CONFIG_FILE);
+
+    let config = match fs::read_to_string(&path) {
+        Ok(config) => config,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return Ok(MigrationsConfig::default())
+        }
+        Err(e) => return Err(e).context(format!("Failed to read {}", path.display())),
+    };
+
+    parse_config(&config).with_context(|| format!("Invalid {}", path.display()))
+}
+
+fn parse_config(config: &str) -> anyhow::Result<MigrationsConfig> {
+    let mut parsed = MigrationsConfig::default();
+
+    for line in config.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        match line.split_once('=').map(|(k, v)| (k.trim(), v.trim())) {
+            Some(("versioning", "timestamp")) => parsed.versioning = Some(Versioning::Timestamp),
+            Some(("versioning", "sequential")) => parsed.versioning = Some(Versioning::Sequential),
+            Some(("versioning", other)) => bail!(
+                "expected `versioning` to be `timestamp` or `sequential`, got {:?}",
+                other
+            ),
+            Some(("width", width)) => match width.parse() {
+                Ok(width) if width > 0 => parsed.width = Some(width),
+                _ => bail!("expected `width` to be a positive number, got {:?}", width),
+            },
+            _ => bail!(
+                "expected `versioning = <timestamp|sequential>` or `width = <WIDTH>`, got {:?}",
+                line
+            ),
+        }
+    }
+
+    Ok(parsed)
+}
+
+fn write_config(migration_source: &str, config: MigrationsConfig) -> anyhow::Result<()> {
+    let path = Path::new(migration_source).join(CONFIG_FILE);
+
+    let mut contents = String::new();
+
+    if let Some(versioning) = config.versioning {
+        contents.push_str(&format!(
+            "# how `sqlx migrate add` numbers new migrations: timestamp or sequential\nversioning = {}\n",
+            versioning.as_str()
+        ));
+    }
+
+    if let Some(width) = config.width {
+        contents.push_str(&format!(
+            "# the width sequential versions are zero-padded to\nwidth = {}\n",
+            width
+        ));
+    }
+
+    fs::write(&path, contents).with_context(|| format!("Failed to write {}", path.display()))
+}
+
+/// Read the template of new migrations, given explicitly or found in the migrations directory.
+fn read_template(
+    migration_source: &str,
+    template: Option<&str>,
+    default_name: &str,
+) -> anyhow::Result<Option<String>> {
+    let path = match template {
+        Some(template) => Path::new(template).to_owned(),
+        None => {
+            let path = Path::new(migration_source).join(default_name);
+
+            if !path.is_file() {
+                return Ok(None);
+            }
+
+            path
+        }
+    };
+
+    let template = fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read template {}", path.display()))?;
+
+    Ok(Some(template))
+}
+
+/// Replace the placeholders of a migration template.
+fn render_template(template: &str, name: &str, version: &str, timestamp: &str) -> String {
+    template
+        .replace("{{name}}", name)
+        .replace("{{version}}", version)
+        .replace("{{timestamp}}", timestamp)
+}
 
 fn create_file(
     migration_source: &str,
     file_prefix: &str,
     description: &str,
     migration_type: MigrationType,
-) -> anyhow::Result<()> {
-    use std::path::PathBuf;
-
+    no_tx: bool,
+    content: Option<&str>,
+) -> anyhow::Result<PathBuf> {
     let mut file_name = file_prefix.to_string();
     file_name.push_str("_");
     file_name.push_str(&description.replace(' ', "_"));
@@ -27,19 +171,29 @@ fn create_file(
     path.push(migration_source);
     path.push(&file_name);
 
-    println!("Creating {}", style(path.display()).cyan());
+    say!("Creating {}", style(path.display()).cyan());
 
     let mut file = File::create(&path).context("Failed to create migration file")?;
 
-    std::io::Write::write_all(&mut file, migration_type.file_content().as_bytes())?;
+    if no_tx {
+        std::io::Write::write_all(&mut file, format!("{}\n", NO_TRANSACTION).as_bytes())?;
+    }
+
+    let content = content.unwrap_or_else(|| migration_type.file_content());
+    std::io::Write::write_all(&mut file, content.as_bytes())?;
 
-    Ok(())
+    Ok(path)
 }
 
 pub async fn add(
     migration_source: &str,
     description: &str,
     reversible: bool,
+    no_tx: bool,
+    template: Option<&str>,
+    down_template: Option<&str>,
+    versioning: Option<Versioning>,
+    width: Option<usize>,
 ) -> anyhow::Result<()> {
     fs::create_dir_all(migration_source).context("Unable to create migrations directory")?;
 
@@ -51,33 +205,113 @@ pub async fn add(
     let migrator = Migrator::new(Path::new(migration_source)).await?;
     // This checks if all existing migrations are of the same type as the reverisble flag passed
     for migration in migrator.iter() {
+        if migration.migration_type.is_repeatable() {
+            continue;
+        }
+
         if migration.migration_type.is_reversible() != reversible {
             bail!(MigrateError::InvalidMixReversibleAndSimple);
         }
     }
 
+    // the numbering is saved once chosen, so that all the migrations of the directory follow it
+    let saved = read_config(migration_source)?;
+    let config_path = || Path::new(migration_source).join(CONFIG_FILE);
+
+    let versioning = match (versioning, saved.versioning) {
+        (Some(versioning), Some(saved)) if versioning != saved => bail!(
+            "the migrations in {} are numbered with `versioning = {}` of {}; edit it to change that",
+            migration_source,
+            saved.as_str(),
+            config_path().display()
+        ),
+        (versioning, saved) => versioning.or(saved),
+    };
+
+    let width = match (width, saved.width) {
+        (Some(width), Some(saved)) if width != saved => bail!(
+            "the migrations in {} are zero-padded with `width = {}` of {}; edit it to change that",
+            migration_source,
+            saved,
+            config_path().display()
+        ),
+        (width, saved) => width.or(saved),
+    };
+
+    if width.is_some() && versioning != Some(Versioning::Sequential) {
+        bail!("the width only applies to sequential versions; pass `--sequential`");
+    }
+
+    let config = MigrationsConfig { versioning, width };
+
+    if config != saved {
+        write_config(migration_source, config)?;
+    }
+
     let dt = Utc::now();
-    let file_prefix = dt.format("%Y%m%d%H%M%S").to_string();
+    let file_prefix = match versioning.unwrap_or(Versioning::Timestamp) {
+        Versioning::Timestamp => dt.format("%Y%m%d%H%M%S").to_string(),
+        Versioning::Sequential => {
+            let latest = migrator
+                .iter()
+                .filter(|m| !m.migration_type.is_repeatable())
+                .map(|m| m.version)
+                .max()
+                .unwrap_or(0);
+
+            format!(
+                "{:0width$}",
+                latest + 1,
+                width = width.unwrap_or(DEFAULT_WIDTH)
+            )
+        }
+    };
+
+    let timestamp = dt.to_rfc3339_opts(SecondsFormat::Secs, true);
+    let render = |template: Option<String>| {
+        template.map(|template| render_template(&template, description, &file_prefix, &timestamp))
+    };
+
+    let up = render(read_template(
+        migration_source,
+        template,
+        ".template.up.sql",
+    )?);
+
+    let mut files = Vec::new();
+
     if reversible {
-        create_file(
+        let down = render(read_template(
+            migration_source,
+            down_template,
+            ".template.down.sql",
+        )?);
+
+        files.push(create_file(
             migration_source,
             &file_prefix,
             description,
             MigrationType::ReversibleUp,
-        )?;
-        create_file(
+            no_tx,
+            up.as_deref(),
+        )?);
+        files.push(create_file(
             migration_source,
             &file_prefix,
             description,
             MigrationType::ReversibleDown,
-        )?;
+            no_tx,
+            down.as_deref(),
+        )?);
     } else {
-        create_file(
+        files.push(create_file(
             migration_source,
             &file_prefix,
             description,
             MigrationType::Simple,
-        )?;
+            no_tx,
+            up.as_deref(),
+        )?);
     }
 
     if !has_existing_migrations {
@@ -87,7 +321,7 @@ pub async fn add(
             "".to_string()
         };
 
-        print!(
+        say!(
             r#"
 Congratulations on creating your first migration!
 
@@ -99,12 +333,15 @@ sqlx::migrate!({}).run(<&your_pool OR &mut your_connection>).await?;
 Note that the compiler won't pick up new migrations if no Rust source files have changed.
 You can create a Cargo build script to work around this with `sqlx migrate build-script`.
 
-See: https://docs.rs/sqlx/0.5/sqlx/macro.migrate.html
-"#,
+See: https://docs.rs/sqlx/0.5/sqlx/macro.migrate.html"#,
             quoted_source
         );
     }
 
+    if output::is_json() {
+        output::print_json(&serde_json::json!({ "files": files }));
+    }
+
     Ok(())
 }
 
@@ -116,61 +353,263 @@ fn short_checksum(checksum: &[u8]) -> String {
     s
 }
 
-pub async fn info(migration_source: &str, connect_opts: &ConnectOpts) -> anyhow::Result<()> {
-    let migrator = Migrator::new(Path::new(migration_source)).await?;
-    let mut conn = crate::connect(&connect_opts).await?;
+/// Whether a migration listed by `sqlx migrate info` is applied.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+enum Status {
+    Installed,
+    Pending,
+}
+
+/// A migration as listed by `sqlx migrate info`.
+#[derive(Debug, serde::Serialize)]
+struct MigrationInfo {
+    /// `None` for a repeatable migration.
+    version: Option<i64>,
+    description: String,
+    status: Status,
+    /// Installed after a migration with a later version, or pending but older than the latest
+    /// applied migration.
+    out_of_order: bool,
+    checksum: String,
+    /// The checksum recorded when the migration was applied, if it differs from `checksum`.
+    applied_checksum: Option<String>,
+    applied_by: Option<String>,
+    hostname: Option<String>,
+    sqlx_version: Option<String>,
+}
+
+pub async fn info(
+    migration_sources: &[&str],
+    connect_opts: &ConnectOpts,
+    table_name: &str,
+) -> anyhow::Result<()> {
+    let migrator = Migrator::new(migration_sources).await?;
+    warn_mixed_migrations(&migrator);
+    let mut conn = crate::connect(connect_opts).await?;
+
+    let infos = migration_infos(&migrator, &mut conn, table_name).await?;
+
+    if output::is_json() {
+        output::print_json(&serde_json::json!({ "migrations": infos }));
+        return Ok(());
+    }
+
+    for info in &infos {
+        let status = match info.status {
+            Status::Installed if info.applied_checksum.is_some() => {
+                style("installed (different checksum)").red()
+            }
+            Status::Installed if info.out_of_order => style("installed (out of order)").magenta(),
+            Status::Installed => style("installed").green(),
+            Status::Pending if info.out_of_order => style("pending (out of order)").magenta(),
+            Status::Pending if info.applied_checksum.is_some() => {
+                style("pending (changed)").yellow()
+            }
+            Status::Pending => style("pending").yellow(),
+        };
+
+        let version = match info.version {
+            Some(version) => version.to_string(),
+            None => "r".to_owned(),
+        };
+
+        println!("{}/{} {}", style(version).cyan(), status, info.description);
+
+        if let Some(applied_on) = describe_applied_on(
+            info.applied_by.as_deref(),
+            info.hostname.as_deref(),
+            info.sqlx_version.as_deref(),
+        ) {
+            println!("    {}", style(applied_on).dim());
+        }
+
+        match &info.applied_checksum {
+            Some(applied_checksum) if info.version.is_some() => {
+                println!("applied migration had checksum {}", applied_checksum);
+                println!("local migration has checksum   {}", info.checksum);
+            }
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
 
-    conn.ensure_migrations_table().await?;
+/// List the migrations and whether they're applied, with the repeatable migrations last.
+async fn migration_infos(
+    migrator: &Migrator,
+    conn: &mut AnyConnection,
+    table_name: &str,
+) -> anyhow::Result<Vec<MigrationInfo>> {
+    conn.ensure_migrations_table(table_name).await?;
 
     let applied_migrations: HashMap<_, _> = conn
-        .list_applied_migrations()
+        .list_applied_migrations(table_name)
         .await?
         .into_iter()
         .map(|m| (m.version, m))
         .collect();
 
+    let latest_applied = applied_migrations.keys().copied().max();
+
+    // migrations installed after one with a later version
+    let table = quote_table_name(conn, table_name);
+    let out_of_order: HashSet<i64> = sqlx::query_scalar(&format!(
+        "SELECT version FROM {} m WHERE EXISTS ( \
+            SELECT 1 FROM {} later \
+            WHERE later.version > m.version AND later.installed_on < m.installed_on \
+        )",
+        table, table
+    ))
+    .fetch_all(&mut *conn)
+    .await?
+    .into_iter()
+    .collect();
+
+    let mut infos = Vec::new();
+
     for migration in migrator.iter() {
-        if migration.migration_type.is_down_migration() {
-            // Skipping down migrations
+        if migration.migration_type.is_down_migration() || migration.migration_type.is_repeatable()
+        {
+            // Skipping down migrations, and repeatable migrations which are listed last
             continue;
         }
 
         let applied = applied_migrations.get(&migration.version);
 
-        let (status_msg, mismatched_checksum) = if let Some(applied) = applied {
-            if applied.checksum != migration.checksum {
-                (style("installed (different checksum)").red(), true)
-            } else {
-                (style("installed").green(), false)
-            }
+        let (status, out_of_order) = if applied.is_some() {
+            (Status::Installed, out_of_order.contains(&migration.version))
         } else {
-            (style("pending").yellow(), false)
+            (
+                Status::Pending,
+                matches!(latest_applied, Some(latest) if migration.version < latest),
+            )
         };
 
-        println!(
-            "{}/{} {}",
-            style(migration.version).cyan(),
-            status_msg,
-            migration.description
-        );
+        infos.push(MigrationInfo {
+            version: Some(migration.version),
+            description: migration.description.to_string(),
+            status,
+            out_of_order,
+            checksum: short_checksum(&migration.checksum),
+            applied_checksum: applied
+                .filter(|applied| applied.checksum != migration.checksum)
+                .map(|applied| short_checksum(&applied.checksum)),
+            applied_by: applied.and_then(|applied| applied.applied_by.clone()),
+            hostname: applied.and_then(|applied| applied.hostname.clone()),
+            sqlx_version: applied.and_then(|applied| applied.sqlx_version.clone()),
+        });
+    }
 
-        if mismatched_checksum {
-            println!(
-                "applied migration had checksum {}",
-                short_checksum(
-                    &applied
-                        .map(|a| a.checksum.clone())
-                        .unwrap_or_else(|| Cow::Owned(vec![]))
-                ),
-            );
-            println!(
-                "local migration has checksum   {}",
-                short_checksum(&migration.checksum)
-            )
+    let applied_repeatable: HashMap<_, _> = conn
+        .list_applied_repeatable_migrations(table_name)
+        .await?
+        .into_iter()
+        .map(|m| (m.description, m.checksum))
+        .collect();
+
+    for migration in migrator.iter() {
+        if !migration.migration_type.is_repeatable() {
+            continue;
         }
+
+        let applied_checksum = applied_repeatable.get(&migration.description);
+
+        infos.push(MigrationInfo {
+            version: None,
+            description: migration.description.to_string(),
+            status: match applied_checksum {
+                Some(checksum) if *checksum == migration.checksum => Status::Installed,
+                _ => Status::Pending,
+            },
+            out_of_order: false,
+            checksum: short_checksum(&migration.checksum),
+            applied_checksum: applied_checksum
+                .filter(|checksum| **checksum != migration.checksum)
+                .map(|checksum| short_checksum(checksum)),
+            applied_by: None,
+            hostname: None,
+            sqlx_version: None,
+        });
     }
 
-    Ok(())
+    Ok(infos)
+}
+
+/// Describe who applied a migration, where and with which version of SQLx, as far as it was
+/// recorded, e.g. `applied by alice on build-01 with SQLx 0.6.1`.
+fn describe_applied_on(
+    applied_by: Option<&str>,
+    hostname: Option<&str>,
+    sqlx_version: Option<&str>,
+) -> Option<String> {
+    let mut parts = Vec::new();
+
+    if let Some(applied_by) = applied_by {
+        parts.push(format!("by {}", applied_by));
+    }
+
+    if let Some(hostname) = hostname {
+        parts.push(format!("on {}", hostname));
+    }
+
+    if let Some(sqlx_version) = sqlx_version {
+        parts.push(format!("with SQLx {}", sqlx_version));
+    }
+
+    if parts.is_empty() {
+        None
+    } else {
+        Some(format!("applied {}", parts.join(" ")))
+    }
+}
+
+/// Warn if the migrations mix reversible and simple migrations, which `migrate add` refuses but
+/// which may be added by hand.
+fn warn_mixed_migrations(migrator: &Migrator) {
+    let versioned = || {
+        migrator
+            .iter()
+            .filter(|m| !m.migration_type.is_repeatable())
+    };
+
+    if versioned().any(|m| m.migration_type.is_reversible())
+        && versioned().any(|m| !m.migration_type.is_reversible())
+    {
+        say!(
+            "{} the migrations mix reversible and simple migrations; \
+             `sqlx migrate revert` can't revert the simple ones",
+            style("warning:").yellow()
+        );
+    }
+}
+
+/// Quote the name of the migrations table for the database of `conn`.
+fn quote_table_name(conn: &AnyConnection, table_name: &str) -> String {
+    let quote = match conn.kind() {
+        #[cfg(feature = "mysql")]
+        AnyKind::MySql => '`',
+        _ => '"',
+    };
+
+    table_name
+        .split('.')
+        .map(|ident| {
+            let escaped = ident.replace(quote, &format!("{}{}", quote, quote));
+            format!("{}{}{}", quote, escaped, quote)
+        })
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+/// The version of a migration as it's printed, which is `r` for repeatable migrations.
+fn display_version(migration: &Migration) -> String {
+    if migration.migration_type.is_repeatable() {
+        "r".to_owned()
+    } else {
+        migration.version.to_string()
+    }
 }
 
 fn validate_applied_migrations(
@@ -182,7 +621,11 @@ fn validate_applied_migrations(
         return Ok(());
     }
 
-    let migrations: HashSet<_> = migrator.iter().map(|m| m.version).collect();
+    let migrations: HashSet<_> = migrator
+        .iter()
+        .filter(|m| !m.migration_type.is_repeatable())
+        .map(|m| m.version)
+        .collect();
 
     for applied_migration in applied_migrations {
         if !migrations.contains(&applied_migration.version) {
@@ -193,149 +636,1346 @@ fn validate_applied_migrations(
     Ok(())
 }
 
+/// The settings of `sqlx migrate run`, for one database or several.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct RunOptions<'a> {
+    /// List the migrations which would be applied, instead of applying them.
+    pub dry_run: bool,
+    pub ignore_missing: bool,
+    pub allow_out_of_order: bool,
+
+    /// The files or directories of seeds to run after the migrations.
+    pub seed_paths: &'a [String],
+}
+
 pub async fn run(
-    migration_source: &str,
+    migration_sources: &[&str],
     connect_opts: &ConnectOpts,
-    dry_run: bool,
-    ignore_missing: bool,
+    table_name: &str,
+    options: RunOptions<'_>,
 ) -> anyhow::Result<()> {
-    let migrator = Migrator::new(Path::new(migration_source)).await?;
+    let json = output::is_json();
+
+    let migrator = run_migrator(migration_sources, table_name, options).await?;
+
     let mut conn = crate::connect(connect_opts).await?;
 
-    conn.ensure_migrations_table().await?;
+    if options.dry_run {
+        let plan = migrator.plan(&mut conn).await?;
 
-    let version = conn.dirty_version().await?;
-    if let Some(version) = version {
-        bail!(MigrateError::Dirty(version));
+        if json {
+            output::print_json(&plan_json(&plan, &migrator));
+        } else {
+            print_plan(&plan, &migrator);
+        }
+
+        return Ok(());
     }
 
-    let applied_migrations = conn.list_applied_migrations().await?;
-    validate_applied_migrations(&applied_migrations, &migrator, ignore_missing)?;
+    let term = Term::stderr();
+    // what is in progress is shown on a line of its own, replaced once it's done
+    let live = !json && term.is_term();
+    let applied = Mutex::new(Vec::new());
+    let seeds = Mutex::new(Vec::new());
 
-    let applied_migrations: HashMap<_, _> = applied_migrations
-        .into_iter()
-        .map(|m| (m.version, m))
-        .collect();
+    if live {
+        let _ = term.write_str("Waiting for the migrations lock...");
+    }
 
-    for migration in migrator.iter() {
-        if migration.migration_type.is_down_migration() {
-            // Skipping down migrations
-            continue;
-        }
+    let res = migrator
+        .run_with_progress(&mut conn, |event| match event {
+            MigrationEvent::LockAcquired if live => {
+                let _ = term.clear_line();
+            }
+
+            MigrationEvent::MigrationStarted { migration } if live => {
+                let _ = term.write_str(&format!(
+                    "Applying {}/{} {}...",
+                    display_version(migration),
+                    migration.migration_type.label(),
+                    migration.description
+                ));
+            }
+
+            MigrationEvent::MigrationFinished {
+                migration,
+                elapsed,
+                success,
+            } => {
+                if live {
+                    let _ = term.clear_line();
+                }
 
-        match applied_migrations.get(&migration.version) {
-            Some(applied_migration) => {
-                if migration.checksum != applied_migration.checksum {
-                    bail!(MigrateError::VersionMismatch(migration.version));
+                if json {
+                    applied
+                        .lock()
+                        .unwrap()
+                        .push(run_json(migration, elapsed, success));
+                } else {
+                    println!(
+                        "{} {}/{} {} {}",
+                        if success {
+                            style("Applied")
+                        } else {
+                            style("Failed").red()
+                        },
+                        style(display_version(migration)).cyan(),
+                        style(migration.migration_type.label()).green(),
+                        migration.description,
+                        style(format!("({:?})", elapsed)).dim()
+                    );
                 }
             }
-            None => {
-                let elapsed = if dry_run {
-                    Duration::new(0, 0)
+
+            // the migrations were applied, so a failed seed is reported as such
+            MigrationEvent::SeedFinished { seed, elapsed } => {
+                if json {
+                    seeds.lock().unwrap().push(seed_json(seed, elapsed));
                 } else {
-                    conn.apply(migration).await?
-                };
-                let text = if dry_run { "Can apply" } else { "Applied" };
-
-                println!(
-                    "{} {}/{} {} {}",
-                    text,
-                    style(migration.version).cyan(),
-                    style(migration.migration_type.label()).green(),
-                    migration.description,
-                    style(format!("({:?})", elapsed)).dim()
-                );
+                    println!(
+                        "Seeded {} {}",
+                        seed.name,
+                        style(format!("({:?})", elapsed)).dim()
+                    );
+                }
+            }
+
+            MigrationEvent::Completed { applied, elapsed } if !json => {
+                if applied == 0 {
+                    println!("No migrations to apply");
+                } else {
+                    println!("Applied {} migration(s) in {:?}", applied, elapsed);
+                }
             }
+
+            _ => {}
+        })
+        .await;
+
+    if live {
+        let _ = term.clear_line();
+    }
+
+    if json {
+        let report = serde_json::json!({
+            "applied": applied.into_inner().unwrap(),
+            "seeds": seeds.into_inner().unwrap(),
+            "error": res.as_ref().err().map(|e| e.to_string()),
+        });
+
+        output::print_json(&report);
+    }
+
+    Ok(res?)
+}
+
+/// The migrator of `migrate run`, with its options and seeds.
+async fn run_migrator(
+    migration_sources: &[&str],
+    table_name: &str,
+    options: RunOptions<'_>,
+) -> anyhow::Result<Migrator> {
+    let mut migrator = Migrator::new(migration_sources).await?;
+    warn_mixed_migrations(&migrator);
+    migrator.set_ignore_missing(options.ignore_missing);
+    migrator.set_allow_out_of_order(options.allow_out_of_order);
+    migrator.set_table_name(table_name.to_owned());
+
+    for path in options.seed_paths {
+        let seeds = Seed::from_path(path)
+            .await
+            .map_err(|e| anyhow::anyhow!("while reading seeds from {}: {}", path, e))?;
+
+        for seed in seeds {
+            migrator.add_seed(seed);
         }
     }
 
-    Ok(())
+    Ok(migrator)
 }
 
-pub async fn revert(
-    migration_source: &str,
-    connect_opts: &ConnectOpts,
-    dry_run: bool,
-    ignore_missing: bool,
-) -> anyhow::Result<()> {
-    let migrator = Migrator::new(Path::new(migration_source)).await?;
-    let mut conn = crate::connect(&connect_opts).await?;
+fn run_json(migration: &Migration, elapsed: Duration, success: bool) -> serde_json::Value {
+    let repeatable = migration.migration_type.is_repeatable();
 
-    conn.ensure_migrations_table().await?;
+    serde_json::json!({
+        "version": if repeatable { None } else { Some(migration.version) },
+        "repeatable": repeatable,
+        "description": migration.description,
+        "success": success,
+        "duration_ns": elapsed.as_nanos() as u64,
+    })
+}
 
-    let version = conn.dirty_version().await?;
-    if let Some(version) = version {
-        bail!(MigrateError::Dirty(version));
+fn seed_json(seed: &Seed, elapsed: Duration) -> serde_json::Value {
+    serde_json::json!({
+        "name": seed.name,
+        "duration_ns": elapsed.as_nanos() as u64,
+    })
+}
+
+/// Runs the migrations against several databases, e.g. the shards of a schema, `jobs` at the
+/// same time, each under its own lock. Unless `keep_going`, the databases not started yet are
+/// skipped once one fails.
+///
+/// With [`RunOptions::dry_run`], the plan of each database is listed instead, and the databases
+/// whose applied migrations differ from those of most databases are reported.
+pub async fn run_targets(
+    migration_sources: &[&str],
+    connect_opts: &ConnectOpts,
+    table_name: &str,
+    options: RunOptions<'_>,
+    jobs: usize,
+    keep_going: bool,
+) -> anyhow::Result<()> {
+    if jobs == 0 {
+        bail!("`--jobs` must be at least 1");
     }
 
-    let applied_migrations = conn.list_applied_migrations().await?;
-    validate_applied_migrations(&applied_migrations, &migrator, ignore_missing)?;
+    let migrator = run_migrator(migration_sources, table_name, options).await?;
+    let targets = connect_opts.targets();
 
-    let applied_migrations: HashMap<_, _> = applied_migrations
-        .into_iter()
-        .map(|m| (m.version, m))
-        .collect();
+    if options.dry_run {
+        return plan_targets(&migrator, &targets, jobs).await;
+    }
 
-    let mut is_applied = false;
-    for migration in migrator.iter().rev() {
-        if !migration.migration_type.is_down_migration() {
-            // Skipping non down migration
-            // This will skip any simple or up migration file
-            continue;
-        }
+    let stop = AtomicBool::new(false);
 
-        if applied_migrations.contains_key(&migration.version) {
-            let elapsed = if dry_run {
-                Duration::new(0, 0)
-            } else {
-                conn.revert(migration).await?
-            };
-            let text = if dry_run { "Can apply" } else { "Applied" };
+    let reports: Vec<serde_json::Value> = stream::iter(&targets)
+        .map(|target| run_target(&migrator, target, keep_going, &stop))
+        .buffered(jobs)
+        .collect()
+        .await;
 
-            println!(
-                "{} {}/{} {} {}",
-                text,
-                style(migration.version).cyan(),
-                style(migration.migration_type.label()).green(),
-                migration.description,
-                style(format!("({:?})", elapsed)).dim()
-            );
+    let count = |status: &str| {
+        reports
+            .iter()
+            .filter(|report| report["status"] == status)
+            .count()
+    };
+    let (failed, skipped) = (count("failed"), count("skipped"));
 
-            is_applied = true;
-            // Only a single migration will be reverted at a time, so we break
-            break;
+    if output::is_json() {
+        output::print_json(&serde_json::json!({ "targets": reports }));
+    } else {
+        let mut summary = format!(
+            "Migrated {} of {} database(s)",
+            targets.len() - failed - skipped,
+            targets.len()
+        );
+        if failed > 0 {
+            let _ = write!(summary, ", {} failed", failed);
         }
+        if skipped > 0 {
+            let _ = write!(summary, ", {} skipped", skipped);
+        }
+        println!("{}", summary);
     }
-    if !is_applied {
-        println!("No migrations available to revert");
+
+    if failed > 0 {
+        bail!(
+            "failed to migrate {} of {} databases",
+            failed,
+            targets.len()
+        );
     }
 
     Ok(())
 }
 
-pub fn build_script(migration_source: &str, force: bool) -> anyhow::Result<()> {
-    anyhow::ensure!(
-        Path::new("Cargo.toml").exists(),
-        "must be run in a Cargo project root"
-    );
+/// Runs the migrations against one of several databases, reporting how it went.
+async fn run_target(
+    migrator: &Migrator,
+    target: &ConnectOpts,
+    keep_going: bool,
+    stop: &AtomicBool,
+) -> serde_json::Value {
+    let database = redact_password(target.database_url());
 
-    anyhow::ensure!(
-        (force || !Path::new("build.rs").exists()),
-        "build.rs already exists; use --force to overwrite"
-    );
+    if stop.load(Ordering::Acquire) {
+        say!("{} {}", style("Skipped").yellow(), database);
 
-    let contents = format!(
-        r#"// generated by `sqlx migrate build-script`
-fn main() {{
-    // trigger recompilation when a new migration is added
-    println!("cargo:rerun-if-changed={}");
-}}"#,
-        migration_source
-    );
+        return serde_json::json!({
+            "database": database,
+            "status": "skipped",
+            "applied": [],
+            "seeds": [],
+            "error": null,
+        });
+    }
 
-    fs::write("build.rs", contents)?;
+    let applied = Mutex::new(Vec::new());
+    let seeds = Mutex::new(Vec::new());
+    let start = Instant::now();
 
-    println!("Created `build.rs`; be sure to check it into version control!");
+    let res = async {
+        let mut conn = crate::connect(target).await?;
 
-    Ok(())
+        migrator
+            .run_with_progress(&mut conn, |event| match event {
+                MigrationEvent::MigrationFinished {
+                    migration,
+                    elapsed,
+                    success,
+                } => applied
+                    .lock()
+                    .unwrap()
+                    .push(run_json(migration, elapsed, success)),
+                MigrationEvent::SeedFinished { seed, elapsed } => {
+                    seeds.lock().unwrap().push(seed_json(seed, elapsed))
+                }
+                _ => {}
+            })
+            .await?;
+
+        Ok::<_, anyhow::Error>(())
+    }
+    .await;
+
+    let applied = applied.into_inner().unwrap();
+    let successes = applied
+        .iter()
+        .filter(|migration| migration["success"] == true)
+        .count();
+
+    match &res {
+        Ok(()) if successes == 0 => say!("{} No migrations to apply", database),
+        Ok(()) => say!(
+            "{} {} {} migration(s) {}",
+            database,
+            style("Applied").green(),
+            successes,
+            style(format!("({:?})", start.elapsed())).dim()
+        ),
+        Err(e) => {
+            if !keep_going {
+                stop.store(true, Ordering::Release);
+            }
+
+            say!(
+                "{} {} after applying {} migration(s): {}",
+                database,
+                style("Failed").red(),
+                successes,
+                e
+            );
+        }
+    }
+
+    serde_json::json!({
+        "database": database,
+        "status": if res.is_ok() { "succeeded" } else { "failed" },
+        "applied": applied,
+        "seeds": seeds.into_inner().unwrap(),
+        "error": res.err().map(|e| e.to_string()),
+    })
+}
+
+/// Lists the plan of each of several databases, and which differ from the others.
+async fn plan_targets(
+    migrator: &Migrator,
+    targets: &[ConnectOpts],
+    jobs: usize,
+) -> anyhow::Result<()> {
+    let plans: Vec<(String, anyhow::Result<MigrationPlan>)> = stream::iter(targets)
+        .map(|target| async move {
+            let plan = async {
+                let mut conn = crate::connect(target).await?;
+                Ok::<_, anyhow::Error>(migrator.plan(&mut conn).await?)
+            }
+            .await;
+
+            (redact_password(target.database_url()), plan)
+        })
+        .buffered(jobs)
+        .collect()
+        .await;
+
+    let planned: Vec<&[AppliedMigration]> = plans
+        .iter()
+        .filter_map(|(_, plan)| plan.as_ref().ok())
+        .map(|plan| &plan.applied[..])
+        .collect();
+    let mut divergences = divergences(&planned).into_iter();
+
+    let mut reports = Vec::new();
+    let mut failed = 0;
+    let mut diverging = 0;
+
+    for (database, plan) in &plans {
+        let (mut report, divergence) = match plan {
+            Ok(plan) => {
+                if !output::is_json() {
+                    println!("{}", style(database).bold());
+                    print_plan(plan, migrator);
+                }
+
+                let divergence = divergences.next().flatten();
+                (plan_json(plan, migrator), divergence)
+            }
+            Err(e) => {
+                failed += 1;
+                say!(
+                    "{} {}: {}",
+                    style(database).bold(),
+                    style("failed").red(),
+                    e
+                );
+
+                (serde_json::json!({ "error": e.to_string() }), None)
+            }
+        };
+
+        if let Some(divergence) = &divergence {
+            diverging += 1;
+            say!(
+                "{} the applied migrations differ from most databases: {}",
+                style("problem:").red(),
+                divergence
+            );
+        }
+
+        if !output::is_json() {
+            println!();
+        }
+
+        let object = report.as_object_mut().unwrap();
+        object.insert("database".into(), database.as_str().into());
+        object.insert("divergence".into(), serde_json::to_value(divergence)?);
+        object.entry("error").or_insert(serde_json::Value::Null);
+        reports.push(report);
+    }
+
+    if output::is_json() {
+        output::print_json(&serde_json::json!({ "targets": reports }));
+    } else if diverging > 0 {
+        println!(
+            "The applied migrations differ on {} of {} database(s)",
+            diverging,
+            targets.len() - failed
+        );
+    } else {
+        println!(
+            "The applied migrations are the same on {} database(s)",
+            targets.len() - failed
+        );
+    }
+
+    if failed > 0 {
+        bail!(
+            "failed to plan the migrations of {} of {} databases",
+            failed,
+            targets.len()
+        );
+    }
+
+    Ok(())
+}
+
+/// How the migrations applied to a database differ from those applied to most databases.
+#[derive(Debug, Default, PartialEq, Eq, serde::Serialize)]
+struct Divergence {
+    /// Applied to most databases, but not to this one.
+    missing: Vec<i64>,
+
+    /// Applied to this database, but not to most.
+    extra: Vec<i64>,
+
+    /// Applied with another checksum than to most databases.
+    modified: Vec<i64>,
+}
+
+impl std::fmt::Display for Divergence {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut parts = Vec::new();
+
+        for (label, versions) in [
+            ("missing", &self.missing),
+            ("extra", &self.extra),
+            ("modified", &self.modified),
+        ] {
+            if !versions.is_empty() {
+                let versions: Vec<_> = versions.iter().map(i64::to_string).collect();
+                parts.push(format!("{} {}", label, versions.join(", ")));
+            }
+        }
+
+        f.write_str(&parts.join("; "))
+    }
+}
+
+/// How the migrations applied to each database differ from the set applied to most of them, the
+/// first one on ties, or `None` for those which have it.
+fn divergences(applied: &[&[AppliedMigration]]) -> Vec<Option<Divergence>> {
+    let sets: Vec<HashMap<i64, &[u8]>> = applied
+        .iter()
+        .map(|applied| {
+            applied
+                .iter()
+                .map(|migration| (migration.version, &*migration.checksum))
+                .collect()
+        })
+        .collect();
+
+    let mut reference = None;
+    let mut reference_count = 0;
+
+    for set in &sets {
+        let count = sets.iter().filter(|other| *other == set).count();
+
+        if count > reference_count {
+            reference = Some(set);
+            reference_count = count;
+        }
+    }
+
+    let reference = match reference {
+        Some(reference) => reference,
+        None => return Vec::new(),
+    };
+
+    sets.iter()
+        .map(|set| {
+            if set == reference {
+                return None;
+            }
+
+            let mut divergence = Divergence::default();
+
+            for (version, checksum) in reference {
+                match set.get(version) {
+                    None => divergence.missing.push(*version),
+                    Some(other) if other != checksum => divergence.modified.push(*version),
+                    Some(_) => {}
+                }
+            }
+
+            divergence.extra = set
+                .keys()
+                .filter(|version| !reference.contains_key(version))
+                .copied()
+                .collect();
+
+            divergence.missing.sort_unstable();
+            divergence.extra.sort_unstable();
+            divergence.modified.sort_unstable();
+
+            Some(divergence)
+        })
+        .collect()
+}
+
+fn print_plan(plan: &MigrationPlan, migrator: &Migrator) {
+    for applied in &plan.applied {
+        println!(
+            "{}/{} {}",
+            style(applied.version).cyan(),
+            style("installed").green(),
+            local_description(migrator, applied.version).unwrap_or("<missing>")
+        );
+    }
+
+    for applied in &plan.applied_repeatable {
+        println!(
+            "{}/{} {}",
+            style("r").cyan(),
+            style("installed").green(),
+            applied.description
+        );
+    }
+
+    for migration in &plan.pending {
+        println!(
+            "{}/{} {}",
+            style(display_version(migration)).cyan(),
+            style("pending").yellow(),
+            migration.description
+        );
+    }
+
+    for seed in migrator.seeds.iter() {
+        println!("{} {}", style("seed").yellow(), seed.name);
+    }
+
+    for problem in &plan.problems {
+        println!("{} {}", style("problem:").red(), problem);
+    }
+
+    match plan.check() {
+        Ok(()) if plan.pending.is_empty() => println!("No migrations to apply"),
+        Ok(()) => println!("Can apply {} migration(s)", plan.pending.len()),
+        Err(e) => println!("{} {}", style("Cannot apply migrations:").red(), e),
+    }
+}
+
+fn plan_json(plan: &MigrationPlan, migrator: &Migrator) -> serde_json::Value {
+    let applied: Vec<_> = plan
+        .applied
+        .iter()
+        .map(|applied| {
+            serde_json::json!({
+                "version": applied.version,
+                "description": local_description(migrator, applied.version),
+                "checksum": short_checksum(&applied.checksum),
+            })
+        })
+        .collect();
+
+    let applied_repeatable: Vec<_> = plan
+        .applied_repeatable
+        .iter()
+        .map(|applied| {
+            serde_json::json!({
+                "description": applied.description,
+                "checksum": short_checksum(&applied.checksum),
+            })
+        })
+        .collect();
+
+    let pending: Vec<_> = plan
+        .pending
+        .iter()
+        .map(|migration| {
+            let repeatable = migration.migration_type.is_repeatable();
+
+            serde_json::json!({
+                "version": if repeatable { None } else { Some(migration.version) },
+                "repeatable": repeatable,
+                "description": migration.description,
+                "checksum": short_checksum(&migration.checksum),
+            })
+        })
+        .collect();
+
+    let problems: Vec<_> = plan
+        .problems
+        .iter()
+        .map(|problem| {
+            let kind = match problem {
+                MigrationProblem::Dirty(_) => "dirty",
+                MigrationProblem::MissingLocally(_) => "missing_locally",
+                MigrationProblem::ChecksumMismatch(_) => "checksum_mismatch",
+                MigrationProblem::OutOfOrder(_) => "out_of_order",
+                _ => "other",
+            };
+
+            serde_json::json!({
+                "kind": kind,
+                "version": problem.version(),
+                "message": problem.to_string(),
+            })
+        })
+        .collect();
+
+    serde_json::json!({
+        "applied": applied,
+        "applied_repeatable": applied_repeatable,
+        "pending": pending,
+        "seeds": migrator.seeds.iter().map(|seed| &seed.name).collect::<Vec<_>>(),
+        "problems": problems,
+        "can_apply": plan.check().is_ok(),
+    })
+}
+
+fn local_description(migrator: &Migrator, version: i64) -> Option<&str> {
+    migrator
+        .iter()
+        .find(|m| {
+            m.version == version
+                && !m.migration_type.is_down_migration()
+                && !m.migration_type.is_repeatable()
+        })
+        .map(|m| &*m.description)
+}
+
+/// How far `sqlx migrate revert` goes back.
+#[derive(Debug, Clone, Copy)]
+pub enum RevertTarget {
+    /// Revert the latest applied migration.
+    Latest,
+    /// Revert every applied migration above this version.
+    Version(i64),
+    /// Revert every applied migration.
+    All,
+}
+
+/// Lock the database and list its applied migrations, once they are checked against the source.
+async fn lock_applied_migrations(
+    conn: &mut AnyConnection,
+    migrator: &Migrator,
+    ignore_missing: bool,
+) -> anyhow::Result<Vec<AppliedMigration>> {
+    let table_name = &migrator.table_name;
+
+    conn.lock(table_name, None).await?;
+
+    conn.ensure_migrations_table(table_name).await?;
+
+    let version = conn.dirty_version(table_name).await?;
+    if let Some(version) = version {
+        bail!(MigrateError::Dirty(version));
+    }
+
+    let applied_migrations = conn.list_applied_migrations(table_name).await?;
+    validate_applied_migrations(&applied_migrations, migrator, ignore_missing)?;
+
+    Ok(applied_migrations)
+}
+
+pub async fn revert(
+    migration_sources: &[&str],
+    connect_opts: &ConnectOpts,
+    table_name: &str,
+    dry_run: bool,
+    target: RevertTarget,
+    ignore_missing: bool,
+) -> anyhow::Result<()> {
+    let mut migrator = Migrator::new(migration_sources).await?;
+    warn_mixed_migrations(&migrator);
+    migrator.set_table_name(table_name.to_owned());
+
+    let mut conn = crate::connect(connect_opts).await?;
+
+    let applied_migrations = lock_applied_migrations(&mut conn, &migrator, ignore_missing).await?;
+
+    let target = match target {
+        // versions are unique, so this only leaves the latest one above the target
+        RevertTarget::Latest => applied_migrations
+            .iter()
+            .map(|m| m.version)
+            .max()
+            .map_or(0, |version| version - 1),
+        RevertTarget::Version(version) => version,
+        RevertTarget::All => 0,
+    };
+
+    let reverts = migrator.reverts(&applied_migrations, target)?;
+    let mut reverted = Vec::new();
+
+    for migration in &reverts {
+        let elapsed = if dry_run {
+            Duration::new(0, 0)
+        } else {
+            conn.revert(table_name, migration).await?
+        };
+
+        print_applied(migration, elapsed, dry_run);
+        reverted.push(applied_json(migration, elapsed));
+    }
+
+    if reverts.is_empty() {
+        say!("No migrations available to revert");
+    }
+
+    conn.unlock(table_name).await?;
+
+    if output::is_json() {
+        output::print_json(&serde_json::json!({
+            "dry_run": dry_run,
+            "reverted": reverted,
+        }));
+    }
+
+    Ok(())
+}
+
+pub async fn redo(
+    migration_sources: &[&str],
+    connect_opts: &ConnectOpts,
+    table_name: &str,
+    dry_run: bool,
+    ignore_missing: bool,
+) -> anyhow::Result<()> {
+    let mut migrator = Migrator::new(migration_sources).await?;
+    warn_mixed_migrations(&migrator);
+    migrator.set_table_name(table_name.to_owned());
+
+    let mut conn = crate::connect(connect_opts).await?;
+
+    let applied_migrations = lock_applied_migrations(&mut conn, &migrator, ignore_missing).await?;
+
+    let latest = match applied_migrations.iter().map(|m| m.version).max() {
+        Some(latest) => latest,
+        None => {
+            say!("No migrations available to redo");
+            conn.unlock(table_name).await?;
+
+            if output::is_json() {
+                output::print_json(&serde_json::json!({
+                    "dry_run": dry_run,
+                    "reverted": [],
+                    "applied": [],
+                }));
+            }
+
+            return Ok(());
+        }
+    };
+
+    let revert = migrator.reverts(&applied_migrations, latest - 1)?;
+    let apply = migrator
+        .iter()
+        .find(|m| {
+            m.version == latest
+                && !m.migration_type.is_down_migration()
+                && !m.migration_type.is_repeatable()
+        })
+        .ok_or(MigrateError::VersionMissing(latest))?;
+
+    let mut reverted = Vec::new();
+    let mut applied = Vec::new();
+
+    for migration in revert.into_iter().chain([apply]) {
+        let elapsed = if dry_run {
+            Duration::new(0, 0)
+        } else if migration.migration_type.is_down_migration() {
+            conn.revert(table_name, migration).await?
+        } else {
+            conn.apply(table_name, migration, migrator.applied_by().as_deref())
+                .await?
+        };
+
+        print_applied(migration, elapsed, dry_run);
+
+        if migration.migration_type.is_down_migration() {
+            reverted.push(applied_json(migration, elapsed));
+        } else {
+            applied.push(applied_json(migration, elapsed));
+        }
+    }
+
+    conn.unlock(table_name).await?;
+
+    if output::is_json() {
+        output::print_json(&serde_json::json!({
+            "dry_run": dry_run,
+            "reverted": reverted,
+            "applied": applied,
+        }));
+    }
+
+    Ok(())
+}
+
+fn print_applied(migration: &Migration, elapsed: Duration, dry_run: bool) {
+    say!(
+        "{} {}/{} {} {}",
+        if dry_run { "Can apply" } else { "Applied" },
+        style(migration.version).cyan(),
+        style(migration.migration_type.label()).green(),
+        migration.description,
+        style(format!("({:?})", elapsed)).dim()
+    );
+}
+
+fn applied_json(migration: &Migration, elapsed: Duration) -> serde_json::Value {
+    serde_json::json!({
+        "version": migration.version,
+        "description": migration.description,
+        "duration_ns": elapsed.as_nanos() as u64,
+    })
+}
+
+pub async fn repair(
+    migration_sources: &[&str],
+    connect_opts: &ConnectOpts,
+    table_name: &str,
+    dry_run: bool,
+    confirm: bool,
+) -> anyhow::Result<()> {
+    let mut migrator = Migrator::new(migration_sources).await?;
+    migrator.set_table_name(table_name.to_owned());
+
+    let mut conn = crate::connect(connect_opts).await?;
+
+    let plan = migrator.plan(&mut conn).await?;
+    let repairs = migrator.repairs(&plan.applied);
+
+    if repairs.is_empty() {
+        say!("No checksums to repair");
+    } else if dry_run {
+        for repair in &repairs {
+            print_repair("Can repair", repair);
+        }
+    } else if !confirm
+        || ask_to_continue(&format!(
+            "Replace the recorded checksums of {} migration(s)?",
+            repairs.len()
+        ))?
+    {
+        for repair in migrator.repair(&mut conn).await? {
+            print_repair("Repaired", &repair);
+        }
+    } else {
+        return Ok(());
+    }
+
+    if output::is_json() {
+        let repairs: Vec<_> = repairs
+            .iter()
+            .map(|repair| {
+                serde_json::json!({
+                    "version": repair.version,
+                    "description": repair.description,
+                    "applied_checksum": short_checksum(&repair.applied_checksum),
+                    "checksum": short_checksum(&repair.checksum),
+                })
+            })
+            .collect();
+
+        output::print_json(&serde_json::json!({
+            "dry_run": dry_run,
+            "repaired": repairs,
+        }));
+    }
+
+    Ok(())
+}
+
+fn print_repair(text: &str, repair: &ChecksumRepair) {
+    say!(
+        "{} {}/{} {}",
+        text,
+        style(repair.version).cyan(),
+        style("checksum").green(),
+        repair.description
+    );
+    say!(
+        "applied migration had checksum {}",
+        short_checksum(&repair.applied_checksum)
+    );
+    say!(
+        "local migration has checksum   {}",
+        short_checksum(&repair.checksum)
+    );
+}
+
+pub async fn baseline(
+    migration_sources: &[&str],
+    connect_opts: &ConnectOpts,
+    table_name: &str,
+    version: i64,
+    dry_run: bool,
+    confirm: bool,
+) -> anyhow::Result<()> {
+    let mut migrator = Migrator::new(migration_sources).await?;
+    migrator.set_table_name(table_name.to_owned());
+
+    let mut conn = crate::connect(connect_opts).await?;
+
+    let plan = migrator.plan(&mut conn).await?;
+    let baselines = migrator.baselines(&plan.applied, version);
+
+    if baselines.is_empty() {
+        say!("No migrations to baseline");
+    } else if dry_run {
+        for migration in &baselines {
+            print_baseline("Can baseline", migration);
+        }
+    } else if !confirm
+        || ask_to_continue(&format!(
+            "Record {} migration(s) as applied without running them?",
+            baselines.len()
+        ))?
+    {
+        for migration in migrator.baseline(&mut conn, version).await? {
+            print_baseline("Baselined", migration);
+        }
+    } else {
+        return Ok(());
+    }
+
+    if output::is_json() {
+        let baselines: Vec<_> = baselines
+            .iter()
+            .map(|migration| {
+                serde_json::json!({
+                    "version": migration.version,
+                    "description": migration.description,
+                })
+            })
+            .collect();
+
+        output::print_json(&serde_json::json!({
+            "dry_run": dry_run,
+            "baselined": baselines,
+        }));
+    }
+
+    Ok(())
+}
+
+fn print_baseline(text: &str, migration: &Migration) {
+    say!(
+        "{} {}/{} {}",
+        text,
+        style(migration.version).cyan(),
+        style(migration.migration_type.label()).green(),
+        migration.description
+    );
+}
+
+/// The differences found by `sqlx migrate verify` which aren't allowed, failing it with their
+/// exit code.
+#[derive(Debug)]
+pub struct DriftFound {
+    exit_code: i32,
+}
+
+impl DriftFound {
+    pub fn exit_code(&self) -> i32 {
+        self.exit_code
+    }
+}
+
+impl std::fmt::Display for DriftFound {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("the database differs from the migrations")
+    }
+}
+
+impl std::error::Error for DriftFound {}
+
+// a bit of the exit code of `verify` for each kind of difference
+fn drift_exit_code(kind: DriftKind) -> i32 {
+    match kind {
+        DriftKind::Pending => 2,
+        DriftKind::OutOfOrder => 4,
+        DriftKind::Missing => 8,
+        DriftKind::Newer => 16,
+        DriftKind::Modified => 32,
+        DriftKind::Dirty => 64,
+        _ => 1,
+    }
+}
+
+pub async fn verify(
+    migration_sources: &[&str],
+    connect_opts: &ConnectOpts,
+    table_name: &str,
+    allowed: &[String],
+    ignore_missing: bool,
+) -> anyhow::Result<()> {
+    let mut migrator = Migrator::new(migration_sources).await?;
+    migrator.set_ignore_missing(ignore_missing);
+    migrator.set_table_name(table_name.to_owned());
+
+    let mut conn = crate::connect(connect_opts).await?;
+    let report = migrator.verify(&mut conn).await?;
+
+    // `--allow` takes the kinds in kebab-case
+    let is_allowed = |kind: DriftKind| {
+        allowed
+            .iter()
+            .any(|a| *a == kind.as_str().replace('_', "-"))
+    };
+
+    let exit_code = report
+        .drift
+        .iter()
+        .filter(|drift| !is_allowed(drift.kind))
+        .fold(0, |code, drift| code | drift_exit_code(drift.kind));
+
+    if output::is_json() {
+        let drift: Vec<_> = report
+            .drift
+            .iter()
+            .map(|drift| {
+                serde_json::json!({
+                    "kind": drift.kind.as_str(),
+                    "version": drift.version,
+                    "description": drift.description,
+                    "message": drift.to_string(),
+                    "allowed": is_allowed(drift.kind),
+                })
+            })
+            .collect();
+
+        let report = serde_json::json!({
+            "drift": drift,
+            "exit_code": exit_code,
+        });
+
+        output::print_json(&report);
+    } else {
+        for drift in &report.drift {
+            let kind = style(drift.kind.as_str());
+
+            println!(
+                "{} {}",
+                if is_allowed(drift.kind) {
+                    kind.yellow()
+                } else {
+                    kind.red()
+                },
+                drift
+            );
+        }
+
+        if report.is_clean() {
+            println!("The database has exactly the migrations applied");
+        } else if exit_code != 0 {
+            println!(
+                "{} the database differs from the migrations",
+                style("error:").bold().red()
+            );
+        }
+    }
+
+    if exit_code != 0 {
+        return Err(DriftFound { exit_code }.into());
+    }
+
+    Ok(())
+}
+
+pub fn build_script(migration_source: &str, force: bool) -> anyhow::Result<()> {
+    anyhow::ensure!(
+        Path::new("Cargo.toml").exists(),
+        "must be run in a Cargo project root"
+    );
+
+    anyhow::ensure!(
+        (force || !Path::new("build.rs").exists()),
+        "build.rs already exists; use --force to overwrite"
+    );
+
+    let contents = format!(
+        r#"// generated by `sqlx migrate build-script`
+fn main() {{
+    // trigger recompilation when a new migration is added
+    println!("cargo:rerun-if-changed={}");
+}}"#,
+        migration_source
+    );
+
+    fs::write("build.rs", contents)?;
+
+    say!("Created `build.rs`; be sure to check it into version control!");
+
+    if output::is_json() {
+        output::print_json(&serde_json::json!({ "files": ["build.rs"] }));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use sqlx::Connection;
+
+    #[test]
+    fn versioning_config_parsing_works() -> anyhow::Result<()> {
+        assert_eq!(parse_config("")?, MigrationsConfig::default());
+        assert_eq!(
+            parse_config("# a comment\n\nversioning = sequential\nwidth = 6\n")?,
+            MigrationsConfig {
+                versioning: Some(Versioning::Sequential),
+                width: Some(6),
+            }
+        );
+        assert_eq!(
+            parse_config("versioning=timestamp")?,
+            MigrationsConfig {
+                versioning: Some(Versioning::Timestamp),
+                width: None,
+            }
+        );
+
+        assert!(parse_config("versioning = semver").is_err());
+        assert!(parse_config("numbering = sequential").is_err());
+        assert!(parse_config("width = 0").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn versioning_config_round_trips() -> anyhow::Result<()> {
+        let dir = std::env::temp_dir().join(format!("sqlx-config-{}", std::process::id()));
+        fs::create_dir_all(&dir)?;
+        let source = dir.to_str().unwrap();
+
+        let config = MigrationsConfig {
+            versioning: Some(Versioning::Sequential),
+            width: Some(3),
+        };
+        write_config(source, config)?;
+        assert_eq!(read_config(source)?, config);
+
+        fs::remove_dir_all(dir)?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn template_rendering_works() {
+        let template = "-- {{version}}: {{name}}, added {{timestamp}}\nSET lock_timeout = '5s';\n";
+
+        assert_eq!(
+            render_template(template, "add users", "0007", "2022-08-01T12:00:00Z"),
+            "-- 0007: add users, added 2022-08-01T12:00:00Z\nSET lock_timeout = '5s';\n"
+        );
+    }
+
+    fn migration(version: i64, migration_type: MigrationType, description: &str) -> Migration {
+        Migration::new(
+            version,
+            description.to_owned().into(),
+            migration_type,
+            format!("CREATE TABLE IF NOT EXISTS t{} (id INTEGER);", version).into(),
+        )
+    }
+
+    #[tokio::test]
+    async fn info_and_plan_json_works() -> anyhow::Result<()> {
+        let mut conn = AnyConnection::connect("sqlite::memory:").await?;
+
+        let mut migrator =
+            Migrator::new(vec![migration(1, MigrationType::Simple, "users")]).await?;
+        migrator.set_applied_by("ci");
+        migrator.run(&mut conn).await?;
+
+        let migrator = Migrator::new(vec![
+            migration(1, MigrationType::Simple, "users"),
+            migration(2, MigrationType::Simple, "posts"),
+            migration(0, MigrationType::Repeatable, "views"),
+        ])
+        .await?;
+        let checksums: Vec<_> = migrator
+            .iter()
+            .map(|migration| short_checksum(&migration.checksum))
+            .collect();
+
+        let infos = migration_infos(
+            &migrator,
+            &mut conn,
+            sqlx::migrate::DEFAULT_MIGRATIONS_TABLE,
+        )
+        .await?;
+        let mut infos = serde_json::to_value(&infos)?;
+        // where the migrations were applied varies
+        assert!(infos[0]["hostname"].is_string());
+        infos[0]["hostname"] = json!("build-01");
+
+        assert_eq!(
+            infos,
+            json!([
+                {
+                    "version": 1,
+                    "description": "users",
+                    "status": "installed",
+                    "out_of_order": false,
+                    "checksum": checksums[0],
+                    "applied_checksum": null,
+                    "applied_by": "ci",
+                    "hostname": "build-01",
+                    "sqlx_version": env!("CARGO_PKG_VERSION"),
+                },
+                {
+                    "version": 2,
+                    "description": "posts",
+                    "status": "pending",
+                    "out_of_order": false,
+                    "checksum": checksums[1],
+                    "applied_checksum": null,
+                    "applied_by": null,
+                    "hostname": null,
+                    "sqlx_version": null,
+                },
+                {
+                    "version": null,
+                    "description": "views",
+                    "status": "pending",
+                    "out_of_order": false,
+                    "checksum": checksums[2],
+                    "applied_checksum": null,
+                    "applied_by": null,
+                    "hostname": null,
+                    "sqlx_version": null,
+                },
+            ])
+        );
+
+        let plan = migrator.plan(&mut conn).await?;
+        assert_eq!(
+            plan_json(&plan, &migrator),
+            json!({
+                "applied": [{"version": 1, "description": "users", "checksum": checksums[0]}],
+                "applied_repeatable": [],
+                "pending": [
+                    {"version": 2, "repeatable": false, "description": "posts", "checksum": checksums[1]},
+                    {"version": null, "repeatable": true, "description": "views", "checksum": checksums[2]},
+                ],
+                "seeds": [],
+                "problems": [],
+                "can_apply": true,
+            })
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn divergences_works() {
+        fn applied(migrations: &[(i64, &'static [u8])]) -> Vec<AppliedMigration> {
+            migrations
+                .iter()
+                .map(|(version, checksum)| AppliedMigration {
+                    version: *version,
+                    checksum: (*checksum).into(),
+                    applied_by: None,
+                    sqlx_version: None,
+                    hostname: None,
+                })
+                .collect()
+        }
+
+        let shards = [
+            applied(&[(1, b"a"), (2, b"b")]),
+            applied(&[(1, b"a")]),
+            applied(&[(1, b"a"), (2, b"b")]),
+            applied(&[(1, b"a"), (2, b"c"), (3, b"d")]),
+            applied(&[(1, b"a"), (2, b"b")]),
+        ];
+        let shards: Vec<_> = shards.iter().map(Vec::as_slice).collect();
+
+        assert_eq!(
+            divergences(&shards),
+            [
+                None,
+                Some(Divergence {
+                    missing: vec![2],
+                    ..Divergence::default()
+                }),
+                None,
+                Some(Divergence {
+                    extra: vec![3],
+                    modified: vec![2],
+                    ..Divergence::default()
+                }),
+                None,
+            ]
+        );
+        assert_eq!(
+            divergences(&shards)[3].as_ref().unwrap().to_string(),
+            "extra 3; modified 2"
+        );
+
+        // the first set wins ties
+        let shards = [applied(&[]), applied(&[(1, b"a")])];
+        let shards: Vec<_> = shards.iter().map(Vec::as_slice).collect();
+        assert_eq!(
+            divergences(&shards),
+            [
+                None,
+                Some(Divergence {
+                    extra: vec![1],
+                    ..Divergence::default()
+                })
+            ]
+        );
+
+        assert!(divergences(&[]).is_empty());
+    }
 }
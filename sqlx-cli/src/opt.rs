@@ -1,10 +1,19 @@
 use std::ops::{Deref, Not};
+use std::path::{Path, PathBuf};
 
-use clap::{Args, Parser};
+use anyhow::{bail, Context};
+use clap::{Args, Parser, ValueHint};
+use sqlx::migrate::CreateDatabaseOptions;
+use url::Url;
 
 #[derive(Parser, Debug)]
 #[clap(version, about, author)]
 pub struct Opt {
+    /// Print the results as JSON on stdout, each document on a line of its own, and any other
+    /// text on stderr. On failure, the last line is `{"error": {"code": ..., "message": ...}}`
+    #[clap(long, global = true)]
+    pub json: bool,
+
     #[clap(subcommand)]
     pub command: Command,
 }
@@ -24,14 +33,35 @@ pub enum Command {
     /// compile-time verification to only read from the cached query metadata.
     #[clap(alias = "prep")]
     Prepare {
-        /// Run in 'check' mode. Exits with 0 if the query metadata is up-to-date. Exits with
-        /// 1 if the query metadata needs updating.
+        /// Run in 'check' mode. Exits with 0 if the query metadata is up-to-date. Otherwise lists
+        /// the queries which changed, and exits with the sum of 2 if queries are missing in the
+        /// metadata, 4 if it has queries which aren't used anymore, and 8 if the describe output
+        /// of queries changed, or with 1 on any other error.
         #[clap(long)]
         check: bool,
 
-        /// Generate a single top-level `sqlx-data.json` file when using a cargo workspace.
-        #[clap(long)]
-        merged: bool,
+        /// Prepare the queries of every workspace member which depends on `sqlx-macros` into a
+        /// single `sqlx-data.json` at the workspace root.
+        #[clap(long, alias = "merged")]
+        workspace: bool,
+
+        /// With `--workspace`, only rebuild the members whose sources changed since the last
+        /// `--incremental` run, reusing the query data of the others. Use `--force` after
+        /// changing the database schema
+        #[clap(long, requires = "workspace")]
+        incremental: bool,
+
+        /// Rebuild every member, ignoring the sources recorded by `--incremental`
+        #[clap(long, requires = "incremental")]
+        force: bool,
+
+        /// With `--workspace`, remove the query data which no member uses anymore, including the
+        /// data of databases no member is prepared against, and print what was removed
+        #[clap(long, conflicts_with = "check")]
+        prune: bool,
+
+        #[clap(flatten)]
+        build_opts: BuildOpts,
 
         /// Arguments to be passed to `cargo rustc ...`.
         #[clap(last = true)]
@@ -43,6 +73,142 @@ pub enum Command {
 
     #[clap(alias = "mig")]
     Migrate(MigrateOpt),
+
+    Config(ConfigOpt),
+
+    Schema(SchemaOpt),
+
+    /// Print the completion script of a shell, e.g. `sqlx completions bash >
+    /// /etc/bash_completion.d/sqlx`, or for zsh, to a file named `_sqlx` in a folder of `$fpath`
+    Completions {
+        #[clap(arg_enum)]
+        shell: clap_complete::Shell,
+    },
+
+    /// Print the man page of `sqlx`, in roff, or with `--out-dir`, write the man pages of `sqlx`
+    /// and of each of its subcommands in a folder, for packaging
+    #[clap(hide = true)]
+    Man {
+        #[clap(long, value_hint = ValueHint::DirPath)]
+        out_dir: Option<PathBuf>,
+    },
+}
+
+impl Command {
+    /// Checks that the command was given a database URL, or several for `migrate run`, read from
+    /// `--database-urls-file` if given, and adds the TLS options to them.
+    pub fn resolve_connect_opts(&mut self) -> anyhow::Result<()> {
+        // `prepare` can look the URL of each crate up instead
+        let (connect_opts, several_urls, optional) = match self {
+            Command::Migrate(MigrateOpt {
+                command:
+                    MigrateCommand::Run {
+                        database_urls_file,
+                        connect_opts,
+                        ..
+                    },
+                ..
+            }) => {
+                if let Some(path) = database_urls_file {
+                    connect_opts.database_urls = read_database_urls(path)?;
+                }
+
+                (connect_opts, true, false)
+            }
+            Command::Prepare { connect_opts, .. } => (connect_opts, false, true),
+            command => match command.connect_opts_mut() {
+                Some(connect_opts) => (connect_opts, false, false),
+                None => return Ok(()),
+            },
+        };
+
+        match connect_opts.database_urls.len() {
+            0 if optional => {}
+            0 => bail!("no database URL: pass `--database-url` or set `DATABASE_URL`"),
+            1 => {}
+            _ if !several_urls => bail!("only `migrate run` accepts several database URLs"),
+            _ => {}
+        }
+
+        connect_opts.apply_tls_options()
+    }
+
+    /// The options to connect to the database with, if the command connects to one.
+    pub fn connect_opts_mut(&mut self) -> Option<&mut ConnectOpts> {
+        match self {
+            Command::Database(database) => match &mut database.command {
+                DatabaseCommand::Create { connect_opts, .. }
+                | DatabaseCommand::Ping { connect_opts }
+                | DatabaseCommand::Drop { connect_opts, .. }
+                | DatabaseCommand::Reset { connect_opts, .. }
+                | DatabaseCommand::Setup { connect_opts, .. } => Some(connect_opts),
+            },
+            Command::Prepare { connect_opts, .. } => Some(connect_opts),
+            Command::Migrate(migrate) => match &mut migrate.command {
+                MigrateCommand::Run { connect_opts, .. }
+                | MigrateCommand::Revert { connect_opts, .. }
+                | MigrateCommand::Redo { connect_opts, .. }
+                | MigrateCommand::Repair { connect_opts, .. }
+                | MigrateCommand::Baseline { connect_opts, .. }
+                | MigrateCommand::Info { connect_opts, .. }
+                | MigrateCommand::Verify { connect_opts, .. } => Some(connect_opts),
+                MigrateCommand::Add { .. } | MigrateCommand::BuildScript { .. } => None,
+            },
+            Command::Schema(schema) => match &mut schema.command {
+                SchemaCommand::Dump { connect_opts, .. } => Some(connect_opts),
+            },
+            Command::Config(_) | Command::Completions { .. } | Command::Man { .. } => None,
+        }
+    }
+}
+
+/// Group of commands for the schema of the database.
+#[derive(Parser, Debug)]
+pub struct SchemaOpt {
+    #[clap(subcommand)]
+    pub command: SchemaCommand,
+}
+
+#[derive(Parser, Debug)]
+pub enum SchemaCommand {
+    /// Dump the schema of the database as SQL, sorted and without the migrations table, e.g. to
+    /// commit a `schema.sql` along with the migrations. PostgreSQL schemas are dumped with
+    /// `pg_dump` if it's installed.
+    Dump {
+        /// File to write the schema to, only if it changed, instead of printing it
+        #[clap(value_hint = ValueHint::FilePath)]
+        file: Option<PathBuf>,
+
+        /// Exit with an error if the file isn't the schema of the database, rather than
+        /// writing it
+        #[clap(long, requires = "file")]
+        check: bool,
+
+        /// Dump PostgreSQL schemas by querying the catalog, even if `pg_dump` is installed
+        #[clap(long)]
+        no_pg_dump: bool,
+
+        #[clap(flatten)]
+        table: MigrationsTable,
+
+        #[clap(flatten)]
+        connect_opts: ConnectOpts,
+    },
+}
+
+/// Group of commands for the configuration in `sqlx.toml`.
+#[derive(Parser, Debug)]
+pub struct ConfigOpt {
+    #[clap(subcommand)]
+    pub command: ConfigCommand,
+}
+
+#[derive(Parser, Debug)]
+pub enum ConfigCommand {
+    /// Print the effective configuration, and where each value comes from: `sqlx.toml` in the
+    /// current directory or its closest ancestor which has one, the environment selected in it
+    /// by `SQLX_ENV`, or the environment variables.
+    Show,
 }
 
 /// Group of commands for creating and dropping your database.
@@ -56,6 +222,16 @@ pub struct DatabaseOpt {
 pub enum DatabaseCommand {
     /// Creates the database specified in your DATABASE_URL.
     Create {
+        #[clap(flatten)]
+        create_opts: CreateOpts,
+
+        #[clap(flatten)]
+        connect_opts: ConnectOpts,
+    },
+
+    /// Checks that the database specified in your DATABASE_URL accepts connections, e.g. for
+    /// health checks.
+    Ping {
         #[clap(flatten)]
         connect_opts: ConnectOpts,
     },
@@ -65,6 +241,11 @@ pub enum DatabaseCommand {
         #[clap(flatten)]
         confirmation: Confirmation,
 
+        /// Terminate the other connections to the database before dropping it, instead of
+        /// failing while they are open (PostgreSQL and MySQL)
+        #[clap(long)]
+        force: bool,
+
         #[clap(flatten)]
         connect_opts: ConnectOpts,
     },
@@ -74,9 +255,25 @@ pub enum DatabaseCommand {
         #[clap(flatten)]
         confirmation: Confirmation,
 
+        /// Terminate the other connections to the database before dropping it, instead of
+        /// failing while they are open (PostgreSQL and MySQL)
+        #[clap(long)]
+        force: bool,
+
         #[clap(flatten)]
         source: Source,
 
+        #[clap(flatten)]
+        table: MigrationsTable,
+
+        /// Seed file, or folder of `.sql` seed files such as `seeds`, run after the migrations
+        /// succeed. May be given several times
+        #[clap(long = "seed", value_hint = ValueHint::AnyPath)]
+        seeds: Vec<String>,
+
+        #[clap(flatten)]
+        create_opts: CreateOpts,
+
         #[clap(flatten)]
         connect_opts: ConnectOpts,
     },
@@ -86,6 +283,17 @@ pub enum DatabaseCommand {
         #[clap(flatten)]
         source: Source,
 
+        #[clap(flatten)]
+        table: MigrationsTable,
+
+        /// Seed file, or folder of `.sql` seed files such as `seeds`, run after the migrations
+        /// succeed. May be given several times
+        #[clap(long = "seed", value_hint = ValueHint::AnyPath)]
+        seeds: Vec<String>,
+
+        #[clap(flatten)]
+        create_opts: CreateOpts,
+
         #[clap(flatten)]
         connect_opts: ConnectOpts,
     },
@@ -96,7 +304,7 @@ pub enum DatabaseCommand {
 pub struct MigrateOpt {
     /// Path to folder containing migrations.
     /// Warning: deprecated, use <SUBCOMMAND> --source <SOURCE>
-    #[clap(long, default_value = "migrations")]
+    #[clap(long, default_value = "migrations", value_hint = ValueHint::DirPath)]
     pub source: String,
 
     #[clap(subcommand)]
@@ -117,36 +325,180 @@ pub enum MigrateCommand {
         /// else creates a single sql file
         #[clap(short)]
         reversible: bool,
+
+        /// Start the migration with `-- sqlx:no-transaction`, to run it outside of a transaction,
+        /// e.g. for `CREATE INDEX CONCURRENTLY`
+        #[clap(long)]
+        no_tx: bool,
+
+        /// Template of the migration, or of the up migration, in which `{{name}}`, `{{version}}`
+        /// and `{{timestamp}}` are replaced [default: <SOURCE>/.template.up.sql if it exists]
+        #[clap(long, value_hint = ValueHint::FilePath)]
+        template: Option<String>,
+
+        /// Template of the down migration [default: <SOURCE>/.template.down.sql if it exists]
+        #[clap(long, requires = "reversible", value_hint = ValueHint::FilePath)]
+        down_template: Option<String>,
+
+        /// Number the migration after the latest one, rather than with the current time.
+        /// The choice is saved in <SOURCE>/.sqlx-config for the next migrations
+        #[clap(long, conflicts_with = "timestamp")]
+        sequential: bool,
+
+        /// Number the migration with the current time, which is the default.
+        /// The choice is saved in <SOURCE>/.sqlx-config for the next migrations
+        #[clap(long)]
+        timestamp: bool,
+
+        /// With `--sequential`, the width versions are zero-padded to [default: 4].
+        /// The choice is saved in <SOURCE>/.sqlx-config for the next migrations
+        #[clap(long, conflicts_with = "timestamp")]
+        width: Option<usize>,
     },
 
     /// Run all pending migrations.
     Run {
         #[clap(flatten)]
-        source: SourceOverride,
+        source: SourcesOverride,
 
-        /// List all the migrations to be run without applying
+        /// List the applied migrations, the ones to be run, and any problem, without applying
         #[clap(long)]
         dry_run: bool,
 
         #[clap(flatten)]
         ignore_missing: IgnoreMissing,
 
+        /// Apply pending migrations which are older than the latest applied migration
+        #[clap(long)]
+        allow_out_of_order: bool,
+
+        /// Seed file, or folder of `.sql` seed files such as `seeds`, run after the migrations
+        /// succeed, each in its own transaction and without being recorded. May be given
+        /// several times
+        #[clap(long = "seed", value_hint = ValueHint::AnyPath)]
+        seeds: Vec<String>,
+
+        #[clap(flatten)]
+        table: MigrationsTable,
+
+        /// File of the URLs of the databases to run the migrations against, one per line,
+        /// instead of `--database-url`. Blank lines and lines starting with `#` are skipped
+        #[clap(long, value_hint = ValueHint::FilePath)]
+        database_urls_file: Option<PathBuf>,
+
+        /// With several databases, how many to migrate at the same time
+        #[clap(long, short = 'j', default_value = "1")]
+        jobs: usize,
+
+        /// With several databases, keep migrating the others after one fails, instead of
+        /// stopping
+        #[clap(long)]
+        keep_going: bool,
+
+        /// After the migrations succeed, dump the schema of the (first) database to this file,
+        /// as with `sqlx schema dump`
+        #[clap(long, conflicts_with = "dry-run", value_hint = ValueHint::FilePath)]
+        dump_schema: Option<PathBuf>,
+
+        /// With `--dump-schema`, dump PostgreSQL schemas by querying the catalog, even if
+        /// `pg_dump` is installed
+        #[clap(long, requires = "dump-schema")]
+        no_pg_dump: bool,
+
         #[clap(flatten)]
         connect_opts: ConnectOpts,
     },
 
-    /// Revert the latest migration with a down file.
+    /// Revert the latest migration, or every migration above a target version.
     Revert {
         #[clap(flatten)]
-        source: SourceOverride,
+        source: SourcesOverride,
 
         /// List the migration to be reverted without applying
         #[clap(long)]
         dry_run: bool,
 
+        /// Revert every applied migration above this version, latest first
+        #[clap(long, conflicts_with = "all")]
+        target_version: Option<i64>,
+
+        /// Revert every applied migration, latest first
+        #[clap(long)]
+        all: bool,
+
+        #[clap(flatten)]
+        ignore_missing: IgnoreMissing,
+
+        #[clap(flatten)]
+        table: MigrationsTable,
+
+        #[clap(flatten)]
+        connect_opts: ConnectOpts,
+    },
+
+    /// Revert the latest migration, then apply it again.
+    Redo {
+        #[clap(flatten)]
+        source: SourcesOverride,
+
+        /// List the migration to be reverted and applied without running it
+        #[clap(long)]
+        dry_run: bool,
+
         #[clap(flatten)]
         ignore_missing: IgnoreMissing,
 
+        #[clap(flatten)]
+        table: MigrationsTable,
+
+        #[clap(flatten)]
+        connect_opts: ConnectOpts,
+    },
+
+    /// Replace the recorded checksums of applied migrations which were modified since,
+    /// e.g. only reformatted.
+    ///
+    /// This rewrites the migration history of the database.
+    Repair {
+        #[clap(flatten)]
+        source: SourcesOverride,
+
+        /// List the checksums to be replaced without replacing them
+        #[clap(long)]
+        dry_run: bool,
+
+        #[clap(flatten)]
+        confirmation: Confirmation,
+
+        #[clap(flatten)]
+        table: MigrationsTable,
+
+        #[clap(flatten)]
+        connect_opts: ConnectOpts,
+    },
+
+    /// Record the migrations up to a version as applied without running them, for a database
+    /// which already has their schema.
+    ///
+    /// This rewrites the migration history of the database.
+    Baseline {
+        #[clap(flatten)]
+        source: SourcesOverride,
+
+        /// Record every migration up to and including this version
+        #[clap(long)]
+        version: i64,
+
+        /// List the migrations to be recorded without recording them
+        #[clap(long)]
+        dry_run: bool,
+
+        #[clap(flatten)]
+        confirmation: Confirmation,
+
+        #[clap(flatten)]
+        table: MigrationsTable,
+
         #[clap(flatten)]
         connect_opts: ConnectOpts,
     },
@@ -154,7 +506,36 @@ pub enum MigrateCommand {
     /// List all available migrations.
     Info {
         #[clap(flatten)]
-        source: SourceOverride,
+        source: SourcesOverride,
+
+        #[clap(flatten)]
+        table: MigrationsTable,
+
+        #[clap(flatten)]
+        connect_opts: ConnectOpts,
+    },
+
+    /// Compare the migrations of the database to the migrations, without applying anything.
+    ///
+    /// Exits with a code combining a bit for each kind of difference found which isn't allowed:
+    /// 2 for pending, 4 for out-of-order, 8 for missing, 16 for newer, 32 for modified and 64
+    /// for dirty migrations.
+    Verify {
+        #[clap(flatten)]
+        source: SourcesOverride,
+
+        /// Kind of difference which doesn't fail verification. May be given several times
+        #[clap(
+            long = "allow",
+            possible_values = &["pending", "out-of-order", "missing", "newer", "modified", "dirty"]
+        )]
+        allowed: Vec<String>,
+
+        #[clap(flatten)]
+        ignore_missing: IgnoreMissing,
+
+        #[clap(flatten)]
+        table: MigrationsTable,
 
         #[clap(flatten)]
         connect_opts: ConnectOpts,
@@ -173,19 +554,18 @@ pub enum MigrateCommand {
     },
 }
 
-/// Argument for the migration scripts source.
+/// Argument for the migration scripts sources.
 #[derive(Args, Debug)]
 pub struct Source {
-    /// Path to folder containing migrations.
-    #[clap(long, default_value = "migrations")]
-    source: String,
+    /// Path to folder containing migrations. May be given several times, to run the migrations
+    /// of several folders, sorted together by version.
+    #[clap(long = "source", default_value = "migrations", value_hint = ValueHint::DirPath)]
+    sources: Vec<String>,
 }
 
-impl Deref for Source {
-    type Target = String;
-
-    fn deref(&self) -> &Self::Target {
-        &self.source
+impl Source {
+    pub(super) fn resolve(&self) -> Vec<&str> {
+        self.sources.iter().map(String::as_str).collect()
     }
 }
 
@@ -194,7 +574,7 @@ impl Deref for Source {
 #[derive(Args, Debug)]
 pub struct SourceOverride {
     /// Path to folder containing migrations [default: migrations]
-    #[clap(long)]
+    #[clap(long, value_hint = ValueHint::DirPath)]
     source: Option<String>,
 }
 
@@ -210,18 +590,153 @@ impl SourceOverride {
     }
 }
 
-/// Argument for the database URL.
+/// Argument for overriding migration scripts sources, which may be given several times.
+// Note: once `MigrateOpt.source` is removed, usage can be replaced with `Source`.
+#[derive(Args, Debug)]
+pub struct SourcesOverride {
+    /// Path to folder containing migrations [default: migrations]. May be given several times,
+    /// to run the migrations of several folders, sorted together by version.
+    #[clap(long = "source", value_hint = ValueHint::DirPath)]
+    sources: Vec<String>,
+}
+
+impl SourcesOverride {
+    /// Override command's `source` flag value with subcommand's
+    /// `source` flag values when provided.
+    #[inline]
+    pub(super) fn resolve<'a>(&'a self, source: &'a str) -> Vec<&'a str> {
+        if self.sources.is_empty() {
+            vec![source]
+        } else {
+            self.sources.iter().map(String::as_str).collect()
+        }
+    }
+}
+
+/// Argument for the table applied migrations are recorded in.
 #[derive(Args, Debug)]
+pub struct MigrationsTable {
+    /// Table the applied migrations are recorded in, which may be qualified with a schema,
+    /// e.g. `ops._sqlx_migrations`
+    #[clap(long, default_value = "_sqlx_migrations")]
+    table: String,
+}
+
+impl Deref for MigrationsTable {
+    type Target = str;
+
+    fn deref(&self) -> &Self::Target {
+        &self.table
+    }
+}
+
+/// Arguments for the options of a created database, which each apply to some databases only.
+#[derive(Args, Debug)]
+pub struct CreateOpts {
+    /// Role owning the database (PostgreSQL)
+    #[clap(long)]
+    owner: Option<String>,
+
+    /// Character encoding of the database, e.g. `UTF8` (PostgreSQL, usually with
+    /// `--template template0`) or `UTF-16le` (SQLite)
+    #[clap(long)]
+    encoding: Option<String>,
+
+    /// Locale of the database, e.g. `en_US.UTF-8` (PostgreSQL)
+    #[clap(long)]
+    locale: Option<String>,
+
+    /// Database to copy, e.g. `template0` for another encoding or locale than the default
+    /// template's (PostgreSQL)
+    #[clap(long)]
+    template: Option<String>,
+
+    /// Character set of the database, e.g. `utf8mb4` (MySQL)
+    #[clap(long)]
+    charset: Option<String>,
+
+    /// Collation of the database, e.g. `C` (PostgreSQL) or `utf8mb4_bin` (MySQL)
+    #[clap(long)]
+    collation: Option<String>,
+}
+
+impl CreateOpts {
+    pub fn options(&self) -> CreateDatabaseOptions {
+        let mut options = CreateDatabaseOptions::new();
+
+        if let Some(owner) = &self.owner {
+            options = options.owner(owner);
+        }
+        if let Some(encoding) = &self.encoding {
+            options = options.encoding(encoding);
+        }
+        if let Some(locale) = &self.locale {
+            options = options.locale(locale);
+        }
+        if let Some(template) = &self.template {
+            options = options.template(template);
+        }
+        if let Some(charset) = &self.charset {
+            options = options.charset(charset);
+        }
+        if let Some(collation) = &self.collation {
+            options = options.collation(collation);
+        }
+
+        options
+    }
+}
+
+/// Argument for the database URL.
+#[derive(Args, Clone, Debug)]
 pub struct ConnectOpts {
-    /// Location of the DB, by default will be read from the DATABASE_URL env var
-    #[clap(long, short = 'D', env)]
-    pub database_url: String,
+    /// Location of the DB, by default will be read from the DATABASE_URL env var. `migrate run`
+    /// accepts it several times, to run the migrations against each database, e.g. the shards
+    /// of a schema
+    #[clap(
+        name = "database-url",
+        long = "database-url",
+        short = 'D',
+        env = "DATABASE_URL",
+        value_name = "DATABASE_URL",
+        value_hint = ValueHint::Url
+    )]
+    pub database_urls: Vec<String>,
 
     /// The maximum time, in seconds, to try connecting to the database server before
     /// returning an error.
     #[clap(long, default_value = "10")]
     pub connect_timeout: u64,
 
+    /// The maximum number of times to retry connecting to the database server, waiting longer
+    /// after each attempt, within `--connect-timeout`. Unlimited by default
+    #[clap(long)]
+    pub connect_retries: Option<u32>,
+
+    /// How to secure the connection to a PostgreSQL or MySQL database, overriding the mode of
+    /// the URL (`sslmode` or `ssl-mode`)
+    #[clap(
+        long,
+        alias = "sslmode",
+        possible_values = &["disable", "prefer", "require", "verify-ca", "verify-full"]
+    )]
+    pub ssl_mode: Option<String>,
+
+    /// File of the root certificates to verify the certificate of a PostgreSQL or MySQL server
+    /// with, overriding the one of the URL (`sslrootcert` or `ssl-ca`)
+    #[clap(long, alias = "sslrootcert", value_hint = ValueHint::FilePath)]
+    pub ssl_root_cert: Option<String>,
+
+    /// Secure the connection without verifying the certificate of the server, like
+    /// `--ssl-mode require`
+    #[clap(long, conflicts_with = "ssl-mode")]
+    pub accept_invalid_certs: bool,
+
+    /// Verify the certificate of the server, but not that it's for the host name of the URL, like
+    /// `--ssl-mode verify-ca`
+    #[clap(long, conflicts_with_all = &["ssl-mode", "accept-invalid-certs"])]
+    pub accept_invalid_hostnames: bool,
+
     /// Set whether or not to create SQLite databases in Write-Ahead Log (WAL) mode:
     /// https://www.sqlite.org/wal.html
     ///
@@ -235,11 +750,209 @@ pub struct ConnectOpts {
     pub sqlite_create_db_wal: bool,
 }
 
+impl ConnectOpts {
+    /// The URL of the database, or of the first one if several were given.
+    pub fn database_url(&self) -> &str {
+        &self.database_urls[0]
+    }
+
+    /// The options to connect to each of the databases, one URL each.
+    pub fn targets(&self) -> Vec<ConnectOpts> {
+        self.database_urls
+            .iter()
+            .map(|url| ConnectOpts {
+                database_urls: vec![url.clone()],
+                ..self.clone()
+            })
+            .collect()
+    }
+
+    /// Adds the TLS options given as flags to the parameters of the database URLs, replacing
+    /// those they have.
+    pub fn apply_tls_options(&mut self) -> anyhow::Result<()> {
+        self.database_urls = self
+            .database_urls
+            .iter()
+            .map(|url| self.with_tls_options(url))
+            .collect::<anyhow::Result<_>>()?;
+
+        Ok(())
+    }
+
+    pub fn with_tls_options(&self, database_url: &str) -> anyhow::Result<String> {
+        let ssl_mode = if self.accept_invalid_certs {
+            Some("require")
+        } else if self.accept_invalid_hostnames {
+            Some("verify-ca")
+        } else {
+            self.ssl_mode.as_deref()
+        };
+
+        if ssl_mode.is_none() && self.ssl_root_cert.is_none() {
+            return Ok(database_url.to_owned());
+        }
+
+        let mut url = Url::parse(database_url).context("invalid database URL")?;
+
+        // the parameters of the URL which the flags replace, under all their names
+        let (mode_keys, root_cert_keys): (&[&str], &[&str]) = match url.scheme() {
+            "postgres" | "postgresql" => (
+                &["sslmode", "ssl-mode"],
+                &["sslrootcert", "ssl-root-cert", "ssl-ca"],
+            ),
+            "mysql" | "mariadb" => (&["ssl-mode", "ssl_mode"], &["ssl-ca", "ssl_ca"]),
+            scheme => bail!(
+                "the TLS options only apply to PostgreSQL and MySQL, not `{}`",
+                scheme
+            ),
+        };
+
+        let mut params: Vec<(String, String)> = url
+            .query_pairs()
+            .into_owned()
+            .filter(|(key, _)| {
+                !((ssl_mode.is_some() && mode_keys.contains(&key.as_str()))
+                    || (self.ssl_root_cert.is_some() && root_cert_keys.contains(&key.as_str())))
+            })
+            .collect();
+
+        if let Some(ssl_mode) = ssl_mode {
+            let ssl_mode = match url.scheme() {
+                // MySQL names the modes differently
+                "mysql" | "mariadb" => match ssl_mode {
+                    "disable" => "DISABLED",
+                    "prefer" => "PREFERRED",
+                    "require" => "REQUIRED",
+                    "verify-ca" => "VERIFY_CA",
+                    _ => "VERIFY_IDENTITY",
+                },
+                _ => ssl_mode,
+            };
+
+            params.push((mode_keys[0].to_owned(), ssl_mode.to_owned()));
+        }
+
+        if let Some(root_cert) = &self.ssl_root_cert {
+            params.push((root_cert_keys[0].to_owned(), root_cert.clone()));
+        }
+
+        url.query_pairs_mut().clear().extend_pairs(params);
+
+        Ok(url.into())
+    }
+}
+
+/// Reads the database URLs of `--database-urls-file`.
+fn read_database_urls(path: &Path) -> anyhow::Result<Vec<String>> {
+    let contents = std::fs::read_to_string(path)
+        .with_context(|| format!("failed to read `{}`", path.display()))?;
+
+    let urls: Vec<String> = contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(str::to_owned)
+        .collect();
+
+    if urls.is_empty() {
+        bail!("`{}` has no database URLs", path.display());
+    }
+
+    Ok(urls)
+}
+
+/// Arguments for the cargo configuration to prepare queries with.
+#[derive(Args, Debug, Default)]
+pub struct BuildOpts {
+    /// Space or comma separated list of features to activate, as for `cargo check`. May be given
+    /// several times
+    #[clap(long, short = 'F')]
+    pub features: Vec<String>,
+
+    /// Activate all available features
+    #[clap(long)]
+    pub all_features: bool,
+
+    /// Do not activate the `default` feature
+    #[clap(long)]
+    pub no_default_features: bool,
+
+    /// Build with the given profile
+    #[clap(long)]
+    pub profile: Option<String>,
+
+    /// Build for the target triple
+    #[clap(long)]
+    pub target: Option<String>,
+}
+
+impl BuildOpts {
+    /// The features to activate, sorted and without duplicates.
+    pub fn features(&self) -> Vec<String> {
+        let mut features: Vec<_> = self
+            .features
+            .iter()
+            .flat_map(|features| features.split(|c: char| c == ',' || c.is_whitespace()))
+            .filter(|feature| !feature.is_empty())
+            .map(str::to_owned)
+            .collect();
+
+        features.sort();
+        features.dedup();
+        features
+    }
+
+    /// The arguments of `cargo metadata` resolving the dependencies as they are built.
+    pub fn metadata_args(&self) -> Vec<String> {
+        let mut args = self.feature_args();
+
+        if let Some(target) = &self.target {
+            args.extend(["--filter-platform".to_owned(), target.clone()]);
+        }
+
+        args
+    }
+
+    /// The arguments of `cargo check` or `cargo rustc`.
+    pub fn cargo_args(&self) -> Vec<String> {
+        let mut args = self.feature_args();
+
+        if let Some(profile) = &self.profile {
+            args.extend(["--profile".to_owned(), profile.clone()]);
+        }
+
+        if let Some(target) = &self.target {
+            args.extend(["--target".to_owned(), target.clone()]);
+        }
+
+        args
+    }
+
+    fn feature_args(&self) -> Vec<String> {
+        let mut args = Vec::new();
+        let features = self.features();
+
+        if !features.is_empty() {
+            args.extend(["--features".to_owned(), features.join(",")]);
+        }
+
+        if self.all_features {
+            args.push("--all-features".to_owned());
+        }
+
+        if self.no_default_features {
+            args.push("--no-default-features".to_owned());
+        }
+
+        args
+    }
+}
+
 /// Argument for automatic confirmation.
 #[derive(Args, Copy, Clone, Debug)]
 pub struct Confirmation {
     /// Automatic confirmation. Without this option, you will be prompted before dropping
-    /// your database.
+    /// your database, or rewriting its migration history.
     #[clap(short)]
     pub yes: bool,
 }
@@ -267,3 +980,146 @@ impl Not for IgnoreMissing {
         !self.ignore_missing
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn connect_opts(args: &[&str]) -> ConnectOpts {
+        #[derive(Parser)]
+        struct Cli {
+            #[clap(flatten)]
+            connect_opts: ConnectOpts,
+        }
+
+        Cli::try_parse_from([&["sqlx"], args].concat())
+            .unwrap()
+            .connect_opts
+    }
+
+    #[test]
+    fn tls_options_are_added_to_the_url() -> anyhow::Result<()> {
+        let mut opts = connect_opts(&["-D", "postgres://localhost/app?sslmode=disable&user=me"]);
+        opts.apply_tls_options()?;
+        assert_eq!(
+            opts.database_url(),
+            "postgres://localhost/app?sslmode=disable&user=me"
+        );
+
+        let mut opts = connect_opts(&[
+            "-D",
+            "postgres://localhost/app?sslmode=disable&user=me",
+            "--sslmode",
+            "verify-full",
+            "--sslrootcert",
+            "/etc/ssl/ca.pem",
+        ]);
+        opts.apply_tls_options()?;
+        assert_eq!(
+            opts.database_url(),
+            "postgres://localhost/app?user=me&sslmode=verify-full&sslrootcert=%2Fetc%2Fssl%2Fca.pem"
+        );
+
+        let mut opts = connect_opts(&[
+            "-D",
+            "mysql://localhost/app?ssl-ca=old.pem",
+            "--accept-invalid-certs",
+        ]);
+        opts.apply_tls_options()?;
+        assert_eq!(
+            opts.database_url(),
+            "mysql://localhost/app?ssl-ca=old.pem&ssl-mode=REQUIRED"
+        );
+
+        let mut opts = connect_opts(&["-D", "sqlite://app.db", "--ssl-mode", "require"]);
+        assert!(opts.apply_tls_options().is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn several_database_urls_are_only_accepted_by_migrate_run() -> anyhow::Result<()> {
+        let path = std::env::temp_dir().join(format!("sqlx-urls-{}.txt", std::process::id()));
+        std::fs::write(
+            &path,
+            "# shards\npostgres://localhost/shard1\n\n  postgres://localhost/shard2  \n",
+        )?;
+
+        let mut opt = Opt::try_parse_from([
+            "sqlx",
+            "migrate",
+            "run",
+            "-D",
+            "postgres://localhost/app",
+            "--database-urls-file",
+            path.to_str().unwrap(),
+            "--ssl-mode",
+            "require",
+        ])?;
+        opt.command.resolve_connect_opts()?;
+        assert_eq!(
+            opt.command.connect_opts_mut().unwrap().database_urls,
+            [
+                "postgres://localhost/shard1?sslmode=require",
+                "postgres://localhost/shard2?sslmode=require"
+            ]
+        );
+
+        let mut opt = Opt::try_parse_from([
+            "sqlx",
+            "migrate",
+            "info",
+            "-D",
+            "sqlite://a.db",
+            "-D",
+            "sqlite://b.db",
+        ])?;
+        assert!(opt.command.resolve_connect_opts().is_err());
+
+        std::fs::write(&path, "# no shards yet\n")?;
+        let mut opt = Opt::try_parse_from([
+            "sqlx",
+            "migrate",
+            "run",
+            "--database-urls-file",
+            path.to_str().unwrap(),
+        ])?;
+        assert!(opt.command.resolve_connect_opts().is_err());
+
+        std::fs::remove_file(path)?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn create_options_are_forwarded() -> anyhow::Result<()> {
+        let opt = Opt::try_parse_from([
+            "sqlx",
+            "database",
+            "setup",
+            "-D",
+            "postgres://localhost/app",
+            "--encoding",
+            "UTF8",
+            "--template",
+            "template0",
+            "--owner",
+            "app",
+        ])?;
+
+        match opt.command {
+            Command::Database(DatabaseOpt {
+                command: DatabaseCommand::Setup { create_opts, .. },
+            }) => assert_eq!(
+                create_opts.options(),
+                CreateDatabaseOptions::new()
+                    .owner("app")
+                    .encoding("UTF8")
+                    .template("template0")
+            ),
+            command => panic!("unexpected command {:?}", command),
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,139 @@
+//! Where the commands print: text for humans goes to stdout, unless `--json` is given, in which
+//! case stdout only gets JSON documents, each on a line of its own, and the text goes to stderr.
+
+use crate::database::ConfirmationRequired;
+use crate::migrate::DriftFound;
+use crate::prepare::StaleQueryData;
+use crate::schema::StaleSchema;
+use sqlx::migrate::MigrateError;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static JSON: AtomicBool = AtomicBool::new(false);
+
+pub fn set_json(json: bool) {
+    JSON.store(json, Ordering::Release);
+}
+
+/// Whether `--json` was given.
+pub fn is_json() -> bool {
+    JSON.load(Ordering::Acquire)
+}
+
+/// Prints a line of text for humans: on stdout, or on stderr with `--json`.
+macro_rules! say {
+    ($($arg:tt)*) => {
+        if $crate::output::is_json() {
+            eprintln!($($arg)*)
+        } else {
+            println!($($arg)*)
+        }
+    };
+}
+
+pub(crate) use say;
+
+/// Prints a JSON document on stdout, on a line of its own.
+pub fn print_json(document: &serde_json::Value) {
+    println!("{}", document);
+}
+
+/// The JSON object printed with `--json` when a command fails.
+pub fn error_json(error: &anyhow::Error) -> serde_json::Value {
+    serde_json::json!({
+        "error": {
+            "code": error_code(error),
+            "message": error.to_string(),
+        }
+    })
+}
+
+/// A code for the kind of `error`, which doesn't change between releases, unlike the messages.
+pub fn error_code(error: &anyhow::Error) -> &'static str {
+    for cause in error.chain() {
+        if cause.is::<DriftFound>() {
+            return "migrations_drift";
+        }
+
+        if cause.is::<StaleQueryData>() {
+            return "stale_query_data";
+        }
+
+        if cause.is::<StaleSchema>() {
+            return "stale_schema";
+        }
+
+        if cause.is::<ConfirmationRequired>() {
+            return "confirmation_required";
+        }
+
+        if let Some(error) = cause.downcast_ref::<MigrateError>() {
+            return migrate_error_code(error);
+        }
+
+        if let Some(error) = cause.downcast_ref::<sqlx::Error>() {
+            return match error.without_context() {
+                sqlx::Error::Configuration(_) => "invalid_database_url",
+                sqlx::Error::Database(_) => "database",
+                sqlx::Error::Io(_)
+                | sqlx::Error::Tls(_)
+                | sqlx::Error::PoolTimedOut
+                | sqlx::Error::PoolClosed => "connection",
+                sqlx::Error::Migrate(error) => migrate_error_code(error),
+                _ => "error",
+            };
+        }
+    }
+
+    "error"
+}
+
+fn migrate_error_code(error: &MigrateError) -> &'static str {
+    match error {
+        MigrateError::Execute(_) => "migration_failed",
+        MigrateError::Source(_) => "invalid_migrations",
+        MigrateError::VersionMissing(_) => "migration_missing",
+        MigrateError::VersionMismatch(_) => "migration_modified",
+        MigrateError::DuplicateVersion(..) => "migration_duplicate",
+        MigrateError::OutOfOrder(_) => "migration_out_of_order",
+        MigrateError::InvalidMixReversibleAndSimple => "invalid_migrations",
+        MigrateError::Dirty(_) => "migration_dirty",
+        MigrateError::MissingDownMigrations(_) => "missing_down_migrations",
+        MigrateError::LockTimeout(_) => "lock_timeout",
+        MigrateError::Seed(..) => "seed_failed",
+        MigrateError::AfterRun(_) => "after_run_failed",
+        _ => "error",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn it_prints_errors_with_a_code() {
+        let error = anyhow::Error::from(MigrateError::Dirty(3));
+        assert_eq!(
+            error_json(&error),
+            json!({
+                "error": {
+                    "code": "migration_dirty",
+                    "message": "migration 3 is partially applied; fix and remove row from `_sqlx_migrations` table",
+                }
+            })
+        );
+
+        let error =
+            anyhow::Error::from(sqlx::Error::Migrate(Box::new(MigrateError::OutOfOrder(2))))
+                .context("while applying migrations");
+        assert_eq!(error_code(&error), "migration_out_of_order");
+
+        let error = anyhow::Error::from(sqlx::Error::PoolTimedOut);
+        assert_eq!(error_code(&error), "connection");
+
+        let error = anyhow::Error::from(ConfirmationRequired);
+        assert_eq!(error_code(&error), "confirmation_required");
+
+        assert_eq!(error_code(&anyhow::anyhow!("oops")), "error");
+    }
+}
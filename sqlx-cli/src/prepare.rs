@@ -1,107 +1,666 @@
-use crate::opt::ConnectOpts;
+use crate::opt::{BuildOpts, ConnectOpts};
+use crate::output::{self, say};
 use anyhow::{bail, Context};
 use console::style;
 use remove_dir_all::remove_dir_all;
 use sqlx::any::{AnyConnectOptions, AnyKind};
 use sqlx::Connection;
-use std::collections::{BTreeMap, BTreeSet};
+use std::collections::{btree_map, BTreeMap, BTreeSet};
+use std::fmt::{self, Display, Formatter};
 use std::fs::File;
-use std::io::{BufReader, BufWriter};
+use std::io::BufWriter;
 use std::path::{Path, PathBuf};
 use std::process::Command;
 use std::str::FromStr;
 use std::time::SystemTime;
 use std::{env, fs};
 
-use crate::metadata::Metadata;
+use crate::metadata::{Metadata, Package};
+
+use self::cache::PrepareCache;
+
+mod cache;
+mod database_url;
 
 type QueryData = BTreeMap<String, serde_json::Value>;
 type JsonObject = serde_json::Map<String, serde_json::Value>;
 
-#[derive(serde::Serialize, serde::Deserialize)]
+/// `sqlx-data.json`: the query data of each database, by the name of its driver, e.g.
+/// `PostgreSQL`, so that crates supporting several databases can be built offline for each.
+#[derive(Debug, Default, serde::Serialize)]
+#[serde(transparent)]
 struct DataFile {
-    db: String,
+    databases: BTreeMap<String, DatabaseData>,
+}
+
+#[derive(Debug, Default, serde::Serialize, serde::Deserialize)]
+struct DatabaseData {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    build: Option<BuildConfig>,
     #[serde(flatten)]
     data: QueryData,
 }
 
+impl DataFile {
+    /// Reads `sqlx-data.json`, or returns an empty data file if it doesn't exist.
+    fn load(path: &Path) -> anyhow::Result<Self> {
+        match fs::read(path) {
+            Ok(contents) => Self::parse(&contents)
+                .with_context(|| format!("failed to parse `{}`", path.display())),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(DataFile::default()),
+            Err(e) => Err(e).with_context(|| format!("failed to read `{}`", path.display())),
+        }
+    }
+
+    fn parse(contents: &[u8]) -> anyhow::Result<Self> {
+        let mut data_file: JsonObject = serde_json::from_slice(contents)?;
+
+        // older versions saved the data of a single database, named by `db`, with the queries
+        // hashed without the name of the database
+        let db = match data_file.remove("db") {
+            Some(serde_json::Value::String(db)) => db,
+            Some(db) => bail!("expected key `db` to be a string, was {:?} instead", db),
+            None => {
+                return Ok(DataFile {
+                    databases: serde_json::from_value(data_file.into())?,
+                })
+            }
+        };
+
+        let DatabaseData { build, data } = serde_json::from_value(data_file.into())?;
+        let data = data
+            .into_values()
+            .map(|query| {
+                let hash = hash_query(&db, query["query"].as_str().unwrap_or_default());
+                (hash, query)
+            })
+            .collect();
+
+        Ok(DataFile {
+            databases: BTreeMap::from([(db, DatabaseData { build, data })]),
+        })
+    }
+
+    /// Rekeys the queries hashed by sqlx 0.6.1 and earlier, which didn't normalize them, returning
+    /// how many were.
+    fn upgrade_hashes(&mut self) -> usize {
+        let mut upgraded = 0;
+
+        for (db, database) in &mut self.databases {
+            let data = std::mem::take(&mut database.data);
+
+            database.data = data
+                .into_iter()
+                .map(|(hash, query)| match query["query"].as_str() {
+                    Some(text) if hash == legacy_hash_query(db, text) => {
+                        let new_hash = hash_query(db, text);
+
+                        if new_hash != hash {
+                            upgraded += 1;
+                        }
+
+                        (new_hash, query)
+                    }
+                    _ => (hash, query),
+                })
+                .collect();
+        }
+
+        upgraded
+    }
+}
+
+/// The key of the data of `query` for the database `db`, as computed by the query macros.
+fn hash_query(db: &str, query: &str) -> String {
+    legacy_hash_query(db, &normalize_query(query))
+}
+
+/// The key of the data of `query` as computed by sqlx 0.6.1 and earlier, without normalizing the
+/// query.
+fn legacy_hash_query(db: &str, query: &str) -> String {
+    use sha2::{Digest, Sha256};
+
+    hex::encode(Sha256::digest(format!("{}\n{}", db, query).as_bytes()))
+}
+
+/// Collapses each run of whitespace in `query` into a single space, except within string
+/// literals, quoted identifiers and line comments, and the newline ending a line comment, as the
+/// query macros do.
+fn normalize_query(query: &str) -> String {
+    let mut normalized = String::with_capacity(query.len());
+    let mut rest = query.trim();
+    let mut in_line_comment = false;
+
+    while let Some(c) = rest.chars().next() {
+        let len = match c {
+            // a doubled quote escaping the quote ends the literal and starts another one
+            '\'' | '"' | '`' => rest[1..].find(c).map_or(rest.len(), |end| end + 2),
+            '-' if rest.starts_with("--") => {
+                in_line_comment = true;
+                rest.find('\n').unwrap_or(rest.len())
+            }
+            '$' => dollar_quoted_len(rest).unwrap_or(1),
+            c if c.is_whitespace() => {
+                let len = rest
+                    .find(|c: char| !c.is_whitespace())
+                    .unwrap_or(rest.len());
+
+                normalized.push(if in_line_comment { '\n' } else { ' ' });
+                in_line_comment = false;
+                rest = &rest[len..];
+                continue;
+            }
+            c => c.len_utf8(),
+        };
+
+        normalized.push_str(&rest[..len]);
+        rest = &rest[len..];
+    }
+
+    normalized
+}
+
+/// The length of the dollar-quoted string of PostgreSQL which `query` starts with, e.g.
+/// `$$text$$` or `$tag$text$tag$`, as opposed to a parameter like `$1`.
+fn dollar_quoted_len(query: &str) -> Option<usize> {
+    let tag_end = query[1..].find(|c: char| !c.is_alphanumeric() && c != '_')? + 1;
+
+    if !query[tag_end..].starts_with('$') || query[1..].starts_with(|c: char| c.is_ascii_digit()) {
+        return None;
+    }
+
+    let tag = &query[..=tag_end];
+    let end = query[tag.len()..].find(tag)?;
+
+    Some(tag.len() + end + tag.len())
+}
+
+/// The cargo configuration the queries were prepared with, which is saved unless it's the default.
+#[derive(Debug, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+struct BuildConfig {
+    features: Vec<String>,
+    all_features: bool,
+    no_default_features: bool,
+    profile: Option<String>,
+    target: Option<String>,
+}
+
+impl BuildConfig {
+    fn new(build_opts: &BuildOpts) -> Option<Self> {
+        let config = BuildConfig {
+            features: build_opts.features(),
+            all_features: build_opts.all_features,
+            no_default_features: build_opts.no_default_features,
+            profile: build_opts.profile.clone(),
+            target: build_opts.target.clone(),
+        };
+
+        (config != BuildConfig::default()).then_some(config)
+    }
+}
+
+impl Display for BuildConfig {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let mut flags = Vec::new();
+
+        if !self.features.is_empty() {
+            flags.push(format!("--features {}", self.features.join(",")));
+        }
+
+        if self.all_features {
+            flags.push("--all-features".to_owned());
+        }
+
+        if self.no_default_features {
+            flags.push("--no-default-features".to_owned());
+        }
+
+        if let Some(profile) = &self.profile {
+            flags.push(format!("--profile {}", profile));
+        }
+
+        if let Some(target) = &self.target {
+            flags.push(format!("--target {}", target));
+        }
+
+        f.write_str(&flags.join(" "))
+    }
+}
+
+// describes the configuration of `sqlx-data.json` in errors
+fn describe_build(build: &Option<BuildConfig>) -> String {
+    match build {
+        Some(build) => format!("`{}`", build),
+        None => "the default features".to_owned(),
+    }
+}
+
 pub async fn run(
     connect_opts: &ConnectOpts,
-    merge: bool,
+    workspace: bool,
+    incremental: bool,
+    force: bool,
+    prune: bool,
+    build_opts: &BuildOpts,
     cargo_args: Vec<String>,
 ) -> anyhow::Result<()> {
-    // Ensure the database server is available.
-    crate::connect(connect_opts).await?.close().await?;
+    if prune && !workspace {
+        // the data of the other databases may have been prepared by another run
+        bail!("`--prune` requires `--workspace`");
+    }
 
-    let url = &connect_opts.database_url;
+    let (cargo, metadata) = cargo_metadata(build_opts)?;
+    let targets = PrepareTargets::resolve(connect_opts, &metadata, workspace).await?;
+    let cargo_args: Vec<_> = build_opts
+        .cargo_args()
+        .into_iter()
+        .chain(cargo_args)
+        .collect();
 
-    let db_kind = get_db_kind(url)?;
-    let data = run_prepare_step(url, merge, cargo_args)?;
+    // the query data of the other databases is kept
+    let data_file_path = data_file_path(&metadata, workspace);
+    let mut data_file = DataFile::load(&data_file_path)?;
+    data_file.upgrade_hashes();
 
-    if data.is_empty() {
-        println!(
+    // the query data depends on the database and on how the crates are built
+    let cache_path = PrepareCache::path(&metadata);
+    let cache_config = format!("{} {}", targets.describe(), cargo_args.join(" "));
+    let mut cache = incremental.then(|| {
+        if force {
+            PrepareCache::new(&cache_config)
+        } else {
+            PrepareCache::load(&cache_path, &cache_config)
+        }
+    });
+    let crates = run_prepare_step(
+        &cargo,
+        &metadata,
+        targets.database_url.as_deref(),
+        workspace,
+        cache.as_mut(),
+        cargo_args,
+    )?;
+
+    let mut fresh = BTreeMap::new();
+
+    for (db_kind, crates) in targets.group(&crates) {
+        fresh.insert(db_kind, merge_crate_data(&crates)?);
+    }
+
+    let queries: usize = fresh.values().map(BTreeMap::len).sum();
+
+    // with `--prune`, every saved query the fresh build doesn't use is removed, including the data
+    // of the databases no member is prepared against anymore
+    let pruned = if prune {
+        orphaned_queries(&data_file, &fresh)
+    } else {
+        Vec::new()
+    };
+
+    if prune {
+        data_file.databases.clear();
+    }
+
+    for (db_kind, data) in fresh {
+        data_file.databases.insert(
+            db_kind.to_owned(),
+            DatabaseData {
+                build: BuildConfig::new(build_opts),
+                data,
+            },
+        );
+    }
+
+    if queries == 0 {
+        say!(
             "{} no queries found; please ensure that the `offline` feature is enabled in sqlx",
             style("warning:").yellow()
         );
     }
 
+    let db_kinds = targets.db_kinds().join(", ");
+
     serde_json::to_writer_pretty(
         BufWriter::new(
-            File::create("sqlx-data.json").context("failed to create/open `sqlx-data.json`")?,
+            File::create(&data_file_path)
+                .with_context(|| format!("failed to create/open `{}`", data_file_path.display()))?,
         ),
-        &DataFile {
-            db: db_kind.to_owned(),
-            data,
-        },
+        &data_file,
     )
-    .context("failed to write to `sqlx-data.json`")?;
+    .with_context(|| format!("failed to write to `{}`", data_file_path.display()))?;
 
-    println!(
-        "query data written to `sqlx-data.json` in the current directory; \
-         please check this into version control"
-    );
+    if let Some(cache) = &cache {
+        cache.save(&cache_path)?;
+    }
+
+    if prune {
+        say!(
+            "removed {} unused {} from `sqlx-data.json`{}",
+            pruned.len(),
+            if pruned.len() == 1 {
+                "query"
+            } else {
+                "queries"
+            },
+            if pruned.is_empty() { "" } else { ":" }
+        );
+
+        for orphan in &pruned {
+            say!("    {}", orphan);
+        }
+    }
+
+    if workspace {
+        say!(
+            "{} query data of {} written to `sqlx-data.json` at the workspace root; \
+             please check this into version control",
+            db_kinds,
+            crates
+                .keys()
+                .map(String::as_str)
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+    } else {
+        say!(
+            "{} query data written to `sqlx-data.json` in the current directory; \
+             please check this into version control",
+            db_kinds
+        );
+    }
+
+    if output::is_json() {
+        output::print_json(&serde_json::json!({
+            "data_file": data_file_path,
+            "databases": targets.db_kinds(),
+            "crates": crates.keys().collect::<Vec<_>>(),
+            "queries": queries,
+            "pruned": pruned.iter().map(OrphanedQuery::to_json).collect::<Vec<_>>(),
+        }));
+    }
 
     Ok(())
 }
 
 pub async fn check(
     connect_opts: &ConnectOpts,
-    merge: bool,
+    workspace: bool,
+    incremental: bool,
+    force: bool,
+    build_opts: &BuildOpts,
     cargo_args: Vec<String>,
 ) -> anyhow::Result<()> {
-    // Ensure the database server is available.
-    crate::connect(connect_opts).await?.close().await?;
+    let (cargo, metadata) = cargo_metadata(build_opts)?;
+    let targets = PrepareTargets::resolve(connect_opts, &metadata, workspace).await?;
+    let cargo_args: Vec<_> = build_opts
+        .cargo_args()
+        .into_iter()
+        .chain(cargo_args)
+        .collect();
 
-    let url = &connect_opts.database_url;
+    // the query data depends on the database and on how the crates are built
+    let cache_path = PrepareCache::path(&metadata);
+    let cache_config = format!("{} {}", targets.describe(), cargo_args.join(" "));
+    let mut cache = incremental.then(|| {
+        if force {
+            PrepareCache::new(&cache_config)
+        } else {
+            PrepareCache::load(&cache_path, &cache_config)
+        }
+    });
+    let crates = run_prepare_step(
+        &cargo,
+        &metadata,
+        targets.database_url.as_deref(),
+        workspace,
+        cache.as_mut(),
+        cargo_args,
+    )?;
 
-    let db_kind = get_db_kind(url)?;
-    let data = run_prepare_step(url, merge, cargo_args)?;
+    let groups = targets.group(&crates);
 
-    let data_file = File::open("sqlx-data.json").context(
-        "failed to open `sqlx-data.json`; you may need to run `cargo sqlx prepare` first",
-    )?;
+    // the crates must agree on the queries they share
+    for crates in groups.values() {
+        merge_crate_data(crates)?;
+    }
 
-    let DataFile {
-        db: expected_db,
-        data: saved_data,
-    } = serde_json::from_reader(BufReader::new(data_file))?;
+    let data_file_path = data_file_path(&metadata, workspace);
+    if !data_file_path.exists() {
+        bail!("failed to open `sqlx-data.json`; you may need to run `cargo sqlx prepare` first");
+    }
 
-    if db_kind != expected_db {
-        bail!(
-            "saved prepare data is for {}, not {} (inferred from `DATABASE_URL`)",
-            expected_db,
-            db_kind
-        )
+    // only the data of the databases the queries were built against (inferred from the database
+    // URL of each crate) is checked; if it's missing, every query is reported as added
+    let mut data_file = DataFile::load(&data_file_path)?;
+    let legacy_hashes = data_file.upgrade_hashes();
+    let mut saved_data = BTreeMap::new();
+
+    for db_kind in groups.keys() {
+        let saved = data_file.databases.remove(*db_kind);
+
+        if let Some(DatabaseData {
+            build: expected_build,
+            ..
+        }) = &saved
+        {
+            let build = BuildConfig::new(build_opts);
+
+            if build != *expected_build {
+                bail!(
+                    "saved {} prepare data was prepared with {}, not {}",
+                    db_kind,
+                    describe_build(expected_build),
+                    describe_build(&build)
+                )
+            }
+        }
+
+        saved_data.insert(*db_kind, saved.map(|saved| saved.data).unwrap_or_default());
+    }
+
+    let mut changes: Vec<_> = groups
+        .iter()
+        .flat_map(|(db_kind, crates)| query_changes(crates, &saved_data[db_kind]))
+        .collect();
+    changes.sort_by(|a, b| (a.kind, a.krate).cmp(&(b.kind, b.krate)));
+
+    // every member of the workspace was checked, so the data of the other databases isn't used
+    let orphans = if workspace {
+        orphaned_queries(&data_file, &BTreeMap::new())
+    } else {
+        Vec::new()
+    };
+
+    if output::is_json() {
+        output::print_json(&changes_json(&changes, &orphans, &data_file_path));
+    } else {
+        if legacy_hashes > 0 {
+            println!(
+                "{} {} queries in `{}` are keyed by the hashes of sqlx 0.6.1 and earlier, which \
+                 the query macros accept until the next release; rerun `cargo sqlx prepare{}`",
+                style("warning:").yellow(),
+                legacy_hashes,
+                data_file_path.display(),
+                if workspace { " --workspace" } else { "" }
+            );
+        }
+
+        if !orphans.is_empty() {
+            println!(
+                "{} `{}` has {} queries of databases no workspace member is prepared against:",
+                style("warning:").yellow(),
+                data_file_path.display(),
+                orphans.len()
+            );
+
+            for orphan in &orphans {
+                println!("    {}", orphan);
+            }
+
+            println!("run `cargo sqlx prepare --workspace --prune` to remove them");
+        }
     }
 
-    if data != saved_data {
-        bail!("`cargo sqlx prepare` needs to be rerun")
+    if !output::is_json() && !changes.is_empty() {
+        println!(
+            "{} the query data in `{}` is out of date:",
+            style("warning:").yellow(),
+            data_file_path.display()
+        );
+
+        for change in &changes {
+            println!(
+                "    {:<8} {}  {}{}",
+                change.kind.as_str(),
+                change.hash.get(..16).unwrap_or(change.hash),
+                change
+                    .krate
+                    .map(|krate| format!("{}: ", style(krate).cyan()))
+                    .unwrap_or_default(),
+                query_summary(change.query)
+            );
+        }
+
+        println!(
+            "{} `cargo sqlx prepare{}` needs to be rerun",
+            style("error:").bold().red(),
+            if workspace { " --workspace" } else { "" }
+        );
+    }
+
+    let exit_code = changes
+        .iter()
+        .fold(0, |exit_code, change| exit_code | change.kind.exit_code());
+
+    if exit_code != 0 {
+        return Err(StaleQueryData { exit_code }.into());
     }
 
     Ok(())
 }
 
-fn run_prepare_step(url: &str, merge: bool, cargo_args: Vec<String>) -> anyhow::Result<QueryData> {
+/// The query data of each crate, by the name of the crate.
+type CrateQueryData = BTreeMap<String, QueryData>;
+
+/// The databases the crates to prepare are checked against.
+struct PrepareTargets {
+    /// The `DATABASE_URL` passed on to the macros, if any.
+    database_url: Option<String>,
+    /// The database of each crate, by the name of the crate.
+    db_kinds: BTreeMap<String, &'static str>,
+    /// The database of crates whose URL wasn't looked up.
+    default_db_kind: &'static str,
+    urls: BTreeSet<String>,
+}
+
+impl PrepareTargets {
+    /// Looks the database URL of each crate up as the query macros do, and ensures their database
+    /// servers are available.
+    async fn resolve(
+        connect_opts: &ConnectOpts,
+        metadata: &Metadata,
+        workspace: bool,
+    ) -> anyhow::Result<Self> {
+        let database_url = connect_opts.database_urls.first().cloned();
+
+        let packages = if workspace {
+            sqlx_macros_dependents(metadata).0
+        } else {
+            let manifest_path = fs::canonicalize("Cargo.toml")?;
+
+            metadata
+                .entries()
+                .map(|(_, package)| package)
+                .filter(|package| {
+                    fs::canonicalize(package.manifest_path())
+                        .is_ok_and(|path| path == manifest_path)
+                })
+                .collect()
+        };
+
+        let mut urls = BTreeSet::new();
+        let mut db_kinds = BTreeMap::new();
+
+        for package in packages {
+            let manifest_dir = package
+                .manifest_path()
+                .parent()
+                .context("manifest path has no parent directory")?;
+            let url = database_url::resolve(package.name(), manifest_dir, database_url.as_deref())?;
+
+            db_kinds.insert(package.name().to_owned(), get_db_kind(&url)?);
+            urls.insert(url);
+        }
+
+        let default_db_kind = match (&database_url, db_kinds.values().next()) {
+            (Some(url), _) => {
+                urls.insert(url.clone());
+                get_db_kind(url)?
+            }
+            (None, Some(db_kind)) => db_kind,
+            (None, None) => bail!("no database URL: pass `--database-url` or set `DATABASE_URL`"),
+        };
+
+        // Ensure the database servers are available.
+        for url in &urls {
+            let connect_opts = ConnectOpts {
+                database_urls: vec![url.clone()],
+                ..connect_opts.clone()
+            };
+
+            crate::connect(&connect_opts).await?.close().await?;
+        }
+
+        Ok(PrepareTargets {
+            database_url,
+            db_kinds,
+            default_db_kind,
+            urls,
+        })
+    }
+
+    /// The databases of the crates, for the prepare cache.
+    fn describe(&self) -> String {
+        self.urls.iter().cloned().collect::<Vec<_>>().join(" ")
+    }
+
+    /// The names of the databases the crates are checked against.
+    fn db_kinds(&self) -> Vec<&'static str> {
+        let db_kinds: BTreeSet<_> = self.db_kinds.values().copied().collect();
+
+        if db_kinds.is_empty() {
+            vec![self.default_db_kind]
+        } else {
+            db_kinds.into_iter().collect()
+        }
+    }
+
+    /// Groups the query data of the crates by their database.
+    fn group(&self, crates: &CrateQueryData) -> BTreeMap<&'static str, CrateQueryData> {
+        let mut groups = BTreeMap::<_, CrateQueryData>::new();
+
+        for db_kind in self.db_kinds() {
+            groups.entry(db_kind).or_default();
+        }
+
+        for (krate, queries) in crates {
+            let db_kind = self
+                .db_kinds
+                .get(krate)
+                .copied()
+                .unwrap_or(self.default_db_kind);
+
+            groups
+                .entry(db_kind)
+                .or_default()
+                .insert(krate.clone(), queries.clone());
+        }
+
+        groups
+    }
+}
+
+fn cargo_metadata(build_opts: &BuildOpts) -> anyhow::Result<(String, Metadata)> {
     anyhow::ensure!(
         Path::new("Cargo.toml").exists(),
         r#"Failed to read `Cargo.toml`.
@@ -114,6 +673,7 @@ hint: This command only works in the manifest directory of a Cargo package."#
 
     let output = Command::new(&cargo)
         .args(&["metadata", "--format-version=1"])
+        .args(build_opts.metadata_args())
         .output()
         .context("Could not fetch metadata")?;
 
@@ -121,33 +681,105 @@ hint: This command only works in the manifest directory of a Cargo package."#
         std::str::from_utf8(&output.stdout).context("Invalid `cargo metadata` output")?;
     let metadata: Metadata = output_str.parse()?;
 
+    Ok((cargo, metadata))
+}
+
+/// The `sqlx-data.json` of the current package, or of the workspace.
+fn data_file_path(metadata: &Metadata, workspace: bool) -> PathBuf {
+    if workspace {
+        metadata.workspace_root().join("sqlx-data.json")
+    } else {
+        PathBuf::from("sqlx-data.json")
+    }
+}
+
+fn run_prepare_step(
+    cargo: &str,
+    metadata: &Metadata,
+    url: Option<&str>,
+    workspace: bool,
+    mut cache: Option<&mut PrepareCache>,
+    cargo_args: Vec<String>,
+) -> anyhow::Result<CrateQueryData> {
     // try removing the target/sqlx directory before running, as stale files
     // have repeatedly caused issues in the past.
     let _ = remove_dir_all(metadata.target_directory().join("sqlx"));
 
-    let check_status = if merge {
+    // the query data of the unchanged workspace members, with `--incremental`
+    let mut fresh = CrateQueryData::new();
+    let mut fingerprints = BTreeMap::new();
+
+    let (check_status, packages) = if workspace {
+        let (dependents, _) = sqlx_macros_dependents(metadata);
+
+        if dependents.is_empty() {
+            bail!("no member of the workspace depends on `sqlx-macros`");
+        }
+
+        if let Some(cache) = &mut cache {
+            cache.retain(|name| dependents.iter().any(|package| package.name() == name));
+
+            for package in dependents {
+                let fingerprint = cache::fingerprint(
+                    package.manifest_path(),
+                    package.src_paths(),
+                    package.features(),
+                )?;
+
+                if let Some(queries) = cache.queries(package.name(), &fingerprint) {
+                    fresh.insert(package.name().to_owned(), queries.clone());
+                }
+
+                fingerprints.insert(package.name().to_owned(), fingerprint);
+            }
+        }
+
+        let mut action = minimal_project_recompile_action(metadata, &fresh)?;
+
+        if cache.is_some() {
+            // only the changed members are rebuilt, and the query data of other crates is ignored
+            action.clean_packages.clear();
+        }
+
+        if action.check_packages.is_empty() {
+            eprintln!(
+                "no package changed since the last `cargo sqlx prepare --incremental`; \
+                 reusing their query data"
+            );
+
+            return Ok(fresh);
+        }
+
         // Try only triggering a recompile on crates that use `sqlx-macros` falling back to a full
         // clean on error
-        match setup_minimal_project_recompile(&cargo, &metadata) {
-            Ok(()) => {}
-            Err(err) => {
-                println!(
-                    "Failed minimal recompile setup. Cleaning entire project. Err: {}",
-                    err
-                );
-                let clean_status = Command::new(&cargo).arg("clean").status()?;
-                if !clean_status.success() {
-                    bail!("`cargo clean` failed with status: {}", clean_status);
-                }
+        if let Err(err) = setup_minimal_project_recompile(cargo, &action) {
+            eprintln!(
+                "Failed minimal recompile setup. Cleaning entire project. Err: {}",
+                err
+            );
+            let clean_status = Command::new(cargo).arg("clean").status()?;
+            if !clean_status.success() {
+                bail!("`cargo clean` failed with status: {}", clean_status);
             }
-        };
+        }
+
+        let packages = action.check_packages;
+
+        let mut check_command = Command::new(cargo);
+        check_command.arg("check");
+
+        for package in &packages {
+            check_command.args(["-p", package]);
+        }
 
-        let mut check_command = Command::new(&cargo);
         check_command
-            .arg("check")
             .args(cargo_args)
             .env("SQLX_OFFLINE", "false")
-            .env("DATABASE_URL", url);
+            .env("CARGO_TARGET_DIR", metadata.target_directory());
+
+        if let Some(url) = url {
+            check_command.env("DATABASE_URL", url);
+        }
 
         // `cargo check` recompiles on changed rust flags which can be set either via the env var
         // or through the `rustflags` field in `$CARGO_HOME/config` when the env var isn't set.
@@ -156,9 +788,10 @@ hint: This command only works in the manifest directory of a Cargo package."#
             check_command.env("RUSTFLAGS", rustflags);
         }
 
-        check_command.status()?
+        (check_command.status()?, Some(packages))
     } else {
-        Command::new(&cargo)
+        let mut rustc_command = Command::new(cargo);
+        rustc_command
             .arg("rustc")
             .args(cargo_args)
             .arg("--")
@@ -171,18 +804,23 @@ hint: This command only works in the manifest directory of a Cargo package."#
                 SystemTime::UNIX_EPOCH.elapsed()?.as_millis()
             ))
             .env("SQLX_OFFLINE", "false")
-            .env("DATABASE_URL", url)
-            .env("CARGO_TARGET_DIR", metadata.target_directory().clone())
-            .status()?
+            .env("CARGO_TARGET_DIR", metadata.target_directory());
+
+        if let Some(url) = url {
+            rustc_command.env("DATABASE_URL", url);
+        }
+
+        (rustc_command.status()?, None)
     };
 
     if !check_status.success() {
         bail!("`cargo check` failed with status: {}", check_status);
     }
 
-    let pattern = metadata.target_directory().join("sqlx/query-*.json");
+    // the query data of each crate is saved in `target/sqlx/<crate>/`
+    let pattern = metadata.target_directory().join("sqlx/*/query-*.json");
 
-    let mut data = BTreeMap::new();
+    let mut crates = CrateQueryData::new();
 
     for path in glob::glob(
         pattern
@@ -190,6 +828,21 @@ hint: This command only works in the manifest directory of a Cargo package."#
             .context("CARGO_TARGET_DIR not valid UTF-8")?,
     )? {
         let path = path?;
+
+        let krate = path
+            .parent()
+            .and_then(Path::file_name)
+            .and_then(|name| name.to_str())
+            .context("query data saved outside of a crate directory")?
+            .to_owned();
+
+        // crates outside of the workspace which were recompiled have their own query data
+        if let Some(packages) = &packages {
+            if !packages.contains(&krate) {
+                continue;
+            }
+        }
+
         let contents = fs::read(&*path)?;
         let mut query_data: JsonObject = serde_json::from_slice(&contents)?;
 
@@ -199,7 +852,10 @@ hint: This command only works in the manifest directory of a Cargo package."#
             .context("expected key `hash` in query data")?;
 
         if let serde_json::Value::String(hash) = hash {
-            data.insert(hash, serde_json::Value::Object(query_data));
+            crates
+                .entry(krate)
+                .or_default()
+                .insert(hash, serde_json::Value::Object(query_data));
         } else {
             bail!(
                 "expected key `hash` in query data to be string, was {:?} instead; file: {}",
@@ -212,35 +868,264 @@ hint: This command only works in the manifest directory of a Cargo package."#
         let _ = fs::remove_file(&path);
     }
 
+    if let (Some(cache), Some(packages)) = (cache, &packages) {
+        for package in packages {
+            let queries = crates.get(package).cloned().unwrap_or_default();
+            cache.insert(package, fingerprints[package].clone(), queries);
+        }
+    }
+
+    crates.extend(fresh);
+
+    Ok(crates)
+}
+
+/// Merges the query data of every crate, which must agree on the queries they share.
+fn merge_crate_data(crates: &CrateQueryData) -> anyhow::Result<QueryData> {
+    let mut data = QueryData::new();
+    let mut owners = BTreeMap::new();
+
+    for (krate, queries) in crates {
+        for (hash, query) in queries {
+            match data.entry(hash.clone()) {
+                btree_map::Entry::Vacant(entry) => {
+                    entry.insert(query.clone());
+                    owners.insert(hash, krate);
+                }
+                btree_map::Entry::Occupied(entry) if same_describe(entry.get(), query) => {}
+                btree_map::Entry::Occupied(_) => bail!(
+                    "query {} has different data in `{}` and `{}`; \
+                     do they depend on different versions of sqlx?\nquery: {}",
+                    hash,
+                    owners[hash],
+                    krate,
+                    query_summary(query)
+                ),
+            }
+        }
+    }
+
     Ok(data)
 }
 
+/// Whether the data of two queries with the same hash agree, as their text may differ in
+/// whitespace only.
+fn same_describe(a: &serde_json::Value, b: &serde_json::Value) -> bool {
+    a["describe"] == b["describe"]
+}
+
+/// The query data found by `cargo sqlx prepare --check` to be out of date, failing it with the
+/// exit code of its changes.
+#[derive(Debug)]
+pub struct StaleQueryData {
+    exit_code: i32,
+}
+
+impl StaleQueryData {
+    pub fn exit_code(&self) -> i32 {
+        self.exit_code
+    }
+}
+
+impl std::fmt::Display for StaleQueryData {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("the query data is out of date")
+    }
+}
+
+impl std::error::Error for StaleQueryData {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum QueryChangeKind {
+    /// The query is missing in the saved data.
+    Added,
+    /// The saved query isn't used by any crate anymore.
+    Removed,
+    /// The describe output of the query differs from the saved one, e.g. as the schema changed.
+    Changed,
+}
+
+impl QueryChangeKind {
+    fn as_str(&self) -> &'static str {
+        match self {
+            QueryChangeKind::Added => "added",
+            QueryChangeKind::Removed => "removed",
+            QueryChangeKind::Changed => "changed",
+        }
+    }
+
+    // a bit of the exit code of `prepare --check` for each kind of change
+    fn exit_code(&self) -> i32 {
+        match self {
+            QueryChangeKind::Added => 2,
+            QueryChangeKind::Removed => 4,
+            QueryChangeKind::Changed => 8,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct QueryChange<'a> {
+    kind: QueryChangeKind,
+    hash: &'a str,
+    /// The crate of the query, or `None` for a removed query.
+    krate: Option<&'a str>,
+    /// The current query data, or the saved data of a removed query.
+    query: &'a serde_json::Value,
+}
+
+/// Compares the query data of the crates to the saved data, by kind of change and crate.
+fn query_changes<'a>(crates: &'a CrateQueryData, saved: &'a QueryData) -> Vec<QueryChange<'a>> {
+    let mut changes = Vec::new();
+
+    for (krate, queries) in crates {
+        for (hash, query) in queries {
+            let kind = match saved.get(hash) {
+                None => QueryChangeKind::Added,
+                Some(saved) if !same_describe(saved, query) => QueryChangeKind::Changed,
+                Some(_) => continue,
+            };
+
+            changes.push(QueryChange {
+                kind,
+                hash,
+                krate: Some(krate),
+                query,
+            });
+        }
+    }
+
+    for (hash, query) in saved {
+        if !crates.values().any(|queries| queries.contains_key(hash)) {
+            changes.push(QueryChange {
+                kind: QueryChangeKind::Removed,
+                hash,
+                krate: None,
+                query,
+            });
+        }
+    }
+
+    changes.sort_by(|a, b| (a.kind, a.krate).cmp(&(b.kind, b.krate)));
+    changes
+}
+
+/// A saved query which no crate uses anymore.
+#[derive(Debug, PartialEq)]
+struct OrphanedQuery {
+    db_kind: String,
+    hash: String,
+    query: serde_json::Value,
+}
+
+impl OrphanedQuery {
+    fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "database": self.db_kind,
+            "hash": self.hash,
+            "query": self.query["query"],
+        })
+    }
+}
+
+impl Display for OrphanedQuery {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:<10} {}  {}",
+            self.db_kind,
+            self.hash.get(..16).unwrap_or(&self.hash),
+            query_summary(&self.query)
+        )
+    }
+}
+
+/// The saved queries missing in the `fresh` data of their database, which is all of them for the
+/// databases without fresh data.
+fn orphaned_queries(saved: &DataFile, fresh: &BTreeMap<&str, QueryData>) -> Vec<OrphanedQuery> {
+    saved
+        .databases
+        .iter()
+        .flat_map(|(db_kind, database)| {
+            let fresh = fresh.get(db_kind.as_str());
+
+            database
+                .data
+                .iter()
+                .filter(move |(hash, _)| !fresh.is_some_and(|fresh| fresh.contains_key(*hash)))
+                .map(move |(hash, query)| OrphanedQuery {
+                    db_kind: db_kind.clone(),
+                    hash: hash.clone(),
+                    query: query.clone(),
+                })
+        })
+        .collect()
+}
+
+/// The document printed by `cargo sqlx prepare --check --json`.
+fn changes_json(
+    changes: &[QueryChange<'_>],
+    orphans: &[OrphanedQuery],
+    data_file_path: &Path,
+) -> serde_json::Value {
+    let changes: Vec<_> = changes
+        .iter()
+        .map(|change| {
+            serde_json::json!({
+                "kind": change.kind.as_str(),
+                "hash": change.hash,
+                "crate": change.krate,
+                "query": change.query["query"],
+            })
+        })
+        .collect();
+
+    serde_json::json!({
+        "data_file": data_file_path,
+        "changes": changes,
+        "orphans": orphans.iter().map(OrphanedQuery::to_json).collect::<Vec<_>>(),
+    })
+}
+
+/// The first line of the query, shortened to fit on a line.
+fn query_summary(query: &serde_json::Value) -> String {
+    let text = query["query"].as_str().unwrap_or_default().trim();
+    let line = text.lines().next().unwrap_or_default();
+
+    if line.chars().count() > 60 {
+        format!("{}...", line.chars().take(60).collect::<String>())
+    } else if line.len() < text.len() {
+        format!("{} ...", line)
+    } else {
+        line.to_owned()
+    }
+}
+
 #[derive(Debug, PartialEq)]
 struct ProjectRecompileAction {
     // The names of the packages
     clean_packages: Vec<String>,
     touch_paths: Vec<PathBuf>,
+    // The names of the workspace members to check
+    check_packages: Vec<String>,
 }
 
 /// Sets up recompiling only crates that depend on `sqlx-macros`
 ///
-/// This gets a listing of all crates that depend on `sqlx-macros` (direct and transitive). The
-/// crates within the current workspace have their source file's mtimes updated while crates
+/// The crates within the current workspace have their source file's mtimes updated while crates
 /// outside the workspace are selectively `cargo clean -p`ed. In this way we can trigger a
 /// recompile of crates that may be using compile-time macros without forcing a full recompile
-fn setup_minimal_project_recompile(cargo: &str, metadata: &Metadata) -> anyhow::Result<()> {
-    let ProjectRecompileAction {
-        clean_packages,
-        touch_paths,
-    } = minimal_project_recompile_action(metadata)?;
-
-    for file in touch_paths {
+fn setup_minimal_project_recompile(
+    cargo: &str,
+    action: &ProjectRecompileAction,
+) -> anyhow::Result<()> {
+    for file in &action.touch_paths {
         let now = filetime::FileTime::now();
-        filetime::set_file_times(&file, now, now)
+        filetime::set_file_times(file, now, now)
             .with_context(|| format!("Failed to update mtime for {:?}", file))?;
     }
 
-    for pkg_id in &clean_packages {
+    for pkg_id in &action.clean_packages {
         let clean_status = Command::new(cargo)
             .args(&["clean", "-p", pkg_id])
             .status()?;
@@ -253,8 +1138,9 @@ fn setup_minimal_project_recompile(cargo: &str, metadata: &Metadata) -> anyhow::
     Ok(())
 }
 
-fn minimal_project_recompile_action(metadata: &Metadata) -> anyhow::Result<ProjectRecompileAction> {
-    // Get all the packages that depend on `sqlx-macros`
+/// Gets all the packages that depend on `sqlx-macros` (direct and transitive), split into the
+/// members of the workspace and the packages outside of it
+fn sqlx_macros_dependents(metadata: &Metadata) -> (Vec<&Package>, Vec<&Package>) {
     let mut sqlx_macros_dependents = BTreeSet::new();
     let sqlx_macros_ids: BTreeSet<_> = metadata
         .entries()
@@ -271,36 +1157,51 @@ fn minimal_project_recompile_action(metadata: &Metadata) -> anyhow::Result<Proje
     let mut in_workspace_dependents = Vec::new();
     let mut out_of_workspace_dependents = Vec::new();
     for dependent in sqlx_macros_dependents {
-        if metadata.workspace_members().contains(&dependent) {
-            in_workspace_dependents.push(dependent);
+        let package = match metadata.package(dependent) {
+            Some(package) => package,
+            None => continue,
+        };
+
+        if metadata.workspace_members().contains(dependent) {
+            in_workspace_dependents.push(package);
         } else {
-            out_of_workspace_dependents.push(dependent);
+            out_of_workspace_dependents.push(package);
         }
     }
 
+    (in_workspace_dependents, out_of_workspace_dependents)
+}
+
+/// Determines how to recompile the crates that depend on `sqlx-macros`, except for the workspace
+/// members whose query data is `fresh`
+fn minimal_project_recompile_action(
+    metadata: &Metadata,
+    fresh: &CrateQueryData,
+) -> anyhow::Result<ProjectRecompileAction> {
+    let (mut in_workspace_dependents, out_of_workspace_dependents) =
+        sqlx_macros_dependents(metadata);
+
+    in_workspace_dependents.retain(|package| !fresh.contains_key(package.name()));
+
     // In-workspace dependents have their source file's mtime updated. Out-of-workspace get
     // `cargo clean -p <PKGID>`ed
     let files_to_touch: Vec<_> = in_workspace_dependents
         .iter()
-        .filter_map(|id| {
-            metadata
-                .package(id)
-                .map(|package| package.src_paths().to_owned())
-        })
-        .flatten()
+        .flat_map(|package| package.src_paths().to_owned())
         .collect();
     let packages_to_clean: Vec<_> = out_of_workspace_dependents
         .iter()
-        .filter_map(|id| {
-            metadata
-                .package(id)
-                .map(|package| package.name().to_owned())
-        })
+        .map(|package| package.name().to_owned())
+        .collect();
+    let packages_to_check: Vec<_> = in_workspace_dependents
+        .iter()
+        .map(|package| package.name().to_owned())
         .collect();
 
     Ok(ProjectRecompileAction {
         clean_packages: packages_to_clean,
         touch_paths: files_to_touch,
+        check_packages: packages_to_check,
     })
 }
 
@@ -320,6 +1221,8 @@ fn get_db_kind(url: &str) -> anyhow::Result<&'static str> {
 
         #[cfg(feature = "mssql")]
         AnyKind::Mssql => Ok("MSSQL"),
+
+        AnyKind::Custom(name) => bail!("the query macros don't support {} databases", name),
     }
 }
 
@@ -332,49 +1235,104 @@ mod tests {
     #[test]
     fn data_file_serialization_works() {
         let data_file = DataFile {
-            db: "mysql".to_owned(),
-            data: {
-                let mut data = BTreeMap::new();
-                data.insert("a".to_owned(), json!({"key1": "value1"}));
-                data.insert("z".to_owned(), json!({"key2": "value2"}));
-                data
-            },
+            databases: BTreeMap::from([(
+                "MySQL".to_owned(),
+                DatabaseData {
+                    build: None,
+                    data: {
+                        let mut data = BTreeMap::new();
+                        data.insert("a".to_owned(), json!({"key1": "value1"}));
+                        data.insert("z".to_owned(), json!({"key2": "value2"}));
+                        data
+                    },
+                },
+            )]),
         };
 
         let data_file = serde_json::to_string(&data_file).expect("Data file serialized.");
 
         assert_eq!(
             data_file,
-            "{\"db\":\"mysql\",\"a\":{\"key1\":\"value1\"},\"z\":{\"key2\":\"value2\"}}"
+            "{\"MySQL\":{\"a\":{\"key1\":\"value1\"},\"z\":{\"key2\":\"value2\"}}}"
         );
     }
 
     #[test]
     fn data_file_deserialization_works() {
         let data_file =
-            "{\"db\":\"mysql\",\"a\":{\"key1\":\"value1\"},\"z\":{\"key2\":\"value2\"}}";
+            "{\"MySQL\":{\"a\":{\"key1\":\"value1\"}},\"SQLite\":{\"z\":{\"key2\":\"value2\"}}}";
 
-        let data_file: DataFile = serde_json::from_str(data_file).expect("Data file deserialized.");
-        let DataFile { db, data } = data_file;
+        let DataFile { databases } =
+            DataFile::parse(data_file.as_bytes()).expect("Data file deserialized.");
 
-        assert_eq!(db, "mysql");
-        assert_eq!(data.len(), 2);
-        assert_eq!(data.get("a"), Some(&json!({"key1": "value1"})));
-        assert_eq!(data.get("z"), Some(&json!({"key2": "value2"})));
+        assert_eq!(databases.keys().collect::<Vec<_>>(), ["MySQL", "SQLite"]);
+        assert_eq!(
+            databases["MySQL"].data.get("a"),
+            Some(&json!({"key1": "value1"}))
+        );
+        assert_eq!(
+            databases["SQLite"].data.get("z"),
+            Some(&json!({"key2": "value2"}))
+        );
     }
 
     #[test]
-    fn data_file_deserialization_works_for_ordered_keys() {
-        let data_file =
-            "{\"a\":{\"key1\":\"value1\"},\"db\":\"mysql\",\"z\":{\"key2\":\"value2\"}}";
+    fn data_file_of_a_single_database_is_upgraded() {
+        let data_file = "{\"a\":{\"query\":\"SELECT 1\"},\"db\":\"MySQL\",\"build\":{\"features\":[\"mysql\"],\"all_features\":false,\"no_default_features\":false,\"profile\":null,\"target\":null}}";
+
+        let DataFile { databases } =
+            DataFile::parse(data_file.as_bytes()).expect("Data file deserialized.");
+
+        assert_eq!(databases.keys().collect::<Vec<_>>(), ["MySQL"]);
+
+        let DatabaseData { build, data } = &databases["MySQL"];
+        assert_eq!(build.as_ref().unwrap().features, ["mysql"]);
+        // the queries are hashed with the name of the database, as by the query macros
+        assert_eq!(
+            data.keys().collect::<Vec<_>>(),
+            [&hash_query("MySQL", "SELECT 1")]
+        );
+        assert_ne!(
+            hash_query("MySQL", "SELECT 1"),
+            hash_query("SQLite", "SELECT 1")
+        );
+    }
 
-        let data_file: DataFile = serde_json::from_str(data_file).expect("Data file deserialized.");
-        let DataFile { db, data } = data_file;
+    #[test]
+    fn data_file_records_the_build_config() {
+        assert_eq!(BuildConfig::new(&BuildOpts::default()), None);
 
-        assert_eq!(db, "mysql");
-        assert_eq!(data.len(), 2);
-        assert_eq!(data.get("a"), Some(&json!({"key1": "value1"})));
-        assert_eq!(data.get("z"), Some(&json!({"key2": "value2"})));
+        let build_opts = BuildOpts {
+            features: vec!["postgres, tls".into(), "postgres".into()],
+            no_default_features: true,
+            ..BuildOpts::default()
+        };
+        let build = BuildConfig::new(&build_opts).expect("build config recorded");
+        assert_eq!(build.features, ["postgres", "tls"]);
+        assert_eq!(
+            build.to_string(),
+            "--features postgres,tls --no-default-features"
+        );
+        assert_eq!(
+            build_opts.cargo_args(),
+            ["--features", "postgres,tls", "--no-default-features"]
+        );
+
+        let data_file = DataFile {
+            databases: BTreeMap::from([(
+                "MySQL".to_owned(),
+                DatabaseData {
+                    build: Some(build),
+                    data: BTreeMap::new(),
+                },
+            )]),
+        };
+
+        let mut data_file =
+            DataFile::parse(serde_json::to_string(&data_file).unwrap().as_bytes()).unwrap();
+        let data = data_file.databases.remove("MySQL").unwrap();
+        assert_eq!(data.build.unwrap().features, ["postgres", "tls"]);
+        assert!(data.data.is_empty());
     }
 
     #[test]
@@ -385,7 +1343,7 @@ mod tests {
         let sample_metadata = std::fs::read_to_string(sample_metadata_path)?;
         let metadata: Metadata = sample_metadata.parse()?;
 
-        let action = minimal_project_recompile_action(&metadata)?;
+        let action = minimal_project_recompile_action(&metadata, &CrateQueryData::new())?;
         assert_eq!(
             action,
             ProjectRecompileAction {
@@ -393,10 +1351,230 @@ mod tests {
                 touch_paths: vec![
                     "/home/user/problematic/workspace/b_in_workspace_lib/src/lib.rs".into(),
                     "/home/user/problematic/workspace/c_in_workspace_bin/src/main.rs".into(),
-                ]
+                ],
+                check_packages: vec!["b_in_workspace_lib".into(), "c_in_workspace_bin".into()],
             }
         );
 
         Ok(())
     }
+
+    fn crate_data(crates: &[(&str, &[(&str, serde_json::Value)])]) -> CrateQueryData {
+        crates
+            .iter()
+            .map(|(krate, queries)| {
+                let queries = queries
+                    .iter()
+                    .map(|(hash, query)| (hash.to_string(), query.clone()))
+                    .collect();
+
+                (krate.to_string(), queries)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn merge_crate_data_works() -> anyhow::Result<()> {
+        let crates = crate_data(&[
+            ("a", &[("1", json!({"query": "SELECT 1"}))]),
+            (
+                "b",
+                &[
+                    ("1", json!({"query": "SELECT 1"})),
+                    ("2", json!({"query": "SELECT 2"})),
+                ],
+            ),
+        ]);
+
+        let data = merge_crate_data(&crates)?;
+        assert_eq!(data.keys().collect::<Vec<_>>(), ["1", "2"]);
+
+        let crates = crate_data(&[
+            ("a", &[("1", json!({"query": "SELECT 1", "describe": 1}))]),
+            ("b", &[("1", json!({"query": "SELECT 1", "describe": 2}))]),
+        ]);
+
+        let err = merge_crate_data(&crates).unwrap_err().to_string();
+        assert!(err.contains("`a` and `b`"), "{}", err);
+
+        Ok(())
+    }
+
+    #[test]
+    fn query_changes_works() {
+        let crates = crate_data(&[
+            (
+                "a",
+                &[
+                    ("1", json!({"query": "SELECT 1"})),
+                    ("2", json!({"query": "SELECT 2"})),
+                ],
+            ),
+            ("b", &[("4", json!({"query": "SELECT 4", "describe": 2}))]),
+        ]);
+
+        let mut saved = QueryData::new();
+        saved.insert("1".into(), json!({"query": "SELECT 1"}));
+        saved.insert("3".into(), json!({"query": "SELECT 3"}));
+        saved.insert("4".into(), json!({"query": "SELECT 4", "describe": 1}));
+
+        let changes = query_changes(&crates, &saved);
+        assert_eq!(
+            changes,
+            [
+                QueryChange {
+                    kind: QueryChangeKind::Added,
+                    hash: "2",
+                    krate: Some("a"),
+                    query: &json!({"query": "SELECT 2"}),
+                },
+                QueryChange {
+                    kind: QueryChangeKind::Removed,
+                    hash: "3",
+                    krate: None,
+                    query: &json!({"query": "SELECT 3"}),
+                },
+                QueryChange {
+                    kind: QueryChangeKind::Changed,
+                    hash: "4",
+                    krate: Some("b"),
+                    query: &json!({"query": "SELECT 4", "describe": 2}),
+                },
+            ]
+        );
+
+        assert!(query_changes(&crates, &merge_crate_data(&crates).unwrap()).is_empty());
+    }
+
+    #[test]
+    fn changes_json_works() {
+        let crates = crate_data(&[("a", &[("2", json!({"query": "SELECT 2"}))])]);
+
+        let mut saved = QueryData::new();
+        saved.insert("3".into(), json!({"query": "SELECT 3"}));
+
+        let changes = query_changes(&crates, &saved);
+        let orphans = [OrphanedQuery {
+            db_kind: "MySQL".into(),
+            hash: "4".into(),
+            query: json!({"query": "SELECT 4"}),
+        }];
+        assert_eq!(
+            changes_json(&changes, &orphans, Path::new("sqlx-data.json")),
+            json!({
+                "data_file": "sqlx-data.json",
+                "changes": [
+                    {"kind": "added", "hash": "2", "crate": "a", "query": "SELECT 2"},
+                    {"kind": "removed", "hash": "3", "crate": null, "query": "SELECT 3"},
+                ],
+                "orphans": [
+                    {"database": "MySQL", "hash": "4", "query": "SELECT 4"},
+                ],
+            })
+        );
+    }
+
+    #[test]
+    fn normalize_query_works() {
+        assert_eq!(
+            normalize_query("\n  SELECT *\n    FROM  foo\tWHERE id = $1\n"),
+            "SELECT * FROM foo WHERE id = $1"
+        );
+        // literals, quoted identifiers and the newline ending a line comment are kept
+        assert_eq!(
+            normalize_query("SELECT 'a  b', \"c  d\",  `e  f`, 'it''s  x'"),
+            "SELECT 'a  b', \"c  d\", `e  f`, 'it''s  x'"
+        );
+        assert_eq!(
+            normalize_query("SELECT 1 -- one  two\n   , 2"),
+            "SELECT 1 -- one  two\n, 2"
+        );
+        assert_eq!(
+            normalize_query("SELECT $$a  b$$,  $tag$c  $$ d$tag$,  $1"),
+            "SELECT $$a  b$$, $tag$c  $$ d$tag$, $1"
+        );
+        // unterminated literals are kept as is
+        assert_eq!(normalize_query("SELECT 'a  b"), "SELECT 'a  b");
+        assert_eq!(normalize_query("SELECT $a  b"), "SELECT $a b");
+
+        assert_eq!(
+            hash_query("PostgreSQL", "SELECT  1\n"),
+            hash_query("PostgreSQL", "SELECT 1")
+        );
+        assert_ne!(
+            hash_query("PostgreSQL", "SELECT '  1'"),
+            hash_query("PostgreSQL", "SELECT ' 1'")
+        );
+    }
+
+    #[test]
+    fn data_file_hashes_are_upgraded() {
+        let mut data_file = DataFile::default();
+        data_file.databases.insert(
+            "SQLite".into(),
+            DatabaseData {
+                build: None,
+                data: BTreeMap::from([
+                    (
+                        legacy_hash_query("SQLite", "SELECT\n  1"),
+                        json!({"query": "SELECT\n  1"}),
+                    ),
+                    (
+                        hash_query("SQLite", "SELECT\n  2"),
+                        json!({"query": "SELECT\n  2"}),
+                    ),
+                    ("3".into(), json!({"query": "SELECT 3"})),
+                ]),
+            },
+        );
+
+        assert_eq!(data_file.upgrade_hashes(), 1);
+
+        let data = &data_file.databases["SQLite"].data;
+        assert!(data.contains_key(&hash_query("SQLite", "SELECT 1")));
+        assert!(data.contains_key(&hash_query("SQLite", "SELECT 2")));
+        assert!(data.contains_key("3"));
+    }
+
+    #[test]
+    fn orphaned_queries_works() {
+        let mut data_file = DataFile::default();
+
+        for (db_kind, hash) in [("MySQL", "1"), ("MySQL", "2"), ("SQLite", "3")] {
+            data_file
+                .databases
+                .entry(db_kind.into())
+                .or_default()
+                .data
+                .insert(hash.into(), json!({ "query": format!("SELECT {}", hash) }));
+        }
+
+        let fresh = BTreeMap::from([(
+            "MySQL",
+            QueryData::from([("1".into(), json!({"query": "SELECT 1"}))]),
+        )]);
+
+        let orphans = orphaned_queries(&data_file, &fresh);
+        assert_eq!(
+            orphans
+                .iter()
+                .map(|orphan| (&*orphan.db_kind, &*orphan.hash))
+                .collect::<Vec<_>>(),
+            [("MySQL", "2"), ("SQLite", "3")]
+        );
+        assert_eq!(orphans[1].to_string(), "SQLite     3  SELECT 3");
+    }
+
+    #[test]
+    fn query_summary_works() {
+        assert_eq!(query_summary(&json!({"query": "SELECT 1"})), "SELECT 1");
+        assert_eq!(
+            query_summary(&json!({"query": "SELECT 1\nFROM foo"})),
+            "SELECT 1 ..."
+        );
+        assert_eq!(
+            query_summary(&json!({ "query": "x".repeat(70) })),
+            format!("{}...", "x".repeat(60))
+        );
+    }
 }
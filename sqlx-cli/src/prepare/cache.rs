@@ -0,0 +1,258 @@
+//! The fingerprints and query data of the workspace members prepared by
+//! `cargo sqlx prepare --workspace --incremental`, so the members which didn't change since aren't
+//! rebuilt.
+
+use super::QueryData;
+use crate::metadata::Metadata;
+use anyhow::Context;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::{self, File};
+use std::hash::{Hash, Hasher};
+use std::io::{BufReader, BufWriter};
+use std::path::{Path, PathBuf};
+
+/// Bumped whenever the format of the cache or of its fingerprints changes, dropping older caches.
+const VERSION: u32 = 3;
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct PrepareCache {
+    version: u32,
+    /// A hash of `DATABASE_URL` and of the cargo configuration, which the query data depends on
+    config: String,
+    packages: BTreeMap<String, CachedPackage>,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+struct CachedPackage {
+    fingerprint: String,
+    queries: QueryData,
+}
+
+impl PrepareCache {
+    /// An empty cache for `config`, e.g. the database URL and the cargo arguments.
+    pub fn new(config: &str) -> Self {
+        PrepareCache {
+            version: VERSION,
+            config: hash_str(config),
+            packages: BTreeMap::new(),
+        }
+    }
+
+    /// The cache is kept in the target directory, next to the query data it stands in for.
+    pub fn path(metadata: &Metadata) -> PathBuf {
+        metadata.target_directory().join("sqlx-prepare-cache.json")
+    }
+
+    /// Reads the cache at `path`, or returns an empty cache if it doesn't exist, can't be read, or
+    /// is for another `config`.
+    pub fn load(path: &Path, config: &str) -> Self {
+        let cache = File::open(path)
+            .ok()
+            .and_then(|file| serde_json::from_reader::<_, Self>(BufReader::new(file)).ok());
+
+        match cache {
+            Some(cache) if cache.version == VERSION && cache.config == hash_str(config) => cache,
+            _ => PrepareCache::new(config),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> anyhow::Result<()> {
+        serde_json::to_writer(
+            BufWriter::new(
+                File::create(path)
+                    .with_context(|| format!("failed to create `{}`", path.display()))?,
+            ),
+            self,
+        )
+        .with_context(|| format!("failed to write to `{}`", path.display()))
+    }
+
+    /// The query data of the package, if its fingerprint didn't change since it was cached.
+    pub fn queries(&self, package: &str, fingerprint: &str) -> Option<&QueryData> {
+        self.packages
+            .get(package)
+            .filter(|cached| cached.fingerprint == fingerprint)
+            .map(|cached| &cached.queries)
+    }
+
+    pub fn insert(&mut self, package: &str, fingerprint: String, queries: QueryData) {
+        self.packages.insert(
+            package.to_owned(),
+            CachedPackage {
+                fingerprint,
+                queries,
+            },
+        );
+    }
+
+    /// Drops the packages for which `f` returns false, e.g. which left the workspace.
+    pub fn retain(&mut self, mut f: impl FnMut(&str) -> bool) {
+        self.packages.retain(|package, _| f(package));
+    }
+}
+
+/// Fingerprints the sources of a package and its activated `features`: its `Cargo.toml` at
+/// `manifest_path`, and the contents of the directories of its targets' source files, such as
+/// `src/`, or the source file alone if it is in the root of the package, such as `build.rs`.
+///
+/// The contents are hashed rather than their mtimes compared, as `cargo sqlx prepare` updates
+/// the mtimes of the source files of the packages it rebuilds.
+pub fn fingerprint(
+    manifest_path: &Path,
+    src_paths: &[PathBuf],
+    features: &[String],
+) -> anyhow::Result<String> {
+    let root = manifest_path.parent().unwrap_or_else(|| Path::new(""));
+
+    let mut files = BTreeSet::new();
+    files.insert(manifest_path.to_path_buf());
+
+    for src_path in src_paths {
+        match src_path.parent() {
+            Some(dir) if dir != root => collect_files(dir, &mut files)?,
+            _ => {
+                files.insert(src_path.clone());
+            }
+        }
+    }
+
+    let mut hasher = DefaultHasher::new();
+    features.hash(&mut hasher);
+
+    for file in &files {
+        let contents =
+            fs::read(file).with_context(|| format!("failed to read `{}`", file.display()))?;
+
+        file.strip_prefix(root).unwrap_or(file).hash(&mut hasher);
+        contents.hash(&mut hasher);
+    }
+
+    Ok(format!("{:016x}", hasher.finish()))
+}
+
+fn collect_files(dir: &Path, files: &mut BTreeSet<PathBuf>) -> anyhow::Result<()> {
+    for entry in fs::read_dir(dir).with_context(|| format!("failed to read `{}`", dir.display()))? {
+        let entry = entry?;
+        let path = entry.path();
+
+        // skip hidden files and directories, e.g. of editors
+        if entry.file_name().to_string_lossy().starts_with('.') {
+            continue;
+        }
+
+        if entry.file_type()?.is_dir() {
+            collect_files(&path, files)?;
+        } else {
+            files.insert(path);
+        }
+    }
+
+    Ok(())
+}
+
+// `DefaultHasher` may change between Rust releases, which only makes the cache miss once
+fn hash_str(s: &str) -> String {
+    let mut hasher = DefaultHasher::new();
+    s.hash(&mut hasher);
+    format!("{:016x}", hasher.finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn temp_package(name: &str) -> anyhow::Result<(PathBuf, Vec<PathBuf>)> {
+        let root = std::env::temp_dir().join(format!(
+            "sqlx-prepare-cache-{}-{}",
+            name,
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("src/bin"))?;
+
+        fs::write(root.join("Cargo.toml"), "[package]")?;
+        fs::write(root.join("build.rs"), "fn main() {}")?;
+        fs::write(root.join("src/lib.rs"), "mod queries;")?;
+        fs::write(root.join("src/queries.sql"), "SELECT 1")?;
+        fs::write(root.join("src/bin/main.rs"), "fn main() {}")?;
+        fs::write(root.join("README.md"), "")?;
+
+        let src_paths = vec![
+            root.join("src/lib.rs"),
+            root.join("src/bin/main.rs"),
+            root.join("build.rs"),
+        ];
+
+        Ok((root, src_paths))
+    }
+
+    #[test]
+    fn fingerprint_tracks_the_sources() -> anyhow::Result<()> {
+        let (root, src_paths) = temp_package("fingerprint")?;
+        let manifest_path = root.join("Cargo.toml");
+        let fingerprint = fingerprint(&manifest_path, &src_paths, &[])?;
+
+        // updating mtimes, files outside of the source directories and hidden files don't matter
+        let now = filetime::FileTime::now();
+        filetime::set_file_times(root.join("src/lib.rs"), now, now)?;
+        fs::write(root.join("README.md"), "# README")?;
+        fs::write(root.join("src/.lib.rs.swp"), "")?;
+        assert_eq!(
+            super::fingerprint(&manifest_path, &src_paths, &[])?,
+            fingerprint
+        );
+
+        fs::write(root.join("src/queries.sql"), "SELECT 2")?;
+        let changed = super::fingerprint(&manifest_path, &src_paths, &[])?;
+        assert_ne!(changed, fingerprint);
+
+        fs::write(root.join("build.rs"), "fn main() { }")?;
+        assert_ne!(
+            super::fingerprint(&manifest_path, &src_paths, &[])?,
+            changed
+        );
+
+        let features = ["postgres".to_owned()];
+        assert_ne!(
+            super::fingerprint(&manifest_path, &src_paths, &features)?,
+            super::fingerprint(&manifest_path, &src_paths, &[])?
+        );
+
+        fs::remove_dir_all(root)?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn cache_round_trips() -> anyhow::Result<()> {
+        let (root, _) = temp_package("round-trip")?;
+        let path = root.join("sqlx-prepare-cache.json");
+        let url = "sqlite://db.sqlite";
+
+        let mut cache = PrepareCache::load(&path, url);
+        assert!(cache.queries("a", "1").is_none());
+
+        let queries: QueryData = [("hash".to_owned(), json!({"query": "SELECT 1"}))]
+            .into_iter()
+            .collect();
+        cache.insert("a", "1".into(), queries.clone());
+        cache.insert("b", "2".into(), QueryData::new());
+        cache.retain(|package| package == "a");
+        cache.save(&path)?;
+
+        let cache = PrepareCache::load(&path, url);
+        assert_eq!(cache.queries("a", "1"), Some(&queries));
+        assert!(cache.queries("a", "2").is_none());
+        assert!(cache.queries("b", "2").is_none());
+
+        // the cache of another database is dropped
+        let cache = PrepareCache::load(&path, "sqlite://other.sqlite");
+        assert!(cache.queries("a", "1").is_none());
+
+        fs::remove_dir_all(root)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,162 @@
+//! The database URL of each crate, looked up as by the query macros, so that the query data is
+//! prepared against the database each crate is checked against. It's looked up, in order, in:
+//!
+//! 1. the `SQLX_DATABASE_URL_<CRATE>` environment variable, e.g. `SQLX_DATABASE_URL_MY_APP` for
+//!    the package `my-app`;
+//! 2. the `.env` files from the manifest directory of the crate up to the root of its workspace,
+//!    for `SQLX_DATABASE_URL_<CRATE>` and then `DATABASE_URL`;
+//! 3. the `DATABASE_URL` given to `prepare`, which is passed on to the macros.
+
+use anyhow::bail;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The environment variable of the database URL of `package`.
+pub fn package_var(package: &str) -> String {
+    format!(
+        "SQLX_DATABASE_URL_{}",
+        package.to_uppercase().replace('-', "_")
+    )
+}
+
+/// Looks the database URL of `package` up, falling back to `database_url`.
+pub fn resolve(
+    package: &str,
+    manifest_dir: &Path,
+    database_url: Option<&str>,
+) -> anyhow::Result<String> {
+    let package_var = package_var(package);
+    let mut consulted = vec![format!("`{}`", package_var)];
+
+    if let Ok(url) = env::var(&package_var) {
+        return Ok(url);
+    }
+
+    for dir in env_dirs(manifest_dir) {
+        let path = dir.join(".env");
+        consulted.push(format!("`{}`", path.display()));
+
+        if let Some(url) = read_env_file(&path, &package_var) {
+            return Ok(url);
+        }
+    }
+
+    consulted.push("`DATABASE_URL`".to_owned());
+
+    match database_url {
+        Some(url) => Ok(url.to_owned()),
+        None => bail!(
+            "no database URL for `{}`: looked in {}",
+            package,
+            consulted.join(", ")
+        ),
+    }
+}
+
+/// The directories from `manifest_dir` up to the root of its workspace, the first one whose
+/// `Cargo.toml` has a `[workspace]` table, or only `manifest_dir` if there's none.
+///
+/// The macros can't run `cargo metadata` for the root of the workspace, so it's found the same
+/// way here.
+fn env_dirs(manifest_dir: &Path) -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+
+    for dir in manifest_dir.ancestors() {
+        dirs.push(dir.to_owned());
+
+        let is_workspace_root = fs::read_to_string(dir.join("Cargo.toml")).is_ok_and(|toml| {
+            toml.lines()
+                .any(|line| line.trim_start().starts_with("[workspace"))
+        });
+
+        if is_workspace_root {
+            return dirs;
+        }
+    }
+
+    vec![manifest_dir.to_owned()]
+}
+
+/// Reads the URL of `package_var`, or else `DATABASE_URL`, from the `.env` file at `path`.
+fn read_env_file(path: &Path, package_var: &str) -> Option<String> {
+    let vars: Vec<(String, String)> = dotenvy::from_path_iter(path)
+        .ok()?
+        .filter_map(Result::ok)
+        .collect();
+
+    [package_var, "DATABASE_URL"].iter().find_map(|name| {
+        vars.iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, url)| url.clone())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolve_works() -> anyhow::Result<()> {
+        let root = env::temp_dir().join(format!("sqlx-database-url-{}", std::process::id()));
+        let member = root.join("services").join("billing");
+        fs::create_dir_all(&member)?;
+        fs::write(
+            root.join("Cargo.toml"),
+            "[workspace]\nmembers = [\"services/*\"]\n",
+        )?;
+        fs::write(member.join("Cargo.toml"), "[package]\nname = \"billing\"\n")?;
+
+        // the `.env` files are only read up to the root of the workspace
+        fs::write(
+            root.join(".env"),
+            "DATABASE_URL=postgres://localhost/root\n\
+             SQLX_DATABASE_URL_SQLX_TEST_ORDERS=postgres://localhost/orders\n",
+        )?;
+
+        assert_eq!(
+            resolve("billing", &member, Some("postgres://localhost/cli"))?,
+            "postgres://localhost/root"
+        );
+        assert_eq!(
+            resolve("sqlx-test-orders", &member, None)?,
+            "postgres://localhost/orders"
+        );
+
+        // a crate's own `.env` comes first
+        fs::write(member.join(".env"), "DATABASE_URL=sqlite:billing.db\n")?;
+        assert_eq!(resolve("billing", &member, None)?, "sqlite:billing.db");
+
+        // then its environment variable
+        env::set_var(
+            "SQLX_DATABASE_URL_SQLX_TEST_BILLING",
+            "mysql://localhost/billing",
+        );
+        assert_eq!(
+            resolve("sqlx-test-billing", &member, None)?,
+            "mysql://localhost/billing"
+        );
+        env::remove_var("SQLX_DATABASE_URL_SQLX_TEST_BILLING");
+
+        fs::remove_file(root.join(".env"))?;
+        fs::remove_file(member.join(".env"))?;
+
+        assert_eq!(
+            resolve("billing", &member, Some("postgres://localhost/cli"))?,
+            "postgres://localhost/cli"
+        );
+
+        let err = resolve("billing", &member, None).unwrap_err().to_string();
+        assert!(err.contains("`SQLX_DATABASE_URL_BILLING`"), "{}", err);
+        assert!(
+            err.contains(&format!("`{}`", root.join(".env").display())),
+            "{}",
+            err
+        );
+        assert!(err.ends_with("`DATABASE_URL`"), "{}", err);
+
+        fs::remove_dir_all(root)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,709 @@
+//! `sqlx schema dump`: the schema of the database as SQL, e.g. for a `schema.sql` committed next
+//! to the migrations so that reviewers see the schema they result in.
+//!
+//! The dump only depends on the schema: the objects are sorted by kind and name, and anything
+//! which varies between databases with the same schema, such as the versions of the tools, the
+//! owners, or the next values of `AUTO_INCREMENT`, is left out, so that its diffs are the
+//! changes of the schema. The migrations table is left out too.
+
+use crate::opt::ConnectOpts;
+use crate::output::{self, say};
+use anyhow::Context;
+use console::style;
+use sqlx::any::AnyKind;
+use sqlx::{AnyConnection, Connection, Row};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Dumps the schema of the database to `file`, or prints it if there is none. With `check`,
+/// fails instead if `file` differs from the dump.
+///
+/// The schema of a PostgreSQL database is dumped with `pg_dump` if it's installed, unless
+/// `pg_dump` is false, or else by querying the catalog.
+pub async fn dump(
+    connect_opts: &ConnectOpts,
+    table_name: &str,
+    file: Option<&Path>,
+    check: bool,
+    pg_dump: bool,
+) -> anyhow::Result<()> {
+    let mut conn = crate::connect(connect_opts).await?;
+    let schema = schema(&mut conn, connect_opts.database_url(), table_name, pg_dump).await?;
+    conn.close().await?;
+
+    let file = match file {
+        Some(file) => file,
+        None => {
+            if output::is_json() {
+                output::print_json(&serde_json::json!({ "schema": schema }));
+            } else {
+                print!("{}", schema);
+            }
+
+            return Ok(());
+        }
+    };
+
+    let current = match fs::read_to_string(file) {
+        Ok(current) => Some(current),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => None,
+        Err(e) => {
+            return Err(e).with_context(|| format!("failed to read `{}`", file.display()));
+        }
+    };
+    let changed = current.as_deref() != Some(&*schema);
+
+    if check {
+        if output::is_json() {
+            output::print_json(&serde_json::json!({ "file": file, "stale": changed }));
+        }
+
+        if changed {
+            return Err(StaleSchema {
+                path: file.to_owned(),
+                line: current.map(|current| first_difference(&current, &schema)),
+            }
+            .into());
+        }
+
+        say!("{} is up to date", file.display());
+
+        return Ok(());
+    }
+
+    if changed {
+        fs::write(file, &schema)
+            .with_context(|| format!("failed to write `{}`", file.display()))?;
+    }
+
+    if output::is_json() {
+        output::print_json(&serde_json::json!({ "file": file, "changed": changed }));
+    } else if changed {
+        println!("Dumped the schema to {}", file.display());
+    } else {
+        println!("{} is up to date", file.display());
+    }
+
+    Ok(())
+}
+
+/// The schema dump checked by `sqlx schema dump --check` differs from the database's.
+#[derive(Debug)]
+pub struct StaleSchema {
+    path: PathBuf,
+    /// The first line which differs, unless the dump doesn't exist.
+    line: Option<usize>,
+}
+
+impl std::fmt::Display for StaleSchema {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.line {
+            Some(line) => write!(
+                f,
+                "`{}` is out of date from line {}",
+                self.path.display(),
+                line
+            )?,
+            None => write!(f, "`{}` doesn't exist", self.path.display())?,
+        }
+
+        write!(
+            f,
+            "; run `sqlx schema dump {}` to update it",
+            self.path.display()
+        )
+    }
+}
+
+impl std::error::Error for StaleSchema {}
+
+/// The number of the first line which differs between `a` and `b`, from 1.
+fn first_difference(a: &str, b: &str) -> usize {
+    let mut a = a.lines();
+    let mut b = b.lines();
+    let mut line = 1;
+
+    while let (Some(x), Some(y)) = (a.next(), b.next()) {
+        if x != y {
+            break;
+        }
+        line += 1;
+    }
+
+    line
+}
+
+async fn schema(
+    conn: &mut AnyConnection,
+    url: &str,
+    table_name: &str,
+    pg_dump: bool,
+) -> anyhow::Result<String> {
+    let statements = match conn.kind() {
+        #[cfg(feature = "postgres")]
+        AnyKind::Postgres => {
+            if pg_dump {
+                if let Some(schema) = postgres::pg_dump(url, table_name)? {
+                    return Ok(normalize(&schema));
+                }
+            }
+
+            postgres::schema(conn, table_name).await?
+        }
+
+        #[cfg(feature = "mysql")]
+        AnyKind::MySql => mysql::schema(conn, table_name).await?,
+
+        #[cfg(feature = "sqlite")]
+        AnyKind::Sqlite => sqlite::schema(conn, table_name).await?,
+
+        #[cfg(feature = "mssql")]
+        AnyKind::Mssql => anyhow::bail!("dumping the schema of MSSQL databases isn't supported"),
+
+        AnyKind::Custom(name) => {
+            anyhow::bail!("dumping the schema of {} databases isn't supported", name)
+        }
+    };
+
+    // the URL is only passed to `pg_dump`
+    let _ = (url, pg_dump);
+
+    Ok(normalize(&statements.join("\n\n")))
+}
+
+/// Drops the trailing whitespace and the repeated blank lines of a dump, and ends it with a
+/// single newline.
+fn normalize(schema: &str) -> String {
+    let mut normalized = String::new();
+    let mut blank = false;
+
+    for line in schema.lines().map(str::trim_end) {
+        if line.is_empty() {
+            blank = !normalized.is_empty();
+            continue;
+        }
+
+        if blank {
+            normalized.push('\n');
+            blank = false;
+        }
+
+        normalized.push_str(line);
+        normalized.push('\n');
+    }
+
+    normalized
+}
+
+/// The schema and the name of the migrations table, which may be qualified with a schema.
+fn split_table_name(table_name: &str) -> (Option<&str>, &str) {
+    match table_name.split_once('.') {
+        Some((schema, table)) => (Some(schema), table),
+        None => (None, table_name),
+    }
+}
+
+#[cfg(feature = "postgres")]
+mod postgres {
+    use super::*;
+
+    // the schemas created by the user, rather than by PostgreSQL
+    const USER_SCHEMAS: &str = "n.nspname <> 'information_schema' AND n.nspname NOT LIKE 'pg\\_%'";
+
+    // the relations which aren't the migrations table, `$1.$2`
+    const NOT_MIGRATIONS_TABLE: &str = "NOT (n.nspname = $1 AND c.relname = $2)";
+
+    /// Dumps the schema with `pg_dump`, or returns `None` if it isn't installed or fails, e.g. as
+    /// it's older than the server.
+    pub(super) fn pg_dump(url: &str, table_name: &str) -> anyhow::Result<Option<String>> {
+        let output = std::process::Command::new("pg_dump")
+            .args(["--schema-only", "--no-owner", "--no-privileges"])
+            .arg(format!("--exclude-table={}", table_name))
+            .arg(format!("--dbname={}", url))
+            .output();
+
+        let output = match output {
+            Ok(output) => output,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e).context("failed to run `pg_dump`"),
+        };
+
+        if !output.status.success() {
+            eprintln!(
+                "{} `pg_dump` failed, so the schema is dumped from the catalog instead: {}",
+                style("warning:").yellow(),
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+
+            return Ok(None);
+        }
+
+        let dump = String::from_utf8(output.stdout).context("`pg_dump` printed invalid UTF-8")?;
+
+        // the comments name the versions of the server and of `pg_dump`, the settings depend on
+        // the version of `pg_dump`, and `\restrict` takes a random key
+        let dump = dump
+            .lines()
+            .filter(|line| {
+                !(line.starts_with("--")
+                    || line.starts_with("SET ")
+                    || line.starts_with("SELECT pg_catalog.set_config(")
+                    || line.starts_with("\\restrict")
+                    || line.starts_with("\\unrestrict"))
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        Ok(Some(dump))
+    }
+
+    /// Dumps the schema by querying the catalog, for when `pg_dump` isn't available.
+    pub(super) async fn schema(
+        conn: &mut AnyConnection,
+        table_name: &str,
+    ) -> anyhow::Result<Vec<String>> {
+        let (schema, table) = split_table_name(table_name);
+        let schema: String = match schema {
+            Some(schema) => schema.to_owned(),
+            None => {
+                sqlx::query_scalar("SELECT current_schema()::text")
+                    .fetch_one(&mut *conn)
+                    .await?
+            }
+        };
+
+        // as with `pg_dump`, the names in the definitions are qualified with their schemas,
+        // whatever the `search_path` of the user
+        sqlx::query("SELECT set_config('search_path', '', false)")
+            .execute(&mut *conn)
+            .await?;
+
+        let mut statements = Vec::new();
+
+        // language=PostgreSQL
+        let rows = strings(
+            conn,
+            &format!(
+                "SELECT format('CREATE SCHEMA %I;', n.nspname) FROM pg_namespace n \
+                 WHERE {} AND n.nspname <> 'public' ORDER BY n.nspname",
+                USER_SCHEMAS
+            ),
+            &[],
+        )
+        .await?;
+        statements.extend(rows);
+
+        // language=PostgreSQL
+        let rows = strings(
+            conn,
+            "SELECT format('CREATE EXTENSION IF NOT EXISTS %I WITH SCHEMA %I;', e.extname, n.nspname) \
+             FROM pg_extension e JOIN pg_namespace n ON n.oid = e.extnamespace \
+             WHERE e.extname <> 'plpgsql' ORDER BY e.extname",
+            &[],
+        )
+        .await?;
+        statements.extend(rows);
+
+        // language=PostgreSQL
+        let rows = strings(
+            conn,
+            &format!(
+                "SELECT format('CREATE TYPE %I.%I AS ENUM (%s);', n.nspname, t.typname, \
+                     string_agg(quote_literal(e.enumlabel), ', ' ORDER BY e.enumsortorder)) \
+                 FROM pg_type t \
+                 JOIN pg_namespace n ON n.oid = t.typnamespace \
+                 JOIN pg_enum e ON e.enumtypid = t.oid \
+                 WHERE {} \
+                 GROUP BY n.nspname, t.typname ORDER BY n.nspname, t.typname",
+                USER_SCHEMAS
+            ),
+            &[],
+        )
+        .await?;
+        statements.extend(rows);
+
+        // the sequences of identity columns are created with them
+        // language=PostgreSQL
+        let rows = strings(
+            conn,
+            &format!(
+                "SELECT format('CREATE SEQUENCE %I.%I;', n.nspname, c.relname) \
+                 FROM pg_class c JOIN pg_namespace n ON n.oid = c.relnamespace \
+                 WHERE c.relkind = 'S' AND {} AND NOT EXISTS ( \
+                     SELECT 1 FROM pg_depend d WHERE d.objid = c.oid AND d.deptype = 'i' \
+                 ) ORDER BY n.nspname, c.relname",
+                USER_SCHEMAS
+            ),
+            &[],
+        )
+        .await?;
+        statements.extend(rows);
+
+        statements.extend(tables(conn, &schema, table).await?);
+
+        // the indexes of constraints are created with them
+        // language=PostgreSQL
+        let rows = strings(
+            conn,
+            &format!(
+                "SELECT pg_get_indexdef(i.indexrelid) || ';' FROM pg_index i \
+                 JOIN pg_class c ON c.oid = i.indrelid \
+                 JOIN pg_class ic ON ic.oid = i.indexrelid \
+                 JOIN pg_namespace n ON n.oid = c.relnamespace \
+                 WHERE {} AND {} AND NOT EXISTS ( \
+                     SELECT 1 FROM pg_constraint con WHERE con.conindid = i.indexrelid \
+                 ) ORDER BY n.nspname, ic.relname",
+                USER_SCHEMAS, NOT_MIGRATIONS_TABLE
+            ),
+            &[&schema, table],
+        )
+        .await?;
+        statements.extend(rows);
+
+        // language=PostgreSQL
+        let rows = strings(
+            conn,
+            &format!(
+                "SELECT format('CREATE %sVIEW %I.%I AS\n%s', \
+                     CASE c.relkind WHEN 'm' THEN 'MATERIALIZED ' ELSE '' END, \
+                     n.nspname, c.relname, rtrim(pg_get_viewdef(c.oid), E'; \\n') || ';') \
+                 FROM pg_class c JOIN pg_namespace n ON n.oid = c.relnamespace \
+                 WHERE c.relkind IN ('v', 'm') AND {} ORDER BY n.nspname, c.relname",
+                USER_SCHEMAS
+            ),
+            &[],
+        )
+        .await?;
+        statements.extend(rows);
+
+        // `prokind` is only there since PostgreSQL 11, and `proisagg` until then
+        // language=PostgreSQL
+        let rows = strings(
+            conn,
+            &format!(
+                "SELECT rtrim(pg_get_functiondef(p.oid), E'\\n') || ';' FROM pg_proc p \
+                 JOIN pg_namespace n ON n.oid = p.pronamespace \
+                 WHERE {} \
+                 AND coalesce(to_jsonb(p) ->> 'prokind', 'f') <> 'a' \
+                 AND coalesce(to_jsonb(p) ->> 'proisagg', 'false') = 'false' \
+                 AND NOT EXISTS ( \
+                     SELECT 1 FROM pg_depend d WHERE d.objid = p.oid AND d.deptype = 'e' \
+                 ) ORDER BY n.nspname, p.proname, p.oid::regprocedure::text",
+                USER_SCHEMAS
+            ),
+            &[],
+        )
+        .await?;
+        statements.extend(rows);
+
+        // language=PostgreSQL
+        let rows = strings(
+            conn,
+            &format!(
+                "SELECT pg_get_triggerdef(t.oid) || ';' FROM pg_trigger t \
+                 JOIN pg_class c ON c.oid = t.tgrelid \
+                 JOIN pg_namespace n ON n.oid = c.relnamespace \
+                 WHERE NOT t.tgisinternal AND {} AND {} \
+                 ORDER BY n.nspname, c.relname, t.tgname",
+                USER_SCHEMAS, NOT_MIGRATIONS_TABLE
+            ),
+            &[&schema, table],
+        )
+        .await?;
+        statements.extend(rows);
+
+        Ok(statements)
+    }
+
+    /// The `CREATE TABLE` statements of the tables, with their columns and constraints.
+    async fn tables(
+        conn: &mut AnyConnection,
+        schema: &str,
+        table: &str,
+    ) -> anyhow::Result<Vec<String>> {
+        // `attidentity` is only there since PostgreSQL 10, and `attgenerated` since 12
+        // language=PostgreSQL
+        let columns = sqlx::query(&format!(
+            "SELECT format('%I.%I', n.nspname, c.relname), format('%I %s', a.attname, \
+                 format_type(a.atttypid, a.atttypmod)) \
+                 || CASE \
+                     WHEN to_jsonb(a) ->> 'attgenerated' = 's' \
+                         THEN format(' GENERATED ALWAYS AS (%s) STORED', pg_get_expr(d.adbin, d.adrelid)) \
+                     WHEN d.adbin IS NOT NULL \
+                         THEN ' DEFAULT ' || pg_get_expr(d.adbin, d.adrelid) \
+                     ELSE '' \
+                 END \
+                 || CASE to_jsonb(a) ->> 'attidentity' \
+                     WHEN 'a' THEN ' GENERATED ALWAYS AS IDENTITY' \
+                     WHEN 'd' THEN ' GENERATED BY DEFAULT AS IDENTITY' \
+                     ELSE '' \
+                 END \
+                 || CASE WHEN a.attnotnull THEN ' NOT NULL' ELSE '' END \
+             FROM pg_class c \
+             JOIN pg_namespace n ON n.oid = c.relnamespace \
+             JOIN pg_attribute a ON a.attrelid = c.oid AND a.attnum > 0 AND NOT a.attisdropped \
+             LEFT JOIN pg_attrdef d ON d.adrelid = c.oid AND d.adnum = a.attnum \
+             WHERE c.relkind IN ('r', 'p') AND {} AND {} \
+             ORDER BY n.nspname, c.relname, a.attnum",
+            USER_SCHEMAS, NOT_MIGRATIONS_TABLE
+        ))
+        .bind(schema)
+        .bind(table)
+        .fetch_all(&mut *conn)
+        .await?;
+
+        // the `NOT NULL` constraints of PostgreSQL 18 are those of the columns
+        // language=PostgreSQL
+        let constraints = sqlx::query(&format!(
+            "SELECT format('%I.%I', n.nspname, c.relname), \
+                 format('CONSTRAINT %I %s', con.conname, pg_get_constraintdef(con.oid)) \
+             FROM pg_constraint con \
+             JOIN pg_class c ON c.oid = con.conrelid \
+             JOIN pg_namespace n ON n.oid = c.relnamespace \
+             WHERE con.contype <> 'n' AND {} AND {} \
+             ORDER BY n.nspname, c.relname, con.conname",
+            USER_SCHEMAS, NOT_MIGRATIONS_TABLE
+        ))
+        .bind(schema)
+        .bind(table)
+        .fetch_all(&mut *conn)
+        .await?;
+
+        let mut tables: Vec<(String, Vec<String>)> = Vec::new();
+
+        for row in columns.iter().chain(&constraints) {
+            let (table, line): (String, String) = (row.try_get(0)?, row.try_get(1)?);
+
+            match tables.iter_mut().find(|(name, _)| *name == table) {
+                Some((_, lines)) => lines.push(line),
+                None => tables.push((table, vec![line])),
+            }
+        }
+
+        tables.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+        Ok(tables
+            .into_iter()
+            .map(|(table, lines)| {
+                format!(
+                    "CREATE TABLE {} (\n    {}\n);",
+                    table,
+                    lines.join(",\n    ")
+                )
+            })
+            .collect())
+    }
+
+    async fn strings(
+        conn: &mut AnyConnection,
+        query: &str,
+        binds: &[&str],
+    ) -> anyhow::Result<Vec<String>> {
+        let mut query = sqlx::query_scalar(query);
+        for bind in binds {
+            query = query.bind(*bind);
+        }
+
+        Ok(query.fetch_all(&mut *conn).await?)
+    }
+}
+
+#[cfg(feature = "mysql")]
+mod mysql {
+    use super::*;
+
+    pub(super) async fn schema(
+        conn: &mut AnyConnection,
+        table_name: &str,
+    ) -> anyhow::Result<Vec<String>> {
+        let (_, migrations_table) = split_table_name(table_name);
+
+        // language=MySQL
+        let tables: Vec<(String, String)> = sqlx::query_as(
+            "SELECT TABLE_NAME, TABLE_TYPE FROM information_schema.TABLES \
+             WHERE TABLE_SCHEMA = DATABASE() ORDER BY TABLE_TYPE, TABLE_NAME",
+        )
+        .fetch_all(&mut *conn)
+        .await?;
+
+        let mut statements = Vec::new();
+
+        for (table, table_type) in tables {
+            if table == migrations_table {
+                continue;
+            }
+
+            let kind = if table_type == "VIEW" {
+                "VIEW"
+            } else {
+                "TABLE"
+            };
+
+            let row = sqlx::query(&format!("SHOW CREATE {} {}", kind, quote(&table)))
+                .fetch_one(&mut *conn)
+                .await?;
+            let create: String = row.try_get(1)?;
+
+            statements.push(format!(
+                "{};",
+                strip_definer(&strip_auto_increment(&create))
+            ));
+        }
+
+        // language=MySQL
+        let triggers: Vec<String> = sqlx::query_scalar(
+            "SELECT TRIGGER_NAME FROM information_schema.TRIGGERS \
+             WHERE TRIGGER_SCHEMA = DATABASE() AND EVENT_OBJECT_TABLE <> ? ORDER BY TRIGGER_NAME",
+        )
+        .bind(migrations_table)
+        .fetch_all(&mut *conn)
+        .await?;
+
+        for trigger in triggers {
+            let row = sqlx::query(&format!("SHOW CREATE TRIGGER {}", quote(&trigger)))
+                .fetch_one(&mut *conn)
+                .await?;
+            let create: String = row.try_get(2)?;
+
+            statements.push(format!("{};", strip_definer(&create)));
+        }
+
+        Ok(statements)
+    }
+
+    fn quote(identifier: &str) -> String {
+        format!("`{}`", identifier.replace('`', "``"))
+    }
+
+    /// Drops the next value of `AUTO_INCREMENT` from the options of a table.
+    pub(super) fn strip_auto_increment(create: &str) -> String {
+        const OPTION: &str = " AUTO_INCREMENT=";
+
+        match create.rfind(OPTION) {
+            Some(start) => {
+                let value = &create[start + OPTION.len()..];
+                let end = value
+                    .find(|c: char| !c.is_ascii_digit())
+                    .unwrap_or(value.len());
+
+                format!("{}{}", &create[..start], &value[end..])
+            }
+            None => create.to_owned(),
+        }
+    }
+
+    /// Drops the user who created a view or a trigger, e.g. ``DEFINER=`app`@`%` ``.
+    pub(super) fn strip_definer(create: &str) -> String {
+        const CLAUSE: &str = "DEFINER=";
+
+        let start = match create.find(CLAUSE) {
+            Some(start) => start,
+            None => return create.to_owned(),
+        };
+
+        // the user and the host are quoted, and separated by `@`
+        let rest = &create[start + CLAUSE.len()..];
+        let mut end = 0;
+        let mut quoted = false;
+
+        for (i, c) in rest.char_indices() {
+            match c {
+                '`' => quoted = !quoted,
+                ' ' if !quoted => break,
+                _ => {}
+            }
+            end = i + c.len_utf8();
+        }
+
+        format!("{}{}", &create[..start], rest[end..].trim_start())
+    }
+}
+
+#[cfg(feature = "sqlite")]
+mod sqlite {
+    use super::*;
+
+    pub(super) async fn schema(
+        conn: &mut AnyConnection,
+        table_name: &str,
+    ) -> anyhow::Result<Vec<String>> {
+        let (_, migrations_table) = split_table_name(table_name);
+
+        // the tables, then the views, the indexes and the triggers, which depend on them
+        // language=SQLite
+        let statements: Vec<String> = sqlx::query_scalar(
+            "SELECT sql || ';' FROM sqlite_master \
+             WHERE sql IS NOT NULL AND name NOT LIKE 'sqlite\\_%' ESCAPE '\\' AND tbl_name <> ?1 \
+             ORDER BY CASE type WHEN 'table' THEN 0 WHEN 'view' THEN 1 WHEN 'index' THEN 2 ELSE 3 END, \
+             name",
+        )
+        .bind(migrations_table)
+        .fetch_all(&mut *conn)
+        .await?;
+
+        Ok(statements)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::Executor;
+
+    #[test]
+    fn normalize_works() {
+        assert_eq!(
+            normalize("\n\nCREATE TABLE a (x);  \n\n\n\nCREATE TABLE b (x);\n\n"),
+            "CREATE TABLE a (x);\n\nCREATE TABLE b (x);\n"
+        );
+        assert_eq!(first_difference("a\nb\nc\n", "a\nb\nd\n"), 3);
+        assert_eq!(first_difference("a\n", "a\nb\n"), 2);
+    }
+
+    #[cfg(feature = "mysql")]
+    #[test]
+    fn mysql_dumps_are_stripped() {
+        assert_eq!(
+            mysql::strip_auto_increment(
+                "CREATE TABLE `users` (\n  `id` int NOT NULL AUTO_INCREMENT\n) ENGINE=InnoDB AUTO_INCREMENT=42 DEFAULT CHARSET=utf8mb4"
+            ),
+            "CREATE TABLE `users` (\n  `id` int NOT NULL AUTO_INCREMENT\n) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4"
+        );
+        assert_eq!(
+            mysql::strip_definer(
+                "CREATE ALGORITHM=UNDEFINED DEFINER=`app user`@`%` SQL SECURITY DEFINER VIEW `v` AS select 1"
+            ),
+            "CREATE ALGORITHM=UNDEFINED SQL SECURITY DEFINER VIEW `v` AS select 1"
+        );
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[tokio::test]
+    async fn sqlite_schema_is_sorted() -> anyhow::Result<()> {
+        let mut conn = AnyConnection::connect("sqlite::memory:").await?;
+        conn.execute(
+            "CREATE VIEW names AS SELECT name FROM users; \
+             CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT UNIQUE); \
+             CREATE INDEX users_name ON users (name); \
+             CREATE TABLE _sqlx_migrations (version BIGINT PRIMARY KEY); \
+             CREATE TABLE posts (id INTEGER PRIMARY KEY AUTOINCREMENT); \
+             INSERT INTO posts DEFAULT VALUES;",
+        )
+        .await?;
+
+        let schema = schema(&mut conn, "", "_sqlx_migrations", false).await?;
+        assert_eq!(
+            schema,
+            "CREATE TABLE posts (id INTEGER PRIMARY KEY AUTOINCREMENT);\n\
+             \n\
+             CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT UNIQUE);\n\
+             \n\
+             CREATE VIEW names AS SELECT name FROM users;\n\
+             \n\
+             CREATE INDEX users_name ON users (name);\n"
+        );
+
+        Ok(())
+    }
+}
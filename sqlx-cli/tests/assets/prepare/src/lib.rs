@@ -0,0 +1 @@
+// the queries of `sqlx-data.json` aren't used anymore
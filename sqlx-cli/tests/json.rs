@@ -0,0 +1,170 @@
+//! Snapshots of the documents printed with `--json`, which scripts depend on: the documents
+//! printed by each command are compared to those saved in `tests/assets/json/<command>.json`.
+//!
+//! Run with `SQLX_UPDATE_SNAPSHOTS=1` to save the documents printed instead, after changing them
+//! on purpose.
+
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+fn assets() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("assets")
+}
+
+/// A directory of its own for each test, with a copy of the given assets.
+fn test_dir(name: &str, assets: &[&str]) -> anyhow::Result<PathBuf> {
+    let dir = std::env::temp_dir().join(format!("sqlx-cli-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir)?;
+
+    for asset in assets {
+        copy_dir(&self::assets().join(asset), &dir)?;
+    }
+
+    Ok(dir)
+}
+
+fn copy_dir(from: &Path, to: &Path) -> anyhow::Result<()> {
+    let to = to.join(from.file_name().unwrap());
+    fs::create_dir_all(&to)?;
+
+    for entry in fs::read_dir(from)? {
+        let path = entry?.path();
+
+        if path.is_dir() {
+            copy_dir(&path, &to)?;
+        } else {
+            fs::copy(&path, to.join(path.file_name().unwrap()))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Runs `sqlx --json <args>` in `dir`, returning the documents it printed and its exit code.
+fn sqlx(dir: &Path, args: &[&str]) -> anyhow::Result<(Vec<Value>, i32)> {
+    let output = Command::new(env!("CARGO_BIN_EXE_sqlx"))
+        .arg("--json")
+        .args(args)
+        .current_dir(dir)
+        // `prepare` runs `cargo check`
+        .env("CARGO", env!("CARGO"))
+        .env_remove("DATABASE_URL")
+        .output()?;
+
+    let documents = String::from_utf8(output.stdout)?
+        .lines()
+        .map(serde_json::from_str)
+        .collect::<Result<Vec<Value>, _>>()?;
+
+    Ok((documents, output.status.code().unwrap_or(-1)))
+}
+
+/// Replaces what varies between runs and releases: how long things took, and where and by which
+/// version of sqlx migrations were applied.
+fn normalize(value: &mut Value) {
+    match value {
+        Value::Object(object) => {
+            for (key, value) in object.iter_mut() {
+                match key.as_str() {
+                    "duration_ns" if value.is_u64() => *value = Value::from(0),
+                    "hostname" if value.is_string() => *value = Value::from("<hostname>"),
+                    "sqlx_version" if value.is_string() => *value = Value::from("<version>"),
+                    _ => normalize(value),
+                }
+            }
+        }
+
+        Value::Array(values) => values.iter_mut().for_each(normalize),
+        _ => {}
+    }
+}
+
+fn assert_snapshot(name: &str, mut documents: Vec<Value>) -> anyhow::Result<()> {
+    documents.iter_mut().for_each(normalize);
+    let documents = Value::Array(documents);
+
+    let path = assets().join("json").join(format!("{}.json", name));
+
+    if std::env::var_os("SQLX_UPDATE_SNAPSHOTS").is_some() {
+        fs::write(&path, serde_json::to_string_pretty(&documents)? + "\n")?;
+        return Ok(());
+    }
+
+    let expected: Value = serde_json::from_str(&fs::read_to_string(&path)?)?;
+    assert_eq!(
+        documents,
+        expected,
+        "the documents printed by `{}` differ from {}",
+        name,
+        path.display()
+    );
+
+    Ok(())
+}
+
+#[test]
+fn database_json_works() -> anyhow::Result<()> {
+    let dir = test_dir("database", &["migrations"])?;
+    let url = format!("sqlite://{}", dir.join("sqlite.db").display());
+
+    let (documents, code) = sqlx(&dir, &["database", "create", "--database-url", &url])?;
+    assert_eq!(code, 0);
+    assert_snapshot("database-create", documents)?;
+
+    let (documents, code) = sqlx(&dir, &["database", "reset", "-y", "--database-url", &url])?;
+    assert_eq!(code, 0);
+    assert_snapshot("database-reset", documents)?;
+
+    let (documents, code) = sqlx(&dir, &["database", "drop", "-y", "--database-url", &url])?;
+    assert_eq!(code, 0);
+    assert_snapshot("database-drop", documents)?;
+
+    fs::remove_dir_all(dir)?;
+
+    Ok(())
+}
+
+#[test]
+fn migrate_json_works() -> anyhow::Result<()> {
+    let dir = test_dir("migrate", &["migrations"])?;
+    let url = format!("sqlite://{}", dir.join("sqlite.db").display());
+
+    let (_, code) = sqlx(&dir, &["database", "create", "--database-url", &url])?;
+    assert_eq!(code, 0);
+
+    let (documents, code) = sqlx(&dir, &["migrate", "run", "--database-url", &url])?;
+    assert_eq!(code, 0);
+    assert_snapshot("migrate-run", documents)?;
+
+    let (documents, code) = sqlx(&dir, &["migrate", "revert", "--database-url", &url])?;
+    assert_eq!(code, 0);
+    assert_snapshot("migrate-revert", documents)?;
+
+    let (documents, code) = sqlx(&dir, &["migrate", "info", "--database-url", &url])?;
+    assert_eq!(code, 0);
+    assert_snapshot("migrate-info", documents)?;
+
+    fs::remove_dir_all(dir)?;
+
+    Ok(())
+}
+
+#[test]
+fn prepare_check_json_works() -> anyhow::Result<()> {
+    let dir = test_dir("prepare", &["prepare"])?.join("prepare");
+    let url = format!("sqlite://{}?mode=rwc", dir.join("sqlite.db").display());
+
+    // the query of `sqlx-data.json` isn't used by the crate, which exits with 4
+    let (documents, code) = sqlx(&dir, &["prepare", "--check", "--database-url", &url])?;
+    assert_eq!(code, 4);
+    assert_snapshot("prepare-check", documents)?;
+
+    fs::remove_dir_all(dir.parent().unwrap())?;
+
+    Ok(())
+}
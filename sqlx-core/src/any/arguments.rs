@@ -1,4 +1,4 @@
-use crate::any::Any;
+use crate::any::{Any, AnyDriverValue};
 use crate::arguments::Arguments;
 use crate::encode::Encode;
 use crate::types::Type;
@@ -46,6 +46,8 @@ pub(crate) enum AnyArgumentBufferKind<'q> {
         crate::mssql::MssqlArguments,
         std::marker::PhantomData<&'q ()>,
     ),
+
+    Custom(Vec<AnyDriverValue>, std::marker::PhantomData<&'q ()>),
 }
 
 // control flow inferred type bounds would be fun
@@ -131,3 +133,23 @@ impl<'q> From<AnyArguments<'q>> for crate::postgres::PgArguments {
         }
     }
 }
+
+#[allow(irrefutable_let_patterns)]
+impl<'q> From<AnyArguments<'q>> for Vec<AnyDriverValue> {
+    fn from(args: AnyArguments<'q>) -> Self {
+        let mut buf = AnyArgumentBuffer(AnyArgumentBufferKind::Custom(
+            Vec::with_capacity(args.values.len()),
+            std::marker::PhantomData,
+        ));
+
+        for value in args.values {
+            let _ = value.encode_by_ref(&mut buf);
+        }
+
+        if let AnyArgumentBufferKind::Custom(args, _) = buf.0 {
+            args
+        } else {
+            unreachable!()
+        }
+    }
+}
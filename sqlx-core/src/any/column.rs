@@ -34,6 +34,11 @@ pub(crate) enum AnyColumnKind {
 
     #[cfg(feature = "mssql")]
     Mssql(MssqlColumn),
+
+    Custom {
+        ordinal: usize,
+        name: String,
+    },
 }
 
 impl Column for AnyColumn {
@@ -52,6 +57,8 @@ impl Column for AnyColumn {
 
             #[cfg(feature = "mssql")]
             AnyColumnKind::Mssql(row) => row.ordinal(),
+
+            AnyColumnKind::Custom { ordinal, .. } => *ordinal,
         }
     }
 
@@ -68,6 +75,8 @@ impl Column for AnyColumn {
 
             #[cfg(feature = "mssql")]
             AnyColumnKind::Mssql(row) => row.name(),
+
+            AnyColumnKind::Custom { name, .. } => name,
         }
     }
 
@@ -34,6 +34,10 @@ impl AnyConnection {
                     .await
                     .map(AnyConnectionKind::Mssql)
             }
+
+            AnyConnectOptionsKind::Custom { driver, url } => (driver.connect)(url)
+                .await
+                .map(|conn| AnyConnectionKind::Custom(driver, conn)),
         }
         .map(AnyConnection)
     }
@@ -1,4 +1,5 @@
 use crate::any::connection::AnyConnectionKind;
+use crate::any::driver;
 use crate::any::{
     Any, AnyColumn, AnyConnection, AnyQueryResult, AnyRow, AnyStatement, AnyTypeInfo,
 };
@@ -22,6 +23,10 @@ impl<'c> Executor<'c> for &'c mut AnyConnection {
         'c: 'e,
         E: Execute<'q, Self::Database>,
     {
+        if let Some(error) = query.take_error() {
+            return Box::pin(futures_util::stream::once(async move { Err(error) }));
+        }
+
         let arguments = query.take_arguments();
         let query = query.sql();
 
@@ -49,6 +54,14 @@ impl<'c> Executor<'c> for &'c mut AnyConnection {
                 .fetch_many((query, arguments.map(Into::into)))
                 .map_ok(|v| v.map_right(Into::into).map_left(Into::into))
                 .boxed(),
+
+            AnyConnectionKind::Custom(driver, conn) => {
+                let driver = *driver;
+
+                conn.fetch_many(query, arguments.map(Into::into))
+                    .map_ok(move |v| v.map_right(|row| driver::row(driver, row)))
+                    .boxed()
+            }
         }
     }
 
@@ -60,6 +73,10 @@ impl<'c> Executor<'c> for &'c mut AnyConnection {
         'c: 'e,
         E: Execute<'q, Self::Database>,
     {
+        if let Some(error) = query.take_error() {
+            return Box::pin(async move { Err(error) });
+        }
+
         let arguments = query.take_arguments();
         let query = query.sql();
 
@@ -88,6 +105,20 @@ impl<'c> Executor<'c> for &'c mut AnyConnection {
                     .fetch_optional((query, arguments.map(Into::into)))
                     .await?
                     .map(Into::into),
+
+                AnyConnectionKind::Custom(driver, conn) => {
+                    let mut s = conn.fetch_many(query, arguments.map(Into::into));
+                    let mut row = None;
+
+                    while let Some(v) = s.try_next().await? {
+                        if let Either::Right(r) = v {
+                            row = Some(driver::row(driver, r));
+                            break;
+                        }
+                    }
+
+                    row
+                }
             })
         })
     }
@@ -114,6 +145,11 @@ impl<'c> Executor<'c> for &'c mut AnyConnection {
 
                 #[cfg(feature = "mssql")]
                 AnyConnectionKind::Mssql(conn) => conn.prepare(sql).await.map(Into::into)?,
+
+                AnyConnectionKind::Custom(driver, conn) => {
+                    let describe = conn.describe(sql).await?;
+                    driver::statement(driver, sql, describe)
+                }
             })
         })
     }
@@ -138,6 +174,11 @@ impl<'c> Executor<'c> for &'c mut AnyConnection {
 
                 #[cfg(feature = "mssql")]
                 AnyConnectionKind::Mssql(conn) => conn.describe(sql).await.map(map_describe)?,
+
+                AnyConnectionKind::Custom(driver, conn) => {
+                    let describe = conn.describe(sql).await?;
+                    driver::describe(driver, describe)
+                }
             })
         })
     }
@@ -159,6 +200,7 @@ where
     Describe {
         parameters,
         nullable: info.nullable,
+        domains: info.domains,
         columns: info.columns.into_iter().map(Into::into).collect(),
     }
 }
@@ -1,6 +1,8 @@
 use futures_core::future::BoxFuture;
 
-use crate::any::{Any, AnyConnectOptions, AnyKind};
+use crate::any::{
+    Any, AnyConnectOptions, AnyDowncast, AnyDriver, AnyDriverConnection, AnyKind, AnyStatement,
+};
 use crate::connection::Connection;
 use crate::error::Error;
 
@@ -15,7 +17,8 @@ use crate::mssql;
 
 #[cfg(feature = "mysql")]
 use crate::mysql;
-use crate::transaction::Transaction;
+use crate::statement::PreparedStatementInfo;
+use crate::transaction::{Transaction, TransactionOptions};
 
 mod establish;
 mod executor;
@@ -47,6 +50,8 @@ pub enum AnyConnectionKind {
 
     #[cfg(feature = "sqlite")]
     Sqlite(sqlite::SqliteConnection),
+
+    Custom(&'static AnyDriver, Box<dyn AnyDriverConnection>),
 }
 
 impl AnyConnectionKind {
@@ -63,6 +68,8 @@ impl AnyConnectionKind {
 
             #[cfg(feature = "mssql")]
             AnyConnectionKind::Mssql(_) => AnyKind::Mssql,
+
+            AnyConnectionKind::Custom(driver, _) => AnyKind::Custom(driver.name),
         }
     }
 }
@@ -72,6 +79,33 @@ impl AnyConnection {
         self.0.kind()
     }
 
+    /// Returns the connection of the driver in use, if it's a `C`, e.g. a
+    /// [`PgConnection`](crate::postgres::PgConnection) for PostgreSQL.
+    ///
+    /// ```rust,no_run
+    /// # async fn example(conn: &mut sqlx_core::any::AnyConnection) -> sqlx_core::error::Result<()> {
+    /// use sqlx_core::executor::Executor;
+    /// use sqlx_core::postgres::PgConnection;
+    ///
+    /// if let Some(conn) = conn.downcast_mut::<PgConnection>() {
+    ///     conn.execute("LISTEN updates").await?;
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// The reference borrows this connection, so it can be held across `.await` points like a
+    /// reference to this connection can.
+    pub fn downcast_ref<C: AnyDowncast<Self>>(&self) -> Option<&C> {
+        C::downcast_ref(self)
+    }
+
+    /// Returns the connection of the driver in use, if it's a `C`; see
+    /// [`downcast_ref`](Self::downcast_ref).
+    pub fn downcast_mut<C: AnyDowncast<Self>>(&mut self) -> Option<&mut C> {
+        C::downcast_mut(self)
+    }
+
     // Used internally in `sqlx-macros`
     #[doc(hidden)]
     pub fn private_get_mut(&mut self) -> &mut AnyConnectionKind {
@@ -80,7 +114,7 @@ impl AnyConnection {
 }
 
 macro_rules! delegate_to {
-    ($self:ident.$method:ident($($arg:ident),*)) => {
+    ($self:ident.$method:ident($($arg:ident),*), $conn:ident => $custom:expr) => {
         match &$self.0 {
             #[cfg(feature = "postgres")]
             AnyConnectionKind::Postgres(conn) => conn.$method($($arg),*),
@@ -93,12 +127,15 @@ macro_rules! delegate_to {
 
             #[cfg(feature = "mssql")]
             AnyConnectionKind::Mssql(conn) => conn.$method($($arg),*),
+
+            #[allow(unused_variables)]
+            AnyConnectionKind::Custom(_, $conn) => $custom,
         }
     };
 }
 
 macro_rules! delegate_to_mut {
-    ($self:ident.$method:ident($($arg:ident),*)) => {
+    ($self:ident.$method:ident($($arg:ident),*), $conn:ident => $custom:expr) => {
         match &mut $self.0 {
             #[cfg(feature = "postgres")]
             AnyConnectionKind::Postgres(conn) => conn.$method($($arg),*),
@@ -111,6 +148,9 @@ macro_rules! delegate_to_mut {
 
             #[cfg(feature = "mssql")]
             AnyConnectionKind::Mssql(conn) => conn.$method($($arg),*),
+
+            #[allow(unused_variables)]
+            AnyConnectionKind::Custom(_, $conn) => $custom,
         }
     };
 }
@@ -133,6 +173,8 @@ impl Connection for AnyConnection {
 
             #[cfg(feature = "mssql")]
             AnyConnectionKind::Mssql(conn) => conn.close(),
+
+            AnyConnectionKind::Custom(_, conn) => conn.close(),
         }
     }
 
@@ -149,11 +191,13 @@ impl Connection for AnyConnection {
 
             #[cfg(feature = "mssql")]
             AnyConnectionKind::Mssql(conn) => conn.close_hard(),
+
+            AnyConnectionKind::Custom(_, conn) => conn.close(),
         }
     }
 
     fn ping(&mut self) -> BoxFuture<'_, Result<(), Error>> {
-        delegate_to_mut!(self.ping())
+        delegate_to_mut!(self.ping(), conn => conn.ping())
     }
 
     fn begin(&mut self) -> BoxFuture<'_, Result<Transaction<'_, Self::Database>, Error>>
@@ -163,6 +207,16 @@ impl Connection for AnyConnection {
         Transaction::begin(self)
     }
 
+    fn begin_with(
+        &mut self,
+        options: TransactionOptions,
+    ) -> BoxFuture<'_, Result<Transaction<'_, Self::Database>, Error>>
+    where
+        Self: Sized,
+    {
+        Transaction::begin_with(self, options)
+    }
+
     fn cached_statements_size(&self) -> usize {
         match &self.0 {
             #[cfg(feature = "postgres")]
@@ -177,6 +231,8 @@ impl Connection for AnyConnection {
             // no cache
             #[cfg(feature = "mssql")]
             AnyConnectionKind::Mssql(_) => 0,
+
+            AnyConnectionKind::Custom(..) => 0,
         }
     }
 
@@ -194,17 +250,81 @@ impl Connection for AnyConnection {
             // no cache
             #[cfg(feature = "mssql")]
             AnyConnectionKind::Mssql(_) => Box::pin(futures_util::future::ok(())),
+
+            AnyConnectionKind::Custom(..) => Box::pin(futures_util::future::ok(())),
+        }
+    }
+
+    fn close_statement<'c, 'q: 'c>(
+        &'c mut self,
+        statement: AnyStatement<'q>,
+    ) -> BoxFuture<'c, Result<(), Error>> {
+        // the statement doesn't refer to the one prepared by the driver, which is closed by SQL
+        let sql = statement.sql;
+
+        match &mut self.0 {
+            #[cfg(feature = "postgres")]
+            AnyConnectionKind::Postgres(conn) => conn.close_statement(postgres::PgStatement {
+                sql,
+                metadata: Default::default(),
+                prepared: None,
+            }),
+
+            #[cfg(feature = "mysql")]
+            AnyConnectionKind::MySql(conn) => conn.close_statement(mysql::MySqlStatement {
+                sql,
+                metadata: Default::default(),
+                prepared: None,
+            }),
+
+            #[cfg(feature = "sqlite")]
+            AnyConnectionKind::Sqlite(conn) => conn.close_statement(sqlite::SqliteStatement {
+                sql,
+                parameters: 0,
+                columns: Default::default(),
+                column_names: Default::default(),
+                read_only: false,
+                connection_id: None,
+            }),
+
+            // no cache
+            #[cfg(feature = "mssql")]
+            AnyConnectionKind::Mssql(_) => Box::pin(futures_util::future::ok(())),
+
+            AnyConnectionKind::Custom(..) => {
+                let _ = sql;
+                Box::pin(futures_util::future::ok(()))
+            }
+        }
+    }
+
+    fn prepared_statements(&mut self) -> BoxFuture<'_, Result<Vec<PreparedStatementInfo>, Error>> {
+        match &mut self.0 {
+            #[cfg(feature = "postgres")]
+            AnyConnectionKind::Postgres(conn) => conn.prepared_statements(),
+
+            #[cfg(feature = "mysql")]
+            AnyConnectionKind::MySql(conn) => conn.prepared_statements(),
+
+            #[cfg(feature = "sqlite")]
+            AnyConnectionKind::Sqlite(conn) => conn.prepared_statements(),
+
+            // no cache
+            #[cfg(feature = "mssql")]
+            AnyConnectionKind::Mssql(_) => Box::pin(futures_util::future::ok(Vec::new())),
+
+            AnyConnectionKind::Custom(..) => Box::pin(futures_util::future::ok(Vec::new())),
         }
     }
 
     #[doc(hidden)]
     fn flush(&mut self) -> BoxFuture<'_, Result<(), Error>> {
-        delegate_to_mut!(self.flush())
+        delegate_to_mut!(self.flush(), conn => Box::pin(futures_util::future::ok(())))
     }
 
     #[doc(hidden)]
     fn should_flush(&self) -> bool {
-        delegate_to!(self.should_flush())
+        delegate_to!(self.should_flush(), conn => false)
     }
 }
 
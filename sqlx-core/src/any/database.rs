@@ -23,6 +23,15 @@ impl Database for Any {
     type TypeInfo = AnyTypeInfo;
 
     type Value = AnyValue;
+
+    /// The smallest limit of the enabled databases, as the database is only known at runtime.
+    const MAX_BIND_PARAMETERS: usize = if cfg!(feature = "mssql") {
+        2098
+    } else if cfg!(feature = "sqlite") {
+        32766
+    } else {
+        65535
+    };
 }
 
 impl<'r> HasValueRef<'r> for Any {
@@ -16,10 +16,11 @@ use crate::sqlite::Sqlite;
 // Implements Decode for any T where T supports Decode for any database that has support currently
 // compiled into SQLx
 macro_rules! impl_any_decode {
-    ($ty:ty) => {
-        impl<'r> crate::decode::Decode<'r, crate::any::Any> for $ty
+    (<$($generic:ident),*> $ty:ty where $($bounds:tt)*) => {
+        impl<'r, $($generic),*> crate::decode::Decode<'r, crate::any::Any> for $ty
         where
             $ty: crate::any::AnyDecode<'r>,
+            $($bounds)*
         {
             fn decode(
                 value: crate::any::AnyValueRef<'r>,
@@ -44,10 +45,18 @@ macro_rules! impl_any_decode {
                     crate::any::value::AnyValueRefKind::Postgres(value) => {
                         <$ty as crate::decode::Decode<'r, crate::postgres::Postgres>>::decode(value)
                     }
+
+                    crate::any::value::AnyValueRefKind::Custom(value) => {
+                        <$ty as crate::any::driver::DriverDecode<'r>>::decode(value)
+                    }
                 }
             }
         }
     };
+
+    ($ty:ty) => {
+        impl_any_decode!(<> $ty where);
+    };
 }
 
 // FIXME: Find a nice way to auto-generate the below or petition Rust to add support for #[cfg]
@@ -0,0 +1,86 @@
+use crate::any::connection::AnyConnectionKind;
+use crate::any::row::AnyRowKind;
+use crate::any::{AnyConnection, AnyRow};
+
+#[cfg(feature = "postgres")]
+use crate::postgres::{PgConnection, PgRow};
+
+#[cfg(feature = "mysql")]
+use crate::mysql::{MySqlConnection, MySqlRow};
+
+#[cfg(feature = "sqlite")]
+use crate::sqlite::{SqliteConnection, SqliteRow};
+
+#[cfg(feature = "mssql")]
+use crate::mssql::{MssqlConnection, MssqlRow};
+
+/// A type of a built-in driver which the `Any` type `A` wraps when that driver is in use, e.g.
+/// [`PgConnection`](crate::postgres::PgConnection) for [`AnyConnection`].
+///
+/// See [`AnyConnection::downcast_ref`] and [`AnyRow::downcast_ref`].
+pub trait AnyDowncast<A>: Sized + private::Sealed {
+    #[doc(hidden)]
+    fn downcast_ref(any: &A) -> Option<&Self>;
+
+    #[doc(hidden)]
+    fn downcast_mut(any: &mut A) -> Option<&mut Self>;
+}
+
+mod private {
+    pub trait Sealed {}
+}
+
+macro_rules! impl_any_downcast {
+    ($ty:ty, $any:ty, $field:tt, $kind:ident::$variant:ident) => {
+        impl private::Sealed for $ty {}
+
+        impl AnyDowncast<$any> for $ty {
+            fn downcast_ref(any: &$any) -> Option<&Self> {
+                match &any.$field {
+                    $kind::$variant(inner) => Some(inner),
+
+                    #[allow(unreachable_patterns)]
+                    _ => None,
+                }
+            }
+
+            fn downcast_mut(any: &mut $any) -> Option<&mut Self> {
+                match &mut any.$field {
+                    $kind::$variant(inner) => Some(inner),
+
+                    #[allow(unreachable_patterns)]
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+
+#[cfg(feature = "postgres")]
+impl_any_downcast!(PgConnection, AnyConnection, 0, AnyConnectionKind::Postgres);
+
+#[cfg(feature = "mysql")]
+impl_any_downcast!(MySqlConnection, AnyConnection, 0, AnyConnectionKind::MySql);
+
+#[cfg(feature = "sqlite")]
+impl_any_downcast!(
+    SqliteConnection,
+    AnyConnection,
+    0,
+    AnyConnectionKind::Sqlite
+);
+
+#[cfg(feature = "mssql")]
+impl_any_downcast!(MssqlConnection, AnyConnection, 0, AnyConnectionKind::Mssql);
+
+#[cfg(feature = "postgres")]
+impl_any_downcast!(PgRow, AnyRow, kind, AnyRowKind::Postgres);
+
+#[cfg(feature = "mysql")]
+impl_any_downcast!(MySqlRow, AnyRow, kind, AnyRowKind::MySql);
+
+#[cfg(feature = "sqlite")]
+impl_any_downcast!(SqliteRow, AnyRow, kind, AnyRowKind::Sqlite);
+
+#[cfg(feature = "mssql")]
+impl_any_downcast!(MssqlRow, AnyRow, kind, AnyRowKind::Mssql);
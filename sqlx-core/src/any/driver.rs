@@ -0,0 +1,593 @@
+//! Database drivers of [`Any`][crate::any::Any] which aren't built into SQLx, installed at
+//! runtime and selected by the scheme of the connection URL like the built-in ones.
+
+use std::fmt::{self, Debug, Formatter};
+use std::sync::RwLock;
+
+use either::Either;
+use futures_core::future::BoxFuture;
+use futures_core::stream::BoxStream;
+use once_cell::sync::Lazy;
+
+use crate::any::column::AnyColumnKind;
+use crate::any::row::AnyRowKind;
+use crate::any::type_info::AnyTypeInfoKind;
+use crate::any::{Any, AnyColumn, AnyKind, AnyQueryResult, AnyRow, AnyStatement, AnyTypeInfo};
+use crate::describe::Describe;
+use crate::error::{BoxDynError, Error};
+use crate::ext::ustr::UStr;
+use crate::transaction::TransactionOptions;
+use crate::HashMap;
+
+#[cfg(feature = "migrate")]
+use crate::migrate::Migrate;
+
+/// A database driver installed with [`install_driver`].
+///
+/// ```rust,ignore
+/// sqlx::any::install_driver(AnyDriver {
+///     name: "MyDB",
+///     url_schemes: &["mydb"],
+///     connect: mydb::connect,
+///     type_info: mydb::type_info,
+///     migrate: None,
+/// })?;
+///
+/// let conn = AnyConnection::connect("mydb://localhost/app").await?;
+/// ```
+pub struct AnyDriver {
+    /// The name of the database, as in [`AnyKind::Custom`].
+    pub name: &'static str,
+
+    /// The schemes of the connection URLs of the database, without the `:`.
+    pub url_schemes: &'static [&'static str],
+
+    /// Connect to the database of a connection URL with one of `url_schemes`.
+    pub connect: fn(&str) -> BoxFuture<'_, Result<Box<dyn AnyDriverConnection>, Error>>,
+
+    /// The values a type of the database, by name, is exchanged as; the Rust types of those
+    /// values are the ones compatible with it. `None` if no Rust type is.
+    pub type_info: fn(&str) -> Option<AnyDriverType>,
+
+    /// Create and drop databases, e.g. for `sqlx database create`. The migrations themselves
+    /// are applied with [`AnyDriverConnection::migrate`].
+    pub migrate: Option<AnyMigrateDriver>,
+}
+
+impl Debug for AnyDriver {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AnyDriver")
+            .field("name", &self.name)
+            .field("url_schemes", &self.url_schemes)
+            .finish_non_exhaustive()
+    }
+}
+
+/// The functions of an [`AnyDriver`] for [`MigrateDatabase`][crate::migrate::MigrateDatabase].
+#[derive(Clone, Copy)]
+pub struct AnyMigrateDriver {
+    pub create_database: fn(&str) -> BoxFuture<'_, Result<(), Error>>,
+    pub database_exists: fn(&str) -> BoxFuture<'_, Result<bool, Error>>,
+    pub drop_database: fn(&str) -> BoxFuture<'_, Result<(), Error>>,
+}
+
+/// A connection of an [`AnyDriver`].
+pub trait AnyDriverConnection: Send + 'static {
+    fn close(self: Box<Self>) -> BoxFuture<'static, Result<(), Error>>;
+
+    fn ping(&mut self) -> BoxFuture<'_, Result<(), Error>>;
+
+    fn begin(&mut self) -> BoxFuture<'_, Result<(), Error>>;
+
+    /// Begins a new transaction with options other than the default ones, which are passed to
+    /// [`begin`](Self::begin) instead; see [`TransactionOptions`].
+    ///
+    /// Returns an error by default, for drivers which don't support any of the options.
+    fn begin_with(&mut self, options: TransactionOptions) -> BoxFuture<'_, Result<(), Error>> {
+        Box::pin(async move { Err(options.unsupported("this driver")) })
+    }
+
+    fn commit(&mut self) -> BoxFuture<'_, Result<(), Error>>;
+
+    fn rollback(&mut self) -> BoxFuture<'_, Result<(), Error>>;
+
+    /// Start rolling back the current transaction as the connection is dropped, without waiting
+    /// for it. See [`TransactionManager::start_rollback`][crate::transaction::TransactionManager::start_rollback].
+    fn start_rollback(&mut self);
+
+    /// Execute `sql`, with the values of its parameters if it's a prepared statement, and
+    /// stream the result of each statement and their rows.
+    fn fetch_many<'q>(
+        &'q mut self,
+        sql: &'q str,
+        arguments: Option<Vec<AnyDriverValue>>,
+    ) -> BoxStream<'q, Result<Either<AnyQueryResult, AnyDriverRow>, Error>>;
+
+    fn describe<'q>(&'q mut self, sql: &'q str) -> BoxFuture<'q, Result<AnyDriverDescribe, Error>>;
+
+    /// The migrations of the database, if the driver supports them.
+    #[cfg(feature = "migrate")]
+    fn migrate(&mut self) -> Option<&mut (dyn Migrate + Send)> {
+        None
+    }
+}
+
+impl Debug for dyn AnyDriverConnection {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AnyDriverConnection")
+            .finish_non_exhaustive()
+    }
+}
+
+/// The values exchanged with an [`AnyDriver`], as parameters and in rows.
+///
+/// A `DateTime<Local>` is exchanged as a [`TimestampTz`](AnyDriverValue::TimestampTz) in UTC, and
+/// `Json<T>` as the [`Json`](AnyDriverValue::Json) value `T` is serialized to.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum AnyDriverValue {
+    Null,
+    Bool(bool),
+    SmallInt(i16),
+    Integer(i32),
+    BigInt(i64),
+    Real(f32),
+    Double(f64),
+    Text(String),
+    Blob(Vec<u8>),
+    #[cfg(feature = "chrono")]
+    Date(chrono::NaiveDate),
+    #[cfg(feature = "chrono")]
+    Time(chrono::NaiveTime),
+    #[cfg(feature = "chrono")]
+    Timestamp(chrono::NaiveDateTime),
+    #[cfg(feature = "chrono")]
+    TimestampTz(chrono::DateTime<chrono::Utc>),
+    #[cfg(feature = "uuid")]
+    Uuid(uuid::Uuid),
+    #[cfg(feature = "json")]
+    Json(serde_json::Value),
+}
+
+/// The variants of [`AnyDriverValue`], which a type of an [`AnyDriver`] maps to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum AnyDriverType {
+    Null,
+    Bool,
+    SmallInt,
+    Integer,
+    BigInt,
+    Real,
+    Double,
+    Text,
+    Blob,
+    #[cfg(feature = "chrono")]
+    Date,
+    #[cfg(feature = "chrono")]
+    Time,
+    #[cfg(feature = "chrono")]
+    Timestamp,
+    #[cfg(feature = "chrono")]
+    TimestampTz,
+    #[cfg(feature = "uuid")]
+    Uuid,
+    #[cfg(feature = "json")]
+    Json,
+}
+
+impl AnyDriverValue {
+    pub fn type_(&self) -> AnyDriverType {
+        match self {
+            AnyDriverValue::Null => AnyDriverType::Null,
+            AnyDriverValue::Bool(_) => AnyDriverType::Bool,
+            AnyDriverValue::SmallInt(_) => AnyDriverType::SmallInt,
+            AnyDriverValue::Integer(_) => AnyDriverType::Integer,
+            AnyDriverValue::BigInt(_) => AnyDriverType::BigInt,
+            AnyDriverValue::Real(_) => AnyDriverType::Real,
+            AnyDriverValue::Double(_) => AnyDriverType::Double,
+            AnyDriverValue::Text(_) => AnyDriverType::Text,
+            AnyDriverValue::Blob(_) => AnyDriverType::Blob,
+            #[cfg(feature = "chrono")]
+            AnyDriverValue::Date(_) => AnyDriverType::Date,
+            #[cfg(feature = "chrono")]
+            AnyDriverValue::Time(_) => AnyDriverType::Time,
+            #[cfg(feature = "chrono")]
+            AnyDriverValue::Timestamp(_) => AnyDriverType::Timestamp,
+            #[cfg(feature = "chrono")]
+            AnyDriverValue::TimestampTz(_) => AnyDriverType::TimestampTz,
+            #[cfg(feature = "uuid")]
+            AnyDriverValue::Uuid(_) => AnyDriverType::Uuid,
+            #[cfg(feature = "json")]
+            AnyDriverValue::Json(_) => AnyDriverType::Json,
+        }
+    }
+}
+
+/// A column of the rows of an [`AnyDriver`].
+#[derive(Debug, Clone)]
+pub struct AnyDriverColumn {
+    pub name: String,
+
+    /// The name of the type of the column, which is passed to [`AnyDriver::type_info`].
+    pub type_name: String,
+}
+
+/// A row of an [`AnyDriver`], with a value for each of its columns.
+#[derive(Debug, Clone)]
+pub struct AnyDriverRow {
+    pub columns: Vec<AnyDriverColumn>,
+    pub values: Vec<AnyDriverValue>,
+}
+
+/// The description of a query by an [`AnyDriver`], as in [`Describe`].
+#[derive(Debug, Clone)]
+pub struct AnyDriverDescribe {
+    pub columns: Vec<AnyDriverColumn>,
+
+    /// The names of the types of the parameters, or only their number.
+    pub parameters: Option<Either<Vec<String>, usize>>,
+
+    /// Whether each column is nullable, if known.
+    pub nullable: Vec<Option<bool>>,
+}
+
+/// The type of a column or parameter of an [`AnyDriver`].
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct AnyDriverTypeInfo {
+    pub(crate) name: String,
+    pub(crate) type_: Option<AnyDriverType>,
+}
+
+/// Install a database driver for [`AnyConnection`][crate::any::AnyConnection], to connect to
+/// the URLs with its schemes.
+///
+/// The drivers compiled into SQLx are installed from the start. Connection URLs are matched
+/// to a driver when they're parsed, so a driver must be installed before its URLs are used.
+///
+/// Returns an error if one of the schemes of the driver, or its name, already belongs to an
+/// installed driver.
+pub fn install_driver(driver: AnyDriver) -> Result<(), Error> {
+    let mut drivers = DRIVERS.write().unwrap_or_else(|e| e.into_inner());
+
+    if driver.url_schemes.is_empty() {
+        return Err(Error::Configuration(
+            format!("the {} driver has no URL schemes", driver.name).into(),
+        ));
+    }
+
+    for (scheme, installed) in drivers.iter() {
+        if driver.url_schemes.contains(scheme) {
+            return Err(Error::Configuration(
+                format!(
+                    "the URL scheme `{}` of the {} driver is already used by the {} driver",
+                    scheme,
+                    driver.name,
+                    installed.name()
+                )
+                .into(),
+            ));
+        }
+
+        if installed.name() == driver.name {
+            return Err(Error::Configuration(
+                format!("a driver named {} is already installed", driver.name).into(),
+            ));
+        }
+    }
+
+    // installed drivers are never removed
+    let driver: &'static AnyDriver = Box::leak(Box::new(driver));
+
+    drivers.extend(
+        driver
+            .url_schemes
+            .iter()
+            .map(|scheme| (*scheme, Driver::Custom(driver))),
+    );
+
+    Ok(())
+}
+
+#[derive(Clone, Copy)]
+pub(crate) enum Driver {
+    Builtin(AnyKind),
+    Custom(&'static AnyDriver),
+}
+
+impl Driver {
+    pub(crate) fn kind(self) -> AnyKind {
+        match self {
+            Driver::Builtin(kind) => kind,
+            Driver::Custom(driver) => AnyKind::Custom(driver.name),
+        }
+    }
+
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            #[cfg(feature = "postgres")]
+            Driver::Builtin(AnyKind::Postgres) => "PostgreSQL",
+
+            #[cfg(feature = "mysql")]
+            Driver::Builtin(AnyKind::MySql) => "MySQL",
+
+            #[cfg(feature = "sqlite")]
+            Driver::Builtin(AnyKind::Sqlite) => "SQLite",
+
+            #[cfg(feature = "mssql")]
+            Driver::Builtin(AnyKind::Mssql) => "MSSQL",
+
+            Driver::Builtin(AnyKind::Custom(name)) => name,
+
+            Driver::Custom(driver) => driver.name,
+        }
+    }
+}
+
+// the URL schemes of the installed drivers
+static DRIVERS: Lazy<RwLock<Vec<(&'static str, Driver)>>> = Lazy::new(|| {
+    RwLock::new(vec![
+        #[cfg(feature = "postgres")]
+        ("postgres", Driver::Builtin(AnyKind::Postgres)),
+        #[cfg(feature = "postgres")]
+        ("postgresql", Driver::Builtin(AnyKind::Postgres)),
+        #[cfg(feature = "mysql")]
+        ("mysql", Driver::Builtin(AnyKind::MySql)),
+        #[cfg(feature = "mysql")]
+        ("mariadb", Driver::Builtin(AnyKind::MySql)),
+        #[cfg(feature = "sqlite")]
+        ("sqlite", Driver::Builtin(AnyKind::Sqlite)),
+        #[cfg(feature = "mssql")]
+        ("mssql", Driver::Builtin(AnyKind::Mssql)),
+        #[cfg(feature = "mssql")]
+        ("sqlserver", Driver::Builtin(AnyKind::Mssql)),
+    ])
+});
+
+/// The installed driver of the scheme of `url`.
+pub(crate) fn find(url: &str) -> Result<Driver, Error> {
+    let scheme = url.split_once(':').map_or("", |(scheme, _)| scheme);
+
+    let driver = DRIVERS
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .iter()
+        .find(|(installed, _)| *installed == scheme)
+        .map(|(_, driver)| *driver);
+
+    if let Some(driver) = driver {
+        return Ok(driver);
+    }
+
+    let feature = match scheme {
+        "postgres" | "postgresql" => Some(("PostgreSQL", "postgres")),
+        "mysql" | "mariadb" => Some(("MySQL", "mysql")),
+        "sqlite" => Some(("SQLite", "sqlite")),
+        "mssql" | "sqlserver" => Some(("MSSQL", "mssql")),
+        _ => None,
+    };
+
+    Err(Error::Configuration(match feature {
+        Some((name, feature)) => format!(
+            "database URL has the scheme of a {} database but the `{}` feature is not enabled",
+            name, feature
+        )
+        .into(),
+
+        None => format!("unrecognized database url: {:?}", url).into(),
+    }))
+}
+
+pub(crate) fn type_info(driver: &AnyDriver, name: &str) -> AnyTypeInfo {
+    AnyTypeInfo(AnyTypeInfoKind::Custom(AnyDriverTypeInfo {
+        type_: (driver.type_info)(name),
+        name: name.to_owned(),
+    }))
+}
+
+fn columns(driver: &AnyDriver, columns: Vec<AnyDriverColumn>) -> Vec<AnyColumn> {
+    columns
+        .into_iter()
+        .enumerate()
+        .map(|(ordinal, column)| AnyColumn {
+            type_info: type_info(driver, &column.type_name),
+            kind: AnyColumnKind::Custom {
+                ordinal,
+                name: column.name,
+            },
+        })
+        .collect()
+}
+
+pub(crate) fn row(driver: &AnyDriver, row: AnyDriverRow) -> AnyRow {
+    AnyRow {
+        columns: columns(driver, row.columns),
+        kind: AnyRowKind::Custom(row.values),
+    }
+}
+
+pub(crate) fn describe(driver: &AnyDriver, describe: AnyDriverDescribe) -> Describe<Any> {
+    let parameters = describe.parameters.map(|parameters| {
+        parameters.map_left(|types| types.iter().map(|name| type_info(driver, name)).collect())
+    });
+
+    Describe {
+        columns: columns(driver, describe.columns),
+        parameters,
+        nullable: describe.nullable,
+        domains: Vec::new(),
+    }
+}
+
+pub(crate) fn statement<'q>(
+    driver: &AnyDriver,
+    sql: &'q str,
+    describe: AnyDriverDescribe,
+) -> AnyStatement<'q> {
+    let describe = self::describe(driver, describe);
+
+    let column_names = describe
+        .columns
+        .iter()
+        .enumerate()
+        .map(|(ordinal, column)| (UStr::new(crate::column::Column::name(column)), ordinal))
+        .collect::<HashMap<_, _>>();
+
+    AnyStatement {
+        sql: sql.into(),
+        parameters: describe.parameters,
+        column_names: column_names.into(),
+        columns: describe.columns,
+    }
+}
+
+// Conversions of the Rust types of `Any` to and from the values of drivers
+
+pub(crate) trait DriverType {
+    fn compatible(ty: AnyDriverType) -> bool;
+}
+
+pub(crate) trait DriverEncode {
+    fn encode(&self) -> AnyDriverValue;
+}
+
+pub(crate) trait DriverDecode<'r>: Sized {
+    fn decode(value: &'r AnyDriverValue) -> Result<Self, BoxDynError>;
+}
+
+fn unexpected(value: &AnyDriverValue, expected: AnyDriverType) -> BoxDynError {
+    format!(
+        "expected a value of {:?} from the driver, got {:?}",
+        expected,
+        value.type_()
+    )
+    .into()
+}
+
+macro_rules! impl_driver_value {
+    ($ty:ty, $variant:ident, $encode:ident => $encoded:expr, $decode:ident => $decoded:expr) => {
+        impl DriverType for $ty {
+            fn compatible(ty: AnyDriverType) -> bool {
+                ty == AnyDriverType::$variant
+            }
+        }
+
+        impl DriverEncode for $ty {
+            fn encode(&self) -> AnyDriverValue {
+                let $encode = self;
+                AnyDriverValue::$variant($encoded)
+            }
+        }
+
+        impl<'r> DriverDecode<'r> for $ty {
+            fn decode(value: &'r AnyDriverValue) -> Result<Self, BoxDynError> {
+                match value {
+                    AnyDriverValue::$variant($decode) => Ok($decoded),
+                    _ => Err(unexpected(value, AnyDriverType::$variant)),
+                }
+            }
+        }
+    };
+}
+
+impl_driver_value!(bool, Bool, v => *v, v => *v);
+impl_driver_value!(i16, SmallInt, v => *v, v => *v);
+impl_driver_value!(i32, Integer, v => *v, v => *v);
+impl_driver_value!(i64, BigInt, v => *v, v => *v);
+impl_driver_value!(f32, Real, v => *v, v => *v);
+impl_driver_value!(f64, Double, v => *v, v => *v);
+impl_driver_value!(String, Text, v => v.clone(), v => v.clone());
+impl_driver_value!(Vec<u8>, Blob, v => v.clone(), v => v.clone());
+
+impl DriverType for str {
+    fn compatible(ty: AnyDriverType) -> bool {
+        ty == AnyDriverType::Text
+    }
+}
+
+impl DriverEncode for &'_ str {
+    fn encode(&self) -> AnyDriverValue {
+        AnyDriverValue::Text((*self).to_owned())
+    }
+}
+
+impl<'r> DriverDecode<'r> for &'r str {
+    fn decode(value: &'r AnyDriverValue) -> Result<Self, BoxDynError> {
+        match value {
+            AnyDriverValue::Text(v) => Ok(v),
+            _ => Err(unexpected(value, AnyDriverType::Text)),
+        }
+    }
+}
+
+impl DriverType for [u8] {
+    fn compatible(ty: AnyDriverType) -> bool {
+        ty == AnyDriverType::Blob
+    }
+}
+
+impl DriverEncode for &'_ [u8] {
+    fn encode(&self) -> AnyDriverValue {
+        AnyDriverValue::Blob(self.to_vec())
+    }
+}
+
+impl<'r> DriverDecode<'r> for &'r [u8] {
+    fn decode(value: &'r AnyDriverValue) -> Result<Self, BoxDynError> {
+        match value {
+            AnyDriverValue::Blob(v) => Ok(v),
+            _ => Err(unexpected(value, AnyDriverType::Blob)),
+        }
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl_driver_value!(chrono::NaiveDate, Date, v => *v, v => *v);
+#[cfg(feature = "chrono")]
+impl_driver_value!(chrono::NaiveTime, Time, v => *v, v => *v);
+#[cfg(feature = "chrono")]
+impl_driver_value!(chrono::NaiveDateTime, Timestamp, v => *v, v => *v);
+#[cfg(feature = "chrono")]
+impl_driver_value!(chrono::DateTime<chrono::Utc>, TimestampTz, v => *v, v => *v);
+#[cfg(feature = "chrono")]
+impl_driver_value!(
+    chrono::DateTime<chrono::Local>,
+    TimestampTz,
+    v => v.with_timezone(&chrono::Utc),
+    v => v.with_timezone(&chrono::Local)
+);
+
+#[cfg(feature = "uuid")]
+impl_driver_value!(uuid::Uuid, Uuid, v => *v, v => *v);
+
+#[cfg(feature = "json")]
+impl<T> DriverType for crate::types::Json<T> {
+    fn compatible(ty: AnyDriverType) -> bool {
+        ty == AnyDriverType::Json
+    }
+}
+
+#[cfg(feature = "json")]
+impl<T> DriverEncode for crate::types::Json<T>
+where
+    T: serde::Serialize,
+{
+    fn encode(&self) -> AnyDriverValue {
+        AnyDriverValue::Json(
+            serde_json::to_value(&self.0).expect("serde_json failed to convert to a value"),
+        )
+    }
+}
+
+#[cfg(feature = "json")]
+impl<'r, T> DriverDecode<'r> for crate::types::Json<T>
+where
+    T: 'r + serde::Deserialize<'r>,
+{
+    fn decode(value: &'r AnyDriverValue) -> Result<Self, BoxDynError> {
+        match value {
+            AnyDriverValue::Json(v) => Ok(crate::types::Json(T::deserialize(v)?)),
+            _ => Err(unexpected(value, AnyDriverType::Json)),
+        }
+    }
+}
@@ -16,10 +16,11 @@ use crate::sqlite::Sqlite;
 // Implements Encode for any T where T supports Encode for any database that has support currently
 // compiled into SQLx
 macro_rules! impl_any_encode {
-    ($ty:ty) => {
-        impl<'q> crate::encode::Encode<'q, crate::any::Any> for $ty
+    (<$($generic:ident),*> $ty:ty where $($bounds:tt)*) => {
+        impl<'q, $($generic),*> crate::encode::Encode<'q, crate::any::Any> for $ty
         where
             $ty: crate::any::AnyEncode<'q>,
+            $($bounds)*
         {
             fn encode_by_ref(
                 &self,
@@ -39,6 +40,10 @@ macro_rules! impl_any_encode {
 
                     #[cfg(feature = "sqlite")]
                     crate::any::arguments::AnyArgumentBufferKind::Sqlite(args) => args.add(self),
+
+                    crate::any::arguments::AnyArgumentBufferKind::Custom(args, _) => {
+                        args.push(crate::any::driver::DriverEncode::encode(self))
+                    }
                 }
 
                 // unused
@@ -46,6 +51,10 @@ macro_rules! impl_any_encode {
             }
         }
     };
+
+    ($ty:ty) => {
+        impl_any_encode!(<> $ty where);
+    };
 }
 
 // FIXME: Find a nice way to auto-generate the below or petition Rust to add support for #[cfg]
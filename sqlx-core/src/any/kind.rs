@@ -1,4 +1,8 @@
+use crate::any::driver::{self, Driver};
+use crate::any::Any;
+use crate::database::Database;
 use crate::error::Error;
+use crate::quote::QuoteError;
 use std::str::FromStr;
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
@@ -14,54 +18,61 @@ pub enum AnyKind {
 
     #[cfg(feature = "mssql")]
     Mssql,
+
+    /// A driver installed with [`install_driver`][crate::any::install_driver], by its name.
+    Custom(&'static str),
 }
 
 impl FromStr for AnyKind {
     type Err = Error;
 
     fn from_str(url: &str) -> Result<Self, Self::Err> {
-        match url {
-            #[cfg(feature = "postgres")]
-            _ if url.starts_with("postgres:") || url.starts_with("postgresql:") => {
-                Ok(AnyKind::Postgres)
-            }
+        driver::find(url).map(Driver::kind)
+    }
+}
 
-            #[cfg(not(feature = "postgres"))]
-            _ if url.starts_with("postgres:") || url.starts_with("postgresql:") => {
-                Err(Error::Configuration("database URL has the scheme of a PostgreSQL database but the `postgres` feature is not enabled".into()))
-            }
+impl AnyKind {
+    /// Quotes an identifier with the rules of this database.
+    ///
+    /// Unlike [`Any::quote_identifier`](crate::database::Database::quote_identifier), which
+    /// can't know the database in use and so quotes as in standard SQL (which MySQL rejects),
+    /// this picks the rules of the driver, e.g. from [`AnyConnection::kind`].
+    ///
+    /// [`AnyConnection::kind`]: crate::any::AnyConnection::kind
+    pub fn quote_identifier(&self, identifier: &str) -> Result<String, QuoteError> {
+        match self {
+            #[cfg(feature = "postgres")]
+            AnyKind::Postgres => crate::postgres::Postgres::quote_identifier(identifier),
 
             #[cfg(feature = "mysql")]
-            _ if url.starts_with("mysql:") || url.starts_with("mariadb:") => {
-                Ok(AnyKind::MySql)
-            }
-
-            #[cfg(not(feature = "mysql"))]
-            _ if url.starts_with("mysql:") || url.starts_with("mariadb:") => {
-                Err(Error::Configuration("database URL has the scheme of a MySQL database but the `mysql` feature is not enabled".into()))
-            }
+            AnyKind::MySql => crate::mysql::MySql::quote_identifier(identifier),
 
             #[cfg(feature = "sqlite")]
-            _ if url.starts_with("sqlite:") => {
-                Ok(AnyKind::Sqlite)
-            }
-
-            #[cfg(not(feature = "sqlite"))]
-            _ if url.starts_with("sqlite:") => {
-                Err(Error::Configuration("database URL has the scheme of a SQLite database but the `sqlite` feature is not enabled".into()))
-            }
+            AnyKind::Sqlite => crate::sqlite::Sqlite::quote_identifier(identifier),
 
             #[cfg(feature = "mssql")]
-            _ if url.starts_with("mssql:") || url.starts_with("sqlserver:") => {
-                Ok(AnyKind::Mssql)
-            }
+            AnyKind::Mssql => crate::mssql::Mssql::quote_identifier(identifier),
 
-            #[cfg(not(feature = "mssql"))]
-            _ if url.starts_with("mssql:") || url.starts_with("sqlserver:") => {
-                Err(Error::Configuration("database URL has the scheme of a MSSQL database but the `mssql` feature is not enabled".into()))
-            }
+            AnyKind::Custom(_) => Any::quote_identifier(identifier),
+        }
+    }
+
+    /// Quotes a string literal with the rules of this database.
+    pub fn quote_literal(&self, value: &str) -> Result<String, QuoteError> {
+        match self {
+            #[cfg(feature = "postgres")]
+            AnyKind::Postgres => crate::postgres::Postgres::quote_literal(value),
+
+            #[cfg(feature = "mysql")]
+            AnyKind::MySql => crate::mysql::MySql::quote_literal(value),
+
+            #[cfg(feature = "sqlite")]
+            AnyKind::Sqlite => crate::sqlite::Sqlite::quote_literal(value),
+
+            #[cfg(feature = "mssql")]
+            AnyKind::Mssql => crate::mssql::Mssql::quote_literal(value),
 
-            _ => Err(Error::Configuration(format!("unrecognized database url: {:?}", url).into()))
+            AnyKind::Custom(_) => Any::quote_literal(value),
         }
     }
 }
@@ -1,9 +1,14 @@
 use crate::any::connection::AnyConnectionKind;
+use crate::any::driver::{self, Driver};
 use crate::any::kind::AnyKind;
-use crate::any::{Any, AnyConnection};
+use crate::any::{Any, AnyConnection, AnyDriver, AnyDriverConnection, AnyMigrateDriver};
 use crate::error::Error;
-use crate::migrate::{AppliedMigration, Migrate, MigrateDatabase, MigrateError, Migration};
+use crate::migrate::{
+    AppliedMigration, AppliedRepeatableMigration, CreateDatabaseOptions, Migrate, MigrateDatabase,
+    MigrateError, Migration, Seed,
+};
 use futures_core::future::BoxFuture;
+use futures_util::future;
 use std::str::FromStr;
 use std::time::Duration;
 
@@ -22,6 +27,33 @@ impl MigrateDatabase for Any {
 
                 #[cfg(feature = "mssql")]
                 AnyKind::Mssql => unimplemented!(),
+
+                AnyKind::Custom(_) => (migrate_driver(url)?.create_database)(url).await,
+            }
+        })
+    }
+
+    fn create_database_with<'a>(
+        url: &'a str,
+        options: &'a CreateDatabaseOptions,
+    ) -> BoxFuture<'a, Result<(), Error>> {
+        Box::pin(async move {
+            match AnyKind::from_str(url)? {
+                #[cfg(feature = "postgres")]
+                AnyKind::Postgres => {
+                    crate::postgres::Postgres::create_database_with(url, options).await
+                }
+
+                #[cfg(feature = "sqlite")]
+                AnyKind::Sqlite => crate::sqlite::Sqlite::create_database_with(url, options).await,
+
+                #[cfg(feature = "mysql")]
+                AnyKind::MySql => crate::mysql::MySql::create_database_with(url, options).await,
+
+                #[cfg(feature = "mssql")]
+                AnyKind::Mssql => unimplemented!(),
+
+                AnyKind::Custom(_) => (migrate_driver(url)?.create_database)(url).await,
             }
         })
     }
@@ -40,6 +72,8 @@ impl MigrateDatabase for Any {
 
                 #[cfg(feature = "mssql")]
                 AnyKind::Mssql => unimplemented!(),
+
+                AnyKind::Custom(_) => (migrate_driver(url)?.database_exists)(url).await,
             }
         })
     }
@@ -58,175 +92,448 @@ impl MigrateDatabase for Any {
 
                 #[cfg(feature = "mssql")]
                 AnyKind::Mssql => unimplemented!(),
+
+                AnyKind::Custom(_) => (migrate_driver(url)?.drop_database)(url).await,
+            }
+        })
+    }
+
+    fn force_drop_database(url: &str) -> BoxFuture<'_, Result<(), Error>> {
+        Box::pin(async move {
+            match AnyKind::from_str(url)? {
+                #[cfg(feature = "postgres")]
+                AnyKind::Postgres => crate::postgres::Postgres::force_drop_database(url).await,
+
+                #[cfg(feature = "sqlite")]
+                AnyKind::Sqlite => crate::sqlite::Sqlite::force_drop_database(url).await,
+
+                #[cfg(feature = "mysql")]
+                AnyKind::MySql => crate::mysql::MySql::force_drop_database(url).await,
+
+                #[cfg(feature = "mssql")]
+                AnyKind::Mssql => unimplemented!(),
+
+                AnyKind::Custom(_) => (migrate_driver(url)?.drop_database)(url).await,
             }
         })
     }
 }
 
 impl Migrate for AnyConnection {
-    fn ensure_migrations_table(&mut self) -> BoxFuture<'_, Result<(), MigrateError>> {
+    fn ensure_migrations_table<'e>(
+        &'e mut self,
+        table_name: &'e str,
+    ) -> BoxFuture<'e, Result<(), MigrateError>> {
         match &mut self.0 {
             #[cfg(feature = "postgres")]
-            AnyConnectionKind::Postgres(conn) => conn.ensure_migrations_table(),
+            AnyConnectionKind::Postgres(conn) => conn.ensure_migrations_table(table_name),
 
             #[cfg(feature = "sqlite")]
-            AnyConnectionKind::Sqlite(conn) => conn.ensure_migrations_table(),
+            AnyConnectionKind::Sqlite(conn) => conn.ensure_migrations_table(table_name),
 
             #[cfg(feature = "mysql")]
-            AnyConnectionKind::MySql(conn) => conn.ensure_migrations_table(),
+            AnyConnectionKind::MySql(conn) => conn.ensure_migrations_table(table_name),
 
             #[cfg(feature = "mssql")]
-            AnyConnectionKind::Mssql(_conn) => unimplemented!(),
+            AnyConnectionKind::Mssql(_conn) => {
+                let _ = table_name;
+                unimplemented!()
+            }
+
+            AnyConnectionKind::Custom(driver, conn) => match migrate(driver, conn) {
+                Ok(conn) => conn.ensure_migrations_table(table_name),
+                Err(e) => Box::pin(future::err(e)),
+            },
+        }
+    }
+
+    fn migrations_table_exists<'e>(
+        &'e mut self,
+        table_name: &'e str,
+    ) -> BoxFuture<'e, Result<bool, MigrateError>> {
+        match &mut self.0 {
+            #[cfg(feature = "postgres")]
+            AnyConnectionKind::Postgres(conn) => conn.migrations_table_exists(table_name),
+
+            #[cfg(feature = "sqlite")]
+            AnyConnectionKind::Sqlite(conn) => conn.migrations_table_exists(table_name),
+
+            #[cfg(feature = "mysql")]
+            AnyConnectionKind::MySql(conn) => conn.migrations_table_exists(table_name),
+
+            #[cfg(feature = "mssql")]
+            AnyConnectionKind::Mssql(_conn) => {
+                let _ = table_name;
+                unimplemented!()
+            }
+
+            AnyConnectionKind::Custom(driver, conn) => match migrate(driver, conn) {
+                Ok(conn) => conn.migrations_table_exists(table_name),
+                Err(e) => Box::pin(future::err(e)),
+            },
         }
     }
 
     #[allow(deprecated)]
-    fn version(&mut self) -> BoxFuture<'_, Result<Option<(i64, bool)>, MigrateError>> {
+    fn version<'e>(
+        &'e mut self,
+        table_name: &'e str,
+    ) -> BoxFuture<'e, Result<Option<(i64, bool)>, MigrateError>> {
         match &mut self.0 {
             #[cfg(feature = "postgres")]
-            AnyConnectionKind::Postgres(conn) => conn.version(),
+            AnyConnectionKind::Postgres(conn) => conn.version(table_name),
 
             #[cfg(feature = "sqlite")]
-            AnyConnectionKind::Sqlite(conn) => conn.version(),
+            AnyConnectionKind::Sqlite(conn) => conn.version(table_name),
 
             #[cfg(feature = "mysql")]
-            AnyConnectionKind::MySql(conn) => conn.version(),
+            AnyConnectionKind::MySql(conn) => conn.version(table_name),
 
             #[cfg(feature = "mssql")]
-            AnyConnectionKind::Mssql(_conn) => unimplemented!(),
+            AnyConnectionKind::Mssql(_conn) => {
+                let _ = table_name;
+                unimplemented!()
+            }
+
+            AnyConnectionKind::Custom(driver, conn) => match migrate(driver, conn) {
+                Ok(conn) => conn.version(table_name),
+                Err(e) => Box::pin(future::err(e)),
+            },
         }
     }
 
-    fn dirty_version(&mut self) -> BoxFuture<'_, Result<Option<i64>, MigrateError>> {
+    fn dirty_version<'e>(
+        &'e mut self,
+        table_name: &'e str,
+    ) -> BoxFuture<'e, Result<Option<i64>, MigrateError>> {
         match &mut self.0 {
             #[cfg(feature = "postgres")]
-            AnyConnectionKind::Postgres(conn) => conn.dirty_version(),
+            AnyConnectionKind::Postgres(conn) => conn.dirty_version(table_name),
 
             #[cfg(feature = "sqlite")]
-            AnyConnectionKind::Sqlite(conn) => conn.dirty_version(),
+            AnyConnectionKind::Sqlite(conn) => conn.dirty_version(table_name),
 
             #[cfg(feature = "mysql")]
-            AnyConnectionKind::MySql(conn) => conn.dirty_version(),
+            AnyConnectionKind::MySql(conn) => conn.dirty_version(table_name),
 
             #[cfg(feature = "mssql")]
-            AnyConnectionKind::Mssql(_conn) => unimplemented!(),
+            AnyConnectionKind::Mssql(_conn) => {
+                let _ = table_name;
+                unimplemented!()
+            }
+
+            AnyConnectionKind::Custom(driver, conn) => match migrate(driver, conn) {
+                Ok(conn) => conn.dirty_version(table_name),
+                Err(e) => Box::pin(future::err(e)),
+            },
         }
     }
 
     #[allow(deprecated)]
     fn validate<'e: 'm, 'm>(
         &'e mut self,
+        table_name: &'m str,
         migration: &'m Migration,
     ) -> BoxFuture<'m, Result<(), MigrateError>> {
         match &mut self.0 {
             #[cfg(feature = "postgres")]
-            AnyConnectionKind::Postgres(conn) => conn.validate(migration),
+            AnyConnectionKind::Postgres(conn) => conn.validate(table_name, migration),
 
             #[cfg(feature = "sqlite")]
-            AnyConnectionKind::Sqlite(conn) => conn.validate(migration),
+            AnyConnectionKind::Sqlite(conn) => conn.validate(table_name, migration),
 
             #[cfg(feature = "mysql")]
-            AnyConnectionKind::MySql(conn) => conn.validate(migration),
+            AnyConnectionKind::MySql(conn) => conn.validate(table_name, migration),
 
             #[cfg(feature = "mssql")]
             AnyConnectionKind::Mssql(_conn) => {
-                let _ = migration;
+                let _ = (table_name, migration);
                 unimplemented!()
             }
+
+            AnyConnectionKind::Custom(driver, conn) => match migrate(driver, conn) {
+                Ok(conn) => conn.validate(table_name, migration),
+                Err(e) => Box::pin(future::err(e)),
+            },
         }
     }
 
-    fn list_applied_migrations(
-        &mut self,
-    ) -> BoxFuture<'_, Result<Vec<AppliedMigration>, MigrateError>> {
+    fn list_applied_migrations<'e>(
+        &'e mut self,
+        table_name: &'e str,
+    ) -> BoxFuture<'e, Result<Vec<AppliedMigration>, MigrateError>> {
         match &mut self.0 {
             #[cfg(feature = "postgres")]
-            AnyConnectionKind::Postgres(conn) => conn.list_applied_migrations(),
+            AnyConnectionKind::Postgres(conn) => conn.list_applied_migrations(table_name),
 
             #[cfg(feature = "sqlite")]
-            AnyConnectionKind::Sqlite(conn) => conn.list_applied_migrations(),
+            AnyConnectionKind::Sqlite(conn) => conn.list_applied_migrations(table_name),
 
             #[cfg(feature = "mysql")]
-            AnyConnectionKind::MySql(conn) => conn.list_applied_migrations(),
+            AnyConnectionKind::MySql(conn) => conn.list_applied_migrations(table_name),
 
             #[cfg(feature = "mssql")]
-            AnyConnectionKind::Mssql(_conn) => unimplemented!(),
+            AnyConnectionKind::Mssql(_conn) => {
+                let _ = table_name;
+                unimplemented!()
+            }
+
+            AnyConnectionKind::Custom(driver, conn) => match migrate(driver, conn) {
+                Ok(conn) => conn.list_applied_migrations(table_name),
+                Err(e) => Box::pin(future::err(e)),
+            },
         }
     }
 
-    fn lock(&mut self) -> BoxFuture<'_, Result<(), MigrateError>> {
+    fn list_applied_repeatable_migrations<'e>(
+        &'e mut self,
+        table_name: &'e str,
+    ) -> BoxFuture<'e, Result<Vec<AppliedRepeatableMigration>, MigrateError>> {
         match &mut self.0 {
             #[cfg(feature = "postgres")]
-            AnyConnectionKind::Postgres(conn) => conn.lock(),
+            AnyConnectionKind::Postgres(conn) => {
+                conn.list_applied_repeatable_migrations(table_name)
+            }
 
             #[cfg(feature = "sqlite")]
-            AnyConnectionKind::Sqlite(conn) => conn.lock(),
+            AnyConnectionKind::Sqlite(conn) => conn.list_applied_repeatable_migrations(table_name),
 
             #[cfg(feature = "mysql")]
-            AnyConnectionKind::MySql(conn) => conn.lock(),
+            AnyConnectionKind::MySql(conn) => conn.list_applied_repeatable_migrations(table_name),
 
             #[cfg(feature = "mssql")]
-            AnyConnectionKind::Mssql(_conn) => unimplemented!(),
+            AnyConnectionKind::Mssql(_conn) => {
+                let _ = table_name;
+                unimplemented!()
+            }
+
+            AnyConnectionKind::Custom(driver, conn) => match migrate(driver, conn) {
+                Ok(conn) => conn.list_applied_repeatable_migrations(table_name),
+                Err(e) => Box::pin(future::err(e)),
+            },
         }
     }
 
-    fn unlock(&mut self) -> BoxFuture<'_, Result<(), MigrateError>> {
+    fn lock<'e>(
+        &'e mut self,
+        table_name: &'e str,
+        timeout: Option<Duration>,
+    ) -> BoxFuture<'e, Result<(), MigrateError>> {
         match &mut self.0 {
             #[cfg(feature = "postgres")]
-            AnyConnectionKind::Postgres(conn) => conn.unlock(),
+            AnyConnectionKind::Postgres(conn) => conn.lock(table_name, timeout),
 
             #[cfg(feature = "sqlite")]
-            AnyConnectionKind::Sqlite(conn) => conn.unlock(),
+            AnyConnectionKind::Sqlite(conn) => conn.lock(table_name, timeout),
 
             #[cfg(feature = "mysql")]
-            AnyConnectionKind::MySql(conn) => conn.unlock(),
+            AnyConnectionKind::MySql(conn) => conn.lock(table_name, timeout),
 
             #[cfg(feature = "mssql")]
-            AnyConnectionKind::Mssql(_conn) => unimplemented!(),
+            AnyConnectionKind::Mssql(_conn) => {
+                let _ = (table_name, timeout);
+                unimplemented!()
+            }
+
+            AnyConnectionKind::Custom(driver, conn) => match migrate(driver, conn) {
+                Ok(conn) => conn.lock(table_name, timeout),
+                Err(e) => Box::pin(future::err(e)),
+            },
+        }
+    }
+
+    fn unlock<'e>(&'e mut self, table_name: &'e str) -> BoxFuture<'e, Result<(), MigrateError>> {
+        match &mut self.0 {
+            #[cfg(feature = "postgres")]
+            AnyConnectionKind::Postgres(conn) => conn.unlock(table_name),
+
+            #[cfg(feature = "sqlite")]
+            AnyConnectionKind::Sqlite(conn) => conn.unlock(table_name),
+
+            #[cfg(feature = "mysql")]
+            AnyConnectionKind::MySql(conn) => conn.unlock(table_name),
+
+            #[cfg(feature = "mssql")]
+            AnyConnectionKind::Mssql(_conn) => {
+                let _ = table_name;
+                unimplemented!()
+            }
+
+            AnyConnectionKind::Custom(driver, conn) => match migrate(driver, conn) {
+                Ok(conn) => conn.unlock(table_name),
+                Err(e) => Box::pin(future::err(e)),
+            },
         }
     }
 
     fn apply<'e: 'm, 'm>(
         &'e mut self,
+        table_name: &'m str,
         migration: &'m Migration,
+        applied_by: Option<&'m str>,
     ) -> BoxFuture<'m, Result<Duration, MigrateError>> {
         match &mut self.0 {
             #[cfg(feature = "postgres")]
-            AnyConnectionKind::Postgres(conn) => conn.apply(migration),
+            AnyConnectionKind::Postgres(conn) => conn.apply(table_name, migration, applied_by),
+
+            #[cfg(feature = "sqlite")]
+            AnyConnectionKind::Sqlite(conn) => conn.apply(table_name, migration, applied_by),
+
+            #[cfg(feature = "mysql")]
+            AnyConnectionKind::MySql(conn) => conn.apply(table_name, migration, applied_by),
+
+            #[cfg(feature = "mssql")]
+            AnyConnectionKind::Mssql(_conn) => {
+                let _ = (table_name, migration, applied_by);
+                unimplemented!()
+            }
+
+            AnyConnectionKind::Custom(driver, conn) => match migrate(driver, conn) {
+                Ok(conn) => conn.apply(table_name, migration, applied_by),
+                Err(e) => Box::pin(future::err(e)),
+            },
+        }
+    }
+
+    fn set_checksum<'e: 'm, 'm>(
+        &'e mut self,
+        table_name: &'m str,
+        migration: &'m Migration,
+    ) -> BoxFuture<'m, Result<(), MigrateError>> {
+        match &mut self.0 {
+            #[cfg(feature = "postgres")]
+            AnyConnectionKind::Postgres(conn) => conn.set_checksum(table_name, migration),
+
+            #[cfg(feature = "sqlite")]
+            AnyConnectionKind::Sqlite(conn) => conn.set_checksum(table_name, migration),
+
+            #[cfg(feature = "mysql")]
+            AnyConnectionKind::MySql(conn) => conn.set_checksum(table_name, migration),
+
+            #[cfg(feature = "mssql")]
+            AnyConnectionKind::Mssql(_conn) => {
+                let _ = (table_name, migration);
+                unimplemented!()
+            }
+
+            AnyConnectionKind::Custom(driver, conn) => match migrate(driver, conn) {
+                Ok(conn) => conn.set_checksum(table_name, migration),
+                Err(e) => Box::pin(future::err(e)),
+            },
+        }
+    }
+
+    fn mark_applied<'e: 'm, 'm>(
+        &'e mut self,
+        table_name: &'m str,
+        migration: &'m Migration,
+        applied_by: Option<&'m str>,
+    ) -> BoxFuture<'m, Result<(), MigrateError>> {
+        match &mut self.0 {
+            #[cfg(feature = "postgres")]
+            AnyConnectionKind::Postgres(conn) => {
+                conn.mark_applied(table_name, migration, applied_by)
+            }
 
             #[cfg(feature = "sqlite")]
-            AnyConnectionKind::Sqlite(conn) => conn.apply(migration),
+            AnyConnectionKind::Sqlite(conn) => conn.mark_applied(table_name, migration, applied_by),
 
             #[cfg(feature = "mysql")]
-            AnyConnectionKind::MySql(conn) => conn.apply(migration),
+            AnyConnectionKind::MySql(conn) => conn.mark_applied(table_name, migration, applied_by),
 
             #[cfg(feature = "mssql")]
             AnyConnectionKind::Mssql(_conn) => {
-                let _ = migration;
+                let _ = (table_name, migration, applied_by);
                 unimplemented!()
             }
+
+            AnyConnectionKind::Custom(driver, conn) => match migrate(driver, conn) {
+                Ok(conn) => conn.mark_applied(table_name, migration, applied_by),
+                Err(e) => Box::pin(future::err(e)),
+            },
         }
     }
 
     fn revert<'e: 'm, 'm>(
         &'e mut self,
+        table_name: &'m str,
         migration: &'m Migration,
     ) -> BoxFuture<'m, Result<Duration, MigrateError>> {
         match &mut self.0 {
             #[cfg(feature = "postgres")]
-            AnyConnectionKind::Postgres(conn) => conn.revert(migration),
+            AnyConnectionKind::Postgres(conn) => conn.revert(table_name, migration),
 
             #[cfg(feature = "sqlite")]
-            AnyConnectionKind::Sqlite(conn) => conn.revert(migration),
+            AnyConnectionKind::Sqlite(conn) => conn.revert(table_name, migration),
 
             #[cfg(feature = "mysql")]
-            AnyConnectionKind::MySql(conn) => conn.revert(migration),
+            AnyConnectionKind::MySql(conn) => conn.revert(table_name, migration),
 
             #[cfg(feature = "mssql")]
             AnyConnectionKind::Mssql(_conn) => {
-                let _ = migration;
+                let _ = (table_name, migration);
                 unimplemented!()
             }
+
+            AnyConnectionKind::Custom(driver, conn) => match migrate(driver, conn) {
+                Ok(conn) => conn.revert(table_name, migration),
+                Err(e) => Box::pin(future::err(e)),
+            },
         }
     }
+
+    fn seed<'e: 'm, 'm>(
+        &'e mut self,
+        seed: &'m Seed,
+    ) -> BoxFuture<'m, Result<Duration, MigrateError>> {
+        match &mut self.0 {
+            #[cfg(feature = "postgres")]
+            AnyConnectionKind::Postgres(conn) => conn.seed(seed),
+
+            #[cfg(feature = "sqlite")]
+            AnyConnectionKind::Sqlite(conn) => conn.seed(seed),
+
+            #[cfg(feature = "mysql")]
+            AnyConnectionKind::MySql(conn) => conn.seed(seed),
+
+            #[cfg(feature = "mssql")]
+            AnyConnectionKind::Mssql(_conn) => {
+                let _ = seed;
+                unimplemented!()
+            }
+
+            AnyConnectionKind::Custom(driver, conn) => match migrate(driver, conn) {
+                Ok(conn) => conn.seed(seed),
+                Err(e) => Box::pin(future::err(e)),
+            },
+        }
+    }
+}
+
+fn migrate_driver(url: &str) -> Result<AnyMigrateDriver, Error> {
+    match driver::find(url)? {
+        Driver::Custom(AnyDriver {
+            migrate: Some(migrate),
+            ..
+        }) => Ok(*migrate),
+
+        driver => Err(Error::Configuration(
+            format!(
+                "the {} driver doesn't support creating and dropping databases",
+                driver.name()
+            )
+            .into(),
+        )),
+    }
+}
+
+fn migrate<'c>(
+    driver: &AnyDriver,
+    conn: &'c mut Box<dyn AnyDriverConnection>,
+) -> Result<&'c mut (dyn Migrate + Send), MigrateError> {
+    conn.migrate().ok_or_else(|| {
+        MigrateError::Execute(Error::Configuration(
+            format!("the {} driver doesn't support migrations", driver.name).into(),
+        ))
+    })
 }
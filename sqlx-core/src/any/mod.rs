@@ -1,4 +1,6 @@
 //! Generic database driver with the specific driver selected at runtime.
+//!
+//! The drivers of databases which aren't built into SQLx can be installed with [`install_driver`].
 
 use crate::executor::Executor;
 
@@ -15,6 +17,8 @@ mod arguments;
 pub(crate) mod column;
 mod connection;
 mod database;
+mod downcast;
+pub(crate) mod driver;
 mod error;
 mod kind;
 mod options;
@@ -29,6 +33,9 @@ pub(crate) mod value;
 #[cfg(feature = "migrate")]
 mod migrate;
 
+#[cfg(feature = "serde_row")]
+mod serde_row;
+
 pub use arguments::{AnyArgumentBuffer, AnyArguments};
 pub use column::{AnyColumn, AnyColumnIndex};
 pub use connection::AnyConnection;
@@ -37,6 +44,11 @@ pub use connection::AnyConnection;
 pub use connection::AnyConnectionKind;
 pub use database::Any;
 pub use decode::AnyDecode;
+pub use downcast::AnyDowncast;
+pub use driver::{
+    install_driver, AnyDriver, AnyDriverColumn, AnyDriverConnection, AnyDriverDescribe,
+    AnyDriverRow, AnyDriverType, AnyDriverValue, AnyMigrateDriver,
+};
 pub use encode::AnyEncode;
 pub use kind::AnyKind;
 pub use options::AnyConnectOptions;
@@ -67,7 +79,7 @@ impl_into_maybe_pool!(Any, AnyConnection);
 // required because some databases have a different handling of NULL
 impl<'q, T> crate::encode::Encode<'q, Any> for Option<T>
 where
-    T: AnyEncode<'q> + 'q,
+    T: AnyEncode<'q> + crate::encode::Encode<'q, Any> + 'q,
 {
     fn encode_by_ref(&self, buf: &mut AnyArgumentBuffer<'q>) -> crate::encode::IsNull {
         match &mut buf.0 {
@@ -82,6 +94,13 @@ where
 
             #[cfg(feature = "sqlite")]
             arguments::AnyArgumentBufferKind::Sqlite(args) => args.add(self),
+
+            arguments::AnyArgumentBufferKind::Custom(args, _) => match self {
+                Some(value) => {
+                    return <T as crate::encode::Encode<'q, Any>>::encode_by_ref(value, buf)
+                }
+                None => args.push(AnyDriverValue::Null),
+            },
         }
 
         // unused
@@ -7,6 +7,15 @@ pub struct AnyQueryResult {
 }
 
 impl AnyQueryResult {
+    /// The result of a statement, for the drivers installed with
+    /// [`install_driver`][crate::any::install_driver].
+    pub fn new(rows_affected: u64, last_insert_id: Option<i64>) -> Self {
+        AnyQueryResult {
+            rows_affected,
+            last_insert_id,
+        }
+    }
+
     pub fn rows_affected(&self) -> u64 {
         self.rows_affected
     }
@@ -16,6 +25,12 @@ impl AnyQueryResult {
     }
 }
 
+impl crate::dyn_connection::DynQueryResult for AnyQueryResult {
+    fn rows_affected(&self) -> u64 {
+        self.rows_affected
+    }
+}
+
 impl Extend<AnyQueryResult> for AnyQueryResult {
     fn extend<T: IntoIterator<Item = AnyQueryResult>>(&mut self, iter: T) {
         for elem in iter {
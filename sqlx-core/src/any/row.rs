@@ -1,13 +1,15 @@
 use crate::any::error::mismatched_types;
-use crate::any::{Any, AnyColumn, AnyColumnIndex};
-use crate::column::ColumnIndex;
+use crate::any::value::AnyValueRefKind;
+use crate::any::{Any, AnyColumn, AnyColumnIndex, AnyDowncast, AnyDriverValue, AnyValueRef};
+use crate::column::{Column, ColumnIndex};
 use crate::database::HasValueRef;
 use crate::decode::Decode;
-use crate::error::Error;
-use crate::row::Row;
+use crate::error::{Error, ErrorContext};
+use crate::row::{with_column_context, Row};
 use crate::type_info::TypeInfo;
 use crate::types::Type;
 use crate::value::ValueRef;
+use std::sync::Arc;
 
 #[cfg(feature = "postgres")]
 use crate::postgres::PgRow;
@@ -28,6 +30,19 @@ pub struct AnyRow {
 
 impl crate::row::private_row::Sealed for AnyRow {}
 
+impl AnyRow {
+    /// Returns the row of the driver in use, if it's an `R`, e.g. a
+    /// [`PgRow`](crate::postgres::PgRow) for PostgreSQL.
+    pub fn downcast_ref<R: AnyDowncast<Self>>(&self) -> Option<&R> {
+        R::downcast_ref(self)
+    }
+
+    /// Returns the row of the driver in use, if it's an `R`.
+    pub fn downcast_mut<R: AnyDowncast<Self>>(&mut self) -> Option<&mut R> {
+        R::downcast_mut(self)
+    }
+}
+
 pub(crate) enum AnyRowKind {
     #[cfg(feature = "postgres")]
     Postgres(PgRow),
@@ -40,6 +55,8 @@ pub(crate) enum AnyRowKind {
 
     #[cfg(feature = "mssql")]
     Mssql(MssqlRow),
+
+    Custom(Vec<AnyDriverValue>),
 }
 
 impl Row for AnyRow {
@@ -70,6 +87,18 @@ impl Row for AnyRow {
 
             #[cfg(feature = "mssql")]
             AnyRowKind::Mssql(row) => row.try_get_raw(index).map(Into::into),
+
+            AnyRowKind::Custom(values) => match values.get(index) {
+                Some(value) => Ok(AnyValueRef {
+                    kind: AnyValueRefKind::Custom(value),
+                    type_info: self.columns[index].type_info.clone(),
+                }),
+
+                None => Err(Error::ColumnIndexOutOfBounds {
+                    index,
+                    len: values.len(),
+                }),
+            },
         }
     }
 
@@ -86,11 +115,33 @@ impl Row for AnyRow {
         } else {
             T::decode(value)
         }
-        .map_err(|source| Error::ColumnDecode {
-            index: format!("{:?}", index),
-            source,
+        .map_err(|source| {
+            let error = Error::ColumnDecode {
+                index: format!("{:?}", index),
+                source,
+            };
+
+            with_column_context::<Self, T, I>(self, &index, error)
         })
     }
+
+    fn error_context(&self) -> Option<&Arc<ErrorContext>> {
+        match &self.kind {
+            #[cfg(feature = "postgres")]
+            AnyRowKind::Postgres(row) => row.error_context(),
+
+            #[cfg(feature = "mysql")]
+            AnyRowKind::MySql(row) => row.error_context(),
+
+            #[cfg(feature = "sqlite")]
+            AnyRowKind::Sqlite(row) => row.error_context(),
+
+            #[cfg(feature = "mssql")]
+            AnyRowKind::Mssql(row) => row.error_context(),
+
+            AnyRowKind::Custom(_) => None,
+        }
+    }
 }
 
 impl<'i> ColumnIndex<AnyRow> for &'i str
@@ -110,6 +161,12 @@ where
 
             #[cfg(feature = "mssql")]
             AnyRowKind::Mssql(row) => self.index(row),
+
+            AnyRowKind::Custom(_) => row
+                .columns
+                .iter()
+                .position(|column| column.name() == *self)
+                .ok_or_else(|| Error::ColumnNotFound((*self).into())),
         }
     }
 }
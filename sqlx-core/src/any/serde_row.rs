@@ -0,0 +1,52 @@
+use crate::any::row::AnyRowKind;
+use crate::any::{AnyDriverValue, AnyRow};
+use crate::error::Error;
+use crate::serde_row::{ColumnValue, ColumnValues};
+
+impl ColumnValues for AnyRow {
+    fn column_value(&self, index: usize) -> Result<ColumnValue, Error> {
+        match &self.kind {
+            #[cfg(feature = "postgres")]
+            AnyRowKind::Postgres(row) => row.column_value(index),
+
+            #[cfg(feature = "mysql")]
+            AnyRowKind::MySql(row) => row.column_value(index),
+
+            #[cfg(feature = "sqlite")]
+            AnyRowKind::Sqlite(row) => row.column_value(index),
+
+            #[cfg(feature = "mssql")]
+            AnyRowKind::Mssql(_) => Err(Error::Decode(
+                "MSSQL rows can't be deserialized with serde".into(),
+            )),
+
+            AnyRowKind::Custom(values) => Ok(convert(&values[index])),
+        }
+    }
+}
+
+fn convert(value: &AnyDriverValue) -> ColumnValue {
+    match value {
+        AnyDriverValue::Null => ColumnValue::Null,
+        AnyDriverValue::Bool(value) => ColumnValue::Bool(*value),
+        AnyDriverValue::SmallInt(value) => ColumnValue::I64((*value).into()),
+        AnyDriverValue::Integer(value) => ColumnValue::I64((*value).into()),
+        AnyDriverValue::BigInt(value) => ColumnValue::I64(*value),
+        AnyDriverValue::Real(value) => ColumnValue::F64((*value).into()),
+        AnyDriverValue::Double(value) => ColumnValue::F64(*value),
+        AnyDriverValue::Text(value) => ColumnValue::String(value.clone()),
+        AnyDriverValue::Blob(value) => ColumnValue::Bytes(value.clone()),
+        #[cfg(feature = "chrono")]
+        AnyDriverValue::Date(value) => ColumnValue::String(value.to_string()),
+        #[cfg(feature = "chrono")]
+        AnyDriverValue::Time(value) => ColumnValue::String(value.to_string()),
+        #[cfg(feature = "chrono")]
+        AnyDriverValue::Timestamp(value) => ColumnValue::String(value.to_string()),
+        #[cfg(feature = "chrono")]
+        AnyDriverValue::TimestampTz(value) => ColumnValue::String(value.to_rfc3339()),
+        #[cfg(feature = "uuid")]
+        AnyDriverValue::Uuid(value) => ColumnValue::String(value.to_string()),
+        #[cfg(feature = "json")]
+        AnyDriverValue::Json(value) => ColumnValue::Json(value.clone()),
+    }
+}
@@ -4,7 +4,7 @@ use crate::any::connection::AnyConnectionKind;
 use crate::any::{Any, AnyConnection};
 use crate::database::Database;
 use crate::error::Error;
-use crate::transaction::TransactionManager;
+use crate::transaction::{TransactionManager, TransactionOptions};
 
 pub struct AnyTransactionManager;
 
@@ -32,6 +32,45 @@ impl TransactionManager for AnyTransactionManager {
             AnyConnectionKind::Mssql(conn) => {
                 <crate::mssql::Mssql as Database>::TransactionManager::begin(conn)
             }
+
+            AnyConnectionKind::Custom(_, conn) => conn.begin(),
+        }
+    }
+
+    fn begin_with(
+        conn: &mut AnyConnection,
+        options: TransactionOptions,
+    ) -> BoxFuture<'_, Result<(), Error>> {
+        match &mut conn.0 {
+            #[cfg(feature = "postgres")]
+            AnyConnectionKind::Postgres(conn) => {
+                <crate::postgres::Postgres as Database>::TransactionManager::begin_with(
+                    conn, options,
+                )
+            }
+
+            #[cfg(feature = "mysql")]
+            AnyConnectionKind::MySql(conn) => {
+                <crate::mysql::MySql as Database>::TransactionManager::begin_with(conn, options)
+            }
+
+            #[cfg(feature = "sqlite")]
+            AnyConnectionKind::Sqlite(conn) => {
+                <crate::sqlite::Sqlite as Database>::TransactionManager::begin_with(conn, options)
+            }
+
+            #[cfg(feature = "mssql")]
+            AnyConnectionKind::Mssql(conn) => {
+                <crate::mssql::Mssql as Database>::TransactionManager::begin_with(conn, options)
+            }
+
+            AnyConnectionKind::Custom(_, conn) => {
+                if options.is_default() {
+                    conn.begin()
+                } else {
+                    conn.begin_with(options)
+                }
+            }
         }
     }
 
@@ -56,6 +95,8 @@ impl TransactionManager for AnyTransactionManager {
             AnyConnectionKind::Mssql(conn) => {
                 <crate::mssql::Mssql as Database>::TransactionManager::commit(conn)
             }
+
+            AnyConnectionKind::Custom(_, conn) => conn.commit(),
         }
     }
 
@@ -80,6 +121,8 @@ impl TransactionManager for AnyTransactionManager {
             AnyConnectionKind::Mssql(conn) => {
                 <crate::mssql::Mssql as Database>::TransactionManager::rollback(conn)
             }
+
+            AnyConnectionKind::Custom(_, conn) => conn.rollback(),
         }
     }
 
@@ -104,6 +147,8 @@ impl TransactionManager for AnyTransactionManager {
             AnyConnectionKind::Mssql(conn) => {
                 <crate::mssql::Mssql as Database>::TransactionManager::start_rollback(conn)
             }
+
+            AnyConnectionKind::Custom(_, conn) => conn.start_rollback(),
         }
     }
 }
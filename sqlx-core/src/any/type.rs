@@ -5,8 +5,11 @@
 // The other use of this trait is for compile-time verification which is not feasible to support
 // for the [`Any`] driver.
 macro_rules! impl_any_type {
-    ($ty:ty) => {
-        impl crate::types::Type<crate::any::Any> for $ty {
+    (<$($generic:ident),*> $ty:ty where $($bounds:tt)*) => {
+        impl<$($generic),*> crate::types::Type<crate::any::Any> for $ty
+        where
+            $($bounds)*
+        {
             fn type_info() -> crate::any::AnyTypeInfo {
                 // FIXME: nicer panic explaining why this isn't possible
                 unimplemented!()
@@ -33,8 +36,17 @@ macro_rules! impl_any_type {
                     crate::any::type_info::AnyTypeInfoKind::Mssql(ty) => {
                         <$ty as crate::types::Type<crate::mssql::Mssql>>::compatible(&ty)
                     }
+
+                    // the types of drivers are mapped to values by the drivers themselves
+                    crate::any::type_info::AnyTypeInfoKind::Custom(ty) => ty
+                        .type_
+                        .map_or(false, <$ty as crate::any::driver::DriverType>::compatible),
                 }
             }
         }
     };
+
+    ($ty:ty) => {
+        impl_any_type!(<> $ty where);
+    };
 }
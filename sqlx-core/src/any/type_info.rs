@@ -1,5 +1,6 @@
 use std::fmt::{self, Display, Formatter};
 
+use crate::any::driver::{AnyDriverType, AnyDriverTypeInfo};
 use crate::type_info::TypeInfo;
 
 #[cfg(feature = "postgres")]
@@ -30,6 +31,8 @@ pub(crate) enum AnyTypeInfoKind {
 
     #[cfg(feature = "mssql")]
     Mssql(MssqlTypeInfo),
+
+    Custom(AnyDriverTypeInfo),
 }
 
 impl TypeInfo for AnyTypeInfo {
@@ -46,6 +49,8 @@ impl TypeInfo for AnyTypeInfo {
 
             #[cfg(feature = "mssql")]
             AnyTypeInfoKind::Mssql(ty) => ty.is_null(),
+
+            AnyTypeInfoKind::Custom(ty) => ty.type_ == Some(AnyDriverType::Null),
         }
     }
 
@@ -62,6 +67,8 @@ impl TypeInfo for AnyTypeInfo {
 
             #[cfg(feature = "mssql")]
             AnyTypeInfoKind::Mssql(ty) => ty.name(),
+
+            AnyTypeInfoKind::Custom(ty) => &ty.name,
         }
     }
 }
@@ -80,6 +87,8 @@ impl Display for AnyTypeInfo {
 
             #[cfg(feature = "mssql")]
             AnyTypeInfoKind::Mssql(ty) => ty.fmt(f),
+
+            AnyTypeInfoKind::Custom(ty) => f.pad(&ty.name),
         }
     }
 }
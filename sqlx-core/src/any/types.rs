@@ -11,6 +11,41 @@
 //! | `f32`                                 | FLOAT                                                |
 //! | `f64`                                 | DOUBLE                                               |
 //! | `&str`, [`String`]                    | VARCHAR, CHAR, TEXT                                  |
+//! | `&[u8]`, `Vec<u8>`                    | BYTEA (PostgreSQL), VARBINARY, BLOB                  |
+//!
+//! ### [`chrono`](https://crates.io/crates/chrono)
+//!
+//! Requires the `chrono` Cargo feature flag.
+//!
+//! | Rust type                             | SQL type(s)                                          |
+//! |---------------------------------------|------------------------------------------------------|
+//! | `chrono::DateTime<Utc>`               | TIMESTAMPTZ (PostgreSQL), TIMESTAMP, DATETIME        |
+//! | `chrono::DateTime<Local>`             | TIMESTAMPTZ (PostgreSQL), TIMESTAMP, DATETIME        |
+//! | `chrono::NaiveDateTime`               | TIMESTAMP, DATETIME                                  |
+//! | `chrono::NaiveDate`                   | DATE                                                 |
+//! | `chrono::NaiveTime`                   | TIME                                                 |
+//!
+//! ### [`uuid`](https://crates.io/crates/uuid)
+//!
+//! Requires the `uuid` Cargo feature flag.
+//!
+//! | Rust type                             | SQL type(s)                                          |
+//! |---------------------------------------|------------------------------------------------------|
+//! | `uuid::Uuid`                          | UUID (PostgreSQL), BINARY(16) (MySQL), BLOB (SQLite) |
+//!
+//! MySQL and SQLite store a `Uuid` as its 16 bytes, so a `CHAR(36)` column of UUIDs in the
+//! hyphenated text format can't be decoded as a `Uuid` through `Any`.
+//!
+//! ### [`json`](https://crates.io/crates/serde_json)
+//!
+//! Requires the `json` Cargo feature flag.
+//!
+//! | Rust type                             | SQL type(s)                                          |
+//! |---------------------------------------|------------------------------------------------------|
+//! | `Json<T>`                             | JSONB, JSON (PostgreSQL), JSON (MySQL), TEXT (SQLite)|
+//! | `serde_json::Value`                   | JSONB, JSON (PostgreSQL), JSON (MySQL), TEXT (SQLite)|
+//!
+//! None of these are supported while the `mssql` Cargo feature flag is enabled.
 //!
 //! # Nullable
 //!
@@ -193,3 +228,53 @@ impl_any_decode!(chrono::DateTime<chrono::offset::Utc>);
     not(feature = "mssql")
 ))]
 impl_any_decode!(chrono::DateTime<chrono::offset::Local>);
+
+// Conversions for UUID SQL types
+// Type
+#[cfg(all(
+    feature = "uuid",
+    any(feature = "mysql", feature = "sqlite", feature = "postgres"),
+    not(feature = "mssql")
+))]
+impl_any_type!(uuid::Uuid);
+
+// Encode
+#[cfg(all(
+    feature = "uuid",
+    any(feature = "mysql", feature = "sqlite", feature = "postgres"),
+    not(feature = "mssql")
+))]
+impl_any_encode!(uuid::Uuid);
+
+// Decode
+#[cfg(all(
+    feature = "uuid",
+    any(feature = "mysql", feature = "sqlite", feature = "postgres"),
+    not(feature = "mssql")
+))]
+impl_any_decode!(uuid::Uuid);
+
+// Conversions for JSON SQL types
+// Type
+#[cfg(all(
+    feature = "json",
+    any(feature = "mysql", feature = "sqlite", feature = "postgres"),
+    not(feature = "mssql")
+))]
+impl_any_type!(<T> crate::types::Json<T> where);
+
+// Encode
+#[cfg(all(
+    feature = "json",
+    any(feature = "mysql", feature = "sqlite", feature = "postgres"),
+    not(feature = "mssql")
+))]
+impl_any_encode!(<T> crate::types::Json<T> where T: serde::Serialize);
+
+// Decode
+#[cfg(all(
+    feature = "json",
+    any(feature = "mysql", feature = "sqlite", feature = "postgres"),
+    not(feature = "mssql")
+))]
+impl_any_decode!(<T> crate::types::Json<T> where T: 'r + serde::Deserialize<'r>);
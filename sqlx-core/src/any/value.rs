@@ -1,7 +1,7 @@
 use std::borrow::Cow;
 
 use crate::any::error::mismatched_types;
-use crate::any::{Any, AnyTypeInfo};
+use crate::any::{Any, AnyDriverValue, AnyTypeInfo};
 use crate::database::HasValueRef;
 use crate::decode::Decode;
 use crate::error::Error;
@@ -38,6 +38,8 @@ pub(crate) enum AnyValueKind {
 
     #[cfg(feature = "mssql")]
     Mssql(MssqlValue),
+
+    Custom(AnyDriverValue),
 }
 
 pub struct AnyValueRef<'r> {
@@ -57,6 +59,8 @@ pub(crate) enum AnyValueRefKind<'r> {
 
     #[cfg(feature = "mssql")]
     Mssql(MssqlValueRef<'r>),
+
+    Custom(&'r AnyDriverValue),
 }
 
 impl Value for AnyValue {
@@ -75,6 +79,11 @@ impl Value for AnyValue {
 
             #[cfg(feature = "mssql")]
             AnyValueKind::Mssql(value) => value.as_ref().into(),
+
+            AnyValueKind::Custom(value) => AnyValueRef {
+                kind: AnyValueRefKind::Custom(value),
+                type_info: self.type_info.clone(),
+            },
         }
     }
 
@@ -95,6 +104,8 @@ impl Value for AnyValue {
 
             #[cfg(feature = "mssql")]
             AnyValueKind::Mssql(value) => value.is_null(),
+
+            AnyValueKind::Custom(value) => *value == AnyDriverValue::Null,
         }
     }
 
@@ -130,6 +141,11 @@ impl<'r> ValueRef<'r> for AnyValueRef<'r> {
 
             #[cfg(feature = "mssql")]
             AnyValueRefKind::Mssql(value) => ValueRef::to_owned(value).into(),
+
+            AnyValueRefKind::Custom(value) => AnyValue {
+                kind: AnyValueKind::Custom((*value).clone()),
+                type_info: self.type_info.clone(),
+            },
         }
     }
 
@@ -150,6 +166,8 @@ impl<'r> ValueRef<'r> for AnyValueRef<'r> {
 
             #[cfg(feature = "mssql")]
             AnyValueRefKind::Mssql(value) => value.is_null(),
+
+            AnyValueRefKind::Custom(value) => **value == AnyDriverValue::Null,
         }
     }
 }
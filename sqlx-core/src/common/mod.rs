@@ -1,5 +1,9 @@
+#[cfg(any(feature = "postgres", feature = "mysql"))]
+mod prepared_statement;
 mod statement_cache;
 
+#[cfg(any(feature = "postgres", feature = "mysql"))]
+pub(crate) use prepared_statement::PreparedStatement;
 pub(crate) use statement_cache::StatementCache;
 use std::fmt::{Debug, Formatter};
 use std::ops::{Deref, DerefMut};
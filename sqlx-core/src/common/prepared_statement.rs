@@ -0,0 +1,73 @@
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+
+use crate::error::Error;
+
+/// A statement prepared on the server by a connection, which the statement returned by
+/// `prepare()` refers to so that the connection can execute it without looking it up in its
+/// cache.
+#[derive(Debug, Clone)]
+pub(crate) struct PreparedStatement<Id> {
+    connection_id: u64,
+    id: Id,
+    state: Arc<State>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    // set once the connection closed the statement, e.g. when evicting it from its cache
+    closed: AtomicBool,
+    hits: AtomicU64,
+}
+
+impl<Id: Copy> PreparedStatement<Id> {
+    pub(crate) fn new(connection_id: u64, id: Id) -> Self {
+        Self {
+            connection_id,
+            id,
+            state: Arc::default(),
+        }
+    }
+
+    /// The id of the statement on the server, which may have been closed.
+    pub(crate) fn id(&self) -> Id {
+        self.id
+    }
+
+    /// Returns the id of the statement if it's still prepared, counting a hit, or an error if
+    /// it was prepared by another connection than `connection_id`.
+    pub(crate) fn reuse(&self, connection_id: u64) -> Result<Option<Id>, Error> {
+        if self.is_closed(connection_id)? {
+            return Ok(None);
+        }
+
+        self.hit();
+
+        Ok(Some(self.id))
+    }
+
+    /// Returns `true` if the statement was closed, or an error if it was prepared by another
+    /// connection than `connection_id`.
+    pub(crate) fn is_closed(&self, connection_id: u64) -> Result<bool, Error> {
+        if self.connection_id != connection_id {
+            return Err(Error::StatementConnectionMismatch {
+                prepared_by: self.connection_id,
+                used_by: connection_id,
+            });
+        }
+
+        Ok(self.state.closed.load(Ordering::Acquire))
+    }
+
+    pub(crate) fn hit(&self) {
+        self.state.hits.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn hits(&self) -> u64 {
+        self.state.hits.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn close(&self) {
+        self.state.closed.store(true, Ordering::Release);
+    }
+}
@@ -38,6 +38,16 @@ impl<T> StatementCache<T> {
         lru_item
     }
 
+    /// Removes the statement with the given key from the cache, returning it.
+    pub fn remove(&mut self, k: &str) -> Option<T> {
+        self.inner.remove(k)
+    }
+
+    /// Returns the cached statements with their keys, from the least recently used.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &T)> {
+        self.inner.iter().map(|(k, v)| (k.as_str(), v))
+    }
+
     /// The number of statements in the cache.
     pub fn len(&self) -> usize {
         self.inner.len()
@@ -58,6 +58,7 @@ use std::fmt::Debug;
 use crate::arguments::Arguments;
 use crate::column::Column;
 use crate::connection::Connection;
+use crate::quote::{self, QuoteError};
 use crate::row::Row;
 use crate::statement::Statement;
 use crate::transaction::TransactionManager;
@@ -98,6 +99,37 @@ pub trait Database:
     /// The concrete type used to hold an owned copy of the not-yet-decoded value that was
     /// received from the database.
     type Value: Value<Database = Self> + 'static;
+
+    /// The most bind parameters a statement can have with the default configuration of the
+    /// database, see [`QueryBuilder::push_bind`](crate::query_builder::QueryBuilder::push_bind).
+    ///
+    /// By default, this is the limit of PostgreSQL and MySQL, whose protocols count the
+    /// parameters with 16 bits.
+    const MAX_BIND_PARAMETERS: usize = 65535;
+
+    /// Quotes an identifier, e.g. the name of a table, to embed it in SQL.
+    ///
+    /// By default, the identifier is wrapped in double quotes, as in standard SQL, and a double
+    /// quote within it is doubled. Drivers override this with the rules of their database, e.g.
+    /// MySQL wraps identifiers in backticks instead.
+    ///
+    /// Returns an error if the identifier is empty, contains a NUL character, or is longer
+    /// than the database allows.
+    fn quote_identifier(identifier: &str) -> Result<String, QuoteError> {
+        quote::check_identifier_len(identifier.len(), usize::MAX)?;
+        quote::quote(identifier, '"', '"')
+    }
+
+    /// Quotes a string literal to embed it in SQL.
+    ///
+    /// By default, the literal is wrapped in single quotes, as in standard SQL, and a single
+    /// quote within it is doubled. Prefer binding values as arguments where possible; this is
+    /// for the parts of a statement which can't be bound, e.g. the options of DDL.
+    ///
+    /// Returns an error if the literal contains a NUL character.
+    fn quote_literal(value: &str) -> Result<String, QuoteError> {
+        quote::quote(value, '\'', '\'')
+    }
 }
 
 /// Associate [`Database`] with a [`ValueRef`](crate::value::ValueRef) of a generic lifetime.
@@ -1,10 +1,37 @@
 use crate::database::Database;
+use crate::error::Error;
+use crate::executor::Executor;
 use either::Either;
+use futures_core::future::BoxFuture;
 use std::convert::identity;
 
+/// Describe a SQL query without executing it: the names and types of its output columns, whether
+/// each of them may be `NULL`, and its parameters.
+///
+/// This is what the query macros (e.g., `query!`, `query_as!`, etc.) check queries with, so the
+/// inferred nullability is the same as theirs. See [`Describe::nullable`] for how each database
+/// infers it.
+///
+/// ```rust,ignore
+/// let describe = sqlx::describe(&mut conn, "SELECT id, owner_id FROM tweet").await?;
+///
+/// for (i, column) in describe.columns().iter().enumerate() {
+///     println!("{}: {} (nullable: {:?})", column.name(), column.type_info(), describe.nullable(i));
+/// }
+/// ```
+pub fn describe<'e, 'c: 'e, E>(
+    executor: E,
+    sql: &'e str,
+) -> BoxFuture<'e, Result<Describe<E::Database>, Error>>
+where
+    E: 'e + Executor<'c>,
+{
+    executor.describe(sql)
+}
+
 /// Provides extended information on a statement.
 ///
-/// Returned from [`Executor::describe`].
+/// Returned from [`describe`] and [`Executor::describe`].
 ///
 /// The query macros (e.g., `query!`, `query_as!`, etc.) use the information here to validate
 /// output and parameter types; and, generate an anonymous record.
@@ -17,11 +44,15 @@ use std::convert::identity;
         deserialize = "DB::TypeInfo: serde::de::DeserializeOwned, DB::Column: serde::de::DeserializeOwned",
     ))
 )]
-#[doc(hidden)]
 pub struct Describe<DB: Database> {
     pub(crate) columns: Vec<DB::Column>,
     pub(crate) parameters: Option<Either<Vec<DB::TypeInfo>, usize>>,
     pub(crate) nullable: Vec<Option<bool>>,
+    #[cfg_attr(
+        feature = "offline",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub(crate) domains: Vec<Option<String>>,
 }
 
 impl<DB: Database> Describe<DB> {
@@ -50,7 +81,23 @@ impl<DB: Database> Describe<DB> {
     }
 
     /// Gets whether a column may be `NULL`, if this information is available.
+    ///
+    /// * **PostgreSQL** tells the `NOT NULL` constraints of the columns of tables, and the columns
+    ///   on the nullable side of an outer join are found in the plan of the query (`EXPLAIN`).
+    ///   The nullability of other expressions is unknown (`None`).
+    /// * **SQLite** infers it by stepping through the bytecode of the query (`EXPLAIN`), from the
+    ///   `NOT NULL` constraints of the tables and the operations on their columns.
+    /// * **MySQL** tells it for each column, including most expressions.
+    /// * **MSSQL** tells it for each column.
     pub fn nullable(&self, column: usize) -> Option<bool> {
         self.nullable.get(column).copied().and_then(identity)
     }
+
+    /// Gets the name of the domain a column is declared with, if any, as the column is described
+    /// with the base type of the domain.
+    ///
+    /// Only **PostgreSQL** has domains.
+    pub fn domain(&self, column: usize) -> Option<&str> {
+        self.domains.get(column).and_then(Option::as_deref)
+    }
 }
@@ -0,0 +1,385 @@
+//! Type-erased connections, for code which must not be generic over the database, e.g. the
+//! plugins of an application which hands them "a database connection".
+//!
+//! Any connection of a built-in driver but MSSQL is a [`DynConnection`], which can be boxed into
+//! a [`BoxDynConnection`] and downcast back to its concrete type:
+//!
+//! ```rust,no_run
+//! # #[cfg(all(feature = "postgres", feature = "sqlite"))]
+//! # async fn example() -> Result<(), sqlx::Error> {
+//! use sqlx::dyn_connection::BoxDynConnection;
+//! use sqlx::{Connection, PgConnection, SqliteConnection};
+//!
+//! async fn count_users(conn: &mut BoxDynConnection) -> Result<i64, sqlx::Error> {
+//!     let row = conn.fetch_one("SELECT COUNT(*) FROM users").await?;
+//!
+//!     Ok(row.try_get_i64(0)?.unwrap_or_default())
+//! }
+//!
+//! let url = std::env::var("DATABASE_URL").unwrap();
+//!
+//! let mut conn: BoxDynConnection = if url.starts_with("sqlite:") {
+//!     Box::new(SqliteConnection::connect(&url).await?)
+//! } else {
+//!     Box::new(PgConnection::connect(&url).await?)
+//! };
+//!
+//! count_users(&mut conn).await?;
+//!
+//! let pg: Option<&mut PgConnection> = conn.downcast_mut();
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! This is deliberately the lowest common denominator of the drivers, with tradeoffs compared
+//! to [`AnyConnection`](crate::any::AnyConnection), which also works with connections of any
+//! database:
+//!
+//! * Statements can't have arguments, so values must be quoted into their SQL with
+//!   [`DynConnection::quote_literal`] and [`DynConnection::quote_identifier`].
+//! * Values are read with a few accessors on [`DynRow`], for strings, integers, floats, bytes
+//!   and booleans, rather than decoded to any type with [`Row::try_get`] and [`FromRow`].
+//! * Transactions are begun and committed on the connection itself, without a guard which rolls
+//!   them back when dropped.
+//! * On the other hand, it doesn't translate anything: the statements are executed as-is and the
+//!   values are decoded with the rules of the database, and it works with any type implementing
+//!   [`Connection`] whose database supports those values, including connections of custom
+//!   drivers.
+//!
+//! [`FromRow`]: crate::from_row::FromRow
+
+use std::any::Any;
+
+use futures_core::future::BoxFuture;
+use futures_core::stream::BoxStream;
+use futures_util::{FutureExt, StreamExt, TryFutureExt, TryStreamExt};
+
+use crate::column::{Column, ColumnIndex};
+use crate::connection::Connection;
+use crate::database::Database;
+use crate::decode::Decode;
+use crate::error::Error;
+use crate::executor::Executor;
+use crate::quote::QuoteError;
+use crate::row::Row;
+use crate::transaction::TransactionManager;
+use crate::type_info::TypeInfo;
+use crate::types::Type;
+use crate::value::ValueRef;
+
+/// A boxed connection of any database.
+pub type BoxDynConnection = Box<dyn DynConnection>;
+
+/// The object-safe subset of [`Connection`] and [`Executor`], implemented for the connections of
+/// every database which supports the values of [`DynRow`].
+///
+/// See the [module documentation](self) for the tradeoffs of this API.
+pub trait DynConnection: Send + 'static {
+    /// Executes a statement, or several separated by semicolons, and returns the total number of
+    /// rows they affected.
+    fn execute<'e>(&'e mut self, sql: &'e str) -> BoxFuture<'e, Result<u64, Error>>;
+
+    /// Executes a statement and returns its rows as a stream.
+    fn fetch<'e>(&'e mut self, sql: &'e str) -> BoxStream<'e, Result<Box<dyn DynRow>, Error>>;
+
+    /// Executes a statement and returns all its rows.
+    fn fetch_all<'e>(
+        &'e mut self,
+        sql: &'e str,
+    ) -> BoxFuture<'e, Result<Vec<Box<dyn DynRow>>, Error>>;
+
+    /// Executes a statement and returns its first row, or [`Error::RowNotFound`] if it returned
+    /// none.
+    fn fetch_one<'e>(&'e mut self, sql: &'e str) -> BoxFuture<'e, Result<Box<dyn DynRow>, Error>>;
+
+    /// Executes a statement and returns its first row, if any.
+    fn fetch_optional<'e>(
+        &'e mut self,
+        sql: &'e str,
+    ) -> BoxFuture<'e, Result<Option<Box<dyn DynRow>>, Error>>;
+
+    /// Begins a new transaction, or establishes a savepoint within the active transaction.
+    ///
+    /// Unlike [`Connection::begin`], the transaction isn't rolled back when anything is dropped:
+    /// it must be ended with [`commit`](Self::commit) or [`rollback`](Self::rollback).
+    fn begin(&mut self) -> BoxFuture<'_, Result<(), Error>>;
+
+    /// Commits the active transaction, or releases the most recent savepoint.
+    fn commit(&mut self) -> BoxFuture<'_, Result<(), Error>>;
+
+    /// Rolls back the active transaction, or restores the most recent savepoint.
+    fn rollback(&mut self) -> BoxFuture<'_, Result<(), Error>>;
+
+    /// Checks if the connection to the database is still valid.
+    fn ping(&mut self) -> BoxFuture<'_, Result<(), Error>>;
+
+    /// Explicitly closes the connection, see [`Connection::close`].
+    fn close(self: Box<Self>) -> BoxFuture<'static, Result<(), Error>>;
+
+    /// Quotes an identifier for the database, see
+    /// [`Database::quote_identifier`](crate::database::Database::quote_identifier).
+    fn quote_identifier(&self, identifier: &str) -> Result<String, QuoteError>;
+
+    /// Quotes a string literal for the database, see
+    /// [`Database::quote_literal`](crate::database::Database::quote_literal).
+    fn quote_literal(&self, literal: &str) -> Result<String, QuoteError>;
+
+    #[doc(hidden)]
+    fn as_any(&self) -> &dyn Any;
+
+    #[doc(hidden)]
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+
+    #[doc(hidden)]
+    fn into_any(self: Box<Self>) -> Box<dyn Any + Send>;
+}
+
+impl dyn DynConnection {
+    /// Returns `true` if this is a connection of type `C`.
+    pub fn is<C: DynConnection>(&self) -> bool {
+        self.as_any().is::<C>()
+    }
+
+    /// Returns a reference to the connection if it has type `C`.
+    pub fn downcast_ref<C: DynConnection>(&self) -> Option<&C> {
+        self.as_any().downcast_ref()
+    }
+
+    /// Returns a mutable reference to the connection if it has type `C`.
+    pub fn downcast_mut<C: DynConnection>(&mut self) -> Option<&mut C> {
+        self.as_any_mut().downcast_mut()
+    }
+
+    /// Converts the connection back to type `C`, or returns it unchanged if it has another type.
+    pub fn downcast<C: DynConnection>(self: Box<Self>) -> Result<Box<C>, Box<Self>> {
+        if self.is::<C>() {
+            Ok(self.into_any().downcast().expect("type was checked"))
+        } else {
+            Err(self)
+        }
+    }
+}
+
+/// The number of rows affected by statements, which the results of every built-in driver report.
+pub trait DynQueryResult {
+    fn rows_affected(&self) -> u64;
+}
+
+impl<C, DB> DynConnection for C
+where
+    C: Connection<Database = DB> + 'static,
+    DB: Database<Connection = C>,
+    DB::QueryResult: DynQueryResult,
+    DB::Row: DynRow,
+    for<'c> &'c mut C: Executor<'c, Database = DB>,
+{
+    fn execute<'e>(&'e mut self, sql: &'e str) -> BoxFuture<'e, Result<u64, Error>> {
+        Executor::execute(self, sql)
+            .map_ok(|result| result.rows_affected())
+            .boxed()
+    }
+
+    fn fetch<'e>(&'e mut self, sql: &'e str) -> BoxStream<'e, Result<Box<dyn DynRow>, Error>> {
+        Executor::fetch(self, sql)
+            .map_ok(|row| Box::new(row) as Box<dyn DynRow>)
+            .boxed()
+    }
+
+    fn fetch_all<'e>(
+        &'e mut self,
+        sql: &'e str,
+    ) -> BoxFuture<'e, Result<Vec<Box<dyn DynRow>>, Error>> {
+        DynConnection::fetch(self, sql).try_collect().boxed()
+    }
+
+    fn fetch_one<'e>(&'e mut self, sql: &'e str) -> BoxFuture<'e, Result<Box<dyn DynRow>, Error>> {
+        DynConnection::fetch_optional(self, sql)
+            .map(|row| row?.ok_or(Error::RowNotFound))
+            .boxed()
+    }
+
+    fn fetch_optional<'e>(
+        &'e mut self,
+        sql: &'e str,
+    ) -> BoxFuture<'e, Result<Option<Box<dyn DynRow>>, Error>> {
+        Executor::fetch_optional(self, sql)
+            .map_ok(|row| row.map(|row| Box::new(row) as Box<dyn DynRow>))
+            .boxed()
+    }
+
+    fn begin(&mut self) -> BoxFuture<'_, Result<(), Error>> {
+        DB::TransactionManager::begin(self)
+    }
+
+    fn commit(&mut self) -> BoxFuture<'_, Result<(), Error>> {
+        DB::TransactionManager::commit(self)
+    }
+
+    fn rollback(&mut self) -> BoxFuture<'_, Result<(), Error>> {
+        DB::TransactionManager::rollback(self)
+    }
+
+    fn ping(&mut self) -> BoxFuture<'_, Result<(), Error>> {
+        Connection::ping(self)
+    }
+
+    fn close(self: Box<Self>) -> BoxFuture<'static, Result<(), Error>> {
+        Connection::close(*self)
+    }
+
+    fn quote_identifier(&self, identifier: &str) -> Result<String, QuoteError> {
+        DB::quote_identifier(identifier)
+    }
+
+    fn quote_literal(&self, literal: &str) -> Result<String, QuoteError> {
+        DB::quote_literal(literal)
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn into_any(self: Box<Self>) -> Box<dyn Any + Send> {
+        self
+    }
+}
+
+/// A row returned by a [`DynConnection`], whose values are read by the position of their column.
+///
+/// The accessors return `None` for `NULL` and decode values with the rules of the database, like
+/// [`Row::try_get`], so a column must have a type compatible with the one which is read, e.g. a
+/// PostgreSQL `TEXT` can't be read as an integer. Integers and floats are widened, e.g. a
+/// PostgreSQL `INT4` is read as an `i64`.
+pub trait DynRow: Send + Sync + 'static {
+    /// Returns the number of columns in this row.
+    fn len(&self) -> usize;
+
+    /// Returns `true` if this row has no columns.
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the name of the column at `index`, or `None` if it's out of bounds.
+    fn column_name(&self, index: usize) -> Option<&str>;
+
+    /// Returns the position of the column named `name`, or [`Error::ColumnNotFound`].
+    fn try_column_index(&self, name: &str) -> Result<usize, Error>;
+
+    /// Returns `true` if the value at `index` is `NULL`.
+    fn try_is_null(&self, index: usize) -> Result<bool, Error>;
+
+    fn try_get_string(&self, index: usize) -> Result<Option<String>, Error>;
+
+    fn try_get_i64(&self, index: usize) -> Result<Option<i64>, Error>;
+
+    fn try_get_f64(&self, index: usize) -> Result<Option<f64>, Error>;
+
+    fn try_get_bytes(&self, index: usize) -> Result<Option<Vec<u8>>, Error>;
+
+    fn try_get_bool(&self, index: usize) -> Result<Option<bool>, Error>;
+
+    #[doc(hidden)]
+    fn as_any(&self) -> &dyn Any;
+}
+
+impl dyn DynRow {
+    /// Returns a reference to the row if it has type `R`, e.g. [`PgRow`](crate::postgres::PgRow).
+    pub fn downcast_ref<R: DynRow>(&self) -> Option<&R> {
+        self.as_any().downcast_ref()
+    }
+}
+
+impl<R, DB> DynRow for R
+where
+    R: Row<Database = DB>,
+    DB: Database,
+    usize: ColumnIndex<R>,
+    for<'a> &'a str: ColumnIndex<R>,
+    for<'r> String: Decode<'r, DB> + Type<DB>,
+    for<'r> i64: Decode<'r, DB> + Type<DB>,
+    for<'r> i32: Decode<'r, DB> + Type<DB>,
+    for<'r> i16: Decode<'r, DB> + Type<DB>,
+    for<'r> f64: Decode<'r, DB> + Type<DB>,
+    for<'r> f32: Decode<'r, DB> + Type<DB>,
+    for<'r> Vec<u8>: Decode<'r, DB> + Type<DB>,
+    for<'r> bool: Decode<'r, DB> + Type<DB>,
+{
+    fn len(&self) -> usize {
+        Row::len(self)
+    }
+
+    fn column_name(&self, index: usize) -> Option<&str> {
+        self.columns().get(index).map(Column::name)
+    }
+
+    fn try_column_index(&self, name: &str) -> Result<usize, Error> {
+        name.index(self)
+    }
+
+    fn try_is_null(&self, index: usize) -> Result<bool, Error> {
+        Ok(self.try_get_raw(index)?.is_null())
+    }
+
+    fn try_get_string(&self, index: usize) -> Result<Option<String>, Error> {
+        self.try_get(index)
+    }
+
+    fn try_get_i64(&self, index: usize) -> Result<Option<i64>, Error> {
+        match widened_type::<R, i64>(self, index)? {
+            Some(ty) if <i32 as Type<DB>>::compatible(&ty) => {
+                Ok(self.try_get::<Option<i32>, _>(index)?.map(i64::from))
+            }
+            Some(ty) if <i16 as Type<DB>>::compatible(&ty) => {
+                Ok(self.try_get::<Option<i16>, _>(index)?.map(i64::from))
+            }
+            _ => self.try_get(index),
+        }
+    }
+
+    fn try_get_f64(&self, index: usize) -> Result<Option<f64>, Error> {
+        match widened_type::<R, f64>(self, index)? {
+            Some(ty) if <f32 as Type<DB>>::compatible(&ty) => {
+                Ok(self.try_get::<Option<f32>, _>(index)?.map(f64::from))
+            }
+            _ => self.try_get(index),
+        }
+    }
+
+    fn try_get_bytes(&self, index: usize) -> Result<Option<Vec<u8>>, Error> {
+        self.try_get(index)
+    }
+
+    fn try_get_bool(&self, index: usize) -> Result<Option<bool>, Error> {
+        self.try_get(index)
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// Returns the type of the value at `index` if it's not `NULL` and not compatible with `T`, so
+/// that it may be read as a narrower type.
+fn widened_type<R, T>(
+    row: &R,
+    index: usize,
+) -> Result<Option<<R::Database as Database>::TypeInfo>, Error>
+where
+    R: Row,
+    usize: ColumnIndex<R>,
+    T: Type<R::Database>,
+{
+    let value = row.try_get_raw(index)?;
+
+    if value.is_null() {
+        return Ok(None);
+    }
+
+    let ty = value.type_info();
+
+    Ok((!ty.is_null() && !T::compatible(&ty)).then(|| ty.into_owned()))
+}
@@ -3,9 +3,10 @@
 use std::any::type_name;
 use std::borrow::Cow;
 use std::error::Error as StdError;
-use std::fmt::Display;
+use std::fmt::{self, Display, Formatter};
 use std::io;
 use std::result::Result as StdResult;
+use std::sync::Arc;
 
 use crate::database::Database;
 use crate::type_info::TypeInfo;
@@ -100,9 +101,31 @@ pub enum Error {
     #[error("attempted to communicate with a crashed background worker")]
     WorkerCrashed,
 
+    /// A statement prepared by a connection was executed or closed by another connection.
+    ///
+    /// A statement can be executed by any connection once it's
+    /// [detached](crate::statement::Statement::detach) from the connection which prepared it,
+    /// like the statements prepared by a pool.
+    #[error(
+        "statement was prepared by connection {prepared_by} but used by connection {used_by}; \
+         detach it to use it with any connection"
+    )]
+    StatementConnectionMismatch { prepared_by: u64, used_by: u64 },
+
     #[cfg(feature = "migrate")]
     #[error("{0}")]
     Migrate(#[source] Box<crate::migrate::MigrateError>),
+
+    /// An error with the context of the statement it occurred with, when enabled by
+    /// [`ConnectOptions::capture_statement_on_error`].
+    ///
+    /// [`ConnectOptions::capture_statement_on_error`]: crate::connection::ConnectOptions::capture_statement_on_error
+    #[error("{error}; {context}")]
+    WithContext {
+        #[source]
+        error: Box<Error>,
+        context: Box<ErrorContext>,
+    },
 }
 
 impl StdError for Box<dyn DatabaseError> {}
@@ -111,6 +134,7 @@ impl Error {
     pub fn into_database_error(self) -> Option<Box<dyn DatabaseError + 'static>> {
         match self {
             Error::Database(err) => Some(err),
+            Error::WithContext { error, .. } => error.into_database_error(),
             _ => None,
         }
     }
@@ -118,10 +142,43 @@ impl Error {
     pub fn as_database_error(&self) -> Option<&(dyn DatabaseError + 'static)> {
         match self {
             Error::Database(err) => Some(&**err),
+            Error::WithContext { error, .. } => error.as_database_error(),
             _ => None,
         }
     }
 
+    /// Returns the context of the statement the error occurred with, if it was captured.
+    ///
+    /// See [`ConnectOptions::capture_statement_on_error`].
+    ///
+    /// [`ConnectOptions::capture_statement_on_error`]: crate::connection::ConnectOptions::capture_statement_on_error
+    pub fn context(&self) -> Option<&ErrorContext> {
+        match self {
+            Error::WithContext { context, .. } => Some(context),
+            _ => None,
+        }
+    }
+
+    /// Returns the error without the context of its statement, e.g. to match on it.
+    pub fn without_context(&self) -> &Error {
+        match self {
+            Error::WithContext { error, .. } => error,
+            error => error,
+        }
+    }
+
+    /// Wraps an error returned from the database for a statement with its context, if captured.
+    pub(crate) fn with_context(self, context: &Option<Arc<ErrorContext>>) -> Self {
+        match (self, context) {
+            (error @ Error::Database(_), Some(context)) => Error::WithContext {
+                error: Box::new(error),
+                context: Box::new(ErrorContext::clone(context)),
+            },
+
+            (error, _) => error,
+        }
+    }
+
     #[allow(dead_code)]
     #[inline]
     pub(crate) fn protocol(err: impl Display) -> Self {
@@ -146,6 +203,143 @@ pub(crate) fn mismatched_types<DB: Database, T: Type<DB>>(ty: &DB::TypeInfo) ->
     .into()
 }
 
+/// The statement an error occurred with, see [`Error::context()`].
+///
+/// Only the SQL and the number of parameters are captured, never the values of the parameters.
+#[derive(Debug, Clone)]
+pub struct ErrorContext {
+    sql: Arc<str>,
+    parameters: usize,
+    column: Option<ColumnContext>,
+}
+
+/// The column of a row which failed to decode, see [`ErrorContext::column()`].
+#[derive(Debug, Clone)]
+pub struct ColumnContext {
+    index: usize,
+    name: String,
+    type_name: String,
+    rust_type: &'static str,
+}
+
+impl ErrorContext {
+    pub(crate) fn new(sql: &str, parameters: usize) -> Self {
+        Self {
+            sql: sql.into(),
+            parameters,
+            column: None,
+        }
+    }
+
+    pub(crate) fn with_column(&self, column: ColumnContext) -> Self {
+        Self {
+            column: Some(column),
+            ..self.clone()
+        }
+    }
+
+    /// The SQL of the statement, as written.
+    pub fn sql(&self) -> &str {
+        &self.sql
+    }
+
+    /// The number of parameters bound to the statement.
+    pub fn parameters(&self) -> usize {
+        self.parameters
+    }
+
+    /// The column which failed to decode, for an error decoding a row returned by the statement.
+    pub fn column(&self) -> Option<&ColumnContext> {
+        self.column.as_ref()
+    }
+}
+
+impl Display for ErrorContext {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if let Some(column) = &self.column {
+            write!(
+                f,
+                "decoding column {} (`{}`) of SQL type `{}` as `{}`, ",
+                column.index, column.name, column.type_name, column.rust_type
+            )?;
+        }
+
+        write!(
+            f,
+            "in statement (parameters: {}): {}",
+            self.parameters, self.sql
+        )
+    }
+}
+
+impl ColumnContext {
+    pub(crate) fn new(index: usize, name: &str, type_name: &str, rust_type: &'static str) -> Self {
+        Self {
+            index,
+            name: name.to_owned(),
+            type_name: type_name.to_owned(),
+            rust_type,
+        }
+    }
+
+    /// The index of the column in the row.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// The name of the column.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The name of the SQL type of the column.
+    pub fn type_name(&self) -> &str {
+        &self.type_name
+    }
+
+    /// The name of the Rust type the column was decoded as.
+    pub fn rust_type(&self) -> &'static str {
+        self.rust_type
+    }
+}
+
+/// The kind of an error returned from the database, see [`DatabaseError::kind()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// The database, or a table of it, is locked by another connection, e.g. `SQLITE_BUSY` or
+    /// `SQLITE_LOCKED` for SQLite.
+    ///
+    /// Retrying the transaction may succeed once the lock is released.
+    Locked,
+
+    /// The statement was interrupted before it completed, e.g. `SQLITE_INTERRUPT` for SQLite.
+    Interrupted,
+
+    /// The snapshot to read from is no longer available, e.g. `SQLITE_ERROR_SNAPSHOT` for SQLite
+    /// once the WAL it was taken from has been checkpointed over.
+    SnapshotUnavailable,
+
+    /// A unique constraint, including a primary key, was violated, e.g. `23505` for PostgreSQL,
+    /// `ER_DUP_ENTRY` for MySQL or `SQLITE_CONSTRAINT_UNIQUE` for SQLite.
+    UniqueViolation,
+
+    /// A foreign key constraint was violated, e.g. `23503` for PostgreSQL,
+    /// `ER_NO_REFERENCED_ROW_2` for MySQL or `SQLITE_CONSTRAINT_FOREIGNKEY` for SQLite.
+    ForeignKeyViolation,
+
+    /// A `NOT NULL` constraint was violated, e.g. `23502` for PostgreSQL, `ER_BAD_NULL_ERROR`
+    /// for MySQL or `SQLITE_CONSTRAINT_NOTNULL` for SQLite.
+    NotNullViolation,
+
+    /// A `CHECK` constraint was violated, e.g. `23514` for PostgreSQL,
+    /// `ER_CHECK_CONSTRAINT_VIOLATED` for MySQL or `SQLITE_CONSTRAINT_CHECK` for SQLite.
+    CheckViolation,
+
+    /// Any other error.
+    Other,
+}
+
 /// An error that was returned from the database.
 pub trait DatabaseError: 'static + Send + Sync + StdError {
     /// The primary, human-readable error message.
@@ -174,10 +368,58 @@ pub trait DatabaseError: 'static + Send + Sync + StdError {
     /// If the error was caused by a conflict of a unique index, this will be the index name.
     ///
     /// ### Note
-    /// Currently only populated by the Postgres driver.
+    /// PostgreSQL reports it for every constraint. MySQL only reports it in the message, which is
+    /// parsed for the unique, foreign key and check constraints. SQLite only reports it in the
+    /// message of a `CHECK` constraint, or of a unique index on expressions.
     fn constraint(&self) -> Option<&str> {
         None
     }
+
+    /// Returns the name of the table the error is with, if applicable.
+    ///
+    /// ### Note
+    /// PostgreSQL reports it for the errors of a table. MySQL only reports it in the message, which
+    /// is parsed for the unique (as of MySQL 8.0.19) and foreign key constraints. SQLite only
+    /// reports it in the message of the unique and `NOT NULL` constraints.
+    fn table(&self) -> Option<&str> {
+        None
+    }
+
+    /// Returns the name of the column the error is with, if applicable.
+    ///
+    /// ### Note
+    /// PostgreSQL reports it for the errors of a column. MySQL only reports it in the message, which
+    /// is parsed for the `NOT NULL` and foreign key constraints. SQLite only reports it in the
+    /// message of the `NOT NULL` constraint, and of a unique constraint of a single column.
+    fn column(&self) -> Option<&str> {
+        None
+    }
+
+    /// Returns the name of the schema of the object the error is with, if applicable.
+    ///
+    /// ### Note
+    /// PostgreSQL reports it for the errors of an object. MySQL only reports it in the message,
+    /// which is parsed for the foreign key constraints, as the name of the database.
+    fn schema(&self) -> Option<&str> {
+        None
+    }
+
+    /// Returns the name of the routine of the database which reported the error, if any.
+    ///
+    /// ### Note
+    /// Only reported by PostgreSQL, as the routine of its source code, e.g. `ExecConstraints`.
+    fn routine(&self) -> Option<&str> {
+        None
+    }
+
+    /// Returns the kind of the error, to react to some errors without matching on their codes.
+    ///
+    /// ### Note
+    /// The violations of constraints are classified by the PostgreSQL, MySQL and SQLite drivers;
+    /// the other kinds only by the SQLite driver.
+    fn kind(&self) -> ErrorKind {
+        ErrorKind::Other
+    }
 }
 
 impl dyn DatabaseError {
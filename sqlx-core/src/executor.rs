@@ -165,8 +165,7 @@ pub trait Executor<'c>: Send + Debug + Sized {
     /// and results.
     ///
     /// This is used by compile-time verification in the query macros to
-    /// power their type inference.
-    #[doc(hidden)]
+    /// power their type inference. See [`describe`][crate::describe::describe].
     fn describe<'e, 'q: 'e>(
         self,
         sql: &'q str,
@@ -198,6 +197,14 @@ pub trait Execute<'q, DB: Database>: Send + Sized {
 
     /// Returns `true` if the statement should be cached.
     fn persistent(&self) -> bool;
+
+    /// Takes the error that keeps the query from being executed, if any, e.g. a dynamic fragment
+    /// of `query!()` with a value it wasn't checked with.
+    ///
+    /// The executor returns this error instead of executing the query.
+    fn take_error(&mut self) -> Option<Error> {
+        None
+    }
 }
 
 // NOTE: `Execute` is explicitly not implemented for String and &String to make it slightly more
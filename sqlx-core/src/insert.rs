@@ -0,0 +1,314 @@
+//! Bulk `INSERT` of the rows of an iterator, split into as few statements as the limit on bind
+//! parameters of the database allows. See [`insert()`].
+
+use std::cmp;
+use std::iter::Peekable;
+
+use crate::acquire::Acquire;
+use crate::arguments::IntoArguments;
+use crate::database::{Database, HasArguments};
+use crate::error::Error;
+use crate::executor::Executor;
+use crate::query::query_with;
+use crate::query_builder::{QueryBuilder, Separated};
+
+/// A row to insert with [`insert()`], usually implemented with `#[derive(InsertRow)]`.
+///
+/// The derive binds the fields of a struct in order, to the columns named like them; the
+/// `rename`, `rename_all` and `flatten` attributes work as with `#[derive(FromRow)]`:
+///
+/// ```rust,ignore
+/// #[derive(sqlx::InsertRow)]
+/// #[sqlx(rename_all = "camelCase")]
+/// struct User {
+///     id: i64,
+///     #[sqlx(rename = "name")]
+///     username: String,
+///     #[sqlx(flatten)]
+///     address: Address,
+/// }
+/// ```
+pub trait InsertRow<'args, DB: Database> {
+    /// The columns to insert into, in the order [`push_values`](Self::push_values) binds them.
+    fn columns() -> Vec<&'static str>;
+
+    /// Bind a value for each of the [`columns`](Self::columns).
+    fn push_values(self, values: &mut Separated<'_, 'args, DB, &'static str>);
+}
+
+/// Insert the `rows` into `table`, with as many rows per `INSERT` statement as the limit on
+/// bind parameters of the database allows, [`Database::MAX_BIND_PARAMETERS`].
+///
+/// The table and the columns of the rows are pushed to the statements as they are, so they must
+/// be quoted if need be, e.g. with [`Database::quote_identifier`].
+///
+/// ```rust,no_run
+/// # async fn example(pool: &sqlx::PgPool) -> sqlx::Result<()> {
+/// #[derive(sqlx::InsertRow)]
+/// struct User {
+///     id: i64,
+///     name: String,
+/// }
+///
+/// let users = (0..100_000).map(|id| User {
+///     id,
+///     name: format!("user {}", id),
+/// });
+///
+/// // 4 statements of up to 32767 rows, as PostgreSQL allows 65535 parameters
+/// let result = sqlx::insert("users", users)
+///     .suffix("ON CONFLICT (id) DO NOTHING")
+///     .transaction(true)
+///     .execute(pool)
+///     .await?;
+///
+/// println!("inserted {} users", result.rows_affected());
+/// # Ok(())
+/// # }
+/// ```
+pub fn insert<I>(table: &str, rows: I) -> Insert<'_, I::IntoIter>
+where
+    I: IntoIterator,
+{
+    Insert {
+        table,
+        rows: rows.into_iter(),
+        suffix: None,
+        max_bind_parameters: None,
+        max_rows: None,
+        transaction: false,
+    }
+}
+
+/// A bulk `INSERT`, see [`insert()`].
+#[must_use = "rows are only inserted once `.execute()` is called"]
+pub struct Insert<'a, I> {
+    table: &'a str,
+    rows: I,
+    suffix: Option<&'a str>,
+    max_bind_parameters: Option<usize>,
+    max_rows: Option<usize>,
+    transaction: bool,
+}
+
+impl<'a, I: Iterator> Insert<'a, I> {
+    /// Push `sql` after the `VALUES` of every statement, e.g. `ON CONFLICT (id) DO NOTHING`
+    /// or `ON DUPLICATE KEY UPDATE name = VALUES(name)`.
+    ///
+    /// It can't have bind parameters, as those would not be counted against the limit.
+    pub fn suffix(mut self, sql: &'a str) -> Self {
+        self.suffix = Some(sql);
+        self
+    }
+
+    /// Set the most bind parameters of a statement, instead of
+    /// [`Database::MAX_BIND_PARAMETERS`], e.g. 999 for SQLite before 3.32.0.
+    pub fn max_bind_parameters(mut self, max: usize) -> Self {
+        self.max_bind_parameters = Some(max);
+        self
+    }
+
+    /// Set the most rows of a statement, regardless of the number of bind parameters, e.g. 1000
+    /// for MSSQL, which allows no more in a `VALUES` clause.
+    pub fn max_rows(mut self, max: usize) -> Self {
+        self.max_rows = Some(max);
+        self
+    }
+
+    /// Sets whether to run the statements in a transaction, so that either all the rows or none
+    /// of them are inserted. Otherwise, the statements before the one which failed are kept.
+    pub fn transaction(mut self, transaction: bool) -> Self {
+        self.transaction = transaction;
+        self
+    }
+
+    /// Insert the rows, returning the results of all the statements combined, e.g. the total
+    /// number of rows affected.
+    ///
+    /// Returns an error before running any statement if a row has more columns than a
+    /// statement can have bind parameters.
+    pub async fn execute<'c, A, DB>(self, conn: A) -> Result<DB::QueryResult, Error>
+    where
+        A: Acquire<'c, Database = DB>,
+        DB: Database,
+        I::Item: for<'args> InsertRow<'args, DB>,
+        for<'args> <DB as HasArguments<'args>>::Arguments: IntoArguments<'args, DB>,
+        for<'e> &'e mut DB::Connection: Executor<'e, Database = DB>,
+    {
+        let columns = <I::Item as InsertRow<'_, DB>>::columns();
+        let max_bind_parameters = self.max_bind_parameters.unwrap_or(DB::MAX_BIND_PARAMETERS);
+
+        let rows_per_statement = rows_per_statement(columns.len(), max_bind_parameters)
+            .ok_or_else(|| {
+                Error::Configuration(
+                    format!(
+                        "can't insert rows of {} columns with at most {} bind parameters \
+                         per statement",
+                        columns.len(),
+                        max_bind_parameters
+                    )
+                    .into(),
+                )
+            })?;
+
+        let mut statements = Statements {
+            insert: format!("INSERT INTO {} ({}) ", self.table, columns.join(", ")),
+            rows: self.rows.peekable(),
+            rows_per_statement: match self.max_rows {
+                Some(max_rows) => cmp::min(rows_per_statement, cmp::max(max_rows, 1)),
+                None => rows_per_statement,
+            },
+            suffix: self.suffix,
+        };
+
+        if statements.rows.peek().is_none() {
+            return Ok(DB::QueryResult::default());
+        }
+
+        if self.transaction {
+            let mut tx = conn.begin().await?;
+            let result = statements.execute(&mut *tx).await?;
+            tx.commit().await?;
+
+            Ok(result)
+        } else {
+            let mut conn = conn.acquire().await?;
+            statements.execute(&mut *conn).await
+        }
+    }
+}
+
+struct Statements<'a, I: Iterator> {
+    insert: String,
+    rows: Peekable<I>,
+    rows_per_statement: usize,
+    suffix: Option<&'a str>,
+}
+
+impl<'a, I: Iterator> Statements<'a, I> {
+    async fn execute<DB>(&mut self, conn: &mut DB::Connection) -> Result<DB::QueryResult, Error>
+    where
+        DB: Database,
+        I::Item: for<'args> InsertRow<'args, DB>,
+        for<'args> <DB as HasArguments<'args>>::Arguments: IntoArguments<'args, DB>,
+        for<'e> &'e mut DB::Connection: Executor<'e, Database = DB>,
+    {
+        let mut result = DB::QueryResult::default();
+
+        while self.rows.peek().is_some() {
+            let mut query_builder = QueryBuilder::new(&self.insert);
+
+            let rows = self.rows.by_ref().take(self.rows_per_statement);
+            query_builder.push_values(rows, |mut values, row| row.push_values(&mut values));
+
+            if let Some(suffix) = self.suffix {
+                query_builder.push(" ").push(suffix);
+            }
+
+            // the arguments of some drivers must live exactly as long as the SQL is borrowed,
+            // which `.build()` borrows for less than they live
+            let (sql, arguments) = query_builder.into_parts();
+            let done = query_with(&sql, arguments).execute(&mut *conn).await?;
+
+            result.extend(Some(done));
+        }
+
+        Ok(result)
+    }
+}
+
+/// The most rows of `columns` columns a statement with at most `max_bind_parameters` bind
+/// parameters can insert, if any.
+fn rows_per_statement(columns: usize, max_bind_parameters: usize) -> Option<usize> {
+    match max_bind_parameters.checked_div(columns) {
+        Some(0) | None => None,
+        rows => rows,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // the statements for `rows` rows, with as many rows as the limit allows
+    fn statements(rows: usize, columns: usize, max_bind_parameters: usize) -> Vec<usize> {
+        let per_statement = rows_per_statement(columns, max_bind_parameters).unwrap();
+
+        (0..rows)
+            .collect::<Vec<_>>()
+            .chunks(per_statement)
+            .map(<[_]>::len)
+            .collect()
+    }
+
+    #[test]
+    fn it_fits_the_most_rows_within_the_limit() {
+        for max in 1..=512 {
+            for columns in 1..=max {
+                let rows = rows_per_statement(columns, max).unwrap();
+
+                assert!(rows * columns <= max, "{} columns, {} max", columns, max);
+                assert!(
+                    (rows + 1) * columns > max,
+                    "{} columns, {} max",
+                    columns,
+                    max
+                );
+            }
+
+            assert_eq!(rows_per_statement(max + 1, max), None);
+        }
+
+        assert_eq!(rows_per_statement(0, 65535), None);
+        assert_eq!(rows_per_statement(1, 0), None);
+    }
+
+    #[test]
+    #[cfg(feature = "all-databases")]
+    fn it_fits_the_most_rows_within_the_limits_of_each_database() {
+        use crate::mssql::Mssql;
+        use crate::mysql::MySql;
+        use crate::postgres::Postgres;
+        use crate::sqlite::Sqlite;
+
+        for (max, rows) in [
+            (Postgres::MAX_BIND_PARAMETERS, [65535, 32767, 21845, 16383]),
+            (MySql::MAX_BIND_PARAMETERS, [65535, 32767, 21845, 16383]),
+            (Sqlite::MAX_BIND_PARAMETERS, [32766, 16383, 10922, 8191]),
+            (Mssql::MAX_BIND_PARAMETERS, [2098, 1049, 699, 524]),
+        ] {
+            for (columns, rows) in (1..=4).zip(rows) {
+                assert_eq!(rows_per_statement(columns, max), Some(rows));
+            }
+
+            assert_eq!(rows_per_statement(max, max), Some(1));
+            assert_eq!(rows_per_statement(max + 1, max), None);
+        }
+    }
+
+    #[test]
+    fn it_splits_rows_into_statements() {
+        assert_eq!(statements(0, 3, 9), Vec::<usize>::new());
+        assert_eq!(statements(1, 3, 9), [1]);
+        assert_eq!(statements(3, 3, 9), [3]);
+        assert_eq!(statements(4, 3, 9), [3, 1]);
+        assert_eq!(statements(7, 3, 10), [3, 3, 1]);
+
+        for rows in 0..200 {
+            for columns in 1..=8 {
+                for max in columns..=64 {
+                    let statements = statements(rows, columns, max);
+
+                    assert_eq!(statements.iter().sum::<usize>(), rows);
+                    assert!(statements.iter().all(|&n| n > 0 && n * columns <= max));
+
+                    // only the last statement has fewer rows than the limit allows
+                    let full = max / columns;
+                    let partial = statements.iter().filter(|&&n| n < full).count();
+                    assert!(partial <= 1);
+                    assert_eq!(statements.len(), (rows + full - 1) / full);
+                }
+            }
+        }
+    }
+}
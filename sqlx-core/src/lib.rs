@@ -61,14 +61,18 @@ mod common;
 pub use either::Either;
 pub mod database;
 pub mod describe;
+pub mod dyn_connection;
 pub mod executor;
 pub mod from_row;
+pub mod insert;
 mod io;
-mod logger;
+pub mod logger;
 mod net;
+pub mod observer;
 pub mod query_as;
 pub mod query_builder;
 pub mod query_scalar;
+pub mod quote;
 pub mod row;
 pub mod type_info;
 pub mod value;
@@ -76,6 +80,10 @@ pub mod value;
 #[cfg(feature = "migrate")]
 pub mod migrate;
 
+#[cfg(feature = "serde_row")]
+#[cfg_attr(docsrs, doc(cfg(feature = "serde_row")))]
+pub mod serde_row;
+
 #[cfg(all(
     any(
         feature = "postgres",
@@ -1,11 +1,117 @@
+//! Logging the statements executed by connections.
+//!
+//! By default, statements are logged through the [`log`] crate by [`DefaultStatementLogger`].
+//! Implement [`StatementLogger`] and set it with
+//! [`ConnectOptions::log_with`](crate::connection::ConnectOptions::log_with) or
+//! [`PoolOptions::log_with`](crate::pool::PoolOptions::log_with) to log them otherwise, e.g. as
+//! structured records.
+
 use crate::connection::LogSettings;
+use crate::error::{Error, ErrorContext};
+use crate::observer::QueryObservation;
+use log::Level;
 #[cfg(feature = "sqlite")]
 use std::collections::HashSet;
-#[cfg(feature = "sqlite")]
-use std::fmt::Debug;
+use std::fmt::{self, Debug, Formatter};
 #[cfg(feature = "sqlite")]
 use std::hash::Hash;
-use std::time::Instant;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Logs the statements executed by connections.
+///
+/// Called synchronously once a statement finished, with the level set with
+/// [`log_statements`](crate::connection::ConnectOptions::log_statements) or
+/// [`log_slow_statements`](crate::connection::ConnectOptions::log_slow_statements), unless that
+/// level is `Off`.
+pub trait StatementLogger: Send + Sync + 'static {
+    fn log(&self, statement: &LoggedStatement<'_>);
+}
+
+impl Debug for dyn StatementLogger {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str("StatementLogger")
+    }
+}
+
+/// A statement to log, passed to [`StatementLogger::log`].
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct LoggedStatement<'a> {
+    /// The SQL of the statement, as it was executed.
+    pub sql: &'a str,
+
+    /// The level to log the statement at.
+    pub level: Level,
+
+    /// The time the statement took.
+    pub elapsed: Duration,
+
+    pub rows_affected: u64,
+
+    pub rows_returned: u64,
+
+    /// Whether the statement took longer than the duration set with
+    /// [`log_slow_statements`](crate::connection::ConnectOptions::log_slow_statements).
+    pub slow: bool,
+
+    /// Identifies the connection which executed the statement, unique within the process.
+    pub connection_id: u64,
+}
+
+/// Logs statements through the [`log`] crate, with the target `sqlx::query`, as the first
+/// words of their SQL followed by their counts of rows and duration, then their whole SQL,
+/// prettified, if it is longer.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DefaultStatementLogger;
+
+impl StatementLogger for DefaultStatementLogger {
+    fn log(&self, statement: &LoggedStatement<'_>) {
+        if !log::log_enabled!(target: "sqlx::query", statement.level) {
+            return;
+        }
+
+        let mut summary = parse_query_summary(statement.sql);
+
+        let sql = if summary != statement.sql {
+            summary.push_str(" …");
+            format!(
+                "\n\n{}\n",
+                sqlformat::format(
+                    statement.sql,
+                    &sqlformat::QueryParams::None,
+                    sqlformat::FormatOptions::default()
+                )
+            )
+        } else {
+            String::new()
+        };
+
+        log::logger().log(
+            &log::Record::builder()
+                .args(format_args!(
+                    "{}; rows affected: {}, rows returned: {}, elapsed: {:.3?}{}",
+                    summary,
+                    statement.rows_affected,
+                    statement.rows_returned,
+                    statement.elapsed,
+                    sql
+                ))
+                .level(statement.level)
+                .module_path_static(Some("sqlx::query"))
+                .target("sqlx::query")
+                .build(),
+        );
+    }
+}
+
+/// Returns a new identifier for a connection.
+pub(crate) fn next_connection_id() -> u64 {
+    static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+
+    NEXT_ID.fetch_add(1, Ordering::Relaxed)
+}
 
 pub(crate) struct QueryLogger<'q> {
     sql: &'q str,
@@ -13,16 +119,45 @@ pub(crate) struct QueryLogger<'q> {
     rows_affected: u64,
     start: Instant,
     settings: LogSettings,
+    context: Option<Arc<ErrorContext>>,
+    span: QuerySpan,
+    observation: Option<Arc<QueryObservation>>,
 }
 
 impl<'q> QueryLogger<'q> {
-    pub(crate) fn new(sql: &'q str, settings: LogSettings) -> Self {
+    pub(crate) fn new(
+        sql: &'q str,
+        parameters: usize,
+        persistent: bool,
+        settings: LogSettings,
+        span: QuerySpan,
+    ) -> Self {
         Self {
             sql,
             rows_returned: 0,
             rows_affected: 0,
             start: Instant::now(),
+            context: settings.error_context(sql, parameters),
+            observation: settings
+                .observer
+                .as_ref()
+                .map(|observer| observer.start(sql, persistent)),
             settings,
+            span,
+        }
+    }
+
+    /// The context to wrap the errors of the statement, and of the rows it returns, with.
+    pub(crate) fn context(&self) -> &Option<Arc<ErrorContext>> {
+        &self.context
+    }
+
+    /// Returns a handle to record the errors of the statement with.
+    pub(crate) fn errors(&self) -> QueryErrors {
+        QueryErrors {
+            context: self.context.clone(),
+            span: self.span.clone(),
+            observation: self.observation.clone(),
         }
     }
 
@@ -34,46 +169,46 @@ impl<'q> QueryLogger<'q> {
         self.rows_affected += n;
     }
 
+    /// Whether the statement took longer than the duration of slow statements so far.
+    pub(crate) fn is_slow(&self) -> bool {
+        self.start.elapsed() >= self.settings.slow_statements_duration
+    }
+
     pub(crate) fn finish(&self) {
         let elapsed = self.start.elapsed();
 
-        let lvl = if elapsed >= self.settings.slow_statements_duration {
+        self.span
+            .record_finish(self.rows_affected, self.rows_returned, elapsed);
+
+        if let Some(observation) = &self.observation {
+            // PostgreSQL also reports the rows returned by a `SELECT` as affected
+            let rows = if self.rows_returned > 0 {
+                self.rows_returned
+            } else {
+                self.rows_affected
+            };
+
+            observation.finish(rows, elapsed);
+        }
+
+        let slow = elapsed >= self.settings.slow_statements_duration;
+
+        let lvl = if slow {
             self.settings.slow_statements_level
         } else {
             self.settings.statements_level
         };
 
-        if let Some(lvl) = lvl
-            .to_level()
-            .filter(|lvl| log::log_enabled!(target: "sqlx::query", *lvl))
-        {
-            let mut summary = parse_query_summary(&self.sql);
-
-            let sql = if summary != self.sql {
-                summary.push_str(" …");
-                format!(
-                    "\n\n{}\n",
-                    sqlformat::format(
-                        &self.sql,
-                        &sqlformat::QueryParams::None,
-                        sqlformat::FormatOptions::default()
-                    )
-                )
-            } else {
-                String::new()
-            };
-
-            log::logger().log(
-                &log::Record::builder()
-                    .args(format_args!(
-                        "{}; rows affected: {}, rows returned: {}, elapsed: {:.3?}{}",
-                        summary, self.rows_affected, self.rows_returned, elapsed, sql
-                    ))
-                    .level(lvl)
-                    .module_path_static(Some("sqlx::query"))
-                    .target("sqlx::query")
-                    .build(),
-            );
+        if let Some(level) = lvl.to_level() {
+            self.settings.logger.log(&LoggedStatement {
+                sql: self.sql,
+                level,
+                elapsed,
+                rows_affected: self.rows_affected,
+                rows_returned: self.rows_returned,
+                slow,
+                connection_id: self.settings.connection_id,
+            });
         }
     }
 }
@@ -164,7 +299,157 @@ impl<'q, O: Debug + Hash + Eq, R: Debug + Hash + Eq, P: Debug> Drop
     }
 }
 
-fn parse_query_summary(sql: &str) -> String {
+/// Records the errors of a statement, for those returned outside of its [`QueryLogger`].
+#[derive(Clone)]
+pub(crate) struct QueryErrors {
+    context: Option<Arc<ErrorContext>>,
+    span: QuerySpan,
+    observation: Option<Arc<QueryObservation>>,
+}
+
+impl QueryErrors {
+    /// Records an error of the statement on its span and for its observer, and wraps it with its
+    /// context, if captured.
+    pub(crate) fn record(&self, error: Error) -> Error {
+        self.span.record_error(&error);
+
+        if let Some(observation) = &self.observation {
+            observation.fail();
+        }
+
+        error.with_context(&self.context)
+    }
+}
+
+/// The `tracing` span of a statement, following the OpenTelemetry conventions for databases.
+///
+/// The span is created when the statement is executed, as a child of the current span, so it must
+/// be created before handing the statement to another thread.
+#[derive(Clone)]
+pub(crate) struct QuerySpan {
+    #[cfg(feature = "tracing")]
+    span: tracing::Span,
+}
+
+impl QuerySpan {
+    #[cfg(not(feature = "tracing"))]
+    pub(crate) fn new(_sql: &str, _settings: &LogSettings) -> Self {
+        Self {}
+    }
+
+    #[cfg(feature = "tracing")]
+    pub(crate) fn new(sql: &str, settings: &LogSettings) -> Self {
+        use tracing::field::Empty;
+
+        let span = tracing::info_span!(
+            target: "sqlx::query",
+            "sqlx.query",
+            otel.name = Empty,
+            otel.kind = "client",
+            otel.status_code = Empty,
+            otel.status_message = Empty,
+            db.system = settings.tracing.system,
+            db.statement = Empty,
+            db.operation = Empty,
+            db.rows_affected = Empty,
+            db.rows_returned = Empty,
+            server.address = Empty,
+            server.port = Empty,
+            duration_ms = Empty,
+        );
+
+        if span.is_disabled() {
+            return Self { span };
+        }
+
+        if let Some(operation) = sql
+            .split_whitespace()
+            .next()
+            .filter(|word| word.chars().all(|c| c.is_ascii_alphabetic()))
+        {
+            let operation = operation.to_ascii_uppercase();
+
+            span.record("otel.name", &operation.as_str());
+            span.record("db.operation", &operation.as_str());
+        }
+
+        // the statement is only recorded when statements are logged
+        if settings.statements_level != log::LevelFilter::Off {
+            let statement = truncate(sql, settings.tracing.statement_limit);
+
+            span.record("db.statement", &&*statement);
+        }
+
+        if let Some(address) = &settings.tracing.server_address {
+            span.record("server.address", &&**address);
+        }
+
+        if let Some(port) = settings.tracing.server_port {
+            span.record("server.port", &port);
+        }
+
+        Self { span }
+    }
+
+    #[allow(unused_variables)]
+    fn record_finish(&self, rows_affected: u64, rows_returned: u64, elapsed: Duration) {
+        #[cfg(feature = "tracing")]
+        self.span
+            .record("db.rows_affected", &rows_affected)
+            .record("db.rows_returned", &rows_returned)
+            .record("duration_ms", &(elapsed.as_secs_f64() * 1000.0));
+    }
+
+    #[allow(unused_variables)]
+    fn record_error(&self, error: &Error) {
+        #[cfg(feature = "tracing")]
+        if !self.span.is_disabled() {
+            self.span
+                .record("otel.status_code", &"ERROR")
+                .record("otel.status_message", &error.to_string().as_str());
+        }
+    }
+}
+
+/// The settings of the spans of the statements of a connection.
+#[cfg(feature = "tracing")]
+#[derive(Clone, Debug)]
+pub(crate) struct TraceSettings {
+    pub(crate) statement_limit: usize,
+    pub(crate) system: &'static str,
+    pub(crate) server_address: Option<Arc<str>>,
+    pub(crate) server_port: Option<u16>,
+}
+
+#[cfg(feature = "tracing")]
+impl Default for TraceSettings {
+    fn default() -> Self {
+        Self {
+            statement_limit: 2048,
+            system: "other_sql",
+            server_address: None,
+            server_port: None,
+        }
+    }
+}
+
+/// Truncates `sql` to at most `limit` bytes, at a character boundary, marking that it was.
+#[cfg(feature = "tracing")]
+fn truncate(sql: &str, limit: usize) -> std::borrow::Cow<'_, str> {
+    if sql.len() <= limit {
+        return sql.into();
+    }
+
+    let mut end = limit;
+
+    while !sql.is_char_boundary(end) {
+        end -= 1;
+    }
+
+    format!("{}…", &sql[..end]).into()
+}
+
+pub(crate) fn parse_query_summary(sql: &str) -> String {
     // For now, just take the first 4 words
     sql.split_whitespace()
         .take(4)
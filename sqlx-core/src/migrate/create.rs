@@ -0,0 +1,97 @@
+use crate::error::Error;
+
+/// Options of a database created with
+/// [`MigrateDatabase::create_database_with()`](super::MigrateDatabase::create_database_with).
+///
+/// Each option only applies to some databases, and creating a database with an option which
+/// doesn't apply to it returns an [`Error::Configuration`].
+///
+/// ```rust
+/// # use sqlx_core::migrate::CreateDatabaseOptions;
+/// let options = CreateDatabaseOptions::new()
+///     .owner("app")
+///     .encoding("UTF8")
+///     .locale("en_US.UTF-8")
+///     .template("template0");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CreateDatabaseOptions {
+    pub(crate) owner: Option<String>,
+    pub(crate) encoding: Option<String>,
+    pub(crate) locale: Option<String>,
+    pub(crate) collation: Option<String>,
+    pub(crate) template: Option<String>,
+    pub(crate) charset: Option<String>,
+}
+
+impl CreateDatabaseOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The role which owns the database (PostgreSQL).
+    pub fn owner(mut self, owner: impl Into<String>) -> Self {
+        self.owner = Some(owner.into());
+        self
+    }
+
+    /// The character encoding of the database, e.g. `UTF8` (PostgreSQL), or `UTF-16le` (SQLite).
+    pub fn encoding(mut self, encoding: impl Into<String>) -> Self {
+        self.encoding = Some(encoding.into());
+        self
+    }
+
+    /// The locale of the database, for both the collation and the character classification,
+    /// e.g. `en_US.UTF-8` (PostgreSQL).
+    pub fn locale(mut self, locale: impl Into<String>) -> Self {
+        self.locale = Some(locale.into());
+        self
+    }
+
+    /// The collation of the database, overriding the one of the locale, e.g. `C` (PostgreSQL)
+    /// or `utf8mb4_bin` (MySQL).
+    pub fn collation(mut self, collation: impl Into<String>) -> Self {
+        self.collation = Some(collation.into());
+        self
+    }
+
+    /// The database the new one is a copy of, e.g. `template0` to create a database with another
+    /// encoding or locale than `template1`, the default (PostgreSQL).
+    pub fn template(mut self, template: impl Into<String>) -> Self {
+        self.template = Some(template.into());
+        self
+    }
+
+    /// The character set of the database, e.g. `utf8mb4` (MySQL).
+    pub fn charset(mut self, charset: impl Into<String>) -> Self {
+        self.charset = Some(charset.into());
+        self
+    }
+
+    /// Returns an error naming the first option which is set, but isn't one of `supported` by
+    /// the `database`.
+    pub(crate) fn check_supported(&self, database: &str, supported: &[&str]) -> Result<(), Error> {
+        let options = [
+            ("owner", &self.owner),
+            ("encoding", &self.encoding),
+            ("locale", &self.locale),
+            ("collation", &self.collation),
+            ("template", &self.template),
+            ("charset", &self.charset),
+        ];
+
+        for (name, value) in options {
+            if value.is_some() && !supported.contains(&name) {
+                return Err(Error::Configuration(
+                    format!(
+                        "the `{}` option doesn't apply to {} databases",
+                        name, database
+                    )
+                    .into(),
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -15,6 +15,14 @@ pub enum MigrateError {
     #[error("migration {0} was previously applied but has been modified")]
     VersionMismatch(i64),
 
+    /// Two migrations have the same version, e.g. in different directories of the source,
+    /// named by their paths.
+    #[error("migration {0} is defined twice, by {1} and {2}")]
+    DuplicateVersion(i64, String, String),
+
+    #[error("migration {0} is older than the latest applied migration; allow migrations out of order to apply it")]
+    OutOfOrder(i64),
+
     #[error("cannot mix reversible migrations with simple migrations. All migrations should be reversible or simple migrations")]
     InvalidMixReversibleAndSimple,
 
@@ -23,4 +31,40 @@ pub enum MigrateError {
         "migration {0} is partially applied; fix and remove row from `_sqlx_migrations` table"
     )]
     Dirty(i64),
+
+    #[error("cannot revert migrations without a down migration: {}", list_versions(.0))]
+    MissingDownMigrations(Vec<i64>),
+
+    /// The migrations lock is held by another migrator, which is named if it is known.
+    ///
+    /// See [`Migrator::set_locking()`](super::Migrator::set_locking).
+    #[error("could not acquire the migrations lock{}", lock_holder(.0))]
+    LockTimeout(Option<String>),
+
+    /// A seed failed after the migrations were applied, named by [`Seed::name`].
+    ///
+    /// [`Seed::name`]: super::Seed::name
+    #[error("while running seed {0}: {1}")]
+    Seed(String, #[source] Error),
+
+    /// An after-run hook failed after the migrations were applied.
+    ///
+    /// See [`Migrator::after_run()`](super::Migrator::after_run).
+    #[error("while running an after-run hook: {0}")]
+    AfterRun(#[source] BoxDynError),
+}
+
+fn lock_holder(holder: &Option<String>) -> String {
+    match holder {
+        Some(holder) => format!(", held by {}", holder),
+        None => String::new(),
+    }
+}
+
+fn list_versions(versions: &[i64]) -> String {
+    versions
+        .iter()
+        .map(|version| version.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
 }
@@ -0,0 +1,36 @@
+use std::time::Duration;
+
+use crate::migrate::{Migration, Seed};
+
+/// The progress of [`Migrator::run_with_progress()`](super::Migrator::run_with_progress), in the
+/// order it happens in.
+#[derive(Debug, Clone, Copy)]
+#[non_exhaustive]
+pub enum MigrationEvent<'a> {
+    /// The migrations lock was acquired, or isn't used.
+    ///
+    /// See [`Migrator::set_locking()`](super::Migrator::set_locking).
+    LockAcquired,
+
+    /// The migrations to apply were resolved against those applied to the database.
+    Resolved { pending: &'a [Migration] },
+
+    /// A migration is being applied.
+    MigrationStarted { migration: &'a Migration },
+
+    /// A migration was applied, or failed after `elapsed`. The `elapsed` time of an applied
+    /// migration is its `execution_time` recorded in the migrations table.
+    MigrationFinished {
+        migration: &'a Migration,
+        elapsed: Duration,
+        success: bool,
+    },
+
+    /// A seed was run.
+    ///
+    /// See [`Migrator::add_seed()`](super::Migrator::add_seed).
+    SeedFinished { seed: &'a Seed, elapsed: Duration },
+
+    /// Every pending migration was applied, and every seed and hook was run.
+    Completed { applied: usize, elapsed: Duration },
+}
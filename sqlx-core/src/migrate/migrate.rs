@@ -1,5 +1,8 @@
 use crate::error::Error;
-use crate::migrate::{AppliedMigration, MigrateError, Migration};
+use crate::migrate::{
+    AppliedMigration, AppliedRepeatableMigration, CreateDatabaseOptions, MigrateError, Migration,
+    Seed,
+};
 use futures_core::future::BoxFuture;
 use std::time::Duration;
 
@@ -8,6 +11,18 @@ pub trait MigrateDatabase {
     // uses a maintenance database depending on driver
     fn create_database(url: &str) -> BoxFuture<'_, Result<(), Error>>;
 
+    // create database in url, with options such as its owner or encoding
+    // errors if one of the options doesn't apply to the driver
+    fn create_database_with<'a>(
+        url: &'a str,
+        options: &'a CreateDatabaseOptions,
+    ) -> BoxFuture<'a, Result<(), Error>> {
+        Box::pin(async move {
+            options.check_supported("these", &[])?;
+            Self::create_database(url).await
+        })
+    }
+
     // check if the database in url exists
     // uses a maintenance database depending on driver
     fn database_exists(url: &str) -> BoxFuture<'_, Result<bool, Error>>;
@@ -15,58 +30,190 @@ pub trait MigrateDatabase {
     // drop database in url
     // uses a maintenance database depending on driver
     fn drop_database(url: &str) -> BoxFuture<'_, Result<(), Error>>;
+
+    // drop database in url, if it exists, terminating the other connections to it first
+    // a driver without connections to terminate drops it as `drop_database` does
+    fn force_drop_database(url: &str) -> BoxFuture<'_, Result<(), Error>> {
+        Self::drop_database(url)
+    }
 }
 
 // 'e = Executor
+// `table_name` is the migrations table, which may be qualified with a schema: `schema.table`
 pub trait Migrate {
     // ensure migrations table exists
     // will create or migrate it if needed
-    fn ensure_migrations_table(&mut self) -> BoxFuture<'_, Result<(), MigrateError>>;
+    fn ensure_migrations_table<'e>(
+        &'e mut self,
+        table_name: &'e str,
+    ) -> BoxFuture<'e, Result<(), MigrateError>>;
+
+    // check if the migrations table exists, without creating it
+    fn migrations_table_exists<'e>(
+        &'e mut self,
+        table_name: &'e str,
+    ) -> BoxFuture<'e, Result<bool, MigrateError>>;
 
     // Return the version on which the database is dirty or None otherwise.
     // "dirty" means there is a partially applied migration that failed.
-    fn dirty_version(&mut self) -> BoxFuture<'_, Result<Option<i64>, MigrateError>>;
+    fn dirty_version<'e>(
+        &'e mut self,
+        table_name: &'e str,
+    ) -> BoxFuture<'e, Result<Option<i64>, MigrateError>>;
 
     // Return the current version and if the database is "dirty".
     // "dirty" means there is a partially applied migration that failed.
     #[deprecated]
-    fn version(&mut self) -> BoxFuture<'_, Result<Option<(i64, bool)>, MigrateError>>;
+    fn version<'e>(
+        &'e mut self,
+        table_name: &'e str,
+    ) -> BoxFuture<'e, Result<Option<(i64, bool)>, MigrateError>>;
 
     // validate the migration
     // checks that it does exist on the database and that the checksum matches
     #[deprecated]
     fn validate<'e: 'm, 'm>(
         &'e mut self,
+        table_name: &'m str,
         migration: &'m Migration,
     ) -> BoxFuture<'m, Result<(), MigrateError>>;
 
     // Return the ordered list of applied migrations
-    fn list_applied_migrations(
-        &mut self,
-    ) -> BoxFuture<'_, Result<Vec<AppliedMigration>, MigrateError>>;
+    fn list_applied_migrations<'e>(
+        &'e mut self,
+        table_name: &'e str,
+    ) -> BoxFuture<'e, Result<Vec<AppliedMigration>, MigrateError>>;
+
+    // Return the list of repeatable migrations which were last applied successfully,
+    // ordered by description
+    // none were applied if their table doesn't exist, which isn't created here
+    fn list_applied_repeatable_migrations<'e>(
+        &'e mut self,
+        table_name: &'e str,
+    ) -> BoxFuture<'e, Result<Vec<AppliedRepeatableMigration>, MigrateError>>;
 
     // Should acquire a database lock so that only one migration process
     // can run at a time. [`Migrate`] will call this function before applying
     // any migrations.
-    fn lock(&mut self) -> BoxFuture<'_, Result<(), MigrateError>>;
+    // migrators of different tables don't wait on each other
+    // waits for the lock indefinitely without a timeout, and fails with
+    // `MigrateError::LockTimeout` once the timeout elapsed; a zero timeout never waits
+    fn lock<'e>(
+        &'e mut self,
+        table_name: &'e str,
+        timeout: Option<Duration>,
+    ) -> BoxFuture<'e, Result<(), MigrateError>>;
 
     // Should release the lock. [`Migrate`] will call this function after all
     // migrations have been run.
-    fn unlock(&mut self) -> BoxFuture<'_, Result<(), MigrateError>>;
+    fn unlock<'e>(&'e mut self, table_name: &'e str) -> BoxFuture<'e, Result<(), MigrateError>>;
 
     // run SQL from migration in a DDL transaction
     // insert new row to [_migrations] table on completion (success or failure)
+    // a repeatable migration is recorded in a table of its own, next to [_migrations], which is
+    // created if needed, and its row replaces the previous one
+    // the row records `applied_by`, or the database user without it, if the table has the
+    // columns for audits
     // returns the time taking to run the migration SQL
     fn apply<'e: 'm, 'm>(
         &'e mut self,
+        table_name: &'m str,
         migration: &'m Migration,
+        applied_by: Option<&'m str>,
     ) -> BoxFuture<'m, Result<Duration, MigrateError>>;
 
+    // replace the checksum recorded for the version of the migration with its checksum
+    fn set_checksum<'e: 'm, 'm>(
+        &'e mut self,
+        table_name: &'m str,
+        migration: &'m Migration,
+    ) -> BoxFuture<'m, Result<(), MigrateError>>;
+
+    // record the migration as applied, without running its SQL
+    fn mark_applied<'e: 'm, 'm>(
+        &'e mut self,
+        table_name: &'m str,
+        migration: &'m Migration,
+        applied_by: Option<&'m str>,
+    ) -> BoxFuture<'m, Result<(), MigrateError>>;
+
     // run a revert SQL from migration in a DDL transaction
     // deletes the row in [_migrations] table with specified migration version on completion (success or failure)
     // returns the time taking to run the migration SQL
     fn revert<'e: 'm, 'm>(
         &'e mut self,
+        table_name: &'m str,
         migration: &'m Migration,
     ) -> BoxFuture<'m, Result<Duration, MigrateError>>;
+
+    // run the SQL of a seed in a transaction, without recording it
+    // fails with `MigrateError::Seed` if the SQL fails
+    // returns the time taking to run the seed SQL
+    fn seed<'e: 'm, 'm>(
+        &'e mut self,
+        seed: &'m Seed,
+    ) -> BoxFuture<'m, Result<Duration, MigrateError>>;
+}
+
+/// Split the name of a migrations table into its schema, if any, and the name of the table.
+#[cfg(any(feature = "postgres", feature = "mysql", feature = "sqlite"))]
+pub(crate) fn split_table_name(table_name: &str) -> (Option<&str>, &str) {
+    match table_name.split_once('.') {
+        Some((schema, table)) => (Some(schema), table),
+        None => (None, table_name),
+    }
+}
+
+/// Quote the name of a migrations table, and its schema, with the identifier quote of the
+/// database, e.g. `"ops"."_sqlx_migrations"`.
+#[cfg(any(feature = "postgres", feature = "mysql", feature = "sqlite"))]
+pub(crate) fn quote_table_name(table_name: &str, quote: char) -> String {
+    let escaped = format!("{}{}", quote, quote);
+    let quote_ident = |ident: &str| format!("{}{}{}", quote, ident.replace(quote, &escaped), quote);
+
+    match split_table_name(table_name) {
+        (Some(schema), table) => format!("{}.{}", quote_ident(schema), quote_ident(table)),
+        (None, table) => quote_ident(table),
+    }
+}
+
+/// The name of the table recording the repeatable migrations of a migrations table, next to it,
+/// e.g. `ops._sqlx_migrations_repeatable` for `ops._sqlx_migrations`.
+///
+/// It is only created once a repeatable migration is applied, so that the migrations table
+/// keeps `version` as its primary key, and nothing changes for those who don't use them.
+#[cfg(any(feature = "postgres", feature = "mysql", feature = "sqlite"))]
+pub(crate) fn repeatable_table_name(table_name: &str) -> String {
+    format!("{}_repeatable", table_name)
+}
+
+/// The version of SQLx and the host name recorded with an applied migration.
+#[cfg(any(feature = "postgres", feature = "mysql", feature = "sqlite"))]
+pub(crate) fn applied_on() -> (&'static str, String) {
+    (env!("CARGO_PKG_VERSION"), whoami::hostname())
+}
+
+/// Warn that the columns for audits couldn't be added to a migrations table created before
+/// them, e.g. for lack of permission. Migrations are then recorded without them.
+#[cfg(any(feature = "postgres", feature = "mysql", feature = "sqlite"))]
+pub(crate) fn warn_missing_audit_columns(table_name: &str, error: &dyn std::fmt::Display) {
+    log::warn!(
+        "could not add the columns recording who applied migrations to {}, so they are recorded without them: {}",
+        table_name,
+        error
+    );
+}
+
+#[cfg(any(feature = "postgres", feature = "mysql", feature = "sqlite"))]
+#[test]
+fn test_quote_table_name() {
+    assert_eq!(
+        quote_table_name("_sqlx_migrations", '"'),
+        "\"_sqlx_migrations\""
+    );
+    assert_eq!(
+        quote_table_name("ops._sqlx_migrations", '`'),
+        "`ops`.`_sqlx_migrations`"
+    );
+    assert_eq!(quote_table_name("my\"table", '"'), "\"my\"\"table\"");
 }
@@ -2,8 +2,13 @@ use std::borrow::Cow;
 
 use sha2::{Digest, Sha384};
 
+use crate::error::BoxDynError;
+
 use super::MigrationType;
 
+/// The first line of a migration which is run outside of a transaction.
+pub const NO_TRANSACTION: &str = "-- sqlx:no-transaction";
+
 #[derive(Debug, Clone)]
 pub struct Migration {
     pub version: i64,
@@ -11,6 +16,23 @@ pub struct Migration {
     pub migration_type: MigrationType,
     pub sql: Cow<'static, str>,
     pub checksum: Cow<'static, [u8]>,
+
+    /// Whether the migration starts with [`NO_TRANSACTION`], for statements which can't be run
+    /// within a transaction, such as `CREATE INDEX CONCURRENTLY` in PostgreSQL or `VACUUM` in
+    /// SQLite.
+    ///
+    /// Each statement of such a migration is then run on its own, and the migration is only
+    /// recorded once all of them succeeded. If one fails, those before it stay applied while the
+    /// migration isn't recorded, so it's run again from the start once fixed: write it so that
+    /// can be done, e.g. with `IF NOT EXISTS`, and drop what a failed statement left behind,
+    /// such as the invalid index of a failed `CREATE INDEX CONCURRENTLY`.
+    ///
+    /// MySQL never runs migrations within a transaction, so this makes no difference there.
+    pub no_tx: bool,
+
+    /// The file the migration was resolved from, which errors name, or `None` if it wasn't
+    /// resolved from a file.
+    pub path: Option<Cow<'static, str>>,
 }
 
 impl Migration {
@@ -21,6 +43,7 @@ impl Migration {
         sql: Cow<'static, str>,
     ) -> Self {
         let checksum = Cow::Owned(Vec::from(Sha384::digest(sql.as_bytes()).as_slice()));
+        let no_tx = sql.lines().next().map(str::trim_end) == Some(NO_TRANSACTION);
 
         Migration {
             version,
@@ -28,12 +51,94 @@ impl Migration {
             migration_type,
             sql,
             checksum,
+            no_tx,
+            path: None,
         }
     }
+
+    /// Build the migration of a file, named like those of a migrations directory:
+    /// `<VERSION>_<DESCRIPTION>.sql`, or `r_<DESCRIPTION>.sql` for a repeatable migration.
+    ///
+    /// This is how a directory resolves its migrations, for
+    /// [`MigrationSource`](super::MigrationSource)s loading files from elsewhere. It returns
+    /// `Ok(None)` if the file isn't named like a migration, which a directory ignores, and fails
+    /// if its version isn't a number.
+    pub fn from_file_name(
+        file_name: &str,
+        sql: Cow<'static, str>,
+    ) -> Result<Option<Self>, BoxDynError> {
+        let migration =
+            parse_file_name(file_name)?.map(|(version, migration_type, description)| {
+                Migration::new(version, Cow::Owned(description), migration_type, sql)
+            });
+
+        Ok(migration)
+    }
+}
+
+/// Parse the version, type and description of a migration from its file name, or `None` if
+/// the file isn't a migration.
+#[doc(hidden)]
+pub fn parse_file_name(
+    file_name: &str,
+) -> Result<Option<(i64, MigrationType, String)>, BoxDynError> {
+    let parts = file_name.splitn(2, '_').collect::<Vec<_>>();
+
+    if parts.len() != 2 || !parts[1].ends_with(".sql") {
+        // not of the format: <VERSION>_<DESCRIPTION>.sql
+        return Ok(None);
+    }
+
+    let (version, migration_type) = if parts[0].eq_ignore_ascii_case("r") {
+        (0, MigrationType::Repeatable)
+    } else {
+        let version: i64 = parts[0].parse()?;
+        (version, MigrationType::from_filename(parts[1]))
+    };
+
+    // remove the `.sql` and replace `_` with ` `
+    let description = parts[1]
+        .trim_end_matches(migration_type.suffix())
+        .replace('_', " ");
+
+    Ok(Some((version, migration_type, description)))
 }
 
 #[derive(Debug, Clone)]
 pub struct AppliedMigration {
     pub version: i64,
     pub checksum: Cow<'static, [u8]>,
+
+    /// Who applied the migration: the name set with
+    /// [`Migrator::set_applied_by()`](super::Migrator::set_applied_by), or the database user.
+    ///
+    /// This and the other columns for audits are `None` for migrations recorded before they
+    /// were added to the migrations table, or if they couldn't be added to it.
+    pub applied_by: Option<String>,
+
+    /// The version of SQLx which applied the migration.
+    pub sqlx_version: Option<String>,
+
+    /// The host name of the machine which applied the migration.
+    pub hostname: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct AppliedRepeatableMigration {
+    pub description: Cow<'static, str>,
+    pub checksum: Cow<'static, [u8]>,
+}
+
+/// An applied migration whose recorded checksum differs from the one of the migration, to be
+/// replaced by [`Migrator::repair()`](super::Migrator::repair).
+#[derive(Debug, Clone)]
+pub struct ChecksumRepair {
+    pub version: i64,
+    pub description: Cow<'static, str>,
+
+    /// The checksum recorded when the migration was applied.
+    pub applied_checksum: Cow<'static, [u8]>,
+
+    /// The checksum of the migration as it is now.
+    pub checksum: Cow<'static, [u8]>,
 }
@@ -11,6 +11,11 @@ pub enum MigrationType {
     /// ReversibleDown migrations represents the  delete or downgrade part of a reversible migrations
     /// It is expected the every migration of this type will have a corresponding up file
     ReversibleDown,
+
+    /// Repeatable migrations have no version (their `version` is 0), and are applied again after
+    /// the versioned migrations whenever they change. They are stored in files named
+    /// `r_<DESCRIPTION>.sql`, and identified by their description
+    Repeatable,
 }
 
 impl MigrationType {
@@ -29,6 +34,7 @@ impl MigrationType {
             MigrationType::Simple => false,
             MigrationType::ReversibleUp => true,
             MigrationType::ReversibleDown => true,
+            MigrationType::Repeatable => false,
         }
     }
 
@@ -37,14 +43,20 @@ impl MigrationType {
             MigrationType::Simple => false,
             MigrationType::ReversibleUp => false,
             MigrationType::ReversibleDown => true,
+            MigrationType::Repeatable => false,
         }
     }
 
+    pub fn is_repeatable(&self) -> bool {
+        matches!(self, MigrationType::Repeatable)
+    }
+
     pub fn label(&self) -> &'static str {
         match self {
             MigrationType::Simple => "migrate",
             MigrationType::ReversibleUp => "migrate",
             MigrationType::ReversibleDown => "revert",
+            MigrationType::Repeatable => "repeat",
         }
     }
 
@@ -53,6 +65,7 @@ impl MigrationType {
             MigrationType::Simple => ".sql",
             MigrationType::ReversibleUp => ".up.sql",
             MigrationType::ReversibleDown => ".down.sql",
+            MigrationType::Repeatable => ".sql",
         }
     }
 
@@ -61,6 +74,7 @@ impl MigrationType {
             MigrationType::Simple => "-- Add migration script here\n",
             MigrationType::ReversibleUp => "-- Add up migration script here\n",
             MigrationType::ReversibleDown => "-- Add down migration script here\n",
+            MigrationType::Repeatable => "-- Add repeatable migration script here\n",
         }
     }
 }
@@ -1,14 +1,76 @@
 use crate::acquire::Acquire;
-use crate::migrate::{AppliedMigration, Migrate, MigrateError, Migration, MigrationSource};
+use crate::error::BoxDynError;
+use crate::migrate::{
+    sort_migrations, AppliedMigration, AppliedRepeatableMigration, ChecksumRepair, Drift,
+    DriftKind, Migrate, MigrateError, Migration, MigrationEvent, MigrationPlan, MigrationProblem,
+    MigrationSource, Seed, VerifyReport,
+};
+use futures_core::future::BoxFuture;
+use std::any::{type_name, Any};
 use std::borrow::Cow;
 use std::collections::{HashMap, HashSet};
+use std::fmt::{self, Debug, Formatter};
 use std::ops::Deref;
 use std::slice;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// The table applied migrations are recorded in, unless set with
+/// [`Migrator::set_table_name()`].
+pub const DEFAULT_MIGRATIONS_TABLE: &str = "_sqlx_migrations";
+
+/// How a [`Migrator`] locks the database, so that only one migrates it at a time.
+///
+/// Set with [`Migrator::set_locking()`]. SQLite has no lock to wait for, as it runs the
+/// transactions of migrations one at a time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Locking {
+    /// Wait for the lock to be released, failing with [`MigrateError::LockTimeout`] once
+    /// `timeout` elapsed, or waiting indefinitely without one. This is the default.
+    Wait { timeout: Option<Duration> },
+
+    /// Fail with [`MigrateError::LockTimeout`] at once if the lock is held.
+    Fail,
+
+    /// Don't lock the database, when only one migrator runs at a time.
+    None,
+}
+
+impl Default for Locking {
+    fn default() -> Self {
+        Locking::Wait { timeout: None }
+    }
+}
+
+type AfterRunFn<C> =
+    dyn for<'c> Fn(&'c mut C) -> BoxFuture<'c, Result<(), BoxDynError>> + Send + Sync;
+
+/// A hook run after migrations, added with [`Migrator::after_run()`].
+#[derive(Clone)]
+pub struct AfterRun {
+    // a `Box<AfterRunFn<C>>` for the connection `C` the hook takes
+    hook: Arc<dyn Any + Send + Sync>,
+    connection: &'static str,
+}
+
+impl Debug for AfterRun {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AfterRun")
+            .field("connection", &self.connection)
+            .finish_non_exhaustive()
+    }
+}
 
 #[derive(Debug)]
 pub struct Migrator {
     pub migrations: Cow<'static, [Migration]>,
     pub ignore_missing: bool,
+    pub allow_out_of_order: bool,
+    pub table_name: Cow<'static, str>,
+    pub locking: Locking,
+    pub applied_by: Option<Cow<'static, str>>,
+    pub seeds: Cow<'static, [Seed]>,
+    pub after_run: Cow<'static, [AfterRun]>,
 }
 
 fn validate_applied_migrations(
@@ -19,7 +81,11 @@ fn validate_applied_migrations(
         return Ok(());
     }
 
-    let migrations: HashSet<_> = migrator.iter().map(|m| m.version).collect();
+    let migrations: HashSet<_> = migrator
+        .iter()
+        .filter(|m| !m.migration_type.is_repeatable())
+        .map(|m| m.version)
+        .collect();
 
     for applied_migration in applied_migrations {
         if !migrations.contains(&applied_migration.version) {
@@ -30,6 +96,37 @@ fn validate_applied_migrations(
     Ok(())
 }
 
+fn check_duplicate_versions(migrations: &[Migration]) -> Result<(), MigrateError> {
+    let name = |m: &Migration| match &m.path {
+        Some(path) => path.to_string(),
+        None => m.description.to_string(),
+    };
+
+    // the up and down migrations of a reversible migration share their version
+    let mut seen = HashMap::new();
+
+    for migration in migrations {
+        if migration.migration_type.is_repeatable() {
+            continue;
+        }
+
+        let key = (
+            migration.version,
+            migration.migration_type.is_down_migration(),
+        );
+
+        if let Some(other) = seen.insert(key, migration) {
+            return Err(MigrateError::DuplicateVersion(
+                migration.version,
+                name(other),
+                name(migration),
+            ));
+        }
+    }
+
+    Ok(())
+}
+
 impl Migrator {
     /// Creates a new instance with the given source.
     ///
@@ -44,18 +141,65 @@ impl Migrator {
     ///
     /// // Read migrations from a local folder: ./migrations
     /// let m = Migrator::new(Path::new("./migrations")).await?;
+    ///
+    /// // Read migrations from several local folders, interleaved by version
+    /// let m = Migrator::new(&[Path::new("./migrations/common"), Path::new("./migrations/pg")][..])
+    ///     .await?;
     /// # Ok(())
     /// # })
     /// # }
     /// ```
     /// See [MigrationSource] for details on structure of the `./migrations` directory.
+    ///
+    /// Fails with [`MigrateError::DuplicateVersion`] if two migrations have the same version.
     pub async fn new<'s, S>(source: S) -> Result<Self, MigrateError>
     where
         S: MigrationSource<'s>,
     {
+        Self::from_iter(source.resolve().await.map_err(MigrateError::Source)?)
+    }
+
+    /// Creates a new instance with the given migrations, in any order, e.g. embedded migrations
+    /// combined with those of a custom [MigrationSource].
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use sqlx_core::migrate::MigrateError;
+    /// # fn main() -> Result<(), MigrateError> {
+    /// # use sqlx_core::migrate::{Migration, Migrator};
+    /// # let embedded: Migrator = Migrator::from_iter([])?;
+    /// let tenant = Migration::from_file_name(
+    ///     "20220801120000_tenant_settings.sql",
+    ///     "CREATE TABLE tenant_settings (key TEXT PRIMARY KEY, value TEXT);".into(),
+    /// )
+    /// .map_err(MigrateError::Source)?
+    /// .unwrap();
+    ///
+    /// let m = Migrator::from_iter(embedded.iter().cloned().chain([tenant]))?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// Fails with [`MigrateError::DuplicateVersion`] if two migrations have the same version.
+    // not `FromIterator`, which can't fail
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_iter(
+        migrations: impl IntoIterator<Item = Migration>,
+    ) -> Result<Self, MigrateError> {
+        let mut migrations: Vec<_> = migrations.into_iter().collect();
+        sort_migrations(&mut migrations);
+        check_duplicate_versions(&migrations)?;
+
         Ok(Self {
-            migrations: Cow::Owned(source.resolve().await.map_err(MigrateError::Source)?),
+            migrations: Cow::Owned(migrations),
             ignore_missing: false,
+            allow_out_of_order: false,
+            table_name: Cow::Borrowed(DEFAULT_MIGRATIONS_TABLE),
+            locking: Locking::Wait { timeout: None },
+            applied_by: None,
+            seeds: Cow::Borrowed(&[]),
+            after_run: Cow::Borrowed(&[]),
         })
     }
 
@@ -65,13 +209,180 @@ impl Migrator {
         self
     }
 
+    /// Specify whether pending migrations older than the latest applied migration should be
+    /// applied, rather than failing with [`MigrateError::OutOfOrder`].
+    ///
+    /// This happens when a branch with an older migration is merged after a newer one was
+    /// deployed. The migrations are applied in order of version, and recorded as usual.
+    pub fn set_allow_out_of_order(&mut self, allow_out_of_order: bool) -> &Self {
+        self.allow_out_of_order = allow_out_of_order;
+        self
+    }
+
+    /// Specify the table applied migrations are recorded in, instead of
+    /// [`DEFAULT_MIGRATIONS_TABLE`], so that several applications can migrate the same database.
+    ///
+    /// The name may be qualified with a schema, e.g. `ops._sqlx_migrations`, which is created
+    /// if it doesn't exist on Postgres; on MySQL the schema is a database, and on SQLite an
+    /// attached database. Each part is quoted, so it is case-sensitive.
+    ///
+    /// Migrators of different tables don't wait on each other's lock.
+    pub fn set_table_name(&mut self, table_name: impl Into<Cow<'static, str>>) -> &Self {
+        self.table_name = table_name.into();
+        self
+    }
+
+    /// Specify how the database is locked while it is migrated.
+    ///
+    /// By default, a migrator waits for the lock indefinitely, e.g. for one which crashed while
+    /// holding it to be disconnected. A waiting migrator logs that it is waiting.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use sqlx_core::migrate::MigrateError;
+    /// # fn main() -> Result<(), MigrateError> {
+    /// # sqlx_rt::block_on(async move {
+    /// # use sqlx_core::migrate::{Locking, Migrator};
+    /// use std::time::Duration;
+    ///
+    /// let mut m = Migrator::new(std::path::Path::new("./migrations")).await?;
+    /// m.set_locking(Locking::Wait {
+    ///     timeout: Some(Duration::from_secs(30)),
+    /// });
+    /// # Ok(())
+    /// # })
+    /// # }
+    /// ```
+    pub fn set_locking(&mut self, locking: Locking) -> &Self {
+        self.locking = locking;
+        self
+    }
+
+    /// Specify who is recorded as having applied migrations, rather than the database user.
+    ///
+    /// Without it, the name is taken from the `SQLX_MIGRATE_APPLIED_BY` environment variable if
+    /// it is set. SQLite has no users, so it records who applied migrations only if either is
+    /// set.
+    pub fn set_applied_by(&mut self, applied_by: impl Into<Cow<'static, str>>) -> &Self {
+        self.applied_by = Some(applied_by.into());
+        self
+    }
+
+    /// Get who is recorded as having applied migrations, or `None` for the database user.
+    ///
+    /// See [`set_applied_by()`](Self::set_applied_by).
+    pub fn applied_by(&self) -> Option<Cow<'_, str>> {
+        match &self.applied_by {
+            Some(applied_by) => Some(Cow::Borrowed(applied_by)),
+            None => std::env::var("SQLX_MIGRATE_APPLIED_BY")
+                .ok()
+                .map(Cow::Owned),
+        }
+    }
+
+    /// Add a seed, run after the migrations by every [`run()`](Self::run) which succeeds, after
+    /// those added before it.
+    ///
+    /// A seed runs in a transaction of its own and isn't recorded in the migrations table, so it
+    /// must be idempotent. A seed which fails fails the run with [`MigrateError::Seed`], after
+    /// the migrations were applied.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use sqlx_core::migrate::MigrateError;
+    /// # fn main() -> Result<(), MigrateError> {
+    /// # sqlx_rt::block_on(async move {
+    /// # use sqlx_core::migrate::{Migrator, Seed};
+    /// let mut m = Migrator::new(std::path::Path::new("./migrations")).await?;
+    ///
+    /// for seed in Seed::from_path("./seeds").await.map_err(MigrateError::Source)? {
+    ///     m.add_seed(seed);
+    /// }
+    /// # Ok(())
+    /// # })
+    /// # }
+    /// ```
+    pub fn add_seed(&mut self, seed: Seed) -> &Self {
+        self.seeds.to_mut().push(seed);
+        self
+    }
+
+    /// Add a hook run after the migrations and seeds by every [`run()`](Self::run) which
+    /// succeeds, e.g. a query validating the data, after those added before it.
+    ///
+    /// The hook takes the connection the migrations were run on, e.g. a `SqliteConnection`
+    /// for a `SqlitePool`, and a run on any other connection fails with
+    /// [`MigrateError::AfterRun`], as does a hook which fails.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use sqlx_core::migrate::MigrateError;
+    /// # fn main() -> Result<(), MigrateError> {
+    /// # sqlx_rt::block_on(async move {
+    /// # use sqlx_core::migrate::Migrator;
+    /// # use sqlx_core::sqlite::SqliteConnection;
+    /// use sqlx_core::query_scalar::query_scalar;
+    ///
+    /// let mut m = Migrator::new(std::path::Path::new("./migrations")).await?;
+    /// m.after_run(|conn: &mut SqliteConnection| {
+    ///     Box::pin(async move {
+    ///         let countries: i64 = query_scalar("SELECT COUNT(*) FROM countries")
+    ///             .fetch_one(conn)
+    ///             .await?;
+    ///
+    ///         if countries == 0 {
+    ///             return Err("no countries were seeded".into());
+    ///         }
+    ///
+    ///         Ok(())
+    ///     })
+    /// });
+    /// # Ok(())
+    /// # })
+    /// # }
+    /// ```
+    pub fn after_run<C, F>(&mut self, hook: F) -> &Self
+    where
+        C: Migrate + 'static,
+        F: for<'c> Fn(&'c mut C) -> BoxFuture<'c, Result<(), BoxDynError>> + Send + Sync + 'static,
+    {
+        let hook: Box<AfterRunFn<C>> = Box::new(hook);
+
+        self.after_run.to_mut().push(AfterRun {
+            hook: Arc::new(hook),
+            connection: type_name::<C>(),
+        });
+
+        self
+    }
+
     /// Get an iterator over all known migrations.
     pub fn iter(&self) -> slice::Iter<'_, Migration> {
         self.migrations.iter()
     }
 
-    /// Run any pending migrations against the database; and, validate previously applied migrations
-    /// against the current migration source to detect accidental changes in previously-applied migrations.
+    async fn lock<C: Migrate>(&self, conn: &mut C) -> Result<(), MigrateError> {
+        match self.locking {
+            Locking::Wait { timeout } => conn.lock(&self.table_name, timeout).await,
+            Locking::Fail => conn.lock(&self.table_name, Some(Duration::ZERO)).await,
+            Locking::None => Ok(()),
+        }
+    }
+
+    async fn unlock<C: Migrate>(&self, conn: &mut C) -> Result<(), MigrateError> {
+        match self.locking {
+            Locking::None => Ok(()),
+            _ => conn.unlock(&self.table_name).await,
+        }
+    }
+
+    /// Compute what [`run()`](Self::run) would do against the database without applying any
+    /// migration: the applied and pending migrations, and the problems with the applied ones.
+    ///
+    /// Nothing is created in the database: without a migrations table, no migration is applied.
     ///
     /// # Examples
     ///
@@ -83,70 +394,428 @@ impl Migrator {
     /// # use sqlx_core::migrate::Migrator;
     /// let m = Migrator::new(std::path::Path::new("./migrations")).await?;
     /// let pool = sqlx_core::sqlite::SqlitePoolOptions::new().connect("sqlite::memory:").await?;
-    /// m.run(&pool).await
+    /// let plan = m.plan(&pool).await?;
+    ///
+    /// for migration in &plan.pending {
+    ///     println!("would apply {} {}", migration.version, migration.description);
+    /// }
+    ///
+    /// plan.check()
     /// #     })
     /// # }
     /// ```
-    pub async fn run<'a, A>(&self, migrator: A) -> Result<(), MigrateError>
+    pub async fn plan<'a, A>(&self, migrator: A) -> Result<MigrationPlan, MigrateError>
     where
         A: Acquire<'a>,
         <A::Connection as Deref>::Target: Migrate,
     {
         let mut conn = migrator.acquire().await?;
-        self.run_direct(&mut *conn).await
+        self.plan_direct(&mut *conn).await
+    }
+
+    /// Compare the migrations of the database to the resolved migrations without applying
+    /// anything, e.g. in CI to catch a database which was changed by hand.
+    ///
+    /// Beyond the problems [`plan()`](Self::plan) finds, this reports the pending migrations,
+    /// and tells the applied migrations newer than every resolved one apart from the other
+    /// missing ones. Missing migrations and migrations out of order are reported unless they are
+    /// ignored or allowed.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use sqlx_core::migrate::MigrateError;
+    /// # #[cfg(feature = "sqlite")]
+    /// # fn main() -> Result<(), MigrateError> {
+    /// #     sqlx_rt::block_on(async move {
+    /// # use sqlx_core::migrate::{DriftKind, Migrator};
+    /// let m = Migrator::new(std::path::Path::new("./migrations")).await?;
+    /// let pool = sqlx_core::sqlite::SqlitePoolOptions::new().connect("sqlite::memory:").await?;
+    /// let report = m.verify(&pool).await?;
+    ///
+    /// // pending migrations are fine, modified ones aren't
+    /// assert!(!report.contains(DriftKind::Modified));
+    /// # Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn verify<'a, A>(&self, migrator: A) -> Result<VerifyReport, MigrateError>
+    where
+        A: Acquire<'a>,
+        <A::Connection as Deref>::Target: Migrate,
+    {
+        let plan = self.plan(migrator).await?;
+
+        Ok(self.verify_plan(&plan))
+    }
+
+    fn verify_plan(&self, plan: &MigrationPlan) -> VerifyReport {
+        let latest = self
+            .iter()
+            .filter(|m| !m.migration_type.is_repeatable())
+            .map(|m| m.version)
+            .max();
+        let is_newer = |version| match latest {
+            Some(latest) => version > latest,
+            None => true,
+        };
+
+        let description = |version| {
+            self.iter()
+                .find(|m| {
+                    m.version == version
+                        && !m.migration_type.is_repeatable()
+                        && !m.migration_type.is_down_migration()
+                })
+                .map(|m| m.description.clone())
+        };
+
+        let mut drift = Vec::new();
+        let mut out_of_order = HashSet::new();
+
+        for problem in &plan.problems {
+            let (kind, version) = match *problem {
+                MigrationProblem::Dirty(version) => (DriftKind::Dirty, version),
+                MigrationProblem::MissingLocally(version) if is_newer(version) => {
+                    (DriftKind::Newer, version)
+                }
+                MigrationProblem::MissingLocally(version) => (DriftKind::Missing, version),
+                MigrationProblem::ChecksumMismatch(version) => (DriftKind::Modified, version),
+                MigrationProblem::OutOfOrder(version) => {
+                    out_of_order.insert(version);
+                    (DriftKind::OutOfOrder, version)
+                }
+            };
+
+            drift.push(Drift {
+                kind,
+                version: Some(version),
+                description: description(version),
+            });
+        }
+
+        for migration in &plan.pending {
+            if migration.migration_type.is_repeatable() {
+                drift.push(Drift {
+                    kind: DriftKind::Pending,
+                    version: None,
+                    description: Some(migration.description.clone()),
+                });
+            } else if !out_of_order.contains(&migration.version) {
+                drift.push(Drift {
+                    kind: DriftKind::Pending,
+                    version: Some(migration.version),
+                    description: Some(migration.description.clone()),
+                });
+            }
+        }
+
+        VerifyReport { drift }
     }
 
-    // Getting around the annoying "implementation of `Acquire` is not general enough" error
     #[doc(hidden)]
-    pub async fn run_direct<C>(&self, conn: &mut C) -> Result<(), MigrateError>
+    pub async fn plan_direct<C>(&self, conn: &mut C) -> Result<MigrationPlan, MigrateError>
     where
         C: Migrate,
     {
-        // lock the database for exclusive access by the migrator
-        conn.lock().await?;
+        // nothing is applied until the [_migrations] table is created, which isn't done here
+        if !conn.migrations_table_exists(&self.table_name).await? {
+            return Ok(self.resolve_plan(Vec::new(), Vec::new(), None));
+        }
 
-        // creates [_migrations] table only if needed
-        // eventually this will likely migrate previous versions of the table
-        conn.ensure_migrations_table().await?;
+        let dirty_version = conn.dirty_version(&self.table_name).await?;
+        let applied_migrations = conn.list_applied_migrations(&self.table_name).await?;
+        let applied_repeatable = conn
+            .list_applied_repeatable_migrations(&self.table_name)
+            .await?;
 
-        let version = conn.dirty_version().await?;
-        if let Some(version) = version {
-            return Err(MigrateError::Dirty(version));
+        Ok(self.resolve_plan(applied_migrations, applied_repeatable, dirty_version))
+    }
+
+    // shared by `plan()` and `run()`, so what is planned is what is run
+    fn resolve_plan(
+        &self,
+        applied_migrations: Vec<AppliedMigration>,
+        applied_repeatable: Vec<AppliedRepeatableMigration>,
+        dirty_version: Option<i64>,
+    ) -> MigrationPlan {
+        let mut problems = Vec::new();
+
+        if let Some(version) = dirty_version {
+            problems.push(MigrationProblem::Dirty(version));
         }
 
-        let applied_migrations = conn.list_applied_migrations().await?;
-        validate_applied_migrations(&applied_migrations, self)?;
+        if !self.ignore_missing {
+            let migrations: HashSet<_> = self
+                .iter()
+                .filter(|m| !m.migration_type.is_repeatable())
+                .map(|m| m.version)
+                .collect();
 
-        let applied_migrations: HashMap<_, _> = applied_migrations
-            .into_iter()
-            .map(|m| (m.version, m))
+            problems.extend(
+                applied_migrations
+                    .iter()
+                    .filter(|m| !migrations.contains(&m.version))
+                    .map(|m| MigrationProblem::MissingLocally(m.version)),
+            );
+        }
+
+        let applied: HashMap<_, _> = applied_migrations
+            .iter()
+            .map(|m| (m.version, &m.checksum))
             .collect();
 
+        let latest_applied = applied_migrations.iter().map(|m| m.version).max();
+
+        let mut pending = Vec::new();
+
         for migration in self.iter() {
-            if migration.migration_type.is_down_migration() {
+            if migration.migration_type.is_down_migration()
+                || migration.migration_type.is_repeatable()
+            {
                 continue;
             }
 
-            match applied_migrations.get(&migration.version) {
-                Some(applied_migration) => {
-                    if migration.checksum != applied_migration.checksum {
-                        return Err(MigrateError::VersionMismatch(migration.version));
+            match applied.get(&migration.version) {
+                Some(checksum) => {
+                    if migration.checksum != **checksum {
+                        problems.push(MigrationProblem::ChecksumMismatch(migration.version));
                     }
                 }
                 None => {
-                    conn.apply(migration).await?;
+                    if !self.allow_out_of_order
+                        && matches!(latest_applied, Some(latest) if migration.version < latest)
+                    {
+                        problems.push(MigrationProblem::OutOfOrder(migration.version));
+                    }
+
+                    pending.push(migration.clone());
                 }
             }
         }
 
+        // repeatable migrations are applied after the versioned ones, whenever they changed
+        let applied_checksums: HashMap<_, _> = applied_repeatable
+            .iter()
+            .map(|m| (&*m.description, &m.checksum))
+            .collect();
+
+        let mut repeatable: Vec<_> = self
+            .iter()
+            .filter(|m| m.migration_type.is_repeatable())
+            .filter(|m| applied_checksums.get(&*m.description) != Some(&&m.checksum))
+            .cloned()
+            .collect();
+
+        repeatable.sort_by(|a, b| a.description.cmp(&b.description));
+        pending.extend(repeatable);
+
+        MigrationPlan {
+            applied: applied_migrations,
+            applied_repeatable,
+            pending,
+            problems,
+        }
+    }
+
+    /// Run any pending migrations against the database; and, validate previously applied migrations
+    /// against the current migration source to detect accidental changes in previously-applied migrations.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use sqlx_core::migrate::MigrateError;
+    /// # #[cfg(feature = "sqlite")]
+    /// # fn main() -> Result<(), MigrateError> {
+    /// #     sqlx_rt::block_on(async move {
+    /// # use sqlx_core::migrate::Migrator;
+    /// let m = Migrator::new(std::path::Path::new("./migrations")).await?;
+    /// let pool = sqlx_core::sqlite::SqlitePoolOptions::new().connect("sqlite::memory:").await?;
+    /// m.run(&pool).await
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn run<'a, A>(&self, migrator: A) -> Result<(), MigrateError>
+    where
+        A: Acquire<'a>,
+        <A::Connection as Deref>::Target: Migrate + 'static,
+    {
+        let mut conn = migrator.acquire().await?;
+        self.run_direct(&mut *conn).await
+    }
+
+    /// Run any pending migrations as [`run()`](Self::run) does, calling `progress` with each
+    /// [`MigrationEvent`] as it happens, e.g. to show which migration is being applied.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use sqlx_core::migrate::MigrateError;
+    /// # #[cfg(feature = "sqlite")]
+    /// # fn main() -> Result<(), MigrateError> {
+    /// #     sqlx_rt::block_on(async move {
+    /// # use sqlx_core::migrate::{MigrationEvent, Migrator};
+    /// let m = Migrator::new(std::path::Path::new("./migrations")).await?;
+    /// let pool = sqlx_core::sqlite::SqlitePoolOptions::new().connect("sqlite::memory:").await?;
+    /// m.run_with_progress(&pool, |event| match event {
+    ///     MigrationEvent::MigrationStarted { migration } => {
+    ///         println!("applying {}", migration.description)
+    ///     }
+    ///     MigrationEvent::MigrationFinished { migration, elapsed, .. } => {
+    ///         println!("applied {} in {:?}", migration.description, elapsed)
+    ///     }
+    ///     _ => {}
+    /// })
+    /// .await
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn run_with_progress<'a, A, F>(
+        &self,
+        migrator: A,
+        progress: F,
+    ) -> Result<(), MigrateError>
+    where
+        A: Acquire<'a>,
+        <A::Connection as Deref>::Target: Migrate + 'static,
+        F: Fn(MigrationEvent<'_>),
+    {
+        let mut conn = migrator.acquire().await?;
+        self.run_direct_with_progress(&mut *conn, progress).await
+    }
+
+    // Getting around the annoying "implementation of `Acquire` is not general enough" error
+    #[doc(hidden)]
+    pub async fn run_direct<C>(&self, conn: &mut C) -> Result<(), MigrateError>
+    where
+        C: Migrate + 'static,
+    {
+        self.run_direct_with_progress(conn, |_| {}).await
+    }
+
+    #[doc(hidden)]
+    pub async fn run_direct_with_progress<C, F>(
+        &self,
+        conn: &mut C,
+        progress: F,
+    ) -> Result<(), MigrateError>
+    where
+        C: Migrate + 'static,
+        F: Fn(MigrationEvent<'_>),
+    {
+        let start = Instant::now();
+
+        // lock the database for exclusive access by the migrator
+        self.lock(conn).await?;
+        progress(MigrationEvent::LockAcquired);
+
+        // creates [_migrations] table only if needed
+        conn.ensure_migrations_table(&self.table_name).await?;
+
+        let plan = self.plan_direct(conn).await?;
+        plan.check()?;
+        progress(MigrationEvent::Resolved {
+            pending: &plan.pending,
+        });
+
+        let applied_by = self.applied_by();
+
+        for migration in &plan.pending {
+            progress(MigrationEvent::MigrationStarted { migration });
+            let migration_start = Instant::now();
+
+            let res = conn
+                .apply(&self.table_name, migration, applied_by.as_deref())
+                .await;
+
+            progress(MigrationEvent::MigrationFinished {
+                migration,
+                // the time recorded for an applied migration
+                elapsed: *res.as_ref().unwrap_or(&migration_start.elapsed()),
+                success: res.is_ok(),
+            });
+
+            res?;
+        }
+
         // unlock the migrator to allow other migrators to run
         // but do nothing as we already migrated
-        conn.unlock().await?;
+        self.unlock(conn).await?;
+
+        for seed in self.seeds.iter() {
+            let elapsed = conn.seed(seed).await?;
+            progress(MigrationEvent::SeedFinished { seed, elapsed });
+        }
+
+        for after_run in self.after_run.iter() {
+            let hook = after_run
+                .hook
+                .downcast_ref::<Box<AfterRunFn<C>>>()
+                .ok_or_else(|| {
+                    MigrateError::AfterRun(
+                        format!(
+                            "the hook takes a {} but the migrations were run on a {}",
+                            after_run.connection,
+                            type_name::<C>()
+                        )
+                        .into(),
+                    )
+                })?;
+
+            hook(conn).await.map_err(MigrateError::AfterRun)?;
+        }
+
+        progress(MigrationEvent::Completed {
+            applied: plan.pending.len(),
+            elapsed: start.elapsed(),
+        });
 
         Ok(())
     }
 
-    /// Run down migrations against the database until a specific version.
+    /// Get the down migrations which revert every migration in `applied_migrations` above
+    /// `target`, latest first.
+    ///
+    /// Fails with [`MigrateError::MissingDownMigrations`] if any of them has no down migration.
+    pub fn reverts<'s>(
+        &'s self,
+        applied_migrations: &[AppliedMigration],
+        target: i64,
+    ) -> Result<Vec<&'s Migration>, MigrateError> {
+        let down_migrations: HashMap<_, _> = self
+            .iter()
+            .filter(|m| m.migration_type.is_down_migration())
+            .map(|m| (m.version, m))
+            .collect();
+
+        let mut versions: Vec<_> = applied_migrations
+            .iter()
+            .map(|m| m.version)
+            .filter(|&version| version > target)
+            .collect();
+
+        versions.sort_unstable_by(|a, b| b.cmp(a));
+
+        let missing: Vec<_> = versions
+            .iter()
+            .copied()
+            .filter(|version| !down_migrations.contains_key(version))
+            .collect();
+
+        if !missing.is_empty() {
+            return Err(MigrateError::MissingDownMigrations(missing));
+        }
+
+        Ok(versions
+            .iter()
+            .map(|version| down_migrations[version])
+            .collect())
+    }
+
+    /// Run down migrations against the database, latest first, until `target` is the latest
+    /// applied version. Pass `0` to revert every migration.
+    ///
+    /// Nothing is reverted if any of the migrations above `target` has no down migration.
     ///
     /// # Examples
     ///
@@ -168,41 +837,216 @@ impl Migrator {
         <A::Connection as Deref>::Target: Migrate,
     {
         let mut conn = migrator.acquire().await?;
+        self.undo_direct(&mut *conn, target).await
+    }
 
+    #[doc(hidden)]
+    pub async fn undo_direct<C>(&self, conn: &mut C, target: i64) -> Result<(), MigrateError>
+    where
+        C: Migrate,
+    {
         // lock the database for exclusive access by the migrator
-        conn.lock().await?;
+        self.lock(conn).await?;
 
         // creates [_migrations] table only if needed
         // eventually this will likely migrate previous versions of the table
-        conn.ensure_migrations_table().await?;
+        conn.ensure_migrations_table(&self.table_name).await?;
 
-        let version = conn.dirty_version().await?;
+        let version = conn.dirty_version(&self.table_name).await?;
         if let Some(version) = version {
             return Err(MigrateError::Dirty(version));
         }
 
-        let applied_migrations = conn.list_applied_migrations().await?;
+        let applied_migrations = conn.list_applied_migrations(&self.table_name).await?;
         validate_applied_migrations(&applied_migrations, self)?;
 
-        let applied_migrations: HashMap<_, _> = applied_migrations
-            .into_iter()
+        for migration in self.reverts(&applied_migrations, target)? {
+            conn.revert(&self.table_name, migration).await?;
+        }
+
+        // unlock the migrator to allow other migrators to run
+        self.unlock(conn).await?;
+
+        Ok(())
+    }
+
+    /// Get the applied migrations whose recorded checksum differs from the one of the migration,
+    /// e.g. because its file was reformatted since.
+    pub fn repairs(&self, applied_migrations: &[AppliedMigration]) -> Vec<ChecksumRepair> {
+        let migrations: HashMap<_, _> = self
+            .iter()
+            .filter(|m| !m.migration_type.is_down_migration() && !m.migration_type.is_repeatable())
             .map(|m| (m.version, m))
             .collect();
 
-        for migration in self
+        applied_migrations
             .iter()
-            .rev()
-            .filter(|m| m.migration_type.is_down_migration())
-            .filter(|m| applied_migrations.contains_key(&m.version))
-            .filter(|m| m.version > target)
-        {
-            conn.revert(migration).await?;
+            .filter_map(|applied| {
+                let migration = migrations.get(&applied.version)?;
+
+                (migration.checksum != applied.checksum).then(|| ChecksumRepair {
+                    version: applied.version,
+                    description: migration.description.clone(),
+                    applied_checksum: applied.checksum.clone(),
+                    checksum: migration.checksum.clone(),
+                })
+            })
+            .collect()
+    }
+
+    /// Replace the recorded checksums of the applied migrations which were modified since, so
+    /// they no longer fail with [`MigrateError::VersionMismatch`]. Returns what was replaced.
+    ///
+    /// This rewrites the history of the database: only use it once the changes to the migrations
+    /// are known to make no difference, like changes to whitespace or comments. See
+    /// [`repairs()`](Self::repairs) to list them without replacing them.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use sqlx_core::migrate::MigrateError;
+    /// # #[cfg(feature = "sqlite")]
+    /// # fn main() -> Result<(), MigrateError> {
+    /// #     sqlx_rt::block_on(async move {
+    /// # use sqlx_core::migrate::Migrator;
+    /// let m = Migrator::new(std::path::Path::new("./migrations")).await?;
+    /// let pool = sqlx_core::sqlite::SqlitePoolOptions::new().connect("sqlite::memory:").await?;
+    ///
+    /// for repair in m.repair(&pool).await? {
+    ///     println!("repaired the checksum of {}", repair.version);
+    /// }
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn repair<'a, A>(&self, migrator: A) -> Result<Vec<ChecksumRepair>, MigrateError>
+    where
+        A: Acquire<'a>,
+        <A::Connection as Deref>::Target: Migrate,
+    {
+        let mut conn = migrator.acquire().await?;
+        self.repair_direct(&mut *conn).await
+    }
+
+    #[doc(hidden)]
+    pub async fn repair_direct<C>(&self, conn: &mut C) -> Result<Vec<ChecksumRepair>, MigrateError>
+    where
+        C: Migrate,
+    {
+        // lock the database for exclusive access by the migrator
+        self.lock(conn).await?;
+
+        conn.ensure_migrations_table(&self.table_name).await?;
+
+        if let Some(version) = conn.dirty_version(&self.table_name).await? {
+            return Err(MigrateError::Dirty(version));
+        }
+
+        let applied_migrations = conn.list_applied_migrations(&self.table_name).await?;
+        let repairs = self.repairs(&applied_migrations);
+
+        for repair in &repairs {
+            let migration = self
+                .iter()
+                .find(|m| {
+                    m.version == repair.version
+                        && !m.migration_type.is_down_migration()
+                        && !m.migration_type.is_repeatable()
+                })
+                .expect("BUG: repaired migration is not resolved");
+
+            conn.set_checksum(&self.table_name, migration).await?;
         }
 
         // unlock the migrator to allow other migrators to run
-        // but do nothing as we already migrated
-        conn.unlock().await?;
+        self.unlock(conn).await?;
 
-        Ok(())
+        Ok(repairs)
+    }
+
+    /// Get the migrations up to `version` which aren't applied, in order.
+    pub fn baselines(
+        &self,
+        applied_migrations: &[AppliedMigration],
+        version: i64,
+    ) -> Vec<&Migration> {
+        let applied: HashSet<_> = applied_migrations.iter().map(|m| m.version).collect();
+
+        self.iter()
+            .filter(|m| !m.migration_type.is_down_migration() && !m.migration_type.is_repeatable())
+            .filter(|m| m.version <= version && !applied.contains(&m.version))
+            .collect()
+    }
+
+    /// Record the migrations up to `version` as applied without running them, for a database
+    /// whose schema was created otherwise, e.g. before it was managed with migrations. Returns
+    /// the migrations which were recorded.
+    ///
+    /// This rewrites the history of the database: the schema is assumed to be what these
+    /// migrations would have made it. See [`baselines()`](Self::baselines) to list them without
+    /// recording them.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use sqlx_core::migrate::MigrateError;
+    /// # #[cfg(feature = "sqlite")]
+    /// # fn main() -> Result<(), MigrateError> {
+    /// #     sqlx_rt::block_on(async move {
+    /// # use sqlx_core::migrate::Migrator;
+    /// let m = Migrator::new(std::path::Path::new("./migrations")).await?;
+    /// let pool = sqlx_core::sqlite::SqlitePoolOptions::new().connect("sqlite::memory:").await?;
+    ///
+    /// // the database already has the schema of migrations 1 to 40
+    /// m.baseline(&pool, 40).await?;
+    /// m.run(&pool).await
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn baseline<'a, A>(
+        &self,
+        migrator: A,
+        version: i64,
+    ) -> Result<Vec<&Migration>, MigrateError>
+    where
+        A: Acquire<'a>,
+        <A::Connection as Deref>::Target: Migrate,
+    {
+        let mut conn = migrator.acquire().await?;
+        self.baseline_direct(&mut *conn, version).await
+    }
+
+    #[doc(hidden)]
+    pub async fn baseline_direct<C>(
+        &self,
+        conn: &mut C,
+        version: i64,
+    ) -> Result<Vec<&Migration>, MigrateError>
+    where
+        C: Migrate,
+    {
+        // lock the database for exclusive access by the migrator
+        self.lock(conn).await?;
+
+        conn.ensure_migrations_table(&self.table_name).await?;
+
+        if let Some(version) = conn.dirty_version(&self.table_name).await? {
+            return Err(MigrateError::Dirty(version));
+        }
+
+        let applied_migrations = conn.list_applied_migrations(&self.table_name).await?;
+        let baselines = self.baselines(&applied_migrations, version);
+
+        let applied_by = self.applied_by();
+
+        for migration in &baselines {
+            conn.mark_applied(&self.table_name, migration, applied_by.as_deref())
+                .await?;
+        }
+
+        // unlock the migrator to allow other migrators to run
+        self.unlock(conn).await?;
+
+        Ok(baselines)
     }
 }
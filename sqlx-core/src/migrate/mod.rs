@@ -1,14 +1,33 @@
+mod create;
 mod error;
+mod event;
 #[allow(clippy::module_inception)]
 mod migrate;
 mod migration;
 mod migration_type;
 mod migrator;
+mod plan;
+mod seed;
 mod source;
+mod verify;
 
+pub use create::CreateDatabaseOptions;
 pub use error::MigrateError;
+pub use event::MigrationEvent;
+#[cfg(any(feature = "postgres", feature = "mysql", feature = "sqlite"))]
+pub(crate) use migrate::{
+    applied_on, quote_table_name, repeatable_table_name, split_table_name,
+    warn_missing_audit_columns,
+};
 pub use migrate::{Migrate, MigrateDatabase};
-pub use migration::{AppliedMigration, Migration};
+pub use migration::{
+    parse_file_name, AppliedMigration, AppliedRepeatableMigration, ChecksumRepair, Migration,
+    NO_TRANSACTION,
+};
 pub use migration_type::MigrationType;
-pub use migrator::Migrator;
+pub use migrator::{AfterRun, Locking, Migrator, DEFAULT_MIGRATIONS_TABLE};
+pub use plan::{MigrationPlan, MigrationProblem};
+pub use seed::Seed;
+pub(crate) use source::sort_migrations;
 pub use source::MigrationSource;
+pub use verify::{Drift, DriftKind, VerifyReport};
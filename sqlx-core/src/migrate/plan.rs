@@ -0,0 +1,103 @@
+use std::fmt::{self, Display, Formatter};
+
+use crate::migrate::{AppliedMigration, AppliedRepeatableMigration, MigrateError, Migration};
+
+/// What [`Migrator::run()`](super::Migrator::run) would do against a database, computed without
+/// applying anything.
+///
+/// Returned by [`Migrator::plan()`](super::Migrator::plan).
+#[derive(Debug, Clone)]
+pub struct MigrationPlan {
+    /// The migrations recorded in the database, ordered by version.
+    pub applied: Vec<AppliedMigration>,
+
+    /// The repeatable migrations recorded in the database, ordered by description.
+    pub applied_repeatable: Vec<AppliedRepeatableMigration>,
+
+    /// The migrations which would be applied, in the order they would be applied in: the
+    /// versioned ones, then the repeatable ones which are new or changed.
+    pub pending: Vec<Migration>,
+
+    /// Everything which keeps the pending migrations from being applied, in the order found.
+    pub problems: Vec<MigrationProblem>,
+}
+
+/// Something wrong with the migrations of a database, found by [`Migrator::plan()`].
+///
+/// [`Migrator::plan()`]: super::Migrator::plan
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum MigrationProblem {
+    /// The migration was only partially applied.
+    Dirty(i64),
+
+    /// The migration was applied but isn't in the resolved migrations.
+    MissingLocally(i64),
+
+    /// The migration was applied but has been modified since.
+    ChecksumMismatch(i64),
+
+    /// The migration is pending, but older than the latest applied migration, and migrations
+    /// out of order aren't allowed.
+    ///
+    /// See [`Migrator::set_allow_out_of_order()`](super::Migrator::set_allow_out_of_order).
+    OutOfOrder(i64),
+}
+
+impl MigrationPlan {
+    /// Fail with the error [`Migrator::run()`](super::Migrator::run) would fail with, if
+    /// there is any problem.
+    pub fn check(&self) -> Result<(), MigrateError> {
+        match self.problems.first() {
+            None => Ok(()),
+            Some(&MigrationProblem::Dirty(version)) => Err(MigrateError::Dirty(version)),
+            Some(&MigrationProblem::MissingLocally(version)) => {
+                Err(MigrateError::VersionMissing(version))
+            }
+            Some(&MigrationProblem::ChecksumMismatch(version)) => {
+                Err(MigrateError::VersionMismatch(version))
+            }
+            Some(&MigrationProblem::OutOfOrder(version)) => Err(MigrateError::OutOfOrder(version)),
+        }
+    }
+}
+
+impl MigrationProblem {
+    /// The version of the migration with the problem.
+    pub fn version(&self) -> i64 {
+        match *self {
+            MigrationProblem::Dirty(version)
+            | MigrationProblem::MissingLocally(version)
+            | MigrationProblem::ChecksumMismatch(version)
+            | MigrationProblem::OutOfOrder(version) => version,
+        }
+    }
+}
+
+impl Display for MigrationProblem {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            MigrationProblem::Dirty(version) => {
+                write!(f, "migration {} is partially applied", version)
+            }
+
+            MigrationProblem::MissingLocally(version) => write!(
+                f,
+                "migration {} was previously applied but is missing in the resolved migrations",
+                version
+            ),
+
+            MigrationProblem::ChecksumMismatch(version) => write!(
+                f,
+                "migration {} was previously applied but has been modified",
+                version
+            ),
+
+            MigrationProblem::OutOfOrder(version) => write!(
+                f,
+                "migration {} is older than the latest applied migration",
+                version
+            ),
+        }
+    }
+}
@@ -0,0 +1,74 @@
+use crate::error::BoxDynError;
+use futures_util::TryStreamExt;
+use sqlx_rt::fs;
+use std::borrow::Cow;
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+
+/// A script run after migrations, e.g. to insert reference data, which isn't recorded in the
+/// migrations table.
+///
+/// A seed is run by every [`Migrator::run()`](super::Migrator::run) which succeeds, in a
+/// transaction of its own, so it must be idempotent, e.g. with `INSERT .. ON CONFLICT DO
+/// NOTHING`. Add it with [`Migrator::add_seed()`](super::Migrator::add_seed).
+#[derive(Debug, Clone)]
+pub struct Seed {
+    /// The name of the seed in errors, e.g. the name of its file.
+    pub name: Cow<'static, str>,
+    pub sql: Cow<'static, str>,
+}
+
+impl Seed {
+    pub fn new(name: impl Into<Cow<'static, str>>, sql: impl Into<Cow<'static, str>>) -> Self {
+        Seed {
+            name: name.into(),
+            sql: sql.into(),
+        }
+    }
+
+    /// Read the seeds at `path`: the file, or the `.sql` files of the directory, e.g. `seeds/`,
+    /// sorted by name. Each file is a seed named after it.
+    pub async fn from_path(path: impl AsRef<Path>) -> Result<Vec<Seed>, BoxDynError> {
+        let path = path.as_ref();
+
+        let mut paths = if fs::metadata(path).await?.is_dir() {
+            let mut paths = Vec::new();
+
+            #[allow(unused_mut)]
+            let mut s = fs::read_dir(path).await?;
+
+            #[cfg(feature = "_rt-tokio")]
+            let mut s = tokio_stream::wrappers::ReadDirStream::new(s);
+
+            while let Some(entry) = s.try_next().await? {
+                let path = entry.path();
+
+                if entry.metadata().await?.is_file() && path.extension() == Some(OsStr::new("sql"))
+                {
+                    // a path of async-std with its runtime
+                    paths.push(PathBuf::from(path.into_os_string()));
+                }
+            }
+
+            paths
+        } else {
+            vec![path.to_path_buf()]
+        };
+
+        paths.sort();
+
+        let mut seeds = Vec::with_capacity(paths.len());
+
+        for path in paths {
+            let sql = fs::read_to_string(&path).await?;
+            let name = path.file_name().map_or_else(
+                || path.display().to_string(),
+                |name| name.to_string_lossy().into_owned(),
+            );
+
+            seeds.push(Seed::new(name, sql));
+        }
+
+        Ok(seeds)
+    }
+}
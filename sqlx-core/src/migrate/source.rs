@@ -1,13 +1,23 @@
 use crate::error::BoxDynError;
-use crate::migrate::{Migration, MigrationType};
+use crate::migrate::{parse_file_name, Migration};
 use futures_core::future::BoxFuture;
 use futures_util::TryStreamExt;
 use sqlx_rt::fs;
 use std::borrow::Cow;
-use std::fmt::Debug;
 use std::path::{Path, PathBuf};
 
-pub trait MigrationSource<'s>: Debug {
+/// A source of migrations, resolved by [`Migrator::new()`](super::Migrator::new).
+///
+/// Implement it to load migrations from elsewhere than a directory, e.g. from an object store
+/// at runtime. Build the migrations with [`Migration::from_file_name()`] or [`Migration::new()`],
+/// so that their checksums are those of the same migrations resolved from files. They may be
+/// resolved in any order, as the migrator sorts them.
+///
+/// `'s` is the lifetime of what the source borrows while it's resolved, e.g. a `&'s Path`, or
+/// `'static` for a source which owns what it needs.
+///
+/// This trait is stable: it will only change in a new major version of SQLx.
+pub trait MigrationSource<'s> {
     fn resolve(self) -> BoxFuture<'s, Result<Vec<Migration>, BoxDynError>>;
 }
 
@@ -17,61 +27,115 @@ pub trait MigrationSource<'s>: Debug {
 /// scripts must be stored in files with names using the format `<VERSION>_<DESCRIPTION>.sql`,
 /// where `<VERSION>` is a string that can be parsed into `i64` and its value is greater than zero,
 /// and `<DESCRIPTION>` is a string.
+///
+/// Repeatable migrations are stored in files named `r_<DESCRIPTION>.sql`. They are sorted after
+/// the versioned migrations, by description.
 impl<'s> MigrationSource<'s> for &'s Path {
     fn resolve(self) -> BoxFuture<'s, Result<Vec<Migration>, BoxDynError>> {
         Box::pin(async move {
-            #[allow(unused_mut)]
-            let mut s = fs::read_dir(self.canonicalize()?).await?;
             let mut migrations = Vec::new();
 
-            #[cfg(feature = "_rt-tokio")]
-            let mut s = tokio_stream::wrappers::ReadDirStream::new(s);
-
-            while let Some(entry) = s.try_next().await? {
-                if !entry.metadata().await?.is_file() {
-                    // not a file; ignore
-                    continue;
-                }
-
-                let file_name = entry.file_name();
-                let file_name = file_name.to_string_lossy();
+            resolve_dir(self, &mut migrations).await?;
+            sort_migrations(&mut migrations);
 
-                let parts = file_name.splitn(2, '_').collect::<Vec<_>>();
-
-                if parts.len() != 2 || !parts[1].ends_with(".sql") {
-                    // not of the format: <VERSION>_<DESCRIPTION>.sql; ignore
-                    continue;
-                }
-
-                let version: i64 = parts[0].parse()?;
+            Ok(migrations)
+        })
+    }
+}
 
-                let migration_type = MigrationType::from_filename(parts[1]);
-                // remove the `.sql` and replace `_` with ` `
-                let description = parts[1]
-                    .trim_end_matches(migration_type.suffix())
-                    .replace('_', " ")
-                    .to_owned();
+impl MigrationSource<'static> for PathBuf {
+    fn resolve(self) -> BoxFuture<'static, Result<Vec<Migration>, BoxDynError>> {
+        Box::pin(async move { self.as_path().resolve().await })
+    }
+}
 
-                let sql = fs::read_to_string(&entry.path()).await?;
+/// Implementation of the `MigrationSource` for several directories, e.g. migrations shared by
+/// all databases and those of a single one.
+///
+/// The migrations of all the directories are resolved as for a single [std::path::Path], then
+/// sorted together, so those of different directories are interleaved by version.
+impl<'s, P> MigrationSource<'s> for &'s [P]
+where
+    P: AsRef<Path> + Sync,
+{
+    fn resolve(self) -> BoxFuture<'s, Result<Vec<Migration>, BoxDynError>> {
+        Box::pin(async move {
+            let mut migrations = Vec::new();
 
-                migrations.push(Migration::new(
-                    version,
-                    Cow::Owned(description),
-                    migration_type,
-                    Cow::Owned(sql),
-                ));
+            for path in self {
+                resolve_dir(path.as_ref(), &mut migrations).await?;
             }
 
-            // ensure that we are sorted by `VERSION ASC`
-            migrations.sort_by_key(|m| m.version);
+            sort_migrations(&mut migrations);
 
             Ok(migrations)
         })
     }
 }
 
-impl MigrationSource<'static> for PathBuf {
+impl MigrationSource<'static> for Vec<PathBuf> {
     fn resolve(self) -> BoxFuture<'static, Result<Vec<Migration>, BoxDynError>> {
-        Box::pin(async move { self.as_path().resolve().await })
+        Box::pin(async move { self.as_slice().resolve().await })
+    }
+}
+
+/// Implementation of the `MigrationSource` for migrations already in memory, e.g. generated at
+/// build time.
+impl MigrationSource<'static> for Vec<Migration> {
+    fn resolve(self) -> BoxFuture<'static, Result<Vec<Migration>, BoxDynError>> {
+        Box::pin(async move { Ok(self) })
+    }
+}
+
+async fn resolve_dir(path: &Path, migrations: &mut Vec<Migration>) -> Result<(), BoxDynError> {
+    #[allow(unused_mut)]
+    let mut s = fs::read_dir(path.canonicalize()?).await?;
+
+    #[cfg(feature = "_rt-tokio")]
+    let mut s = tokio_stream::wrappers::ReadDirStream::new(s);
+
+    while let Some(entry) = s.try_next().await? {
+        if !entry.metadata().await?.is_file() {
+            // not a file; ignore
+            continue;
+        }
+
+        let file_name = entry.file_name();
+
+        let (version, migration_type, description) =
+            match parse_file_name(&file_name.to_string_lossy())? {
+                Some(parsed) => parsed,
+                // not of the format: <VERSION>_<DESCRIPTION>.sql; ignore
+                None => continue,
+            };
+
+        let sql = fs::read_to_string(&entry.path()).await?;
+
+        let mut migration = Migration::new(
+            version,
+            Cow::Owned(description),
+            migration_type,
+            Cow::Owned(sql),
+        );
+        migration.path = Some(Cow::Owned(entry.path().to_string_lossy().into_owned()));
+
+        migrations.push(migration);
     }
+
+    Ok(())
+}
+
+pub(crate) fn sort_migrations(migrations: &mut [Migration]) {
+    // ensure that we are sorted by `VERSION ASC`, then repeatable migrations by `DESCRIPTION ASC`
+    migrations.sort_by(|a, b| {
+        let key = |m: &Migration| {
+            (
+                m.migration_type.is_repeatable(),
+                m.version,
+                m.description.clone(),
+            )
+        };
+
+        key(a).cmp(&key(b))
+    });
 }
@@ -0,0 +1,103 @@
+use std::borrow::Cow;
+use std::fmt::{self, Display, Formatter};
+
+/// How the migrations of a database differ from the resolved migrations, found by
+/// [`Migrator::verify()`](super::Migrator::verify).
+#[derive(Debug, Clone, Default)]
+pub struct VerifyReport {
+    /// Every difference found, in the order found.
+    pub drift: Vec<Drift>,
+}
+
+/// A difference between the migrations of a database and the resolved migrations.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Drift {
+    pub kind: DriftKind,
+
+    /// The version of the migration, or `None` for a repeatable migration.
+    pub version: Option<i64>,
+
+    /// The description of the migration, if it is resolved.
+    pub description: Option<Cow<'static, str>>,
+}
+
+/// The kind of a [`Drift`], from the least to the most severe.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[non_exhaustive]
+pub enum DriftKind {
+    /// The migration isn't applied, or is a repeatable migration which changed since it was.
+    Pending,
+
+    /// The migration isn't applied, and is older than the latest applied migration, which
+    /// [`Migrator::run()`](super::Migrator::run) refuses unless migrations out of order are
+    /// allowed.
+    OutOfOrder,
+
+    /// The migration was applied but isn't in the resolved migrations.
+    Missing,
+
+    /// The migration was applied but is newer than every resolved migration, e.g. by a newer
+    /// version of the application.
+    Newer,
+
+    /// The migration was applied but has been modified since.
+    Modified,
+
+    /// The migration was only partially applied.
+    Dirty,
+}
+
+impl VerifyReport {
+    /// Whether the database has exactly the resolved migrations applied.
+    pub fn is_clean(&self) -> bool {
+        self.drift.is_empty()
+    }
+
+    /// Whether any difference is of the given kind.
+    pub fn contains(&self, kind: DriftKind) -> bool {
+        self.drift.iter().any(|drift| drift.kind == kind)
+    }
+}
+
+impl DriftKind {
+    /// The name of the kind in `snake_case`, e.g. `out_of_order`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DriftKind::Pending => "pending",
+            DriftKind::OutOfOrder => "out_of_order",
+            DriftKind::Missing => "missing",
+            DriftKind::Newer => "newer",
+            DriftKind::Modified => "modified",
+            DriftKind::Dirty => "dirty",
+        }
+    }
+}
+
+impl Display for Drift {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match (self.version, &self.description) {
+            (Some(version), Some(description)) => {
+                write!(f, "migration {} ({})", version, description)?
+            }
+            (Some(version), None) => write!(f, "migration {}", version)?,
+            (None, description) => write!(
+                f,
+                "repeatable migration {}",
+                description.as_deref().unwrap_or("<unknown>")
+            )?,
+        }
+
+        match self.kind {
+            DriftKind::Pending => f.write_str(" is pending"),
+            DriftKind::OutOfOrder => f.write_str(" is older than the latest applied migration"),
+            DriftKind::Missing => {
+                f.write_str(" was applied but is missing in the resolved migrations")
+            }
+            DriftKind::Newer => {
+                f.write_str(" was applied but is newer than every resolved migration")
+            }
+            DriftKind::Modified => f.write_str(" was applied but has been modified since"),
+            DriftKind::Dirty => f.write_str(" is partially applied"),
+        }
+    }
+}
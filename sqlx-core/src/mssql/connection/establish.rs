@@ -1,4 +1,5 @@
 use crate::common::StatementCache;
+use crate::connection::LogSettings;
 use crate::error::Error;
 use crate::io::Decode;
 use crate::mssql::connection::stream::MssqlStream;
@@ -82,7 +83,17 @@ impl MssqlConnection {
         Ok(Self {
             stream,
             cache_statement: StatementCache::new(1024),
-            log_settings: options.log_settings.clone(),
+            log_settings: log_settings(options),
         })
     }
 }
+
+fn log_settings(options: &MssqlConnectOptions) -> LogSettings {
+    #[allow(unused_mut)]
+    let mut settings = options.log_settings.for_connection();
+
+    #[cfg(feature = "tracing")]
+    settings.trace_server("mssql", Some(&options.host), Some(options.port));
+
+    settings
+}
@@ -1,7 +1,7 @@
 use crate::describe::Describe;
 use crate::error::Error;
 use crate::executor::{Execute, Executor};
-use crate::logger::QueryLogger;
+use crate::logger::{QueryLogger, QuerySpan};
 use crate::mssql::connection::prepare::prepare;
 use crate::mssql::protocol::col_meta_data::Flags;
 use crate::mssql::protocol::done::Status;
@@ -77,11 +77,20 @@ impl<'c> Executor<'c> for &'c mut MssqlConnection {
         'c: 'e,
         E: Execute<'q, Self::Database>,
     {
+        if let Some(error) = query.take_error() {
+            return Box::pin(futures_util::stream::once(async move { Err(error) }));
+        }
+
         let sql = query.sql();
         let arguments = query.take_arguments();
-        let mut logger = QueryLogger::new(sql, self.log_settings.clone());
-
-        Box::pin(try_stream! {
+        let parameters = arguments.as_ref().map_or(0, |arguments| arguments.ordinal);
+        let persistent = query.persistent();
+        let span = QuerySpan::new(sql, &self.log_settings);
+        let mut logger =
+            QueryLogger::new(sql, parameters, persistent, self.log_settings.clone(), span);
+        let errors = logger.errors();
+
+        let stream = try_stream! {
             self.run(sql, arguments).await?;
 
             loop {
@@ -94,7 +103,12 @@ impl<'c> Executor<'c> for &'c mut MssqlConnection {
 
                         logger.increment_rows_returned();
 
-                        r#yield!(Either::Right(MssqlRow { row, column_names, columns }));
+                        r#yield!(Either::Right(MssqlRow {
+                            row,
+                            column_names,
+                            columns,
+                            context: logger.context().clone(),
+                        }));
                     }
 
                     Message::Done(done) | Message::DoneProc(done) => {
@@ -130,7 +144,9 @@ impl<'c> Executor<'c> for &'c mut MssqlConnection {
             }
 
             Ok(())
-        })
+        };
+
+        Box::pin(stream.map_err(move |error| errors.record(error)))
     }
 
     fn fetch_optional<'e, 'q: 'e, E: 'q>(
@@ -192,6 +208,7 @@ impl<'c> Executor<'c> for &'c mut MssqlConnection {
                 nullable,
                 columns: (metadata.columns).clone(),
                 parameters: None,
+                domains: Vec::new(),
             })
         })
     }
@@ -3,6 +3,7 @@ use crate::mssql::{
     MssqlArguments, MssqlColumn, MssqlConnection, MssqlQueryResult, MssqlRow, MssqlStatement,
     MssqlTransactionManager, MssqlTypeInfo, MssqlValue, MssqlValueRef,
 };
+use crate::quote::{self, QuoteError};
 
 /// MSSQL database driver.
 #[derive(Debug)]
@@ -22,6 +23,19 @@ impl Database for Mssql {
     type TypeInfo = MssqlTypeInfo;
 
     type Value = MssqlValue;
+
+    /// A procedure call can have 2100 parameters, two of which are the statement and the
+    /// declarations of its parameters passed to `sp_executesql`.
+    const MAX_BIND_PARAMETERS: usize = 2098;
+
+    fn quote_identifier(identifier: &str) -> Result<String, QuoteError> {
+        quote::check_identifier_len(identifier.chars().count(), 128)?;
+        quote::quote(identifier, '[', ']')
+    }
+
+    fn quote_literal(value: &str) -> Result<String, QuoteError> {
+        Ok(format!("N{}", quote::quote(value, '\'', '\'')?))
+    }
 }
 
 impl<'r> HasValueRef<'r> for Mssql {
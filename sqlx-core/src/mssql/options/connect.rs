@@ -1,9 +1,13 @@
 use crate::connection::ConnectOptions;
 use crate::error::Error;
+use crate::logger::StatementLogger;
 use crate::mssql::{MssqlConnectOptions, MssqlConnection};
+use crate::observer::QueryObserver;
 use futures_core::future::BoxFuture;
 use log::LevelFilter;
+use std::sync::Arc;
 use std::time::Duration;
+use url::Url;
 
 impl ConnectOptions for MssqlConnectOptions {
     type Connection = MssqlConnection;
@@ -15,6 +19,10 @@ impl ConnectOptions for MssqlConnectOptions {
         Box::pin(MssqlConnection::establish(self))
     }
 
+    fn to_url_lossless(&self) -> Result<Url, Error> {
+        self.build_url()
+    }
+
     fn log_statements(&mut self, level: LevelFilter) -> &mut Self {
         self.log_settings.log_statements(level);
         self
@@ -24,4 +32,25 @@ impl ConnectOptions for MssqlConnectOptions {
         self.log_settings.log_slow_statements(level, duration);
         self
     }
+
+    fn capture_statement_on_error(&mut self, capture: bool) -> &mut Self {
+        self.log_settings.capture_statement_on_error(capture);
+        self
+    }
+
+    fn log_with(&mut self, logger: Arc<dyn StatementLogger>) -> &mut Self {
+        self.log_settings.log_with(logger);
+        self
+    }
+
+    fn query_observer(&mut self, observer: Arc<dyn QueryObserver>) -> &mut Self {
+        self.log_settings.query_observer(observer);
+        self
+    }
+
+    #[cfg(feature = "tracing")]
+    fn trace_statement_limit(&mut self, limit: usize) -> &mut Self {
+        self.log_settings.trace_statement_limit(limit);
+        self
+    }
 }
@@ -1,9 +1,11 @@
-use crate::connection::LogSettings;
+use std::fmt::{self, Debug, Formatter};
+
+use crate::connection::{LogSettings, REDACTED};
 
 mod connect;
 mod parse;
 
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct MssqlConnectOptions {
     pub(crate) host: String,
     pub(crate) port: u16,
@@ -13,6 +15,19 @@ pub struct MssqlConnectOptions {
     pub(crate) log_settings: LogSettings,
 }
 
+impl Debug for MssqlConnectOptions {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MssqlConnectOptions")
+            .field("host", &self.host)
+            .field("port", &self.port)
+            .field("username", &self.username)
+            .field("database", &self.database)
+            .field("password", &self.password.as_ref().map(|_| REDACTED))
+            .field("log_settings", &self.log_settings)
+            .finish()
+    }
+}
+
 impl Default for MssqlConnectOptions {
     fn default() -> Self {
         Self::new()
@@ -1,3 +1,4 @@
+use crate::connection::{set_url_host, unrepresentable_in_url};
 use crate::error::Error;
 use crate::mssql::MssqlConnectOptions;
 use percent_encoding::percent_decode_str;
@@ -45,6 +46,54 @@ impl FromStr for MssqlConnectOptions {
     }
 }
 
+impl MssqlConnectOptions {
+    /// The URL which parses to these options, see [`ConnectOptions::to_url_lossless`].
+    ///
+    /// [`ConnectOptions::to_url_lossless`]: crate::connection::ConnectOptions::to_url_lossless
+    pub(crate) fn build_url(&self) -> Result<Url, Error> {
+        let mut url = Url::parse("mssql://").expect("BUG: failed to parse a constant URL");
+
+        if !set_url_host(&mut url, &self.host) {
+            return Err(unrepresentable_in_url(
+                "host",
+                "as it is empty or has characters which would be escaped",
+            ));
+        }
+
+        let _ = url.set_port(Some(self.port));
+
+        if self.username.is_empty() {
+            return Err(unrepresentable_in_url("username", "as it is empty"));
+        }
+
+        let _ = url.set_username(&self.username);
+
+        match &self.password {
+            Some(password) if password.is_empty() => {
+                return Err(unrepresentable_in_url("password", "as it is empty"));
+            }
+            Some(password) => {
+                let _ = url.set_password(Some(password));
+            }
+            None => {}
+        }
+
+        url.set_path(&format!("/{}", self.database));
+
+        if self.database.is_empty()
+            || self.database.starts_with('/')
+            || url.path()[1..] != *self.database
+        {
+            return Err(unrepresentable_in_url(
+                "database",
+                "as it is empty or has characters which would be escaped",
+            ));
+        }
+
+        Ok(url)
+    }
+}
+
 #[test]
 fn it_parses_username_with_at_sign_correctly() {
     let url = "mysql://user@hostname:password@hostname:5432/database";
@@ -60,3 +109,36 @@ fn it_parses_password_with_non_ascii_chars_correctly() {
 
     assert_eq!(Some("p@ssw0rd".into()), opts.password);
 }
+
+#[test]
+fn it_converts_options_to_a_url_and_back() {
+    use crate::connection::ConnectOptions;
+
+    let options = MssqlConnectOptions::new()
+        .host("[::1]")
+        .port(14330)
+        .username("user@host")
+        .password("p@ss:w/o?r#d")
+        .database("shop");
+
+    let url = options.to_url_lossless().unwrap();
+    let parsed = MssqlConnectOptions::from_str(url.as_str()).unwrap();
+
+    assert_eq!(format!("{:?}", parsed), format!("{:?}", options), "{}", url);
+    assert_eq!(parsed.password, options.password, "{}", url);
+
+    let url = options.to_url_redacted().unwrap();
+    assert!(!url.as_str().contains("ss:w"), "{}", url);
+    assert!(!format!("{:?}", options).contains("ss:w"));
+
+    for options in [
+        MssqlConnectOptions::new().host("::1"),
+        MssqlConnectOptions::new().password(""),
+        MssqlConnectOptions::new().database("with space"),
+    ] {
+        assert!(matches!(
+            options.to_url_lossless(),
+            Err(Error::Configuration(_))
+        ));
+    }
+}
@@ -11,6 +11,12 @@ impl MssqlQueryResult {
     }
 }
 
+impl crate::dyn_connection::DynQueryResult for MssqlQueryResult {
+    fn rows_affected(&self) -> u64 {
+        self.rows_affected
+    }
+}
+
 impl Extend<MssqlQueryResult> for MssqlQueryResult {
     fn extend<T: IntoIterator<Item = MssqlQueryResult>>(&mut self, iter: T) {
         for elem in iter {
@@ -1,5 +1,5 @@
 use crate::column::ColumnIndex;
-use crate::error::Error;
+use crate::error::{Error, ErrorContext};
 use crate::ext::ustr::UStr;
 use crate::mssql::protocol::row::Row as ProtocolRow;
 use crate::mssql::{Mssql, MssqlColumn, MssqlValueRef};
@@ -11,6 +11,7 @@ pub struct MssqlRow {
     pub(crate) row: ProtocolRow,
     pub(crate) columns: Arc<Vec<MssqlColumn>>,
     pub(crate) column_names: Arc<HashMap<UStr, usize>>,
+    pub(crate) context: Option<Arc<ErrorContext>>,
 }
 
 impl crate::row::private_row::Sealed for MssqlRow {}
@@ -34,6 +35,10 @@ impl Row for MssqlRow {
 
         Ok(value)
     }
+
+    fn error_context(&self) -> Option<&Arc<ErrorContext>> {
+        self.context.as_ref()
+    }
 }
 
 impl ColumnIndex<MssqlRow> for &'_ str {
@@ -9,6 +9,17 @@ pub struct MySqlArguments {
     pub(crate) values: Vec<u8>,
     pub(crate) types: Vec<MySqlTypeInfo>,
     pub(crate) null_bitmap: Vec<u8>,
+    pub(crate) attributes: MySqlAttributes,
+}
+
+/// Query attributes, which are sent after the arguments and can be read by the server with
+/// `mysql_query_attribute_string()`.
+#[derive(Debug, Default, Clone)]
+pub(crate) struct MySqlAttributes {
+    pub(crate) names: Vec<String>,
+    pub(crate) values: Vec<u8>,
+    pub(crate) types: Vec<MySqlTypeInfo>,
+    pub(crate) nulls: Vec<bool>,
 }
 
 impl MySqlArguments {
@@ -27,6 +38,26 @@ impl MySqlArguments {
         }
     }
 
+    /// Add a query attribute, which is sent along with the query to servers that support them
+    /// (MySQL 8.0.23 and later), and silently left out for servers that don't.
+    ///
+    /// Attributes are read by the server with `mysql_query_attribute_string()`, once the
+    /// `query_attributes` component is installed.
+    pub fn add_attribute<'q, T>(&mut self, name: &str, value: T)
+    where
+        T: Encode<'q, MySql> + Type<MySql>,
+    {
+        let attributes = &mut self.attributes;
+
+        attributes
+            .types
+            .push(value.produces().unwrap_or_else(T::type_info));
+        attributes.names.push(name.to_owned());
+        attributes
+            .nulls
+            .push(matches!(value.encode(&mut attributes.values), IsNull::Yes));
+    }
+
     #[doc(hidden)]
     pub fn len(&self) -> usize {
         self.types.len()
@@ -3,7 +3,9 @@ use bytes::Bytes;
 use digest::{Digest, OutputSizeUser};
 use generic_array::GenericArray;
 use rand::thread_rng;
-use rsa::{pkcs8::DecodePublicKey, PaddingScheme, PublicKey, RsaPublicKey};
+use rsa::{
+    pkcs1::DecodeRsaPublicKey, pkcs8::DecodePublicKey, PaddingScheme, PublicKey, RsaPublicKey,
+};
 use sha1::Sha1;
 use sha2::Sha256;
 
@@ -11,11 +13,13 @@ use crate::error::Error;
 use crate::mysql::connection::stream::MySqlStream;
 use crate::mysql::protocol::auth::AuthPlugin;
 use crate::mysql::protocol::Packet;
+use crate::mysql::MySqlServerFlavor;
 
 impl AuthPlugin {
-    pub(super) async fn scramble(
+    pub(super) fn scramble(
         self,
-        stream: &mut MySqlStream,
+        stream: &MySqlStream,
+        server_public_key: Option<&[u8]>,
         password: &str,
         nonce: &Chain<Bytes, Bytes>,
     ) -> Result<Vec<u8>, Error> {
@@ -26,26 +30,82 @@ impl AuthPlugin {
             AuthPlugin::MySqlNativePassword => Ok(scramble_sha1(password, nonce).to_vec()),
 
             // https://mariadb.com/kb/en/sha256_password-plugin/
-            AuthPlugin::Sha256Password => encrypt_rsa(stream, 0x01, password, nonce).await,
+            AuthPlugin::Sha256Password => {
+                if stream.is_tls() {
+                    Ok(to_asciz(password))
+                } else if let Some(key) = server_public_key {
+                    encrypt_rsa(key, password, nonce)
+                } else {
+                    // ask for the public key of the server, which is answered
+                    // with a packet that is passed to `handle`
+                    Ok(vec![0x01])
+                }
+            }
+
+            // https://dev.mysql.com/doc/refman/8.0/en/cleartext-pluggable-authentication.html
+            AuthPlugin::MySqlClearPassword => {
+                if stream.is_tls() {
+                    Ok(to_asciz(password))
+                } else {
+                    Err(Error::Configuration(
+                        "refusing to send the password in clear text for `mysql_clear_password` \
+                         authentication over a connection without TLS"
+                            .into(),
+                    ))
+                }
+            }
+
+            // only MariaDB has this plugin, so another server asking for it is refused before
+            // anything derived from the password is sent
+            AuthPlugin::ClientEd25519 if stream.flavor != MySqlServerFlavor::MariaDb => {
+                Err(err_protocol!(
+                    "the server asked for MariaDB's `client_ed25519` authentication, \
+                     but it isn't MariaDB"
+                ))
+            }
+
+            // https://mariadb.com/kb/en/authentication-plugin-ed25519/
+            #[cfg(feature = "mysql-ed25519")]
+            AuthPlugin::ClientEd25519 => {
+                let (a, b) = (nonce.first_ref(), nonce.last_ref());
+                let mut message = Vec::with_capacity(a.len() + b.len());
+                message.extend_from_slice(a);
+                message.extend_from_slice(b);
+
+                Ok(super::ed25519::sign(password.as_bytes(), &message))
+            }
+
+            #[cfg(not(feature = "mysql-ed25519"))]
+            AuthPlugin::ClientEd25519 => Err(Error::Configuration(
+                "`client_ed25519` authentication requires the `mysql-ed25519` feature".into(),
+            )),
         }
     }
 
     pub(super) async fn handle(
         self,
         stream: &mut MySqlStream,
+        server_public_key: Option<&[u8]>,
         packet: Packet<Bytes>,
         password: &str,
         nonce: &Chain<Bytes, Bytes>,
     ) -> Result<bool, Error> {
+        let header = *packet
+            .first()
+            .ok_or_else(|| err_protocol!("unexpected empty packet during authentication"))?;
+
         match self {
-            AuthPlugin::CachingSha2Password if packet[0] == 0x01 => {
-                match packet[1] {
+            AuthPlugin::CachingSha2Password if header == 0x01 => {
+                match packet.get(1) {
                     // AUTH_OK
-                    0x03 => Ok(true),
+                    // the server follows up with an OK packet, which ends authentication
+                    Some(0x03) => Ok(false),
 
                     // AUTH_CONTINUE
-                    0x04 => {
-                        let payload = encrypt_rsa(stream, 0x02, password, nonce).await?;
+                    // the password is not in the cache of the server and has to be sent in full
+                    Some(0x04) => {
+                        let payload =
+                            full_auth(stream, server_public_key, password, nonce).await?;
 
                         stream.write_packet(&*payload);
                         stream.flush().await?;
@@ -54,14 +114,24 @@ impl AuthPlugin {
                     }
 
                     v => {
-                        Err(err_protocol!("unexpected result from fast authentication 0x{:x} when expecting 0x03 (AUTH_OK) or 0x04 (AUTH_CONTINUE)", v))
+                        Err(err_protocol!("unexpected result from fast authentication {:x?} when expecting 0x03 (AUTH_OK) or 0x04 (AUTH_CONTINUE)", v))
                     }
                 }
             }
 
+            // the public key of the server, which was requested by `scramble`
+            AuthPlugin::Sha256Password if header == 0x01 && server_public_key.is_none() => {
+                let payload = encrypt_rsa(&packet[1..], password, nonce)?;
+
+                stream.write_packet(&*payload);
+                stream.flush().await?;
+
+                Ok(false)
+            }
+
             _ => Err(err_protocol!(
                 "unexpected packet 0x{:02x} for auth plugin '{}' during authentication",
-                packet[0],
+                header,
                 self.name()
             )),
         }
@@ -123,36 +193,66 @@ fn scramble_sha256(
     pw_hash
 }
 
-async fn encrypt_rsa<'s>(
-    stream: &'s mut MySqlStream,
-    public_key_request_id: u8,
-    password: &'s str,
-    nonce: &'s Chain<Bytes, Bytes>,
+async fn full_auth(
+    stream: &mut MySqlStream,
+    server_public_key: Option<&[u8]>,
+    password: &str,
+    nonce: &Chain<Bytes, Bytes>,
 ) -> Result<Vec<u8>, Error> {
-    // https://mariadb.com/kb/en/caching_sha2_password-authentication-plugin/
+    // https://dev.mysql.com/doc/dev/mysql-server/8.0.12/page_caching_sha2_authentication_exchanges.html
 
     if stream.is_tls() {
         // If in a TLS stream, send the password directly in clear text
         return Ok(to_asciz(password));
     }
 
+    if let Some(key) = server_public_key {
+        // the key was configured by the user, so there is no need to trust the one the server
+        // would send us over the insecure connection
+        return encrypt_rsa(key, password, nonce);
+    }
+
     // client sends a public key request
-    stream.write_packet(&[public_key_request_id][..]);
+    stream.write_packet(&[0x02][..]);
     stream.flush().await?;
 
     // server sends a public key response
     let packet = stream.recv_packet().await?;
-    let rsa_pub_key = &packet[1..];
 
+    match packet.first() {
+        Some(0x01) => {}
+
+        Some(header) => {
+            return Err(err_protocol!(
+                "expected 0x01 (AUTH_MORE_DATA) with the public key of the server but found 0x{:02x}",
+                header
+            ));
+        }
+
+        None => {
+            return Err(err_protocol!(
+                "expected 0x01 (AUTH_MORE_DATA) with the public key of the server but found an empty packet"
+            ));
+        }
+    }
+
+    encrypt_rsa(&packet[1..], password, nonce)
+}
+
+fn encrypt_rsa(
+    rsa_pub_key: &[u8],
+    password: &str,
+    nonce: &Chain<Bytes, Bytes>,
+) -> Result<Vec<u8>, Error> {
     // xor the password with the given nonce
     let mut pass = to_asciz(password);
 
     let (a, b) = (nonce.first_ref(), nonce.last_ref());
     let mut nonce = Vec::with_capacity(a.len() + b.len());
-    nonce.extend_from_slice(&*a);
-    nonce.extend_from_slice(&*b);
+    nonce.extend_from_slice(a);
+    nonce.extend_from_slice(b);
 
-    xor_eq(&mut pass, &*nonce);
+    xor_eq(&mut pass, &nonce);
 
     // client sends an RSA encrypted password
     let pkey = parse_rsa_pub_key(rsa_pub_key)?;
@@ -183,9 +283,11 @@ fn to_asciz(s: &str) -> Vec<u8> {
 fn parse_rsa_pub_key(key: &[u8]) -> Result<RsaPublicKey, Error> {
     let pem = std::str::from_utf8(key).map_err(Error::protocol)?;
 
-    // This takes advantage of the knowledge that we know
-    // we are receiving a PKCS#8 RSA Public Key at all
-    // times from MySQL
+    // MySQL always sends a PKCS#8 RSA Public Key, but a key configured with
+    // `server_rsa_public_key_path` might have been written in the PKCS#1 format
+    if pem.contains("BEGIN RSA PUBLIC KEY") {
+        return RsaPublicKey::from_pkcs1_pem(pem).map_err(Error::protocol);
+    }
 
-    RsaPublicKey::from_public_key_pem(&pem).map_err(Error::protocol)
+    RsaPublicKey::from_public_key_pem(pem).map_err(Error::protocol)
 }
@@ -0,0 +1,482 @@
+use std::cmp;
+use std::io::{self, Read, Write};
+use std::mem::replace;
+use std::ops::{Deref, DerefMut};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use bytes::{Buf, Bytes, BytesMut};
+use flate2::read::ZlibDecoder;
+use flate2::write::ZlibEncoder;
+use futures_util::ready;
+use sqlx_rt::{AsyncRead, AsyncWrite};
+
+use crate::error::Error;
+use crate::mysql::MySqlCompression;
+use crate::net::{poll_read_chunk, read_capacity, read_out, PollReadBuf, PollReadOut};
+
+// https://dev.mysql.com/doc/dev/mysql-server/latest/page_protocol_basic_compression.html
+
+// compressed length, sequence ID and uncompressed length
+const HEADER_SIZE: usize = 7;
+
+// payloads smaller than this are sent uncompressed, as libmysql and the server do
+const MIN_COMPRESS_LENGTH: usize = 50;
+
+// the length of a payload is an int<3>
+const MAX_PAYLOAD_LENGTH: usize = 0xFF_FF_FF;
+
+/// A stream which may be compressed with the compressed protocol after authentication.
+pub enum MaybeCompressedStream<S>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    Raw(S),
+    Compressed(CompressedStream<S>),
+    Upgrading,
+}
+
+impl<S> MaybeCompressedStream<S>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    /// Compress all further traffic with `compression`.
+    pub fn compress(&mut self, compression: MySqlCompression) -> Result<(), Error> {
+        match replace(self, MaybeCompressedStream::Upgrading) {
+            MaybeCompressedStream::Raw(inner) => {
+                *self = MaybeCompressedStream::Compressed(CompressedStream {
+                    inner,
+                    compression,
+                    sequence_id: 0,
+                    read_buf: BytesMut::new(),
+                    decompressed: Bytes::new(),
+                    write_buf: Bytes::new(),
+                });
+
+                Ok(())
+            }
+
+            MaybeCompressedStream::Compressed(_) => Err(err_protocol!(
+                "attempted to compress a connection that is already compressed"
+            )),
+
+            MaybeCompressedStream::Upgrading => {
+                Err(Error::Io(io::ErrorKind::ConnectionAborted.into()))
+            }
+        }
+    }
+
+    /// Start a new command, which numbers its compressed packets from zero, independently of
+    /// the packets they contain.
+    pub fn reset_sequence_id(&mut self) {
+        if let MaybeCompressedStream::Compressed(s) = self {
+            s.sequence_id = 0;
+        }
+    }
+}
+
+/// Wraps `S` so that everything written is sent as compressed packets, and everything read is
+/// decompressed from such packets.
+pub struct CompressedStream<S> {
+    inner: S,
+    compression: MySqlCompression,
+
+    // sequence ID of the next compressed packet sent to the server
+    sequence_id: u8,
+
+    // compressed data read from `inner`, not yet forming a complete packet
+    read_buf: BytesMut,
+
+    // decompressed data not yet returned to the reader
+    decompressed: Bytes,
+
+    // compressed packets not yet written to `inner`
+    write_buf: Bytes,
+}
+
+impl<S> CompressedStream<S>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    // write out any pending compressed packets
+    fn poll_write_buf(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        while !self.write_buf.is_empty() {
+            let written = ready!(Pin::new(&mut self.inner).poll_write(cx, &self.write_buf))?;
+
+            if written == 0 {
+                return Poll::Ready(Err(io::ErrorKind::WriteZero.into()));
+            }
+
+            self.write_buf.advance(written);
+        }
+
+        Poll::Ready(Ok(()))
+    }
+}
+
+// encode `payload` as a compressed packet, sent uncompressed if compressing doesn't pay off
+fn encode_packet(
+    compression: MySqlCompression,
+    sequence_id: u8,
+    payload: &[u8],
+) -> io::Result<Vec<u8>> {
+    debug_assert!(payload.len() <= MAX_PAYLOAD_LENGTH);
+
+    let compressed = if payload.len() >= MIN_COMPRESS_LENGTH {
+        Some(compress(compression, payload)?).filter(|compressed| compressed.len() < payload.len())
+    } else {
+        None
+    };
+
+    // an uncompressed length of 0 marks a payload which is not compressed
+    let (body, uncompressed_len) = match &compressed {
+        Some(compressed) => (&compressed[..], payload.len()),
+        None => (payload, 0),
+    };
+
+    let mut packet = Vec::with_capacity(HEADER_SIZE + body.len());
+    packet.extend_from_slice(&(body.len() as u32).to_le_bytes()[..3]);
+    packet.push(sequence_id);
+    packet.extend_from_slice(&(uncompressed_len as u32).to_le_bytes()[..3]);
+    packet.extend_from_slice(body);
+
+    Ok(packet)
+}
+
+// decode the next compressed packet from `buf`, if it has been received in full, returning
+// its sequence ID and payload
+fn decode_packet(
+    compression: MySqlCompression,
+    buf: &mut BytesMut,
+) -> io::Result<Option<(u8, Bytes)>> {
+    if buf.len() < HEADER_SIZE {
+        return Ok(None);
+    }
+
+    let compressed_len = u32::from_le_bytes([buf[0], buf[1], buf[2], 0]) as usize;
+    let sequence_id = buf[3];
+    let uncompressed_len = u32::from_le_bytes([buf[4], buf[5], buf[6], 0]) as usize;
+
+    if buf.len() < HEADER_SIZE + compressed_len {
+        return Ok(None);
+    }
+
+    buf.advance(HEADER_SIZE);
+    let body = buf.split_to(compressed_len).freeze();
+
+    if uncompressed_len == 0 {
+        return Ok(Some((sequence_id, body)));
+    }
+
+    let payload = decompress(compression, &body, uncompressed_len)?;
+
+    if payload.len() != uncompressed_len {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!(
+                "compressed packet expanded to {} bytes instead of {}",
+                payload.len(),
+                uncompressed_len
+            ),
+        ));
+    }
+
+    Ok(Some((sequence_id, payload.into())))
+}
+
+fn compress(compression: MySqlCompression, data: &[u8]) -> io::Result<Vec<u8>> {
+    match compression {
+        MySqlCompression::Zlib => {
+            let mut encoder = ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+            encoder.write_all(data)?;
+            encoder.finish()
+        }
+
+        MySqlCompression::Zstd { level } => zstd::bulk::compress(data, level.into()),
+    }
+}
+
+fn decompress(compression: MySqlCompression, data: &[u8], len: usize) -> io::Result<Vec<u8>> {
+    match compression {
+        MySqlCompression::Zlib => {
+            let mut decompressed = Vec::with_capacity(len);
+
+            // one byte more than expected is enough to reject the packet, however far it expands
+            ZlibDecoder::new(data)
+                .take(len as u64 + 1)
+                .read_to_end(&mut decompressed)?;
+
+            Ok(decompressed)
+        }
+
+        MySqlCompression::Zstd { .. } => zstd::bulk::decompress(data, len),
+    }
+}
+
+impl<S> AsyncRead for CompressedStream<S>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut PollReadBuf<'_>,
+    ) -> Poll<io::Result<PollReadOut>> {
+        let this = &mut *self;
+
+        while this.decompressed.is_empty() {
+            if let Some((sequence_id, payload)) =
+                decode_packet(this.compression, &mut this.read_buf)?
+            {
+                // the next packet we send continues the numbering of the server
+                this.sequence_id = sequence_id.wrapping_add(1);
+                this.decompressed = payload;
+
+                continue;
+            }
+
+            let mut chunk = [0; 8192];
+            let read = ready!(poll_read_chunk(&mut this.inner, cx, &mut chunk))?;
+
+            if read == 0 {
+                if !this.read_buf.is_empty() {
+                    return Poll::Ready(Err(io::ErrorKind::UnexpectedEof.into()));
+                }
+
+                // end of stream
+                return read_out(buf, &[]);
+            }
+
+            this.read_buf.extend_from_slice(&chunk[..read]);
+        }
+
+        let len = cmp::min(read_capacity(buf), this.decompressed.len());
+        let out = read_out(buf, &this.decompressed[..len]);
+        this.decompressed.advance(len);
+
+        out
+    }
+}
+
+impl<S> AsyncWrite for CompressedStream<S>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = &mut *self;
+
+        ready!(this.poll_write_buf(cx))?;
+
+        if buf.is_empty() {
+            return Poll::Ready(Ok(0));
+        }
+
+        let len = cmp::min(buf.len(), MAX_PAYLOAD_LENGTH);
+        let packet = encode_packet(this.compression, this.sequence_id, &buf[..len])?;
+
+        this.sequence_id = this.sequence_id.wrapping_add(1);
+        this.write_buf = packet.into();
+
+        // the data is accepted once it has been compressed; it is sent by the next write or flush
+        let _ = this.poll_write_buf(cx)?;
+
+        Poll::Ready(Ok(len))
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        ready!(self.poll_write_buf(cx))?;
+
+        Pin::new(&mut self.inner).poll_flush(cx)
+    }
+
+    #[cfg(feature = "_rt-tokio")]
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        ready!(self.poll_write_buf(cx))?;
+
+        Pin::new(&mut self.inner).poll_shutdown(cx)
+    }
+
+    #[cfg(feature = "_rt-async-std")]
+    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        ready!(self.poll_write_buf(cx))?;
+
+        Pin::new(&mut self.inner).poll_close(cx)
+    }
+}
+
+impl<S> AsyncRead for MaybeCompressedStream<S>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut PollReadBuf<'_>,
+    ) -> Poll<io::Result<PollReadOut>> {
+        match &mut *self {
+            MaybeCompressedStream::Raw(s) => Pin::new(s).poll_read(cx, buf),
+            MaybeCompressedStream::Compressed(s) => Pin::new(s).poll_read(cx, buf),
+
+            MaybeCompressedStream::Upgrading => {
+                Poll::Ready(Err(io::ErrorKind::ConnectionAborted.into()))
+            }
+        }
+    }
+}
+
+impl<S> AsyncWrite for MaybeCompressedStream<S>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        match &mut *self {
+            MaybeCompressedStream::Raw(s) => Pin::new(s).poll_write(cx, buf),
+            MaybeCompressedStream::Compressed(s) => Pin::new(s).poll_write(cx, buf),
+
+            MaybeCompressedStream::Upgrading => {
+                Poll::Ready(Err(io::ErrorKind::ConnectionAborted.into()))
+            }
+        }
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match &mut *self {
+            MaybeCompressedStream::Raw(s) => Pin::new(s).poll_flush(cx),
+            MaybeCompressedStream::Compressed(s) => Pin::new(s).poll_flush(cx),
+
+            MaybeCompressedStream::Upgrading => {
+                Poll::Ready(Err(io::ErrorKind::ConnectionAborted.into()))
+            }
+        }
+    }
+
+    #[cfg(feature = "_rt-tokio")]
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match &mut *self {
+            MaybeCompressedStream::Raw(s) => Pin::new(s).poll_shutdown(cx),
+            MaybeCompressedStream::Compressed(s) => Pin::new(s).poll_shutdown(cx),
+
+            MaybeCompressedStream::Upgrading => {
+                Poll::Ready(Err(io::ErrorKind::ConnectionAborted.into()))
+            }
+        }
+    }
+
+    #[cfg(feature = "_rt-async-std")]
+    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match &mut *self {
+            MaybeCompressedStream::Raw(s) => Pin::new(s).poll_close(cx),
+            MaybeCompressedStream::Compressed(s) => Pin::new(s).poll_close(cx),
+
+            MaybeCompressedStream::Upgrading => {
+                Poll::Ready(Err(io::ErrorKind::ConnectionAborted.into()))
+            }
+        }
+    }
+}
+
+impl<S> Deref for MaybeCompressedStream<S>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    type Target = S;
+
+    fn deref(&self) -> &Self::Target {
+        match self {
+            MaybeCompressedStream::Raw(s) => s,
+            MaybeCompressedStream::Compressed(s) => &s.inner,
+
+            MaybeCompressedStream::Upgrading => {
+                panic!("{}", io::Error::from(io::ErrorKind::ConnectionAborted))
+            }
+        }
+    }
+}
+
+impl<S> DerefMut for MaybeCompressedStream<S>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        match self {
+            MaybeCompressedStream::Raw(s) => s,
+            MaybeCompressedStream::Compressed(s) => &mut s.inner,
+
+            MaybeCompressedStream::Upgrading => {
+                panic!("{}", io::Error::from(io::ErrorKind::ConnectionAborted))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip(compression: MySqlCompression, payload: &[u8]) -> Vec<u8> {
+        let packet = encode_packet(compression, 5, payload).unwrap();
+
+        // the packet is only decoded once it has been received in full
+        let mut buf = BytesMut::from(&packet[..packet.len() - 1]);
+        assert!(decode_packet(compression, &mut buf).unwrap().is_none());
+
+        let mut buf = BytesMut::from(&packet[..]);
+        let (sequence_id, decoded) = decode_packet(compression, &mut buf).unwrap().unwrap();
+
+        assert_eq!(sequence_id, 5);
+        assert_eq!(&decoded[..], payload);
+        assert!(buf.is_empty());
+
+        packet
+    }
+
+    #[test]
+    fn it_compresses_packets() {
+        let payload = "SELECT 'sqlx' ".repeat(1000);
+
+        for compression in [MySqlCompression::Zlib, MySqlCompression::Zstd { level: 3 }] {
+            let packet = round_trip(compression, payload.as_bytes());
+
+            assert!(packet.len() < payload.len() / 10);
+            assert_eq!(&packet[4..7], &(payload.len() as u32).to_le_bytes()[..3]);
+        }
+    }
+
+    #[test]
+    fn it_sends_small_packets_uncompressed() {
+        let payload = b"\x03SELECT 1";
+
+        for compression in [MySqlCompression::Zlib, MySqlCompression::Zstd { level: 3 }] {
+            let packet = round_trip(compression, payload);
+
+            assert_eq!(&packet[..], b"\x09\x00\x00\x05\x00\x00\x00\x03SELECT 1");
+        }
+    }
+
+    #[test]
+    fn it_rejects_packets_expanding_past_their_length() {
+        let body = compress(MySqlCompression::Zlib, &[0; 1_000_000]).unwrap();
+
+        let mut packet = BytesMut::new();
+        packet.extend_from_slice(&(body.len() as u32).to_le_bytes()[..3]);
+        packet.extend_from_slice(&[0]);
+        packet.extend_from_slice(&100_u32.to_le_bytes()[..3]);
+        packet.extend_from_slice(&body);
+
+        let err = decode_packet(MySqlCompression::Zlib, &mut packet).unwrap_err();
+
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(
+            err.to_string(),
+            "compressed packet expanded to 101 bytes instead of 100"
+        );
+    }
+}
@@ -0,0 +1,46 @@
+use ed25519_dalek::hazmat::{raw_sign, ExpandedSecretKey};
+use ed25519_dalek::VerifyingKey;
+use sha2::{Digest, Sha512};
+
+// https://mariadb.com/kb/en/authentication-plugin-ed25519/
+// https://datatracker.ietf.org/doc/html/rfc8032#section-5.1.6
+
+/// Sign the nonce sent by the server for the `client_ed25519` authentication plugin.
+///
+/// MariaDB uses the password in place of the 32-byte seed of the private key, so the key
+/// is expanded from an input of any length, which is why this signs with an
+/// [`ExpandedSecretKey`] rather than a `SigningKey`.
+pub(super) fn sign(password: &[u8], nonce: &[u8]) -> Vec<u8> {
+    let mut expanded = [0; 64];
+    expanded.copy_from_slice(&Sha512::digest(password));
+
+    // clamps the lower half into the scalar, the upper half is the prefix hashed with the nonce
+    let secret_key = ExpandedSecretKey::from_bytes(&expanded);
+    expanded.fill(0);
+
+    let public_key = VerifyingKey::from(&secret_key);
+
+    raw_sign::<Sha512>(&secret_key, nonce, &public_key)
+        .to_bytes()
+        .to_vec()
+}
+
+#[test]
+fn test_sign() {
+    // https://datatracker.ietf.org/doc/html/rfc8032#section-7.1, with the seeds as passwords
+    let seed =
+        hex::decode("9d61b19deffd5a60ba844af492ec2cc44449c5697b326919703bac031cae7f60").unwrap();
+
+    assert_eq!(
+        hex::encode(sign(&seed, b"")),
+        "e5564300c360ac729086e2cc806e828a84877f1eb8e5d974d873e065224901555fb8821590a33bacc61e39701cf9b46bd25bf5f0595bbe24655141438e7a100b"
+    );
+
+    let seed =
+        hex::decode("4ccd089b28ff96da9db6c346ec114e0f5b8a319f35aba624da8cf6ed4fb8a6fb").unwrap();
+
+    assert_eq!(
+        hex::encode(sign(&seed, &[0x72])),
+        "92a009a9f0d4cab8720e820b5f642540a2b27b5416503f8fb3762223ebdb69da085ac1e43e15996e458f3613d0f11d8c387b2eaeb4302aeeb00d291612bb0c00"
+    );
+}
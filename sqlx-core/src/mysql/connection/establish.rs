@@ -2,13 +2,16 @@ use bytes::buf::Buf;
 use bytes::Bytes;
 
 use crate::common::StatementCache;
+use crate::connection::LogSettings;
 use crate::error::Error;
 use crate::mysql::connection::{tls, MySqlStream, MAX_PACKET_SIZE};
 use crate::mysql::protocol::connect::{
     AuthSwitchRequest, AuthSwitchResponse, Handshake, HandshakeResponse,
 };
 use crate::mysql::protocol::Capabilities;
-use crate::mysql::{MySqlConnectOptions, MySqlConnection, MySqlSslMode};
+use crate::mysql::{
+    MySqlCompression, MySqlConnectOptions, MySqlConnection, MySqlServerFlavor, MySqlSslMode,
+};
 
 impl MySqlConnection {
     pub(crate) async fn establish(options: &MySqlConnectOptions) -> Result<Self, Error> {
@@ -22,34 +25,11 @@ impl MySqlConnection {
         let mut plugin = handshake.auth_plugin;
         let mut nonce = handshake.auth_plugin_data;
 
-        // FIXME: server version parse is a bit ugly
-        // expecting MAJOR.MINOR.PATCH
-
-        let mut server_version = handshake.server_version.split('.');
-
-        let server_version_major: u16 = server_version
-            .next()
-            .unwrap_or_default()
-            .parse()
-            .unwrap_or(0);
-
-        let server_version_minor: u16 = server_version
-            .next()
-            .unwrap_or_default()
-            .parse()
-            .unwrap_or(0);
+        let (flavor, server_version) =
+            MySqlServerFlavor::detect(&handshake.server_version, handshake.server_capabilities);
 
-        let server_version_patch: u16 = server_version
-            .next()
-            .unwrap_or_default()
-            .parse()
-            .unwrap_or(0);
-
-        stream.server_version = (
-            server_version_major,
-            server_version_minor,
-            server_version_patch,
-        );
+        stream.flavor = flavor;
+        stream.server_version = server_version;
 
         stream.capabilities &= handshake.server_capabilities;
         stream.capabilities |= Capabilities::PROTOCOL_41;
@@ -88,8 +68,25 @@ impl MySqlConnection {
             tls::maybe_upgrade(&mut stream, options).await?
         }
 
+        let server_public_key = match &options.server_rsa_public_key_path {
+            Some(path) => Some(sqlx_rt::fs::read(path).await.map_err(|e| {
+                Error::Configuration(
+                    format!(
+                        "failed to read server RSA public key from {}: {}",
+                        path.display(),
+                        e
+                    )
+                    .into(),
+                )
+            })?),
+
+            None => None,
+        };
+
+        let server_public_key = server_public_key.as_deref();
+
         let auth_response = if let (Some(plugin), Some(password)) = (plugin, &options.password) {
-            Some(plugin.scramble(&mut stream, password, &nonce).await?)
+            Some(plugin.scramble(&stream, server_public_key, password, &nonce)?)
         } else {
             None
         };
@@ -101,6 +98,11 @@ impl MySqlConnection {
             database: options.database.as_deref(),
             auth_plugin: plugin,
             auth_response: auth_response.as_deref(),
+            connect_attrs: &options.connect_attrs,
+            zstd_compression_level: match options.compression {
+                Some(MySqlCompression::Zstd { level }) => level,
+                _ => MySqlCompression::DEFAULT_ZSTD_LEVEL,
+            },
         });
 
         stream.flush().await?;
@@ -120,14 +122,12 @@ impl MySqlConnection {
                     plugin = Some(switch.plugin);
                     nonce = switch.data.chain(Bytes::new());
 
-                    let response = switch
-                        .plugin
-                        .scramble(
-                            &mut stream,
-                            options.password.as_deref().unwrap_or_default(),
-                            &nonce,
-                        )
-                        .await?;
+                    let response = switch.plugin.scramble(
+                        &stream,
+                        server_public_key,
+                        options.password.as_deref().unwrap_or_default(),
+                        &nonce,
+                    )?;
 
                     stream.write_packet(AuthSwitchResponse(response));
                     stream.flush().await?;
@@ -135,7 +135,10 @@ impl MySqlConnection {
 
                 id => {
                     if let (Some(plugin), Some(password)) = (plugin, &options.password) {
-                        if plugin.handle(&mut stream, packet, password, &nonce).await? {
+                        if plugin
+                            .handle(&mut stream, server_public_key, packet, password, &nonce)
+                            .await?
+                        {
                             // plugin signaled authentication is ok
                             break;
                         }
@@ -151,11 +154,41 @@ impl MySqlConnection {
             }
         }
 
+        // all further packets are compressed, if both sides agreed to it in the handshake
+        #[cfg(feature = "mysql-compression")]
+        if let Some(compression) = options.compression {
+            if stream
+                .capabilities
+                .intersects(Capabilities::COMPRESS | Capabilities::ZSTD_COMPRESSION_ALGORITHM)
+            {
+                stream.compress(compression)?;
+            }
+        }
+
         Ok(Self {
             stream,
             transaction_depth: 0,
             cache_statement: StatementCache::new(options.statement_cache_capacity),
-            log_settings: options.log_settings.clone(),
+            log_settings: log_settings(options),
+            local_infile_handler: options
+                .local_infile_handler
+                .as_ref()
+                .filter(|_| options.enable_local_infile)
+                .map(|handler| handler.0.clone()),
+            zero_dates: options.zero_dates,
         })
     }
 }
+
+fn log_settings(options: &MySqlConnectOptions) -> LogSettings {
+    #[allow(unused_mut)]
+    let mut settings = options.log_settings.for_connection();
+
+    #[cfg(feature = "tracing")]
+    match &options.socket {
+        Some(socket) => settings.trace_server("mysql", socket.to_str(), None),
+        None => settings.trace_server("mysql", Some(&options.host), Some(options.port)),
+    }
+
+    settings
+}
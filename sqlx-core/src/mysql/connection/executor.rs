@@ -1,9 +1,10 @@
 use super::MySqlStream;
+use crate::common::PreparedStatement;
 use crate::describe::Describe;
 use crate::error::Error;
 use crate::executor::{Execute, Executor};
 use crate::ext::ustr::UStr;
-use crate::logger::QueryLogger;
+use crate::logger::{QueryLogger, QuerySpan};
 use crate::mysql::connection::stream::Waiting;
 use crate::mysql::io::MySqlBufExt;
 use crate::mysql::protocol::response::Status;
@@ -13,8 +14,8 @@ use crate::mysql::protocol::statement::{
 use crate::mysql::protocol::text::{ColumnDefinition, ColumnFlags, Query, TextRow};
 use crate::mysql::statement::{MySqlStatement, MySqlStatementMetadata};
 use crate::mysql::{
-    MySql, MySqlArguments, MySqlColumn, MySqlConnection, MySqlQueryResult, MySqlRow, MySqlTypeInfo,
-    MySqlValueFormat,
+    MySql, MySqlArguments, MySqlColumn, MySqlConnection, MySqlQueryResult, MySqlResultSets,
+    MySqlRow, MySqlTypeInfo, MySqlValueFormat,
 };
 use crate::HashMap;
 use either::Either;
@@ -24,15 +25,28 @@ use futures_core::Stream;
 use futures_util::{pin_mut, TryStreamExt};
 use std::{borrow::Cow, sync::Arc};
 
+/// An item of the response to a query, as produced by [`MySqlConnection::run`].
+pub(crate) enum Step {
+    /// The start of a result set with rows, and its columns.
+    Columns(Arc<Vec<MySqlColumn>>),
+
+    Row(MySqlRow),
+
+    /// The end of a result set, or the result of a statement that doesn't return rows.
+    Done(MySqlQueryResult),
+}
+
 impl MySqlConnection {
     async fn get_or_prepare<'c>(
         &mut self,
         sql: &str,
         persistent: bool,
-    ) -> Result<(u32, MySqlStatementMetadata), Error> {
-        if let Some(statement) = self.cache_statement.get_mut(sql) {
+    ) -> Result<(PreparedStatement<u32>, MySqlStatementMetadata), Error> {
+        if let Some((prepared, metadata)) = self.cache_statement.get_mut(sql) {
+            prepared.hit();
+
             // <MySqlStatementMetadata> is internally reference-counted
-            return Ok((*statement).clone());
+            return Ok((prepared.clone(), metadata.clone()));
         }
 
         // https://dev.mysql.com/doc/internals/en/com-stmt-prepare.html
@@ -65,7 +79,7 @@ impl MySqlConnection {
             Default::default()
         };
 
-        let id = ok.statement_id;
+        let prepared = PreparedStatement::new(self.log_settings.connection_id, ok.statement_id);
         let metadata = MySqlStatementMetadata {
             parameters: ok.params as usize,
             columns: Arc::new(columns),
@@ -74,12 +88,20 @@ impl MySqlConnection {
 
         if persistent && self.cache_statement.is_enabled() {
             // in case of the cache being full, close the least recently used statement
-            if let Some((id, _)) = self.cache_statement.insert(sql, (id, metadata.clone())) {
-                self.stream.send_packet(StmtClose { statement: id }).await?;
+            let cached = (prepared.clone(), metadata.clone());
+
+            if let Some((evicted, _)) = self.cache_statement.insert(sql, cached) {
+                evicted.close();
+
+                self.stream
+                    .send_packet(StmtClose {
+                        statement: evicted.id(),
+                    })
+                    .await?;
             }
         }
 
-        Ok((id, metadata))
+        Ok((prepared, metadata))
     }
 
     #[allow(clippy::needless_lifetimes)]
@@ -88,9 +110,16 @@ impl MySqlConnection {
         sql: &'q str,
         arguments: Option<MySqlArguments>,
         persistent: bool,
-    ) -> Result<impl Stream<Item = Result<Either<MySqlQueryResult, MySqlRow>, Error>> + 'e, Error>
-    {
-        let mut logger = QueryLogger::new(sql, self.log_settings.clone());
+        prepared: Option<(PreparedStatement<u32>, MySqlStatementMetadata)>,
+        mut logger: QueryLogger<'q>,
+    ) -> Result<impl Stream<Item = Result<Step, Error>> + 'e, Error> {
+        // a statement prepared by this connection is executed directly, unless it was closed
+        let prepared = match prepared {
+            Some((prepared, metadata)) => prepared
+                .reuse(self.log_settings.connection_id)?
+                .map(|id| (id, metadata)),
+            None => None,
+        };
 
         self.stream.wait_until_ready().await?;
         self.stream.waiting.push_back(Waiting::Result);
@@ -102,11 +131,13 @@ impl MySqlConnection {
             let mut columns = Arc::new(Vec::new());
 
             let (mut column_names, format, mut needs_metadata) = if let Some(arguments) = arguments {
-                let (id, metadata) = self.get_or_prepare(
-                    sql,
-                    persistent,
-                )
-                .await?;
+                let (id, metadata) = match prepared {
+                    Some(prepared) => prepared,
+                    None => {
+                        let (prepared, metadata) = self.get_or_prepare(sql, persistent).await?;
+                        (prepared.id(), metadata)
+                    }
+                };
 
                 // https://dev.mysql.com/doc/internals/en/com-stmt-execute.html
                 self.stream
@@ -116,7 +147,10 @@ impl MySqlConnection {
                     })
                     .await?;
 
-                (metadata.column_names, MySqlValueFormat::Binary, false)
+                // the columns of the result sets of a `CALL` aren't known when it's prepared
+                let needs_metadata = metadata.columns.is_empty();
+
+                (metadata.column_names, MySqlValueFormat::Binary, needs_metadata)
             } else {
                 // https://dev.mysql.com/doc/internals/en/com-query.html
                 self.stream.send_packet(Query(sql)).await?;
@@ -126,9 +160,22 @@ impl MySqlConnection {
 
             loop {
                 // query response is a meta-packet which may be one of:
-                //  Ok, Err, ResultSet, or (unhandled) LocalInfileRequest
+                //  Ok, Err, ResultSet, or LocalInfileRequest
                 let mut packet = self.stream.recv_packet().await?;
 
+                if packet[0] == 0xfb {
+                    // the server asks for the content of a file for `LOAD DATA LOCAL INFILE`,
+                    // and responds as usual once it has been sent
+                    if let Err(error) = self.send_local_infile(&packet[1..]).await {
+                        // skip the response, so the connection can still be used
+                        let _ = self.stream.wait_until_ready().await;
+
+                        return Err(error);
+                    }
+
+                    continue;
+                }
+
                 if packet[0] == 0x00 || packet[0] == 0xff {
                     // first packet in a query response is OK or ERR
                     // this indicates either a successful query with no rows at all or a failed query
@@ -141,7 +188,7 @@ impl MySqlConnection {
                         last_insert_id: ok.last_insert_id,
                     };
 
-                    r#yield!(Either::Left(done));
+                    r#yield!(Step::Done(done));
 
                     if ok.status.contains(Status::SERVER_MORE_RESULTS_EXISTS) {
                         // more result sets exist, continue to the next one
@@ -167,6 +214,8 @@ impl MySqlConnection {
                     recv_result_columns(&mut self.stream, num_columns, Arc::make_mut(&mut columns)).await?;
                 }
 
+                r#yield!(Step::Columns(Arc::clone(&columns)));
+
                 // finally, there will be none or many result-rows
                 loop {
                     let packet = self.stream.recv_packet().await?;
@@ -174,7 +223,7 @@ impl MySqlConnection {
                     if packet[0] == 0xfe && packet.len() < 9 {
                         let eof = packet.eof(self.stream.capabilities)?;
 
-                        r#yield!(Either::Left(MySqlQueryResult {
+                        r#yield!(Step::Done(MySqlQueryResult {
                             rows_affected: 0,
                             last_insert_id: 0,
                         }));
@@ -194,11 +243,13 @@ impl MySqlConnection {
                         MySqlValueFormat::Text => packet.decode_with::<TextRow, _>(&columns)?.0,
                     };
 
-                    let v = Either::Right(MySqlRow {
+                    let v = Step::Row(MySqlRow {
                         row,
                         format,
                         columns: Arc::clone(&columns),
                         column_names: Arc::clone(&column_names),
+                        zero_dates: self.zero_dates,
+                        context: logger.context().clone(),
                     });
 
                     logger.increment_rows_returned();
@@ -210,6 +261,87 @@ impl MySqlConnection {
     }
 }
 
+impl MySqlConnection {
+    /// Execute the query and return a cursor over each of its result sets, such as those
+    /// returned by `CALL` for a stored procedure, or by a query with several statements.
+    ///
+    /// Each [`MySqlResultSet`](crate::mysql::MySqlResultSet) has its own columns and streams its rows. Statements that
+    /// don't return rows, including the status at the end of a `CALL`, produce a result set
+    /// without columns or rows.
+    ///
+    /// Rows that are not read, including those of any result sets not yet reached when the
+    /// cursor is dropped, are skipped before the connection is used again.
+    ///
+    /// ```rust,no_run
+    /// # async fn example() -> Result<(), sqlx_core::error::Error> {
+    /// use futures_util::TryStreamExt;
+    /// use sqlx_core::connection::Connection;
+    /// use sqlx_core::mysql::MySqlConnection;
+    /// use sqlx_core::row::Row;
+    ///
+    /// let mut conn = MySqlConnection::connect("mysql://localhost/sqlx").await?;
+    /// let mut results = conn.fetch_multi("CALL my_proc()");
+    ///
+    /// while let Some(mut result) = results.next().await? {
+    ///     println!("{} columns", result.columns().len());
+    ///
+    ///     while let Some(row) = result.try_next().await? {
+    ///         let value: i64 = row.try_get(0)?;
+    ///         println!("{}", value);
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn fetch_multi<'c, 'q: 'c, E>(&'c mut self, mut query: E) -> MySqlResultSets<'c>
+    where
+        E: Execute<'q, MySql> + 'q,
+    {
+        if let Some(error) = query.take_error() {
+            return MySqlResultSets::new(Box::pin(futures_util::stream::once(async move {
+                Err(error)
+            })));
+        }
+
+        let sql = query.sql();
+        let prepared = query
+            .statement()
+            .and_then(|s| Some((s.prepared.clone()?, s.metadata.clone())));
+        let arguments = query.take_arguments();
+        let persistent = query.persistent();
+
+        let logger = self.query_logger(sql, &arguments, persistent);
+        let errors = logger.errors();
+
+        let stream = try_stream! {
+            let s = self.run(sql, arguments, persistent, prepared, logger).await?;
+            pin_mut!(s);
+
+            while let Some(v) = s.try_next().await? {
+                r#yield!(v);
+            }
+
+            Ok(())
+        };
+
+        MySqlResultSets::new(Box::pin(stream.map_err(move |error| errors.record(error))))
+    }
+
+    fn query_logger<'q>(
+        &self,
+        sql: &'q str,
+        arguments: &Option<MySqlArguments>,
+        persistent: bool,
+    ) -> QueryLogger<'q> {
+        let parameters = arguments
+            .as_ref()
+            .map_or(0, |arguments| arguments.types.len());
+        let span = QuerySpan::new(sql, &self.log_settings);
+
+        QueryLogger::new(sql, parameters, persistent, self.log_settings.clone(), span)
+    }
+}
+
 impl<'c> Executor<'c> for &'c mut MySqlConnection {
     type Database = MySql;
 
@@ -221,20 +353,36 @@ impl<'c> Executor<'c> for &'c mut MySqlConnection {
         'c: 'e,
         E: Execute<'q, Self::Database>,
     {
+        if let Some(error) = query.take_error() {
+            return Box::pin(futures_util::stream::once(async move { Err(error) }));
+        }
+
         let sql = query.sql();
+        let prepared = query
+            .statement()
+            .and_then(|s| Some((s.prepared.clone()?, s.metadata.clone())));
         let arguments = query.take_arguments();
         let persistent = query.persistent();
 
-        Box::pin(try_stream! {
-            let s = self.run(sql, arguments, persistent).await?;
+        let logger = self.query_logger(sql, &arguments, persistent);
+        let errors = logger.errors();
+
+        let stream = try_stream! {
+            let s = self.run(sql, arguments, persistent, prepared, logger).await?;
             pin_mut!(s);
 
             while let Some(v) = s.try_next().await? {
-                r#yield!(v);
+                match v {
+                    Step::Row(row) => r#yield!(Either::Right(row)),
+                    Step::Done(done) => r#yield!(Either::Left(done)),
+                    Step::Columns(_) => {}
+                }
             }
 
             Ok(())
-        })
+        };
+
+        Box::pin(stream.map_err(move |error| errors.record(error)))
     }
 
     fn fetch_optional<'e, 'q: 'e, E: 'q>(
@@ -269,12 +417,13 @@ impl<'c> Executor<'c> for &'c mut MySqlConnection {
         Box::pin(async move {
             self.stream.wait_until_ready().await?;
 
-            let (_, metadata) = self.get_or_prepare(sql, true).await?;
+            let (prepared, metadata) = self.get_or_prepare(sql, true).await?;
 
             Ok(MySqlStatement {
                 sql: Cow::Borrowed(sql),
                 // metadata has internal Arcs for expensive data structures
                 metadata: metadata.clone(),
+                prepared: Some(prepared),
             })
         })
     }
@@ -303,6 +452,7 @@ impl<'c> Executor<'c> for &'c mut MySqlConnection {
                 parameters: Some(Either::Right(metadata.parameters)),
                 columns,
                 nullable,
+                domains: Vec::new(),
             })
         })
     }
@@ -0,0 +1,51 @@
+use sqlx_rt::AsyncReadExt;
+
+use crate::error::Error;
+use crate::mysql::MySqlConnection;
+
+// the content of a file is sent in packets of (at most) this size
+const CHUNK_SIZE: usize = 64 * 1024;
+
+impl MySqlConnection {
+    /// Answer a LOCAL INFILE request of the server for `filename`, which is ended with an empty
+    /// packet whether or not the content could be sent.
+    pub(super) async fn send_local_infile(&mut self, filename: &[u8]) -> Result<(), Error> {
+        // https://dev.mysql.com/doc/dev/mysql-server/latest/page_protocol_com_query_response_local_infile_request.html
+
+        let result = self.send_local_infile_content(filename).await;
+
+        self.stream.write_packet(&[][..]);
+        self.stream.flush().await?;
+
+        result
+    }
+
+    async fn send_local_infile_content(&mut self, filename: &[u8]) -> Result<(), Error> {
+        let filename = String::from_utf8_lossy(filename);
+
+        let handler = self.local_infile_handler.clone().ok_or_else(|| {
+            Error::Configuration(
+                format!(
+                    "server requested the local file {:?}, but `LOAD DATA LOCAL INFILE` is not \
+                     enabled with a handler; see `MySqlConnectOptions::local_infile_handler`",
+                    filename
+                )
+                .into(),
+            )
+        })?;
+
+        let mut reader = handler(&filename).await?;
+        let mut chunk = vec![0; CHUNK_SIZE];
+
+        loop {
+            let read = reader.read(&mut chunk).await?;
+
+            if read == 0 {
+                return Ok(());
+            }
+
+            self.stream.write_packet(&chunk[..read]);
+            self.stream.flush().await?;
+        }
+    }
+}
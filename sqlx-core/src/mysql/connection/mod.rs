@@ -1,21 +1,32 @@
-use crate::common::StatementCache;
-use crate::connection::{Connection, LogSettings};
+use crate::common::{PreparedStatement, StatementCache};
+use crate::connection::{time_from_unix_micros, Connection, LogSettings};
 use crate::error::Error;
 use crate::mysql::protocol::statement::StmtClose;
 use crate::mysql::protocol::text::{Ping, Quit};
-use crate::mysql::statement::MySqlStatementMetadata;
-use crate::mysql::{MySql, MySqlConnectOptions};
-use crate::transaction::Transaction;
+use crate::mysql::statement::{MySqlStatement, MySqlStatementMetadata};
+use crate::mysql::{
+    MySql, MySqlConnectOptions, MySqlLocalInfileHandler, MySqlServerFlavor, MySqlZeroDateBehavior,
+};
+use crate::query_scalar::query_scalar;
+use crate::statement::PreparedStatementInfo;
+use crate::transaction::{Transaction, TransactionOptions};
 use futures_core::future::BoxFuture;
 use futures_util::FutureExt;
 use std::fmt::{self, Debug, Formatter};
+use std::time::SystemTime;
 
 mod auth;
+#[cfg(feature = "mysql-compression")]
+mod compressed_stream;
+#[cfg(feature = "mysql-ed25519")]
+mod ed25519;
 mod establish;
 mod executor;
+mod local_infile;
 mod stream;
 mod tls;
 
+pub(crate) use executor::Step;
 pub(crate) use stream::{MySqlStream, Waiting};
 
 const MAX_PACKET_SIZE: u32 = 1024;
@@ -31,9 +42,42 @@ pub struct MySqlConnection {
     pub(crate) transaction_depth: usize,
 
     // cache by query string to the statement id and metadata
-    cache_statement: StatementCache<(u32, MySqlStatementMetadata)>,
+    cache_statement: StatementCache<(PreparedStatement<u32>, MySqlStatementMetadata)>,
 
     log_settings: LogSettings,
+
+    // serves the files for `LOAD DATA LOCAL INFILE`, if enabled
+    local_infile_handler: Option<MySqlLocalInfileHandler>,
+
+    // passed on to the rows of results, for decoding zero dates
+    zero_dates: MySqlZeroDateBehavior,
+}
+
+impl MySqlConnection {
+    /// Returns whether the server is MySQL or MariaDB, as detected from its handshake.
+    pub fn server_flavor(&self) -> MySqlServerFlavor {
+        self.stream.flavor
+    }
+
+    /// Returns the version of the server as `(major, minor, patch)`, e.g. `(10, 6, 12)` for
+    /// MariaDB 10.6.12, whose handshake reports `5.5.5-10.6.12-MariaDB`.
+    pub fn server_version(&self) -> (u16, u16, u16) {
+        self.stream.server_version
+    }
+
+    /// Returns the current time of the server, e.g. to detect clock skew by comparing it to the
+    /// local time.
+    ///
+    /// This is `SYSDATE(6)` rather than `NOW(6)`, which is the time the current statement
+    /// started, e.g. the time a stored procedure was called.
+    pub async fn server_time(&mut self) -> Result<SystemTime, Error> {
+        let micros: i64 =
+            query_scalar("SELECT CAST(UNIX_TIMESTAMP(SYSDATE(6)) * 1000000 AS SIGNED)")
+                .fetch_one(&mut *self)
+                .await?;
+
+        Ok(time_from_unix_micros(micros))
+    }
 }
 
 impl Debug for MySqlConnection {
@@ -84,10 +128,12 @@ impl Connection for MySqlConnection {
 
     fn clear_cached_statements(&mut self) -> BoxFuture<'_, Result<(), Error>> {
         Box::pin(async move {
-            while let Some((statement_id, _)) = self.cache_statement.remove_lru() {
+            while let Some((prepared, _)) = self.cache_statement.remove_lru() {
+                prepared.close();
+
                 self.stream
                     .send_packet(StmtClose {
-                        statement: statement_id,
+                        statement: prepared.id(),
                     })
                     .await?;
             }
@@ -96,6 +142,59 @@ impl Connection for MySqlConnection {
         })
     }
 
+    fn close_statement<'c, 'q: 'c>(
+        &'c mut self,
+        statement: MySqlStatement<'q>,
+    ) -> BoxFuture<'c, Result<(), Error>> {
+        Box::pin(async move {
+            let prepared = match statement.prepared {
+                Some(prepared) => {
+                    if prepared.is_closed(self.log_settings.connection_id)? {
+                        return Ok(());
+                    }
+
+                    // the statement is cached unless the cache is disabled
+                    if let Some((cached, _)) = self.cache_statement.get_mut(&statement.sql) {
+                        if cached.id() == prepared.id() {
+                            self.cache_statement.remove(&statement.sql);
+                        }
+                    }
+
+                    prepared
+                }
+
+                None => match self.cache_statement.remove(&statement.sql) {
+                    Some((prepared, _)) => prepared,
+                    None => return Ok(()),
+                },
+            };
+
+            prepared.close();
+
+            self.stream.wait_until_ready().await?;
+
+            // the server doesn't respond to this command
+            self.stream
+                .send_packet(StmtClose {
+                    statement: prepared.id(),
+                })
+                .await
+        })
+    }
+
+    fn prepared_statements(&mut self) -> BoxFuture<'_, Result<Vec<PreparedStatementInfo>, Error>> {
+        let statements = self
+            .cache_statement
+            .iter()
+            .map(|(sql, (prepared, _))| PreparedStatementInfo {
+                sql: sql.to_owned(),
+                hits: prepared.hits(),
+            })
+            .collect();
+
+        Box::pin(async move { Ok(statements) })
+    }
+
     #[doc(hidden)]
     fn should_flush(&self) -> bool {
         !self.stream.wbuf.is_empty()
@@ -107,4 +206,14 @@ impl Connection for MySqlConnection {
     {
         Transaction::begin(self)
     }
+
+    fn begin_with(
+        &mut self,
+        options: TransactionOptions,
+    ) -> BoxFuture<'_, Result<Transaction<'_, Self::Database>, Error>>
+    where
+        Self: Sized,
+    {
+        Transaction::begin_with(self, options)
+    }
 }
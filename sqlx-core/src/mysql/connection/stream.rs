@@ -6,15 +6,27 @@ use bytes::{Buf, Bytes};
 use crate::error::Error;
 use crate::io::{BufStream, Decode, Encode};
 use crate::mysql::collation::{CharSet, Collation};
+#[cfg(feature = "mysql-compression")]
+use crate::mysql::connection::compressed_stream::MaybeCompressedStream;
 use crate::mysql::io::MySqlBufExt;
 use crate::mysql::protocol::response::{EofPacket, ErrPacket, OkPacket, Status};
 use crate::mysql::protocol::{Capabilities, Packet};
-use crate::mysql::{MySqlConnectOptions, MySqlDatabaseError};
+#[cfg(feature = "mysql-compression")]
+use crate::mysql::MySqlCompression;
+use crate::mysql::{MySqlConnectOptions, MySqlDatabaseError, MySqlServerFlavor};
 use crate::net::{MaybeTlsStream, Socket};
 
+// with compression support, the (possibly TLS) stream may be compressed once authenticated
+#[cfg(feature = "mysql-compression")]
+type MySqlSocket = MaybeCompressedStream<MaybeTlsStream<Socket>>;
+
+#[cfg(not(feature = "mysql-compression"))]
+type MySqlSocket = MaybeTlsStream<Socket>;
+
 pub struct MySqlStream {
-    stream: BufStream<MaybeTlsStream<Socket>>,
+    stream: BufStream<MySqlSocket>,
     pub(crate) server_version: (u16, u16, u16),
+    pub(crate) flavor: MySqlServerFlavor,
     pub(super) capabilities: Capabilities,
     pub(crate) sequence_id: u8,
     pub(crate) waiting: VecDeque<Waiting>,
@@ -57,20 +69,62 @@ impl MySqlStream {
             | Capabilities::MULTI_RESULTS
             | Capabilities::PLUGIN_AUTH
             | Capabilities::PS_MULTI_RESULTS
+            | Capabilities::QUERY_ATTRIBUTES
             | Capabilities::SSL;
 
         if options.database.is_some() {
             capabilities |= Capabilities::CONNECT_WITH_DB;
         }
 
+        if options.enable_local_infile {
+            capabilities |= Capabilities::LOCAL_FILES;
+        }
+
+        if !options.connect_attrs.is_empty() {
+            capabilities |= Capabilities::CONNECT_ATTRS;
+        }
+
+        match options.compression {
+            #[cfg(feature = "mysql-compression")]
+            Some(MySqlCompression::Zlib) => {
+                capabilities |= Capabilities::COMPRESS;
+            }
+
+            #[cfg(feature = "mysql-compression")]
+            Some(MySqlCompression::Zstd { level }) => {
+                if !(1..=22).contains(&level) {
+                    return Err(Error::Configuration(
+                        format!("zstd compression level {} is not between 1 and 22", level).into(),
+                    ));
+                }
+
+                capabilities |= Capabilities::ZSTD_COMPRESSION_ALGORITHM;
+            }
+
+            #[cfg(not(feature = "mysql-compression"))]
+            Some(_) => {
+                return Err(Error::Configuration(
+                    "compression requires the `mysql-compression` feature".into(),
+                ));
+            }
+
+            None => {}
+        }
+
+        let stream = MaybeTlsStream::Raw(socket);
+
+        #[cfg(feature = "mysql-compression")]
+        let stream = MaybeCompressedStream::Raw(stream);
+
         Ok(Self {
             waiting: VecDeque::new(),
             capabilities,
             server_version: (0, 0, 0),
+            flavor: MySqlServerFlavor::MySql,
             sequence_id: 0,
             collation,
             charset,
-            stream: BufStream::new(MaybeTlsStream::Raw(socket)),
+            stream: BufStream::new(stream),
         })
     }
 
@@ -113,11 +167,24 @@ impl MySqlStream {
         Ok(())
     }
 
+    /// Compress all further packets, once authentication is complete.
+    #[cfg(feature = "mysql-compression")]
+    pub(super) fn compress(&mut self, compression: MySqlCompression) -> Result<(), Error> {
+        // nothing may be buffered, as it would be sent or read as if it were compressed
+        debug_assert!(self.stream.wbuf.is_empty());
+
+        self.stream.stream.compress(compression)
+    }
+
     pub(crate) async fn send_packet<'en, T>(&mut self, payload: T) -> Result<(), Error>
     where
         T: Encode<'en, Capabilities>,
     {
         self.sequence_id = 0;
+
+        #[cfg(feature = "mysql-compression")]
+        self.stream.stream.reset_sequence_id();
+
         self.write_packet(payload);
         self.flush().await
     }
@@ -198,7 +265,7 @@ impl MySqlStream {
 }
 
 impl Deref for MySqlStream {
-    type Target = BufStream<MaybeTlsStream<Socket>>;
+    type Target = BufStream<MySqlSocket>;
 
     fn deref(&self) -> &Self::Target {
         &self.stream
@@ -4,6 +4,7 @@ use crate::mysql::{
     MySqlArguments, MySqlColumn, MySqlConnection, MySqlQueryResult, MySqlRow, MySqlStatement,
     MySqlTransactionManager, MySqlTypeInfo,
 };
+use crate::quote::{self, QuoteError};
 
 /// MySQL database driver.
 #[derive(Debug)]
@@ -23,6 +24,17 @@ impl Database for MySql {
     type TypeInfo = MySqlTypeInfo;
 
     type Value = MySqlValue;
+
+    fn quote_identifier(identifier: &str) -> Result<String, QuoteError> {
+        quote::check_identifier_len(identifier.chars().count(), 64)?;
+        quote::quote(identifier, '`', '`')
+    }
+
+    /// Escapes backslashes, so the literal reads wrong if the `NO_BACKSLASH_ESCAPES` SQL mode
+    /// is enabled.
+    fn quote_literal(value: &str) -> Result<String, QuoteError> {
+        quote::quote(&value.replace('\\', "\\\\"), '\'', '\'')
+    }
 }
 
 impl<'r> HasValueRef<'r> for MySql {
@@ -0,0 +1,273 @@
+use crate::column::ColumnIndex;
+use crate::decode::Decode;
+use crate::error::{BoxDynError, Error};
+use crate::mysql::protocol::text::{ColumnFlags, ColumnType};
+use crate::mysql::value::format_binary_temporal;
+use crate::mysql::{MySql, MySqlRow, MySqlValueFormat, MySqlValueRef};
+use crate::row::Row;
+use crate::type_info::TypeInfo;
+use crate::value::ValueRef;
+
+/// A value of any type, decoded without knowing its Rust type in advance.
+///
+/// This is returned by [`MySqlRow::try_get_dynamic`] and [`MySqlValueRef::to_dynamic`], for tools
+/// that handle the results of arbitrary queries, e.g. to export them as JSON or CSV.
+///
+/// Values of types that don't have a variant of their own are returned as [`Other`], with the
+/// raw bytes of the value as the server sent them. `DECIMAL` values and the date and time types
+/// are formatted the same as MySQL would format them in the text protocol.
+///
+/// [`Other`]: MySqlDynamicValue::Other
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum MySqlDynamicValue {
+    Null,
+
+    /// A signed `TINYINT`, `SMALLINT`, `MEDIUMINT`, `INT`, `BIGINT` or `YEAR` value.
+    Int(i64),
+
+    /// An `UNSIGNED` integer or a `BIT` value.
+    UInt(u64),
+    Float(f32),
+    Double(f64),
+
+    /// A `DECIMAL` value, e.g. `"-12.3400"`.
+    Decimal(String),
+
+    /// A `CHAR`, `VARCHAR`, `TEXT`, `ENUM` or `SET` value.
+    Text(String),
+
+    /// A `BINARY`, `VARBINARY` or `BLOB` value.
+    Bytes(Vec<u8>),
+
+    /// A `DATE` value, e.g. `"2022-02-28"`.
+    Date(String),
+
+    /// A `TIME` value, e.g. `"-838:59:59"`.
+    Time(String),
+
+    /// A `DATETIME` or `TIMESTAMP` value, e.g. `"2022-02-28 13:05:00"`.
+    Datetime(String),
+
+    /// A `JSON` value, as JSON text.
+    Json(String),
+
+    /// A value of any other type.
+    Other {
+        type_name: String,
+        format: MySqlValueFormat,
+        bytes: Vec<u8>,
+    },
+}
+
+impl MySqlValueRef<'_> {
+    /// Decode this value into a [`MySqlDynamicValue`], based on its type.
+    pub fn to_dynamic(&self) -> Result<MySqlDynamicValue, BoxDynError> {
+        if self.is_null() {
+            return Ok(MySqlDynamicValue::Null);
+        }
+
+        decode_dynamic(self.clone())
+    }
+}
+
+impl MySqlRow {
+    /// Index into the database row and decode a single value into a [`MySqlDynamicValue`],
+    /// based on the type of its column.
+    ///
+    /// # Errors
+    ///
+    ///  * [`ColumnNotFound`] if the column by the given name was not found.
+    ///  * [`ColumnIndexOutOfBounds`] if the `usize` index was greater than the number of columns in the row.
+    ///  * [`ColumnDecode`] if the value could not be decoded.
+    ///
+    /// [`ColumnDecode`]: Error::ColumnDecode
+    /// [`ColumnNotFound`]: Error::ColumnNotFound
+    /// [`ColumnIndexOutOfBounds`]: Error::ColumnIndexOutOfBounds
+    pub fn try_get_dynamic<I>(&self, index: I) -> Result<MySqlDynamicValue, Error>
+    where
+        I: ColumnIndex<Self>,
+    {
+        self.try_get_raw(&index)?
+            .to_dynamic()
+            .map_err(|source| Error::ColumnDecode {
+                index: format!("{:?}", index),
+                source,
+            })
+    }
+}
+
+fn decode_dynamic(value: MySqlValueRef<'_>) -> Result<MySqlDynamicValue, BoxDynError> {
+    let binary = value.format() == MySqlValueFormat::Binary;
+    let ty = value.type_info.r#type;
+
+    Ok(match ty {
+        ColumnType::Tiny
+        | ColumnType::Short
+        | ColumnType::Int24
+        | ColumnType::Long
+        | ColumnType::LongLong
+        | ColumnType::Year => {
+            if value.type_info.flags.contains(ColumnFlags::UNSIGNED) {
+                MySqlDynamicValue::UInt(decode(value)?)
+            } else {
+                MySqlDynamicValue::Int(decode(value)?)
+            }
+        }
+
+        ColumnType::Bit => MySqlDynamicValue::UInt(decode(value)?),
+        ColumnType::Float => MySqlDynamicValue::Float(decode(value)?),
+        ColumnType::Double => MySqlDynamicValue::Double(decode(value)?),
+
+        // decimals are sent as strings in the binary protocol too
+        ColumnType::Decimal | ColumnType::NewDecimal => {
+            MySqlDynamicValue::Decimal(value.as_str()?.to_owned())
+        }
+
+        ColumnType::Json => MySqlDynamicValue::Json(value.as_str()?.to_owned()),
+
+        ColumnType::VarChar
+        | ColumnType::VarString
+        | ColumnType::String
+        | ColumnType::TinyBlob
+        | ColumnType::MediumBlob
+        | ColumnType::LongBlob
+        | ColumnType::Blob
+        | ColumnType::Enum
+        | ColumnType::Set => {
+            // the binary character set is used for binary strings
+            if value.type_info.char_set == 63 {
+                MySqlDynamicValue::Bytes(value.as_bytes()?.to_owned())
+            } else {
+                MySqlDynamicValue::Text(value.as_str()?.to_owned())
+            }
+        }
+
+        ColumnType::Time if binary => {
+            MySqlDynamicValue::Time(format_binary_time(value.as_bytes()?))
+        }
+
+        ColumnType::Date | ColumnType::Datetime | ColumnType::Timestamp if binary => {
+            let s = format_binary_temporal(value.as_bytes()?, ty);
+
+            if ty == ColumnType::Date {
+                MySqlDynamicValue::Date(s)
+            } else {
+                MySqlDynamicValue::Datetime(s)
+            }
+        }
+
+        // in the text protocol, these are already formatted by the server
+        ColumnType::Date => MySqlDynamicValue::Date(value.as_str()?.to_owned()),
+        ColumnType::Time => MySqlDynamicValue::Time(value.as_str()?.to_owned()),
+        ColumnType::Datetime | ColumnType::Timestamp => {
+            MySqlDynamicValue::Datetime(value.as_str()?.to_owned())
+        }
+
+        ColumnType::Null | ColumnType::Geometry => MySqlDynamicValue::Other {
+            type_name: value.type_info.name().to_owned(),
+            format: value.format(),
+            bytes: value.as_bytes()?.to_owned(),
+        },
+    })
+}
+
+fn decode<'r, T: Decode<'r, MySql>>(value: MySqlValueRef<'r>) -> Result<T, BoxDynError> {
+    T::decode(value)
+}
+
+// renders a `TIME` value of the binary protocol as MySQL would in the text protocol
+// https://dev.mysql.com/doc/internals/en/binary-protocol-value.html#packet-ProtocolBinary::MYSQL_TYPE_TIME
+fn format_binary_time(value: &[u8]) -> String {
+    let fields = match value.split_first() {
+        Some((&len, fields)) => fields.get(..len as usize).unwrap_or(fields),
+        None => &[],
+    };
+
+    let field = |i: usize| fields.get(i).copied().unwrap_or_default();
+
+    let is_negative = field(0) == 1;
+    let days = u32::from_le_bytes([field(1), field(2), field(3), field(4)]);
+    let hours = u64::from(days) * 24 + u64::from(field(5));
+
+    let mut s = format!(
+        "{}{:02}:{:02}:{:02}",
+        if is_negative { "-" } else { "" },
+        hours,
+        field(6),
+        field(7)
+    );
+
+    if fields.len() > 8 {
+        let micros = u32::from_le_bytes([field(8), field(9), field(10), field(11)]);
+        s += &format!(".{:06}", micros);
+    }
+
+    s
+}
+
+#[cfg(feature = "json")]
+impl From<MySqlDynamicValue> for serde_json::Value {
+    /// Convert the value to JSON.
+    ///
+    /// Numbers that JSON can't represent, as well as `DECIMAL` values (to preserve their
+    /// precision), are converted to strings. Binary data is converted to a string in the format
+    /// of a hexadecimal literal, e.g. `"0xdeadbeef"`.
+    fn from(value: MySqlDynamicValue) -> Self {
+        use serde_json::Value;
+
+        fn float(value: f64) -> Value {
+            serde_json::Number::from_f64(value)
+                .map_or_else(|| Value::String(value.to_string()), Value::Number)
+        }
+
+        fn hex(bytes: &[u8]) -> Value {
+            Value::String(format!("0x{}", hex::encode(bytes)))
+        }
+
+        match value {
+            MySqlDynamicValue::Null => Value::Null,
+            MySqlDynamicValue::Int(value) => value.into(),
+            MySqlDynamicValue::UInt(value) => value.into(),
+            MySqlDynamicValue::Float(value) => float(value.into()),
+            MySqlDynamicValue::Double(value) => float(value),
+            MySqlDynamicValue::Bytes(bytes) => hex(&bytes),
+
+            MySqlDynamicValue::Decimal(s)
+            | MySqlDynamicValue::Text(s)
+            | MySqlDynamicValue::Date(s)
+            | MySqlDynamicValue::Time(s)
+            | MySqlDynamicValue::Datetime(s) => Value::String(s),
+
+            MySqlDynamicValue::Json(json) => {
+                serde_json::from_str(&json).unwrap_or(Value::String(json))
+            }
+
+            MySqlDynamicValue::Other { format, bytes, .. } => match format {
+                MySqlValueFormat::Text => match String::from_utf8(bytes) {
+                    Ok(s) => Value::String(s),
+                    Err(e) => hex(e.as_bytes()),
+                },
+
+                MySqlValueFormat::Binary => hex(&bytes),
+            },
+        }
+    }
+}
+
+#[test]
+fn test_format_binary_time() {
+    assert_eq!(format_binary_time(&[0]), "00:00:00");
+    assert_eq!(
+        format_binary_time(&[8, 0, 0, 0, 0, 0, 13, 5, 0]),
+        "13:05:00"
+    );
+    assert_eq!(
+        format_binary_time(&[8, 1, 34, 0, 0, 0, 22, 59, 59]),
+        "-838:59:59"
+    );
+    assert_eq!(
+        format_binary_time(&[12, 0, 1, 0, 0, 0, 1, 2, 3, 0x20, 0xA1, 0x07, 0]),
+        "25:02:03.500000"
+    );
+}
@@ -1,13 +1,130 @@
 use std::error::Error;
 use std::fmt::{self, Debug, Display, Formatter};
 
-use crate::error::DatabaseError;
+use crate::error::{DatabaseError, ErrorKind};
 use crate::mysql::protocol::response::ErrPacket;
 use smallvec::alloc::borrow::Cow;
 
 /// An error returned from the MySQL database.
 pub struct MySqlDatabaseError(pub(super) ErrPacket);
 
+// Server Error Message Reference
+// https://dev.mysql.com/doc/mysql-errors/8.0/en/server-error-reference.html
+
+const ER_DUP_ENTRY: u16 = 1062;
+const ER_DUP_ENTRY_WITH_KEY_NAME: u16 = 1586;
+const ER_NO_REFERENCED_ROW: u16 = 1216;
+const ER_ROW_IS_REFERENCED: u16 = 1217;
+const ER_ROW_IS_REFERENCED_2: u16 = 1451;
+const ER_NO_REFERENCED_ROW_2: u16 = 1452;
+const ER_BAD_NULL_ERROR: u16 = 1048;
+const ER_NO_DEFAULT_FOR_FIELD: u16 = 1364;
+const ER_CHECK_CONSTRAINT_VIOLATED: u16 = 3819;
+
+// MariaDB's error for a violated `CHECK` constraint
+const ER_CONSTRAINT_FAILED: u16 = 4025;
+
+/// The objects of a violated constraint, which MySQL only reports in the message of the error.
+#[derive(Default)]
+struct Violation<'a> {
+    schema: Option<&'a str>,
+    table: Option<&'a str>,
+    column: Option<&'a str>,
+    constraint: Option<&'a str>,
+}
+
+impl<'a> Violation<'a> {
+    fn parse(number: u16, message: &'a str) -> Self {
+        match number {
+            // Duplicate entry '1' for key 'users.PRIMARY'
+            // (without the name of the table before MySQL 8.0.19 and in MariaDB)
+            ER_DUP_ENTRY | ER_DUP_ENTRY_WITH_KEY_NAME => {
+                // the entry may contain " for key " itself
+                let key = message
+                    .rsplit_once(" for key ")
+                    .and_then(|(_, key)| quoted(key, '\''))
+                    .map(|(key, _)| key);
+
+                match key.and_then(|key| key.split_once('.')) {
+                    Some((table, constraint)) => Violation {
+                        table: Some(table),
+                        constraint: Some(constraint),
+                        ..Violation::default()
+                    },
+
+                    None => Violation {
+                        constraint: key,
+                        ..Violation::default()
+                    },
+                }
+            }
+
+            // Cannot add or update a child row: a foreign key constraint fails (`db`.`child`,
+            // CONSTRAINT `fk` FOREIGN KEY (`parent_id`) REFERENCES `parent` (`id`))
+            ER_ROW_IS_REFERENCED_2 | ER_NO_REFERENCED_ROW_2 => {
+                let parse = || {
+                    let (schema, rest) = quoted(after(message, "constraint fails (")?, '`')?;
+                    let (table, rest) = quoted(rest.strip_prefix('.')?, '`')?;
+                    let (constraint, rest) = quoted(after(rest, "CONSTRAINT ")?, '`')?;
+                    let (column, _) = quoted(after(rest, "FOREIGN KEY (")?, '`')?;
+
+                    Some(Violation {
+                        schema: Some(schema),
+                        table: Some(table),
+                        column: Some(column),
+                        constraint: Some(constraint),
+                    })
+                };
+
+                parse().unwrap_or_default()
+            }
+
+            // Column 'name' cannot be null
+            // Field 'name' doesn't have a default value
+            ER_BAD_NULL_ERROR | ER_NO_DEFAULT_FOR_FIELD => Violation {
+                column: message
+                    .find('\'')
+                    .and_then(|i| quoted(&message[i..], '\''))
+                    .map(|(column, _)| column),
+                ..Violation::default()
+            },
+
+            // Check constraint 'price_positive' is violated.
+            ER_CHECK_CONSTRAINT_VIOLATED => Violation {
+                constraint: message
+                    .find('\'')
+                    .and_then(|i| quoted(&message[i..], '\''))
+                    .map(|(constraint, _)| constraint),
+                ..Violation::default()
+            },
+
+            // CONSTRAINT `price_positive` failed for `db`.`products`
+            ER_CONSTRAINT_FAILED => Violation {
+                constraint: message
+                    .strip_prefix("CONSTRAINT ")
+                    .and_then(|rest| quoted(rest, '`'))
+                    .map(|(constraint, _)| constraint),
+                ..Violation::default()
+            },
+
+            _ => Violation::default(),
+        }
+    }
+}
+
+/// Returns the rest of `s` after the first occurrence of `pattern`.
+fn after<'a>(s: &'a str, pattern: &str) -> Option<&'a str> {
+    s.find(pattern).map(|i| &s[i + pattern.len()..])
+}
+
+/// Splits `s` after the identifier or string at its start, which is quoted with `quote`.
+fn quoted(s: &str, quote: char) -> Option<(&str, &str)> {
+    let s = s.strip_prefix(quote)?;
+    let end = s.find(quote)?;
+
+    Some((&s[..end], &s[end + 1..]))
+}
+
 impl MySqlDatabaseError {
     /// The [SQLSTATE](https://dev.mysql.com/doc/refman/8.0/en/server-error-reference.html) code for this error.
     pub fn code(&self) -> Option<&str> {
@@ -62,6 +179,43 @@ impl DatabaseError for MySqlDatabaseError {
         self.code().map(Cow::Borrowed)
     }
 
+    /// Parsed from the message of the error, for the unique, foreign key and check constraints.
+    fn constraint(&self) -> Option<&str> {
+        Violation::parse(self.number(), self.message()).constraint
+    }
+
+    /// Parsed from the message of the error, for the unique and foreign key constraints.
+    fn table(&self) -> Option<&str> {
+        Violation::parse(self.number(), self.message()).table
+    }
+
+    /// Parsed from the message of the error, for the `NOT NULL` and foreign key constraints.
+    fn column(&self) -> Option<&str> {
+        Violation::parse(self.number(), self.message()).column
+    }
+
+    /// Parsed from the message of the error, for the foreign key constraints.
+    fn schema(&self) -> Option<&str> {
+        Violation::parse(self.number(), self.message()).schema
+    }
+
+    fn kind(&self) -> ErrorKind {
+        match self.number() {
+            ER_DUP_ENTRY | ER_DUP_ENTRY_WITH_KEY_NAME => ErrorKind::UniqueViolation,
+
+            ER_NO_REFERENCED_ROW
+            | ER_ROW_IS_REFERENCED
+            | ER_ROW_IS_REFERENCED_2
+            | ER_NO_REFERENCED_ROW_2 => ErrorKind::ForeignKeyViolation,
+
+            ER_BAD_NULL_ERROR | ER_NO_DEFAULT_FOR_FIELD => ErrorKind::NotNullViolation,
+
+            ER_CHECK_CONSTRAINT_VIOLATED | ER_CONSTRAINT_FAILED => ErrorKind::CheckViolation,
+
+            _ => ErrorKind::Other,
+        }
+    }
+
     #[doc(hidden)]
     fn as_error(&self) -> &(dyn Error + Send + Sync + 'static) {
         self
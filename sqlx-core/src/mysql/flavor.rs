@@ -0,0 +1,104 @@
+use crate::mysql::protocol::Capabilities;
+
+/// The kind of server a [`MySqlConnection`](super::MySqlConnection) is connected to.
+///
+/// MariaDB speaks the MySQL protocol, but differs in its authentication plugins, types and
+/// SQL, e.g. it supports `RETURNING` on `INSERT` and `DELETE`.
+///
+/// The connection only authenticates with MariaDB's `client_ed25519` plugin if the server is
+/// detected as MariaDB. The rows of a `RETURNING` clause are a result set like any other, so
+/// they need no handling of their own.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MySqlServerFlavor {
+    /// MySQL, or a server derived from it such as Percona Server.
+    MySql,
+
+    /// MariaDB.
+    MariaDb,
+}
+
+impl MySqlServerFlavor {
+    /// Detect the flavor and the version of the server from its handshake.
+    pub(crate) fn detect(
+        server_version: &str,
+        server_capabilities: Capabilities,
+    ) -> (Self, (u16, u16, u16)) {
+        // MariaDB 10.2+ unsets the `CLIENT_MYSQL` flag to signal its extended capabilities
+        let flavor = if server_version.contains("MariaDB")
+            || !server_capabilities.contains(Capabilities::MYSQL)
+        {
+            MySqlServerFlavor::MariaDb
+        } else {
+            MySqlServerFlavor::MySql
+        };
+
+        // MariaDB 10 and later prefix their version with `5.5.5-` for old clients, which
+        // wouldn't expect a major version above 5
+        let version = match flavor {
+            MySqlServerFlavor::MariaDb => server_version
+                .strip_prefix("5.5.5-")
+                .unwrap_or(server_version),
+            MySqlServerFlavor::MySql => server_version,
+        };
+
+        // expecting MAJOR.MINOR.PATCH, followed by anything such as `-MariaDB` or `-log`
+        let mut parts = version.splitn(3, '.').map(|part| {
+            let end = part
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(part.len());
+
+            part[..end].parse().unwrap_or(0)
+        });
+
+        let major = parts.next().unwrap_or(0);
+        let minor = parts.next().unwrap_or(0);
+        let patch = parts.next().unwrap_or(0);
+
+        (flavor, (major, minor, patch))
+    }
+}
+
+#[test]
+fn test_detect_mysql() {
+    let capabilities = Capabilities::MYSQL | Capabilities::PROTOCOL_41;
+
+    assert_eq!(
+        MySqlServerFlavor::detect("8.0.33", capabilities),
+        (MySqlServerFlavor::MySql, (8, 0, 33))
+    );
+
+    assert_eq!(
+        MySqlServerFlavor::detect("5.7.42-log", capabilities),
+        (MySqlServerFlavor::MySql, (5, 7, 42))
+    );
+
+    assert_eq!(
+        MySqlServerFlavor::detect("8.0.33-0ubuntu0.22.04.2", capabilities),
+        (MySqlServerFlavor::MySql, (8, 0, 33))
+    );
+}
+
+#[test]
+fn test_detect_mariadb() {
+    assert_eq!(
+        MySqlServerFlavor::detect(
+            "5.5.5-10.6.12-MariaDB-1:10.6.12+maria~ubu2004",
+            Capabilities::PROTOCOL_41
+        ),
+        (MySqlServerFlavor::MariaDb, (10, 6, 12))
+    );
+
+    assert_eq!(
+        MySqlServerFlavor::detect("11.0.2-MariaDB", Capabilities::PROTOCOL_41),
+        (MySqlServerFlavor::MariaDb, (11, 0, 2))
+    );
+
+    // MariaDB before 10.2 still sets `CLIENT_MYSQL`
+    assert_eq!(
+        MySqlServerFlavor::detect(
+            "5.5.5-10.1.48-MariaDB",
+            Capabilities::MYSQL | Capabilities::PROTOCOL_41
+        ),
+        (MySqlServerFlavor::MariaDb, (10, 1, 48))
+    );
+}
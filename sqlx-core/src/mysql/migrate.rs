@@ -1,14 +1,19 @@
-use crate::connection::ConnectOptions;
+use crate::connection::{ConnectOptions, Connection};
 use crate::error::Error;
 use crate::executor::Executor;
-use crate::migrate::MigrateError;
-use crate::migrate::{AppliedMigration, Migration};
+use crate::migrate::DEFAULT_MIGRATIONS_TABLE;
+use crate::migrate::{applied_on, warn_missing_audit_columns};
+use crate::migrate::{quote_table_name, repeatable_table_name, split_table_name};
+use crate::migrate::{AppliedMigration, AppliedRepeatableMigration, Migration, Seed};
+use crate::migrate::{CreateDatabaseOptions, MigrateError};
 use crate::migrate::{Migrate, MigrateDatabase};
-use crate::mysql::{MySql, MySqlConnectOptions, MySqlConnection};
+use crate::mysql::{MySql, MySqlConnectOptions, MySqlConnection, MySqlDatabaseError};
 use crate::query::query;
 use crate::query_as::query_as;
 use crate::query_scalar::query_scalar;
 use futures_core::future::BoxFuture;
+use std::borrow::Cow;
+use std::fmt::Write;
 use std::str::FromStr;
 use std::time::Duration;
 use std::time::Instant;
@@ -32,13 +37,33 @@ fn parse_for_maintenance(url: &str) -> Result<(MySqlConnectOptions, String), Err
 
 impl MigrateDatabase for MySql {
     fn create_database(url: &str) -> BoxFuture<'_, Result<(), Error>> {
+        Box::pin(
+            async move { Self::create_database_with(url, &CreateDatabaseOptions::default()).await },
+        )
+    }
+
+    fn create_database_with<'a>(
+        url: &'a str,
+        create_options: &'a CreateDatabaseOptions,
+    ) -> BoxFuture<'a, Result<(), Error>> {
         Box::pin(async move {
+            create_options.check_supported("MySQL", &["charset", "collation"])?;
+
             let (options, database) = parse_for_maintenance(url)?;
             let mut conn = options.connect().await?;
 
-            let _ = conn
-                .execute(&*format!("CREATE DATABASE `{}`", database))
-                .await?;
+            let mut sql = format!("CREATE DATABASE {}", quote_database(&database));
+
+            // the names of character sets and collations may be quoted as identifiers
+            if let Some(charset) = &create_options.charset {
+                let _ = write!(sql, " CHARACTER SET {}", quote_database(charset));
+            }
+
+            if let Some(collation) = &create_options.collation {
+                let _ = write!(sql, " COLLATE {}", quote_database(collation));
+            }
+
+            let _ = conn.execute(&*sql).await?;
 
             Ok(())
         })
@@ -66,42 +91,131 @@ impl MigrateDatabase for MySql {
             let mut conn = options.connect().await?;
 
             let _ = conn
-                .execute(&*format!("DROP DATABASE IF EXISTS `{}`", database,))
+                .execute(&*format!(
+                    "DROP DATABASE IF EXISTS {}",
+                    quote_database(&database)
+                ))
                 .await?;
 
             Ok(())
         })
     }
+
+    fn force_drop_database(url: &str) -> BoxFuture<'_, Result<(), Error>> {
+        Box::pin(async move {
+            let (options, database) = parse_for_maintenance(url)?;
+            let mut conn = options.connect().await?;
+
+            // language=MySQL
+            let ids: Vec<u64> = query_scalar(
+                "SELECT ID FROM information_schema.PROCESSLIST \
+                 WHERE DB = ? AND ID <> CONNECTION_ID()",
+            )
+            .bind(&database)
+            .fetch_all(&mut conn)
+            .await?;
+
+            for id in ids {
+                match conn.execute(&*format!("KILL {}", id)).await {
+                    // ER_NO_SUCH_THREAD: the connection was closed since
+                    Err(e)
+                        if e.as_database_error()
+                            .and_then(|e| e.try_downcast_ref::<MySqlDatabaseError>())
+                            .map(MySqlDatabaseError::number)
+                            == Some(1094) => {}
+                    res => {
+                        let _ = res?;
+                    }
+                }
+            }
+
+            let _ = conn
+                .execute(&*format!(
+                    "DROP DATABASE IF EXISTS {}",
+                    quote_database(&database)
+                ))
+                .await?;
+
+            Ok(())
+        })
+    }
+}
+
+fn quote_database(database: &str) -> String {
+    format!("`{}`", database.replace('`', "``"))
 }
 
 impl Migrate for MySqlConnection {
-    fn ensure_migrations_table(&mut self) -> BoxFuture<'_, Result<(), MigrateError>> {
+    fn ensure_migrations_table<'e>(
+        &'e mut self,
+        table_name: &'e str,
+    ) -> BoxFuture<'e, Result<(), MigrateError>> {
         Box::pin(async move {
+            let table = quote_table_name(table_name, '`');
+
             // language=MySQL
-            self.execute(
+            self.execute(&*format!(
                 r#"
-CREATE TABLE IF NOT EXISTS _sqlx_migrations (
+CREATE TABLE IF NOT EXISTS {} (
     version BIGINT PRIMARY KEY,
     description TEXT NOT NULL,
     installed_on TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
     success BOOLEAN NOT NULL,
     checksum BLOB NOT NULL,
-    execution_time BIGINT NOT NULL
+    execution_time BIGINT NOT NULL,
+    applied_by TEXT,
+    sqlx_version TEXT,
+    hostname TEXT
 );
                 "#,
-            )
+                table
+            ))
             .await?;
 
+            if !has_audit_columns(self, table_name).await? {
+                // language=MySQL
+                let added = self
+                    .execute(&*format!(
+                        r#"
+ALTER TABLE {}
+    ADD COLUMN applied_by TEXT,
+    ADD COLUMN sqlx_version TEXT,
+    ADD COLUMN hostname TEXT;
+                        "#,
+                        table
+                    ))
+                    .await;
+
+                match added {
+                    Ok(_) => {}
+                    Err(e) if e.as_database_error().is_some() => {
+                        warn_missing_audit_columns(table_name, &e)
+                    }
+                    Err(e) => return Err(e.into()),
+                }
+            }
+
             Ok(())
         })
     }
 
-    fn version(&mut self) -> BoxFuture<'_, Result<Option<(i64, bool)>, MigrateError>> {
+    fn migrations_table_exists<'e>(
+        &'e mut self,
+        table_name: &'e str,
+    ) -> BoxFuture<'e, Result<bool, MigrateError>> {
+        Box::pin(async move { table_exists(self, table_name).await })
+    }
+
+    fn version<'e>(
+        &'e mut self,
+        table_name: &'e str,
+    ) -> BoxFuture<'e, Result<Option<(i64, bool)>, MigrateError>> {
         Box::pin(async move {
             // language=SQL
-            let row = query_as(
-                "SELECT version, NOT success FROM _sqlx_migrations ORDER BY version DESC LIMIT 1",
-            )
+            let row = query_as(&format!(
+                "SELECT version, NOT success FROM {} ORDER BY version DESC LIMIT 1",
+                quote_table_name(table_name, '`')
+            ))
             .fetch_optional(self)
             .await?;
 
@@ -109,12 +223,16 @@ CREATE TABLE IF NOT EXISTS _sqlx_migrations (
         })
     }
 
-    fn dirty_version(&mut self) -> BoxFuture<'_, Result<Option<i64>, MigrateError>> {
+    fn dirty_version<'e>(
+        &'e mut self,
+        table_name: &'e str,
+    ) -> BoxFuture<'e, Result<Option<i64>, MigrateError>> {
         Box::pin(async move {
             // language=SQL
-            let row: Option<(i64,)> = query_as(
-                "SELECT version FROM _sqlx_migrations WHERE success = false ORDER BY version LIMIT 1",
-            )
+            let row: Option<(i64,)> = query_as(&format!(
+                "SELECT version FROM {} WHERE success = false ORDER BY version LIMIT 1",
+                quote_table_name(table_name, '`')
+            ))
             .fetch_optional(self)
             .await?;
 
@@ -122,20 +240,66 @@ CREATE TABLE IF NOT EXISTS _sqlx_migrations (
         })
     }
 
-    fn list_applied_migrations(
-        &mut self,
-    ) -> BoxFuture<'_, Result<Vec<AppliedMigration>, MigrateError>> {
+    fn list_applied_migrations<'e>(
+        &'e mut self,
+        table_name: &'e str,
+    ) -> BoxFuture<'e, Result<Vec<AppliedMigration>, MigrateError>> {
         Box::pin(async move {
-            // language=SQL
-            let rows: Vec<(i64, Vec<u8>)> =
-                query_as("SELECT version, checksum FROM _sqlx_migrations ORDER BY version")
-                    .fetch_all(self)
-                    .await?;
+            let columns = if has_audit_columns(self, table_name).await? {
+                "version, checksum, applied_by, sqlx_version, hostname"
+            } else {
+                "version, checksum, NULL, NULL, NULL"
+            };
+
+            // language=MySQL
+            let rows: Vec<AppliedRow> = query_as(&format!(
+                "SELECT {} FROM {} ORDER BY version",
+                columns,
+                quote_table_name(table_name, '`')
+            ))
+            .fetch_all(self)
+            .await?;
 
             let migrations = rows
                 .into_iter()
-                .map(|(version, checksum)| AppliedMigration {
-                    version,
+                .map(
+                    |(version, checksum, applied_by, sqlx_version, hostname)| AppliedMigration {
+                        version,
+                        checksum: checksum.into(),
+                        applied_by,
+                        sqlx_version,
+                        hostname,
+                    },
+                )
+                .collect();
+
+            Ok(migrations)
+        })
+    }
+
+    fn list_applied_repeatable_migrations<'e>(
+        &'e mut self,
+        table_name: &'e str,
+    ) -> BoxFuture<'e, Result<Vec<AppliedRepeatableMigration>, MigrateError>> {
+        Box::pin(async move {
+            let repeatable_table = repeatable_table_name(table_name);
+
+            if !table_exists(self, &repeatable_table).await? {
+                return Ok(Vec::new());
+            }
+
+            // language=MySQL
+            let rows: Vec<(String, Vec<u8>)> = query_as(&format!(
+                "SELECT description, checksum FROM {} WHERE success = true ORDER BY description",
+                quote_table_name(&repeatable_table, '`')
+            ))
+            .fetch_all(self)
+            .await?;
+
+            let migrations = rows
+                .into_iter()
+                .map(|(description, checksum)| AppliedRepeatableMigration {
+                    description: description.into(),
                     checksum: checksum.into(),
                 })
                 .collect();
@@ -144,31 +308,60 @@ CREATE TABLE IF NOT EXISTS _sqlx_migrations (
         })
     }
 
-    fn lock(&mut self) -> BoxFuture<'_, Result<(), MigrateError>> {
+    fn lock<'e>(
+        &'e mut self,
+        table_name: &'e str,
+        timeout: Option<Duration>,
+    ) -> BoxFuture<'e, Result<(), MigrateError>> {
         Box::pin(async move {
             let database_name = current_database(self).await?;
-            let lock_id = generate_lock_id(&database_name);
+            let lock_id = generate_lock_id(&database_name, table_name);
 
             // create an application lock over the database
-            // this function will not return until the lock is acquired
-
-            // https://www.postgresql.org/docs/current/explicit-locking.html#ADVISORY-LOCKS
-            // https://www.postgresql.org/docs/current/functions-admin.html#FUNCTIONS-ADVISORY-LOCKS-TABLE
+            // https://dev.mysql.com/doc/refman/8.0/en/locking-functions.html
 
-            // language=MySQL
-            let _ = query("SELECT GET_LOCK(?, -1)")
-                .bind(lock_id)
-                .execute(self)
-                .await?;
+            if get_lock(self, &lock_id, 0).await? {
+                return Ok(());
+            }
 
-            Ok(())
+            let holder = lock_holder(self, &lock_id).await?;
+
+            let timeout = match timeout {
+                Some(timeout) if timeout.is_zero() => {
+                    return Err(MigrateError::LockTimeout(holder))
+                }
+                timeout => timeout,
+            };
+
+            log::info!(
+                "waiting for the migrations lock on {}{}",
+                table_name,
+                holder
+                    .as_deref()
+                    .map(|holder| format!(", held by {}", holder))
+                    .unwrap_or_default()
+            );
+
+            // a negative timeout waits indefinitely, and `GET_LOCK()` takes whole seconds
+            let seconds = match timeout {
+                Some(timeout) => timeout.as_secs() as i64 + i64::from(timeout.subsec_nanos() > 0),
+                None => -1,
+            };
+
+            if get_lock(self, &lock_id, seconds).await? {
+                Ok(())
+            } else {
+                Err(MigrateError::LockTimeout(
+                    lock_holder(self, &lock_id).await?,
+                ))
+            }
         })
     }
 
-    fn unlock(&mut self) -> BoxFuture<'_, Result<(), MigrateError>> {
+    fn unlock<'e>(&'e mut self, table_name: &'e str) -> BoxFuture<'e, Result<(), MigrateError>> {
         Box::pin(async move {
             let database_name = current_database(self).await?;
-            let lock_id = generate_lock_id(&database_name);
+            let lock_id = generate_lock_id(&database_name, table_name);
 
             // language=MySQL
             let _ = query("SELECT RELEASE_LOCK(?)")
@@ -182,15 +375,18 @@ CREATE TABLE IF NOT EXISTS _sqlx_migrations (
 
     fn validate<'e: 'm, 'm>(
         &'e mut self,
+        table_name: &'m str,
         migration: &'m Migration,
     ) -> BoxFuture<'m, Result<(), MigrateError>> {
         Box::pin(async move {
             // language=SQL
-            let checksum: Option<Vec<u8>> =
-                query_scalar("SELECT checksum FROM _sqlx_migrations WHERE version = ?")
-                    .bind(migration.version)
-                    .fetch_optional(self)
-                    .await?;
+            let checksum: Option<Vec<u8>> = query_scalar(&format!(
+                "SELECT checksum FROM {} WHERE version = ?",
+                quote_table_name(table_name, '`')
+            ))
+            .bind(migration.version)
+            .fetch_optional(self)
+            .await?;
 
             if let Some(checksum) = checksum {
                 return if checksum == &*migration.checksum {
@@ -206,7 +402,9 @@ CREATE TABLE IF NOT EXISTS _sqlx_migrations (
 
     fn apply<'e: 'm, 'm>(
         &'e mut self,
+        table_name: &'m str,
         migration: &'m Migration,
+        applied_by: Option<&'m str>,
     ) -> BoxFuture<'m, Result<Duration, MigrateError>> {
         Box::pin(async move {
             let start = Instant::now();
@@ -215,29 +413,68 @@ CREATE TABLE IF NOT EXISTS _sqlx_migrations (
 
             let elapsed = start.elapsed();
 
+            if migration.migration_type.is_repeatable() {
+                record_applied_repeatable(
+                    self,
+                    table_name,
+                    migration,
+                    res.is_ok(),
+                    elapsed.as_nanos() as i64,
+                    applied_by,
+                )
+                .await?;
+            } else {
+                record_applied(
+                    self,
+                    table_name,
+                    migration,
+                    res.is_ok(),
+                    elapsed.as_nanos() as i64,
+                    applied_by,
+                )
+                .await?;
+            }
+
+            res?;
+
+            Ok(elapsed)
+        })
+    }
+
+    fn set_checksum<'e: 'm, 'm>(
+        &'e mut self,
+        table_name: &'m str,
+        migration: &'m Migration,
+    ) -> BoxFuture<'m, Result<(), MigrateError>> {
+        Box::pin(async move {
             // language=MySQL
-            let _ = query(
-                r#"
-    INSERT INTO _sqlx_migrations ( version, description, success, checksum, execution_time )
-    VALUES ( ?, ?, ?, ?, ? )
-                "#,
-            )
-            .bind(migration.version)
-            .bind(&*migration.description)
-            .bind(res.is_ok())
+            let _ = query(&format!(
+                r#"UPDATE {} SET checksum = ? WHERE version = ?"#,
+                quote_table_name(table_name, '`')
+            ))
             .bind(&*migration.checksum)
-            .bind(elapsed.as_nanos() as i64)
+            .bind(migration.version)
             .execute(self)
             .await?;
 
-            res?;
-
-            Ok(elapsed)
+            Ok(())
         })
     }
 
+    fn mark_applied<'e: 'm, 'm>(
+        &'e mut self,
+        table_name: &'m str,
+        migration: &'m Migration,
+        applied_by: Option<&'m str>,
+    ) -> BoxFuture<'m, Result<(), MigrateError>> {
+        Box::pin(
+            async move { record_applied(self, table_name, migration, true, 0, applied_by).await },
+        )
+    }
+
     fn revert<'e: 'm, 'm>(
         &'e mut self,
+        table_name: &'m str,
         migration: &'m Migration,
     ) -> BoxFuture<'m, Result<Duration, MigrateError>> {
         Box::pin(async move {
@@ -248,14 +485,66 @@ CREATE TABLE IF NOT EXISTS _sqlx_migrations (
             let elapsed = start.elapsed();
 
             // language=SQL
-            let _ = query(r#"DELETE FROM _sqlx_migrations WHERE version = ?"#)
-                .bind(migration.version)
-                .execute(self)
-                .await?;
+            let _ = query(&format!(
+                r#"DELETE FROM {} WHERE version = ?"#,
+                quote_table_name(table_name, '`')
+            ))
+            .bind(migration.version)
+            .execute(self)
+            .await?;
 
             Ok(elapsed)
         })
     }
+
+    fn seed<'e: 'm, 'm>(
+        &'e mut self,
+        seed: &'m Seed,
+    ) -> BoxFuture<'m, Result<Duration, MigrateError>> {
+        Box::pin(async move {
+            let start = Instant::now();
+
+            let run = async {
+                let mut tx = self.begin().await?;
+                let _ = tx.execute(&*seed.sql).await?;
+                tx.commit().await
+            };
+
+            run.await
+                .map_err(|e| MigrateError::Seed(seed.name.to_string(), e))?;
+
+            Ok(start.elapsed())
+        })
+    }
+}
+
+async fn get_lock(
+    conn: &mut MySqlConnection,
+    lock_id: &str,
+    timeout_seconds: i64,
+) -> Result<bool, MigrateError> {
+    // language=MySQL
+    let acquired: Option<i64> = query_scalar("SELECT GET_LOCK(?, ?)")
+        .bind(lock_id)
+        .bind(timeout_seconds)
+        .fetch_one(conn)
+        .await?;
+
+    Ok(acquired == Some(1))
+}
+
+/// Describe the connection holding the lock `lock_id`, if it is still held.
+async fn lock_holder(
+    conn: &mut MySqlConnection,
+    lock_id: &str,
+) -> Result<Option<String>, MigrateError> {
+    // language=MySQL
+    let id: Option<i64> = query_scalar("SELECT IS_USED_LOCK(?)")
+        .bind(lock_id)
+        .fetch_one(conn)
+        .await?;
+
+    Ok(id.map(|id| format!("the connection with ID {}", id)))
 }
 
 async fn current_database(conn: &mut MySqlConnection) -> Result<String, MigrateError> {
@@ -264,11 +553,173 @@ async fn current_database(conn: &mut MySqlConnection) -> Result<String, MigrateE
 }
 
 // inspired from rails: https://github.com/rails/rails/blob/6e49cc77ab3d16c06e12f93158eaf3e507d4120e/activerecord/lib/active_record/migration.rb#L1308
-fn generate_lock_id(database_name: &str) -> String {
+fn generate_lock_id(database_name: &str, table_name: &str) -> String {
     const CRC_IEEE: crc::Crc<u32> = crc::Crc::<u32>::new(&crc::CRC_32_ISO_HDLC);
+
+    // the lock of the default table is kept as it was, so older migrators still wait on it
+    let key = if table_name == DEFAULT_MIGRATIONS_TABLE {
+        Cow::Borrowed(database_name)
+    } else {
+        Cow::Owned(format!("{}.{}", database_name, table_name))
+    };
+
     // 0x3d32ad9e chosen by fair dice roll
     format!(
         "{:x}",
-        0x3d32ad9e * (CRC_IEEE.checksum(database_name.as_bytes()) as i64)
+        0x3d32ad9e * (CRC_IEEE.checksum(key.as_bytes()) as i64)
     )
 }
+
+// version, checksum, applied_by, sqlx_version, hostname
+type AppliedRow = (i64, Vec<u8>, Option<String>, Option<String>, Option<String>);
+
+// the columns for audits are missing from migrations tables created before them, if they
+// couldn't be added
+async fn has_audit_columns(
+    conn: &mut MySqlConnection,
+    table_name: &str,
+) -> Result<bool, MigrateError> {
+    let (schema, name) = split_table_name(table_name);
+
+    // language=MySQL
+    let exists: Option<i64> = query_scalar(
+        r#"
+SELECT 1 FROM information_schema.COLUMNS
+WHERE TABLE_SCHEMA = COALESCE(?, DATABASE()) AND TABLE_NAME = ? AND COLUMN_NAME = 'applied_by'
+        "#,
+    )
+    .bind(schema)
+    .bind(name)
+    .fetch_optional(conn)
+    .await?;
+
+    Ok(exists.is_some())
+}
+
+async fn table_exists(conn: &mut MySqlConnection, table_name: &str) -> Result<bool, MigrateError> {
+    let (schema, name) = split_table_name(table_name);
+
+    // language=MySQL
+    let exists: Option<i64> = query_scalar(
+        r#"
+SELECT 1 FROM information_schema.TABLES
+WHERE TABLE_SCHEMA = COALESCE(?, DATABASE()) AND TABLE_NAME = ?
+        "#,
+    )
+    .bind(schema)
+    .bind(name)
+    .fetch_optional(conn)
+    .await?;
+
+    Ok(exists.is_some())
+}
+
+async fn record_applied(
+    conn: &mut MySqlConnection,
+    table_name: &str,
+    migration: &Migration,
+    success: bool,
+    execution_time: i64,
+    applied_by: Option<&str>,
+) -> Result<(), MigrateError> {
+    let table = quote_table_name(table_name, '`');
+
+    if has_audit_columns(conn, table_name).await? {
+        let (sqlx_version, hostname) = applied_on();
+
+        // language=MySQL
+        let _ = query(&format!(
+            r#"
+    INSERT INTO {} ( version, description, success, checksum, execution_time, applied_by, sqlx_version, hostname )
+    VALUES ( ?, ?, ?, ?, ?, COALESCE(?, CURRENT_USER()), ?, ? )
+            "#,
+            table
+        ))
+        .bind(migration.version)
+        .bind(&*migration.description)
+        .bind(success)
+        .bind(&*migration.checksum)
+        .bind(execution_time)
+        .bind(applied_by)
+        .bind(sqlx_version)
+        .bind(hostname)
+        .execute(conn)
+        .await?;
+    } else {
+        // language=MySQL
+        let _ = query(&format!(
+            r#"
+    INSERT INTO {} ( version, description, success, checksum, execution_time )
+    VALUES ( ?, ?, ?, ?, ? )
+            "#,
+            table
+        ))
+        .bind(migration.version)
+        .bind(&*migration.description)
+        .bind(success)
+        .bind(&*migration.checksum)
+        .bind(execution_time)
+        .execute(conn)
+        .await?;
+    }
+
+    Ok(())
+}
+
+// the table of repeatable migrations is created with the first one, in the database of the
+// migrations table
+async fn record_applied_repeatable(
+    conn: &mut MySqlConnection,
+    table_name: &str,
+    migration: &Migration,
+    success: bool,
+    execution_time: i64,
+    applied_by: Option<&str>,
+) -> Result<(), MigrateError> {
+    let table = quote_table_name(&repeatable_table_name(table_name), '`');
+    let (sqlx_version, hostname) = applied_on();
+
+    // language=MySQL
+    conn.execute(&*format!(
+        r#"
+CREATE TABLE IF NOT EXISTS {} (
+    description VARCHAR(255) PRIMARY KEY,
+    installed_on TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    success BOOLEAN NOT NULL,
+    checksum BLOB NOT NULL,
+    execution_time BIGINT NOT NULL,
+    applied_by TEXT,
+    sqlx_version TEXT,
+    hostname TEXT
+);
+        "#,
+        table
+    ))
+    .await?;
+
+    // language=MySQL
+    let _ = query(&format!(r#"DELETE FROM {} WHERE description = ?"#, table))
+        .bind(&*migration.description)
+        .execute(&mut *conn)
+        .await?;
+
+    // language=MySQL
+    let _ = query(&format!(
+        r#"
+    INSERT INTO {} ( description, success, checksum, execution_time, applied_by, sqlx_version, hostname )
+    VALUES ( ?, ?, ?, ?, COALESCE(?, CURRENT_USER()), ?, ? )
+        "#,
+        table
+    ))
+    .bind(&*migration.description)
+    .bind(success)
+    .bind(&*migration.checksum)
+    .bind(execution_time)
+    .bind(applied_by)
+    .bind(sqlx_version)
+    .bind(hostname)
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
@@ -7,11 +7,15 @@ mod collation;
 mod column;
 mod connection;
 mod database;
+mod dynamic_value;
 mod error;
+mod flavor;
 mod io;
 mod options;
 mod protocol;
+mod query_attributes;
 mod query_result;
+mod result_set;
 mod row;
 mod statement;
 mod transaction;
@@ -25,13 +29,22 @@ mod migrate;
 #[cfg(feature = "migrate")]
 mod testing;
 
+#[cfg(feature = "serde_row")]
+mod serde_row;
+
 pub use arguments::MySqlArguments;
 pub use column::MySqlColumn;
 pub use connection::MySqlConnection;
 pub use database::MySql;
+pub use dynamic_value::MySqlDynamicValue;
 pub use error::MySqlDatabaseError;
-pub use options::{MySqlConnectOptions, MySqlSslMode};
+pub use flavor::MySqlServerFlavor;
+pub use options::{
+    MySqlCompression, MySqlConnectOptions, MySqlLocalInfileHandler, MySqlSslMode,
+    MySqlZeroDateBehavior,
+};
 pub use query_result::MySqlQueryResult;
+pub use result_set::{MySqlResultSet, MySqlResultSets};
 pub use row::MySqlRow;
 pub use statement::MySqlStatement;
 pub use transaction::MySqlTransactionManager;
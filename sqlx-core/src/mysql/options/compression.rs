@@ -0,0 +1,41 @@
+use crate::error::Error;
+use std::str::FromStr;
+
+/// Algorithms for compressing the traffic of a MySQL connection with the compressed protocol.
+///
+/// It is used by the [`compression`](super::MySqlConnectOptions::compression) method.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MySqlCompression {
+    /// Compress packets with zlib, which is supported by MySQL and MariaDB.
+    Zlib,
+
+    /// Compress packets with zstd, which is supported from MySQL 8.0.18.
+    ///
+    /// `level` is a zstd compression level, from `1` (fastest) to `22` (smallest).
+    Zstd { level: u8 },
+}
+
+impl MySqlCompression {
+    /// The compression level used for [`Zstd`](Self::Zstd) when none is given, which is also
+    /// the default of MySQL.
+    pub const DEFAULT_ZSTD_LEVEL: u8 = 3;
+}
+
+impl FromStr for MySqlCompression {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Error> {
+        Ok(match &*s.to_ascii_lowercase() {
+            "zlib" => MySqlCompression::Zlib,
+            "zstd" => MySqlCompression::Zstd {
+                level: MySqlCompression::DEFAULT_ZSTD_LEVEL,
+            },
+
+            _ => {
+                return Err(Error::Configuration(
+                    format!("unknown value {:?} for `compression`", s).into(),
+                ));
+            }
+        })
+    }
+}
@@ -1,10 +1,14 @@
 use crate::connection::ConnectOptions;
 use crate::error::Error;
 use crate::executor::Executor;
+use crate::logger::StatementLogger;
 use crate::mysql::{MySqlConnectOptions, MySqlConnection};
+use crate::observer::QueryObserver;
 use futures_core::future::BoxFuture;
 use log::LevelFilter;
+use std::sync::Arc;
 use std::time::Duration;
+use url::Url;
 
 impl ConnectOptions for MySqlConnectOptions {
     type Connection = MySqlConnection;
@@ -35,7 +39,7 @@ impl ConnectOptions for MySqlConnectOptions {
             // --
 
             // Setting the time zone allows us to assume that the output
-            // from a TIMESTAMP field is UTC
+            // from a TIMESTAMP field is UTC, unless configured otherwise
 
             // --
 
@@ -43,7 +47,9 @@ impl ConnectOptions for MySqlConnectOptions {
 
             let mut options = String::new();
             options.push_str(r#"SET sql_mode=(SELECT CONCAT(@@sql_mode, ',PIPES_AS_CONCAT,NO_ENGINE_SUBSTITUTION')),"#);
-            options.push_str(r#"time_zone='+00:00',"#);
+            if let Some(timezone) = &self.timezone {
+                options.push_str(&format!(r#"time_zone='{}',"#, timezone.replace('\'', "''")));
+            }
             options.push_str(&format!(
                 r#"NAMES {} COLLATE {};"#,
                 conn.stream.charset.as_str(),
@@ -56,6 +62,10 @@ impl ConnectOptions for MySqlConnectOptions {
         })
     }
 
+    fn to_url_lossless(&self) -> Result<Url, Error> {
+        self.build_url()
+    }
+
     fn log_statements(&mut self, level: LevelFilter) -> &mut Self {
         self.log_settings.log_statements(level);
         self
@@ -65,4 +75,25 @@ impl ConnectOptions for MySqlConnectOptions {
         self.log_settings.log_slow_statements(level, duration);
         self
     }
+
+    fn capture_statement_on_error(&mut self, capture: bool) -> &mut Self {
+        self.log_settings.capture_statement_on_error(capture);
+        self
+    }
+
+    fn log_with(&mut self, logger: Arc<dyn StatementLogger>) -> &mut Self {
+        self.log_settings.log_with(logger);
+        self
+    }
+
+    fn query_observer(&mut self, observer: Arc<dyn QueryObserver>) -> &mut Self {
+        self.log_settings.query_observer(observer);
+        self
+    }
+
+    #[cfg(feature = "tracing")]
+    fn trace_statement_limit(&mut self, limit: usize) -> &mut Self {
+        self.log_settings.trace_statement_limit(limit);
+        self
+    }
 }
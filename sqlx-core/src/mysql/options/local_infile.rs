@@ -0,0 +1,29 @@
+use std::fmt::{self, Debug, Formatter};
+use std::io;
+use std::sync::Arc;
+
+use futures_core::future::BoxFuture;
+use sqlx_rt::AsyncRead;
+
+/// A function serving the content of files for `LOAD DATA LOCAL INFILE` statements.
+///
+/// It is called with the name of the file requested by the server, which is the one given in the
+/// statement, and returns a reader producing the content to upload. As the server chooses which
+/// file it asks for, the handler should check the name against the files the application
+/// means to serve and return an error for any other.
+///
+/// It is used by the [`local_infile_handler`](super::MySqlConnectOptions::local_infile_handler)
+/// method.
+pub type MySqlLocalInfileHandler = Arc<
+    dyn Fn(&str) -> BoxFuture<'static, io::Result<Box<dyn AsyncRead + Send + Unpin>>> + Send + Sync,
+>;
+
+// `MySqlLocalInfileHandler` can't be `Debug`, which `MySqlConnectOptions` is
+#[derive(Clone)]
+pub(crate) struct LocalInfileHandler(pub(crate) MySqlLocalInfileHandler);
+
+impl Debug for LocalInfileHandler {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LocalInfileHandler").finish_non_exhaustive()
+    }
+}
@@ -30,6 +30,18 @@ pub enum MySqlSslMode {
     VerifyIdentity,
 }
 
+impl MySqlSslMode {
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            MySqlSslMode::Disabled => "DISABLED",
+            MySqlSslMode::Preferred => "PREFERRED",
+            MySqlSslMode::Required => "REQUIRED",
+            MySqlSslMode::VerifyCa => "VERIFY_CA",
+            MySqlSslMode::VerifyIdentity => "VERIFY_IDENTITY",
+        }
+    }
+}
+
 impl Default for MySqlSslMode {
     fn default() -> Self {
         MySqlSslMode::Preferred
@@ -40,7 +52,9 @@ impl FromStr for MySqlSslMode {
     type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Error> {
-        Ok(match &*s.to_ascii_lowercase() {
+        // MySQL spells the modes with underscores, e.g. `VERIFY_IDENTITY`, but dashes are
+        // accepted as well, like the modes of Postgres
+        Ok(match &*s.to_ascii_lowercase().replace('-', "_") {
             "disabled" => MySqlSslMode::Disabled,
             "preferred" => MySqlSslMode::Preferred,
             "required" => MySqlSslMode::Required,
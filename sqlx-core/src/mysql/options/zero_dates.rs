@@ -0,0 +1,51 @@
+use crate::error::Error;
+use std::str::FromStr;
+
+/// Options for decoding the "zero" values of MySQL temporal types, such as `0000-00-00` and
+/// `0000-00-00 00:00:00`, which MySQL allows unless the `NO_ZERO_DATE` SQL mode is set, but
+/// which aren't valid dates in Rust.
+///
+/// It is used by the [`zero_dates`](super::MySqlConnectOptions::zero_dates) method, and applies
+/// to `DATE`, `DATETIME` and `TIMESTAMP` values in both the text and binary protocols.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MySqlZeroDateBehavior {
+    /// Decoding a zero date returns an error.
+    Error,
+
+    /// Zero dates are treated as `NULL`, so decoding into an `Option` returns `None`, and
+    /// decoding into any other type returns an error.
+    #[default]
+    ConvertToNone,
+
+    /// Zero dates are decoded as the earliest value supported by MySQL, `1000-01-01` or
+    /// `1000-01-01 00:00:00`.
+    ConvertToMin,
+}
+
+impl MySqlZeroDateBehavior {
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            MySqlZeroDateBehavior::Error => "error",
+            MySqlZeroDateBehavior::ConvertToNone => "convert_to_none",
+            MySqlZeroDateBehavior::ConvertToMin => "convert_to_min",
+        }
+    }
+}
+
+impl FromStr for MySqlZeroDateBehavior {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Error> {
+        Ok(match &*s.to_ascii_lowercase() {
+            "error" => MySqlZeroDateBehavior::Error,
+            "convert_to_none" => MySqlZeroDateBehavior::ConvertToNone,
+            "convert_to_min" => MySqlZeroDateBehavior::ConvertToMin,
+
+            _ => {
+                return Err(Error::Configuration(
+                    format!("unknown value {:?} for `zero_dates`", s).into(),
+                ));
+            }
+        })
+    }
+}
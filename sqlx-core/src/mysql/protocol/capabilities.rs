@@ -74,6 +74,9 @@ bitflags::bitflags! {
         // Support ZSTD protocol compression
         const ZSTD_COMPRESSION_ALGORITHM = (1 << 26);
 
+        // [MySQL 8.0.23+] Query attributes can be sent with COM_QUERY and COM_STMT_EXECUTE
+        const QUERY_ATTRIBUTES = (1 << 27);
+
         // Verify server certificate
         const SSL_VERIFY_SERVER_CERT = (1 << 30);
 
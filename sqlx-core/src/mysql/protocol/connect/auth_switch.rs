@@ -26,15 +26,37 @@ impl Decode<'_> for AuthSwitchRequest {
 
         let plugin = buf.get_str_nul()?.parse()?;
 
-        // See: https://github.com/mysql/mysql-server/blob/ea7d2e2d16ac03afdd9cb72a972a95981107bf51/sql/auth/sha2_password.cc#L942
-        if buf.len() != 21 {
-            return Err(err_protocol!(
-                "expected 21 bytes but found {} bytes",
-                buf.len()
-            ));
-        }
-        let data = buf.get_bytes(20);
-        buf.advance(1); // NUL-terminator
+        let data = match plugin {
+            // the password is sent as it is, so any data is of no use
+            AuthPlugin::MySqlClearPassword => Bytes::new(),
+
+            // https://mariadb.com/kb/en/connection/#client_ed25519-plugin
+            // a 32-byte nonce, which isn't NUL-terminated
+            AuthPlugin::ClientEd25519 => {
+                if buf.len() != 32 {
+                    return Err(err_protocol!(
+                        "expected 32 bytes but found {} bytes",
+                        buf.len()
+                    ));
+                }
+
+                buf
+            }
+
+            _ => {
+                // See: https://github.com/mysql/mysql-server/blob/ea7d2e2d16ac03afdd9cb72a972a95981107bf51/sql/auth/sha2_password.cc#L942
+                if buf.len() != 21 {
+                    return Err(err_protocol!(
+                        "expected 21 bytes but found {} bytes",
+                        buf.len()
+                    ));
+                }
+                let data = buf.get_bytes(20);
+                buf.advance(1); // NUL-terminator
+
+                data
+            }
+        };
 
         Ok(Self { plugin, data })
     }
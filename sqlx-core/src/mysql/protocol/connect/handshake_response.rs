@@ -7,6 +7,9 @@ use crate::mysql::protocol::Capabilities;
 // https://dev.mysql.com/doc/internals/en/connection-phase-packets.html#packet-Protocol::HandshakeResponse
 // https://mariadb.com/kb/en/connection/#client-handshake-response
 
+// the server rejects connections sending more than 64KB of connection attributes
+const MAX_CONNECT_ATTRS_LEN: usize = 65535;
+
 #[derive(Debug)]
 pub struct HandshakeResponse<'a> {
     pub database: Option<&'a str>,
@@ -25,6 +28,12 @@ pub struct HandshakeResponse<'a> {
 
     /// Opaque authentication response
     pub auth_response: Option<&'a [u8]>,
+
+    /// Key-value pairs describing the client, shown by `performance_schema.session_connect_attrs`
+    pub connect_attrs: &'a [(String, String)],
+
+    /// Compression level for the connection, if it is compressed with zstd
+    pub zstd_compression_level: u8,
 }
 
 impl Encode<'_, Capabilities> for HandshakeResponse<'_> {
@@ -69,5 +78,65 @@ impl Encode<'_, Capabilities> for HandshakeResponse<'_> {
                 buf.push(0);
             }
         }
+
+        if capabilities.contains(Capabilities::CONNECT_ATTRS) {
+            buf.put_bytes_lenenc(&encode_connect_attrs(self.connect_attrs));
+        }
+
+        if capabilities.contains(Capabilities::ZSTD_COMPRESSION_ALGORITHM) {
+            buf.push(self.zstd_compression_level);
+        }
+    }
+}
+
+fn encode_connect_attrs(attrs: &[(String, String)]) -> Vec<u8> {
+    let mut buf = Vec::new();
+
+    for (i, (key, value)) in attrs.iter().enumerate() {
+        let len = buf.len();
+
+        buf.put_str_lenenc(key);
+        buf.put_str_lenenc(value);
+
+        if buf.len() > MAX_CONNECT_ATTRS_LEN {
+            // drop this attribute and those after it, so the same ones are always sent
+            buf.truncate(len);
+
+            log::warn!(
+                "connection attributes exceed the limit of {} bytes; not sending the last {} of them, starting with {:?}",
+                MAX_CONNECT_ATTRS_LEN,
+                attrs.len() - i,
+                key
+            );
+
+            break;
+        }
     }
+
+    buf
+}
+
+#[test]
+fn test_encode_connect_attrs() {
+    let attrs = vec![
+        ("_client_name".to_owned(), "sqlx".to_owned()),
+        ("app".to_owned(), "x".repeat(300)),
+    ];
+
+    let buf = encode_connect_attrs(&attrs);
+
+    assert!(buf.starts_with(b"\x0c_client_name\x04sqlx\x03app\xfc\x2c\x01xxx"));
+    assert_eq!(buf.len(), 13 + 5 + 4 + 3 + 300);
+}
+
+#[test]
+fn test_encode_connect_attrs_truncated() {
+    let attrs = vec![
+        ("_client_name".to_owned(), "sqlx".to_owned()),
+        ("big".to_owned(), "x".repeat(MAX_CONNECT_ATTRS_LEN)),
+        ("small".to_owned(), "x".to_owned()),
+    ];
+
+    // everything from the attribute crossing the limit is dropped
+    assert_eq!(encode_connect_attrs(&attrs), b"\x0c_client_name\x04sqlx");
 }
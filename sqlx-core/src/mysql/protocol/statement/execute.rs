@@ -1,10 +1,14 @@
 use crate::io::Encode;
+use crate::mysql::io::MySqlBufMutExt;
 use crate::mysql::protocol::text::ColumnFlags;
 use crate::mysql::protocol::Capabilities;
-use crate::mysql::MySqlArguments;
+use crate::mysql::{MySqlArguments, MySqlTypeInfo};
 
 // https://dev.mysql.com/doc/dev/mysql-server/8.0.12/page_protocol_com_stmt_execute.html
 
+// the parameter count is sent, as it can include query attributes
+const PARAMETER_COUNT_AVAILABLE: u8 = 0x08;
+
 #[derive(Debug)]
 pub struct Execute<'q> {
     pub statement: u32,
@@ -12,27 +16,106 @@ pub struct Execute<'q> {
 }
 
 impl<'q> Encode<'_, Capabilities> for Execute<'q> {
-    fn encode_with(&self, buf: &mut Vec<u8>, _: Capabilities) {
+    fn encode_with(&self, buf: &mut Vec<u8>, capabilities: Capabilities) {
         buf.push(0x17); // COM_STMT_EXECUTE
         buf.extend(&self.statement.to_le_bytes());
-        buf.push(0); // NO_CURSOR
+
+        if !capabilities.contains(Capabilities::QUERY_ATTRIBUTES) {
+            buf.push(0); // NO_CURSOR
+            buf.extend(&1_u32.to_le_bytes()); // iterations (always 1): int<4>
+
+            if !self.arguments.types.is_empty() {
+                buf.extend(&*self.arguments.null_bitmap);
+                buf.push(1); // send type to server
+
+                for ty in &self.arguments.types {
+                    encode_type(buf, ty);
+                }
+
+                buf.extend(&*self.arguments.values);
+            }
+
+            return;
+        }
+
+        // query attributes follow the arguments as named parameters
+        let attributes = &self.arguments.attributes;
+        let num_arguments = self.arguments.types.len();
+        let num_params = num_arguments + attributes.types.len();
+
+        buf.push(PARAMETER_COUNT_AVAILABLE); // NO_CURSOR, with the parameter count
         buf.extend(&1_u32.to_le_bytes()); // iterations (always 1): int<4>
+        buf.put_uint_lenenc(num_params as u64);
+
+        if num_params > 0 {
+            let mut null_bitmap = vec![0; num_params.div_ceil(8)];
+            null_bitmap[..self.arguments.null_bitmap.len()]
+                .copy_from_slice(&self.arguments.null_bitmap);
+
+            for (index, _) in (num_arguments..)
+                .zip(&attributes.nulls)
+                .filter(|(_, n)| **n)
+            {
+                null_bitmap[index / 8] |= (1 << (index % 8)) as u8;
+            }
 
-        if !self.arguments.types.is_empty() {
-            buf.extend(&*self.arguments.null_bitmap);
+            buf.extend(&*null_bitmap);
             buf.push(1); // send type to server
 
             for ty in &self.arguments.types {
-                buf.push(ty.r#type as u8);
+                encode_type(buf, ty);
+                buf.put_str_lenenc(""); // arguments are unnamed
+            }
 
-                buf.push(if ty.flags.contains(ColumnFlags::UNSIGNED) {
-                    0x80
-                } else {
-                    0
-                });
+            for (ty, name) in attributes.types.iter().zip(&attributes.names) {
+                encode_type(buf, ty);
+                buf.put_str_lenenc(name);
             }
 
             buf.extend(&*self.arguments.values);
+            buf.extend(&*attributes.values);
         }
     }
 }
+
+fn encode_type(buf: &mut Vec<u8>, ty: &MySqlTypeInfo) {
+    buf.push(ty.r#type as u8);
+
+    buf.push(if ty.flags.contains(ColumnFlags::UNSIGNED) {
+        0x80
+    } else {
+        0
+    });
+}
+
+#[test]
+fn test_encode_execute_with_attributes() {
+    let mut arguments = MySqlArguments::default();
+    arguments.add(1_i32);
+    arguments.add_attribute("trace", "ab");
+    arguments.add_attribute("span", Option::<i32>::None);
+
+    let execute = Execute {
+        statement: 1,
+        arguments: &arguments,
+    };
+
+    let mut buf = Vec::new();
+    execute.encode_with(&mut buf, Capabilities::QUERY_ATTRIBUTES);
+
+    assert_eq!(
+        buf,
+        b"\x17\x01\x00\x00\x00\x08\x01\x00\x00\x00\x03\x04\x01\
+          \x03\x00\x00\xfd\x00\x05trace\x03\x00\x04span\
+          \x01\x00\x00\x00\x02ab"
+    );
+
+    // servers without support for query attributes don't get them
+    let mut buf = Vec::new();
+    execute.encode_with(&mut buf, Capabilities::empty());
+
+    assert_eq!(
+        buf,
+        b"\x17\x01\x00\x00\x00\x00\x01\x00\x00\x00\x00\x01\x03\x00\x01\x00\x00\x00"
+    );
+}
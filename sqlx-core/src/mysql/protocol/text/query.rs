@@ -1,4 +1,5 @@
 use crate::io::Encode;
+use crate::mysql::io::MySqlBufMutExt;
 use crate::mysql::protocol::Capabilities;
 
 // https://dev.mysql.com/doc/internals/en/com-query.html
@@ -7,8 +8,15 @@ use crate::mysql::protocol::Capabilities;
 pub(crate) struct Query<'q>(pub(crate) &'q str);
 
 impl Encode<'_, Capabilities> for Query<'_> {
-    fn encode_with(&self, buf: &mut Vec<u8>, _: Capabilities) {
+    fn encode_with(&self, buf: &mut Vec<u8>, capabilities: Capabilities) {
         buf.push(0x03); // COM_QUERY
+
+        if capabilities.contains(Capabilities::QUERY_ATTRIBUTES) {
+            // text queries are sent without query attributes
+            buf.put_uint_lenenc(0); // parameter_count
+            buf.put_uint_lenenc(1); // parameter_set_count (always 1)
+        }
+
         buf.extend(self.0.as_bytes())
     }
 }
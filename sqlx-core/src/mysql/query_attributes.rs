@@ -0,0 +1,66 @@
+use crate::encode::Encode;
+use crate::mysql::{MySql, MySqlArguments};
+use crate::query::Query;
+use crate::query_as::QueryAs;
+use crate::query_scalar::QueryScalar;
+use crate::types::Type;
+
+impl<'q> Query<'q, MySql, MySqlArguments> {
+    /// Add a query attribute, such as a trace ID, which is sent along with this query.
+    ///
+    /// Query attributes are supported by MySQL 8.0.23 and later and read by the server with
+    /// `mysql_query_attribute_string()`; servers without support for them ignore them.
+    /// See [`MySqlArguments::add_attribute`].
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # async fn example(conn: &mut sqlx_core::mysql::MySqlConnection) -> sqlx_core::error::Result<()> {
+    /// let (trace_id,): (Option<String>,) =
+    ///     sqlx_core::query_as::query_as("SELECT mysql_query_attribute_string('trace_id')")
+    ///         .attribute("trace_id", "5c8a9d")
+    ///         .fetch_one(conn)
+    ///         .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn attribute<T: 'q + Send + Encode<'q, MySql> + Type<MySql>>(
+        mut self,
+        name: &str,
+        value: T,
+    ) -> Self {
+        if let Some(arguments) = &mut self.arguments {
+            arguments.add_attribute(name, value);
+        }
+
+        self
+    }
+}
+
+impl<'q, O> QueryAs<'q, MySql, O, MySqlArguments> {
+    /// Add a query attribute which is sent along with this query.
+    ///
+    /// See [`Query::attribute`](Query::attribute).
+    pub fn attribute<T: 'q + Send + Encode<'q, MySql> + Type<MySql>>(
+        mut self,
+        name: &str,
+        value: T,
+    ) -> Self {
+        self.inner = self.inner.attribute(name, value);
+        self
+    }
+}
+
+impl<'q, O> QueryScalar<'q, MySql, O, MySqlArguments> {
+    /// Add a query attribute which is sent along with this query.
+    ///
+    /// See [`Query::attribute`](Query::attribute).
+    pub fn attribute<T: 'q + Send + Encode<'q, MySql> + Type<MySql>>(
+        mut self,
+        name: &str,
+        value: T,
+    ) -> Self {
+        self.inner = self.inner.attribute(name, value);
+        self
+    }
+}
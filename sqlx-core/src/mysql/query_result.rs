@@ -16,6 +16,12 @@ impl MySqlQueryResult {
     }
 }
 
+impl crate::dyn_connection::DynQueryResult for MySqlQueryResult {
+    fn rows_affected(&self) -> u64 {
+        self.rows_affected
+    }
+}
+
 impl Extend<MySqlQueryResult> for MySqlQueryResult {
     fn extend<T: IntoIterator<Item = MySqlQueryResult>>(&mut self, iter: T) {
         for elem in iter {
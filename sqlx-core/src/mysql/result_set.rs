@@ -0,0 +1,139 @@
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use futures_core::stream::BoxStream;
+use futures_core::Stream;
+use futures_util::TryStreamExt;
+
+use crate::error::Error;
+use crate::mysql::connection::Step;
+use crate::mysql::{MySqlColumn, MySqlQueryResult, MySqlRow};
+
+/// A cursor over the result sets of a query, returned by
+/// [`MySqlConnection::fetch_multi`](crate::mysql::MySqlConnection::fetch_multi).
+pub struct MySqlResultSets<'c> {
+    steps: BoxStream<'c, Result<Step, Error>>,
+
+    // the rows of the last result set returned by `next` have not all been read
+    reading_rows: bool,
+}
+
+/// A single result set of a query, which is a stream of its rows.
+pub struct MySqlResultSet<'r> {
+    steps: Pin<&'r mut (dyn Stream<Item = Result<Step, Error>> + Send + 'r)>,
+    reading_rows: &'r mut bool,
+    columns: Arc<Vec<MySqlColumn>>,
+    done: Option<MySqlQueryResult>,
+}
+
+impl<'c> MySqlResultSets<'c> {
+    pub(crate) fn new(steps: BoxStream<'c, Result<Step, Error>>) -> Self {
+        Self {
+            steps,
+            reading_rows: false,
+        }
+    }
+
+    /// Advance to the next result set, skipping any rows left unread in the current one.
+    ///
+    /// Returns `None` once all of the result sets have been read.
+    pub async fn next(&mut self) -> Result<Option<MySqlResultSet<'_>>, Error> {
+        while self.reading_rows {
+            match self.steps.try_next().await? {
+                Some(Step::Done(_)) | None => self.reading_rows = false,
+                Some(_) => {}
+            }
+        }
+
+        let (columns, done) = match self.steps.try_next().await? {
+            Some(Step::Columns(columns)) => {
+                self.reading_rows = true;
+
+                (columns, None)
+            }
+
+            Some(Step::Done(done)) => (Arc::default(), Some(done)),
+
+            Some(Step::Row(_)) => {
+                return Err(err_protocol!(
+                    "received a row before the start of a result set"
+                ));
+            }
+
+            None => return Ok(None),
+        };
+
+        Ok(Some(MySqlResultSet {
+            steps: self.steps.as_mut(),
+            reading_rows: &mut self.reading_rows,
+            columns,
+            done,
+        }))
+    }
+}
+
+impl MySqlResultSet<'_> {
+    /// The columns of the rows in this result set; empty if the statement doesn't return rows.
+    pub fn columns(&self) -> &[MySqlColumn] {
+        &self.columns
+    }
+
+    /// The number of rows affected by a statement that doesn't return rows.
+    pub fn rows_affected(&self) -> u64 {
+        self.done
+            .as_ref()
+            .map_or(0, MySqlQueryResult::rows_affected)
+    }
+
+    /// The last ID generated for an `AUTO_INCREMENT` column by a statement that doesn't return
+    /// rows.
+    pub fn last_insert_id(&self) -> u64 {
+        self.done
+            .as_ref()
+            .map_or(0, MySqlQueryResult::last_insert_id)
+    }
+}
+
+impl Stream for MySqlResultSet<'_> {
+    type Item = Result<MySqlRow, Error>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+
+        if !*this.reading_rows {
+            return Poll::Ready(None);
+        }
+
+        match futures_core::ready!(this.steps.as_mut().poll_next(cx)) {
+            Some(Ok(Step::Row(row))) => Poll::Ready(Some(Ok(row))),
+
+            Some(Ok(Step::Done(done))) => {
+                *this.reading_rows = false;
+                this.done = Some(done);
+
+                Poll::Ready(None)
+            }
+
+            Some(Ok(Step::Columns(_))) => {
+                *this.reading_rows = false;
+
+                Poll::Ready(Some(Err(err_protocol!(
+                    "received the start of a result set before the end of the previous one"
+                ))))
+            }
+
+            Some(Err(error)) => {
+                *this.reading_rows = false;
+
+                Poll::Ready(Some(Err(error)))
+            }
+
+            None => {
+                *this.reading_rows = false;
+
+                Poll::Ready(None)
+            }
+        }
+    }
+}
@@ -1,7 +1,9 @@
 use crate::column::ColumnIndex;
-use crate::error::Error;
+use crate::error::{Error, ErrorContext};
 use crate::ext::ustr::UStr;
-use crate::mysql::{protocol, MySql, MySqlColumn, MySqlValueFormat, MySqlValueRef};
+use crate::mysql::{
+    protocol, MySql, MySqlColumn, MySqlValueFormat, MySqlValueRef, MySqlZeroDateBehavior,
+};
 use crate::row::Row;
 use crate::HashMap;
 use std::sync::Arc;
@@ -13,6 +15,8 @@ pub struct MySqlRow {
     pub(crate) format: MySqlValueFormat,
     pub(crate) columns: Arc<Vec<MySqlColumn>>,
     pub(crate) column_names: Arc<HashMap<UStr, usize>>,
+    pub(crate) zero_dates: MySqlZeroDateBehavior,
+    pub(crate) context: Option<Arc<ErrorContext>>,
 }
 
 impl crate::row::private_row::Sealed for MySqlRow {}
@@ -36,9 +40,15 @@ impl Row for MySqlRow {
             format: self.format,
             row: Some(&self.row.storage),
             type_info: column.type_info.clone(),
+            column_name: Some(&column.name),
+            zero_dates: self.zero_dates,
             value,
         })
     }
+
+    fn error_context(&self) -> Option<&Arc<ErrorContext>> {
+        self.context.as_ref()
+    }
 }
 
 impl ColumnIndex<MySqlRow> for &'_ str {
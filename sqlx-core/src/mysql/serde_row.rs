@@ -0,0 +1,156 @@
+use crate::column::Column;
+use crate::decode::Decode;
+use crate::error::{BoxDynError, Error};
+use crate::mysql::protocol::text::{ColumnFlags, ColumnType};
+use crate::mysql::{MySql, MySqlRow, MySqlValueFormat, MySqlValueRef};
+use crate::row::Row;
+use crate::serde_row::{column_error, unsupported_type, ColumnValue, ColumnValues};
+use crate::type_info::TypeInfo;
+use crate::value::ValueRef;
+use byteorder::{ByteOrder, LittleEndian};
+
+impl ColumnValues for MySqlRow {
+    fn column_value(&self, index: usize) -> Result<ColumnValue, Error> {
+        let name = self.columns()[index].name();
+        let value = self.try_get_raw(index)?;
+
+        if value.is_null() {
+            return Ok(ColumnValue::Null);
+        }
+
+        convert(value)
+            .map_err(|source| column_error(name, source))?
+            .ok_or_else(|| unsupported_type(name, self.columns()[index].type_info().name()))
+    }
+}
+
+fn convert(value: MySqlValueRef<'_>) -> Result<Option<ColumnValue>, BoxDynError> {
+    let ty = &value.type_info;
+    let unsigned = ty.flags.contains(ColumnFlags::UNSIGNED);
+
+    Ok(Some(match ty.r#type {
+        ColumnType::Null => ColumnValue::Null,
+
+        ColumnType::Tiny if ty.max_size == Some(1) => ColumnValue::Bool(decode(value)?),
+
+        ColumnType::Tiny
+        | ColumnType::Short
+        | ColumnType::Long
+        | ColumnType::Int24
+        | ColumnType::LongLong
+            if unsigned =>
+        {
+            ColumnValue::U64(decode(value)?)
+        }
+
+        ColumnType::Tiny
+        | ColumnType::Short
+        | ColumnType::Long
+        | ColumnType::Int24
+        | ColumnType::LongLong
+        | ColumnType::Year => ColumnValue::I64(decode(value)?),
+
+        ColumnType::Bit => ColumnValue::U64(decode(value)?),
+
+        ColumnType::Float => ColumnValue::F64(decode::<f32>(value)?.into()),
+        ColumnType::Double => ColumnValue::F64(decode(value)?),
+
+        // decimals are sent as text in both formats
+        ColumnType::Decimal | ColumnType::NewDecimal | ColumnType::Enum | ColumnType::Set => {
+            ColumnValue::String(value.as_str()?.to_owned())
+        }
+
+        ColumnType::Json => ColumnValue::Json(serde_json::from_slice(value.as_bytes()?)?),
+
+        ColumnType::VarChar
+        | ColumnType::VarString
+        | ColumnType::String
+        | ColumnType::TinyBlob
+        | ColumnType::Blob
+        | ColumnType::MediumBlob
+        | ColumnType::LongBlob => {
+            if ty.char_set == 63 {
+                ColumnValue::Bytes(value.as_bytes()?.to_owned())
+            } else {
+                ColumnValue::String(value.as_str()?.to_owned())
+            }
+        }
+
+        ColumnType::Date | ColumnType::Datetime | ColumnType::Timestamp | ColumnType::Time => {
+            match value.format() {
+                MySqlValueFormat::Text => ColumnValue::String(value.as_str()?.to_owned()),
+                MySqlValueFormat::Binary => {
+                    ColumnValue::String(format_temporal(ty.r#type, value.as_bytes()?)?)
+                }
+            }
+        }
+
+        ColumnType::Geometry => return Ok(None),
+    }))
+}
+
+fn decode<'r, T: Decode<'r, MySql>>(value: MySqlValueRef<'r>) -> Result<T, BoxDynError> {
+    T::decode(value)
+}
+
+/// Formats a date or time in the binary format like MySQL does in the text format, e.g.
+/// `2022-02-28 13:05:00.500000` or `-838:59:59`.
+fn format_temporal(ty: ColumnType, buf: &[u8]) -> Result<String, BoxDynError> {
+    // https://dev.mysql.com/doc/internals/en/binary-protocol-value.html
+    let (&len, buf) = buf.split_first().ok_or("empty date or time value")?;
+    let len = usize::from(len);
+
+    if buf.len() < len {
+        return Err(format!("expected a date or time of {} bytes", len).into());
+    }
+
+    if ty == ColumnType::Time {
+        // all of the fields are omitted if they are zero
+        let mut fields = [0_u8; 12];
+        fields[..len].copy_from_slice(&buf[..len]);
+
+        let sign = if fields[0] == 1 { "-" } else { "" };
+        let hours = u64::from(LittleEndian::read_u32(&fields[1..])) * 24 + u64::from(fields[5]);
+        let micros = LittleEndian::read_u32(&fields[8..]);
+
+        return Ok(format!(
+            "{}{:02}:{:02}:{:02}{}",
+            sign,
+            hours,
+            fields[6],
+            fields[7],
+            format_micros(micros)
+        ));
+    }
+
+    let mut fields = [0_u8; 11];
+    fields[..len].copy_from_slice(&buf[..len]);
+
+    let date = format!(
+        "{:04}-{:02}-{:02}",
+        LittleEndian::read_u16(&fields),
+        fields[2],
+        fields[3]
+    );
+
+    if ty == ColumnType::Date {
+        return Ok(date);
+    }
+
+    Ok(format!(
+        "{} {:02}:{:02}:{:02}{}",
+        date,
+        fields[4],
+        fields[5],
+        fields[6],
+        format_micros(LittleEndian::read_u32(&fields[7..]))
+    ))
+}
+
+fn format_micros(micros: u32) -> String {
+    if micros == 0 {
+        String::new()
+    } else {
+        format!(".{:06}", micros)
+    }
+}
@@ -1,5 +1,6 @@
 use super::MySqlColumn;
 use crate::column::ColumnIndex;
+use crate::common::PreparedStatement;
 use crate::error::Error;
 use crate::ext::ustr::UStr;
 use crate::mysql::{MySql, MySqlArguments, MySqlTypeInfo};
@@ -13,6 +14,8 @@ use std::sync::Arc;
 pub struct MySqlStatement<'q> {
     pub(crate) sql: Cow<'q, str>,
     pub(crate) metadata: MySqlStatementMetadata,
+    // the statement prepared by a connection, unless the statement was detached from it
+    pub(crate) prepared: Option<PreparedStatement<u32>>,
 }
 
 #[derive(Debug, Default, Clone)]
@@ -29,6 +32,7 @@ impl<'q> Statement<'q> for MySqlStatement<'q> {
         MySqlStatement::<'static> {
             sql: Cow::Owned(self.sql.clone().into_owned()),
             metadata: self.metadata.clone(),
+            prepared: self.prepared.clone(),
         }
     }
 
@@ -36,6 +40,10 @@ impl<'q> Statement<'q> for MySqlStatement<'q> {
         &self.sql
     }
 
+    fn detach(&mut self) {
+        self.prepared = None;
+    }
+
     fn parameters(&self) -> Option<Either<&[MySqlTypeInfo], usize>> {
         Some(Either::Right(self.metadata.parameters))
     }
@@ -53,6 +53,16 @@ impl TestSupport for MySql {
         })
     }
 
+    fn execute_without_transaction<'c>(
+        conn: &'c mut MySqlConnection,
+        sql: &'c str,
+    ) -> BoxFuture<'c, Result<(), Error>> {
+        Box::pin(async move {
+            let _ = conn.execute(sql).await?;
+            Ok(())
+        })
+    }
+
     fn cleanup_test_dbs() -> BoxFuture<'static, Result<Option<usize>, Error>> {
         Box::pin(async move {
             let url = dotenvy::var("DATABASE_URL").expect("DATABASE_URL must be set");
@@ -138,6 +148,16 @@ async fn test_context(args: &TestArgs) -> Result<TestContext<MySql>, Error> {
 
     let new_db_name = db_name(new_db_id);
 
+    // Mark the database as in use for tests running in other processes, e.g. with
+    // `cargo nextest`. The lock is held by a dedicated connection rather than one of the master
+    // pool, and MySQL releases it when it's closed, even if the test process dies.
+    let mut guard = MySqlConnection::connect_with(master_pool.connect_options()).await?;
+
+    query("select get_lock(?, 10)")
+        .bind(&new_db_name)
+        .execute(&mut guard)
+        .await?;
+
     conn.execute(&format!("create database {}", new_db_name)[..])
         .await?;
 
@@ -154,6 +174,7 @@ async fn test_context(args: &TestArgs) -> Result<TestContext<MySql>, Error> {
             .parent(master_pool.clone()),
         connect_opts: master_pool.connect_options().clone().database(&new_db_name),
         db_name: new_db_name,
+        guard: Some(guard),
     })
 }
 
@@ -177,6 +198,16 @@ async fn do_cleanup(conn: &mut MySqlConnection) -> Result<usize, Error> {
 
         let db_name = db_name(db_id);
 
+        // Skip the databases of tests still running in other processes, which hold their lock.
+        let in_use: bool = query_scalar("select is_used_lock(?) is not null")
+            .bind(&db_name)
+            .fetch_one(&mut *conn)
+            .await?;
+
+        if in_use {
+            continue;
+        }
+
         writeln!(command, "drop database if exists {}", db_name).ok();
         match conn.execute(&*command).await {
             Ok(_deleted) => {
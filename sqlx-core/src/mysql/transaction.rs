@@ -7,7 +7,7 @@ use crate::mysql::protocol::text::Query;
 use crate::mysql::{MySql, MySqlConnection};
 use crate::transaction::{
     begin_ansi_transaction_sql, commit_ansi_transaction_sql, rollback_ansi_transaction_sql,
-    TransactionManager,
+    TransactionManager, TransactionOptions,
 };
 
 /// Implementation of [`TransactionManager`] for MySQL.
@@ -27,6 +27,43 @@ impl TransactionManager for MySqlTransactionManager {
         })
     }
 
+    fn begin_with(
+        conn: &mut MySqlConnection,
+        options: TransactionOptions,
+    ) -> BoxFuture<'_, Result<(), Error>> {
+        Box::pin(async move {
+            let depth = conn.transaction_depth;
+
+            options.check_savepoint(depth)?;
+
+            #[cfg(feature = "sqlite")]
+            if options.get_sqlite_behavior().is_some() {
+                return Err(options.unsupported("MySQL"));
+            }
+
+            if depth > 0 || options.is_default() {
+                return Self::begin(conn).await;
+            }
+
+            // applies to the next transaction of the session only
+            if let Some(level) = options.get_isolation_level() {
+                conn.execute(&*format!("SET TRANSACTION ISOLATION LEVEL {}", level.sql()))
+                    .await?;
+            }
+
+            let query = if options.is_read_only() {
+                "START TRANSACTION READ ONLY"
+            } else {
+                "START TRANSACTION"
+            };
+
+            conn.execute(query).await?;
+            conn.transaction_depth = depth + 1;
+
+            Ok(())
+        })
+    }
+
     fn commit(conn: &mut MySqlConnection) -> BoxFuture<'_, Result<(), Error>> {
         Box::pin(async move {
             let depth = conn.transaction_depth;
@@ -78,7 +78,13 @@ impl TypeInfo for MySqlTypeInfo {
 
 impl PartialEq<MySqlTypeInfo> for MySqlTypeInfo {
     fn eq(&self, other: &MySqlTypeInfo) -> bool {
-        if self.r#type != other.r#type {
+        // `MEDIUMINT` has no Rust type of its own, and maps to the same types as `INT`
+        let normalize = |ty| match ty {
+            ColumnType::Int24 => ColumnType::Long,
+            ty => ty,
+        };
+
+        if normalize(self.r#type) != normalize(other.r#type) {
             return false;
         }
 
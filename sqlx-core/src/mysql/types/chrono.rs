@@ -3,7 +3,7 @@ use chrono::{DateTime, Datelike, Local, NaiveDate, NaiveDateTime, NaiveTime, Tim
 
 use crate::decode::Decode;
 use crate::encode::{Encode, IsNull};
-use crate::error::{BoxDynError, UnexpectedNullError};
+use crate::error::BoxDynError;
 use crate::mysql::protocol::text::ColumnType;
 use crate::mysql::type_info::MySqlTypeInfo;
 use crate::mysql::{MySql, MySqlValueFormat, MySqlValueRef};
@@ -19,14 +19,16 @@ impl Type<MySql> for DateTime<Utc> {
     }
 }
 
-/// Note: assumes the connection's `time_zone` is set to `+00:00` (UTC).
+/// Note: assumes the connection's `time_zone` is `+00:00` (UTC), which it is unless changed with
+/// [`MySqlConnectOptions::timezone`](crate::mysql::MySqlConnectOptions::timezone).
 impl Encode<'_, MySql> for DateTime<Utc> {
     fn encode_by_ref(&self, buf: &mut Vec<u8>) -> IsNull {
         Encode::<MySql>::encode(&self.naive_utc(), buf)
     }
 }
 
-/// Note: assumes the connection's `time_zone` is set to `+00:00` (UTC).
+/// Note: assumes the connection's `time_zone` is `+00:00` (UTC), which it is unless changed with
+/// [`MySqlConnectOptions::timezone`](crate::mysql::MySqlConnectOptions::timezone).
 impl<'r> Decode<'r, MySql> for DateTime<Utc> {
     fn decode(value: MySqlValueRef<'r>) -> Result<Self, BoxDynError> {
         let naive: NaiveDateTime = Decode::<MySql>::decode(value)?;
@@ -45,14 +47,16 @@ impl Type<MySql> for DateTime<Local> {
     }
 }
 
-/// Note: assumes the connection's `time_zone` is set to `+00:00` (UTC).
+/// Note: assumes the connection's `time_zone` is `+00:00` (UTC), which it is unless changed with
+/// [`MySqlConnectOptions::timezone`](crate::mysql::MySqlConnectOptions::timezone).
 impl Encode<'_, MySql> for DateTime<Local> {
     fn encode_by_ref(&self, buf: &mut Vec<u8>) -> IsNull {
         Encode::<MySql>::encode(&self.naive_utc(), buf)
     }
 }
 
-/// Note: assumes the connection's `time_zone` is set to `+00:00` (UTC).
+/// Note: assumes the connection's `time_zone` is `+00:00` (UTC), which it is unless changed with
+/// [`MySqlConnectOptions::timezone`](crate::mysql::MySqlConnectOptions::timezone).
 impl<'r> Decode<'r, MySql> for DateTime<Local> {
     fn decode(value: MySqlValueRef<'r>) -> Result<Self, BoxDynError> {
         Ok(<DateTime<Utc> as Decode<'r, MySql>>::decode(value)?.with_timezone(&Local))
@@ -150,14 +154,17 @@ impl Encode<'_, MySql> for NaiveDate {
 
 impl<'r> Decode<'r, MySql> for NaiveDate {
     fn decode(value: MySqlValueRef<'r>) -> Result<Self, BoxDynError> {
+        if value.is_zero_date() {
+            return value.decode_zero_date(min_date);
+        }
+
         match value.format() {
-            MySqlValueFormat::Binary => {
-                decode_date(&value.as_bytes()?[1..]).ok_or_else(|| UnexpectedNullError.into())
-            }
+            MySqlValueFormat::Binary => decode_date(&value.as_bytes()?[1..])
+                .ok_or_else(|| value.invalid_temporal("out of range")),
 
             MySqlValueFormat::Text => {
                 let s = value.as_str()?;
-                NaiveDate::parse_from_str(s, "%Y-%m-%d").map_err(Into::into)
+                NaiveDate::parse_from_str(s, "%Y-%m-%d").map_err(|e| value.invalid_temporal(e))
             }
         }
     }
@@ -207,12 +214,17 @@ impl Encode<'_, MySql> for NaiveDateTime {
 
 impl<'r> Decode<'r, MySql> for NaiveDateTime {
     fn decode(value: MySqlValueRef<'r>) -> Result<Self, BoxDynError> {
+        if value.is_zero_date() {
+            return value.decode_zero_date(|| min_date().and_hms(0, 0, 0));
+        }
+
         match value.format() {
             MySqlValueFormat::Binary => {
                 let buf = value.as_bytes()?;
 
                 let len = buf[0];
-                let date = decode_date(&buf[1..]).ok_or(UnexpectedNullError)?;
+                let date =
+                    decode_date(&buf[1..]).ok_or_else(|| value.invalid_temporal("out of range"))?;
 
                 let dt = if len > 4 {
                     date.and_time(decode_time(len - 4, &buf[5..]))
@@ -225,7 +237,8 @@ impl<'r> Decode<'r, MySql> for NaiveDateTime {
 
             MySqlValueFormat::Text => {
                 let s = value.as_str()?;
-                NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S%.f").map_err(Into::into)
+                NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S%.f")
+                    .map_err(|e| value.invalid_temporal(e))
             }
         }
     }
@@ -247,14 +260,17 @@ fn decode_date(mut buf: &[u8]) -> Option<NaiveDate> {
         None
     } else {
         let year = buf.get_u16_le();
-        Some(NaiveDate::from_ymd(
-            year as i32,
-            buf[0] as u32,
-            buf[1] as u32,
-        ))
+
+        // months and days of zero are allowed by MySQL without the `NO_ZERO_IN_DATE` SQL mode
+        NaiveDate::from_ymd_opt(year as i32, buf[0] as u32, buf[1] as u32)
     }
 }
 
+// the earliest date supported by MySQL, which zero dates may be converted to
+fn min_date() -> NaiveDate {
+    NaiveDate::from_ymd(1000, 1, 1)
+}
+
 fn encode_time(time: &NaiveTime, include_micros: bool, buf: &mut Vec<u8>) {
     buf.push(time.hour() as u8);
     buf.push(time.minute() as u8);
@@ -279,3 +295,96 @@ fn decode_time(len: u8, mut buf: &[u8]) -> NaiveTime {
 
     NaiveTime::from_hms_micro(hour as u32, minute as u32, seconds as u32, micros as u32)
 }
+
+#[cfg(test)]
+fn zero_date_value<'r>(
+    value: &'r [u8],
+    format: MySqlValueFormat,
+    ty: ColumnType,
+    zero_dates: crate::mysql::MySqlZeroDateBehavior,
+) -> MySqlValueRef<'r> {
+    MySqlValueRef {
+        value: Some(value),
+        row: None,
+        type_info: MySqlTypeInfo::binary(ty),
+        format,
+        column_name: None,
+        zero_dates,
+    }
+}
+
+#[test]
+fn test_decode_zero_dates() {
+    use crate::mysql::MySqlZeroDateBehavior;
+    use crate::value::ValueRef;
+
+    let min = NaiveDate::from_ymd(1000, 1, 1);
+
+    for (value, format) in [
+        (&b"0000-00-00"[..], MySqlValueFormat::Text),
+        (&b"\x00"[..], MySqlValueFormat::Binary),
+    ] {
+        let zero = |zero_dates| zero_date_value(value, format, ColumnType::Date, zero_dates);
+
+        assert!(zero(MySqlZeroDateBehavior::ConvertToNone).is_null());
+        assert!(!zero(MySqlZeroDateBehavior::Error).is_null());
+        assert!(!zero(MySqlZeroDateBehavior::ConvertToMin).is_null());
+
+        let error =
+            <NaiveDate as Decode<MySql>>::decode(zero(MySqlZeroDateBehavior::Error)).unwrap_err();
+        assert!(error.to_string().contains("`0000-00-00`"), "{}", error);
+
+        assert_eq!(
+            <NaiveDate as Decode<MySql>>::decode(zero(MySqlZeroDateBehavior::ConvertToMin))
+                .unwrap(),
+            min
+        );
+    }
+
+    for (value, format) in [
+        (&b"0000-00-00 00:00:00"[..], MySqlValueFormat::Text),
+        (&b"\x00"[..], MySqlValueFormat::Binary),
+    ] {
+        let zero = zero_date_value(
+            value,
+            format,
+            ColumnType::Timestamp,
+            MySqlZeroDateBehavior::ConvertToMin,
+        );
+
+        assert_eq!(
+            <NaiveDateTime as Decode<MySql>>::decode(zero).unwrap(),
+            min.and_hms(0, 0, 0)
+        );
+    }
+}
+
+#[test]
+fn test_decode_invalid_dates() {
+    use crate::mysql::MySqlZeroDateBehavior;
+
+    // 2020-00-15, with a zero month
+    for (value, format) in [
+        (&b"2020-00-15"[..], MySqlValueFormat::Text),
+        (&b"\x04\xe4\x07\x00\x0f"[..], MySqlValueFormat::Binary),
+    ] {
+        let value = MySqlValueRef {
+            column_name: Some(&crate::ext::ustr::UStr::Static("created_at")),
+            ..zero_date_value(
+                value,
+                format,
+                ColumnType::Date,
+                MySqlZeroDateBehavior::ConvertToNone,
+            )
+        };
+
+        let error = <NaiveDate as Decode<MySql>>::decode(value)
+            .unwrap_err()
+            .to_string();
+        assert!(
+            error.starts_with("invalid DATE value `2020-00-15` in column `created_at`"),
+            "{}",
+            error
+        );
+    }
+}
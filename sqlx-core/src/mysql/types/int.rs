@@ -1,3 +1,5 @@
+use std::any::type_name;
+
 use byteorder::{ByteOrder, LittleEndian};
 
 use crate::decode::Decode;
@@ -8,6 +10,7 @@ use crate::mysql::{MySql, MySqlTypeInfo, MySqlValueFormat, MySqlValueRef};
 use crate::types::Type;
 
 fn int_compatible(ty: &MySqlTypeInfo) -> bool {
+    // unsigned columns are accepted too, with values out of range failing to decode
     matches!(
         ty.r#type,
         ColumnType::Tiny
@@ -15,7 +18,7 @@ fn int_compatible(ty: &MySqlTypeInfo) -> bool {
             | ColumnType::Long
             | ColumnType::Int24
             | ColumnType::LongLong
-    ) && !ty.flags.contains(ColumnFlags::UNSIGNED)
+    )
 }
 
 impl Type<MySql> for i8 {
@@ -90,31 +93,51 @@ impl Encode<'_, MySql> for i64 {
     }
 }
 
-fn int_decode(value: MySqlValueRef<'_>) -> Result<i64, BoxDynError> {
-    Ok(match value.format() {
+/// Decode an integer into `T`, reading the value as unsigned if the column is `UNSIGNED`, so
+/// that it never wraps around, and returning an error if it is out of range for `T`.
+pub(super) fn int_decode<T>(value: MySqlValueRef<'_>) -> Result<T, BoxDynError>
+where
+    T: TryFrom<i128>,
+{
+    let int: i128 = match value.format() {
         MySqlValueFormat::Text => value.as_str()?.parse()?,
         MySqlValueFormat::Binary => {
             let buf = value.as_bytes()?;
-            LittleEndian::read_int(buf, buf.len())
+
+            if value.type_info.flags.contains(ColumnFlags::UNSIGNED) {
+                LittleEndian::read_uint(buf, buf.len()).into()
+            } else {
+                LittleEndian::read_int(buf, buf.len()).into()
+            }
         }
+    };
+
+    T::try_from(int).map_err(|_| {
+        format!(
+            "value {} of {} is out of range for `{}`",
+            int,
+            value.type_info,
+            type_name::<T>()
+        )
+        .into()
     })
 }
 
 impl Decode<'_, MySql> for i8 {
     fn decode(value: MySqlValueRef<'_>) -> Result<Self, BoxDynError> {
-        int_decode(value)?.try_into().map_err(Into::into)
+        int_decode(value)
     }
 }
 
 impl Decode<'_, MySql> for i16 {
     fn decode(value: MySqlValueRef<'_>) -> Result<Self, BoxDynError> {
-        int_decode(value)?.try_into().map_err(Into::into)
+        int_decode(value)
     }
 }
 
 impl Decode<'_, MySql> for i32 {
     fn decode(value: MySqlValueRef<'_>) -> Result<Self, BoxDynError> {
-        int_decode(value)?.try_into().map_err(Into::into)
+        int_decode(value)
     }
 }
 
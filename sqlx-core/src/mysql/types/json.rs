@@ -5,7 +5,7 @@ use crate::encode::{Encode, IsNull};
 use crate::error::BoxDynError;
 use crate::mysql::protocol::text::ColumnType;
 use crate::mysql::{MySql, MySqlTypeInfo, MySqlValueRef};
-use crate::types::{Json, Type};
+use crate::types::{Json, JsonRawValue, Type};
 
 impl<T> Type<MySql> for Json<T> {
     fn type_info() -> MySqlTypeInfo {
@@ -16,6 +16,8 @@ impl<T> Type<MySql> for Json<T> {
     }
 
     fn compatible(ty: &MySqlTypeInfo) -> bool {
+        // MySQL reports `JSON` columns as their own type, while MariaDB uses an alias for
+        // `LONGTEXT` with a `JSON_VALID` check, which is only distinguishable from text by name
         ty.r#type == ColumnType::Json
             || <&str as Type<MySql>>::compatible(ty)
             || <&[u8] as Type<MySql>>::compatible(ty)
@@ -34,11 +36,20 @@ where
     }
 }
 
+// a raw value is already serialized, so can be sent as it is
+impl Encode<'_, MySql> for &'_ JsonRawValue {
+    fn encode_by_ref(&self, buf: &mut Vec<u8>) -> IsNull {
+        <&str as Encode<MySql>>::encode(self.get(), buf)
+    }
+}
+
 impl<'r, T> Decode<'r, MySql> for Json<T>
 where
     T: 'r + Deserialize<'r>,
 {
     fn decode(value: MySqlValueRef<'r>) -> Result<Self, BoxDynError> {
+        // both protocols send JSON as text, so a SQL `NULL` never reaches here and a JSON `null`
+        // is decoded like any other document
         let string_value = <&str as Decode<MySql>>::decode(value)?;
 
         serde_json::from_str(&string_value)
@@ -16,7 +16,11 @@
 //! | `f32`                                 | FLOAT                                                |
 //! | `f64`                                 | DOUBLE                                               |
 //! | `&str`, [`String`]                    | VARCHAR, CHAR, TEXT                                  |
-//! | `&[u8]`, `Vec<u8>`                    | VARBINARY, BINARY, BLOB                              |
+//! | `&[u8]`, `Vec<u8>`, [`Bytes`](crate::types::Bytes)| VARBINARY, BINARY, BLOB                              |
+//!
+//! Decoding to [`Bytes`](crate::types::Bytes) shares the buffer of the row instead of copying
+//! the value, so a large `BLOB` can be handed off without holding it in memory twice. The
+//! buffer of the whole row is kept alive until all of its `Bytes` are dropped.
 //!
 //! ### [`chrono`](https://crates.io/crates/chrono)
 //!
@@ -74,6 +78,9 @@
 //! | `serde_json::JsonValue`               | JSON                                                 |
 //! | `&serde_json::value::RawValue`        | JSON                                                 |
 //!
+//! MariaDB's `JSON` is an alias for `LONGTEXT`, so the query macros infer `String` for its
+//! columns; use a type override such as ``SELECT data as `data: Json<T>` `` to decode them as JSON.
+//!
 //! # Nullable
 //!
 //! In addition, `Option<T>` is supported where `T` implements `Type`. An `Option<T>` represents
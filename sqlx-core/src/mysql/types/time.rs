@@ -21,6 +21,8 @@ impl Type<MySql> for OffsetDateTime {
     }
 }
 
+/// Note: assumes the connection's `time_zone` is `+00:00` (UTC), which it is unless changed with
+/// [`MySqlConnectOptions::timezone`](crate::mysql::MySqlConnectOptions::timezone).
 impl Encode<'_, MySql> for OffsetDateTime {
     fn encode_by_ref(&self, buf: &mut Vec<u8>) -> IsNull {
         let utc_dt = self.to_offset(UtcOffset::UTC);
@@ -30,6 +32,8 @@ impl Encode<'_, MySql> for OffsetDateTime {
     }
 }
 
+/// Note: assumes the connection's `time_zone` is `+00:00` (UTC), which it is unless changed with
+/// [`MySqlConnectOptions::timezone`](crate::mysql::MySqlConnectOptions::timezone).
 impl<'r> Decode<'r, MySql> for OffsetDateTime {
     fn decode(value: MySqlValueRef<'r>) -> Result<Self, BoxDynError> {
         let primitive: PrimitiveDateTime = Decode::<MySql>::decode(value)?;
@@ -130,13 +134,18 @@ impl Encode<'_, MySql> for Date {
 
 impl<'r> Decode<'r, MySql> for Date {
     fn decode(value: MySqlValueRef<'r>) -> Result<Self, BoxDynError> {
+        if value.is_zero_date() {
+            return value.decode_zero_date(min_date);
+        }
+
         match value.format() {
-            MySqlValueFormat::Binary => {
-                Ok(decode_date(&value.as_bytes()?[1..])?.ok_or(UnexpectedNullError)?)
-            }
+            MySqlValueFormat::Binary => decode_date(&value.as_bytes()?[1..])
+                .map_err(|e| value.invalid_temporal(e))?
+                .ok_or_else(|| UnexpectedNullError.into()),
             MySqlValueFormat::Text => {
                 let s = value.as_str()?;
-                Date::parse(s, &format_description!("[year]-[month]-[day]")).map_err(Into::into)
+                Date::parse(s, &format_description!("[year]-[month]-[day]"))
+                    .map_err(|e| value.invalid_temporal(e))
             }
         }
     }
@@ -181,11 +190,17 @@ impl Encode<'_, MySql> for PrimitiveDateTime {
 
 impl<'r> Decode<'r, MySql> for PrimitiveDateTime {
     fn decode(value: MySqlValueRef<'r>) -> Result<Self, BoxDynError> {
+        if value.is_zero_date() {
+            return value.decode_zero_date(|| min_date().midnight());
+        }
+
         match value.format() {
             MySqlValueFormat::Binary => {
                 let buf = value.as_bytes()?;
                 let len = buf[0];
-                let date = decode_date(&buf[1..])?.ok_or(UnexpectedNullError)?;
+                let date = decode_date(&buf[1..])
+                    .map_err(|e| value.invalid_temporal(e))?
+                    .ok_or(UnexpectedNullError)?;
 
                 let dt = if len > 4 {
                     date.with_time(decode_time(len - 4, &buf[5..])?)
@@ -207,13 +222,13 @@ impl<'r> Decode<'r, MySql> for PrimitiveDateTime {
                             "[year]-[month]-[day] [hour]:[minute]:[second].[subsecond]"
                         ),
                     )
-                    .map_err(Into::into)
+                    .map_err(|e| value.invalid_temporal(e))
                 } else {
                     PrimitiveDateTime::parse(
                         s,
                         &format_description!("[year]-[month]-[day] [hour]:[minute]:[second]"),
                     )
-                    .map_err(Into::into)
+                    .map_err(|e| value.invalid_temporal(e))
                 }
             }
         }
@@ -230,6 +245,11 @@ fn encode_date(date: &Date, buf: &mut Vec<u8>) {
     buf.push(date.day());
 }
 
+// the earliest date supported by MySQL, which zero dates may be converted to
+fn min_date() -> Date {
+    Date::from_calendar_date(1000, time::Month::January, 1).unwrap()
+}
+
 fn decode_date(buf: &[u8]) -> Result<Option<Date>, BoxDynError> {
     if buf.is_empty() {
         // zero buffer means a zero date (null)
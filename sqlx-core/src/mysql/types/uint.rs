@@ -2,9 +2,10 @@ use crate::decode::Decode;
 use crate::encode::{Encode, IsNull};
 use crate::error::BoxDynError;
 use crate::mysql::protocol::text::{ColumnFlags, ColumnType};
-use crate::mysql::{MySql, MySqlTypeInfo, MySqlValueFormat, MySqlValueRef};
+use crate::mysql::types::int::int_decode;
+use crate::mysql::{MySql, MySqlTypeInfo, MySqlValueRef};
 use crate::types::Type;
-use byteorder::{ByteOrder, LittleEndian};
+use std::any::type_name;
 
 fn uint_type_info(ty: ColumnType) -> MySqlTypeInfo {
     MySqlTypeInfo {
@@ -34,7 +35,8 @@ impl Type<MySql> for u8 {
     }
 
     fn compatible(ty: &MySqlTypeInfo) -> bool {
-        uint_compatible(ty)
+        // years are never small enough
+        uint_compatible(ty) && ty.r#type != ColumnType::Year
     }
 }
 
@@ -100,45 +102,48 @@ impl Encode<'_, MySql> for u64 {
     }
 }
 
-fn uint_decode(value: MySqlValueRef<'_>) -> Result<u64, BoxDynError> {
+fn uint_decode<T>(value: MySqlValueRef<'_>) -> Result<T, BoxDynError>
+where
+    T: TryFrom<u64> + TryFrom<i128>,
+{
     if value.type_info.r#type == ColumnType::Bit {
         // NOTE: Regardless of the value format, there is raw binary data here
 
         let buf = value.as_bytes()?;
-        let mut value: u64 = 0;
+        let mut int: u64 = 0;
 
         for b in buf {
-            value = (*b as u64) | (value << 8);
+            int = (*b as u64) | (int << 8);
         }
 
-        return Ok(value);
+        return T::try_from(int).map_err(|_| {
+            format!(
+                "value {} of BIT is out of range for `{}`",
+                int,
+                type_name::<T>()
+            )
+            .into()
+        });
     }
 
-    Ok(match value.format() {
-        MySqlValueFormat::Text => value.as_str()?.parse()?,
-
-        MySqlValueFormat::Binary => {
-            let buf = value.as_bytes()?;
-            LittleEndian::read_uint(buf, buf.len())
-        }
-    })
+    int_decode(value)
 }
 
 impl Decode<'_, MySql> for u8 {
     fn decode(value: MySqlValueRef<'_>) -> Result<Self, BoxDynError> {
-        uint_decode(value)?.try_into().map_err(Into::into)
+        uint_decode(value)
     }
 }
 
 impl Decode<'_, MySql> for u16 {
     fn decode(value: MySqlValueRef<'_>) -> Result<Self, BoxDynError> {
-        uint_decode(value)?.try_into().map_err(Into::into)
+        uint_decode(value)
     }
 }
 
 impl Decode<'_, MySql> for u32 {
     fn decode(value: MySqlValueRef<'_>) -> Result<Self, BoxDynError> {
-        uint_decode(value)?.try_into().map_err(Into::into)
+        uint_decode(value)
     }
 }
 
@@ -1,12 +1,13 @@
 use crate::error::{BoxDynError, UnexpectedNullError};
+use crate::ext::ustr::UStr;
 use crate::mysql::protocol::text::ColumnType;
-use crate::mysql::{MySql, MySqlTypeInfo};
+use crate::mysql::{MySql, MySqlTypeInfo, MySqlZeroDateBehavior};
 use crate::value::{Value, ValueRef};
 use bytes::Bytes;
 use std::borrow::Cow;
 use std::str::from_utf8;
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
 #[repr(u8)]
 pub enum MySqlValueFormat {
     Text,
@@ -19,6 +20,8 @@ pub struct MySqlValue {
     value: Option<Bytes>,
     type_info: MySqlTypeInfo,
     format: MySqlValueFormat,
+    column_name: Option<UStr>,
+    zero_dates: MySqlZeroDateBehavior,
 }
 
 /// Implementation of [`ValueRef`] for MySQL.
@@ -28,6 +31,8 @@ pub struct MySqlValueRef<'r> {
     pub(crate) row: Option<&'r Bytes>,
     pub(crate) type_info: MySqlTypeInfo,
     pub(crate) format: MySqlValueFormat,
+    pub(crate) column_name: Option<&'r UStr>,
+    pub(crate) zero_dates: MySqlZeroDateBehavior,
 }
 
 impl<'r> MySqlValueRef<'r> {
@@ -45,6 +50,63 @@ impl<'r> MySqlValueRef<'r> {
     pub(crate) fn as_str(&self) -> Result<&'r str, BoxDynError> {
         Ok(from_utf8(self.as_bytes()?)?)
     }
+
+    /// Whether this is the zero value of a `DATE`, `DATETIME` or `TIMESTAMP`, e.g. `0000-00-00`.
+    #[cfg(any(feature = "chrono", feature = "time"))]
+    pub(crate) fn is_zero_date(&self) -> bool {
+        is_zero_date(self.value, &self.type_info, self.format)
+    }
+
+    /// Decode a zero date as configured with [`MySqlZeroDateBehavior`], where `min` is the value
+    /// it is converted to.
+    #[cfg(any(feature = "chrono", feature = "time"))]
+    pub(crate) fn decode_zero_date<T>(&self, min: impl FnOnce() -> T) -> Result<T, BoxDynError> {
+        match self.zero_dates {
+            MySqlZeroDateBehavior::ConvertToMin => Ok(min()),
+
+            // zero dates are NULL, so this is only reached when not decoding into an `Option`
+            MySqlZeroDateBehavior::ConvertToNone => {
+                Err(self.invalid_temporal("zero dates are decoded as NULL"))
+            }
+
+            MySqlZeroDateBehavior::Error => Err(self.invalid_temporal(
+                "zero dates are not allowed; see `MySqlConnectOptions::zero_dates`",
+            )),
+        }
+    }
+
+    /// An error for a `DATE`, `DATETIME` or `TIMESTAMP` value that can't be decoded, naming its
+    /// column and raw value.
+    #[cfg(any(feature = "chrono", feature = "time"))]
+    pub(crate) fn invalid_temporal(&self, reason: impl std::fmt::Display) -> BoxDynError {
+        use crate::type_info::TypeInfo;
+
+        let raw = match (self.value, self.format) {
+            (None, _) => "NULL".to_owned(),
+            (Some(value), MySqlValueFormat::Text) => String::from_utf8_lossy(value).into_owned(),
+            (Some(value), MySqlValueFormat::Binary) => {
+                format_binary_temporal(value, self.type_info.r#type)
+            }
+        };
+
+        match self.column_name {
+            Some(column) => format!(
+                "invalid {} value `{}` in column `{}`: {}",
+                self.type_info.name(),
+                raw,
+                column,
+                reason
+            ),
+
+            None => format!(
+                "invalid {} value `{}`: {}",
+                self.type_info.name(),
+                raw,
+                reason
+            ),
+        }
+        .into()
+    }
 }
 
 impl Value for MySqlValue {
@@ -53,9 +115,11 @@ impl Value for MySqlValue {
     fn as_ref(&self) -> MySqlValueRef<'_> {
         MySqlValueRef {
             value: self.value.as_deref(),
-            row: None,
+            row: self.value.as_ref(),
             type_info: self.type_info.clone(),
             format: self.format,
+            column_name: self.column_name.as_ref(),
+            zero_dates: self.zero_dates,
         }
     }
 
@@ -64,7 +128,12 @@ impl Value for MySqlValue {
     }
 
     fn is_null(&self) -> bool {
-        is_null(self.value.as_deref(), &self.type_info)
+        is_null(
+            self.value.as_deref(),
+            &self.type_info,
+            self.format,
+            self.zero_dates,
+        )
     }
 }
 
@@ -84,6 +153,8 @@ impl<'r> ValueRef<'r> for MySqlValueRef<'r> {
             value,
             format: self.format,
             type_info: self.type_info.clone(),
+            column_name: self.column_name.cloned(),
+            zero_dates: self.zero_dates,
         }
     }
 
@@ -93,7 +164,7 @@ impl<'r> ValueRef<'r> for MySqlValueRef<'r> {
 
     #[inline]
     fn is_null(&self) -> bool {
-        is_null(self.value.as_deref(), &self.type_info)
+        is_null(self.value, &self.type_info, self.format, self.zero_dates)
     }
 }
 
@@ -119,17 +190,61 @@ impl From<MySqlValue> for crate::any::AnyValue {
     }
 }
 
-fn is_null(value: Option<&[u8]>, ty: &MySqlTypeInfo) -> bool {
-    if let Some(value) = value {
-        // zero dates and date times should be treated the same as NULL
-        if matches!(
-            ty.r#type,
-            ColumnType::Date | ColumnType::Timestamp | ColumnType::Datetime
-        ) && value.get(0) == Some(&0)
-        {
-            return true;
-        }
+fn is_null(
+    value: Option<&[u8]>,
+    ty: &MySqlTypeInfo,
+    format: MySqlValueFormat,
+    zero_dates: MySqlZeroDateBehavior,
+) -> bool {
+    // zero dates and date times are treated the same as NULL, unless configured otherwise
+    if zero_dates == MySqlZeroDateBehavior::ConvertToNone && is_zero_date(value, ty, format) {
+        return true;
     }
 
     value.is_none()
 }
+
+fn is_zero_date(value: Option<&[u8]>, ty: &MySqlTypeInfo, format: MySqlValueFormat) -> bool {
+    if !matches!(
+        ty.r#type,
+        ColumnType::Date | ColumnType::Timestamp | ColumnType::Datetime
+    ) {
+        return false;
+    }
+
+    match (value, format) {
+        // a zero date is sent with a length of 0 and no fields, but check the fields anyway
+        (Some(value), MySqlValueFormat::Binary) => {
+            value.first() == Some(&0) || value.get(1..5) == Some(&[0, 0, 0, 0])
+        }
+
+        (Some(value), MySqlValueFormat::Text) => value.starts_with(b"0000-00-00"),
+
+        (None, _) => false,
+    }
+}
+
+// renders a value of the binary protocol as MySQL would in the text protocol
+// https://dev.mysql.com/doc/internals/en/binary-protocol-value.html
+pub(super) fn format_binary_temporal(value: &[u8], ty: ColumnType) -> String {
+    let fields = match value.split_first() {
+        Some((&len, fields)) => fields.get(..len as usize).unwrap_or(fields),
+        None => &[],
+    };
+
+    let field = |i: usize| fields.get(i).copied().unwrap_or_default();
+    let year = u16::from_le_bytes([field(0), field(1)]);
+
+    let mut s = format!("{:04}-{:02}-{:02}", year, field(2), field(3));
+
+    if ty != ColumnType::Date {
+        s += &format!(" {:02}:{:02}:{:02}", field(4), field(5), field(6));
+
+        if fields.len() > 7 {
+            let micros = u32::from_le_bytes([field(7), field(8), field(9), field(10)]);
+            s += &format!(".{:06}", micros);
+        }
+    }
+
+    s
+}
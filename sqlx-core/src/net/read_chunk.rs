@@ -0,0 +1,56 @@
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use sqlx_rt::AsyncRead;
+
+use super::{PollReadBuf, PollReadOut};
+
+// helpers for streams which transform the data of the stream they wrap, and so read from it
+// in chunks of their own and hand out the result piecemeal
+
+#[cfg(feature = "_rt-tokio")]
+pub(crate) fn poll_read_chunk<S: AsyncRead + Unpin>(
+    stream: &mut S,
+    cx: &mut Context<'_>,
+    chunk: &mut [u8],
+) -> Poll<io::Result<usize>> {
+    let mut buf = sqlx_rt::ReadBuf::new(chunk);
+    futures_util::ready!(Pin::new(stream).poll_read(cx, &mut buf))?;
+
+    Poll::Ready(Ok(buf.filled().len()))
+}
+
+#[cfg(feature = "_rt-async-std")]
+pub(crate) fn poll_read_chunk<S: AsyncRead + Unpin>(
+    stream: &mut S,
+    cx: &mut Context<'_>,
+    chunk: &mut [u8],
+) -> Poll<io::Result<usize>> {
+    Pin::new(stream).poll_read(cx, chunk)
+}
+
+#[cfg(feature = "_rt-tokio")]
+pub(crate) fn read_capacity(buf: &PollReadBuf<'_>) -> usize {
+    buf.remaining()
+}
+
+#[cfg(feature = "_rt-async-std")]
+pub(crate) fn read_capacity(buf: &PollReadBuf<'_>) -> usize {
+    buf.len()
+}
+
+// what is read is only counted with async-std, so this returns the result of `poll_read` whole
+#[cfg(feature = "_rt-tokio")]
+pub(crate) fn read_out(buf: &mut PollReadBuf<'_>, data: &[u8]) -> Poll<io::Result<PollReadOut>> {
+    buf.put_slice(data);
+
+    Poll::Ready(Ok(()))
+}
+
+#[cfg(feature = "_rt-async-std")]
+pub(crate) fn read_out(buf: &mut PollReadBuf<'_>, data: &[u8]) -> Poll<io::Result<PollReadOut>> {
+    buf[..data.len()].copy_from_slice(data);
+
+    Poll::Ready(Ok(data.len()))
+}
@@ -24,6 +24,27 @@ impl Socket {
             .map(Socket::Tcp)
     }
 
+    pub async fn connect_tcp_addr(addr: SocketAddr) -> io::Result<Self> {
+        TcpStream::connect(addr).await.map(Socket::Tcp)
+    }
+
+    /// Resolve all of the addresses of `host`, in the order they would be connected to.
+    pub async fn lookup_host(host: &str, port: u16) -> io::Result<Vec<SocketAddr>> {
+        let addrs: Vec<_> =
+            sqlx_rt::lookup_host((host.trim_matches(|c| c == '[' || c == ']'), port))
+                .await?
+                .collect();
+
+        if addrs.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("no addresses found for host {:?}", host),
+            ));
+        }
+
+        Ok(addrs)
+    }
+
     #[cfg(unix)]
     pub async fn connect_uds(path: impl AsRef<Path>) -> io::Result<Self> {
         sqlx_rt::UnixStream::connect(path.as_ref())
@@ -1,8 +1,10 @@
 use crate::net::CertificateInput;
 use rustls::{
     client::{ServerCertVerified, ServerCertVerifier, WebPkiVerifier},
-    ClientConfig, Error as TlsError, OwnedTrustAnchor, RootCertStore, ServerName,
+    Certificate, ClientConfig, Error as TlsError, OwnedTrustAnchor, PrivateKey, RootCertStore,
+    ServerName,
 };
+use rustls_pemfile::Item;
 use std::io::Cursor;
 use std::sync::Arc;
 use std::time::SystemTime;
@@ -13,13 +15,12 @@ pub async fn configure_tls_connector(
     accept_invalid_certs: bool,
     accept_invalid_hostnames: bool,
     root_cert_path: Option<&CertificateInput>,
+    client_identity: Option<(&CertificateInput, &CertificateInput)>,
 ) -> Result<sqlx_rt::TlsConnector, Error> {
-    let config = ClientConfig::builder().with_safe_defaults();
-
-    let config = if accept_invalid_certs {
-        config
-            .with_custom_certificate_verifier(Arc::new(DummyTlsVerifier))
-            .with_no_client_auth()
+    // the verifier is always set explicitly, so that a client certificate can be
+    // configured the same way for each of them
+    let verifier: Arc<dyn ServerCertVerifier> = if accept_invalid_certs {
+        Arc::new(DummyTlsVerifier)
     } else {
         let mut cert_store = RootCertStore::empty();
         cert_store.add_server_trust_anchors(webpki_roots::TLS_SERVER_ROOTS.0.iter().map(|ta| {
@@ -43,22 +44,59 @@ pub async fn configure_tls_connector(
             }
         }
 
-        if accept_invalid_hostnames {
-            let verifier = WebPkiVerifier::new(cert_store, None);
+        let verifier = WebPkiVerifier::new(cert_store, None);
 
-            config
-                .with_custom_certificate_verifier(Arc::new(NoHostnameTlsVerifier { verifier }))
-                .with_no_client_auth()
+        if accept_invalid_hostnames {
+            Arc::new(NoHostnameTlsVerifier { verifier })
         } else {
-            config
-                .with_root_certificates(cert_store)
-                .with_no_client_auth()
+            Arc::new(verifier)
         }
     };
 
+    let config = ClientConfig::builder()
+        .with_safe_defaults()
+        .with_custom_certificate_verifier(verifier);
+
+    let config = if let Some((cert, key)) = client_identity {
+        let cert_chain = load_certs(cert).await?;
+        let key = load_key(key).await?;
+
+        config
+            .with_single_cert(cert_chain, key)
+            .map_err(|err| Error::Tls(err.into()))?
+    } else {
+        config.with_no_client_auth()
+    };
+
     Ok(Arc::new(config).into())
 }
 
+async fn load_certs(input: &CertificateInput) -> Result<Vec<Certificate>, Error> {
+    let data = input.data().await?;
+    let mut cursor = Cursor::new(data);
+
+    rustls_pemfile::certs(&mut cursor)
+        .map(|certs| certs.into_iter().map(Certificate).collect())
+        .map_err(|_| Error::Tls(format!("Invalid certificate {}", input).into()))
+}
+
+async fn load_key(input: &CertificateInput) -> Result<PrivateKey, Error> {
+    let data = input.data().await?;
+    let mut cursor = Cursor::new(data);
+
+    let items = rustls_pemfile::read_all(&mut cursor)
+        .map_err(|_| Error::Tls(format!("Invalid key {}", input).into()))?;
+
+    // keys can be in the PKCS#1, PKCS#8 or SEC1 format
+    items
+        .into_iter()
+        .find_map(|item| match item {
+            Item::RSAKey(key) | Item::PKCS8Key(key) | Item::ECKey(key) => Some(PrivateKey(key)),
+            _ => None,
+        })
+        .ok_or_else(|| Error::Tls(format!("No private key found in {}", input).into()))
+}
+
 struct DummyTlsVerifier;
 
 impl ServerCertVerifier for DummyTlsVerifier {
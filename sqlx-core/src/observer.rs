@@ -0,0 +1,340 @@
+//! Observing the statements executed by connections, e.g. to record metrics for them.
+//!
+//! Implement [`QueryObserver`] and set it on the connections of a pool with
+//! [`PoolOptions::query_observer`](crate::pool::PoolOptions::query_observer), or on a single
+//! connection with [`ConnectOptions::query_observer`](crate::connection::ConnectOptions::query_observer).
+//!
+//! ```rust,no_run
+//! use sqlx::observer::{Fingerprint, QueryObserver, QueryOutcome};
+//! use std::time::Duration;
+//!
+//! struct SlowQueries;
+//!
+//! impl QueryObserver for SlowQueries {
+//!     fn on_query_end(
+//!         &self,
+//!         fingerprint: &Fingerprint,
+//!         outcome: QueryOutcome,
+//!         rows: u64,
+//!         elapsed: Duration,
+//!     ) {
+//!         if elapsed > Duration::from_millis(100) {
+//!             eprintln!("{:?} {} ({} rows) took {:?}", outcome, fingerprint, rows, elapsed);
+//!         }
+//!     }
+//! }
+//! ```
+
+use crate::common::StatementCache;
+use std::fmt::{self, Debug, Display, Formatter};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Observes the statements executed by connections.
+///
+/// The methods are called synchronously by the task executing the statement (or, for SQLite, by
+/// the worker thread of the connection), so they should return quickly, e.g. by incrementing
+/// counters and recording histograms, and must not block.
+pub trait QueryObserver: Send + Sync + 'static {
+    /// Called when a statement is executed, before it is sent to the database.
+    ///
+    /// Does nothing by default.
+    fn on_query_start(&self, fingerprint: &Fingerprint) {
+        let _ = fingerprint;
+    }
+
+    /// Called once a statement finished executing, with the number of rows it returned (or, if it
+    /// returned none, the number of rows it affected) and the time it took.
+    ///
+    /// A statement whose results were dropped before they were all read, e.g. by `fetch_one`,
+    /// also finishes, successfully unless it returned an error.
+    fn on_query_end(
+        &self,
+        fingerprint: &Fingerprint,
+        outcome: QueryOutcome,
+        rows: u64,
+        elapsed: Duration,
+    );
+}
+
+/// Whether a statement observed by a [`QueryObserver`] succeeded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum QueryOutcome {
+    Success,
+    Error,
+}
+
+/// The SQL of a statement with its literals replaced by `?`, its comments removed and its
+/// whitespace collapsed, so that statements which only differ in those are counted as one.
+///
+/// ```rust
+/// # use sqlx_core::observer::Fingerprint;
+/// let fingerprint = Fingerprint::new("SELECT *\n  FROM users WHERE id = 42 AND name = 'x' -- ?");
+///
+/// assert_eq!(fingerprint.as_str(), "SELECT * FROM users WHERE id = ? AND name = ?");
+/// ```
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct Fingerprint(Arc<str>);
+
+impl Fingerprint {
+    /// Computes the fingerprint of a statement.
+    pub fn new(sql: &str) -> Self {
+        let mut fingerprint = String::with_capacity(sql.len());
+        let mut chars = sql.chars().peekable();
+
+        // whether the previous character continues a word, so that a digit isn't a literal
+        let mut in_word = false;
+        let mut space = false;
+
+        while let Some(c) = chars.next() {
+            match c {
+                '-' if chars.peek() == Some(&'-') => {
+                    chars.by_ref().find(|&c| c == '\n');
+                    space = true;
+                    in_word = false;
+                    continue;
+                }
+
+                '/' if chars.peek() == Some(&'*') => {
+                    chars.next();
+
+                    let mut previous = '\0';
+                    chars.by_ref().find(|&c| {
+                        let end = previous == '*' && c == '/';
+                        previous = c;
+                        end
+                    });
+
+                    space = true;
+                    in_word = false;
+                    continue;
+                }
+
+                c if c.is_whitespace() => {
+                    space = true;
+                    in_word = false;
+                    continue;
+                }
+
+                _ => {}
+            }
+
+            if space && !fingerprint.is_empty() {
+                fingerprint.push(' ');
+            }
+
+            space = false;
+
+            match c {
+                '\'' => {
+                    // a doubled quote escapes a quote
+                    while let Some(c) = chars.next() {
+                        if c == '\'' && chars.next_if_eq(&'\'').is_none() {
+                            break;
+                        }
+                    }
+
+                    fingerprint.push('?');
+                    in_word = false;
+                }
+
+                '"' | '`' => {
+                    fingerprint.push(c);
+
+                    for quoted in chars.by_ref() {
+                        fingerprint.push(quoted);
+
+                        if quoted == c {
+                            break;
+                        }
+                    }
+
+                    in_word = false;
+                }
+
+                c if c.is_ascii_digit() && !in_word => {
+                    while chars
+                        .next_if(|c| c.is_ascii_alphanumeric() || *c == '.')
+                        .is_some()
+                    {}
+
+                    fingerprint.push('?');
+                    in_word = false;
+                }
+
+                c => {
+                    fingerprint.push(c);
+
+                    // `$1` and `?1` are parameters, not literals
+                    in_word = c.is_alphanumeric() || matches!(c, '_' | '$' | '?' | ':' | '@');
+                }
+            }
+        }
+
+        Fingerprint(fingerprint.into())
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl AsRef<str> for Fingerprint {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Display for Fingerprint {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl Debug for Fingerprint {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Debug::fmt(&*self.0, f)
+    }
+}
+
+/// The observer of the connections opened with some options, with the fingerprints of their
+/// statements, shared by those connections.
+#[derive(Clone)]
+pub(crate) struct ObserverSettings {
+    observer: Arc<dyn QueryObserver>,
+    fingerprints: Arc<Mutex<StatementCache<Fingerprint>>>,
+}
+
+impl ObserverSettings {
+    pub(crate) fn new(observer: Arc<dyn QueryObserver>) -> Self {
+        Self {
+            observer,
+            fingerprints: Arc::new(Mutex::new(StatementCache::new(1024))),
+        }
+    }
+
+    /// Starts observing a statement, which ends when the returned observation is dropped.
+    ///
+    /// The fingerprints of persistent statements are cached with them, as they are likely to be
+    /// executed again.
+    pub(crate) fn start(&self, sql: &str, persistent: bool) -> Arc<QueryObservation> {
+        let fingerprint = if persistent {
+            let mut fingerprints = self.fingerprints.lock().unwrap();
+
+            match fingerprints.get_mut(sql) {
+                Some(fingerprint) => fingerprint.clone(),
+                None => {
+                    let fingerprint = Fingerprint::new(sql);
+                    fingerprints.insert(sql, fingerprint.clone());
+                    fingerprint
+                }
+            }
+        } else {
+            Fingerprint::new(sql)
+        };
+
+        self.observer.on_query_start(&fingerprint);
+
+        Arc::new(QueryObservation {
+            observer: Arc::clone(&self.observer),
+            fingerprint,
+            start: Instant::now(),
+            end: Mutex::new(ObservedEnd::default()),
+        })
+    }
+}
+
+impl Debug for ObserverSettings {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ObserverSettings").finish_non_exhaustive()
+    }
+}
+
+/// An observed statement, reported to the observer when the last reference to it is dropped:
+/// both the logger of the statement and the stream of its results record on it.
+pub(crate) struct QueryObservation {
+    observer: Arc<dyn QueryObserver>,
+    fingerprint: Fingerprint,
+    start: Instant,
+    end: Mutex<ObservedEnd>,
+}
+
+#[derive(Default)]
+struct ObservedEnd {
+    rows: u64,
+    elapsed: Option<Duration>,
+    failed: bool,
+}
+
+impl QueryObservation {
+    pub(crate) fn finish(&self, rows: u64, elapsed: Duration) {
+        let mut end = self.end.lock().unwrap();
+
+        end.rows = rows;
+        end.elapsed = Some(elapsed);
+    }
+
+    pub(crate) fn fail(&self) {
+        self.end.lock().unwrap().failed = true;
+    }
+}
+
+impl Drop for QueryObservation {
+    fn drop(&mut self) {
+        let end = self
+            .end
+            .get_mut()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        let outcome = if end.failed {
+            QueryOutcome::Error
+        } else {
+            QueryOutcome::Success
+        };
+
+        self.observer.on_query_end(
+            &self.fingerprint,
+            outcome,
+            end.rows,
+            end.elapsed.unwrap_or_else(|| self.start.elapsed()),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Fingerprint;
+
+    #[test]
+    fn it_fingerprints_statements() {
+        let cases = [
+            ("SELECT 1", "SELECT ?"),
+            ("  SELECT\n\t*   FROM t  ", "SELECT * FROM t"),
+            (
+                "SELECT * FROM t WHERE a = 1.5 AND b = 'it''s' AND c = -3e10",
+                "SELECT * FROM t WHERE a = ? AND b = ? AND c = -?",
+            ),
+            (
+                "SELECT t1.c2 FROM t1 WHERE x = $1 OR y = ?2",
+                "SELECT t1.c2 FROM t1 WHERE x = $1 OR y = ?2",
+            ),
+            (
+                r#"SELECT "col 1", `42` FROM t"#,
+                r#"SELECT "col 1", `42` FROM t"#,
+            ),
+            (
+                "SELECT /* a comment */ 1 -- another\nFROM t",
+                "SELECT ? FROM t",
+            ),
+            (
+                "INSERT INTO t VALUES (1, 'a')",
+                "INSERT INTO t VALUES (?, ?)",
+            ),
+        ];
+
+        for (sql, expected) in cases {
+            assert_eq!(Fingerprint::new(sql).as_str(), expected, "{}", sql);
+        }
+    }
+}
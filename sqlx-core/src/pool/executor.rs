@@ -8,6 +8,7 @@ use crate::describe::Describe;
 use crate::error::Error;
 use crate::executor::{Execute, Executor};
 use crate::pool::Pool;
+use crate::statement::Statement;
 
 impl<'p, DB: Database> Executor<'p> for &'_ Pool<DB>
 where
@@ -55,7 +56,14 @@ where
     ) -> BoxFuture<'e, Result<<Self::Database as HasStatement<'q>>::Statement, Error>> {
         let pool = self.clone();
 
-        Box::pin(async move { pool.acquire().await?.prepare_with(sql, parameters).await })
+        Box::pin(async move {
+            let mut statement = pool.acquire().await?.prepare_with(sql, parameters).await?;
+
+            // the statement may be executed by any connection of the pool
+            statement.detach();
+
+            Ok(statement)
+        })
     }
 
     #[doc(hidden)]
@@ -33,10 +33,20 @@ pub(crate) struct PoolInner<DB: Database> {
 impl<DB: Database> PoolInner<DB> {
     pub(super) fn new_arc(
         options: PoolOptions<DB>,
-        connect_options: <DB::Connection as Connection>::Options,
+        mut connect_options: <DB::Connection as Connection>::Options,
     ) -> Arc<Self> {
         let capacity = options.max_connections as usize;
 
+        if let Some(logger) = &options.statement_logger {
+            connect_options.log_with(Arc::clone(logger));
+        }
+
+        if let Some(observer) = &options.query_observer {
+            connect_options.query_observer(Arc::clone(observer));
+        }
+
+        connect_options.check_pool_size(options.max_connections);
+
         let semaphore_capacity = if let Some(parent) = &options.parent_pool {
             assert!(options.max_connections <= parent.options().max_connections);
             assert_eq!(options.fair, parent.options().fair);
@@ -68,7 +68,7 @@ use crate::any::{Any, AnyKind};
 use crate::connection::Connection;
 use crate::database::Database;
 use crate::error::Error;
-use crate::transaction::Transaction;
+use crate::transaction::{Transaction, TransactionOptions};
 use event_listener::EventListener;
 use futures_core::FusedFuture;
 use futures_util::FutureExt;
@@ -356,6 +356,20 @@ impl<DB: Database> Pool<DB> {
         Ok(Transaction::begin(MaybePoolConnection::PoolConnection(self.acquire().await?)).await?)
     }
 
+    /// Retrieves a connection and immediately begins a new transaction with the given options.
+    ///
+    /// See [`Connection::begin_with`](crate::connection::Connection::begin_with).
+    pub async fn begin_with(
+        &self,
+        options: TransactionOptions,
+    ) -> Result<Transaction<'static, DB>, Error> {
+        Transaction::begin_with(
+            MaybePoolConnection::PoolConnection(self.acquire().await?),
+            options,
+        )
+        .await
+    }
+
     /// Attempts to retrieve a connection and immediately begins a new transaction if successful.
     pub async fn try_begin(&self) -> Result<Option<Transaction<'static, DB>>, Error> {
         match self.try_acquire() {
@@ -518,6 +532,18 @@ impl Pool<Any> {
     }
 }
 
+#[cfg(feature = "postgres")]
+impl Pool<crate::postgres::Postgres> {
+    /// Forget the OIDs of user-defined types resolved by connections of this pool.
+    ///
+    /// The OID of a type is looked up by name the first time it is used as a bind parameter
+    /// and then shared with every connection of the pool. This clears that cache so types will
+    /// be looked up again, which is necessary after a type is dropped and re-created.
+    pub fn clear_type_cache(&self) {
+        self.0.connect_options.type_cache.clear();
+    }
+}
+
 /// Returns a new [Pool] tied to the same shared connection pool.
 impl<DB: Database> Clone for Pool<DB> {
     fn clone(&self) -> Self {
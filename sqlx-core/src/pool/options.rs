@@ -1,6 +1,8 @@
 use crate::connection::Connection;
 use crate::database::Database;
 use crate::error::Error;
+use crate::logger::StatementLogger;
+use crate::observer::QueryObserver;
 use crate::pool::inner::PoolInner;
 use crate::pool::Pool;
 use futures_core::future::BoxFuture;
@@ -80,6 +82,8 @@ pub struct PoolOptions<DB: Database> {
     pub(crate) max_lifetime: Option<Duration>,
     pub(crate) idle_timeout: Option<Duration>,
     pub(crate) fair: bool,
+    pub(crate) statement_logger: Option<Arc<dyn StatementLogger>>,
+    pub(crate) query_observer: Option<Arc<dyn QueryObserver>>,
 
     pub(crate) parent_pool: Option<Pool<DB>>,
 }
@@ -127,6 +131,8 @@ impl<DB: Database> PoolOptions<DB> {
             idle_timeout: Some(Duration::from_secs(10 * 60)),
             max_lifetime: Some(Duration::from_secs(30 * 60)),
             fair: true,
+            statement_logger: None,
+            query_observer: None,
             parent_pool: None,
         }
     }
@@ -403,6 +409,25 @@ impl<DB: Database> PoolOptions<DB> {
         self
     }
 
+    /// Log the statements executed by the connections of the pool with `logger`.
+    ///
+    /// This sets [`ConnectOptions::log_with`](crate::connection::ConnectOptions::log_with) on the
+    /// options the pool connects with.
+    pub fn log_with(mut self, logger: Arc<dyn StatementLogger>) -> Self {
+        self.statement_logger = Some(logger);
+        self
+    }
+
+    /// Report every statement executed by the connections of the pool to `observer`, e.g. to
+    /// record metrics for them.
+    ///
+    /// This sets [`ConnectOptions::query_observer`](crate::connection::ConnectOptions::query_observer)
+    /// on the options the pool connects with, see [`QueryObserver`] for details.
+    pub fn query_observer(mut self, observer: Arc<dyn QueryObserver>) -> Self {
+        self.query_observer = Some(observer);
+        self
+    }
+
     /// Set the parent `Pool` from which the new pool will inherit its semaphore.
     ///
     /// This is currently an internal-only API.
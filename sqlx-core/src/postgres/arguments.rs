@@ -4,7 +4,6 @@ use std::ops::{Deref, DerefMut};
 use crate::arguments::Arguments;
 use crate::encode::{Encode, IsNull};
 use crate::error::Error;
-use crate::ext::ustr::UStr;
 use crate::postgres::{PgConnection, PgTypeInfo, Postgres};
 use crate::types::Type;
 
@@ -40,11 +39,11 @@ pub struct PgArgumentBuffer {
     // Whenever an `Encode` impl encounters a `PgTypeInfo` object that does not have an OID
     // It pushes a "hole" that must be patched later.
     //
-    // The hole is a `usize` offset into the buffer with the type name that should be resolved
+    // The hole is a `usize` offset into the buffer with the declared type that should be resolved
     // This is done for Records and Arrays as the OID is needed well before we are in an async
     // function and can just ask postgres.
     //
-    type_holes: Vec<(usize, UStr)>, // Vec<{ offset, type_name }>
+    type_holes: Vec<(usize, PgTypeInfo)>, // Vec<{ offset, type_info }>
 }
 
 /// Implementation of [`Arguments`] for PostgreSQL.
@@ -94,8 +93,8 @@ impl PgArguments {
             callback(buf, ty);
         }
 
-        for (offset, name) in type_holes {
-            let oid = conn.fetch_type_id_by_name(&*name).await?;
+        for (offset, ty) in type_holes {
+            let oid = conn.fetch_declared_type_id(ty).await?;
             buffer[*offset..(*offset + 4)].copy_from_slice(&oid.0.to_be_bytes());
         }
 
@@ -158,13 +157,19 @@ impl PgArgumentBuffer {
         self.patches.push((offset, index, Box::new(callback)));
     }
 
-    // Extends the inner buffer by enough space to have an OID
-    // Remembers where the OID goes and type name for the OID
-    pub(crate) fn patch_type_by_name(&mut self, type_name: &UStr) {
+    // Writes the OID of the type, if it is known
+    // Otherwise, extends the inner buffer by enough space to have an OID and
+    // remembers where the OID goes and the declared type to resolve it from
+    pub(crate) fn put_type_oid(&mut self, ty: &PgTypeInfo) {
+        if let Some(oid) = ty.0.try_oid() {
+            self.extend_from_slice(&oid.0.to_be_bytes());
+            return;
+        }
+
         let offset = self.len();
 
         self.extend_from_slice(&0_u32.to_be_bytes());
-        self.type_holes.push((offset, type_name.clone()));
+        self.type_holes.push((offset, ty.clone()));
     }
 }
 
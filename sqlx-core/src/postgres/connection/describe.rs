@@ -2,11 +2,11 @@ use crate::error::Error;
 use crate::ext::ustr::UStr;
 use crate::postgres::message::{ParameterDescription, RowDescription};
 use crate::postgres::statement::PgStatementMetadata;
-use crate::postgres::type_info::{PgCustomType, PgType, PgTypeKind};
+use crate::postgres::type_info::{PgArrayOf, PgCustomType, PgType, PgTypeKind};
 use crate::postgres::types::Oid;
 use crate::postgres::{PgArguments, PgColumn, PgConnection, PgTypeInfo};
-use crate::query_as::query_as;
-use crate::query_scalar::{query_scalar, query_scalar_with};
+use crate::query_as::{query_as, query_as_with};
+use crate::query_scalar::query_scalar;
 use crate::types::Json;
 use crate::HashMap;
 use futures_core::future::BoxFuture;
@@ -157,6 +157,8 @@ impl PgConnection {
         }
 
         // next we check a local cache for user-defined type names <-> object id
+        self.sync_type_cache();
+
         if let Some(info) = self.cache_type_info.get(&oid) {
             return Ok(info.clone());
         }
@@ -170,6 +172,8 @@ impl PgConnection {
             self.cache_type_info.insert(oid, info.clone());
             self.cache_type_oid
                 .insert(info.0.name().to_string().into(), oid);
+            self.shared_type_cache
+                .insert(&self.shared_type_cache_target, info.0.name(), oid);
 
             Ok(info)
         } else {
@@ -338,11 +342,31 @@ WHERE rngtypid = $1
         })
     }
 
+    /// Returns the OID of `ty`, looking it up if the type is only declared by name.
+    pub(crate) async fn fetch_declared_type_id(&mut self, ty: &PgTypeInfo) -> Result<Oid, Error> {
+        match &ty.0 {
+            PgType::DeclareWithName(name) => self.fetch_type_id_by_name(name).await,
+            PgType::DeclareArrayOf(array) => self.fetch_array_type_id(array).await,
+            ty => Ok(ty.oid()),
+        }
+    }
+
     pub(crate) async fn fetch_type_id_by_name(&mut self, name: &str) -> Result<Oid, Error> {
+        self.sync_type_cache();
+
         if let Some(oid) = self.cache_type_oid.get(name) {
             return Ok(*oid);
         }
 
+        // another connection from the same options may have already resolved this type
+        if let Some(oid) = self
+            .shared_type_cache
+            .get(&self.shared_type_cache_target, name)
+        {
+            self.cache_type_oid.insert(name.to_string().into(), oid);
+            return Ok(oid);
+        }
+
         // language=SQL
         let (oid,): (Oid,) = query_as(
             "
@@ -357,28 +381,73 @@ SELECT oid FROM pg_catalog.pg_type WHERE typname ILIKE $1
         })?;
 
         self.cache_type_oid.insert(name.to_string().into(), oid);
+        self.shared_type_cache
+            .insert(&self.shared_type_cache_target, name, oid);
+
         Ok(oid)
     }
 
-    pub(crate) async fn get_nullable_for_columns(
+    async fn fetch_array_type_id(&mut self, array: &PgArrayOf) -> Result<Oid, Error> {
+        self.sync_type_cache();
+
+        if let Some(oid) = self.cache_type_oid.get(&array.name) {
+            return Ok(*oid);
+        }
+
+        if let Some(oid) = self
+            .shared_type_cache
+            .get(&self.shared_type_cache_target, &array.name)
+        {
+            self.cache_type_oid.insert(array.name.clone(), oid);
+            return Ok(oid);
+        }
+
+        // the array type is usually named `_<element>`, but not always (e.g. if that name is
+        // taken or too long), so ask for the array type of the element instead
+        // language=SQL
+        let oid: Oid = query_scalar(
+            "
+SELECT typarray FROM pg_catalog.pg_type WHERE typname ILIKE $1
+                ",
+        )
+        .bind(&*array.elem_name)
+        .fetch_optional(&mut *self)
+        .await?
+        // an OID of 0 means the type doesn't have an array type
+        .filter(|oid: &Oid| oid.0 != 0)
+        .ok_or_else(|| Error::TypeNotFound {
+            type_name: array.name.to_string(),
+        })?;
+
+        self.cache_type_oid.insert(array.name.clone(), oid);
+        self.shared_type_cache
+            .insert(&self.shared_type_cache_target, &array.name, oid);
+
+        Ok(oid)
+    }
+
+    /// Gets whether the columns may be `NULL`, and the domains they're declared with, as the
+    /// columns of a domain are described with its base type.
+    pub(crate) async fn get_nullable_and_domains_for_columns(
         &mut self,
-        stmt_id: Oid,
+        stmt_id: Option<Oid>,
         meta: &PgStatementMetadata,
-    ) -> Result<Vec<Option<bool>>, Error> {
+    ) -> Result<(Vec<Option<bool>>, Vec<Option<String>>), Error> {
         if meta.columns.is_empty() {
-            return Ok(vec![]);
+            return Ok((vec![], vec![]));
         }
 
-        let mut nullable_query = String::from("SELECT NOT pg_attribute.attnotnull FROM (VALUES ");
+        let mut columns_query =
+            String::from("SELECT NOT pg_attribute.attnotnull, domain.typname::text FROM (VALUES ");
         let mut args = PgArguments::default();
 
         for (i, (column, bind)) in meta.columns.iter().zip((1..).step_by(3)).enumerate() {
             if !args.buffer.is_empty() {
-                nullable_query += ", ";
+                columns_query += ", ";
             }
 
             let _ = write!(
-                nullable_query,
+                columns_query,
                 "(${}::int4, ${}::int4, ${}::int2)",
                 bind,
                 bind + 1,
@@ -390,21 +459,30 @@ SELECT oid FROM pg_catalog.pg_type WHERE typname ILIKE $1
             args.add(column.relation_attribute_no);
         }
 
-        nullable_query.push_str(
+        columns_query.push_str(
             ") as col(idx, table_id, col_idx) \
             LEFT JOIN pg_catalog.pg_attribute \
                 ON table_id IS NOT NULL \
                AND attrelid = table_id \
                AND attnum = col_idx \
+            LEFT JOIN pg_catalog.pg_type domain \
+                ON domain.oid = pg_attribute.atttypid \
+               AND domain.typtype = 'd' \
             ORDER BY col.idx",
         );
 
-        let mut nullables = query_scalar_with::<_, Option<bool>, _>(&nullable_query, args)
-            .fetch_all(&mut *self)
-            .await?;
+        let (mut nullables, mut domains): (Vec<_>, Vec<_>) =
+            query_as_with::<_, (Option<bool>, Option<String>), _>(&columns_query, args)
+                .fetch_all(&mut *self)
+                .await?
+                .into_iter()
+                .unzip();
 
         // if it's cockroachdb skip this step #1248
-        if !self.stream.parameter_statuses.contains_key("crdb_version") {
+        let is_cockroach = self.stream.parameter_statuses.contains_key("crdb_version");
+
+        // EXPLAIN can only execute a named statement
+        if let (Some(stmt_id), false) = (stmt_id, is_cockroach) {
             // patch up our null inference with data from EXPLAIN
             let nullable_patch = self
                 .nullables_from_explain(stmt_id, meta.parameters.len())
@@ -415,7 +493,12 @@ SELECT oid FROM pg_catalog.pg_type WHERE typname ILIKE $1
             }
         }
 
-        Ok(nullables)
+        // which keeps the data of the queries without domains as it was
+        if domains.iter().all(Option::is_none) {
+            domains.clear();
+        }
+
+        Ok((nullables, domains))
     }
 
     /// Infer nullability for columns of this statement using EXPLAIN VERBOSE.
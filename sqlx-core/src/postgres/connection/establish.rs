@@ -1,24 +1,104 @@
+use std::net::SocketAddr;
+use std::sync::Arc;
+
 use crate::HashMap;
 
 use crate::common::StatementCache;
+use crate::connection::{Connection, LogSettings};
 use crate::error::Error;
+use crate::executor::Executor;
 use crate::io::Decode;
+use crate::net::Socket;
+#[cfg(feature = "postgres-gssapi")]
+use crate::postgres::connection::gss;
+use crate::postgres::connection::type_cache::PgTypeCacheTarget;
 use crate::postgres::connection::{sasl, stream::PgStream, tls};
 use crate::postgres::message::{
     Authentication, BackendKeyData, MessageFormat, Password, ReadyForQuery, Startup,
 };
 use crate::postgres::types::Oid;
-use crate::postgres::{PgConnectOptions, PgConnection};
+use crate::postgres::{PgConnectOptions, PgConnection, PgTargetSessionAttrs};
+use crate::row::Row;
 
 // https://www.postgresql.org/docs/current/protocol-flow.html#id-1.10.5.7.3
 // https://www.postgresql.org/docs/current/protocol-flow.html#id-1.10.5.7.11
 
 impl PgConnection {
     pub(crate) async fn establish(options: &PgConnectOptions) -> Result<Self, Error> {
-        let mut stream = PgStream::connect(options).await?;
+        let target_session_attrs = options.target_session_attrs;
+
+        if target_session_attrs == PgTargetSessionAttrs::Any {
+            return Self::establish_at(options, None).await;
+        }
+
+        // a server whose session doesn't match is disconnected from, to try the next address
+        // of the host (e.g. with DNS round-robin), the same as libpq does with multiple hosts
+        let addrs = match options.fetch_socket() {
+            Some(_) => vec![None],
+            None => Socket::lookup_host(&options.host, options.port)
+                .await?
+                .into_iter()
+                .map(Some)
+                .collect(),
+        };
+
+        // `prefer-standby` falls back to any server when none of them is a standby
+        let passes = match target_session_attrs {
+            PgTargetSessionAttrs::PreferStandby => {
+                vec![PgTargetSessionAttrs::Standby, PgTargetSessionAttrs::Any]
+            }
+
+            attrs => vec![attrs],
+        };
+
+        let mut last_error = None;
+
+        for attrs in passes {
+            for &addr in &addrs {
+                let mut conn = match Self::establish_at(options, addr).await {
+                    Ok(conn) => conn,
+
+                    Err(error) => {
+                        last_error = Some(error);
+                        continue;
+                    }
+                };
+
+                match conn.check_target_session_attrs(attrs).await {
+                    Ok(None) => return Ok(conn),
+
+                    Ok(Some(reason)) => {
+                        last_error = Some(Error::Configuration(
+                            format!(
+                                "{}, but `target_session_attrs` is {:?}",
+                                reason, target_session_attrs
+                            )
+                            .into(),
+                        ));
+                    }
+
+                    Err(error) => last_error = Some(error),
+                }
 
-        // Upgrade to TLS if we were asked to and the server supports it
-        tls::maybe_upgrade(&mut stream, options).await?;
+                let _ = conn.close().await;
+            }
+        }
+
+        // `addrs` is never empty, so there is always an error for the last address tried
+        Err(last_error.expect("BUG: no address was tried"))
+    }
+
+    async fn establish_at(
+        options: &PgConnectOptions,
+        addr: Option<SocketAddr>,
+    ) -> Result<Self, Error> {
+        let mut stream = PgStream::connect(options, addr).await?;
+
+        // Encrypt the connection with GSSAPI if we were asked to and the server supports it,
+        // otherwise upgrade to TLS if we were asked to and the server supports it
+        if !maybe_encrypt_gss(&mut stream, options).await? {
+            tls::maybe_upgrade(&mut stream, options).await?;
+        }
 
         // To begin a session, a frontend opens a connection to the server
         // and sends a startup message.
@@ -46,6 +126,18 @@ impl PgConnection {
             params.push(("options", options));
         }
 
+        // these come after our defaults so they can override them
+        for (name, value) in &options.startup_parameters {
+            params.push((name, value));
+        }
+
+        #[cfg(feature = "replication")]
+        if options.replication {
+            // enables the replication protocol, connected to a specific database
+            // as required for logical replication
+            params.push(("replication", "database"));
+        }
+
         stream
             .send(Startup {
                 username: Some(&options.username),
@@ -63,6 +155,9 @@ impl PgConnection {
         let mut secret_key = 0;
         let transaction_status;
 
+        #[cfg(feature = "postgres-gssapi")]
+        let mut gss_ctx = None;
+
         loop {
             let message = stream.recv().await?;
             match message.format {
@@ -102,6 +197,26 @@ impl PgConnection {
                         sasl::authenticate(&mut stream, options, body).await?;
                     }
 
+                    #[cfg(feature = "postgres-gssapi")]
+                    Authentication::Gss | Authentication::Sspi => {
+                        // SSPI is compatible with GSSAPI when the server uses Kerberos
+                        gss::authenticate(&mut stream, options, &mut gss_ctx, None).await?;
+                    }
+
+                    #[cfg(feature = "postgres-gssapi")]
+                    Authentication::GssContinue(token) => {
+                        gss::authenticate(&mut stream, options, &mut gss_ctx, Some(&token)).await?;
+                    }
+
+                    #[cfg(not(feature = "postgres-gssapi"))]
+                    Authentication::Gss | Authentication::Sspi => {
+                        return Err(Error::Configuration(
+                            "server requested GSSAPI authentication, which requires \
+                             the `postgres-gssapi` feature"
+                                .into(),
+                        ));
+                    }
+
                     method => {
                         return Err(err_protocol!(
                             "unsupported authentication method: {:?}",
@@ -137,7 +252,7 @@ impl PgConnection {
             }
         }
 
-        Ok(PgConnection {
+        let conn = PgConnection {
             stream,
             process_id,
             secret_key,
@@ -145,10 +260,114 @@ impl PgConnection {
             transaction_depth: 0,
             pending_ready_for_query_count: 0,
             next_statement_id: Oid(1),
-            cache_statement: StatementCache::new(options.statement_cache_capacity),
+            cache_statement: StatementCache::new(if options.prefer_simple_protocol {
+                0
+            } else {
+                options.statement_cache_capacity
+            }),
+            cache_statistics: Default::default(),
+            prefer_simple_protocol: options.prefer_simple_protocol,
             cache_type_oid: HashMap::new(),
             cache_type_info: HashMap::new(),
-            log_settings: options.log_settings.clone(),
+            shared_type_cache: Arc::clone(&options.type_cache),
+            shared_type_cache_target: PgTypeCacheTarget::new(options),
+            shared_type_cache_generation: options.type_cache.generation(),
+            log_settings: log_settings(options),
+            auto_explain: options.auto_explain.clone(),
+            explaining: false,
+        };
+
+        Ok(conn)
+    }
+
+    // returns why the session doesn't match `attrs`, if it doesn't
+    // https://www.postgresql.org/docs/current/libpq-connect.html#LIBPQ-CONNECT-TARGET-SESSION-ATTRS
+    async fn check_target_session_attrs(
+        &mut self,
+        attrs: PgTargetSessionAttrs,
+    ) -> Result<Option<&'static str>, Error> {
+        Ok(match attrs {
+            // `prefer-standby` is checked as `standby`, then `any`, by `establish()`
+            PgTargetSessionAttrs::Any | PgTargetSessionAttrs::PreferStandby => None,
+
+            PgTargetSessionAttrs::ReadWrite | PgTargetSessionAttrs::ReadOnly => {
+                // PostgreSQL 14+ reports both of these, saving a round trip
+                let read_only = match (
+                    self.parameter("default_transaction_read_only"),
+                    self.parameter("in_hot_standby"),
+                ) {
+                    (Some(default_read_only), Some(in_hot_standby)) => {
+                        default_read_only == "on" || in_hot_standby == "on"
+                    }
+
+                    _ => {
+                        let row = self.fetch_one("SHOW transaction_read_only").await?;
+                        row.try_get::<String, _>(0)? == "on"
+                    }
+                };
+
+                match (attrs, read_only) {
+                    (PgTargetSessionAttrs::ReadWrite, true) => Some("session is read-only"),
+                    (PgTargetSessionAttrs::ReadOnly, false) => Some("session is not read-only"),
+                    _ => None,
+                }
+            }
+
+            PgTargetSessionAttrs::Primary | PgTargetSessionAttrs::Standby => {
+                let in_hot_standby = match self.parameter("in_hot_standby") {
+                    Some(in_hot_standby) => in_hot_standby == "on",
+
+                    None => {
+                        let row = self
+                            .fetch_one("SELECT pg_catalog.pg_is_in_recovery()")
+                            .await?;
+
+                        row.try_get::<bool, _>(0)?
+                    }
+                };
+
+                match (attrs, in_hot_standby) {
+                    (PgTargetSessionAttrs::Primary, true) => Some("server is in hot standby mode"),
+                    (PgTargetSessionAttrs::Standby, false) => {
+                        Some("server is not in hot standby mode")
+                    }
+                    _ => None,
+                }
+            }
         })
     }
 }
+
+#[cfg(feature = "postgres-gssapi")]
+async fn maybe_encrypt_gss(
+    stream: &mut PgStream,
+    options: &PgConnectOptions,
+) -> Result<bool, Error> {
+    gss::maybe_encrypt(stream, options).await
+}
+
+#[cfg(not(feature = "postgres-gssapi"))]
+async fn maybe_encrypt_gss(_: &mut PgStream, options: &PgConnectOptions) -> Result<bool, Error> {
+    use crate::postgres::PgGssEncMode;
+
+    if let PgGssEncMode::Require = options.gss_enc_mode {
+        return Err(Error::Configuration(
+            "GSSAPI encryption requires the `postgres-gssapi` feature".into(),
+        ));
+    }
+
+    Ok(false)
+}
+
+fn log_settings(options: &PgConnectOptions) -> LogSettings {
+    #[allow(unused_mut)]
+    let mut settings = options.log_settings.for_connection();
+
+    #[cfg(feature = "tracing")]
+    match &options.socket {
+        Some(socket) => settings.trace_server("postgresql", socket.to_str(), None),
+        None => settings.trace_server("postgresql", Some(&options.host), Some(options.port)),
+    }
+
+    settings
+}
@@ -1,13 +1,13 @@
+use crate::common::PreparedStatement;
 use crate::describe::Describe;
 use crate::error::Error;
 use crate::executor::{Execute, Executor};
-use crate::logger::QueryLogger;
+use crate::logger::{QueryLogger, QuerySpan};
 use crate::postgres::message::{
     self, Bind, Close, CommandComplete, DataRow, MessageFormat, ParameterDescription, Parse, Query,
     RowDescription,
 };
 use crate::postgres::statement::PgStatementMetadata;
-use crate::postgres::type_info::PgType;
 use crate::postgres::types::Oid;
 use crate::postgres::{
     statement::PgStatement, PgArguments, PgConnection, PgQueryResult, PgRow, PgTypeInfo,
@@ -17,18 +17,17 @@ use either::Either;
 use futures_core::future::BoxFuture;
 use futures_core::stream::BoxStream;
 use futures_core::Stream;
-use futures_util::{pin_mut, TryStreamExt};
+use futures_util::{pin_mut, TryFutureExt, TryStreamExt};
 use std::{borrow::Cow, sync::Arc};
 
 async fn prepare(
     conn: &mut PgConnection,
+    // `None` prepares the unnamed statement
+    id: Option<Oid>,
     sql: &str,
     parameters: &[PgTypeInfo],
     metadata: Option<Arc<PgStatementMetadata>>,
-) -> Result<(Oid, Arc<PgStatementMetadata>), Error> {
-    let id = conn.next_statement_id;
-    conn.next_statement_id.incr_one();
-
+) -> Result<Arc<PgStatementMetadata>, Error> {
     // build a list of type OIDs to send to the database in the PARSE command
     // we have not yet started the query sequence, so we are *safe* to cleanly make
     // additional queries here to get any missing OIDs
@@ -36,11 +35,7 @@ async fn prepare(
     let mut param_types = Vec::with_capacity(parameters.len());
 
     for ty in parameters {
-        param_types.push(if let PgType::DeclareWithName(name) = &ty.0 {
-            conn.fetch_type_id_by_name(name).await?
-        } else {
-            ty.0.oid()
-        });
+        param_types.push(conn.fetch_declared_type_id(ty).await?);
     }
 
     // flush and wait until we are re-ready
@@ -55,7 +50,10 @@ async fn prepare(
 
     if metadata.is_none() {
         // get the statement columns and parameters
-        conn.stream.write(message::Describe::Statement(id));
+        conn.stream.write(match id {
+            Some(id) => message::Describe::Statement(id),
+            None => message::Describe::UnnamedStatement,
+        });
     }
 
     // we ask for the server to immediately send us the result of the PARSE command
@@ -68,6 +66,8 @@ async fn prepare(
         .recv_expect(MessageFormat::ParseComplete)
         .await?;
 
+    conn.cache_statistics.prepared += 1;
+
     let metadata = if let Some(metadata) = metadata {
         // each SYNC produces one READY FOR QUERY
         conn.recv_ready_for_query().await?;
@@ -97,7 +97,7 @@ async fn prepare(
         })
     };
 
-    Ok((id, metadata))
+    Ok(metadata)
 }
 
 async fn recv_desc_params(conn: &mut PgConnection) -> Result<ParameterDescription, Error> {
@@ -154,6 +154,28 @@ impl PgConnection {
         Ok(())
     }
 
+    /// Prepare `sql` as a named statement which isn't cached, and so must be closed with
+    /// [`close_uncached`](Self::close_uncached), returning its id and its number of parameters.
+    pub(crate) async fn prepare_uncached(&mut self, sql: &str) -> Result<(Oid, usize), Error> {
+        let id = self.next_statement_id;
+        self.next_statement_id.incr_one();
+
+        let metadata = prepare(self, Some(id), sql, &[], None).await?;
+
+        Ok((id, metadata.parameters.len()))
+    }
+
+    pub(crate) async fn close_uncached(&mut self, id: Oid) -> Result<(), Error> {
+        self.wait_until_ready().await?;
+
+        self.stream.write(Close::Statement(id));
+        self.write_sync();
+        self.stream.flush().await?;
+
+        self.wait_for_close_complete(1).await?;
+        self.recv_ready_for_query().await
+    }
+
     pub(crate) fn write_sync(&mut self) {
         self.stream.write(message::Sync);
 
@@ -170,26 +192,61 @@ impl PgConnection {
         // optional metadata that was provided by the user, this means they are reusing
         // a statement object
         metadata: Option<Arc<PgStatementMetadata>>,
-    ) -> Result<(Oid, Arc<PgStatementMetadata>), Error> {
-        if let Some(statement) = self.cache_statement.get_mut(sql) {
-            return Ok((*statement).clone());
+    ) -> Result<(Option<PreparedStatement<Oid>>, Arc<PgStatementMetadata>), Error> {
+        if let Some((prepared, metadata)) = self.cache_statement.get_mut(sql) {
+            self.cache_statistics.hits += 1;
+            prepared.hit();
+
+            return Ok((Some(prepared.clone()), Arc::clone(metadata)));
+        }
+
+        if !store_to_cache {
+            // the unnamed statement is replaced by the next one prepared, so there is nothing
+            // to close later
+            let metadata = prepare(self, None, sql, parameters, metadata).await?;
+
+            return Ok((None, metadata));
         }
 
-        let statement = prepare(self, sql, parameters, metadata).await?;
+        let id = self.next_statement_id;
+        self.next_statement_id.incr_one();
 
-        if store_to_cache && self.cache_statement.is_enabled() {
-            if let Some((id, _)) = self.cache_statement.insert(sql, statement.clone()) {
-                self.stream.write(Close::Statement(id));
+        let metadata = prepare(self, Some(id), sql, parameters, metadata).await?;
+        let prepared = PreparedStatement::new(self.log_settings.connection_id, id);
+
+        if self.cache_statement.is_enabled() {
+            let cached = (prepared.clone(), metadata.clone());
+
+            if let Some((evicted, _)) = self.cache_statement.insert(sql, cached) {
+                // close the evicted statement so it doesn't linger on the server
+                evicted.close();
+                self.stream.write(Close::Statement(evicted.id()));
                 self.write_sync();
 
                 self.stream.flush().await?;
 
                 self.wait_for_close_complete(1).await?;
                 self.recv_ready_for_query().await?;
+
+                self.cache_statistics.evictions += 1;
             }
         }
 
-        Ok(statement)
+        Ok((Some(prepared), metadata))
+    }
+
+    fn query_logger<'q>(
+        &self,
+        sql: &'q str,
+        arguments: &Option<PgArguments>,
+        persistent: bool,
+    ) -> QueryLogger<'q> {
+        let parameters = arguments
+            .as_ref()
+            .map_or(0, |arguments| arguments.types.len());
+        let span = QuerySpan::new(sql, &self.log_settings);
+
+        QueryLogger::new(sql, parameters, persistent, self.log_settings.clone(), span)
     }
 
     async fn run<'e, 'c: 'e, 'q: 'e>(
@@ -198,23 +255,52 @@ impl PgConnection {
         arguments: Option<PgArguments>,
         limit: u8,
         persistent: bool,
-        metadata_opt: Option<Arc<PgStatementMetadata>>,
+        // the statement prepared by the user, if they are reusing a statement object
+        statement: Option<(Option<PreparedStatement<Oid>>, Arc<PgStatementMetadata>)>,
+        mut logger: QueryLogger<'q>,
     ) -> Result<impl Stream<Item = Result<Either<PgQueryResult, PgRow>, Error>> + 'e, Error> {
-        let mut logger = QueryLogger::new(query, self.log_settings.clone());
+        // a statement prepared by this connection is executed directly, unless it was closed
+        let (prepared_id, metadata_opt) = match statement {
+            Some((Some(prepared), metadata)) => (
+                prepared.reuse(self.log_settings.connection_id)?,
+                Some(metadata),
+            ),
+            Some((None, metadata)) => (None, Some(metadata)),
+            None => (None, None),
+        };
 
         // before we continue, wait until we are "ready" to accept more queries
         self.wait_until_ready().await?;
 
         let mut metadata: Arc<PgStatementMetadata>;
 
+        // without bind parameters, the simple query protocol does the job in a single message
+        let arguments = arguments
+            .filter(|arguments| !(self.prefer_simple_protocol && arguments.types.is_empty()));
+
         let format = if let Some(mut arguments) = arguments {
-            // prepare the statement if this our first time executing it
-            // always return the statement ID here
-            let (statement, metadata_) = self
-                .get_or_prepare(query, &arguments.types, persistent, metadata_opt)
-                .await?;
+            let statement = match (prepared_id, metadata_opt) {
+                (Some(id), Some(metadata_)) => {
+                    metadata = metadata_;
+                    Some(id)
+                }
 
-            metadata = metadata_;
+                (_, metadata_opt) => {
+                    // prepare the statement if this our first time executing it
+                    let (prepared, metadata_) = self
+                        .get_or_prepare(
+                            query,
+                            &arguments.types,
+                            persistent && !self.prefer_simple_protocol,
+                            metadata_opt,
+                        )
+                        .await
+                        .inspect_err(|error| self.invalidate_type_cache_on(error))?;
+
+                    metadata = metadata_;
+                    prepared.map(|prepared| prepared.id())
+                }
+            };
 
             // patch holes created during encoding
             arguments.apply_patches(self, &metadata.parameters).await?;
@@ -223,6 +309,19 @@ impl PgConnection {
             // consume messages til `ReadyForQuery` before bind and execute
             self.wait_until_ready().await?;
 
+            if self.prefer_simple_protocol {
+                // a connection pooler may have moved us to another server connection since the
+                // statement was described, so parse it again in the same round-trip as the bind
+                let param_types: Vec<Oid> =
+                    metadata.parameters.iter().map(|ty| ty.0.oid()).collect();
+
+                self.stream.write(Parse {
+                    param_types: &param_types,
+                    query,
+                    statement: None,
+                });
+            }
+
             // bind to attach the arguments to the statement and create a portal
             self.stream.write(Bind {
                 portal: None,
@@ -265,13 +364,20 @@ impl PgConnection {
 
         Ok(try_stream! {
             loop {
-                let message = self.stream.recv().await?;
+                let message = match self.stream.recv().await {
+                    Ok(message) => message,
+                    Err(error) => {
+                        self.invalidate_type_cache_on(&error);
+                        return Err(error);
+                    }
+                };
 
                 match message.format {
                     MessageFormat::BindComplete
                     | MessageFormat::ParseComplete
                     | MessageFormat::ParameterDescription
-                    | MessageFormat::NoData => {
+                    | MessageFormat::NoData
+                    | MessageFormat::PortalSuspended => {
                         // harmless messages to ignore
                     }
 
@@ -312,6 +418,7 @@ impl PgConnection {
                             data,
                             format,
                             metadata: Arc::clone(&metadata),
+                            context: logger.context().clone(),
                         };
 
                         r#yield!(Either::Right(row));
@@ -332,6 +439,12 @@ impl PgConnection {
                 }
             }
 
+            if self.auto_explain.is_some() && logger.is_slow() {
+                // log the statement first, so that its time doesn't include explaining it
+                drop(logger);
+                self.explain_slow_statement(query).await;
+            }
+
             Ok(())
         })
     }
@@ -348,13 +461,22 @@ impl<'c> Executor<'c> for &'c mut PgConnection {
         'c: 'e,
         E: Execute<'q, Self::Database>,
     {
+        if let Some(error) = query.take_error() {
+            return Box::pin(futures_util::stream::once(async move { Err(error) }));
+        }
+
         let sql = query.sql();
-        let metadata = query.statement().map(|s| Arc::clone(&s.metadata));
+        let statement = query
+            .statement()
+            .map(|s| (s.prepared.clone(), Arc::clone(&s.metadata)));
         let arguments = query.take_arguments();
         let persistent = query.persistent();
 
-        Box::pin(try_stream! {
-            let s = self.run(sql, arguments, 0, persistent, metadata).await?;
+        let logger = self.query_logger(sql, &arguments, persistent);
+        let errors = logger.errors();
+
+        let stream = try_stream! {
+            let s = self.run(sql, arguments, 0, persistent, statement, logger).await?;
             pin_mut!(s);
 
             while let Some(v) = s.try_next().await? {
@@ -362,7 +484,9 @@ impl<'c> Executor<'c> for &'c mut PgConnection {
             }
 
             Ok(())
-        })
+        };
+
+        Box::pin(stream.map_err(move |error| errors.record(error)))
     }
 
     fn fetch_optional<'e, 'q: 'e, E: 'q>(
@@ -373,23 +497,44 @@ impl<'c> Executor<'c> for &'c mut PgConnection {
         'c: 'e,
         E: Execute<'q, Self::Database>,
     {
+        if let Some(error) = query.take_error() {
+            return Box::pin(async move { Err(error) });
+        }
+
         let sql = query.sql();
-        let metadata = query.statement().map(|s| Arc::clone(&s.metadata));
+        let statement = query
+            .statement()
+            .map(|s| (s.prepared.clone(), Arc::clone(&s.metadata)));
         let arguments = query.take_arguments();
         let persistent = query.persistent();
 
-        Box::pin(async move {
-            let s = self.run(sql, arguments, 1, persistent, metadata).await?;
+        let logger = self.query_logger(sql, &arguments, persistent);
+        let errors = logger.errors();
+
+        let future = async move {
+            let explain = self.auto_explain.is_some();
+            let s = self
+                .run(sql, arguments, 1, persistent, statement, logger)
+                .await?;
             pin_mut!(s);
 
+            let mut row = None;
+
             while let Some(s) = s.try_next().await? {
                 if let Either::Right(r) = s {
-                    return Ok(Some(r));
+                    row = Some(r);
+
+                    // the rest of the stream explains the statement if it was slow
+                    if !explain {
+                        break;
+                    }
                 }
             }
 
-            Ok(None)
-        })
+            Ok(row)
+        };
+
+        Box::pin(future.map_err(move |error: Error| errors.record(error)))
     }
 
     fn prepare_with<'e, 'q: 'e>(
@@ -403,11 +548,14 @@ impl<'c> Executor<'c> for &'c mut PgConnection {
         Box::pin(async move {
             self.wait_until_ready().await?;
 
-            let (_, metadata) = self.get_or_prepare(sql, parameters, true, None).await?;
+            let (prepared, metadata) = self
+                .get_or_prepare(sql, parameters, !self.prefer_simple_protocol, None)
+                .await?;
 
             Ok(PgStatement {
                 sql: Cow::Borrowed(sql),
                 metadata,
+                prepared,
             })
         })
     }
@@ -422,13 +570,20 @@ impl<'c> Executor<'c> for &'c mut PgConnection {
         Box::pin(async move {
             self.wait_until_ready().await?;
 
-            let (stmt_id, metadata) = self.get_or_prepare(sql, &[], true, None).await?;
+            let (prepared, metadata) = self
+                .get_or_prepare(sql, &[], !self.prefer_simple_protocol, None)
+                .await?;
+
+            let stmt_id = prepared.map(|prepared| prepared.id());
 
-            let nullable = self.get_nullable_for_columns(stmt_id, &metadata).await?;
+            let (nullable, domains) = self
+                .get_nullable_and_domains_for_columns(stmt_id, &metadata)
+                .await?;
 
             Ok(Describe {
                 columns: metadata.columns.clone(),
                 nullable,
+                domains,
                 parameters: Some(Either::Left(metadata.parameters.clone())),
             })
         })
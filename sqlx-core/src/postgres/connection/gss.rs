@@ -0,0 +1,207 @@
+use bytes::{Buf, Bytes};
+use libgssapi::context::{ClientCtx, CtxFlags, SecurityContext};
+use libgssapi::credential::{Cred, CredUsage};
+use libgssapi::name::Name;
+use libgssapi::oid::{OidSet, GSS_MECH_KRB5, GSS_NT_HOSTBASED_SERVICE};
+
+use crate::error::Error;
+use crate::postgres::connection::stream::PgStream;
+use crate::postgres::message::{GssEncRequest, GssResponse, Notice};
+use crate::postgres::{PgConnectOptions, PgDatabaseError, PgGssEncMode};
+
+// the largest token libpq and the server accept during GSSAPI encryption negotiation
+const MAX_NEGOTIATION_TOKEN: usize = 16384 - 4;
+
+/// Start a client security context for authenticating with the server named by `options`.
+pub(super) fn client_context(
+    options: &PgConnectOptions,
+    flags: CtxFlags,
+) -> Result<ClientCtx, Error> {
+    let mut mechs = OidSet::new().map_err(gss_error)?;
+    mechs.add(&GSS_MECH_KRB5).map_err(gss_error)?;
+
+    // fails if there are no credentials in the cache, e.g. when `kinit` has not been run
+    let cred = Cred::acquire(None, None, CredUsage::Initiate, Some(&mechs)).map_err(gss_error)?;
+
+    // the service principal is `<krbsrvname>/<host>@<realm>`
+    let target = format!("{}@{}", options.krbsrvname, options.host);
+    let target = Name::new(target.as_bytes(), Some(&GSS_NT_HOSTBASED_SERVICE))
+        .and_then(|name| name.canonicalize(Some(&GSS_MECH_KRB5)))
+        .map_err(gss_error)?;
+
+    Ok(ClientCtx::new(cred, target, flags, Some(&GSS_MECH_KRB5)))
+}
+
+/// Perform a step of GSSAPI (or SSPI) authentication in response to an `AuthenticationGSS`
+/// (`server_token` is `None`) or `AuthenticationGSSContinue` message.
+pub(super) async fn authenticate(
+    stream: &mut PgStream,
+    options: &PgConnectOptions,
+    ctx: &mut Option<ClientCtx>,
+    server_token: Option<&[u8]>,
+) -> Result<(), Error> {
+    let ctx = match ctx {
+        Some(ctx) => ctx,
+        None => ctx.insert(client_context(options, CtxFlags::GSS_C_MUTUAL_FLAG)?),
+    };
+
+    if let Some(token) = ctx.step(server_token, None).map_err(gss_error)? {
+        stream.send(GssResponse(&token)).await?;
+    }
+
+    Ok(())
+}
+
+/// Negotiate GSSAPI transport encryption if asked to, returning `true` if the connection is now
+/// encrypted.
+///
+/// If encryption is only preferred and negotiating it fails, the server has closed the connection,
+/// so `stream` is replaced with a new connection, without GSSAPI encryption, as libpq does.
+pub(super) async fn maybe_encrypt(
+    stream: &mut PgStream,
+    options: &PgConnectOptions,
+) -> Result<bool, Error> {
+    // GSSAPI encryption is only used for TCP connections, like libpq
+    if options.fetch_socket().is_some() {
+        return match options.gss_enc_mode {
+            PgGssEncMode::Require => Err(Error::Configuration(
+                "GSSAPI encryption is not supported for Unix domain socket connections".into(),
+            )),
+            _ => Ok(false),
+        };
+    }
+
+    let ctx = match options.gss_enc_mode {
+        PgGssEncMode::Disable => return Ok(false),
+
+        PgGssEncMode::Prefer => match client_context(options, encryption_flags()) {
+            Ok(ctx) => ctx,
+
+            // no credentials, so don't bother asking the server
+            Err(_) => return Ok(false),
+        },
+
+        PgGssEncMode::Require => client_context(options, encryption_flags())?,
+    };
+
+    match encrypt(stream, ctx, options.gss_enc_mode).await {
+        Err(_) if options.gss_enc_mode == PgGssEncMode::Prefer => {
+            *stream = PgStream::connect(options, stream.addr).await?;
+
+            Ok(false)
+        }
+
+        res => res,
+    }
+}
+
+fn encryption_flags() -> CtxFlags {
+    CtxFlags::GSS_C_MUTUAL_FLAG
+        | CtxFlags::GSS_C_REPLAY_FLAG
+        | CtxFlags::GSS_C_SEQUENCE_FLAG
+        | CtxFlags::GSS_C_CONF_FLAG
+        | CtxFlags::GSS_C_INTEG_FLAG
+}
+
+async fn encrypt(
+    stream: &mut PgStream,
+    mut ctx: ClientCtx,
+    mode: PgGssEncMode,
+) -> Result<bool, Error> {
+    // https://www.postgresql.org/docs/current/protocol-flow.html#PROTOCOL-FLOW-GSSAPI
+
+    // To initiate a GSSAPI-encrypted connection, the frontend initially sends a
+    // GSSENCRequest message rather than a StartupMessage
+
+    stream.send(GssEncRequest).await?;
+
+    // The server then responds with a single byte containing G or N, indicating that
+    // it is willing or unwilling to perform GSSAPI encryption, respectively.
+
+    match stream.read::<Bytes>(1).await?[0] {
+        b'G' => {
+            // The server is ready and willing to accept a GSSAPI-encrypted connection
+        }
+
+        b'N' => {
+            // The server is _unwilling_ to perform GSSAPI encryption
+            return match mode {
+                PgGssEncMode::Require => Err(Error::Configuration(
+                    "server does not support GSSAPI encryption".into(),
+                )),
+                _ => Ok(false),
+            };
+        }
+
+        b'E' => {
+            // Postgres 11 and older don't recognize the request and close the connection,
+            // so this is only an error if encryption is required
+            return Err(err_protocol!(
+                "server rejected GSSENCRequest, it may not support GSSAPI encryption; \
+                 try connecting with `gssencmode=disable`"
+            ));
+        }
+
+        other => {
+            return Err(err_protocol!(
+                "unexpected response from GSSENCRequest: 0x{:02x}",
+                other
+            ));
+        }
+    }
+
+    // The frontend and server then exchange length-prefixed tokens until the security context
+    // is established, starting with the frontend
+    let mut server_token: Option<Bytes> = None;
+
+    loop {
+        if let Some(token) = ctx.step(server_token.as_deref(), None).map_err(gss_error)? {
+            let mut packet = Vec::with_capacity(token.len() + 4);
+            packet.extend(&(token.len() as u32).to_be_bytes());
+            packet.extend(&*token);
+
+            stream.write(&*packet);
+            stream.flush().await?;
+        }
+
+        if ctx.is_complete() {
+            break;
+        }
+
+        let mut header: Bytes = stream.read(4).await?;
+
+        if header[0] == b'E' {
+            // the server reports failures with an `ErrorResponse` instead of a token; as tokens
+            // are never this large, its tag can't be confused with the length of a token
+            let len = u32::from_be_bytes([
+                header[1],
+                header[2],
+                header[3],
+                stream.read::<Bytes>(1).await?[0],
+            ]);
+            let notice: Notice = stream.read(len as usize - 4).await?;
+
+            return Err(PgDatabaseError(notice).into());
+        }
+
+        let len = header.get_u32() as usize;
+
+        if len > MAX_NEGOTIATION_TOKEN {
+            return Err(err_protocol!(
+                "oversize GSSAPI token received from server: {} bytes",
+                len
+            ));
+        }
+
+        server_token = Some(stream.read(len).await?);
+    }
+
+    // all further traffic, starting with the StartupMessage, is encrypted
+    stream.encrypt(ctx)?;
+
+    Ok(true)
+}
+
+pub(super) fn gss_error(error: libgssapi::error::Error) -> Error {
+    err_protocol!("GSSAPI error: {}", error)
+}
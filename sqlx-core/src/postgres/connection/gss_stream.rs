@@ -0,0 +1,345 @@
+use std::cmp;
+use std::io;
+use std::mem::replace;
+use std::ops::{Deref, DerefMut};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use bytes::{Buf, Bytes, BytesMut};
+use futures_util::ready;
+use libgssapi::context::{ClientCtx, SecurityContext};
+use sqlx_rt::{AsyncRead, AsyncWrite};
+
+use crate::error::Error;
+use crate::net::{poll_read_chunk, read_capacity, read_out, PollReadBuf, PollReadOut};
+
+// https://www.postgresql.org/docs/current/protocol-flow.html#PROTOCOL-FLOW-GSSAPI
+
+// libpq and the server use buffers of this size for a packet, including its length prefix
+const MAX_PACKET_SIZE: usize = 16384;
+
+// plaintext is split into chunks leaving ample room for the overhead of wrapping it,
+// whatever the Kerberos encryption type
+const MAX_PLAINTEXT_SIZE: usize = MAX_PACKET_SIZE - 4 - 512;
+
+/// A stream which may be encrypted with GSSAPI after the connection has been established.
+pub enum MaybeGssEncStream<S>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    Raw(S),
+    Gss(GssEncStream<S>),
+    Upgrading,
+}
+
+impl<S> MaybeGssEncStream<S>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    /// Encrypt all further traffic using the established security context `ctx`.
+    pub fn encrypt(&mut self, ctx: ClientCtx) -> Result<(), Error> {
+        match replace(self, MaybeGssEncStream::Upgrading) {
+            MaybeGssEncStream::Raw(inner) => {
+                *self = MaybeGssEncStream::Gss(GssEncStream {
+                    inner,
+                    ctx,
+                    read_buf: BytesMut::new(),
+                    decrypted: Bytes::new(),
+                    write_buf: Bytes::new(),
+                });
+
+                Ok(())
+            }
+
+            MaybeGssEncStream::Gss(_) => Err(err_protocol!(
+                "attempted to encrypt a connection that is already encrypted"
+            )),
+
+            MaybeGssEncStream::Upgrading => Err(Error::Io(io::ErrorKind::ConnectionAborted.into())),
+        }
+    }
+}
+
+/// Wraps `S` so that everything written is encrypted with GSSAPI and sent as length-prefixed
+/// packets, and everything read is decrypted from such packets.
+pub struct GssEncStream<S> {
+    inner: S,
+    ctx: ClientCtx,
+
+    // encrypted data read from `inner`, not yet forming a complete packet
+    read_buf: BytesMut,
+
+    // decrypted data not yet returned to the reader
+    decrypted: Bytes,
+
+    // encrypted packets not yet written to `inner`
+    write_buf: Bytes,
+}
+
+impl<S> GssEncStream<S>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    // decrypt the next packet from `read_buf`, if it has been received in full
+    fn decrypt_packet(&mut self) -> io::Result<bool> {
+        if self.read_buf.len() < 4 {
+            return Ok(false);
+        }
+
+        let len = u32::from_be_bytes([
+            self.read_buf[0],
+            self.read_buf[1],
+            self.read_buf[2],
+            self.read_buf[3],
+        ]) as usize;
+
+        if len > MAX_PACKET_SIZE - 4 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("oversize GSSAPI packet received from server: {} bytes", len),
+            ));
+        }
+
+        if self.read_buf.len() < len + 4 {
+            return Ok(false);
+        }
+
+        self.read_buf.advance(4);
+        let packet = self.read_buf.split_to(len);
+
+        let decrypted = self.ctx.unwrap(&packet).map_err(gss_io_error)?;
+        self.decrypted = Bytes::copy_from_slice(&decrypted);
+
+        Ok(true)
+    }
+
+    // write out any pending encrypted packets
+    fn poll_write_buf(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        while !self.write_buf.is_empty() {
+            let written = ready!(Pin::new(&mut self.inner).poll_write(cx, &self.write_buf))?;
+
+            if written == 0 {
+                return Poll::Ready(Err(io::ErrorKind::WriteZero.into()));
+            }
+
+            self.write_buf.advance(written);
+        }
+
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<S> AsyncRead for GssEncStream<S>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut PollReadBuf<'_>,
+    ) -> Poll<io::Result<PollReadOut>> {
+        let this = &mut *self;
+
+        while this.decrypted.is_empty() {
+            if this.decrypt_packet()? {
+                continue;
+            }
+
+            let mut chunk = [0; 8192];
+            let read = ready!(poll_read_chunk(&mut this.inner, cx, &mut chunk))?;
+
+            if read == 0 {
+                if !this.read_buf.is_empty() {
+                    return Poll::Ready(Err(io::ErrorKind::UnexpectedEof.into()));
+                }
+
+                // end of stream
+                return read_out(buf, &[]);
+            }
+
+            this.read_buf.extend_from_slice(&chunk[..read]);
+        }
+
+        let len = cmp::min(read_capacity(buf), this.decrypted.len());
+        let out = read_out(buf, &this.decrypted[..len]);
+        this.decrypted.advance(len);
+
+        out
+    }
+}
+
+impl<S> AsyncWrite for GssEncStream<S>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = &mut *self;
+
+        ready!(this.poll_write_buf(cx))?;
+
+        if buf.is_empty() {
+            return Poll::Ready(Ok(0));
+        }
+
+        let len = cmp::min(buf.len(), MAX_PLAINTEXT_SIZE);
+        let encrypted = this.ctx.wrap(true, &buf[..len]).map_err(gss_io_error)?;
+
+        if encrypted.len() > MAX_PACKET_SIZE - 4 {
+            return Poll::Ready(Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "GSSAPI packet of {} bytes is too large to send",
+                    encrypted.len()
+                ),
+            )));
+        }
+
+        let mut packet = Vec::with_capacity(encrypted.len() + 4);
+        packet.extend(&(encrypted.len() as u32).to_be_bytes());
+        packet.extend_from_slice(&encrypted);
+
+        this.write_buf = packet.into();
+
+        // the data is accepted once it has been encrypted; it is sent by the next write or flush
+        let _ = this.poll_write_buf(cx)?;
+
+        Poll::Ready(Ok(len))
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        ready!(self.poll_write_buf(cx))?;
+
+        Pin::new(&mut self.inner).poll_flush(cx)
+    }
+
+    #[cfg(feature = "_rt-tokio")]
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        ready!(self.poll_write_buf(cx))?;
+
+        Pin::new(&mut self.inner).poll_shutdown(cx)
+    }
+
+    #[cfg(feature = "_rt-async-std")]
+    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        ready!(self.poll_write_buf(cx))?;
+
+        Pin::new(&mut self.inner).poll_close(cx)
+    }
+}
+
+fn gss_io_error(error: libgssapi::error::Error) -> io::Error {
+    io::Error::new(io::ErrorKind::Other, error)
+}
+
+impl<S> AsyncRead for MaybeGssEncStream<S>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut PollReadBuf<'_>,
+    ) -> Poll<io::Result<PollReadOut>> {
+        match &mut *self {
+            MaybeGssEncStream::Raw(s) => Pin::new(s).poll_read(cx, buf),
+            MaybeGssEncStream::Gss(s) => Pin::new(s).poll_read(cx, buf),
+
+            MaybeGssEncStream::Upgrading => {
+                Poll::Ready(Err(io::ErrorKind::ConnectionAborted.into()))
+            }
+        }
+    }
+}
+
+impl<S> AsyncWrite for MaybeGssEncStream<S>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        match &mut *self {
+            MaybeGssEncStream::Raw(s) => Pin::new(s).poll_write(cx, buf),
+            MaybeGssEncStream::Gss(s) => Pin::new(s).poll_write(cx, buf),
+
+            MaybeGssEncStream::Upgrading => {
+                Poll::Ready(Err(io::ErrorKind::ConnectionAborted.into()))
+            }
+        }
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match &mut *self {
+            MaybeGssEncStream::Raw(s) => Pin::new(s).poll_flush(cx),
+            MaybeGssEncStream::Gss(s) => Pin::new(s).poll_flush(cx),
+
+            MaybeGssEncStream::Upgrading => {
+                Poll::Ready(Err(io::ErrorKind::ConnectionAborted.into()))
+            }
+        }
+    }
+
+    #[cfg(feature = "_rt-tokio")]
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match &mut *self {
+            MaybeGssEncStream::Raw(s) => Pin::new(s).poll_shutdown(cx),
+            MaybeGssEncStream::Gss(s) => Pin::new(s).poll_shutdown(cx),
+
+            MaybeGssEncStream::Upgrading => {
+                Poll::Ready(Err(io::ErrorKind::ConnectionAborted.into()))
+            }
+        }
+    }
+
+    #[cfg(feature = "_rt-async-std")]
+    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match &mut *self {
+            MaybeGssEncStream::Raw(s) => Pin::new(s).poll_close(cx),
+            MaybeGssEncStream::Gss(s) => Pin::new(s).poll_close(cx),
+
+            MaybeGssEncStream::Upgrading => {
+                Poll::Ready(Err(io::ErrorKind::ConnectionAborted.into()))
+            }
+        }
+    }
+}
+
+impl<S> Deref for MaybeGssEncStream<S>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    type Target = S;
+
+    fn deref(&self) -> &Self::Target {
+        match self {
+            MaybeGssEncStream::Raw(s) => s,
+            MaybeGssEncStream::Gss(s) => &s.inner,
+
+            MaybeGssEncStream::Upgrading => {
+                panic!("{}", io::Error::from(io::ErrorKind::ConnectionAborted))
+            }
+        }
+    }
+}
+
+impl<S> DerefMut for MaybeGssEncStream<S>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        match self {
+            MaybeGssEncStream::Raw(s) => s,
+            MaybeGssEncStream::Gss(s) => &mut s.inner,
+
+            MaybeGssEncStream::Upgrading => {
+                panic!("{}", io::Error::from(io::ErrorKind::ConnectionAborted))
+            }
+        }
+    }
+}
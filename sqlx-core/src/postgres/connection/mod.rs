@@ -1,31 +1,41 @@
 use std::fmt::{self, Debug, Formatter};
 use std::sync::Arc;
+use std::time::SystemTime;
 
 use crate::HashMap;
 use futures_core::future::BoxFuture;
 use futures_util::FutureExt;
 
-use crate::common::StatementCache;
-use crate::connection::{Connection, LogSettings};
+use crate::common::{PreparedStatement, StatementCache};
+use crate::connection::{time_from_unix_micros, Connection, LogSettings};
 use crate::error::Error;
 use crate::ext::ustr::UStr;
 use crate::io::Decode;
+use crate::postgres::connection::type_cache::{PgStaleType, PgTypeCache, PgTypeCacheTarget};
+use crate::postgres::explain::PgAutoExplain;
 use crate::postgres::message::{
     Close, Message, MessageFormat, Query, ReadyForQuery, Terminate, TransactionStatus,
 };
-use crate::postgres::statement::PgStatementMetadata;
+use crate::postgres::statement::{PgStatement, PgStatementMetadata};
 use crate::postgres::types::Oid;
 use crate::postgres::{PgConnectOptions, PgTypeInfo, Postgres};
-use crate::transaction::Transaction;
+use crate::query_scalar::query_scalar;
+use crate::statement::PreparedStatementInfo;
+use crate::transaction::{Transaction, TransactionOptions};
 
 pub use self::stream::PgStream;
 
 pub(crate) mod describe;
 mod establish;
 mod executor;
+#[cfg(feature = "postgres-gssapi")]
+mod gss;
+#[cfg(feature = "postgres-gssapi")]
+mod gss_stream;
 mod sasl;
 mod stream;
 mod tls;
+pub(crate) mod type_cache;
 
 /// A connection to a PostgreSQL database.
 pub struct PgConnection {
@@ -36,7 +46,6 @@ pub struct PgConnection {
 
     // process id of this backend
     // used to send cancel requests
-    #[allow(dead_code)]
     process_id: u32,
 
     // secret key of this backend
@@ -49,20 +58,50 @@ pub struct PgConnection {
     next_statement_id: Oid,
 
     // cache statement by query string to the id and columns
-    cache_statement: StatementCache<(Oid, Arc<PgStatementMetadata>)>,
+    cache_statement: StatementCache<(PreparedStatement<Oid>, Arc<PgStatementMetadata>)>,
+    cache_statistics: PgStatementCacheStatistics,
+
+    // avoid named prepared statements, see `PgConnectOptions::prefer_simple_protocol`
+    prefer_simple_protocol: bool,
 
     // cache user-defined types by id <-> info
     cache_type_info: HashMap<Oid, PgTypeInfo>,
     cache_type_oid: HashMap<UStr, Oid>,
 
+    // cache user-defined type names -> id, shared with all connections from the same options
+    shared_type_cache: Arc<PgTypeCache>,
+    shared_type_cache_target: PgTypeCacheTarget,
+    shared_type_cache_generation: u64,
+
     // number of ReadyForQuery messages that we are currently expecting
     pub(crate) pending_ready_for_query_count: usize,
 
     // current transaction status
-    transaction_status: TransactionStatus,
+    pub(crate) transaction_status: TransactionStatus,
     pub(crate) transaction_depth: usize,
 
-    log_settings: LogSettings,
+    pub(crate) log_settings: LogSettings,
+
+    // explain slow statements, see `PgConnectOptions::explain_slow_statements`
+    pub(crate) auto_explain: Option<Arc<PgAutoExplain>>,
+    pub(crate) explaining: bool,
+}
+
+/// Prepared statement counters for a [`PgConnection`], returned by
+/// [`PgConnection::cache_statistics`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct PgStatementCacheStatistics {
+    /// The number of statements prepared on the server, including those of queries that are not
+    /// [`persistent`](crate::query::Query::persistent) and so are never cached.
+    pub prepared: u64,
+
+    /// The number of times a cached statement was reused instead of preparing the query again.
+    pub hits: u64,
+
+    /// The number of statements closed on the server because they were the least recently used
+    /// when the cache was full.
+    pub evictions: u64,
 }
 
 impl PgConnection {
@@ -71,6 +110,52 @@ impl PgConnection {
         self.stream.server_version_num
     }
 
+    /// Returns the process ID of the server backend handling this connection,
+    /// as returned by `pg_backend_pid()`.
+    pub fn backend_pid(&self) -> u32 {
+        self.process_id
+    }
+
+    /// Returns the current value of a run-time parameter reported by the server.
+    ///
+    /// The server reports the values of a [fixed set of parameters][reported] (such as
+    /// `server_version`, `TimeZone` or `application_name`) when the connection is established
+    /// and again whenever they change during the session, e.g. after a `SET`. Returns `None`
+    /// for any other parameter; use `SHOW` or `current_setting()` to query those instead.
+    ///
+    /// [reported]: https://www.postgresql.org/docs/current/protocol-flow.html#PROTOCOL-ASYNC
+    pub fn parameter(&self, name: &str) -> Option<&str> {
+        self.stream.parameter_statuses.get(name).map(String::as_str)
+    }
+
+    /// Returns counters describing how this connection has used its prepared statement cache.
+    ///
+    /// The capacity of the cache is set with
+    /// [`PgConnectOptions::statement_cache_capacity`](crate::postgres::PgConnectOptions::statement_cache_capacity).
+    pub fn cache_statistics(&self) -> PgStatementCacheStatistics {
+        self.cache_statistics
+    }
+
+    /// Returns the current time of the server, e.g. to detect clock skew by comparing it to the
+    /// local time.
+    ///
+    /// This is `clock_timestamp()` rather than `now()`, which is the time the current transaction
+    /// started. Unlike [`ping`](Connection::ping), it executes a statement, so it fails inside a
+    /// transaction which is aborted.
+    pub async fn server_time(&mut self) -> Result<SystemTime, Error> {
+        let micros: i64 =
+            query_scalar("SELECT (EXTRACT(EPOCH FROM clock_timestamp()) * 1000000)::int8")
+                .fetch_one(&mut *self)
+                .await?;
+
+        Ok(time_from_unix_micros(micros))
+    }
+
+    /// Returns `true` if the connection is inside a transaction block, as last reported by the server.
+    pub(in crate::postgres) fn in_transaction(&self) -> bool {
+        !matches!(self.transaction_status, TransactionStatus::Idle)
+    }
+
     // will return when the connection is ready for another query
     pub(in crate::postgres) async fn wait_until_ready(&mut self) -> Result<(), Error> {
         if !self.stream.wbuf.is_empty() {
@@ -107,6 +192,36 @@ impl PgConnection {
         Ok(())
     }
 
+    /// Forget the cached OID of the type `error` reports missing, as it may be stale, which
+    /// happens when a user-defined type is dropped and re-created.
+    pub(in crate::postgres) fn invalidate_type_cache_on(&mut self, error: &Error) {
+        let error = match error {
+            Error::Database(error) => error,
+            _ => return,
+        };
+
+        let code = match error.code() {
+            Some(code) => code,
+            None => return,
+        };
+
+        if let Some(stale) = PgStaleType::from_error(&code, error.message()) {
+            self.shared_type_cache
+                .invalidate(&self.shared_type_cache_target, &stale);
+        }
+    }
+
+    /// Drop the local type caches if the shared cache has been cleared since we last looked.
+    pub(in crate::postgres) fn sync_type_cache(&mut self) {
+        let generation = self.shared_type_cache.generation();
+
+        if generation != self.shared_type_cache_generation {
+            self.cache_type_info.clear();
+            self.cache_type_oid.clear();
+            self.shared_type_cache_generation = generation;
+        }
+    }
+
     /// Queue a simple query (not prepared) to execute the next time this connection is used.
     ///
     /// Used for rolling back transactions and releasing advisory locks.
@@ -169,6 +284,16 @@ impl Connection for PgConnection {
         Transaction::begin(self)
     }
 
+    fn begin_with(
+        &mut self,
+        options: TransactionOptions,
+    ) -> BoxFuture<'_, Result<Transaction<'_, Self::Database>, Error>>
+    where
+        Self: Sized,
+    {
+        Transaction::begin_with(self, options)
+    }
+
     fn cached_statements_size(&self) -> usize {
         self.cache_statement.len()
     }
@@ -179,8 +304,9 @@ impl Connection for PgConnection {
 
             self.wait_until_ready().await?;
 
-            while let Some((id, _)) = self.cache_statement.remove_lru() {
-                self.stream.write(Close::Statement(id));
+            while let Some((prepared, _)) = self.cache_statement.remove_lru() {
+                prepared.close();
+                self.stream.write(Close::Statement(prepared.id()));
                 cleared += 1;
             }
 
@@ -196,6 +322,61 @@ impl Connection for PgConnection {
         })
     }
 
+    fn close_statement<'c, 'q: 'c>(
+        &'c mut self,
+        statement: PgStatement<'q>,
+    ) -> BoxFuture<'c, Result<(), Error>> {
+        Box::pin(async move {
+            let prepared = match statement.prepared {
+                Some(prepared) => {
+                    if prepared.is_closed(self.log_settings.connection_id)? {
+                        return Ok(());
+                    }
+
+                    // the statement is cached unless the cache is disabled
+                    if let Some((cached, _)) = self.cache_statement.get_mut(&statement.sql) {
+                        if cached.id() == prepared.id() {
+                            self.cache_statement.remove(&statement.sql);
+                        }
+                    }
+
+                    prepared
+                }
+
+                None => match self.cache_statement.remove(&statement.sql) {
+                    Some((prepared, _)) => prepared,
+                    None => return Ok(()),
+                },
+            };
+
+            prepared.close();
+
+            self.wait_until_ready().await?;
+
+            self.stream.write(Close::Statement(prepared.id()));
+            self.write_sync();
+            self.stream.flush().await?;
+
+            self.wait_for_close_complete(1).await?;
+            self.recv_ready_for_query().await?;
+
+            Ok(())
+        })
+    }
+
+    fn prepared_statements(&mut self) -> BoxFuture<'_, Result<Vec<PreparedStatementInfo>, Error>> {
+        let statements = self
+            .cache_statement
+            .iter()
+            .map(|(sql, (prepared, _))| PreparedStatementInfo {
+                sql: sql.to_owned(),
+                hits: prepared.hits(),
+            })
+            .collect();
+
+        Box::pin(async move { Ok(statements) })
+    }
+
     #[doc(hidden)]
     fn flush(&mut self) -> BoxFuture<'_, Result<(), Error>> {
         self.wait_until_ready().boxed()
@@ -1,4 +1,5 @@
 use std::collections::BTreeMap;
+use std::net::SocketAddr;
 use std::ops::{Deref, DerefMut};
 use std::str::FromStr;
 
@@ -10,6 +11,8 @@ use log::Level;
 use crate::error::Error;
 use crate::io::{BufStream, Decode, Encode};
 use crate::net::{MaybeTlsStream, Socket};
+#[cfg(feature = "postgres-gssapi")]
+use crate::postgres::connection::gss_stream::MaybeGssEncStream;
 use crate::postgres::message::{Message, MessageFormat, Notice, Notification, ParameterStatus};
 use crate::postgres::{PgConnectOptions, PgDatabaseError, PgSeverity};
 
@@ -22,8 +25,15 @@ use crate::postgres::{PgConnectOptions, PgDatabaseError, PgSeverity};
 // in other words, `self` in any PgConnection method is a live connection to postgres that
 // is fully prepared to receive queries
 
+// with GSSAPI support, the socket may be encrypted with GSSAPI instead of TLS
+#[cfg(feature = "postgres-gssapi")]
+type PgSocket = MaybeGssEncStream<Socket>;
+
+#[cfg(not(feature = "postgres-gssapi"))]
+type PgSocket = Socket;
+
 pub struct PgStream {
-    inner: BufStream<MaybeTlsStream<Socket>>,
+    inner: BufStream<MaybeTlsStream<PgSocket>>,
 
     // buffer of unreceived notification messages from `PUBLISH`
     // this is set when creating a PgListener and only written to if that listener is
@@ -33,15 +43,27 @@ pub struct PgStream {
     pub(crate) parameter_statuses: BTreeMap<String, String>,
 
     pub(crate) server_version_num: Option<u32>,
+
+    // the address connected to, if it was resolved ahead of time rather than by the host name,
+    // to connect to the same server again if it refuses GSSAPI encryption
+    #[cfg(feature = "postgres-gssapi")]
+    pub(crate) addr: Option<SocketAddr>,
 }
 
 impl PgStream {
-    pub(super) async fn connect(options: &PgConnectOptions) -> Result<Self, Error> {
-        let socket = match options.fetch_socket() {
-            Some(ref path) => Socket::connect_uds(path).await?,
-            None => Socket::connect_tcp(&options.host, options.port).await?,
+    pub(super) async fn connect(
+        options: &PgConnectOptions,
+        addr: Option<SocketAddr>,
+    ) -> Result<Self, Error> {
+        let socket = match (options.fetch_socket(), addr) {
+            (Some(ref path), _) => Socket::connect_uds(path).await?,
+            (None, Some(addr)) => Socket::connect_tcp_addr(addr).await?,
+            (None, None) => Socket::connect_tcp(&options.host, options.port).await?,
         };
 
+        #[cfg(feature = "postgres-gssapi")]
+        let socket = MaybeGssEncStream::Raw(socket);
+
         let inner = BufStream::new(MaybeTlsStream::Raw(socket));
 
         Ok(Self {
@@ -49,6 +71,8 @@ impl PgStream {
             notifications: None,
             parameter_statuses: BTreeMap::default(),
             server_version_num: None,
+            #[cfg(feature = "postgres-gssapi")]
+            addr,
         })
     }
 
@@ -119,15 +143,12 @@ impl PgStream {
                     let ParameterStatus { name, value } = message.decode()?;
                     // TODO: handle `client_encoding`, `DateStyle` change
 
-                    match name.as_str() {
-                        "server_version" => {
-                            self.server_version_num = parse_server_version(&value);
-                        }
-                        _ => {
-                            self.parameter_statuses.insert(name, value);
-                        }
+                    if name == "server_version" {
+                        self.server_version_num = parse_server_version(&value);
                     }
 
+                    self.parameter_statuses.insert(name, value);
+
                     continue;
                 }
 
@@ -171,7 +192,7 @@ impl PgStream {
 }
 
 impl Deref for PgStream {
-    type Target = BufStream<MaybeTlsStream<Socket>>;
+    type Target = BufStream<MaybeTlsStream<PgSocket>>;
 
     #[inline]
     fn deref(&self) -> &Self::Target {
@@ -0,0 +1,262 @@
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+use crate::HashMap;
+
+use crate::postgres::types::Oid;
+use crate::postgres::PgConnectOptions;
+
+/// A cache of user-defined type names to their OIDs, shared by every connection
+/// established from clones of the same [`PgConnectOptions`] (e.g. all connections of a pool).
+///
+/// As the options may be cloned and pointed at another server or database, entries are namespaced
+/// by the connection target so they are never served to a connection that could disagree.
+#[derive(Debug, Default)]
+pub(crate) struct PgTypeCache {
+    oids: Mutex<HashMap<PgTypeCacheKey, Oid>>,
+    // bumped whenever entries are removed so connections know to drop their local caches too
+    generation: AtomicU64,
+}
+
+/// Identifies the database that a set of cache entries belongs to, and what its type names
+/// resolve to, as that depends on the user and `search_path` too.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct PgTypeCacheTarget {
+    host: String,
+    port: u16,
+    socket: Option<PathBuf>,
+    database: Option<String>,
+    username: String,
+    search_path: Option<String>,
+}
+
+/// A type which the server reported missing, whose entry may be stale, e.g. as the type was
+/// dropped and re-created.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum PgStaleType<'a> {
+    Name(&'a str),
+    Oid(Oid),
+}
+
+#[derive(Debug, PartialEq, Eq, Hash)]
+struct PgTypeCacheKey {
+    target: PgTypeCacheTarget,
+    name: String,
+}
+
+impl PgTypeCacheTarget {
+    pub(crate) fn new(options: &PgConnectOptions) -> Self {
+        Self {
+            host: options.host.clone(),
+            port: options.port,
+            socket: options.socket.clone(),
+            database: options.database.clone(),
+            username: options.username.clone(),
+            search_path: search_path(options),
+        }
+    }
+}
+
+// the `search_path` set when connecting; the startup parameters are applied after `options`
+fn search_path(options: &PgConnectOptions) -> Option<String> {
+    if let Some(search_path) = options.startup_parameters.get("search_path") {
+        return Some(search_path.clone());
+    }
+
+    // `-c search_path=..`, `-csearch_path=..` or `--search_path=..`, the last one winning
+    options
+        .options
+        .as_deref()?
+        .split_whitespace()
+        .rev()
+        .find_map(|arg| {
+            let arg = arg
+                .strip_prefix("--")
+                .or_else(|| arg.strip_prefix("-c"))
+                .unwrap_or(arg);
+
+            let (name, value) = arg.split_once('=')?;
+
+            (name.replace('-', "_") == "search_path").then(|| value.to_owned())
+        })
+}
+
+impl<'a> PgStaleType<'a> {
+    /// The type an error with `code` and `message` reports missing, if any.
+    pub(crate) fn from_error(code: &str, message: &'a str) -> Option<Self> {
+        match code {
+            // undefined_object
+            "42704" => {
+                if let Some(oid) = message
+                    .strip_prefix("type with OID ")
+                    .and_then(|rest| rest.strip_suffix(" does not exist"))
+                {
+                    return oid.parse().ok().map(|oid| PgStaleType::Oid(Oid(oid)));
+                }
+
+                message
+                    .strip_prefix("type \"")
+                    .and_then(|rest| rest.strip_suffix("\" does not exist"))
+                    .map(PgStaleType::Name)
+            }
+
+            // internal_error, raised when the backend cannot find an OID in its syscache
+            "XX000" => message
+                .strip_prefix("cache lookup failed for type ")
+                .and_then(|oid| oid.parse().ok())
+                .map(|oid| PgStaleType::Oid(Oid(oid))),
+
+            _ => None,
+        }
+    }
+
+    /// Whether this is the type named `name`, or with the OID `oid`, in a cache.
+    pub(crate) fn matches(&self, name: &str, oid: Oid) -> bool {
+        match *self {
+            // the server may qualify the name with the schema it was looked up in
+            PgStaleType::Name(stale) => {
+                name.eq_ignore_ascii_case(stale)
+                    || matches!(stale.rsplit_once('.'), Some((_, stale)) if name.eq_ignore_ascii_case(stale))
+            }
+            PgStaleType::Oid(stale) => oid == stale,
+        }
+    }
+}
+
+impl PgTypeCache {
+    pub(crate) fn get(&self, target: &PgTypeCacheTarget, name: &str) -> Option<Oid> {
+        let key = PgTypeCacheKey {
+            target: target.clone(),
+            name: name.to_owned(),
+        };
+
+        self.oids.lock().unwrap().get(&key).copied()
+    }
+
+    pub(crate) fn insert(&self, target: &PgTypeCacheTarget, name: &str, oid: Oid) {
+        let key = PgTypeCacheKey {
+            target: target.clone(),
+            name: name.to_owned(),
+        };
+
+        self.oids.lock().unwrap().insert(key, oid);
+    }
+
+    /// Forget the entry of the `stale` type resolved against `target`.
+    pub(crate) fn invalidate(&self, target: &PgTypeCacheTarget, stale: &PgStaleType<'_>) {
+        self.oids
+            .lock()
+            .unwrap()
+            .retain(|key, oid| key.target != *target || !stale.matches(&key.name, *oid));
+
+        self.generation.fetch_add(1, Ordering::AcqRel);
+    }
+
+    /// Forget every entry.
+    pub(crate) fn clear(&self) {
+        self.oids.lock().unwrap().clear();
+        self.generation.fetch_add(1, Ordering::AcqRel);
+    }
+
+    /// Incremented every time entries are removed from the cache.
+    pub(crate) fn generation(&self) -> u64 {
+        self.generation.load(Ordering::Acquire)
+    }
+}
+
+#[test]
+fn test_type_cache_is_keyed_by_target() {
+    let options = PgConnectOptions::new_without_pgpass()
+        .host("localhost")
+        .port(5432);
+
+    let a = PgTypeCacheTarget::new(&options.clone().database("a"));
+    let b = PgTypeCacheTarget::new(&options.clone().database("b"));
+    let c = PgTypeCacheTarget::new(&options.clone().database("a").port(5433));
+    let d = PgTypeCacheTarget::new(&options.clone().database("a").username("other"));
+    let e = PgTypeCacheTarget::new(
+        &options
+            .clone()
+            .database("a")
+            .options([("search_path", "app")]),
+    );
+
+    let cache = PgTypeCache::default();
+
+    cache.insert(&a, "mood", Oid(16385));
+    cache.insert(&a, "feeling", Oid(16390));
+
+    assert_eq!(cache.get(&a, "mood"), Some(Oid(16385)));
+    assert_eq!(cache.get(&a, "color"), None);
+    assert_eq!(cache.get(&b, "mood"), None);
+    assert_eq!(cache.get(&c, "mood"), None);
+    assert_eq!(cache.get(&d, "mood"), None);
+    assert_eq!(cache.get(&e, "mood"), None);
+
+    cache.insert(&b, "mood", Oid(16400));
+    cache.invalidate(&a, &PgStaleType::Name("public.mood"));
+
+    assert_eq!(cache.get(&a, "mood"), None);
+    assert_eq!(cache.get(&a, "feeling"), Some(Oid(16390)));
+    assert_eq!(cache.get(&b, "mood"), Some(Oid(16400)));
+
+    cache.invalidate(&a, &PgStaleType::Oid(Oid(16390)));
+
+    assert_eq!(cache.get(&a, "feeling"), None);
+
+    let generation = cache.generation();
+    cache.clear();
+
+    assert_eq!(cache.get(&b, "mood"), None);
+    assert_eq!(cache.generation(), generation + 1);
+}
+
+#[test]
+fn test_type_cache_is_keyed_by_search_path() {
+    let options = PgConnectOptions::new_without_pgpass();
+
+    assert_eq!(search_path(&options), None);
+    assert_eq!(
+        search_path(&options.clone().options([("search_path", "app,public")])),
+        Some("app,public".into())
+    );
+    assert_eq!(
+        search_path(
+            &options
+                .clone()
+                .options([("search_path", "app")])
+                .startup_parameter("search_path", "other")
+        ),
+        Some("other".into())
+    );
+
+    let mut options = options;
+    options.options = Some("-c geqo=off --search-path=a -csearch_path=b".into());
+
+    assert_eq!(search_path(&options), Some("b".into()));
+}
+
+#[test]
+fn test_stale_type_from_error() {
+    assert_eq!(
+        PgStaleType::from_error("42704", "type \"mood\" does not exist"),
+        Some(PgStaleType::Name("mood"))
+    );
+    assert_eq!(
+        PgStaleType::from_error("42704", "type with OID 16385 does not exist"),
+        Some(PgStaleType::Oid(Oid(16385)))
+    );
+    assert_eq!(
+        PgStaleType::from_error("XX000", "cache lookup failed for type 16385"),
+        Some(PgStaleType::Oid(Oid(16385)))
+    );
+    assert_eq!(
+        PgStaleType::from_error("42704", "unrecognized configuration parameter \"foo\""),
+        None
+    );
+    assert_eq!(
+        PgStaleType::from_error("XX000", "cache lookup failed for relation 1"),
+        None
+    );
+}
@@ -5,6 +5,7 @@ use crate::postgres::{
     PgArguments, PgColumn, PgConnection, PgQueryResult, PgRow, PgStatement, PgTransactionManager,
     PgTypeInfo,
 };
+use crate::quote::{self, QuoteError};
 
 /// PostgreSQL database driver.
 #[derive(Debug)]
@@ -24,6 +25,23 @@ impl Database for Postgres {
     type TypeInfo = PgTypeInfo;
 
     type Value = PgValue;
+
+    fn quote_identifier(identifier: &str) -> Result<String, QuoteError> {
+        // longer identifiers would be truncated to `NAMEDATALEN - 1` bytes
+        quote::check_identifier_len(identifier.len(), 63)?;
+        quote::quote(identifier, '"', '"')
+    }
+
+    fn quote_literal(value: &str) -> Result<String, QuoteError> {
+        // an escape string reads the same whether `standard_conforming_strings` is on or off
+        if value.contains('\\') {
+            let quoted = quote::quote(&value.replace('\\', "\\\\"), '\'', '\'')?;
+
+            return Ok(format!("E{}", quoted));
+        }
+
+        quote::quote(value, '\'', '\'')
+    }
 }
 
 impl<'r> HasValueRef<'r> for Postgres {
@@ -0,0 +1,586 @@
+use crate::column::ColumnIndex;
+use crate::decode::Decode;
+use crate::error::{BoxDynError, Error};
+use crate::postgres::type_info::PgType;
+use crate::postgres::types::Oid;
+use crate::postgres::{PgRow, PgTypeInfo, PgTypeKind, PgValueFormat, PgValueRef, Postgres};
+use crate::row::Row;
+use crate::type_info::TypeInfo;
+use crate::value::ValueRef;
+use bytes::Buf;
+use std::cmp;
+use std::fmt::Write;
+
+/// A value of any type, decoded without knowing its Rust type in advance.
+///
+/// This is returned by [`PgRow::try_get_dynamic`] and [`PgValueRef::to_dynamic`], for tools that
+/// handle the results of arbitrary queries, e.g. to export them as JSON or CSV.
+///
+/// Values of types that don't have a variant of their own are returned as [`Other`], with the
+/// raw bytes of the value as the server sent them. Values that don't map to a Rust primitive,
+/// such as `NUMERIC` and the date and time types, are formatted the same as Postgres would format
+/// them in the text format (so `TIMESTAMPTZ` is always in UTC, as SQLx sets the `TimeZone` of
+/// the connection to `UTC`).
+///
+/// [`Other`]: PgDynamicValue::Other
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum PgDynamicValue {
+    Null,
+    Bool(bool),
+    Int2(i16),
+    Int4(i32),
+    Int8(i64),
+    Float4(f32),
+    Float8(f64),
+
+    /// A `NUMERIC` value, e.g. `"-12.3400"` or `"NaN"`.
+    Numeric(String),
+
+    /// A `TEXT`, `VARCHAR`, `CHAR(N)` or `NAME` value, or the label of an enum.
+    Text(String),
+    Bytea(Vec<u8>),
+
+    /// A `TIMESTAMP` value, e.g. `"2022-02-28 13:05:00.5"`.
+    Timestamp(String),
+
+    /// A `TIMESTAMPTZ` value, e.g. `"2022-02-28 13:05:00.5+00"`.
+    TimestampTz(String),
+
+    /// A `DATE` value, e.g. `"2022-02-28"`.
+    Date(String),
+
+    /// A `TIME` value, e.g. `"13:05:00.5"`.
+    Time(String),
+
+    /// A `UUID` value in its hyphenated form, e.g. `"a2b0d3c6-33e2-4b0a-9e49-fd4a9dc2b4a2"`.
+    Uuid(String),
+
+    /// A `JSON` or `JSONB` value, as JSON text.
+    Json(String),
+
+    /// An array; multi-dimensional arrays are nested, with the outermost dimension first.
+    ///
+    /// Arrays in the text format, i.e. in the results of a simple query, are returned as
+    /// [`Other`](PgDynamicValue::Other).
+    Array(Vec<PgDynamicValue>),
+
+    /// A value of any other type.
+    Other {
+        type_name: String,
+        format: PgValueFormat,
+        bytes: Vec<u8>,
+    },
+}
+
+impl PgValueRef<'_> {
+    /// Decode this value into a [`PgDynamicValue`], based on its type.
+    pub fn to_dynamic(&self) -> Result<PgDynamicValue, BoxDynError> {
+        if self.is_null() {
+            return Ok(PgDynamicValue::Null);
+        }
+
+        decode_dynamic(self.clone())
+    }
+}
+
+impl PgRow {
+    /// Index into the database row and decode a single value into a [`PgDynamicValue`], based on
+    /// the type of its column.
+    ///
+    /// # Errors
+    ///
+    ///  * [`ColumnNotFound`] if the column by the given name was not found.
+    ///  * [`ColumnIndexOutOfBounds`] if the `usize` index was greater than the number of columns in the row.
+    ///  * [`ColumnDecode`] if the value could not be decoded.
+    ///
+    /// [`ColumnDecode`]: Error::ColumnDecode
+    /// [`ColumnNotFound`]: Error::ColumnNotFound
+    /// [`ColumnIndexOutOfBounds`]: Error::ColumnIndexOutOfBounds
+    pub fn try_get_dynamic<I>(&self, index: I) -> Result<PgDynamicValue, Error>
+    where
+        I: ColumnIndex<Self>,
+    {
+        self.try_get_raw(&index)?
+            .to_dynamic()
+            .map_err(|source| Error::ColumnDecode {
+                index: format!("{:?}", index),
+                source,
+            })
+    }
+}
+
+fn decode_dynamic(value: PgValueRef<'_>) -> Result<PgDynamicValue, BoxDynError> {
+    let binary = value.format() == PgValueFormat::Binary;
+
+    Ok(match &value.type_info.0 {
+        PgType::Bool => PgDynamicValue::Bool(decode(value)?),
+        PgType::Int2 => PgDynamicValue::Int2(decode(value)?),
+        PgType::Int4 => PgDynamicValue::Int4(decode(value)?),
+        PgType::Int8 => PgDynamicValue::Int8(decode(value)?),
+        PgType::Float4 => PgDynamicValue::Float4(decode(value)?),
+        PgType::Float8 => PgDynamicValue::Float8(decode(value)?),
+        PgType::Bytea => PgDynamicValue::Bytea(decode(value)?),
+
+        PgType::Text | PgType::Varchar | PgType::Bpchar | PgType::Name | PgType::Unknown => {
+            PgDynamicValue::Text(decode(value)?)
+        }
+
+        PgType::Numeric if binary => PgDynamicValue::Numeric(format_numeric(value.as_bytes()?)?),
+
+        PgType::Timestamp if binary => {
+            PgDynamicValue::Timestamp(format_timestamp(read_i64(value.as_bytes()?)?, false))
+        }
+
+        PgType::Timestamptz if binary => {
+            PgDynamicValue::TimestampTz(format_timestamp(read_i64(value.as_bytes()?)?, true))
+        }
+
+        PgType::Date if binary => PgDynamicValue::Date(format_date(read_i32(value.as_bytes()?)?)),
+
+        PgType::Time if binary => PgDynamicValue::Time(format_time(read_i64(value.as_bytes()?)?)),
+
+        PgType::Uuid if binary => PgDynamicValue::Uuid(format_uuid(value.as_bytes()?)?),
+
+        PgType::Jsonb if binary => {
+            // JSONB is prefixed by its version number (as of 2020-03-20)
+            match value.as_bytes()?.split_first() {
+                Some((1, json)) => PgDynamicValue::Json(std::str::from_utf8(json)?.to_owned()),
+                _ => return Err("unsupported JSONB format version; please open an issue".into()),
+            }
+        }
+
+        // in the text format, these are already formatted by the server
+        PgType::Numeric => PgDynamicValue::Numeric(value.as_str()?.to_owned()),
+        PgType::Timestamp => PgDynamicValue::Timestamp(value.as_str()?.to_owned()),
+        PgType::Timestamptz => PgDynamicValue::TimestampTz(value.as_str()?.to_owned()),
+        PgType::Date => PgDynamicValue::Date(value.as_str()?.to_owned()),
+        PgType::Time => PgDynamicValue::Time(value.as_str()?.to_owned()),
+        PgType::Uuid => PgDynamicValue::Uuid(value.as_str()?.to_owned()),
+        PgType::Json | PgType::Jsonb => PgDynamicValue::Json(value.as_str()?.to_owned()),
+
+        // the type of a column is left unresolved if the connection couldn't look it up
+        PgType::DeclareWithOid(_) | PgType::DeclareWithName(_) | PgType::DeclareArrayOf(_) => {
+            other(&value)?
+        }
+
+        _ => match value.type_info.kind() {
+            PgTypeKind::Enum(_) => PgDynamicValue::Text(value.as_str()?.to_owned()),
+
+            PgTypeKind::Domain(base) => {
+                // a domain has the same representation as its base type
+                let base = base.clone();
+                decode_dynamic(PgValueRef {
+                    type_info: base,
+                    ..value
+                })?
+            }
+
+            PgTypeKind::Array(element) if binary => {
+                let element = element.clone();
+                decode_array(value, element)?
+            }
+
+            _ => other(&value)?,
+        },
+    })
+}
+
+fn decode<'r, T: Decode<'r, Postgres>>(value: PgValueRef<'r>) -> Result<T, BoxDynError> {
+    T::decode(value)
+}
+
+fn other(value: &PgValueRef<'_>) -> Result<PgDynamicValue, BoxDynError> {
+    Ok(PgDynamicValue::Other {
+        type_name: value.type_info.name().to_owned(),
+        format: value.format(),
+        bytes: value.as_bytes()?.to_owned(),
+    })
+}
+
+fn decode_array(value: PgValueRef<'_>, element: PgTypeInfo) -> Result<PgDynamicValue, BoxDynError> {
+    // https://github.com/postgres/postgres/blob/a995b371ae29de2d38c4b7881cf414b1560e9746/src/backend/utils/adt/arrayfuncs.c#L1548
+
+    let mut buf = value.as_bytes()?;
+
+    if buf.len() < 12 {
+        return Err("invalid array header".into());
+    }
+
+    let ndim = buf.get_i32();
+    let _flags = buf.get_i32();
+    let element_type_oid = Oid(buf.get_u32());
+
+    if ndim == 0 {
+        return Ok(PgDynamicValue::Array(Vec::new()));
+    }
+
+    if ndim < 0 || buf.len() < ndim as usize * 8 {
+        return Err(format!("invalid number of array dimensions: {}", ndim).into());
+    }
+
+    let dims: Vec<usize> = (0..ndim)
+        .map(|_| {
+            let len = buf.get_i32();
+            let _lower_bound = buf.get_i32();
+
+            cmp::max(len, 0) as usize
+        })
+        .collect();
+
+    let count = dims
+        .iter()
+        .try_fold(1_usize, |count, &len| count.checked_mul(len))
+        .ok_or("invalid array dimensions")?;
+
+    let element_type = PgTypeInfo::resolve_nested(element_type_oid, Some(&element));
+
+    let mut elements = Vec::new();
+
+    for _ in 0..count {
+        if buf.len() < 4 {
+            return Err("unexpected end of array".into());
+        }
+
+        let len = buf.get_i32();
+
+        let element = if len < 0 {
+            PgDynamicValue::Null
+        } else {
+            let len = len as usize;
+            if buf.len() < len {
+                return Err("unexpected end of array".into());
+            }
+
+            let (bytes, rest) = buf.split_at(len);
+            buf = rest;
+
+            decode_dynamic(PgValueRef {
+                value: Some(bytes),
+                row: None,
+                type_info: element_type.clone(),
+                format: PgValueFormat::Binary,
+            })?
+        };
+
+        elements.push(element);
+    }
+
+    // the elements are in row-major order, so nest them from the innermost dimension outwards
+    for &len in dims[1..].iter().rev() {
+        let mut flat = elements.into_iter();
+        elements = Vec::new();
+
+        loop {
+            let chunk: Vec<_> = flat.by_ref().take(len).collect();
+            if chunk.is_empty() {
+                break;
+            }
+
+            elements.push(PgDynamicValue::Array(chunk));
+        }
+    }
+
+    Ok(PgDynamicValue::Array(elements))
+}
+
+fn read_i32(buf: &[u8]) -> Result<i32, BoxDynError> {
+    Ok(i32::from_be_bytes(buf.try_into()?))
+}
+
+fn read_i64(buf: &[u8]) -> Result<i64, BoxDynError> {
+    Ok(i64::from_be_bytes(buf.try_into()?))
+}
+
+// https://github.com/postgres/postgres/blob/bcd1c3630095e48bc3b1eb0fc8e8c8a7c851eba1/src/backend/utils/adt/numeric.c#L874
+// https://github.com/postgres/postgres/blob/bcd1c3630095e48bc3b1eb0fc8e8c8a7c851eba1/src/backend/utils/adt/numeric.c#L6802
+fn format_numeric(mut buf: &[u8]) -> Result<String, BoxDynError> {
+    if buf.len() < 8 {
+        return Err("invalid NUMERIC value".into());
+    }
+
+    let num_digits = buf.get_u16() as usize;
+    let weight = i32::from(buf.get_i16());
+    let sign = buf.get_u16();
+    let scale = cmp::max(buf.get_i16(), 0) as usize;
+
+    match sign {
+        0x0000 | 0x4000 => {}
+        0xC000 => return Ok("NaN".into()),
+
+        // Postgres 14 and later
+        0xD000 => return Ok("Infinity".into()),
+        0xF000 => return Ok("-Infinity".into()),
+
+        _ => return Err(format!("invalid NUMERIC sign: {:#06X}", sign).into()),
+    }
+
+    if buf.len() != num_digits * 2 {
+        return Err("invalid NUMERIC value".into());
+    }
+
+    // base-10000 digits, most significant first
+    let digits: Vec<i16> = (0..num_digits).map(|_| buf.get_i16()).collect();
+
+    let digit = |i: i32| {
+        usize::try_from(i)
+            .ok()
+            .and_then(|i| digits.get(i))
+            .copied()
+            .unwrap_or(0)
+    };
+
+    let mut s = String::new();
+
+    if sign == 0x4000 {
+        s.push('-');
+    }
+
+    if weight < 0 {
+        s.push('0');
+    } else {
+        // the first digit of the integral part is written without leading zeros
+        write!(s, "{}", digit(0))?;
+
+        for i in 1..=weight {
+            write!(s, "{:04}", digit(i))?;
+        }
+    }
+
+    if scale > 0 {
+        let mut fraction = String::with_capacity(scale + 4);
+
+        // `digits[weight + 1]` holds the first four decimal places
+        let mut i = weight + 1;
+        while fraction.len() < scale {
+            write!(fraction, "{:04}", digit(i))?;
+            i += 1;
+        }
+
+        fraction.truncate(scale);
+
+        s.push('.');
+        s.push_str(&fraction);
+    }
+
+    Ok(s)
+}
+
+// the Postgres epoch is 2000-01-01, 10957 days after the Unix epoch
+const POSTGRES_EPOCH_DAYS: i64 = 10957;
+
+const MICROS_PER_DAY: i64 = 86_400_000_000;
+
+fn format_timestamp(micros: i64, with_tz: bool) -> String {
+    match micros {
+        i64::MAX => return "infinity".into(),
+        i64::MIN => return "-infinity".into(),
+        _ => {}
+    }
+
+    let (year, month, day) = civil_from_days(micros.div_euclid(MICROS_PER_DAY));
+
+    let mut s = format_ymd(year, month, day);
+    s.push(' ');
+    s.push_str(&format_time(micros.rem_euclid(MICROS_PER_DAY)));
+
+    if with_tz {
+        s.push_str("+00");
+    }
+
+    if year <= 0 {
+        s.push_str(" BC");
+    }
+
+    s
+}
+
+fn format_date(days: i32) -> String {
+    match days {
+        i32::MAX => return "infinity".into(),
+        i32::MIN => return "-infinity".into(),
+        _ => {}
+    }
+
+    let (year, month, day) = civil_from_days(days.into());
+
+    let mut s = format_ymd(year, month, day);
+
+    if year <= 0 {
+        s.push_str(" BC");
+    }
+
+    s
+}
+
+fn format_ymd(year: i64, month: i64, day: i64) -> String {
+    // there is no year 0, 1 BC comes right before 1 AD
+    let year = if year <= 0 { 1 - year } else { year };
+
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+fn format_time(micros: i64) -> String {
+    let secs = micros / 1_000_000;
+    let fraction = micros % 1_000_000;
+
+    let mut s = format!("{:02}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60);
+
+    if fraction != 0 {
+        let fraction = format!("{:06}", fraction);
+        s.push('.');
+        s.push_str(fraction.trim_end_matches('0'));
+    }
+
+    s
+}
+
+// the proleptic Gregorian date of a number of days since the Postgres epoch
+// http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let z = days + POSTGRES_EPOCH_DAYS + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    (year, month, day)
+}
+
+fn format_uuid(buf: &[u8]) -> Result<String, BoxDynError> {
+    if buf.len() != 16 {
+        return Err(format!("invalid UUID length: {}", buf.len()).into());
+    }
+
+    let mut s = String::with_capacity(36);
+
+    for (i, byte) in buf.iter().enumerate() {
+        if matches!(i, 4 | 6 | 8 | 10) {
+            s.push('-');
+        }
+
+        write!(s, "{:02x}", byte)?;
+    }
+
+    Ok(s)
+}
+
+#[cfg(feature = "json")]
+impl From<PgDynamicValue> for serde_json::Value {
+    /// Convert the value to JSON.
+    ///
+    /// Numbers that JSON can't represent, as well as `NUMERIC` values (to preserve their
+    /// precision), are converted to strings. Binary data is converted to a string in the
+    /// hex format of `BYTEA`, e.g. `"\\xdeadbeef"`.
+    fn from(value: PgDynamicValue) -> Self {
+        use serde_json::Value;
+
+        fn float(value: f64) -> Value {
+            serde_json::Number::from_f64(value).map_or_else(
+                || {
+                    Value::String(
+                        if value.is_nan() {
+                            "NaN"
+                        } else if value > 0.0 {
+                            "Infinity"
+                        } else {
+                            "-Infinity"
+                        }
+                        .into(),
+                    )
+                },
+                Value::Number,
+            )
+        }
+
+        fn hex(bytes: &[u8]) -> Value {
+            Value::String(format!("\\x{}", hex::encode(bytes)))
+        }
+
+        match value {
+            PgDynamicValue::Null => Value::Null,
+            PgDynamicValue::Bool(value) => value.into(),
+            PgDynamicValue::Int2(value) => value.into(),
+            PgDynamicValue::Int4(value) => value.into(),
+            PgDynamicValue::Int8(value) => value.into(),
+            PgDynamicValue::Float4(value) => float(value.into()),
+            PgDynamicValue::Float8(value) => float(value),
+            PgDynamicValue::Bytea(bytes) => hex(&bytes),
+
+            PgDynamicValue::Numeric(s)
+            | PgDynamicValue::Text(s)
+            | PgDynamicValue::Timestamp(s)
+            | PgDynamicValue::TimestampTz(s)
+            | PgDynamicValue::Date(s)
+            | PgDynamicValue::Time(s)
+            | PgDynamicValue::Uuid(s) => Value::String(s),
+
+            PgDynamicValue::Json(json) => {
+                serde_json::from_str(&json).unwrap_or(Value::String(json))
+            }
+
+            PgDynamicValue::Array(elements) => elements.into_iter().map(Value::from).collect(),
+
+            PgDynamicValue::Other { format, bytes, .. } => match format {
+                PgValueFormat::Text => match String::from_utf8(bytes) {
+                    Ok(s) => Value::String(s),
+                    Err(e) => hex(e.as_bytes()),
+                },
+
+                PgValueFormat::Binary => hex(&bytes),
+            },
+        }
+    }
+}
+
+#[test]
+fn test_format_numeric() {
+    fn numeric(digits: &[i16], weight: i16, sign: u16, scale: i16) -> String {
+        let mut buf = Vec::new();
+        buf.extend(&(digits.len() as i16).to_be_bytes());
+        buf.extend(&weight.to_be_bytes());
+        buf.extend(&sign.to_be_bytes());
+        buf.extend(&scale.to_be_bytes());
+        for digit in digits {
+            buf.extend(&digit.to_be_bytes());
+        }
+
+        format_numeric(&buf).unwrap()
+    }
+
+    assert_eq!(numeric(&[], 0, 0, 0), "0");
+    assert_eq!(numeric(&[], 0, 0, 2), "0.00");
+    assert_eq!(numeric(&[12, 3400], 1, 0, 0), "123400");
+    assert_eq!(numeric(&[12, 3400], 0, 0x4000, 4), "-12.3400");
+    assert_eq!(numeric(&[1234, 5678], -1, 0, 8), "0.12345678");
+    assert_eq!(numeric(&[5], -1, 0, 5), "0.00050");
+    assert_eq!(numeric(&[5], -2, 0, 8), "0.00000005");
+    assert_eq!(numeric(&[1], 2, 0, 1), "100000000.0");
+    assert_eq!(numeric(&[], 0, 0xC000, 0), "NaN");
+    assert_eq!(numeric(&[], 0, 0xD000, 0), "Infinity");
+}
+
+#[test]
+fn test_format_date_and_time() {
+    assert_eq!(format_date(0), "2000-01-01");
+    assert_eq!(format_date(-1), "1999-12-31");
+    assert_eq!(format_date(8460), "2023-03-01");
+    assert_eq!(format_date(-730_120), "0001-12-31 BC");
+    assert_eq!(format_date(-730_485), "0001-01-01 BC");
+    assert_eq!(format_date(i32::MAX), "infinity");
+
+    assert_eq!(format_time(0), "00:00:00");
+    assert_eq!(format_time(47_100_500_000), "13:05:00.5");
+    assert_eq!(format_time(MICROS_PER_DAY - 1), "23:59:59.999999");
+
+    assert_eq!(format_timestamp(-1, false), "1999-12-31 23:59:59.999999");
+    assert_eq!(
+        format_timestamp(8460 * MICROS_PER_DAY + 1_000_000, true),
+        "2023-03-01 00:00:01+00"
+    );
+    assert_eq!(format_timestamp(i64::MIN, true), "-infinity");
+}
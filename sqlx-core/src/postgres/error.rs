@@ -4,7 +4,7 @@ use std::fmt::{self, Debug, Display, Formatter};
 use atoi::atoi;
 use smallvec::alloc::borrow::Cow;
 
-use crate::error::DatabaseError;
+use crate::error::{DatabaseError, ErrorKind};
 use crate::postgres::message::{Notice, PgSeverity};
 
 /// An error returned from the PostgreSQL database.
@@ -202,4 +202,31 @@ impl DatabaseError for PgDatabaseError {
     fn constraint(&self) -> Option<&str> {
         self.constraint()
     }
+
+    fn table(&self) -> Option<&str> {
+        self.table()
+    }
+
+    fn column(&self) -> Option<&str> {
+        self.column()
+    }
+
+    fn schema(&self) -> Option<&str> {
+        self.schema()
+    }
+
+    fn routine(&self) -> Option<&str> {
+        self.routine()
+    }
+
+    fn kind(&self) -> ErrorKind {
+        // https://www.postgresql.org/docs/current/errcodes-appendix.html
+        match self.code() {
+            "23505" => ErrorKind::UniqueViolation,
+            "23503" => ErrorKind::ForeignKeyViolation,
+            "23502" => ErrorKind::NotNullViolation,
+            "23514" => ErrorKind::CheckViolation,
+            _ => ErrorKind::Other,
+        }
+    }
 }
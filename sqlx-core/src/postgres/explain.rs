@@ -0,0 +1,921 @@
+use std::fmt::{self, Display, Formatter};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use futures_core::future::BoxFuture;
+use serde::{Deserialize, Deserializer};
+
+use crate::error::Error;
+use crate::executor::{Execute, Executor};
+use crate::postgres::message::TransactionStatus;
+use crate::postgres::types::Oid;
+use crate::postgres::{PgConnection, Postgres};
+use crate::query_scalar::{query_scalar, query_scalar_with};
+
+// https://www.postgresql.org/docs/current/sql-explain.html
+
+/// Options for [`PgConnection::explain`], which default to planning the query without running it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PgExplainOptions {
+    analyze: bool,
+    buffers: bool,
+    verbose: bool,
+}
+
+impl PgExplainOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets whether to run the query, to report the actual rows and time of each node.
+    ///
+    /// The query is run as usual, so its writes are only undone if it is explained in a
+    /// transaction which is rolled back. A query with parameters can only be analyzed with
+    /// arguments bound to it.
+    pub fn analyze(mut self, analyze: bool) -> Self {
+        self.analyze = analyze;
+        self
+    }
+
+    /// Sets whether to report the buffers each node used, which requires `analyze` before
+    /// PostgreSQL 13.
+    pub fn buffers(mut self, buffers: bool) -> Self {
+        self.buffers = buffers;
+        self
+    }
+
+    /// Sets whether to report the output columns of each node, and the schemas of relations.
+    pub fn verbose(mut self, verbose: bool) -> Self {
+        self.verbose = verbose;
+        self
+    }
+
+    fn sql(&self, statement: &str) -> String {
+        format!(
+            "EXPLAIN (FORMAT JSON, ANALYZE {}, BUFFERS {}, VERBOSE {}) {}",
+            self.analyze, self.buffers, self.verbose, statement
+        )
+    }
+}
+
+/// The plan chosen by PostgreSQL to run a statement, as reported by `EXPLAIN (FORMAT JSON)`.
+///
+/// Returned by [`PgConnection::explain`], or parsed from the output of `EXPLAIN` with
+/// [`from_json`](Self::from_json). The [`Display`] implementation prints it as a tree, like the
+/// text format of `EXPLAIN` does.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[non_exhaustive]
+pub struct PgExplainPlan {
+    #[serde(rename = "Plan")]
+    pub root: PgExplainNode,
+
+    /// The time it took to plan the query, since PostgreSQL 10 with `analyze` (and with
+    /// `summary`, which the output of `auto_explain` may have).
+    #[serde(rename = "Planning Time", default, deserialize_with = "millis")]
+    pub planning_time: Option<Duration>,
+
+    /// The time it took to run the query, with `analyze`.
+    #[serde(
+        rename = "Execution Time",
+        alias = "Total Runtime",
+        default,
+        deserialize_with = "millis"
+    )]
+    pub execution_time: Option<Duration>,
+}
+
+/// A node of a [`PgExplainPlan`], e.g. `Seq Scan` or `Hash Join`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[non_exhaustive]
+pub struct PgExplainNode {
+    #[serde(rename = "Node Type")]
+    pub node_type: String,
+
+    /// The table, view or other relation which the node scans or modifies.
+    #[serde(rename = "Relation Name")]
+    pub relation_name: Option<String>,
+
+    /// The schema of the relation, with `verbose`.
+    #[serde(rename = "Schema")]
+    pub schema: Option<String>,
+
+    #[serde(rename = "Alias")]
+    pub alias: Option<String>,
+
+    #[serde(rename = "Index Name")]
+    pub index_name: Option<String>,
+
+    /// The estimated cost to return the first row, in the units of the cost parameters of the
+    /// planner, e.g. `seq_page_cost`.
+    #[serde(rename = "Startup Cost")]
+    pub startup_cost: f64,
+
+    /// The estimated cost to return all the rows.
+    #[serde(rename = "Total Cost")]
+    pub total_cost: f64,
+
+    /// The estimated number of rows returned.
+    #[serde(rename = "Plan Rows")]
+    pub plan_rows: f64,
+
+    /// The estimated average size of the rows, in bytes.
+    #[serde(rename = "Plan Width")]
+    pub plan_width: u64,
+
+    /// The time it took to return the first row, averaged over the loops, with `analyze`.
+    #[serde(rename = "Actual Startup Time", default, deserialize_with = "millis")]
+    pub actual_startup_time: Option<Duration>,
+
+    /// The time it took to return all the rows, averaged over the loops, with `analyze`.
+    #[serde(rename = "Actual Total Time", default, deserialize_with = "millis")]
+    pub actual_total_time: Option<Duration>,
+
+    /// The number of rows returned, averaged over the loops, with `analyze`.
+    #[serde(rename = "Actual Rows")]
+    pub actual_rows: Option<f64>,
+
+    /// The number of times the node was run, with `analyze`.
+    #[serde(rename = "Actual Loops")]
+    pub actual_loops: Option<u64>,
+
+    /// The buffers the node used, with `buffers`.
+    #[serde(flatten)]
+    pub buffers: Option<PgExplainBuffers>,
+
+    /// The nodes whose rows this one consumes, e.g. the two sides of a join.
+    #[serde(rename = "Plans", default)]
+    pub children: Vec<PgExplainNode>,
+
+    /// The other properties of the node, which depend on its type and on the version of
+    /// PostgreSQL, e.g. `Filter` or `Join Type`.
+    #[serde(flatten)]
+    pub properties: serde_json::Map<String, serde_json::Value>,
+}
+
+/// The numbers of blocks a [`PgExplainNode`] and its children used, by kind.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[non_exhaustive]
+pub struct PgExplainBuffers {
+    /// The blocks of tables and indexes found in the shared buffer cache.
+    #[serde(rename = "Shared Hit Blocks")]
+    pub shared_hit: u64,
+
+    /// The blocks of tables and indexes read from the operating system.
+    #[serde(rename = "Shared Read Blocks")]
+    pub shared_read: u64,
+
+    #[serde(rename = "Shared Dirtied Blocks")]
+    pub shared_dirtied: u64,
+
+    #[serde(rename = "Shared Written Blocks")]
+    pub shared_written: u64,
+
+    /// The blocks of temporary tables found in the local buffers of the session.
+    #[serde(rename = "Local Hit Blocks")]
+    pub local_hit: u64,
+
+    #[serde(rename = "Local Read Blocks")]
+    pub local_read: u64,
+
+    /// The blocks of the temporary files used by sorts, hashes and the like.
+    #[serde(rename = "Temp Read Blocks")]
+    pub temp_read: u64,
+
+    #[serde(rename = "Temp Written Blocks")]
+    pub temp_written: u64,
+}
+
+// the times are reported in milliseconds
+fn millis<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Duration>, D::Error> {
+    let millis = Option::<f64>::deserialize(deserializer)?;
+
+    Ok(millis.map(|millis| Duration::from_secs_f64(millis.max(0.0) / 1000.0)))
+}
+
+impl PgExplainPlan {
+    /// Parses the output of `EXPLAIN (FORMAT JSON)`, which is an array with the plan of the
+    /// statement, or a single plan, as logged by `auto_explain`.
+    pub fn from_json(json: &str) -> Result<Self, Error> {
+        Self::from_value(serde_json::from_str(json).map_err(|e| Error::Decode(e.into()))?)
+    }
+
+    fn from_value(value: serde_json::Value) -> Result<Self, Error> {
+        let value = match value {
+            serde_json::Value::Array(plans) => plans.into_iter().next().ok_or_else(|| {
+                Error::Decode("expected the plan of a statement, got an empty array".into())
+            })?,
+            value => value,
+        };
+
+        serde_json::from_value(value).map_err(|e| Error::Decode(e.into()))
+    }
+
+    /// All the nodes of the plan, depth-first.
+    pub fn iter(&self) -> impl Iterator<Item = &PgExplainNode> {
+        let mut stack = vec![&self.root];
+
+        std::iter::from_fn(move || {
+            let node = stack.pop()?;
+            stack.extend(node.children.iter().rev());
+            Some(node)
+        })
+    }
+}
+
+impl Display for PgExplainPlan {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        fn write_node(f: &mut Formatter<'_>, node: &PgExplainNode, depth: usize) -> fmt::Result {
+            if depth > 0 {
+                write!(f, "{:width$}->  ", "", width = depth * 6 - 4)?;
+            }
+
+            // like the text format, which names a `ModifyTable` by its operation
+            match node.properties.get("Operation").and_then(|op| op.as_str()) {
+                Some(operation) if node.node_type == "ModifyTable" => write!(f, "{}", operation)?,
+                _ => write!(f, "{}", node.node_type)?,
+            }
+
+            if let Some(index) = &node.index_name {
+                write!(f, " using {}", index)?;
+            }
+
+            if let Some(relation) = &node.relation_name {
+                match &node.schema {
+                    Some(schema) => write!(f, " on {}.{}", schema, relation)?,
+                    None => write!(f, " on {}", relation)?,
+                }
+
+                if let Some(alias) = node.alias.as_ref().filter(|alias| *alias != relation) {
+                    write!(f, " {}", alias)?;
+                }
+            }
+
+            write!(
+                f,
+                "  (cost={:.2}..{:.2} rows={} width={})",
+                node.startup_cost, node.total_cost, node.plan_rows, node.plan_width
+            )?;
+
+            if let (Some(startup), Some(total), Some(rows), Some(loops)) = (
+                node.actual_startup_time,
+                node.actual_total_time,
+                node.actual_rows,
+                node.actual_loops,
+            ) {
+                write!(
+                    f,
+                    " (actual time={:.3}..{:.3} rows={} loops={})",
+                    startup.as_secs_f64() * 1000.0,
+                    total.as_secs_f64() * 1000.0,
+                    rows,
+                    loops
+                )?;
+            }
+
+            writeln!(f)?;
+
+            for child in &node.children {
+                write_node(f, child, depth + 1)?;
+            }
+
+            Ok(())
+        }
+
+        write_node(f, &self.root, 0)?;
+
+        if let Some(time) = self.planning_time {
+            writeln!(f, "Planning Time: {:.3} ms", time.as_secs_f64() * 1000.0)?;
+        }
+
+        if let Some(time) = self.execution_time {
+            writeln!(f, "Execution Time: {:.3} ms", time.as_secs_f64() * 1000.0)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl PgConnection {
+    /// Ask PostgreSQL how it would run the statement `query`, or with
+    /// [`analyze`](PgExplainOptions::analyze), how it ran it.
+    ///
+    /// The arguments bound to `query` are bound to the `EXPLAIN` statement, so that the plan is
+    /// the one for their values. A query with parameters but without arguments is prepared
+    /// instead, and its generic plan is explained, which is the plan PostgreSQL may reuse for
+    /// any values once it ran the prepared statement a few times.
+    ///
+    /// ```rust,no_run
+    /// # use sqlx_core::error::Error;
+    /// # use sqlx_core::postgres::{PgConnection, PgExplainOptions};
+    /// # async fn example(conn: &mut PgConnection) -> Result<(), Error> {
+    /// let plan = conn
+    ///     .explain(
+    ///         sqlx::query("SELECT * FROM users WHERE email = $1").bind("alice@example.com"),
+    ///         PgExplainOptions::new(),
+    ///     )
+    ///     .await?;
+    ///
+    /// assert!(plan.iter().all(|node| node.node_type != "Seq Scan"), "{}", plan);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn explain<'q, E>(
+        &mut self,
+        mut query: E,
+        options: PgExplainOptions,
+    ) -> Result<PgExplainPlan, Error>
+    where
+        E: Execute<'q, Postgres>,
+    {
+        if let Some(error) = query.take_error() {
+            return Err(error);
+        }
+
+        let sql = query.sql();
+
+        let plan = match query.take_arguments() {
+            Some(arguments) if !arguments.types.is_empty() => {
+                query_scalar_with(&options.sql(sql), arguments)
+                    .persistent(false)
+                    .fetch_one(&mut *self)
+                    .await?
+            }
+            _ => self.explain_prepared(sql, options).await?,
+        };
+
+        PgExplainPlan::from_value(plan)
+    }
+
+    // explains the statement `sql` once prepared, with `NULL` for each of its parameters
+    async fn explain_prepared(
+        &mut self,
+        sql: &str,
+        options: PgExplainOptions,
+    ) -> Result<serde_json::Value, Error> {
+        // unlike `PREPARE`, this fails if `sql` has several statements, rather than running those
+        // after the first
+        let (id, parameters) = self.prepare_uncached(sql).await?;
+
+        let plan = self.explain_generic_plan(id, parameters, options).await;
+
+        // the statement outlives the transaction it was prepared in, even if that is aborted
+        self.close_uncached(id).await?;
+
+        plan
+    }
+
+    async fn explain_generic_plan(
+        &mut self,
+        id: Oid,
+        parameters: usize,
+        options: PgExplainOptions,
+    ) -> Result<serde_json::Value, Error> {
+        if parameters == 0 {
+            return query_scalar(&options.sql(&format!("EXECUTE sqlx_s_{}", id.0)))
+                .persistent(false)
+                .fetch_one(&mut *self)
+                .await;
+        }
+
+        if options.analyze {
+            return Err(Error::Configuration(
+                format!(
+                    "can't analyze a query with {} parameters but no arguments bound to them",
+                    parameters
+                )
+                .into(),
+            ));
+        }
+
+        let explain = options.sql(&format!(
+            "EXECUTE sqlx_s_{}({})",
+            id.0,
+            vec!["NULL"; parameters].join(", ")
+        ));
+
+        // `plan_cache_mode` is supported since PostgreSQL 12; before, the first executions of a
+        // prepared statement are planned for the `NULL`s
+        if !matches!(self.server_version_num(), Some(version) if version >= 120000) {
+            return query_scalar(&explain)
+                .persistent(false)
+                .fetch_one(&mut *self)
+                .await;
+        }
+
+        let mode: String = query_scalar(
+            "SELECT current_setting('plan_cache_mode'), \
+             set_config('plan_cache_mode', 'force_generic_plan', false)",
+        )
+        .fetch_one(&mut *self)
+        .await?;
+
+        let plan = query_scalar(&explain)
+            .persistent(false)
+            .fetch_one(&mut *self)
+            .await;
+
+        query_scalar::<_, String>("SELECT set_config('plan_cache_mode', $1, false)")
+            .bind(mode)
+            .fetch_one(&mut *self)
+            .await?;
+
+        plan
+    }
+
+    /// Logs the plan of `sql` after it took longer than the duration of slow statements, see
+    /// [`PgConnectOptions::explain_slow_statements`](crate::postgres::PgConnectOptions::explain_slow_statements).
+    pub(crate) fn explain_slow_statement<'c>(&'c mut self, sql: &'c str) -> BoxFuture<'c, ()> {
+        Box::pin(async move {
+            let level = match self.log_settings.slow_statements_level.to_level() {
+                Some(level) if log::log_enabled!(target: "sqlx::explain", level) => level,
+                _ => return,
+            };
+
+            // explaining a statement which can't be explained, e.g. `CREATE TABLE`, fails, and
+            // would abort the transaction it is run in without a savepoint
+            let explainable = matches!(
+                sql.split_whitespace().next(),
+                Some(keyword) if EXPLAINABLE.iter().any(|e| keyword.eq_ignore_ascii_case(e))
+            );
+
+            let auto_explain = match &self.auto_explain {
+                Some(auto_explain) if explainable && !self.explaining => auto_explain.clone(),
+                _ => return,
+            };
+
+            let in_transaction = match self.transaction_status {
+                TransactionStatus::Idle => false,
+                TransactionStatus::Transaction => true,
+                TransactionStatus::Error => return,
+            };
+
+            if !auto_explain.try_start() {
+                return;
+            }
+
+            self.explaining = true;
+
+            let plan = if in_transaction {
+                self.explain_in_savepoint(sql).await
+            } else {
+                self.explain_prepared(sql, PgExplainOptions::new()).await
+            };
+
+            self.explaining = false;
+
+            let mut summary = crate::logger::parse_query_summary(sql);
+
+            if summary != sql {
+                summary.push_str(" …");
+            }
+
+            match plan.and_then(PgExplainPlan::from_value) {
+                Ok(plan) => log::log!(
+                    target: "sqlx::explain",
+                    level,
+                    "{}; plan of a slow statement:\n\n{}",
+                    summary,
+                    plan.to_string().trim_end()
+                ),
+                Err(error) => log::debug!(
+                    target: "sqlx::explain",
+                    "{}; failed to explain a slow statement: {}",
+                    summary,
+                    error
+                ),
+            }
+        })
+    }
+
+    async fn explain_in_savepoint(&mut self, sql: &str) -> Result<serde_json::Value, Error> {
+        self.execute("SAVEPOINT _sqlx_explain").await?;
+
+        let plan = self.explain_prepared(sql, PgExplainOptions::new()).await;
+
+        if plan.is_err() {
+            self.execute("ROLLBACK TO SAVEPOINT _sqlx_explain").await?;
+        }
+
+        self.execute("RELEASE SAVEPOINT _sqlx_explain").await?;
+
+        plan
+    }
+}
+
+// the statements which `EXPLAIN` accepts
+const EXPLAINABLE: &[&str] = &[
+    "SELECT", "INSERT", "UPDATE", "DELETE", "MERGE", "VALUES", "TABLE", "WITH",
+];
+
+/// Explains slow statements at most once per interval, for the connections opened with the same
+/// options.
+#[derive(Debug)]
+pub(crate) struct PgAutoExplain {
+    min_interval: Duration,
+    last: Mutex<Option<Instant>>,
+}
+
+impl PgAutoExplain {
+    pub(crate) fn new(min_interval: Duration) -> Self {
+        Self {
+            min_interval,
+            last: Mutex::new(None),
+        }
+    }
+
+    fn try_start(&self) -> bool {
+        let mut last = self.last.lock().unwrap_or_else(|e| e.into_inner());
+
+        if matches!(*last, Some(last) if last.elapsed() < self.min_interval) {
+            return false;
+        }
+
+        *last = Some(Instant::now());
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // PostgreSQL 15, `EXPLAIN (FORMAT JSON, ANALYZE, BUFFERS)`
+    const PG15_ANALYZE_BUFFERS: &str = r#"[
+  {
+    "Plan": {
+      "Node Type": "Hash Join",
+      "Parallel Aware": false,
+      "Async Capable": false,
+      "Join Type": "Inner",
+      "Startup Cost": 2.26,
+      "Total Cost": 4.54,
+      "Plan Rows": 1,
+      "Plan Width": 14,
+      "Actual Startup Time": 0.029,
+      "Actual Total Time": 0.045,
+      "Actual Rows": 2,
+      "Actual Loops": 1,
+      "Inner Unique": true,
+      "Hash Cond": "(b.id = a.id)",
+      "Shared Hit Blocks": 0,
+      "Shared Read Blocks": 0,
+      "Shared Dirtied Blocks": 0,
+      "Shared Written Blocks": 0,
+      "Local Hit Blocks": 2,
+      "Local Read Blocks": 0,
+      "Local Dirtied Blocks": 0,
+      "Local Written Blocks": 0,
+      "Temp Read Blocks": 0,
+      "Temp Written Blocks": 0,
+      "Plans": [
+        {
+          "Node Type": "Seq Scan",
+          "Parent Relationship": "Outer",
+          "Parallel Aware": false,
+          "Async Capable": false,
+          "Relation Name": "t",
+          "Alias": "b",
+          "Startup Cost": 0.00,
+          "Total Cost": 2.00,
+          "Plan Rows": 100,
+          "Plan Width": 7,
+          "Actual Startup Time": 0.003,
+          "Actual Total Time": 0.010,
+          "Actual Rows": 100,
+          "Actual Loops": 1,
+          "Shared Hit Blocks": 0,
+          "Shared Read Blocks": 0,
+          "Shared Dirtied Blocks": 0,
+          "Shared Written Blocks": 0,
+          "Local Hit Blocks": 1,
+          "Local Read Blocks": 0,
+          "Local Dirtied Blocks": 0,
+          "Local Written Blocks": 0,
+          "Temp Read Blocks": 0,
+          "Temp Written Blocks": 0
+        },
+        {
+          "Node Type": "Hash",
+          "Parent Relationship": "Inner",
+          "Parallel Aware": false,
+          "Async Capable": false,
+          "Startup Cost": 2.25,
+          "Total Cost": 2.25,
+          "Plan Rows": 1,
+          "Plan Width": 7,
+          "Actual Startup Time": 0.015,
+          "Actual Total Time": 0.015,
+          "Actual Rows": 2,
+          "Actual Loops": 1,
+          "Hash Buckets": 1024,
+          "Original Hash Buckets": 1024,
+          "Hash Batches": 1,
+          "Original Hash Batches": 1,
+          "Peak Memory Usage": 9,
+          "Shared Hit Blocks": 0,
+          "Shared Read Blocks": 0,
+          "Shared Dirtied Blocks": 0,
+          "Shared Written Blocks": 0,
+          "Local Hit Blocks": 1,
+          "Local Read Blocks": 0,
+          "Local Dirtied Blocks": 0,
+          "Local Written Blocks": 0,
+          "Temp Read Blocks": 0,
+          "Temp Written Blocks": 0,
+          "Plans": [
+            {
+              "Node Type": "Seq Scan",
+              "Parent Relationship": "Outer",
+              "Parallel Aware": false,
+              "Async Capable": false,
+              "Relation Name": "t",
+              "Alias": "a",
+              "Startup Cost": 0.00,
+              "Total Cost": 2.25,
+              "Plan Rows": 1,
+              "Plan Width": 7,
+              "Actual Startup Time": 0.005,
+              "Actual Total Time": 0.009,
+              "Actual Rows": 2,
+              "Actual Loops": 1,
+              "Filter": "(id < 3)",
+              "Rows Removed by Filter": 98,
+              "Shared Hit Blocks": 0,
+              "Shared Read Blocks": 0,
+              "Shared Dirtied Blocks": 0,
+              "Shared Written Blocks": 0,
+              "Local Hit Blocks": 1,
+              "Local Read Blocks": 0,
+              "Local Dirtied Blocks": 0,
+              "Local Written Blocks": 0,
+              "Temp Read Blocks": 0,
+              "Temp Written Blocks": 0
+            }
+          ]
+        }
+      ]
+    },
+    "Planning": {
+      "Shared Hit Blocks": 55,
+      "Shared Read Blocks": 0,
+      "Shared Dirtied Blocks": 0,
+      "Shared Written Blocks": 0,
+      "Local Hit Blocks": 9,
+      "Local Read Blocks": 0,
+      "Local Dirtied Blocks": 0,
+      "Local Written Blocks": 0,
+      "Temp Read Blocks": 0,
+      "Temp Written Blocks": 0
+    },
+    "Planning Time": 0.237,
+    "Triggers": [
+    ],
+    "Execution Time": 0.059
+  }
+]"#;
+
+    // PostgreSQL 9.6, `EXPLAIN (FORMAT JSON, VERBOSE)`
+    const PG96_VERBOSE: &str = r#"[
+  {
+    "Plan": {
+      "Node Type": "Index Scan",
+      "Parallel Aware": false,
+      "Scan Direction": "Forward",
+      "Index Name": "users_pkey",
+      "Relation Name": "users",
+      "Schema": "public",
+      "Alias": "users",
+      "Startup Cost": 0.15,
+      "Total Cost": 8.17,
+      "Plan Rows": 1,
+      "Plan Width": 36,
+      "Output": ["id", "name"],
+      "Index Cond": "(users.id = 1)"
+    }
+  }
+]"#;
+
+    // PostgreSQL 9.3, `EXPLAIN (FORMAT JSON, ANALYZE)`, which reported the total runtime
+    const PG93_ANALYZE: &str = r#"[
+  {
+    "Plan": {
+      "Node Type": "Result",
+      "Startup Cost": 0.00,
+      "Total Cost": 0.01,
+      "Plan Rows": 1,
+      "Plan Width": 0,
+      "Actual Startup Time": 0.001,
+      "Actual Total Time": 0.001,
+      "Actual Rows": 1,
+      "Actual Loops": 1
+    },
+    "Triggers": [
+    ],
+    "Total Runtime": 0.014
+  }
+]"#;
+
+    // PostgreSQL 18, `EXPLAIN (FORMAT JSON, ANALYZE)`, which reports fractional rows
+    const PG18_ANALYZE: &str = r#"[
+  {
+    "Plan": {
+      "Node Type": "Nested Loop",
+      "Parallel Aware": false,
+      "Async Capable": false,
+      "Join Type": "Inner",
+      "Disabled": false,
+      "Startup Cost": 0.29,
+      "Total Cost": 16.62,
+      "Plan Rows": 1,
+      "Plan Width": 8,
+      "Actual Startup Time": 0.020,
+      "Actual Total Time": 0.031,
+      "Actual Rows": 0.50,
+      "Actual Loops": 2,
+      "Inner Unique": true,
+      "Shared Hit Blocks": 6,
+      "Shared Read Blocks": 0,
+      "Shared Dirtied Blocks": 0,
+      "Shared Written Blocks": 0,
+      "Local Hit Blocks": 0,
+      "Local Read Blocks": 0,
+      "Local Dirtied Blocks": 0,
+      "Local Written Blocks": 0,
+      "Temp Read Blocks": 0,
+      "Temp Written Blocks": 0,
+      "Plans": [
+        {
+          "Node Type": "Seq Scan",
+          "Parent Relationship": "Outer",
+          "Parallel Aware": false,
+          "Async Capable": false,
+          "Relation Name": "orders",
+          "Alias": "o",
+          "Disabled": false,
+          "Startup Cost": 0.00,
+          "Total Cost": 8.30,
+          "Plan Rows": 1,
+          "Plan Width": 8,
+          "Actual Startup Time": 0.010,
+          "Actual Total Time": 0.012,
+          "Actual Rows": 1.00,
+          "Actual Loops": 1,
+          "Filter": "(o.total > 100)",
+          "Rows Removed by Filter": 23
+        },
+        {
+          "Node Type": "Index Only Scan",
+          "Parent Relationship": "Inner",
+          "Parallel Aware": false,
+          "Async Capable": false,
+          "Scan Direction": "Forward",
+          "Index Name": "users_pkey",
+          "Relation Name": "users",
+          "Alias": "u",
+          "Disabled": false,
+          "Startup Cost": 0.29,
+          "Total Cost": 8.30,
+          "Plan Rows": 1,
+          "Plan Width": 4,
+          "Actual Startup Time": 0.007,
+          "Actual Total Time": 0.007,
+          "Actual Rows": 0.00,
+          "Actual Loops": 1,
+          "Index Cond": "(u.id = o.user_id)",
+          "Heap Fetches": 0,
+          "Index Searches": 1
+        }
+      ]
+    },
+    "Planning Time": 0.120,
+    "Triggers": [
+    ],
+    "Execution Time": 0.052
+  }
+]"#;
+
+    fn millis(millis: f64) -> Option<Duration> {
+        Some(Duration::from_secs_f64(millis / 1000.0))
+    }
+
+    #[test]
+    fn it_parses_an_analyzed_plan_with_buffers() {
+        let plan = PgExplainPlan::from_json(PG15_ANALYZE_BUFFERS).unwrap();
+
+        assert_eq!(plan.planning_time, millis(0.237));
+        assert_eq!(plan.execution_time, millis(0.059));
+
+        let root = &plan.root;
+        assert_eq!(root.node_type, "Hash Join");
+        assert_eq!((root.startup_cost, root.total_cost), (2.26, 4.54));
+        assert_eq!((root.plan_rows, root.plan_width), (1.0, 14));
+        assert_eq!(root.actual_startup_time, millis(0.029));
+        assert_eq!(root.actual_total_time, millis(0.045));
+        assert_eq!((root.actual_rows, root.actual_loops), (Some(2.0), Some(1)));
+        assert_eq!(root.buffers.unwrap().local_hit, 2);
+        assert_eq!(root.properties["Hash Cond"], "(b.id = a.id)");
+        assert!(!root.properties.contains_key("Plans"));
+        assert!(!root.properties.contains_key("Shared Hit Blocks"));
+
+        let nodes: Vec<_> = plan
+            .iter()
+            .map(|node| (&*node.node_type, node.alias.as_deref()))
+            .collect();
+        assert_eq!(
+            nodes,
+            [
+                ("Hash Join", None),
+                ("Seq Scan", Some("b")),
+                ("Hash", None),
+                ("Seq Scan", Some("a"))
+            ]
+        );
+
+        let scan = &plan.root.children[1].children[0];
+        assert_eq!(scan.relation_name.as_deref(), Some("t"));
+        assert_eq!(scan.properties["Rows Removed by Filter"], 98);
+    }
+
+    #[test]
+    fn it_parses_plans_of_several_versions() {
+        let plan = PgExplainPlan::from_json(PG96_VERBOSE).unwrap();
+        let root = &plan.root;
+        assert_eq!(root.node_type, "Index Scan");
+        assert_eq!(root.index_name.as_deref(), Some("users_pkey"));
+        assert_eq!(root.schema.as_deref(), Some("public"));
+        assert_eq!(root.actual_rows, None);
+        assert_eq!(root.buffers, None);
+        assert_eq!((plan.planning_time, plan.execution_time), (None, None));
+
+        let plan = PgExplainPlan::from_json(PG93_ANALYZE).unwrap();
+        assert_eq!(plan.root.node_type, "Result");
+        assert_eq!(plan.execution_time, millis(0.014));
+
+        let plan = PgExplainPlan::from_json(PG18_ANALYZE).unwrap();
+        assert_eq!(plan.root.actual_rows, Some(0.5));
+        assert_eq!(plan.root.actual_loops, Some(2));
+        assert_eq!(plan.root.buffers.unwrap().shared_hit, 6);
+        assert_eq!(plan.root.children[1].buffers, None);
+        assert_eq!(plan.root.children[1].properties["Index Searches"], 1);
+
+        // a single plan, as logged by `auto_explain`
+        let plan = PgExplainPlan::from_json(
+            r#"{"Query Text": "SELECT 1", "Plan": {"Node Type": "Result", "Startup Cost": 0.0,
+                "Total Cost": 0.01, "Plan Rows": 1, "Plan Width": 4}}"#,
+        )
+        .unwrap();
+        assert_eq!(plan.root.node_type, "Result");
+
+        assert!(PgExplainPlan::from_json("[]").is_err());
+        assert!(PgExplainPlan::from_json(r#"[{"Plan": {}}]"#).is_err());
+    }
+
+    #[test]
+    fn it_displays_a_plan_as_a_tree() {
+        let plan = PgExplainPlan::from_json(PG15_ANALYZE_BUFFERS).unwrap();
+
+        assert_eq!(
+            plan.to_string(),
+            "\
+Hash Join  (cost=2.26..4.54 rows=1 width=14) (actual time=0.029..0.045 rows=2 loops=1)
+  ->  Seq Scan on t b  (cost=0.00..2.00 rows=100 width=7) (actual time=0.003..0.010 rows=100 loops=1)
+  ->  Hash  (cost=2.25..2.25 rows=1 width=7) (actual time=0.015..0.015 rows=2 loops=1)
+        ->  Seq Scan on t a  (cost=0.00..2.25 rows=1 width=7) (actual time=0.005..0.009 rows=2 loops=1)
+Planning Time: 0.237 ms
+Execution Time: 0.059 ms
+"
+        );
+
+        let plan = PgExplainPlan::from_json(PG96_VERBOSE).unwrap();
+        assert_eq!(
+            plan.to_string(),
+            "Index Scan using users_pkey on public.users  (cost=0.15..8.17 rows=1 width=36)\n"
+        );
+    }
+
+    #[test]
+    fn it_builds_the_explain_statement() {
+        assert_eq!(
+            PgExplainOptions::new().sql("SELECT 1"),
+            "EXPLAIN (FORMAT JSON, ANALYZE false, BUFFERS false, VERBOSE false) SELECT 1"
+        );
+        assert_eq!(
+            PgExplainOptions::new()
+                .analyze(true)
+                .buffers(true)
+                .sql("SELECT 1"),
+            "EXPLAIN (FORMAT JSON, ANALYZE true, BUFFERS true, VERBOSE false) SELECT 1"
+        );
+    }
+
+    #[test]
+    fn it_rate_limits_explaining_slow_statements() {
+        let auto_explain = PgAutoExplain::new(Duration::from_secs(60));
+        assert!(auto_explain.try_start());
+        assert!(!auto_explain.try_start());
+
+        let auto_explain = PgAutoExplain::new(Duration::ZERO);
+        assert!(auto_explain.try_start());
+        assert!(auto_explain.try_start());
+    }
+}
@@ -5,7 +5,7 @@ pub trait PgBufMutExt {
     where
         F: FnOnce(&mut Vec<u8>);
 
-    fn put_statement_name(&mut self, id: Oid);
+    fn put_statement_name(&mut self, id: Option<Oid>);
 
     fn put_portal_name(&mut self, id: Option<Oid>);
 }
@@ -31,11 +31,13 @@ impl PgBufMutExt for Vec<u8> {
 
     // writes a statement name by ID
     #[inline]
-    fn put_statement_name(&mut self, id: Oid) {
+    fn put_statement_name(&mut self, id: Option<Oid>) {
         // N.B. if you change this don't forget to update it in ../describe.rs
-        self.extend(b"sqlx_s_");
+        if let Some(id) = id {
+            self.extend(b"sqlx_s_");
 
-        self.extend(itoa::Buffer::new().format(id.0).as_bytes());
+            self.extend(itoa::Buffer::new().format(id.0).as_bytes());
+        }
 
         self.push(0);
     }
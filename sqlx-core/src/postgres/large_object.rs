@@ -0,0 +1,271 @@
+use crate::error::{Error, Result};
+use crate::postgres::types::Oid;
+use crate::postgres::PgConnection;
+use crate::query::query;
+use crate::query_scalar::query_scalar;
+use sqlx_rt::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use std::io::SeekFrom;
+use std::ops::DerefMut;
+
+// the same buffer size `libpq` uses for `lo_import` and `lo_export`
+const BUFFER_SIZE: usize = 8192;
+
+// https://github.com/postgres/postgres/blob/master/src/include/libpq/libpq-fs.h
+const INV_WRITE: i32 = 0x0002_0000;
+const INV_READ: i32 = 0x0004_0000;
+
+impl PgConnection {
+    /// Create a new, empty [large object] and return its OID.
+    ///
+    /// Large objects can only be used inside a transaction; an error is returned otherwise.
+    ///
+    /// [large object]: https://www.postgresql.org/docs/current/largeobjects.html
+    pub async fn create_large_object(&mut self) -> Result<Oid> {
+        self.ensure_large_object_transaction().await?;
+
+        query_scalar("SELECT pg_catalog.lo_create(0)")
+            .fetch_one(self)
+            .await
+    }
+
+    /// Open an existing [large object] for reading and/or writing, as specified by `mode`.
+    ///
+    /// Large objects can only be used inside a transaction; an error is returned otherwise.
+    /// The returned handle is closed automatically when the transaction ends, or it can be
+    /// closed early with [PgLargeObject::close].
+    ///
+    /// [large object]: https://www.postgresql.org/docs/current/largeobjects.html
+    pub async fn open_large_object(
+        &mut self,
+        oid: Oid,
+        mode: PgLargeObjectMode,
+    ) -> Result<PgLargeObject<&mut Self>> {
+        PgLargeObject::open(self, oid, mode).await
+    }
+
+    /// Delete a [large object] from the database.
+    ///
+    /// Large objects can only be used inside a transaction; an error is returned otherwise.
+    ///
+    /// [large object]: https://www.postgresql.org/docs/current/largeobjects.html
+    pub async fn unlink_large_object(&mut self, oid: Oid) -> Result<()> {
+        self.ensure_large_object_transaction().await?;
+
+        query("SELECT pg_catalog.lo_unlink($1)")
+            .bind(oid)
+            .execute(self)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn ensure_large_object_transaction(&mut self) -> Result<()> {
+        // the transaction status is only accurate once all pending queries have completed
+        self.wait_until_ready().await?;
+
+        if !self.in_transaction() {
+            return Err(Error::Configuration(
+                "large objects can only be accessed inside a transaction".into(),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+/// The access mode of an open [PgLargeObject].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum PgLargeObjectMode {
+    /// The object is opened for reading only.
+    ///
+    /// Reads observe the state of the object as of the start of the transaction's snapshot.
+    Read,
+
+    /// The object is opened for writing only.
+    Write,
+
+    /// The object is opened for both reading and writing.
+    ///
+    /// Reads observe the latest committed state of the object, including writes from this
+    /// transaction.
+    ReadWrite,
+}
+
+impl PgLargeObjectMode {
+    fn flags(self) -> i32 {
+        match self {
+            PgLargeObjectMode::Read => INV_READ,
+            PgLargeObjectMode::Write => INV_WRITE,
+            PgLargeObjectMode::ReadWrite => INV_READ | INV_WRITE,
+        }
+    }
+}
+
+/// An open descriptor for a Postgres [large object].
+///
+/// Created by [PgConnection::open_large_object].
+///
+/// The descriptor is only valid for the duration of the transaction that opened it.
+/// Offsets are 64-bit, so objects larger than 2 GiB are supported (Postgres 9.3+).
+///
+/// [large object]: https://www.postgresql.org/docs/current/largeobjects.html
+#[derive(Debug)]
+pub struct PgLargeObject<C: DerefMut<Target = PgConnection>> {
+    conn: C,
+    oid: Oid,
+    fd: i32,
+}
+
+impl<C: DerefMut<Target = PgConnection>> PgLargeObject<C> {
+    async fn open(mut conn: C, oid: Oid, mode: PgLargeObjectMode) -> Result<Self> {
+        conn.ensure_large_object_transaction().await?;
+
+        let fd = query_scalar("SELECT pg_catalog.lo_open($1, $2)")
+            .bind(oid)
+            .bind(mode.flags())
+            .fetch_one(&mut *conn)
+            .await?;
+
+        Ok(PgLargeObject { conn, oid, fd })
+    }
+
+    /// Returns the OID of this large object.
+    pub fn oid(&self) -> Oid {
+        self.oid
+    }
+
+    /// Read up to `buf.len()` bytes from the current position into `buf`,
+    /// returning the number of bytes read.
+    ///
+    /// A return value of `0` indicates the end of the object has been reached.
+    pub async fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        let len = i32::try_from(buf.len()).unwrap_or(i32::MAX);
+
+        let data: Vec<u8> = query_scalar("SELECT pg_catalog.loread($1, $2)")
+            .bind(self.fd)
+            .bind(len)
+            .fetch_one(&mut *self.conn)
+            .await?;
+
+        buf[..data.len()].copy_from_slice(&data);
+
+        Ok(data.len())
+    }
+
+    /// Write all of `data` at the current position.
+    pub async fn write(&mut self, data: &[u8]) -> Result<()> {
+        for chunk in data.chunks(BUFFER_SIZE) {
+            query("SELECT pg_catalog.lowrite($1, $2)")
+                .bind(self.fd)
+                .bind(chunk)
+                .execute(&mut *self.conn)
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Move the current position, returning the new position from the start of the object.
+    pub async fn seek(&mut self, pos: SeekFrom) -> Result<u64> {
+        let (offset, whence) = match pos {
+            SeekFrom::Start(offset) => (
+                i64::try_from(offset).map_err(|_| {
+                    Error::Configuration(
+                        format!("large object offset out of range: {}", offset).into(),
+                    )
+                })?,
+                0,
+            ),
+            SeekFrom::Current(offset) => (offset, 1),
+            SeekFrom::End(offset) => (offset, 2),
+        };
+
+        let pos: i64 = query_scalar("SELECT pg_catalog.lo_lseek64($1, $2, $3)")
+            .bind(self.fd)
+            .bind(offset)
+            .bind(whence)
+            .fetch_one(&mut *self.conn)
+            .await?;
+
+        Ok(pos as u64)
+    }
+
+    /// Returns the current position from the start of the object.
+    pub async fn tell(&mut self) -> Result<u64> {
+        let pos: i64 = query_scalar("SELECT pg_catalog.lo_tell64($1)")
+            .bind(self.fd)
+            .fetch_one(&mut *self.conn)
+            .await?;
+
+        Ok(pos as u64)
+    }
+
+    /// Truncate (or extend with zeroes) the object to `len` bytes.
+    ///
+    /// The current position is not changed.
+    pub async fn truncate(&mut self, len: u64) -> Result<()> {
+        let len = i64::try_from(len).map_err(|_| {
+            Error::Configuration(format!("large object length out of range: {}", len).into())
+        })?;
+
+        query("SELECT pg_catalog.lo_truncate64($1, $2)")
+            .bind(self.fd)
+            .bind(len)
+            .execute(&mut *self.conn)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Copy the object from the current position to the end into `dest`,
+    /// returning the number of bytes copied.
+    pub async fn read_to(&mut self, mut dest: impl AsyncWrite + Unpin) -> Result<u64> {
+        let mut buf = vec![0; BUFFER_SIZE];
+        let mut total = 0;
+
+        loop {
+            let read = self.read(&mut buf).await?;
+
+            if read == 0 {
+                break;
+            }
+
+            dest.write_all(&buf[..read]).await?;
+            total += read as u64;
+        }
+
+        dest.flush().await?;
+
+        Ok(total)
+    }
+
+    /// Copy `source` to the end into the object at the current position,
+    /// returning the number of bytes copied.
+    pub async fn write_from(&mut self, mut source: impl AsyncRead + Unpin) -> Result<u64> {
+        let mut buf = vec![0; BUFFER_SIZE];
+        let mut total = 0;
+
+        loop {
+            let read = source.read(&mut buf).await?;
+
+            if read == 0 {
+                break;
+            }
+
+            self.write(&buf[..read]).await?;
+            total += read as u64;
+        }
+
+        Ok(total)
+    }
+
+    /// Close the descriptor, returning the connection.
+    pub async fn close(mut self) -> Result<C> {
+        query("SELECT pg_catalog.lo_close($1)")
+            .bind(self.fd)
+            .execute(&mut *self.conn)
+            .await?;
+
+        Ok(self.conn)
+    }
+}
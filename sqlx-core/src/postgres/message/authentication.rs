@@ -56,6 +56,29 @@ pub enum Authentication {
     /// The server will next send [Authentication::Ok] to
     /// indicate successful authentication.
     SaslFinal(AuthenticationSaslFinal),
+
+    /// The frontend must now initiate a GSSAPI negotiation.
+    ///
+    /// The frontend will send a [GssResponse] with the first part of the
+    /// GSSAPI data stream in response to this. If further messages are needed,
+    /// the server will respond with [Authentication::GssContinue].
+    Gss,
+
+    /// The frontend must now initiate an SSPI negotiation.
+    ///
+    /// This is handled the same way as [Authentication::Gss], as SSPI is
+    /// interoperable with GSSAPI.
+    Sspi,
+
+    /// This message contains the response data from the previous step of GSSAPI
+    /// or SSPI negotiation.
+    ///
+    /// If the GSSAPI context is not yet complete, the frontend must respond with
+    /// another [GssResponse].
+    ///
+    /// It only follows a [GssResponse], which is never sent without the `postgres-gssapi` feature.
+    #[cfg(feature = "postgres-gssapi")]
+    GssContinue(Bytes),
 }
 
 impl Decode<'_> for Authentication {
@@ -72,6 +95,11 @@ impl Decode<'_> for Authentication {
                 Authentication::Md5Password(AuthenticationMd5Password { salt })
             }
 
+            7 => Authentication::Gss,
+            #[cfg(feature = "postgres-gssapi")]
+            8 => Authentication::GssContinue(buf),
+            9 => Authentication::Sspi,
+
             10 => Authentication::Sasl(AuthenticationSasl(buf)),
             11 => Authentication::SaslContinue(AuthenticationSaslContinue::decode(buf)?),
             12 => Authentication::SaslFinal(AuthenticationSaslFinal::decode(buf)?),
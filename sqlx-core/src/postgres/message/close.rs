@@ -21,7 +21,7 @@ impl Encode<'_> for Close {
         buf.put_length_prefixed(|buf| match self {
             Close::Statement(id) => {
                 buf.push(CLOSE_STATEMENT);
-                buf.put_statement_name(*id);
+                buf.put_statement_name(Some(*id));
             }
 
             Close::Portal(id) => {
@@ -0,0 +1,45 @@
+use crate::io::Encode;
+use crate::postgres::io::PgBufMutExt;
+
+/// Sent instead of a startup message to ask the server to encrypt the connection with GSSAPI.
+pub struct GssEncRequest;
+
+impl Encode<'_> for GssEncRequest {
+    #[inline]
+    fn encode_with(&self, buf: &mut Vec<u8>, _: ()) {
+        buf.extend(&8_u32.to_be_bytes());
+        buf.extend(&(((1234 << 16) | 5680) as u32).to_be_bytes());
+    }
+}
+
+/// A GSSAPI or SSPI token sent during authentication.
+pub struct GssResponse<'a>(pub &'a [u8]);
+
+impl Encode<'_> for GssResponse<'_> {
+    fn encode_with(&self, buf: &mut Vec<u8>, _: ()) {
+        buf.push(b'p');
+        buf.put_length_prefixed(|buf| {
+            buf.extend(self.0);
+        });
+    }
+}
+
+#[test]
+fn test_encode_gss_enc_request() {
+    const EXPECTED: &[u8] = b"\x00\x00\x00\x08\x04\xd2\x160";
+
+    let mut buf = Vec::new();
+    GssEncRequest.encode(&mut buf);
+
+    assert_eq!(buf, EXPECTED);
+}
+
+#[test]
+fn test_encode_gss_response() {
+    const EXPECTED: &[u8] = b"p\x00\x00\x00\x07token";
+
+    let mut buf = Vec::new();
+    GssResponse(b"token").encode(&mut buf);
+
+    assert_eq!(buf, EXPECTED);
+}
@@ -13,6 +13,8 @@ mod data_row;
 mod describe;
 mod execute;
 mod flush;
+#[cfg(feature = "postgres-gssapi")]
+mod gss;
 mod notification;
 mod parameter_description;
 mod parameter_status;
@@ -38,6 +40,8 @@ pub use data_row::DataRow;
 pub use describe::Describe;
 pub use execute::Execute;
 pub use flush::Flush;
+#[cfg(feature = "postgres-gssapi")]
+pub use gss::{GssEncRequest, GssResponse};
 pub use notification::Notification;
 pub use parameter_description::ParameterDescription;
 pub use parameter_status::ParameterStatus;
@@ -61,6 +65,7 @@ pub enum MessageFormat {
     BindComplete,
     CloseComplete,
     CommandComplete,
+    CopyBothResponse,
     CopyData,
     CopyDone,
     CopyInResponse,
@@ -108,6 +113,7 @@ impl MessageFormat {
             b'c' => MessageFormat::CopyDone,
             b'G' => MessageFormat::CopyInResponse,
             b'H' => MessageFormat::CopyOutResponse,
+            b'W' => MessageFormat::CopyBothResponse,
             b'D' => MessageFormat::DataRow,
             b'E' => MessageFormat::ErrorResponse,
             b'I' => MessageFormat::EmptyQueryResponse,
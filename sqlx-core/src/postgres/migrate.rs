@@ -1,14 +1,20 @@
 use crate::connection::{ConnectOptions, Connection};
+use crate::database::Database;
 use crate::error::Error;
 use crate::executor::Executor;
-use crate::migrate::MigrateError;
-use crate::migrate::{AppliedMigration, Migration};
+use crate::migrate::DEFAULT_MIGRATIONS_TABLE;
+use crate::migrate::{applied_on, warn_missing_audit_columns};
+use crate::migrate::{quote_table_name, repeatable_table_name, split_table_name};
+use crate::migrate::{AppliedMigration, AppliedRepeatableMigration, Migration, Seed};
+use crate::migrate::{CreateDatabaseOptions, MigrateError};
 use crate::migrate::{Migrate, MigrateDatabase};
 use crate::postgres::{PgConnectOptions, PgConnection, Postgres};
 use crate::query::query;
 use crate::query_as::query_as;
 use crate::query_scalar::query_scalar;
 use futures_core::future::BoxFuture;
+use std::borrow::Cow;
+use std::fmt::Write;
 use std::str::FromStr;
 use std::time::Duration;
 use std::time::Instant;
@@ -37,16 +43,75 @@ fn parse_for_maintenance(url: &str) -> Result<(PgConnectOptions, String), Error>
 
 impl MigrateDatabase for Postgres {
     fn create_database(url: &str) -> BoxFuture<'_, Result<(), Error>> {
+        Box::pin(
+            async move { Self::create_database_with(url, &CreateDatabaseOptions::default()).await },
+        )
+    }
+
+    fn create_database_with<'a>(
+        url: &'a str,
+        create_options: &'a CreateDatabaseOptions,
+    ) -> BoxFuture<'a, Result<(), Error>> {
         Box::pin(async move {
+            create_options.check_supported(
+                "PostgreSQL",
+                &["owner", "encoding", "locale", "collation", "template"],
+            )?;
+
             let (options, database) = parse_for_maintenance(url)?;
             let mut conn = options.connect().await?;
 
-            let _ = conn
-                .execute(&*format!(
-                    "CREATE DATABASE \"{}\"",
-                    database.replace('"', "\"\"")
-                ))
-                .await?;
+            let mut sql = format!(
+                "CREATE DATABASE {}",
+                Postgres::quote_identifier(&database).map_err(Error::config)?
+            );
+
+            if let Some(owner) = &create_options.owner {
+                let _ = write!(
+                    sql,
+                    " OWNER {}",
+                    Postgres::quote_identifier(owner).map_err(Error::config)?
+                );
+            }
+
+            if let Some(template) = &create_options.template {
+                let _ = write!(
+                    sql,
+                    " TEMPLATE {}",
+                    Postgres::quote_identifier(template).map_err(Error::config)?
+                );
+            }
+
+            if let Some(encoding) = &create_options.encoding {
+                let _ = write!(
+                    sql,
+                    " ENCODING {}",
+                    Postgres::quote_literal(encoding).map_err(Error::config)?
+                );
+            }
+
+            // `LOCALE` sets both, but only since PostgreSQL 13
+            if let Some(collation) = create_options
+                .collation
+                .as_ref()
+                .or(create_options.locale.as_ref())
+            {
+                let _ = write!(
+                    sql,
+                    " LC_COLLATE {}",
+                    Postgres::quote_literal(collation).map_err(Error::config)?
+                );
+            }
+
+            if let Some(locale) = &create_options.locale {
+                let _ = write!(
+                    sql,
+                    " LC_CTYPE {}",
+                    Postgres::quote_literal(locale).map_err(Error::config)?
+                );
+            }
+
+            let _ = conn.execute(&*sql).await?;
 
             Ok(())
         })
@@ -74,44 +139,139 @@ impl MigrateDatabase for Postgres {
 
             let _ = conn
                 .execute(&*format!(
-                    "DROP DATABASE IF EXISTS \"{}\"",
-                    database.replace('"', "\"\"")
+                    "DROP DATABASE IF EXISTS {}",
+                    Postgres::quote_identifier(&database).map_err(Error::config)?
                 ))
                 .await?;
 
             Ok(())
         })
     }
+
+    fn force_drop_database(url: &str) -> BoxFuture<'_, Result<(), Error>> {
+        Box::pin(async move {
+            let (options, database) = parse_for_maintenance(url)?;
+            let mut conn = options.connect().await?;
+
+            let exists: bool =
+                query_scalar("SELECT EXISTS (SELECT 1 FROM pg_database WHERE datname = $1)")
+                    .bind(&database)
+                    .fetch_one(&mut conn)
+                    .await?;
+
+            if !exists {
+                return Ok(());
+            }
+
+            let quoted = Postgres::quote_identifier(&database).map_err(Error::config)?;
+
+            // keep new sessions from connecting while the others are terminated
+            let _ = conn
+                .execute(&*format!(
+                    "REVOKE CONNECT ON DATABASE {} FROM public",
+                    quoted
+                ))
+                .await?;
+
+            // language=PostgreSQL
+            let _ = query(
+                "SELECT pg_terminate_backend(pid) FROM pg_stat_activity \
+                 WHERE datname = $1 AND pid <> pg_backend_pid()",
+            )
+            .bind(&database)
+            .execute(&mut conn)
+            .await?;
+
+            // waits for the terminated sessions to exit
+            let _ = conn
+                .execute(&*format!("DROP DATABASE IF EXISTS {}", quoted))
+                .await?;
+
+            Ok(())
+        })
+    }
 }
 
 impl Migrate for PgConnection {
-    fn ensure_migrations_table(&mut self) -> BoxFuture<'_, Result<(), MigrateError>> {
+    fn ensure_migrations_table<'e>(
+        &'e mut self,
+        table_name: &'e str,
+    ) -> BoxFuture<'e, Result<(), MigrateError>> {
         Box::pin(async move {
+            let table = quote_table_name(table_name, '"');
+
+            if let (Some(schema), _) = split_table_name(table_name) {
+                // language=SQL
+                self.execute(&*format!(
+                    "CREATE SCHEMA IF NOT EXISTS {}",
+                    Postgres::quote_identifier(schema).map_err(Error::config)?
+                ))
+                .await?;
+            }
+
             // language=SQL
-            self.execute(
+            self.execute(&*format!(
                 r#"
-CREATE TABLE IF NOT EXISTS _sqlx_migrations (
+CREATE TABLE IF NOT EXISTS {} (
     version BIGINT PRIMARY KEY,
     description TEXT NOT NULL,
     installed_on TIMESTAMPTZ NOT NULL DEFAULT now(),
     success BOOLEAN NOT NULL,
     checksum BYTEA NOT NULL,
-    execution_time BIGINT NOT NULL
+    execution_time BIGINT NOT NULL,
+    applied_by TEXT,
+    sqlx_version TEXT,
+    hostname TEXT
 );
                 "#,
-            )
+                table
+            ))
             .await?;
 
+            if !has_audit_columns(self, &table).await? {
+                // language=SQL
+                let added = self
+                    .execute(&*format!(
+                        r#"
+ALTER TABLE {}
+    ADD COLUMN IF NOT EXISTS applied_by TEXT,
+    ADD COLUMN IF NOT EXISTS sqlx_version TEXT,
+    ADD COLUMN IF NOT EXISTS hostname TEXT;
+                        "#,
+                        table
+                    ))
+                    .await;
+
+                match added {
+                    Ok(_) => {}
+                    Err(e) if e.as_database_error().is_some() => {
+                        warn_missing_audit_columns(table_name, &e)
+                    }
+                    Err(e) => return Err(e.into()),
+                }
+            }
+
             Ok(())
         })
     }
 
-    fn version(&mut self) -> BoxFuture<'_, Result<Option<(i64, bool)>, MigrateError>> {
+    fn migrations_table_exists<'e>(
+        &'e mut self,
+        table_name: &'e str,
+    ) -> BoxFuture<'e, Result<bool, MigrateError>> {
+        Box::pin(async move { table_exists(self, &quote_table_name(table_name, '"')).await })
+    }
+
+    fn version<'e>(
+        &'e mut self,
+        table_name: &'e str,
+    ) -> BoxFuture<'e, Result<Option<(i64, bool)>, MigrateError>> {
         Box::pin(async move {
             // language=SQL
-            let row = query_as(
-                "SELECT version, NOT success FROM _sqlx_migrations ORDER BY version DESC LIMIT 1",
-            )
+            let row = query_as(&format!(
+                "SELECT version, NOT success FROM {} ORDER BY version DESC LIMIT 1",
+                quote_table_name(table_name, '"')
+            ))
             .fetch_optional(self)
             .await?;
 
@@ -119,12 +279,16 @@ CREATE TABLE IF NOT EXISTS _sqlx_migrations (
         })
     }
 
-    fn dirty_version(&mut self) -> BoxFuture<'_, Result<Option<i64>, MigrateError>> {
+    fn dirty_version<'e>(
+        &'e mut self,
+        table_name: &'e str,
+    ) -> BoxFuture<'e, Result<Option<i64>, MigrateError>> {
         Box::pin(async move {
             // language=SQL
-            let row: Option<(i64,)> = query_as(
-                "SELECT version FROM _sqlx_migrations WHERE success = false ORDER BY version LIMIT 1",
-            )
+            let row: Option<(i64,)> = query_as(&format!(
+                "SELECT version FROM {} WHERE success = false ORDER BY version LIMIT 1",
+                quote_table_name(table_name, '"')
+            ))
             .fetch_optional(self)
             .await?;
 
@@ -132,20 +296,67 @@ CREATE TABLE IF NOT EXISTS _sqlx_migrations (
         })
     }
 
-    fn list_applied_migrations(
-        &mut self,
-    ) -> BoxFuture<'_, Result<Vec<AppliedMigration>, MigrateError>> {
+    fn list_applied_migrations<'e>(
+        &'e mut self,
+        table_name: &'e str,
+    ) -> BoxFuture<'e, Result<Vec<AppliedMigration>, MigrateError>> {
         Box::pin(async move {
+            let table = quote_table_name(table_name, '"');
+
+            let columns = if has_audit_columns(self, &table).await? {
+                "version, checksum, applied_by, sqlx_version, hostname"
+            } else {
+                "version, checksum, NULL::text, NULL::text, NULL::text"
+            };
+
             // language=SQL
-            let rows: Vec<(i64, Vec<u8>)> =
-                query_as("SELECT version, checksum FROM _sqlx_migrations ORDER BY version")
-                    .fetch_all(self)
-                    .await?;
+            let rows: Vec<AppliedRow> = query_as(&format!(
+                "SELECT {} FROM {} ORDER BY version",
+                columns, table
+            ))
+            .fetch_all(self)
+            .await?;
+
+            let migrations = rows
+                .into_iter()
+                .map(
+                    |(version, checksum, applied_by, sqlx_version, hostname)| AppliedMigration {
+                        version,
+                        checksum: checksum.into(),
+                        applied_by,
+                        sqlx_version,
+                        hostname,
+                    },
+                )
+                .collect();
+
+            Ok(migrations)
+        })
+    }
+
+    fn list_applied_repeatable_migrations<'e>(
+        &'e mut self,
+        table_name: &'e str,
+    ) -> BoxFuture<'e, Result<Vec<AppliedRepeatableMigration>, MigrateError>> {
+        Box::pin(async move {
+            let table = quote_table_name(&repeatable_table_name(table_name), '"');
+
+            if !table_exists(self, &table).await? {
+                return Ok(Vec::new());
+            }
+
+            // language=SQL
+            let rows: Vec<(String, Vec<u8>)> = query_as(&format!(
+                "SELECT description, checksum FROM {} WHERE success = true ORDER BY description",
+                table
+            ))
+            .fetch_all(self)
+            .await?;
 
             let migrations = rows
                 .into_iter()
-                .map(|(version, checksum)| AppliedMigration {
-                    version,
+                .map(|(description, checksum)| AppliedRepeatableMigration {
+                    description: description.into(),
                     checksum: checksum.into(),
                 })
                 .collect();
@@ -154,31 +365,77 @@ CREATE TABLE IF NOT EXISTS _sqlx_migrations (
         })
     }
 
-    fn lock(&mut self) -> BoxFuture<'_, Result<(), MigrateError>> {
+    fn lock<'e>(
+        &'e mut self,
+        table_name: &'e str,
+        timeout: Option<Duration>,
+    ) -> BoxFuture<'e, Result<(), MigrateError>> {
         Box::pin(async move {
             let database_name = current_database(self).await?;
-            let lock_id = generate_lock_id(&database_name);
+            let lock_id = generate_lock_id(&database_name, table_name);
 
             // create an application lock over the database
-            // this function will not return until the lock is acquired
 
             // https://www.postgresql.org/docs/current/explicit-locking.html#ADVISORY-LOCKS
             // https://www.postgresql.org/docs/current/functions-admin.html#FUNCTIONS-ADVISORY-LOCKS-TABLE
 
-            // language=SQL
-            let _ = query("SELECT pg_advisory_lock($1)")
-                .bind(lock_id)
-                .execute(self)
-                .await?;
+            if try_lock(self, lock_id).await? {
+                return Ok(());
+            }
 
-            Ok(())
+            let holder = lock_holder(self, lock_id).await?;
+
+            let timeout = match timeout {
+                Some(timeout) if timeout.is_zero() => {
+                    return Err(MigrateError::LockTimeout(holder))
+                }
+                timeout => timeout,
+            };
+
+            log::info!(
+                "waiting for the migrations lock on {}{}",
+                table_name,
+                holder
+                    .as_deref()
+                    .map(|holder| format!(", held by {}", holder))
+                    .unwrap_or_default()
+            );
+
+            let timeout = match timeout {
+                Some(timeout) => timeout,
+                None => {
+                    // this function will not return until the lock is acquired
+
+                    // language=SQL
+                    let _ = query("SELECT pg_advisory_lock($1)")
+                        .bind(lock_id)
+                        .execute(self)
+                        .await?;
+
+                    return Ok(());
+                }
+            };
+
+            // a blocking `pg_advisory_lock()` can't be given a timeout without changing the
+            // `lock_timeout` of the session, so poll instead
+            let deadline = Instant::now() + timeout;
+
+            while Instant::now() < deadline {
+                sqlx_rt::sleep(LOCK_POLL_INTERVAL).await;
+
+                if try_lock(self, lock_id).await? {
+                    return Ok(());
+                }
+            }
+
+            Err(MigrateError::LockTimeout(lock_holder(self, lock_id).await?))
         })
     }
 
-    fn unlock(&mut self) -> BoxFuture<'_, Result<(), MigrateError>> {
+    fn unlock<'e>(&'e mut self, table_name: &'e str) -> BoxFuture<'e, Result<(), MigrateError>> {
         Box::pin(async move {
             let database_name = current_database(self).await?;
-            let lock_id = generate_lock_id(&database_name);
+            let lock_id = generate_lock_id(&database_name, table_name);
 
             // language=SQL
             let _ = query("SELECT pg_advisory_unlock($1)")
@@ -192,15 +449,18 @@ CREATE TABLE IF NOT EXISTS _sqlx_migrations (
 
     fn validate<'e: 'm, 'm>(
         &'e mut self,
+        table_name: &'m str,
         migration: &'m Migration,
     ) -> BoxFuture<'m, Result<(), MigrateError>> {
         Box::pin(async move {
             // language=SQL
-            let checksum: Option<Vec<u8>> =
-                query_scalar("SELECT checksum FROM _sqlx_migrations WHERE version = $1")
-                    .bind(migration.version)
-                    .fetch_optional(self)
-                    .await?;
+            let checksum: Option<Vec<u8>> = query_scalar(&format!(
+                "SELECT checksum FROM {} WHERE version = $1",
+                quote_table_name(table_name, '"')
+            ))
+            .bind(migration.version)
+            .fetch_optional(self)
+            .await?;
 
             if let Some(checksum) = checksum {
                 return if checksum == &*migration.checksum {
@@ -216,59 +476,434 @@ CREATE TABLE IF NOT EXISTS _sqlx_migrations (
 
     fn apply<'e: 'm, 'm>(
         &'e mut self,
+        table_name: &'m str,
         migration: &'m Migration,
+        applied_by: Option<&'m str>,
     ) -> BoxFuture<'m, Result<Duration, MigrateError>> {
         Box::pin(async move {
-            let mut tx = self.begin().await?;
+            let table = quote_table_name(table_name, '"');
             let start = Instant::now();
 
-            let _ = tx.execute(&*migration.sql).await?;
-
-            tx.commit().await?;
+            if migration.no_tx {
+                execute_statements(self, &migration.sql).await?;
+            } else {
+                let mut tx = self.begin().await?;
+                let _ = tx.execute(&*migration.sql).await?;
+                tx.commit().await?;
+            }
 
             let elapsed = start.elapsed();
 
+            if migration.migration_type.is_repeatable() {
+                record_applied_repeatable(
+                    self,
+                    table_name,
+                    migration,
+                    elapsed.as_nanos() as i64,
+                    applied_by,
+                )
+                .await?;
+            } else {
+                record_applied(
+                    self,
+                    &table,
+                    migration,
+                    elapsed.as_nanos() as i64,
+                    applied_by,
+                )
+                .await?;
+            }
+
+            Ok(elapsed)
+        })
+    }
+
+    fn set_checksum<'e: 'm, 'm>(
+        &'e mut self,
+        table_name: &'m str,
+        migration: &'m Migration,
+    ) -> BoxFuture<'m, Result<(), MigrateError>> {
+        Box::pin(async move {
             // language=SQL
-            let _ = query(
-                r#"
-    INSERT INTO _sqlx_migrations ( version, description, success, checksum, execution_time )
-    VALUES ( $1, $2, TRUE, $3, $4 )
-                "#,
-            )
-            .bind(migration.version)
-            .bind(&*migration.description)
+            let _ = query(&format!(
+                r#"UPDATE {} SET checksum = $1 WHERE version = $2"#,
+                quote_table_name(table_name, '"')
+            ))
             .bind(&*migration.checksum)
-            .bind(elapsed.as_nanos() as i64)
+            .bind(migration.version)
             .execute(self)
             .await?;
 
-            Ok(elapsed)
+            Ok(())
+        })
+    }
+
+    fn mark_applied<'e: 'm, 'm>(
+        &'e mut self,
+        table_name: &'m str,
+        migration: &'m Migration,
+        applied_by: Option<&'m str>,
+    ) -> BoxFuture<'m, Result<(), MigrateError>> {
+        Box::pin(async move {
+            let table = quote_table_name(table_name, '"');
+
+            record_applied(self, &table, migration, 0, applied_by).await
         })
     }
 
     fn revert<'e: 'm, 'm>(
         &'e mut self,
+        table_name: &'m str,
         migration: &'m Migration,
     ) -> BoxFuture<'m, Result<Duration, MigrateError>> {
         Box::pin(async move {
-            let mut tx = self.begin().await?;
             let start = Instant::now();
 
-            let _ = tx.execute(&*migration.sql).await?;
-
-            tx.commit().await?;
+            if migration.no_tx {
+                execute_statements(self, &migration.sql).await?;
+            } else {
+                let mut tx = self.begin().await?;
+                let _ = tx.execute(&*migration.sql).await?;
+                tx.commit().await?;
+            }
 
             let elapsed = start.elapsed();
 
             // language=SQL
-            let _ = query(r#"DELETE FROM _sqlx_migrations WHERE version = $1"#)
-                .bind(migration.version)
-                .execute(self)
-                .await?;
+            let _ = query(&format!(
+                r#"DELETE FROM {} WHERE version = $1"#,
+                quote_table_name(table_name, '"')
+            ))
+            .bind(migration.version)
+            .execute(self)
+            .await?;
 
             Ok(elapsed)
         })
     }
+
+    fn seed<'e: 'm, 'm>(
+        &'e mut self,
+        seed: &'m Seed,
+    ) -> BoxFuture<'m, Result<Duration, MigrateError>> {
+        Box::pin(async move {
+            let start = Instant::now();
+
+            let run = async {
+                let mut tx = self.begin().await?;
+                let _ = tx.execute(&*seed.sql).await?;
+                tx.commit().await
+            };
+
+            run.await
+                .map_err(|e| MigrateError::Seed(seed.name.to_string(), e))?;
+
+            Ok(start.elapsed())
+        })
+    }
+}
+
+// version, checksum, applied_by, sqlx_version, hostname
+type AppliedRow = (i64, Vec<u8>, Option<String>, Option<String>, Option<String>);
+
+// the columns for audits are missing from migrations tables created before them, if they
+// couldn't be added
+async fn has_audit_columns(conn: &mut PgConnection, table: &str) -> Result<bool, MigrateError> {
+    // language=SQL
+    let exists = query_scalar(
+        r#"
+SELECT EXISTS (
+    SELECT 1 FROM pg_attribute
+    WHERE attrelid = $1::regclass AND attname = 'applied_by' AND NOT attisdropped
+)
+        "#,
+    )
+    .bind(table)
+    .fetch_one(conn)
+    .await?;
+
+    Ok(exists)
+}
+
+async fn table_exists(conn: &mut PgConnection, table: &str) -> Result<bool, MigrateError> {
+    // language=SQL
+    let exists = query_scalar("SELECT to_regclass($1) IS NOT NULL")
+        .bind(table)
+        .fetch_one(conn)
+        .await?;
+
+    Ok(exists)
+}
+
+async fn record_applied(
+    conn: &mut PgConnection,
+    table: &str,
+    migration: &Migration,
+    execution_time: i64,
+    applied_by: Option<&str>,
+) -> Result<(), MigrateError> {
+    if has_audit_columns(conn, table).await? {
+        let (sqlx_version, hostname) = applied_on();
+
+        // language=SQL
+        let _ = query(&format!(
+            r#"
+    INSERT INTO {} ( version, description, success, checksum, execution_time, applied_by, sqlx_version, hostname )
+    VALUES ( $1, $2, TRUE, $3, $4, COALESCE($5, current_user::text), $6, $7 )
+            "#,
+            table
+        ))
+        .bind(migration.version)
+        .bind(&*migration.description)
+        .bind(&*migration.checksum)
+        .bind(execution_time)
+        .bind(applied_by)
+        .bind(sqlx_version)
+        .bind(hostname)
+        .execute(conn)
+        .await?;
+    } else {
+        // language=SQL
+        let _ = query(&format!(
+            r#"
+    INSERT INTO {} ( version, description, success, checksum, execution_time )
+    VALUES ( $1, $2, TRUE, $3, $4 )
+            "#,
+            table
+        ))
+        .bind(migration.version)
+        .bind(&*migration.description)
+        .bind(&*migration.checksum)
+        .bind(execution_time)
+        .execute(conn)
+        .await?;
+    }
+
+    Ok(())
+}
+
+// the table of repeatable migrations is created with the first one, in the schema of the
+// migrations table, which exists by then
+async fn record_applied_repeatable(
+    conn: &mut PgConnection,
+    table_name: &str,
+    migration: &Migration,
+    execution_time: i64,
+    applied_by: Option<&str>,
+) -> Result<(), MigrateError> {
+    let table = quote_table_name(&repeatable_table_name(table_name), '"');
+    let (sqlx_version, hostname) = applied_on();
+
+    // language=SQL
+    conn.execute(&*format!(
+        r#"
+CREATE TABLE IF NOT EXISTS {} (
+    description TEXT PRIMARY KEY,
+    installed_on TIMESTAMPTZ NOT NULL DEFAULT now(),
+    success BOOLEAN NOT NULL,
+    checksum BYTEA NOT NULL,
+    execution_time BIGINT NOT NULL,
+    applied_by TEXT,
+    sqlx_version TEXT,
+    hostname TEXT
+);
+        "#,
+        table
+    ))
+    .await?;
+
+    // language=SQL
+    let _ = query(&format!(r#"DELETE FROM {} WHERE description = $1"#, table))
+        .bind(&*migration.description)
+        .execute(&mut *conn)
+        .await?;
+
+    // language=SQL
+    let _ = query(&format!(
+        r#"
+    INSERT INTO {} ( description, success, checksum, execution_time, applied_by, sqlx_version, hostname )
+    VALUES ( $1, TRUE, $2, $3, COALESCE($4, current_user::text), $5, $6 )
+        "#,
+        table
+    ))
+    .bind(&*migration.description)
+    .bind(&*migration.checksum)
+    .bind(execution_time)
+    .bind(applied_by)
+    .bind(sqlx_version)
+    .bind(hostname)
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+// the statements of a single query are run in one transaction, so run them one at a time
+pub(crate) async fn execute_statements(conn: &mut PgConnection, sql: &str) -> Result<(), Error> {
+    for statement in split_statements(sql) {
+        let _ = conn.execute(statement).await?;
+    }
+
+    Ok(())
+}
+
+/// Split SQL into its statements, skipping over the semicolons in comments, quoted identifiers,
+/// and string constants, including dollar-quoted ones.
+fn split_statements(sql: &str) -> Vec<&str> {
+    fn find(bytes: &[u8], from: usize, needle: &[u8]) -> Option<usize> {
+        bytes[from..]
+            .windows(needle.len())
+            .position(|window| window == needle)
+            .map(|position| from + position)
+    }
+
+    fn is_ident(b: u8) -> bool {
+        b.is_ascii_alphanumeric() || b == b'_' || b == b'$'
+    }
+
+    // the length of the `$tag$` starting a dollar-quoted string constant, if any
+    fn dollar_tag(bytes: &[u8]) -> Option<usize> {
+        let end = bytes[1..].iter().position(|&b| b == b'$')? + 1;
+        let tag = &bytes[1..end];
+
+        let valid = !matches!(tag.first(), Some(b) if b.is_ascii_digit())
+            && tag.iter().all(|&b| b.is_ascii_alphanumeric() || b == b'_');
+
+        valid.then(|| end + 1)
+    }
+
+    let bytes = sql.as_bytes();
+    let mut statements = Vec::new();
+
+    let mut start = 0;
+    let mut has_code = false;
+    let mut i = 0;
+
+    while i < bytes.len() {
+        match bytes[i] {
+            b'-' if bytes.get(i + 1) == Some(&b'-') => {
+                i = find(bytes, i, b"\n").map_or(bytes.len(), |end| end + 1);
+            }
+
+            // block comments nest
+            b'/' if bytes.get(i + 1) == Some(&b'*') => {
+                let mut depth = 0;
+
+                while i < bytes.len() {
+                    if bytes[i..].starts_with(b"/*") {
+                        depth += 1;
+                        i += 2;
+                    } else if bytes[i..].starts_with(b"*/") {
+                        depth -= 1;
+                        i += 2;
+
+                        if depth == 0 {
+                            break;
+                        }
+                    } else {
+                        i += 1;
+                    }
+                }
+            }
+
+            b'\'' => {
+                // a backslash escapes the quote in `E'...'`
+                let escapes = i > 0
+                    && matches!(bytes[i - 1], b'E' | b'e')
+                    && (i < 2 || !is_ident(bytes[i - 2]));
+
+                has_code = true;
+                i += 1;
+
+                while i < bytes.len() {
+                    match bytes[i] {
+                        b'\\' if escapes => i += 2,
+                        b'\'' => {
+                            i += 1;
+                            break;
+                        }
+                        _ => i += 1,
+                    }
+                }
+            }
+
+            b'"' => {
+                has_code = true;
+                i = find(bytes, i + 1, b"\"").map_or(bytes.len(), |end| end + 1);
+            }
+
+            b'$' if i == 0 || !is_ident(bytes[i - 1]) => {
+                has_code = true;
+
+                match dollar_tag(&bytes[i..]) {
+                    Some(tag_len) => {
+                        let tag = &bytes[i..i + tag_len];
+
+                        i = find(bytes, i + tag_len, tag).map_or(bytes.len(), |end| end + tag_len);
+                    }
+
+                    None => i += 1,
+                }
+            }
+
+            b';' => {
+                if has_code {
+                    statements.push(sql[start..i].trim());
+                }
+
+                start = i + 1;
+                has_code = false;
+                i += 1;
+            }
+
+            b => {
+                has_code |= !b.is_ascii_whitespace();
+                i += 1;
+            }
+        }
+    }
+
+    if has_code {
+        statements.push(sql[start..].trim());
+    }
+
+    statements
+}
+
+// how often a migrator waiting for the lock with a timeout checks whether it was released
+const LOCK_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+async fn try_lock(conn: &mut PgConnection, lock_id: i64) -> Result<bool, MigrateError> {
+    // language=SQL
+    Ok(query_scalar("SELECT pg_try_advisory_lock($1)")
+        .bind(lock_id)
+        .fetch_one(conn)
+        .await?)
+}
+
+/// Describe the backend holding the advisory lock `lock_id`, if it is still held.
+async fn lock_holder(
+    conn: &mut PgConnection,
+    lock_id: i64,
+) -> Result<Option<String>, MigrateError> {
+    // a `bigint` key is split into `classid` and `objid`, and marked with an `objsubid` of 1:
+    // https://www.postgresql.org/docs/current/view-pg-locks.html
+
+    // language=SQL
+    let pid: Option<i32> = query_scalar(
+        r#"
+SELECT pid FROM pg_locks
+WHERE locktype = 'advisory' AND granted AND objsubid = 1 AND pid IS NOT NULL
+    AND classid::text::bigint = ($1 >> 32) & 4294967295
+    AND objid::text::bigint = $1 & 4294967295
+LIMIT 1
+        "#,
+    )
+    .bind(lock_id)
+    .fetch_optional(conn)
+    .await?;
+
+    Ok(pid.map(|pid| format!("the backend with PID {}", pid)))
 }
 
 async fn current_database(conn: &mut PgConnection) -> Result<String, MigrateError> {
@@ -279,8 +914,46 @@ async fn current_database(conn: &mut PgConnection) -> Result<String, MigrateErro
 }
 
 // inspired from rails: https://github.com/rails/rails/blob/6e49cc77ab3d16c06e12f93158eaf3e507d4120e/activerecord/lib/active_record/migration.rb#L1308
-fn generate_lock_id(database_name: &str) -> i64 {
+fn generate_lock_id(database_name: &str, table_name: &str) -> i64 {
     const CRC_IEEE: crc::Crc<u32> = crc::Crc::<u32>::new(&crc::CRC_32_ISO_HDLC);
+
+    // the lock of the default table is kept as it was, so older migrators still wait on it
+    let key = if table_name == DEFAULT_MIGRATIONS_TABLE {
+        Cow::Borrowed(database_name)
+    } else {
+        Cow::Owned(format!("{}.{}", database_name, table_name))
+    };
+
     // 0x3d32ad9e chosen by fair dice roll
-    0x3d32ad9e * (CRC_IEEE.checksum(database_name.as_bytes()) as i64)
+    0x3d32ad9e * (CRC_IEEE.checksum(key.as_bytes()) as i64)
+}
+
+#[test]
+fn test_split_statements() {
+    assert_eq!(
+        split_statements(
+            "-- sqlx:no-transaction\n\
+             CREATE INDEX CONCURRENTLY idx ON t (a);\n\
+             CREATE INDEX CONCURRENTLY \"i;dx\" ON t (b);"
+        ),
+        [
+            "-- sqlx:no-transaction\nCREATE INDEX CONCURRENTLY idx ON t (a)",
+            "CREATE INDEX CONCURRENTLY \"i;dx\" ON t (b)",
+        ]
+    );
+
+    assert_eq!(
+        split_statements(
+            "CREATE FUNCTION f() RETURNS text AS $body$ SELECT 'a;b'; $body$ LANGUAGE sql;\n\
+             /* one; /* nested; */ */ SELECT 'it''s; here', E'\\';', $$;$$;\n\
+             -- trailing; comment\n"
+        ),
+        [
+            "CREATE FUNCTION f() RETURNS text AS $body$ SELECT 'a;b'; $body$ LANGUAGE sql",
+            "/* one; /* nested; */ */ SELECT 'it''s; here', E'\\';', $$;$$",
+        ]
+    );
+
+    assert_eq!(split_statements("SELECT 1"), ["SELECT 1"]);
+    assert!(split_statements(" ;\n-- nothing\n").is_empty());
 }
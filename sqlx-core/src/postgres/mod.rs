@@ -8,8 +8,11 @@ mod column;
 mod connection;
 mod copy;
 mod database;
+mod dynamic_value;
 mod error;
+mod explain;
 mod io;
+mod large_object;
 mod listener;
 mod message;
 mod options;
@@ -27,16 +30,25 @@ mod migrate;
 #[cfg(feature = "migrate")]
 mod testing;
 
+#[cfg(feature = "serde_row")]
+mod serde_row;
+
+#[cfg(feature = "replication")]
+pub mod replication;
+
 pub use advisory_lock::{PgAdvisoryLock, PgAdvisoryLockGuard, PgAdvisoryLockKey};
 pub use arguments::{PgArgumentBuffer, PgArguments};
 pub use column::PgColumn;
-pub use connection::PgConnection;
+pub use connection::{PgConnection, PgStatementCacheStatistics};
 pub use copy::PgCopyIn;
 pub use database::Postgres;
+pub use dynamic_value::PgDynamicValue;
 pub use error::{PgDatabaseError, PgErrorPosition};
+pub use explain::{PgExplainBuffers, PgExplainNode, PgExplainOptions, PgExplainPlan};
+pub use large_object::{PgLargeObject, PgLargeObjectMode};
 pub use listener::{PgListener, PgNotification};
 pub use message::PgSeverity;
-pub use options::{PgConnectOptions, PgSslMode};
+pub use options::{PgConnectOptions, PgGssEncMode, PgSslMode, PgTargetSessionAttrs};
 pub use query_result::PgQueryResult;
 pub use row::PgRow;
 pub use statement::PgStatement;
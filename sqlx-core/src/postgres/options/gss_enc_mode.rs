@@ -0,0 +1,53 @@
+use crate::error::Error;
+use std::str::FromStr;
+
+/// Options for controlling whether a PostgreSQL connection is encrypted with GSSAPI.
+///
+/// It is used by the [`gss_enc_mode`](super::PgConnectOptions::gss_enc_mode) method.
+///
+/// GSSAPI encryption requires the `postgres-gssapi` feature. When it is enabled and a GSSAPI
+/// encrypted connection is established, TLS is not used as the connection is already encrypted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PgGssEncMode {
+    /// Only try a non-GSSAPI-encrypted connection.
+    Disable,
+
+    /// If there are GSSAPI credentials present (i.e., in a credentials cache), first try a
+    /// GSSAPI-encrypted connection; if that fails or there are no credentials, try a
+    /// non-GSSAPI-encrypted connection.
+    ///
+    /// Without the `postgres-gssapi` feature, this behaves the same as `Disable`.
+    #[default]
+    Prefer,
+
+    /// Only try a GSSAPI-encrypted connection.
+    Require,
+}
+
+impl PgGssEncMode {
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            PgGssEncMode::Disable => "disable",
+            PgGssEncMode::Prefer => "prefer",
+            PgGssEncMode::Require => "require",
+        }
+    }
+}
+
+impl FromStr for PgGssEncMode {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Error> {
+        Ok(match &*s.to_ascii_lowercase() {
+            "disable" => PgGssEncMode::Disable,
+            "prefer" => PgGssEncMode::Prefer,
+            "require" => PgGssEncMode::Require,
+
+            _ => {
+                return Err(Error::Configuration(
+                    format!("unknown value {:?} for `gss_enc_mode`", s).into(),
+                ));
+            }
+        })
+    }
+}
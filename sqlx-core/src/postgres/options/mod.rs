@@ -1,14 +1,24 @@
 use std::borrow::Cow;
+use std::collections::BTreeMap;
 use std::env::var;
-use std::fmt::{Display, Write};
+use std::fmt::{self, Debug, Display, Formatter, Write};
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
 
 mod connect;
+mod gss_enc_mode;
 mod parse;
 mod pgpass;
 mod ssl_mode;
-use crate::{connection::LogSettings, net::CertificateInput};
+mod target_session_attrs;
+use crate::connection::{LogSettings, REDACTED};
+use crate::net::CertificateInput;
+use crate::postgres::connection::type_cache::PgTypeCache;
+use crate::postgres::explain::PgAutoExplain;
+pub use gss_enc_mode::PgGssEncMode;
 pub use ssl_mode::PgSslMode;
+pub use target_session_attrs::PgTargetSessionAttrs;
 
 /// Options and flags which can be used to configure a PostgreSQL connection.
 ///
@@ -27,7 +37,10 @@ pub use ssl_mode::PgSslMode;
 /// |---------|-------|-----------|
 /// | `sslmode` | `prefer` | Determines whether or with what priority a secure SSL TCP/IP connection will be negotiated. See [`PgSslMode`]. |
 /// | `sslrootcert` | `None` | Sets the name of a file containing a list of trusted SSL Certificate Authorities. |
+/// | `gssencmode` | `prefer` | Determines whether or with what priority a GSSAPI encrypted connection will be negotiated. See [`PgGssEncMode`]. |
+/// | `krbsrvname` | `postgres` | The Kerberos service name to use when authenticating with GSSAPI. |
 /// | `statement-cache-capacity` | `100` | The maximum number of prepared statements stored in the cache. Set to `0` to disable. |
+/// | `prefer_simple_protocol` | `false` | Avoid named prepared statements, e.g. when connecting through PgBouncer. See [`prefer_simple_protocol`](PgConnectOptions::prefer_simple_protocol). |
 /// | `host` | `None` | Path to the directory containing a PostgreSQL unix domain socket, which will be used instead of TCP if set. |
 /// | `hostaddr` | `None` | Same as `host`, but only accepts IP addresses. |
 /// | `application-name` | `None` | The name will be displayed in the pg_stat_activity view and included in CSV log entries. |
@@ -35,7 +48,12 @@ pub use ssl_mode::PgSslMode;
 /// | `password` | `None` | Password to be used if the server demands password authentication. |
 /// | `port` | `5432` | Port number to connect to at the server host, or socket file name extension for Unix-domain connections. |
 /// | `dbname` | `None` | The database name. |
-/// | `options` | `None` | The runtime parameters to send to the server at connection start. |
+/// | `options` | `None` | The runtime parameters to send to the server at connection start, instead of those of `PGOPTIONS`. |
+/// | `options[<name>]` | `None` | A runtime parameter to add to `options`, as `-c <name>=<value>`. |
+/// | `startup_parameter[<name>]` | `None` | A run-time parameter to send in the startup message. See [`startup_parameter`](PgConnectOptions::startup_parameter). |
+/// | `extra_float_digits` | `3` | The precision of floating-point values in text mode, or empty not to send it. See [`extra_float_digits`](PgConnectOptions::extra_float_digits). |
+/// | `target_session_attrs` | `any` | The kind of session that is acceptable, e.g. `read-write` to reject standby servers. See [`PgTargetSessionAttrs`]. |
+/// | `replication` | `None` | Set to `database` to open the connection in logical replication mode. Requires the `replication` feature. |
 ///
 /// The URL scheme designator can be either `postgresql://` or `postgres://`.
 /// Each of the URL parts is optional.
@@ -50,6 +68,10 @@ pub use ssl_mode::PgSslMode;
 /// postgresql://localhost?dbname=mydb&user=postgres&password=postgres
 /// ```
 ///
+/// The options can be converted back to a URL with
+/// [`to_url_lossless`](crate::connection::ConnectOptions::to_url_lossless). Their `Debug` output
+/// doesn't show the password.
+///
 /// # Example
 ///
 /// ```rust,no_run
@@ -75,7 +97,7 @@ pub use ssl_mode::PgSslMode;
 /// # }).unwrap();
 /// # }
 /// ```
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct PgConnectOptions {
     pub(crate) host: String,
     pub(crate) port: u16,
@@ -85,11 +107,51 @@ pub struct PgConnectOptions {
     pub(crate) database: Option<String>,
     pub(crate) ssl_mode: PgSslMode,
     pub(crate) ssl_root_cert: Option<CertificateInput>,
+    pub(crate) gss_enc_mode: PgGssEncMode,
+    pub(crate) krbsrvname: String,
     pub(crate) statement_cache_capacity: usize,
+    pub(crate) prefer_simple_protocol: bool,
     pub(crate) application_name: Option<String>,
     pub(crate) log_settings: LogSettings,
     pub(crate) extra_float_digits: Option<Cow<'static, str>>,
     pub(crate) options: Option<String>,
+    pub(crate) startup_parameters: BTreeMap<String, String>,
+    pub(crate) target_session_attrs: PgTargetSessionAttrs,
+    pub(crate) type_cache: Arc<PgTypeCache>,
+    pub(crate) auto_explain: Option<Arc<PgAutoExplain>>,
+    #[cfg(feature = "replication")]
+    pub(crate) replication: bool,
+}
+
+impl Debug for PgConnectOptions {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let mut s = f.debug_struct("PgConnectOptions");
+
+        s.field("host", &self.host)
+            .field("port", &self.port)
+            .field("socket", &self.socket)
+            .field("username", &self.username)
+            .field("password", &self.password.as_ref().map(|_| REDACTED))
+            .field("database", &self.database)
+            .field("ssl_mode", &self.ssl_mode)
+            .field("ssl_root_cert", &self.ssl_root_cert)
+            .field("gss_enc_mode", &self.gss_enc_mode)
+            .field("krbsrvname", &self.krbsrvname)
+            .field("statement_cache_capacity", &self.statement_cache_capacity)
+            .field("prefer_simple_protocol", &self.prefer_simple_protocol)
+            .field("application_name", &self.application_name)
+            .field("log_settings", &self.log_settings)
+            .field("extra_float_digits", &self.extra_float_digits)
+            .field("options", &self.options)
+            .field("startup_parameters", &self.startup_parameters)
+            .field("target_session_attrs", &self.target_session_attrs)
+            .field("auto_explain", &self.auto_explain);
+
+        #[cfg(feature = "replication")]
+        s.field("replication", &self.replication);
+
+        s.finish_non_exhaustive()
+    }
 }
 
 impl Default for PgConnectOptions {
@@ -111,7 +173,10 @@ impl PgConnectOptions {
     ///  * `PGDATABASE`
     ///  * `PGSSLROOTCERT`
     ///  * `PGSSLMODE`
+    ///  * `PGGSSENCMODE`
+    ///  * `PGKRBSRVNAME`
     ///  * `PGAPPNAME`
+    ///  * `PGTARGETSESSIONATTRS`
     ///
     /// # Example
     ///
@@ -147,11 +212,26 @@ impl PgConnectOptions {
                 .ok()
                 .and_then(|v| v.parse().ok())
                 .unwrap_or_default(),
+            gss_enc_mode: var("PGGSSENCMODE")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or_default(),
+            krbsrvname: var("PGKRBSRVNAME").unwrap_or_else(|_| "postgres".into()),
             statement_cache_capacity: 100,
+            prefer_simple_protocol: false,
             application_name: var("PGAPPNAME").ok(),
             extra_float_digits: Some("3".into()),
             log_settings: Default::default(),
             options: var("PGOPTIONS").ok(),
+            startup_parameters: BTreeMap::new(),
+            target_session_attrs: var("PGTARGETSESSIONATTRS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or_default(),
+            type_cache: Arc::default(),
+            auto_explain: None,
+            #[cfg(feature = "replication")]
+            replication: false,
         }
     }
 
@@ -328,6 +408,69 @@ impl PgConnectOptions {
         self
     }
 
+    /// Sets whether or with what priority a GSSAPI encrypted TCP/IP connection will be negotiated
+    /// with the server.
+    ///
+    /// By default, the GSSAPI encryption mode is [`Prefer`](PgGssEncMode::Prefer), and the
+    /// client will attempt a GSSAPI encrypted connection if credentials are available, falling
+    /// back to TLS (as configured by [`ssl_mode`](Self::ssl_mode)) or an unencrypted connection.
+    ///
+    /// GSSAPI encryption requires the `postgres-gssapi` feature; connecting with
+    /// [`Require`](PgGssEncMode::Require) returns an error if it is not enabled.
+    ///
+    /// Ignored for Unix domain socket communication.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use sqlx_core::postgres::{PgGssEncMode, PgConnectOptions};
+    /// let options = PgConnectOptions::new()
+    ///     .gss_enc_mode(PgGssEncMode::Require);
+    /// ```
+    pub fn gss_enc_mode(mut self, mode: PgGssEncMode) -> Self {
+        self.gss_enc_mode = mode;
+        self
+    }
+
+    /// Sets the kind of server session that is acceptable.
+    ///
+    /// Once connected, the session is checked against the requested attributes, using the
+    /// `in_hot_standby` and `default_transaction_read_only` parameters reported by PostgreSQL 14
+    /// and newer, or by querying the server on older versions. If the session doesn't match,
+    /// the connection is closed and the next address of the host is tried, e.g. when its name
+    /// resolves to a primary and its replicas; an error is returned if none of them match.
+    ///
+    /// By default, any session is accepted.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use sqlx_core::postgres::{PgTargetSessionAttrs, PgConnectOptions};
+    /// let options = PgConnectOptions::new()
+    ///     .target_session_attrs(PgTargetSessionAttrs::ReadWrite);
+    /// ```
+    pub fn target_session_attrs(mut self, attrs: PgTargetSessionAttrs) -> Self {
+        self.target_session_attrs = attrs;
+        self
+    }
+
+    /// Sets the Kerberos service name to use when authenticating with GSSAPI.
+    ///
+    /// This must match the service name specified in the server configuration for Kerberos
+    /// authentication to succeed. Defaults to `postgres`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use sqlx_core::postgres::PgConnectOptions;
+    /// let options = PgConnectOptions::new()
+    ///     .krbsrvname("postgres-prod");
+    /// ```
+    pub fn krbsrvname(mut self, name: &str) -> Self {
+        self.krbsrvname = name.into();
+        self
+    }
+
     /// Sets the capacity of the connection's statement cache in a number of stored
     /// distinct statements. Caching is handled using LRU, meaning when the
     /// amount of queries hits the defined limit, the oldest statement will get
@@ -339,6 +482,45 @@ impl PgConnectOptions {
         self
     }
 
+    /// Sets whether to avoid named prepared statements, which is needed to connect through
+    /// a connection pooler like PgBouncer in transaction pooling mode (unless its
+    /// `max_prepared_statements` setting is used).
+    ///
+    /// When enabled:
+    ///
+    /// * queries without bind parameters are sent with the simple query protocol, so their
+    ///   results are decoded from the text format instead of the binary format;
+    /// * queries with bind parameters use the unnamed statement, which is parsed again in the
+    ///   same round-trip as it is bound and executed, so it doesn't matter if the pooler moved
+    ///   the session to another server connection after the statement was described;
+    /// * the statement cache is disabled, regardless of
+    ///   [`statement_cache_capacity`](Self::statement_cache_capacity).
+    ///
+    /// This trades performance for compatibility: every query with bind parameters takes an
+    /// extra round-trip to be described, and the server plans every query again. The text format
+    /// also has its limits; custom types that the connection hasn't seen before, such as
+    /// composite types, can't be decoded from the results of queries without bind parameters.
+    /// The nullability of columns reported by [`describe`](crate::executor::Executor::describe)
+    /// is not inferred from the query plan either.
+    ///
+    /// Bind parameters are always sent to the server separately from the query, and never
+    /// interpolated into the query string.
+    ///
+    /// The default is `false`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use sqlx_core::postgres::PgConnectOptions;
+    /// let options = PgConnectOptions::new()
+    ///     .port(6432)
+    ///     .prefer_simple_protocol(true);
+    /// ```
+    pub fn prefer_simple_protocol(mut self, prefer: bool) -> Self {
+        self.prefer_simple_protocol = prefer;
+        self
+    }
+
     /// Sets the application name. Defaults to None
     ///
     /// # Example
@@ -433,6 +615,95 @@ impl PgConnectOptions {
         self
     }
 
+    /// Set a run-time parameter to send in the startup message of the connection.
+    ///
+    /// Unlike [`options`](Self::options), which are passed to the server as command-line
+    /// arguments, each parameter is sent as its own name-value pair, the same as libpq does for
+    /// `application_name`. The values apply from the start of the session, including while it
+    /// authenticates, so there is no need to `SET` them in an `after_connect` hook.
+    ///
+    /// Setting a parameter again replaces its value. If the server doesn't recognize a parameter,
+    /// connecting fails with the error it returns.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use sqlx_core::postgres::PgConnectOptions;
+    /// let options = PgConnectOptions::new()
+    ///     .startup_parameter("search_path", "app,public")
+    ///     .startup_parameter("lock_timeout", "10s");
+    /// ```
+    pub fn startup_parameter(mut self, name: &str, value: &str) -> Self {
+        self.startup_parameters.insert(name.into(), value.into());
+        self
+    }
+
+    /// Sets the `statement_timeout` of the connection, aborting any statement that takes longer
+    /// than `timeout` to execute.
+    ///
+    /// This is sent in the startup message, see [`startup_parameter`](Self::startup_parameter).
+    /// The timeout is rounded down to whole milliseconds; zero disables it.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use std::time::Duration;
+    /// # use sqlx_core::postgres::PgConnectOptions;
+    /// let options = PgConnectOptions::new()
+    ///     .statement_timeout(Duration::from_secs(30));
+    /// ```
+    pub fn statement_timeout(self, timeout: Duration) -> Self {
+        let millis = timeout.as_millis().to_string();
+        self.startup_parameter("statement_timeout", &millis)
+    }
+
+    /// Log the plan of the statements which take longer than the duration of slow statements,
+    /// see [`ConnectOptions::log_slow_statements`](crate::connection::ConnectOptions::log_slow_statements).
+    ///
+    /// The plan is logged at the level of slow statements, with the target `sqlx::explain`,
+    /// once the statement completed. It is the generic plan from
+    /// [`PgConnection::explain`](crate::postgres::PgConnection::explain), without the values of
+    /// the arguments, as the statement is not run a second time. Statements which can't be
+    /// explained, e.g. `CREATE TABLE`, are skipped, and so are those in a failed transaction.
+    ///
+    /// At most one statement is explained per `min_interval`, by all the connections opened with
+    /// these options, as explaining costs a few round trips to the server.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use std::time::Duration;
+    /// # use sqlx_core::postgres::PgConnectOptions;
+    /// let options = PgConnectOptions::new()
+    ///     .explain_slow_statements(Duration::from_secs(60));
+    /// ```
+    pub fn explain_slow_statements(mut self, min_interval: Duration) -> Self {
+        self.auto_explain = Some(Arc::new(PgAutoExplain::new(min_interval)));
+        self
+    }
+
+    /// Open the connection in logical replication mode (`replication=database`).
+    ///
+    /// Such a connection accepts the [replication commands] used to create and stream from
+    /// replication slots, as well as ordinary SQL when not streaming. The user must have the
+    /// `REPLICATION` attribute and the server must be configured with `wal_level = logical`.
+    ///
+    /// [replication commands]: https://www.postgresql.org/docs/current/protocol-replication.html
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use sqlx_core::postgres::PgConnectOptions;
+    /// let options = PgConnectOptions::new()
+    ///     .database("mydb")
+    ///     .replication(true);
+    /// ```
+    #[cfg(feature = "replication")]
+    pub fn replication(mut self, replication: bool) -> Self {
+        self.replication = replication;
+        self
+    }
+
     /// We try using a socket if hostname starts with `/` or if socket parameter
     /// is specified.
     pub(crate) fn fetch_socket(&self) -> Option<String> {
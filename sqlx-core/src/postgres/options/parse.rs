@@ -1,6 +1,8 @@
+use crate::connection::{path_in_url, set_url_host, unrepresentable_in_url};
 use crate::error::Error;
 use crate::postgres::PgConnectOptions;
 use percent_encoding::percent_decode_str;
+use std::borrow::Cow;
 use std::net::IpAddr;
 use std::str::FromStr;
 use url::Url;
@@ -47,6 +49,9 @@ impl FromStr for PgConnectOptions {
             options = options.database(path);
         }
 
+        // the options of the URL replace those of `PGOPTIONS`, as libpq does
+        let mut options_in_url = false;
+
         for (key, value) in url.query_pairs().into_iter() {
             match &*key {
                 "sslmode" | "ssl-mode" => {
@@ -57,11 +62,21 @@ impl FromStr for PgConnectOptions {
                     options = options.ssl_root_cert(&*value);
                 }
 
+                "gssencmode" | "gss-enc-mode" => {
+                    options = options.gss_enc_mode(value.parse().map_err(Error::config)?);
+                }
+
+                "krbsrvname" => options = options.krbsrvname(&value),
+
                 "statement-cache-capacity" => {
                     options =
                         options.statement_cache_capacity(value.parse().map_err(Error::config)?);
                 }
 
+                "prefer_simple_protocol" | "prefer-simple-protocol" => {
+                    options = options.prefer_simple_protocol(value.parse().map_err(Error::config)?);
+                }
+
                 "host" => {
                     if value.starts_with("/") {
                         options = options.socket(&*value);
@@ -85,21 +100,66 @@ impl FromStr for PgConnectOptions {
 
                 "application_name" => options = options.application_name(&*value),
 
+                "target_session_attrs" | "target-session-attrs" => {
+                    options = options.target_session_attrs(value.parse().map_err(Error::config)?);
+                }
+
                 "options" => {
-                    if let Some(options) = options.options.as_mut() {
-                        options.push(' ');
-                        options.push_str(&*value);
-                    } else {
-                        options.options = Some(value.to_string());
+                    match options.options.as_mut() {
+                        Some(options) if options_in_url => {
+                            options.push(' ');
+                            options.push_str(&value);
+                        }
+                        _ => options.options = Some(value.to_string()),
                     }
+
+                    options_in_url = true;
+                }
+
+                "extra_float_digits" | "extra-float-digits" => {
+                    options = if value.is_empty() {
+                        options.extra_float_digits(None)
+                    } else {
+                        options.extra_float_digits(value.parse::<i8>().map_err(Error::config)?)
+                    };
                 }
 
+                #[cfg(feature = "replication")]
+                "replication" => match &*value {
+                    "database" => options = options.replication(true),
+                    "false" | "off" | "no" | "0" => options = options.replication(false),
+                    _ => {
+                        return Err(Error::Configuration(
+                            format!(
+                                "unsupported replication mode {:?}, only `database` (logical replication) is supported",
+                                value
+                            )
+                            .into(),
+                        ))
+                    }
+                },
+
                 k if k.starts_with("options[") => {
                     if let Some(key) = k.strip_prefix("options[").unwrap().strip_suffix(']') {
+                        if !options_in_url {
+                            options.options = None;
+                            options_in_url = true;
+                        }
+
                         options = options.options([(key, &*value)]);
                     }
                 }
 
+                k if k.starts_with("startup_parameter[") => {
+                    if let Some(name) = k
+                        .strip_prefix("startup_parameter[")
+                        .unwrap()
+                        .strip_suffix(']')
+                    {
+                        options = options.startup_parameter(name, &value);
+                    }
+                }
+
                 _ => log::warn!("ignoring unrecognized connect parameter: {}={}", key, value),
             }
         }
@@ -110,6 +170,114 @@ impl FromStr for PgConnectOptions {
     }
 }
 
+impl PgConnectOptions {
+    /// The URL which parses to these options, see [`ConnectOptions::to_url_lossless`].
+    ///
+    /// [`ConnectOptions::to_url_lossless`]: crate::connection::ConnectOptions::to_url_lossless
+    pub(crate) fn build_url(&self) -> Result<Url, Error> {
+        let mut url = Url::parse("postgres://").expect("BUG: failed to parse a constant URL");
+        let mut params: Vec<(Cow<'_, str>, &str)> = Vec::new();
+
+        let port = self.port.to_string();
+        let statement_cache_capacity = self.statement_cache_capacity.to_string();
+
+        // a host which is a path is parsed back as the socket, which it is equivalent to
+        if !self.host.starts_with('/') && set_url_host(&mut url, &self.host) {
+            let _ = url.set_port(Some(self.port));
+
+            if self.username.is_empty() || url.set_username(&self.username).is_err() {
+                params.push(("user".into(), &self.username));
+            }
+
+            match &self.password {
+                Some(password) if !password.is_empty() => {
+                    let _ = url.set_password(Some(password));
+                }
+                Some(password) => params.push(("password".into(), password)),
+                None => {}
+            }
+        } else {
+            params.push(("host".into(), &self.host));
+            params.push(("port".into(), &port));
+            params.push(("user".into(), &self.username));
+
+            if let Some(password) = &self.password {
+                params.push(("password".into(), password));
+            }
+        }
+
+        if let Some(database) = &self.database {
+            url.set_path(&format!("/{}", database));
+
+            if database.is_empty() || database.starts_with('/') || url.path()[1..] != **database {
+                url.set_path("");
+                params.push(("dbname".into(), database));
+            }
+        }
+
+        if let Some(socket) = &self.socket {
+            if !socket.is_absolute() {
+                return Err(unrepresentable_in_url(
+                    "socket",
+                    "as a URL can only have the absolute path of a socket",
+                ));
+            }
+
+            params.push(("host".into(), path_in_url("socket", socket)?));
+        }
+
+        params.push(("sslmode".into(), self.ssl_mode.as_str()));
+
+        if let Some(cert) = &self.ssl_root_cert {
+            params.push(("sslrootcert".into(), cert.path_in_url("sslrootcert")?));
+        }
+
+        params.push(("gssencmode".into(), self.gss_enc_mode.as_str()));
+        params.push(("krbsrvname".into(), &self.krbsrvname));
+        params.push((
+            "target_session_attrs".into(),
+            self.target_session_attrs.as_str(),
+        ));
+
+        if self.statement_cache_capacity != 100 {
+            params.push(("statement-cache-capacity".into(), &statement_cache_capacity));
+        }
+
+        if self.prefer_simple_protocol {
+            params.push(("prefer_simple_protocol".into(), "true"));
+        }
+
+        if let Some(application_name) = &self.application_name {
+            params.push(("application_name".into(), application_name));
+        }
+
+        if let Some(options) = &self.options {
+            params.push(("options".into(), options));
+        }
+
+        if self.extra_float_digits.as_deref() != Some("3") {
+            params.push((
+                "extra_float_digits".into(),
+                self.extra_float_digits.as_deref().unwrap_or_default(),
+            ));
+        }
+
+        for (name, value) in &self.startup_parameters {
+            params.push((format!("startup_parameter[{}]", name).into(), value));
+        }
+
+        #[cfg(feature = "replication")]
+        if self.replication {
+            params.push(("replication".into(), "database"));
+        }
+
+        url.query_pairs_mut()
+            .extend_pairs(params.iter().map(|(key, value)| (&**key, value)));
+
+        Ok(url)
+    }
+}
+
 #[test]
 fn it_parses_socket_correctly_from_parameter() {
     let url = "postgres:///?host=/var/run/postgres/";
@@ -232,3 +400,262 @@ fn it_parses_sqlx_options_correctly() {
         opts.options
     );
 }
+
+#[test]
+#[cfg(feature = "replication")]
+fn it_parses_replication_mode() {
+    let opts = PgConnectOptions::from_str("postgres:///?replication=database").unwrap();
+    assert!(opts.replication);
+
+    let opts = PgConnectOptions::from_str("postgres:///?replication=false").unwrap();
+    assert!(!opts.replication);
+
+    // physical replication is not supported
+    assert!(PgConnectOptions::from_str("postgres:///?replication=true").is_err());
+}
+
+#[test]
+fn it_parses_gss_parameters() {
+    use crate::postgres::PgGssEncMode;
+
+    let opts =
+        PgConnectOptions::from_str("postgres:///?gssencmode=require&krbsrvname=pg").unwrap();
+
+    assert_eq!(opts.gss_enc_mode, PgGssEncMode::Require);
+    assert_eq!(opts.krbsrvname, "pg");
+
+    assert!(PgConnectOptions::from_str("postgres:///?gssencmode=allow").is_err());
+}
+
+#[test]
+fn it_parses_target_session_attrs() {
+    use crate::postgres::PgTargetSessionAttrs;
+
+    let opts = PgConnectOptions::from_str("postgres:///?target_session_attrs=read-write").unwrap();
+
+    assert_eq!(opts.target_session_attrs, PgTargetSessionAttrs::ReadWrite);
+
+    let opts =
+        PgConnectOptions::from_str("postgres:///?target_session_attrs=prefer-standby").unwrap();
+
+    assert_eq!(
+        opts.target_session_attrs,
+        PgTargetSessionAttrs::PreferStandby
+    );
+
+    assert!(PgConnectOptions::from_str("postgres:///?target_session_attrs=master").is_err());
+}
+
+#[test]
+fn it_parses_prefer_simple_protocol() {
+    let opts = PgConnectOptions::from_str("postgres:///?prefer_simple_protocol=true").unwrap();
+    assert!(opts.prefer_simple_protocol);
+
+    let opts = PgConnectOptions::from_str("postgres:///").unwrap();
+    assert!(!opts.prefer_simple_protocol);
+
+    assert!(PgConnectOptions::from_str("postgres:///?prefer_simple_protocol=1").is_err());
+}
+
+#[test]
+fn it_converts_options_to_a_url_and_back() {
+    use crate::connection::ConnectOptions;
+    use crate::postgres::{PgGssEncMode, PgSslMode, PgTargetSessionAttrs};
+    use rand::rngs::StdRng;
+    use rand::seq::SliceRandom;
+    use rand::{Rng, SeedableRng};
+
+    // strings which have to be escaped in a URL, or which its authority or path can't hold
+    const URL_STRINGS: &[&str] = &[
+        "",
+        "postgres",
+        "db.example.com",
+        "LocalHost",
+        "10.0.0.1",
+        "[::1]",
+        "::1",
+        "user@host",
+        "p@ss:w/o?r#d",
+        "with space",
+        "a+b&c=d;e",
+        "100%",
+        "café",
+        "/starts/with/slash",
+    ];
+
+    let mut rng = StdRng::seed_from_u64(0x5f3759df);
+
+    for _ in 0..5000 {
+        let mut string = || *URL_STRINGS.choose(&mut rng).unwrap();
+        let (host, username, password, database) = (string(), string(), string(), string());
+        let (krbsrvname, application_name, name, value) = (string(), string(), string(), string());
+
+        let mut options = PgConnectOptions::new()
+            .host(host.trim_start_matches('/'))
+            .port(rng.gen())
+            .username(username)
+            .ssl_mode(
+                *[
+                    PgSslMode::Disable,
+                    PgSslMode::Allow,
+                    PgSslMode::Prefer,
+                    PgSslMode::Require,
+                    PgSslMode::VerifyCa,
+                    PgSslMode::VerifyFull,
+                ]
+                .choose(&mut rng)
+                .unwrap(),
+            )
+            .gss_enc_mode(
+                *[
+                    PgGssEncMode::Disable,
+                    PgGssEncMode::Prefer,
+                    PgGssEncMode::Require,
+                ]
+                .choose(&mut rng)
+                .unwrap(),
+            )
+            .target_session_attrs(
+                *[
+                    PgTargetSessionAttrs::Any,
+                    PgTargetSessionAttrs::ReadWrite,
+                    PgTargetSessionAttrs::ReadOnly,
+                    PgTargetSessionAttrs::Primary,
+                    PgTargetSessionAttrs::Standby,
+                    PgTargetSessionAttrs::PreferStandby,
+                ]
+                .choose(&mut rng)
+                .unwrap(),
+            )
+            .krbsrvname(krbsrvname)
+            .statement_cache_capacity(*[0, 100, 250].choose(&mut rng).unwrap())
+            .prefer_simple_protocol(rng.gen())
+            .extra_float_digits(
+                *[None, Some(-15), Some(0), Some(3)]
+                    .choose(&mut rng)
+                    .unwrap(),
+            );
+
+        if rng.gen() {
+            options = options.password(password);
+        }
+
+        if rng.gen() {
+            options = options.database(database);
+        }
+
+        if rng.gen() {
+            options = options.socket(
+                *["/var/run/postgresql", "/tmp/with space/"]
+                    .choose(&mut rng)
+                    .unwrap(),
+            );
+        }
+
+        if rng.gen() {
+            options = options.ssl_root_cert("certs/root ca.crt");
+        }
+
+        if rng.gen() {
+            options = options.application_name(application_name);
+        }
+
+        if rng.gen() {
+            options = options.options([("search_path", "app,public"), ("geqo", "off")]);
+        }
+
+        if rng.gen() {
+            options = options
+                .startup_parameter(name, value)
+                .statement_timeout(std::time::Duration::from_secs(30));
+        }
+
+        #[cfg(feature = "replication")]
+        {
+            options = options.replication(rng.gen());
+        }
+
+        let url = options.to_url_lossless().unwrap();
+        let parsed = PgConnectOptions::from_str(url.as_str()).unwrap();
+
+        assert_eq!(format!("{:?}", parsed), format!("{:?}", options), "{}", url);
+        assert_eq!(parsed.password, options.password, "{}", url);
+    }
+}
+
+#[test]
+fn it_converts_options_which_a_url_cant_represent() {
+    use crate::connection::ConnectOptions;
+    use std::path::Path;
+
+    // a host which is the directory of a socket is parsed back as the socket
+    let options = PgConnectOptions::new().host("/var/run/postgresql");
+    let url = options.to_url_lossless().unwrap();
+    let parsed = PgConnectOptions::from_str(url.as_str()).unwrap();
+    assert_eq!(
+        parsed.socket.as_deref(),
+        Some(Path::new("/var/run/postgresql"))
+    );
+    assert_eq!(parsed.fetch_socket(), options.fetch_socket());
+
+    let options = PgConnectOptions::new().socket("run/postgresql");
+    assert!(matches!(
+        options.to_url_lossless(),
+        Err(Error::Configuration(_))
+    ));
+
+    let options =
+        PgConnectOptions::new().ssl_root_cert_from_pem(b"-----BEGIN CERTIFICATE-----".to_vec());
+    assert!(matches!(
+        options.to_url_lossless(),
+        Err(Error::Configuration(_))
+    ));
+}
+
+#[test]
+fn it_replaces_pgoptions_with_the_options_of_the_url() {
+    let mut options = PgConnectOptions::from_str("postgres:///").unwrap();
+    options.options = Some("-c geqo=on".into());
+
+    let url = "postgres:///?options=-c%20geqo%3Doff&options[search_path]=app";
+    let parsed = PgConnectOptions::from_str(url).unwrap();
+    assert_eq!(
+        parsed.options.as_deref(),
+        Some("-c geqo=off -c search_path=app")
+    );
+}
+
+#[test]
+fn it_parses_startup_parameters_and_extra_float_digits() {
+    let url = "postgres:///?startup_parameter[lock_timeout]=10s&extra_float_digits=";
+    let opts = PgConnectOptions::from_str(url).unwrap();
+
+    assert_eq!(opts.startup_parameters["lock_timeout"], "10s");
+    assert_eq!(opts.extra_float_digits, None);
+
+    let opts = PgConnectOptions::from_str("postgres:///?extra_float_digits=-2").unwrap();
+    assert_eq!(opts.extra_float_digits.as_deref(), Some("-2"));
+}
+
+#[test]
+fn it_redacts_the_password() {
+    use crate::connection::ConnectOptions;
+
+    for host in ["localhost", "::1"] {
+        let options = PgConnectOptions::new()
+            .host(host)
+            .username("postgres")
+            .password("hunter2");
+
+        assert!(!format!("{:?}", options).contains("hunter2"));
+        assert!(options
+            .to_url_lossless()
+            .unwrap()
+            .as_str()
+            .contains("hunter2"));
+
+        let url = options.to_url_redacted().unwrap();
+        assert!(!url.as_str().contains("hunter2"), "{}", url);
+        assert!(url.as_str().contains("****"), "{}", url);
+    }
+}
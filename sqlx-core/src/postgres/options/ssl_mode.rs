@@ -28,6 +28,19 @@ pub enum PgSslMode {
     VerifyFull,
 }
 
+impl PgSslMode {
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            PgSslMode::Disable => "disable",
+            PgSslMode::Allow => "allow",
+            PgSslMode::Prefer => "prefer",
+            PgSslMode::Require => "require",
+            PgSslMode::VerifyCa => "verify-ca",
+            PgSslMode::VerifyFull => "verify-full",
+        }
+    }
+}
+
 impl Default for PgSslMode {
     fn default() -> Self {
         PgSslMode::Prefer
@@ -0,0 +1,70 @@
+use crate::error::Error;
+use std::str::FromStr;
+
+/// Options for controlling which kind of server session is acceptable for a PostgreSQL
+/// connection, matching libpq's `target_session_attrs`.
+///
+/// It is used by the [`target_session_attrs`](super::PgConnectOptions::target_session_attrs)
+/// method.
+///
+/// The properties of the session are checked once the connection is established; a server that
+/// doesn't match is disconnected from, and the next address of the host is tried. Connecting
+/// fails if none of them match.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PgTargetSessionAttrs {
+    /// Any successful connection is acceptable.
+    #[default]
+    Any,
+
+    /// The session must accept read-write transactions by default, i.e. the server must not be
+    /// in hot standby mode and `default_transaction_read_only` must be `off`.
+    ReadWrite,
+
+    /// The session must not accept read-write transactions by default (the converse of
+    /// `ReadWrite`).
+    ReadOnly,
+
+    /// The server must not be in hot standby mode.
+    Primary,
+
+    /// The server must be in hot standby mode.
+    Standby,
+
+    /// Prefer a server in hot standby mode, but accept any server if none of the addresses of
+    /// the host is one.
+    PreferStandby,
+}
+
+impl PgTargetSessionAttrs {
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            PgTargetSessionAttrs::Any => "any",
+            PgTargetSessionAttrs::ReadWrite => "read-write",
+            PgTargetSessionAttrs::ReadOnly => "read-only",
+            PgTargetSessionAttrs::Primary => "primary",
+            PgTargetSessionAttrs::Standby => "standby",
+            PgTargetSessionAttrs::PreferStandby => "prefer-standby",
+        }
+    }
+}
+
+impl FromStr for PgTargetSessionAttrs {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Error> {
+        Ok(match &*s.to_ascii_lowercase() {
+            "any" => PgTargetSessionAttrs::Any,
+            "read-write" => PgTargetSessionAttrs::ReadWrite,
+            "read-only" => PgTargetSessionAttrs::ReadOnly,
+            "primary" => PgTargetSessionAttrs::Primary,
+            "standby" => PgTargetSessionAttrs::Standby,
+            "prefer-standby" => PgTargetSessionAttrs::PreferStandby,
+
+            _ => {
+                return Err(Error::Configuration(
+                    format!("unknown value {:?} for `target_session_attrs`", s).into(),
+                ));
+            }
+        })
+    }
+}
@@ -11,6 +11,12 @@ impl PgQueryResult {
     }
 }
 
+impl crate::dyn_connection::DynQueryResult for PgQueryResult {
+    fn rows_affected(&self) -> u64 {
+        self.rows_affected
+    }
+}
+
 impl Extend<PgQueryResult> for PgQueryResult {
     fn extend<T: IntoIterator<Item = PgQueryResult>>(&mut self, iter: T) {
         for elem in iter {
@@ -0,0 +1,75 @@
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+use crate::error::Error;
+
+/// A position in the write-ahead log (`pg_lsn` in Postgres).
+///
+/// Displayed and parsed in the same `XXXXXXXX/XXXXXXXX` hexadecimal format as Postgres uses.
+///
+/// The zero LSN, `0/0`, is used by Postgres to mean an invalid or unknown position.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct PgLsn(
+    /// The raw 64-bit position.
+    pub u64,
+);
+
+impl PgLsn {
+    /// The invalid or unknown position, `0/0`.
+    pub const INVALID: PgLsn = PgLsn(0);
+}
+
+impl Display for PgLsn {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{:X}/{:X}", self.0 >> 32, self.0 & 0xFFFF_FFFF)
+    }
+}
+
+impl FromStr for PgLsn {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid =
+            || Error::Decode(format!("invalid LSN, expected `XXXXXXXX/XXXXXXXX`: {:?}", s).into());
+
+        let (high, low) = s.split_once('/').ok_or_else(invalid)?;
+
+        let high = u32::from_str_radix(high, 16).map_err(|_| invalid())?;
+        let low = u32::from_str_radix(low, 16).map_err(|_| invalid())?;
+
+        Ok(PgLsn((u64::from(high) << 32) | u64::from(low)))
+    }
+}
+
+impl From<u64> for PgLsn {
+    fn from(lsn: u64) -> Self {
+        PgLsn(lsn)
+    }
+}
+
+impl From<PgLsn> for u64 {
+    fn from(lsn: PgLsn) -> Self {
+        lsn.0
+    }
+}
+
+#[test]
+fn test_lsn_round_trip() {
+    assert_eq!("0/0".parse::<PgLsn>().unwrap(), PgLsn::INVALID);
+    assert_eq!(
+        "16/B374D848".parse::<PgLsn>().unwrap(),
+        PgLsn(0x16_B374_D848)
+    );
+    assert_eq!(
+        "FFFFFFFF/FFFFFFFF".parse::<PgLsn>().unwrap(),
+        PgLsn(u64::MAX)
+    );
+
+    assert_eq!(PgLsn(0x16_B374_D848).to_string(), "16/B374D848");
+    assert_eq!(PgLsn(0x1_0000_0001).to_string(), "1/1");
+
+    assert!("16B374D848".parse::<PgLsn>().is_err());
+    assert!("1/2/3".parse::<PgLsn>().is_err());
+    assert!("G/0".parse::<PgLsn>().is_err());
+    assert!("100000000/0".parse::<PgLsn>().is_err());
+}
@@ -0,0 +1,138 @@
+//! Consuming [logical replication] streams from Postgres.
+//!
+//! A replication connection is opened by enabling
+//! [`PgConnectOptions::replication`][crate::postgres::PgConnectOptions::replication] (or adding
+//! `replication=database` to the connection URL). Changes can then be streamed from a replication
+//! slot with [`PgConnection::start_replication`], and the output of the built-in `pgoutput`
+//! plugin decoded with [`PgOutputMessage::decode`].
+//!
+//! [logical replication]: https://www.postgresql.org/docs/current/logicaldecoding.html
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::database::Database;
+use crate::error::{Error, Result};
+use crate::executor::Executor;
+use crate::postgres::{PgConnection, Postgres};
+use crate::row::Row;
+
+mod lsn;
+mod pgoutput;
+mod stream;
+
+pub use lsn::PgLsn;
+pub use pgoutput::{
+    PgOutputBegin, PgOutputColumn, PgOutputCommit, PgOutputDelete, PgOutputInsert, PgOutputMessage,
+    PgOutputOldRow, PgOutputOrigin, PgOutputRelation, PgOutputTruncate, PgOutputType,
+    PgOutputUpdate, PgOutputValue,
+};
+pub use stream::{PgPrimaryKeepalive, PgReplicationMessage, PgReplicationStream, PgXLogData};
+
+// seconds from the Unix epoch to the Postgres epoch, 2000-01-01 00:00:00 UTC
+const PG_EPOCH_OFFSET: Duration = Duration::from_secs(946_684_800);
+
+/// A replication slot created by [PgConnection::create_replication_slot].
+#[derive(Debug, Clone)]
+pub struct PgReplicationSlot {
+    pub slot_name: String,
+
+    /// The position from which the slot will stream changes; this is the earliest position that
+    /// can be passed to [PgConnection::start_replication].
+    pub consistent_point: PgLsn,
+
+    /// The snapshot exported by the command, which can be used with `SET TRANSACTION SNAPSHOT`
+    /// to read the state of the database as of `consistent_point`.
+    pub snapshot_name: Option<String>,
+
+    pub output_plugin: String,
+}
+
+impl PgConnection {
+    /// Create a logical replication slot named `name` which decodes changes with the output
+    /// plugin `plugin` (e.g. `pgoutput` or `wal2json`).
+    ///
+    /// A `temporary` slot is dropped automatically when this connection is closed.
+    ///
+    /// The connection must have been opened in replication mode; see
+    /// [`PgConnectOptions::replication`][crate::postgres::PgConnectOptions::replication].
+    pub async fn create_replication_slot(
+        &mut self,
+        name: &str,
+        plugin: &str,
+        temporary: bool,
+    ) -> Result<PgReplicationSlot> {
+        let statement = format!(
+            "CREATE_REPLICATION_SLOT {}{} LOGICAL {}",
+            Postgres::quote_identifier(name).map_err(Error::config)?,
+            if temporary { " TEMPORARY" } else { "" },
+            Postgres::quote_identifier(plugin).map_err(Error::config)?,
+        );
+
+        // replication commands are only accepted through the simple query protocol
+        let row = self.fetch_one(&*statement).await?;
+
+        let consistent_point: String = row.try_get("consistent_point")?;
+
+        Ok(PgReplicationSlot {
+            slot_name: row.try_get("slot_name")?,
+            consistent_point: consistent_point.parse().map_err(|e| {
+                err_protocol!("invalid consistent_point {:?}: {}", consistent_point, e)
+            })?,
+            snapshot_name: row.try_get("snapshot_name")?,
+            output_plugin: row.try_get("output_plugin")?,
+        })
+    }
+
+    /// Drop the replication slot named `name`.
+    ///
+    /// If `wait` is `true` and the slot is in use by another connection, this waits until it is
+    /// released instead of returning an error.
+    ///
+    /// The connection must have been opened in replication mode; see
+    /// [`PgConnectOptions::replication`][crate::postgres::PgConnectOptions::replication].
+    pub async fn drop_replication_slot(&mut self, name: &str, wait: bool) -> Result<()> {
+        let statement = format!(
+            "DROP_REPLICATION_SLOT {}{}",
+            Postgres::quote_identifier(name).map_err(Error::config)?,
+            if wait { " WAIT" } else { "" }
+        );
+
+        self.execute(&*statement).await?;
+
+        Ok(())
+    }
+}
+
+// timestamps in the replication protocol are microseconds since the Postgres epoch
+pub(crate) fn from_pg_timestamp(micros: i64) -> SystemTime {
+    let epoch = UNIX_EPOCH + PG_EPOCH_OFFSET;
+
+    if micros >= 0 {
+        epoch + Duration::from_micros(micros as u64)
+    } else {
+        epoch - Duration::from_micros(micros.unsigned_abs())
+    }
+}
+
+pub(crate) fn to_pg_timestamp(time: SystemTime) -> i64 {
+    let epoch = UNIX_EPOCH + PG_EPOCH_OFFSET;
+
+    match time.duration_since(epoch) {
+        Ok(since) => since.as_micros() as i64,
+        Err(e) => -(e.duration().as_micros() as i64),
+    }
+}
+
+#[test]
+fn test_pg_timestamp_round_trip() {
+    let epoch = UNIX_EPOCH + PG_EPOCH_OFFSET;
+
+    assert_eq!(from_pg_timestamp(0), epoch);
+    assert_eq!(to_pg_timestamp(epoch), 0);
+
+    for micros in [1, -1, 1_234_567_890_123, -1_234_567_890_123] {
+        assert_eq!(to_pg_timestamp(from_pg_timestamp(micros)), micros);
+    }
+
+    assert_eq!(to_pg_timestamp(UNIX_EPOCH), -946_684_800_000_000);
+}
@@ -0,0 +1,347 @@
+use std::cmp;
+use std::io;
+use std::ops::DerefMut;
+use std::time::{Duration, Instant, SystemTime};
+
+use bytes::{Buf, BufMut, Bytes};
+
+use crate::database::Database;
+use crate::error::{Error, Result};
+use crate::postgres::message::{CopyData, CopyDone, CopyResponse, MessageFormat, Query};
+use crate::postgres::replication::{from_pg_timestamp, to_pg_timestamp, PgLsn};
+use crate::postgres::{PgConnection, Postgres};
+
+/// A message received from the server while streaming changes from a replication slot.
+#[derive(Debug, Clone)]
+pub enum PgReplicationMessage {
+    /// A chunk of changes, in the format emitted by the output plugin of the slot.
+    XLogData(PgXLogData),
+
+    /// A heartbeat from the server.
+    ///
+    /// If [`reply_requested`][PgPrimaryKeepalive::reply_requested] is set, a status update has
+    /// already been sent in response by the time this is returned.
+    PrimaryKeepalive(PgPrimaryKeepalive),
+}
+
+/// A chunk of changes sent by the server (`XLogData`).
+#[derive(Debug, Clone)]
+pub struct PgXLogData {
+    /// The starting point of the data in the WAL.
+    pub wal_start: PgLsn,
+
+    /// The current end of the WAL on the server.
+    pub wal_end: PgLsn,
+
+    /// The server's system clock at the time of transmission.
+    pub server_time: SystemTime,
+
+    /// The data emitted by the output plugin.
+    ///
+    /// For the `pgoutput` plugin this can be decoded with
+    /// [`PgOutputMessage::decode`][crate::postgres::replication::PgOutputMessage::decode].
+    pub data: Bytes,
+}
+
+/// A heartbeat sent by the server (`Primary keepalive message`).
+#[derive(Debug, Clone)]
+pub struct PgPrimaryKeepalive {
+    /// The current end of the WAL on the server.
+    pub wal_end: PgLsn,
+
+    /// The server's system clock at the time of transmission.
+    pub server_time: SystemTime,
+
+    /// `true` if the server asked for a status update as soon as possible,
+    /// to avoid a timeout disconnect.
+    pub reply_requested: bool,
+}
+
+impl PgConnection {
+    /// Start streaming changes from the logical replication slot named `slot`,
+    /// beginning at `start_lsn`.
+    ///
+    /// `options` are passed to the output plugin of the slot; for `pgoutput` these should include
+    /// `proto_version` and `publication_names`.
+    ///
+    /// The connection must have been opened in replication mode; see
+    /// [`PgConnectOptions::replication`][crate::postgres::PgConnectOptions::replication].
+    pub async fn start_replication<K, V>(
+        &mut self,
+        slot: &str,
+        start_lsn: PgLsn,
+        options: impl IntoIterator<Item = (K, V)>,
+    ) -> Result<PgReplicationStream<&mut Self>>
+    where
+        K: AsRef<str>,
+        V: AsRef<str>,
+    {
+        PgReplicationStream::start(self, slot, start_lsn, options).await
+    }
+}
+
+/// A connection streaming changes from a logical replication slot.
+///
+/// Created by [PgConnection::start_replication].
+///
+/// Status updates are sent to the server automatically, every
+/// [`status_interval`][Self::status_interval] and whenever the server asks for one, as long as
+/// [`recv`][Self::recv] is called regularly. These report the position of the data received so
+/// far as written, but the flushed and applied positions are only reported as far as set with
+/// [`set_flushed_lsn`][Self::set_flushed_lsn] and [`set_applied_lsn`][Self::set_applied_lsn];
+/// the server will not discard WAL which has not been reported as flushed.
+///
+/// ### Note
+/// [PgReplicationStream::finish] should be called when finished, which sends a final status
+/// update. If the stream is dropped instead, it is ended the next time the connection is used.
+pub struct PgReplicationStream<C: DerefMut<Target = PgConnection>> {
+    conn: Option<C>,
+    written_lsn: PgLsn,
+    flushed_lsn: PgLsn,
+    applied_lsn: PgLsn,
+    status_interval: Option<Duration>,
+    last_status: Instant,
+}
+
+impl<C: DerefMut<Target = PgConnection>> PgReplicationStream<C> {
+    async fn start<K, V>(
+        mut conn: C,
+        slot: &str,
+        start_lsn: PgLsn,
+        options: impl IntoIterator<Item = (K, V)>,
+    ) -> Result<Self>
+    where
+        K: AsRef<str>,
+        V: AsRef<str>,
+    {
+        let mut statement = format!(
+            "START_REPLICATION SLOT {} LOGICAL {}",
+            Postgres::quote_identifier(slot).map_err(Error::config)?,
+            start_lsn
+        );
+
+        let options = options
+            .into_iter()
+            .map(|(key, value)| {
+                // the replication grammar has no escape strings, so values can't use
+                // `Postgres::quote_literal`
+                Ok(format!(
+                    "{} '{}'",
+                    Postgres::quote_identifier(key.as_ref()).map_err(Error::config)?,
+                    value.as_ref().replace('\'', "''")
+                ))
+            })
+            .collect::<Result<Vec<String>>>()?;
+
+        if !options.is_empty() {
+            statement.push_str(" (");
+            statement.push_str(&options.join(", "));
+            statement.push(')');
+        }
+
+        conn.wait_until_ready().await?;
+        conn.stream.send(Query(&statement)).await?;
+        conn.pending_ready_for_query_count += 1;
+
+        let _: CopyResponse = conn
+            .stream
+            .recv_expect(MessageFormat::CopyBothResponse)
+            .await?;
+
+        Ok(PgReplicationStream {
+            conn: Some(conn),
+            written_lsn: start_lsn,
+            flushed_lsn: PgLsn::INVALID,
+            applied_lsn: PgLsn::INVALID,
+            status_interval: Some(Duration::from_secs(10)),
+            last_status: Instant::now(),
+        })
+    }
+
+    /// Set how often status updates are sent to the server while receiving messages.
+    ///
+    /// This should be shorter than `wal_sender_timeout` on the server.
+    /// If `None`, status updates are only sent when requested by the server.
+    ///
+    /// The default is 10 seconds.
+    pub fn status_interval(mut self, interval: impl Into<Option<Duration>>) -> Self {
+        self.status_interval = interval.into();
+        self
+    }
+
+    /// Report that all changes up to `lsn` have been durably stored by the client.
+    ///
+    /// The server may then discard WAL up to this point; after reconnecting, streaming will
+    /// resume from here even if an earlier start position is given.
+    pub fn set_flushed_lsn(&mut self, lsn: PgLsn) {
+        self.flushed_lsn = lsn;
+        self.written_lsn = cmp::max(self.written_lsn, lsn);
+    }
+
+    /// Report that all changes up to `lsn` have been applied by the client.
+    pub fn set_applied_lsn(&mut self, lsn: PgLsn) {
+        self.applied_lsn = lsn;
+    }
+
+    /// Receive the next message from the server.
+    ///
+    /// Returns `None` if the server ended the stream (e.g. because it is shutting down).
+    pub async fn recv(&mut self) -> Result<Option<PgReplicationMessage>> {
+        if let Some(interval) = self.status_interval {
+            if self.conn.is_some() && self.last_status.elapsed() >= interval {
+                self.send_status_update(false).await?;
+            }
+        }
+
+        let conn: &mut PgConnection = match self.conn.as_deref_mut() {
+            Some(conn) => conn,
+            None => return Ok(None),
+        };
+
+        let message = conn.stream.recv().await?;
+
+        let mut data = match message.format {
+            MessageFormat::CopyData => message.decode::<CopyData<Bytes>>()?.0,
+
+            MessageFormat::CopyDone => {
+                // the server ended the stream; we must end our side too before it
+                // completes the command
+                conn.stream.send(CopyDone).await?;
+                conn.wait_until_ready().await?;
+                self.conn = None;
+
+                return Ok(None);
+            }
+
+            format => {
+                return Err(err_protocol!(
+                    "unexpected message format during replication: {:?}",
+                    format
+                ))
+            }
+        };
+
+        if data.is_empty() {
+            return Err(err_protocol!("received empty CopyData during replication"));
+        }
+
+        let message = match data.get_u8() {
+            b'w' => {
+                ensure_remaining(&data, 24)?;
+
+                let wal_start = PgLsn(data.get_u64());
+                let wal_end = PgLsn(data.get_u64());
+                let server_time = from_pg_timestamp(data.get_i64());
+
+                self.written_lsn = cmp::max(self.written_lsn, wal_start);
+
+                PgReplicationMessage::XLogData(PgXLogData {
+                    wal_start,
+                    wal_end,
+                    server_time,
+                    data,
+                })
+            }
+
+            b'k' => {
+                ensure_remaining(&data, 17)?;
+
+                let keepalive = PgPrimaryKeepalive {
+                    wal_end: PgLsn(data.get_u64()),
+                    server_time: from_pg_timestamp(data.get_i64()),
+                    reply_requested: data.get_u8() != 0,
+                };
+
+                if keepalive.reply_requested {
+                    self.send_status_update(false).await?;
+                }
+
+                PgReplicationMessage::PrimaryKeepalive(keepalive)
+            }
+
+            tag => {
+                return Err(err_protocol!(
+                    "unexpected replication message: {:?}",
+                    tag as char
+                ))
+            }
+        };
+
+        Ok(Some(message))
+    }
+
+    /// Immediately send a status update (`Standby status update`) to the server.
+    ///
+    /// If `reply_requested` is `true`, the server will respond with a keepalive message.
+    ///
+    /// Returns an error if the stream has ended, i.e. after [`recv`][Self::recv] returned `None`.
+    pub async fn send_status_update(&mut self, reply_requested: bool) -> Result<()> {
+        let mut update = Vec::with_capacity(34);
+
+        update.push(b'r');
+        update.put_u64(self.written_lsn.0);
+        update.put_u64(self.flushed_lsn.0);
+        update.put_u64(self.applied_lsn.0);
+        update.put_i64(to_pg_timestamp(SystemTime::now()));
+        update.push(reply_requested as u8);
+
+        self.conn
+            .as_deref_mut()
+            .ok_or_else(stream_ended)?
+            .stream
+            .send(CopyData(update))
+            .await?;
+
+        self.last_status = Instant::now();
+
+        Ok(())
+    }
+
+    /// Stop streaming and return the connection to normal operation.
+    ///
+    /// A final status update is sent first so the server knows the latest flushed position.
+    pub async fn finish(mut self) -> Result<()> {
+        if self.conn.is_none() {
+            // the server already ended the stream
+            return Ok(());
+        }
+
+        self.send_status_update(false).await?;
+
+        let mut conn = self.conn.take().ok_or_else(stream_ended)?;
+
+        conn.stream.send(CopyDone).await?;
+
+        // any data the server sent before it saw `CopyDone` is discarded
+        conn.wait_until_ready().await
+    }
+}
+
+fn stream_ended() -> Error {
+    Error::Io(io::Error::new(
+        io::ErrorKind::NotConnected,
+        "the replication stream has ended",
+    ))
+}
+
+fn ensure_remaining(data: &Bytes, len: usize) -> Result<()> {
+    if data.remaining() < len {
+        return Err(err_protocol!(
+            "replication message is truncated: expected at least {} more bytes, got {}",
+            len,
+            data.remaining()
+        ));
+    }
+
+    Ok(())
+}
+
+impl<C: DerefMut<Target = PgConnection>> Drop for PgReplicationStream<C> {
+    fn drop(&mut self) {
+        if let Some(mut conn) = self.conn.take() {
+            // end the stream; any data still in flight is discarded when the connection
+            // next waits for the `ReadyForQuery` that completes the command
+            conn.stream.write(CopyDone);
+        }
+    }
+}
@@ -1,5 +1,5 @@
 use crate::column::ColumnIndex;
-use crate::error::Error;
+use crate::error::{Error, ErrorContext};
 use crate::postgres::message::DataRow;
 use crate::postgres::statement::PgStatementMetadata;
 use crate::postgres::value::PgValueFormat;
@@ -12,6 +12,7 @@ pub struct PgRow {
     pub(crate) data: DataRow,
     pub(crate) format: PgValueFormat,
     pub(crate) metadata: Arc<PgStatementMetadata>,
+    pub(crate) context: Option<Arc<ErrorContext>>,
 }
 
 impl crate::row::private_row::Sealed for PgRow {}
@@ -38,6 +39,10 @@ impl Row for PgRow {
             value,
         })
     }
+
+    fn error_context(&self) -> Option<&Arc<ErrorContext>> {
+        self.context.as_ref()
+    }
 }
 
 impl ColumnIndex<PgRow> for &'_ str {
@@ -0,0 +1,66 @@
+use crate::column::Column;
+use crate::error::Error;
+use crate::postgres::{PgDynamicValue, PgRow, PgValueFormat};
+use crate::row::Row;
+use crate::serde_row::{column_error, unsupported_type, ColumnValue, ColumnValues};
+use crate::value::ValueRef;
+
+impl ColumnValues for PgRow {
+    fn column_value(&self, index: usize) -> Result<ColumnValue, Error> {
+        let name = self.columns()[index].name();
+        let value = self.try_get_raw(index)?;
+
+        if value.is_null() {
+            return Ok(ColumnValue::Null);
+        }
+
+        let value = value
+            .to_dynamic()
+            .map_err(|source| column_error(name, source))?;
+
+        convert(name, value)
+    }
+}
+
+fn convert(name: &str, value: PgDynamicValue) -> Result<ColumnValue, Error> {
+    Ok(match value {
+        PgDynamicValue::Null => ColumnValue::Null,
+        PgDynamicValue::Bool(value) => ColumnValue::Bool(value),
+        PgDynamicValue::Int2(value) => ColumnValue::I64(value.into()),
+        PgDynamicValue::Int4(value) => ColumnValue::I64(value.into()),
+        PgDynamicValue::Int8(value) => ColumnValue::I64(value),
+        PgDynamicValue::Float4(value) => ColumnValue::F64(value.into()),
+        PgDynamicValue::Float8(value) => ColumnValue::F64(value),
+        PgDynamicValue::Bytea(value) => ColumnValue::Bytes(value),
+
+        PgDynamicValue::Numeric(value)
+        | PgDynamicValue::Text(value)
+        | PgDynamicValue::Timestamp(value)
+        | PgDynamicValue::TimestampTz(value)
+        | PgDynamicValue::Date(value)
+        | PgDynamicValue::Time(value)
+        | PgDynamicValue::Uuid(value) => ColumnValue::String(value),
+
+        PgDynamicValue::Json(json) => {
+            ColumnValue::Json(serde_json::from_str(&json).map_err(|e| column_error(name, e))?)
+        }
+
+        PgDynamicValue::Array(values) => ColumnValue::Seq(
+            values
+                .into_iter()
+                .map(|value| convert(name, value))
+                .collect::<Result<_, _>>()?,
+        ),
+
+        // in the text format, a value is formatted by the server
+        PgDynamicValue::Other {
+            type_name,
+            format: PgValueFormat::Text,
+            bytes,
+        } => ColumnValue::String(
+            String::from_utf8(bytes).map_err(|_| unsupported_type(name, &type_name))?,
+        ),
+
+        PgDynamicValue::Other { type_name, .. } => return Err(unsupported_type(name, &type_name)),
+    })
+}
@@ -1,7 +1,9 @@
 use super::{PgColumn, PgTypeInfo};
 use crate::column::ColumnIndex;
+use crate::common::PreparedStatement;
 use crate::error::Error;
 use crate::ext::ustr::UStr;
+use crate::postgres::types::Oid;
 use crate::postgres::{PgArguments, Postgres};
 use crate::statement::Statement;
 use crate::HashMap;
@@ -13,6 +15,8 @@ use std::sync::Arc;
 pub struct PgStatement<'q> {
     pub(crate) sql: Cow<'q, str>,
     pub(crate) metadata: Arc<PgStatementMetadata>,
+    // the named statement prepared by a connection, unless the statement was detached from it
+    pub(crate) prepared: Option<PreparedStatement<Oid>>,
 }
 
 #[derive(Debug, Default)]
@@ -29,6 +33,7 @@ impl<'q> Statement<'q> for PgStatement<'q> {
         PgStatement::<'static> {
             sql: Cow::Owned(self.sql.clone().into_owned()),
             metadata: self.metadata.clone(),
+            prepared: self.prepared.clone(),
         }
     }
 
@@ -36,6 +41,10 @@ impl<'q> Statement<'q> for PgStatement<'q> {
         &self.sql
     }
 
+    fn detach(&mut self) {
+        self.prepared = None;
+    }
+
     fn parameters(&self) -> Option<Either<&[PgTypeInfo], usize>> {
         Some(Either::Left(&self.metadata.parameters))
     }
@@ -12,6 +12,7 @@ use crate::connection::Connection;
 use crate::error::Error;
 use crate::executor::Executor;
 use crate::pool::{Pool, PoolOptions};
+use crate::postgres::migrate::execute_statements;
 use crate::postgres::{PgConnectOptions, PgConnection, Postgres};
 use crate::query::query;
 use crate::query_scalar::query_scalar;
@@ -50,6 +51,13 @@ impl TestSupport for Postgres {
         })
     }
 
+    fn execute_without_transaction<'c>(
+        conn: &'c mut PgConnection,
+        sql: &'c str,
+    ) -> BoxFuture<'c, Result<(), Error>> {
+        Box::pin(execute_statements(conn, sql))
+    }
+
     fn cleanup_test_dbs() -> BoxFuture<'static, Result<Option<usize>, Error>> {
         Box::pin(async move {
             let url = dotenvy::var("DATABASE_URL").expect("DATABASE_URL must be set");
@@ -147,6 +155,16 @@ async fn test_context(args: &TestArgs) -> Result<TestContext<Postgres>, Error> {
     .fetch_one(&mut conn)
     .await?;
 
+    // Tests running in other processes, e.g. with `cargo nextest`, don't share `DO_CLEANUP`,
+    // so mark the database as in use with a lock on a dedicated connection, which Postgres
+    // releases when it's closed, even if the test process dies.
+    let mut guard = PgConnection::connect_with(master_pool.connect_options()).await?;
+
+    query("select pg_advisory_lock($1)")
+        .bind(lock_key(&new_db_name))
+        .execute(&mut guard)
+        .await?;
+
     conn.execute(&format!("create database {:?}", new_db_name)[..])
         .await?;
 
@@ -161,9 +179,18 @@ async fn test_context(args: &TestArgs) -> Result<TestContext<Postgres>, Error> {
             .parent(master_pool.clone()),
         connect_opts: master_pool.connect_options().clone().database(&new_db_name),
         db_name: new_db_name,
+        guard: Some(guard),
     })
 }
 
+/// The key of the advisory lock held by the test using a database, its number.
+fn lock_key(db_name: &str) -> i64 {
+    db_name
+        .trim_start_matches("_sqlx_test_")
+        .parse()
+        .expect("test database name should end with its number")
+}
+
 async fn do_cleanup(conn: &mut PgConnection) -> Result<usize, Error> {
     let delete_db_names: Vec<String> =
         query_scalar("select db_name from _sqlx_test.databases where created_at < now()")
@@ -180,9 +207,28 @@ async fn do_cleanup(conn: &mut PgConnection) -> Result<usize, Error> {
     let mut command = String::new();
 
     for db_name in delete_db_names {
+        let key = lock_key(&db_name);
+
+        // Skip the databases of tests still running in other processes, which hold their lock.
+        let locked: bool = query_scalar("select pg_try_advisory_lock($1)")
+            .bind(key)
+            .fetch_one(&mut *conn)
+            .await?;
+
+        if !locked {
+            continue;
+        }
+
         command.clear();
         writeln!(command, "drop database if exists {:?};", db_name).ok();
-        match conn.execute(&*command).await {
+        let res = conn.execute(&*command).await;
+
+        query("select pg_advisory_unlock($1)")
+            .bind(key)
+            .execute(&mut *conn)
+            .await?;
+
+        match res {
             Ok(_deleted) => {
                 deleted_db_names.push(db_name);
             }
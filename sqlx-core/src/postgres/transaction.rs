@@ -5,7 +5,7 @@ use crate::executor::Executor;
 use crate::postgres::{PgConnection, Postgres};
 use crate::transaction::{
     begin_ansi_transaction_sql, commit_ansi_transaction_sql, rollback_ansi_transaction_sql,
-    TransactionManager,
+    TransactionManager, TransactionOptions,
 };
 
 /// Implementation of [`TransactionManager`] for PostgreSQL.
@@ -25,6 +25,41 @@ impl TransactionManager for PgTransactionManager {
         })
     }
 
+    fn begin_with(
+        conn: &mut PgConnection,
+        options: TransactionOptions,
+    ) -> BoxFuture<'_, Result<(), Error>> {
+        Box::pin(async move {
+            options.check_savepoint(conn.transaction_depth)?;
+
+            #[cfg(feature = "sqlite")]
+            if options.get_sqlite_behavior().is_some() {
+                return Err(options.unsupported("PostgreSQL"));
+            }
+
+            if conn.transaction_depth > 0 || options.is_default() {
+                return Self::begin(conn).await;
+            }
+
+            let mut query = String::from("BEGIN");
+
+            if let Some(level) = options.get_isolation_level() {
+                query.push_str(" ISOLATION LEVEL ");
+                query.push_str(level.sql());
+            }
+
+            if options.is_read_only() {
+                query.push_str(" READ ONLY");
+            }
+
+            conn.execute(&*query).await?;
+
+            conn.transaction_depth += 1;
+
+            Ok(())
+        })
+    }
+
     fn commit(conn: &mut PgConnection) -> BoxFuture<'_, Result<(), Error>> {
         Box::pin(async move {
             if conn.transaction_depth > 0 {
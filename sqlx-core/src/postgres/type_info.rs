@@ -128,6 +128,9 @@ pub enum PgType {
     // From [`PgTypeInfo::with_name`]
     DeclareWithName(UStr),
 
+    // From [`PgTypeInfo::array_of`]
+    DeclareArrayOf(Arc<PgArrayOf>),
+
     // NOTE: Do we want to bring back type declaration by ID? It's notoriously fragile but
     //       someone may have a user for it
     DeclareWithOid(Oid),
@@ -142,6 +145,15 @@ pub struct PgCustomType {
     pub(crate) kind: PgTypeKind,
 }
 
+/// An array of a user-defined type, declared by the name of its element type.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "offline", derive(serde::Serialize, serde::Deserialize))]
+pub struct PgArrayOf {
+    pub(crate) elem_name: UStr,
+    // following the convention Postgres uses to name array types, e.g. `_foo` for `foo[]`
+    pub(crate) name: UStr,
+}
+
 #[derive(Debug, Clone)]
 #[cfg_attr(feature = "offline", derive(serde::Serialize, serde::Deserialize))]
 pub enum PgTypeKind {
@@ -217,6 +229,36 @@ impl PgTypeInfo {
         Self(PgType::DeclareWithName(UStr::Static(name)))
     }
 
+    /// Create a `PgTypeInfo` of an array of the type named `elem_name`.
+    ///
+    /// The OID of the array type is looked up from the `typarray` of its element type on use of
+    /// a value of this type, so this works for any type that has an array type, such as enums,
+    /// composites and domains declared with `CREATE TYPE`. The fetched OID will be cached
+    /// per-connection.
+    pub fn array_of(elem_name: &'static str) -> Self {
+        Self(PgType::DeclareArrayOf(Arc::new(PgArrayOf {
+            elem_name: UStr::Static(elem_name),
+            name: format!("_{}", elem_name).into(),
+        })))
+    }
+
+    /// Returns the type of a value nested in an array or record, from the OID sent with it.
+    ///
+    /// Built-in types are recognized by their OID and custom types are taken from `declared`,
+    /// the resolved type of the container's element or field, when their OIDs agree. Otherwise,
+    /// the type is left declared by OID only: the value can still be decoded as a custom type that
+    /// is declared by name, but not as any other type.
+    pub(crate) fn resolve_nested(oid: Oid, declared: Option<&PgTypeInfo>) -> Self {
+        if let Some(ty) = Self::try_from_oid(oid) {
+            return ty;
+        }
+
+        match declared {
+            Some(ty) if ty.0.try_oid() == Some(oid) => ty.clone(),
+            _ => Self::with_oid(oid),
+        }
+    }
+
     /// Create a `PgTypeInfo` from an OID.
     ///
     /// Note that the OID for a type is very dependent on the environment. If you only ever use
@@ -440,7 +482,7 @@ impl PgType {
             PgType::Custom(ty) => ty.oid,
 
             PgType::DeclareWithOid(oid) => *oid,
-            PgType::DeclareWithName(_) => {
+            PgType::DeclareWithName(_) | PgType::DeclareArrayOf(_) => {
                 return None;
             }
         })
@@ -543,6 +585,7 @@ impl PgType {
             PgType::Custom(ty) => &*ty.name,
             PgType::DeclareWithOid(_) => "?",
             PgType::DeclareWithName(name) => name,
+            PgType::DeclareArrayOf(array) => &array.name,
         }
     }
 
@@ -643,6 +686,7 @@ impl PgType {
             PgType::Custom(ty) => &*ty.name,
             PgType::DeclareWithOid(_) => "?",
             PgType::DeclareWithName(name) => name,
+            PgType::DeclareArrayOf(array) => &array.name,
         }
     }
 
@@ -750,13 +794,19 @@ impl PgType {
             PgType::DeclareWithName(name) => {
                 unreachable!("(bug) use of unresolved type declaration [name={}]", name);
             }
+            PgType::DeclareArrayOf(array) => {
+                unreachable!(
+                    "(bug) use of unresolved type declaration [array of={}]",
+                    array.elem_name
+                );
+            }
         }
     }
 
     /// If `self` is an array type, return the type info for its element.
     ///
     /// This method should only be called on resolved types: calling it on
-    /// a type that is merely declared (DeclareWithOid/Name) is a bug.
+    /// a type that is merely declared by name (DeclareWithName/ArrayOf) is a bug.
     pub(crate) fn try_array_element(&self) -> Option<Cow<'_, PgTypeInfo>> {
         // We explicitly match on all the `None` cases to ensure an exhaustive match.
         match self {
@@ -863,12 +913,18 @@ impl PgType {
                 PgTypeKind::Enum(_) => None,
                 PgTypeKind::Range(_) => None,
             },
-            PgType::DeclareWithOid(oid) => {
-                unreachable!("(bug) use of unresolved type declaration [oid={}]", oid.0);
-            }
+            // an array or record sent without its type being resolved, e.g. one nested in an
+            // anonymous record
+            PgType::DeclareWithOid(_) => None,
             PgType::DeclareWithName(name) => {
                 unreachable!("(bug) use of unresolved type declaration [name={}]", name);
             }
+            PgType::DeclareArrayOf(array) => {
+                unreachable!(
+                    "(bug) use of unresolved type declaration [array of={}]",
+                    array.elem_name
+                );
+            }
         }
     }
 }
@@ -1123,11 +1179,17 @@ impl PartialEq<PgType> for PgType {
             a == b
         } else if matches!(
             (self, other),
-            (PgType::DeclareWithName(_), PgType::DeclareWithOid(_))
-                | (PgType::DeclareWithOid(_), PgType::DeclareWithName(_))
+            (
+                PgType::DeclareWithName(_) | PgType::DeclareArrayOf(_),
+                PgType::DeclareWithOid(_)
+            ) | (
+                PgType::DeclareWithOid(_),
+                PgType::DeclareWithName(_) | PgType::DeclareArrayOf(_)
+            )
         ) {
             // One is a declare-with-name and the other is a declare-with-id
-            // This only occurs in the TEXT protocol with custom types
+            // This only occurs in the TEXT protocol with custom types, or with custom types
+            // nested in anonymous records
             // Just opt-out of type checking here
             true
         } else {
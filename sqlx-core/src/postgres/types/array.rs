@@ -1,10 +1,8 @@
 use bytes::Buf;
-use std::borrow::Cow;
 
 use crate::decode::Decode;
 use crate::encode::{Encode, IsNull};
 use crate::error::BoxDynError;
-use crate::postgres::type_info::PgType;
 use crate::postgres::types::Oid;
 use crate::postgres::{PgArgumentBuffer, PgTypeInfo, PgValueFormat, PgValueRef, Postgres};
 use crate::types::Type;
@@ -104,13 +102,7 @@ where
         buf.extend(&0_i32.to_be_bytes()); // flags
 
         // element type
-        match type_info.0 {
-            PgType::DeclareWithName(name) => buf.patch_type_by_name(&name),
-
-            ty => {
-                buf.extend(&ty.oid().0.to_be_bytes());
-            }
-        }
+        buf.put_type_oid(&type_info);
 
         buf.extend(&(self.len() as i32).to_be_bytes()); // len
         buf.extend(&1_i32.to_be_bytes()); // lower bound
@@ -168,14 +160,10 @@ where
 
                 // the OID of the element
                 let element_type_oid = Oid(buf.get_u32());
-                let element_type_info: PgTypeInfo = PgTypeInfo::try_from_oid(element_type_oid)
-                    .or_else(|| value.type_info.try_array_element().map(Cow::into_owned))
-                    .ok_or_else(|| {
-                        BoxDynError::from(format!(
-                            "failed to resolve array element type for oid {}",
-                            element_type_oid.0
-                        ))
-                    })?;
+                let element_type_info = PgTypeInfo::resolve_nested(
+                    element_type_oid,
+                    value.type_info.try_array_element().as_deref(),
+                );
 
                 // length of the array axis
                 let len = buf.get_i32();
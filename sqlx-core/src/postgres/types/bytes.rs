@@ -5,6 +5,7 @@ use crate::postgres::{
     PgArgumentBuffer, PgHasArrayType, PgTypeInfo, PgValueFormat, PgValueRef, Postgres,
 };
 use crate::types::Type;
+use bytes::Bytes;
 
 impl PgHasArrayType for u8 {
     fn array_type_info() -> PgTypeInfo {
@@ -24,6 +25,18 @@ impl PgHasArrayType for Vec<u8> {
     }
 }
 
+impl Type<Postgres> for Bytes {
+    fn type_info() -> PgTypeInfo {
+        PgTypeInfo::BYTEA
+    }
+}
+
+impl PgHasArrayType for Bytes {
+    fn array_type_info() -> PgTypeInfo {
+        <[&[u8]] as Type<Postgres>>::type_info()
+    }
+}
+
 impl<const N: usize> PgHasArrayType for [u8; N] {
     fn array_type_info() -> PgTypeInfo {
         <[&[u8]] as Type<Postgres>>::type_info()
@@ -44,6 +57,12 @@ impl Encode<'_, Postgres> for Vec<u8> {
     }
 }
 
+impl Encode<'_, Postgres> for Bytes {
+    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> IsNull {
+        <&[u8] as Encode<Postgres>>::encode(self, buf)
+    }
+}
+
 impl<const N: usize> Encode<'_, Postgres> for [u8; N] {
     fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> IsNull {
         <&[u8] as Encode<Postgres>>::encode(self.as_slice(), buf)
@@ -79,6 +98,17 @@ impl Decode<'_, Postgres> for Vec<u8> {
     }
 }
 
+impl Decode<'_, Postgres> for Bytes {
+    fn decode(value: PgValueRef<'_>) -> Result<Self, BoxDynError> {
+        Ok(match (value.format(), value.row) {
+            // shares the buffer of the row, which is kept alive for as long as the value is
+            (PgValueFormat::Binary, Some(row)) => row.slice_ref(value.as_bytes()?),
+            (PgValueFormat::Binary, None) => Bytes::copy_from_slice(value.as_bytes()?),
+            (PgValueFormat::Text, _) => hex::decode(text_hex_decode_input(value)?)?.into(),
+        })
+    }
+}
+
 impl<const N: usize> Decode<'_, Postgres> for [u8; N] {
     fn decode(value: PgValueRef<'_>) -> Result<Self, BoxDynError> {
         let mut bytes = [0u8; N];
@@ -19,6 +19,56 @@ pub struct PgInterval {
     pub microseconds: i64,
 }
 
+const MICROSECONDS_PER_DAY: i64 = 24 * 60 * 60 * 1_000_000;
+
+impl PgInterval {
+    /// Construct an interval spanning the given number of months.
+    pub const fn from_months(months: i32) -> Self {
+        Self {
+            months,
+            days: 0,
+            microseconds: 0,
+        }
+    }
+
+    /// Construct an interval spanning the given number of days.
+    pub const fn from_days(days: i32) -> Self {
+        Self {
+            months: 0,
+            days,
+            microseconds: 0,
+        }
+    }
+
+    /// Construct an interval spanning the given number of microseconds.
+    pub const fn from_micros(microseconds: i64) -> Self {
+        Self {
+            months: 0,
+            days: 0,
+            microseconds,
+        }
+    }
+
+    /// Returns the total length of this interval in microseconds, counting a day as exactly
+    /// 24 hours.
+    ///
+    /// This returns an error if `months` is non-zero, as the length of a month is ambiguous,
+    /// or if the total overflows an `i64`.
+    fn total_microseconds(&self) -> Result<i64, BoxDynError> {
+        if self.months != 0 {
+            return Err(
+                "PostgreSQL `INTERVAL` with non-zero months cannot be converted to a duration"
+                    .into(),
+            );
+        }
+
+        i64::from(self.days)
+            .checked_mul(MICROSECONDS_PER_DAY)
+            .and_then(|days| days.checked_add(self.microseconds))
+            .ok_or_else(|| "Overflow has occurred for PostgreSQL `INTERVAL`".into())
+    }
+}
+
 impl Type<Postgres> for PgInterval {
     fn type_info() -> PgTypeInfo {
         PgTypeInfo::INTERVAL
@@ -116,6 +166,28 @@ impl TryFrom<std::time::Duration> for PgInterval {
     }
 }
 
+impl TryFrom<PgInterval> for std::time::Duration {
+    type Error = BoxDynError;
+
+    /// Convert a `PgInterval` to a `std::time::Duration`.
+    ///
+    /// A day is considered to be exactly 24 hours. This returns an error if `months` is
+    /// non-zero, if the interval is negative or if there is a microsecond overflow.
+    fn try_from(value: PgInterval) -> Result<Self, BoxDynError> {
+        let microseconds = u64::try_from(value.total_microseconds()?).map_err(|_| {
+            "negative PostgreSQL `INTERVAL` cannot be converted to `std::time::Duration`"
+        })?;
+
+        Ok(std::time::Duration::from_micros(microseconds))
+    }
+}
+
+impl<'de> Decode<'de, Postgres> for std::time::Duration {
+    fn decode(value: PgValueRef<'de>) -> Result<Self, BoxDynError> {
+        PgInterval::decode(value)?.try_into()
+    }
+}
+
 #[cfg(feature = "chrono")]
 impl Type<Postgres> for chrono::Duration {
     fn type_info() -> PgTypeInfo {
@@ -178,6 +250,26 @@ impl TryFrom<chrono::Duration> for PgInterval {
     }
 }
 
+#[cfg(feature = "chrono")]
+impl TryFrom<PgInterval> for chrono::Duration {
+    type Error = BoxDynError;
+
+    /// Convert a `PgInterval` to a `chrono::Duration`.
+    ///
+    /// A day is considered to be exactly 24 hours. This returns an error if `months` is
+    /// non-zero or if there is a microsecond overflow.
+    fn try_from(value: PgInterval) -> Result<Self, BoxDynError> {
+        Ok(chrono::Duration::microseconds(value.total_microseconds()?))
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl<'de> Decode<'de, Postgres> for chrono::Duration {
+    fn decode(value: PgValueRef<'de>) -> Result<Self, BoxDynError> {
+        PgInterval::decode(value)?.try_into()
+    }
+}
+
 #[cfg(feature = "time")]
 impl Type<Postgres> for time::Duration {
     fn type_info() -> PgTypeInfo {
@@ -225,6 +317,26 @@ impl TryFrom<time::Duration> for PgInterval {
     }
 }
 
+#[cfg(feature = "time")]
+impl TryFrom<PgInterval> for time::Duration {
+    type Error = BoxDynError;
+
+    /// Convert a `PgInterval` to a `time::Duration`.
+    ///
+    /// A day is considered to be exactly 24 hours. This returns an error if `months` is
+    /// non-zero or if there is a microsecond overflow.
+    fn try_from(value: PgInterval) -> Result<Self, BoxDynError> {
+        Ok(time::Duration::microseconds(value.total_microseconds()?))
+    }
+}
+
+#[cfg(feature = "time")]
+impl<'de> Decode<'de, Postgres> for time::Duration {
+    fn decode(value: PgValueRef<'de>) -> Result<Self, BoxDynError> {
+        PgInterval::decode(value)?.try_into()
+    }
+}
+
 #[test]
 fn test_encode_interval() {
     let mut buf = PgArgumentBuffer::default();
@@ -392,3 +504,117 @@ fn test_pginterval_time() {
     assert!(PgInterval::try_from(time::Duration::seconds(10_000_000_000_000)).is_err());
     assert!(PgInterval::try_from(time::Duration::seconds(-10_000_000_000_000)).is_err());
 }
+
+#[test]
+fn test_pginterval_constructors() {
+    assert_eq!(
+        PgInterval::from_months(-3),
+        PgInterval {
+            months: -3,
+            days: 0,
+            microseconds: 0,
+        }
+    );
+    assert_eq!(
+        PgInterval::from_days(12),
+        PgInterval {
+            months: 0,
+            days: 12,
+            microseconds: 0,
+        }
+    );
+    assert_eq!(
+        PgInterval::from_micros(-27_000),
+        PgInterval {
+            months: 0,
+            days: 0,
+            microseconds: -27_000,
+        }
+    );
+}
+
+#[test]
+fn test_std_from_pginterval() {
+    // Case for positive duration
+    let interval = PgInterval {
+        months: 0,
+        days: 1,
+        microseconds: 27_000,
+    };
+    assert_eq!(
+        std::time::Duration::try_from(interval).unwrap(),
+        std::time::Duration::from_micros(86_400_000_000 + 27_000)
+    );
+
+    // Case for negative duration
+    assert!(std::time::Duration::try_from(PgInterval::from_micros(-1)).is_err());
+
+    // Case when months are ambiguous
+    assert!(std::time::Duration::try_from(PgInterval::from_months(1)).is_err());
+}
+
+#[test]
+#[cfg(feature = "chrono")]
+fn test_chrono_from_pginterval() {
+    // Case for positive duration
+    assert_eq!(
+        chrono::Duration::try_from(PgInterval::from_micros(27_000)).unwrap(),
+        chrono::Duration::microseconds(27_000)
+    );
+
+    // Case for negative duration, mixing days and microseconds
+    let interval = PgInterval {
+        months: 0,
+        days: -1,
+        microseconds: 3_600_000_000,
+    };
+    assert_eq!(
+        chrono::Duration::try_from(interval).unwrap(),
+        chrono::Duration::hours(-23)
+    );
+
+    // Case when months are ambiguous
+    assert!(chrono::Duration::try_from(PgInterval::from_months(1)).is_err());
+    assert!(chrono::Duration::try_from(PgInterval::from_months(-1)).is_err());
+
+    // Case when microsecond overflow occurs
+    let interval = PgInterval {
+        months: 0,
+        days: i32::MAX,
+        microseconds: i64::MAX,
+    };
+    assert!(chrono::Duration::try_from(interval).is_err());
+}
+
+#[test]
+#[cfg(feature = "time")]
+fn test_time_from_pginterval() {
+    // Case for positive duration
+    assert_eq!(
+        time::Duration::try_from(PgInterval::from_micros(27_000)).unwrap(),
+        time::Duration::microseconds(27_000)
+    );
+
+    // Case for negative duration, mixing days and microseconds
+    let interval = PgInterval {
+        months: 0,
+        days: -1,
+        microseconds: 3_600_000_000,
+    };
+    assert_eq!(
+        time::Duration::try_from(interval).unwrap(),
+        time::Duration::hours(-23)
+    );
+
+    // Case when months are ambiguous
+    assert!(time::Duration::try_from(PgInterval::from_months(1)).is_err());
+    assert!(time::Duration::try_from(PgInterval::from_months(-1)).is_err());
+
+    // Case when microsecond overflow occurs
+    let interval = PgInterval {
+        months: 0,
+        days: i32::MIN,
+        microseconds: i64::MIN,
+    };
+    assert!(time::Duration::try_from(interval).is_err());
+}
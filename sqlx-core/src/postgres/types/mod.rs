@@ -12,11 +12,15 @@
 //! | `f32`                                 | REAL, FLOAT4                                         |
 //! | `f64`                                 | DOUBLE PRECISION, FLOAT8                             |
 //! | `&str`, [`String`]                    | VARCHAR, CHAR(N), TEXT, NAME                         |
-//! | `&[u8]`, `Vec<u8>`                    | BYTEA                                                |
+//! | `&[u8]`, `Vec<u8>`, [`Bytes`](crate::types::Bytes)| BYTEA                                                |
 //! | [`PgInterval`]                        | INTERVAL                                             |
+//! | `std::time::Duration`                 | INTERVAL                                             |
 //! | [`PgRange<T>`](PgRange)               | INT8RANGE, INT4RANGE, TSRANGE, TSTZRANGE, DATERANGE, NUMRANGE |
 //! | [`PgMoney`]                           | MONEY                                                |
 //!
+//! A `BYTEA` decoded to [`Bytes`](crate::types::Bytes) references the buffer of its row rather
+//! than being copied, as long as it was received in the binary format of prepared queries.
+//!
 //!
 //! ### [`bigdecimal`](https://crates.io/crates/bigdecimal)
 //! Requires the `bigdecimal` Cargo feature flag.
@@ -44,6 +48,7 @@
 //! | `chrono::NaiveDate`                   | DATE                                                 |
 //! | `chrono::NaiveTime`                   | TIME                                                 |
 //! | [`PgTimeTz`]                          | TIMETZ                                               |
+//! | `chrono::Duration`                    | INTERVAL                                             |
 //!
 //! ### [`time`](https://crates.io/crates/time)
 //!
@@ -56,6 +61,7 @@
 //! | `time::Date`                          | DATE                                                 |
 //! | `time::Time`                          | TIME                                                 |
 //! | [`PgTimeTz`]                          | TIMETZ                                               |
+//! | `time::Duration`                      | INTERVAL                                             |
 //!
 //! ### [`uuid`](https://crates.io/crates/uuid)
 //!
@@ -133,6 +139,10 @@
 //!
 //! One-dimensional arrays are supported as `Vec<T>` or `&[T]` where `T` implements `Type`.
 //!
+//! For user-defined types, `T` must also implement [`PgHasArrayType`], which the derive for
+//! `Type` does for enumerations and composite types, with [`PgTypeInfo::array_of`]; so a
+//! `mood[]` can be decoded as a `Vec<Mood>` (or `Vec<Option<Mood>>` if it may contain `NULL`).
+//!
 //! # [Enumerations](https://www.postgresql.org/docs/current/datatype-enum.html)
 //!
 //! User-defined enumerations are supported through a derive for `Type`.
@@ -41,14 +41,8 @@ impl<'a> PgRecordEncoder<'a> {
     {
         let ty = value.produces().unwrap_or_else(T::type_info);
 
-        if let PgType::DeclareWithName(name) = ty.0 {
-            // push a hole for this type ID
-            // to be filled in on query execution
-            self.buf.patch_type_by_name(&name);
-        } else {
-            // write type id
-            self.buf.extend(&ty.0.oid().0.to_be_bytes());
-        }
+        // write type id, or push a hole for it to be filled in on query execution
+        self.buf.put_type_oid(&ty);
 
         self.buf.encode(value);
         self.num += 1;
@@ -103,9 +97,15 @@ impl<'r> PgRecordDecoder<'r> {
         match self.fmt {
             PgValueFormat::Binary => {
                 let element_type_oid = Oid(self.buf.get_u32());
-                let element_type_opt = match self.typ.0.kind() {
+                let element_type = match self.typ.0.kind() {
                     PgTypeKind::Simple if self.typ.0 == PgType::Record => {
-                        PgTypeInfo::try_from_oid(element_type_oid)
+                        // an anonymous record doesn't declare its field types
+                        let ty = PgTypeInfo::resolve_nested(element_type_oid, None);
+                        if let PgType::DeclareWithOid(_) = ty.0 {
+                            return Err(format!("custom types in records are not fully supported yet: failed to retrieve type info for field {} with type oid {}", self.ind, element_type_oid.0).into());
+                        }
+
+                        ty
                     }
 
                     PgTypeKind::Composite(fields) => {
@@ -114,7 +114,7 @@ impl<'r> PgRecordDecoder<'r> {
                             return Err("unexpected mismatch of composite type information".into());
                         }
 
-                        Some(ty)
+                        ty
                     }
 
                     _ => {
@@ -125,16 +125,10 @@ impl<'r> PgRecordDecoder<'r> {
                     }
                 };
 
-                if let Some(ty) = &element_type_opt {
-                    if !ty.is_null() && !T::compatible(ty) {
-                        return Err(mismatched_types::<Postgres, T>(ty));
-                    }
+                if !element_type.is_null() && !T::compatible(&element_type) {
+                    return Err(mismatched_types::<Postgres, T>(&element_type));
                 }
 
-                let element_type =
-                    element_type_opt
-                        .ok_or_else(|| BoxDynError::from(format!("custom types in records are not fully supported yet: failed to retrieve type info for field {} with type oid {}", self.ind, element_type_oid.0)))?;
-
                 self.ind += 1;
 
                 T::decode(PgValueRef::get(&mut self.buf, self.fmt, element_type))
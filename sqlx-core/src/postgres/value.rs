@@ -73,7 +73,7 @@ impl Value for PgValue {
     fn as_ref(&self) -> PgValueRef<'_> {
         PgValueRef {
             value: self.value.as_deref(),
-            row: None,
+            row: self.value.as_ref(),
             type_info: self.type_info.clone(),
             format: self.format,
         }
@@ -12,6 +12,11 @@ use crate::executor::{Execute, Executor};
 use crate::statement::Statement;
 use crate::types::Type;
 
+#[cfg(feature = "serde_row")]
+use crate::serde_row::DeserializeRow;
+#[cfg(feature = "serde_row")]
+use serde::de::DeserializeOwned;
+
 /// Raw SQL query with bind parameters. Returned by [`query`][crate::query::query].
 #[must_use = "query must be executed to affect database"]
 pub struct Query<'q, DB: Database, A> {
@@ -19,6 +24,7 @@ pub struct Query<'q, DB: Database, A> {
     pub(crate) arguments: Option<A>,
     pub(crate) database: PhantomData<DB>,
     pub(crate) persistent: bool,
+    pub(crate) error: Option<Error>,
 }
 
 /// SQL query that will map its results to owned Rust types.
@@ -65,6 +71,11 @@ where
     fn persistent(&self) -> bool {
         self.persistent
     }
+
+    #[inline]
+    fn take_error(&mut self) -> Option<Error> {
+        self.error.take()
+    }
 }
 
 impl<'q, DB: Database> Query<'q, DB, <DB as HasArguments<'q>>::Arguments> {
@@ -225,6 +236,62 @@ where
     {
         executor.fetch_optional(self).await
     }
+
+    /// Execute the query and deserialize all the rows with serde.
+    ///
+    /// See [`DeserializeRow::deserialize`](crate::serde_row::DeserializeRow::deserialize).
+    #[cfg(feature = "serde_row")]
+    pub async fn fetch_all_deserialized<'e, 'c: 'e, E, T>(
+        self,
+        executor: E,
+    ) -> Result<Vec<T>, Error>
+    where
+        'q: 'e,
+        A: 'e,
+        E: Executor<'c, Database = DB>,
+        DB::Row: DeserializeRow,
+        T: DeserializeOwned,
+    {
+        executor
+            .fetch_all(self)
+            .await?
+            .iter()
+            .map(|row| row.deserialize())
+            .collect()
+    }
+
+    /// Execute the query and deserialize exactly one row with serde.
+    #[cfg(feature = "serde_row")]
+    pub async fn fetch_one_deserialized<'e, 'c: 'e, E, T>(self, executor: E) -> Result<T, Error>
+    where
+        'q: 'e,
+        A: 'e,
+        E: Executor<'c, Database = DB>,
+        DB::Row: DeserializeRow,
+        T: DeserializeOwned,
+    {
+        executor.fetch_one(self).await?.deserialize()
+    }
+
+    /// Execute the query and deserialize at most one row with serde.
+    #[cfg(feature = "serde_row")]
+    pub async fn fetch_optional_deserialized<'e, 'c: 'e, E, T>(
+        self,
+        executor: E,
+    ) -> Result<Option<T>, Error>
+    where
+        'q: 'e,
+        A: 'e,
+        E: Executor<'c, Database = DB>,
+        DB::Row: DeserializeRow,
+        T: DeserializeOwned,
+    {
+        executor
+            .fetch_optional(self)
+            .await?
+            .map(|row| row.deserialize())
+            .transpose()
+    }
 }
 
 impl<'q, DB, F: Send, A: Send> Execute<'q, DB> for Map<'q, DB, F, A>
@@ -251,6 +318,11 @@ where
     fn persistent(&self) -> bool {
         self.inner.arguments.is_some()
     }
+
+    #[inline]
+    fn take_error(&mut self) -> Option<Error> {
+        self.inner.take_error()
+    }
 }
 
 impl<'q, DB, F, O, A> Map<'q, DB, F, A>
@@ -406,6 +478,7 @@ where
         arguments: Some(Default::default()),
         statement: Either::Right(statement),
         persistent: true,
+        error: None,
     }
 }
 
@@ -423,6 +496,7 @@ where
         arguments: Some(arguments),
         statement: Either::Right(statement),
         persistent: true,
+        error: None,
     }
 }
 
@@ -436,6 +510,7 @@ where
         arguments: Some(Default::default()),
         statement: Either::Left(sql),
         persistent: true,
+        error: None,
     }
 }
 
@@ -450,5 +525,30 @@ where
         arguments: Some(arguments),
         statement: Either::Left(sql),
         persistent: true,
+        error: None,
+    }
+}
+
+/// Make a SQL query, with the given arguments, that returns the error of `sql` instead of being
+/// executed if it has one.
+///
+/// Used by the `query!()` family of macros for a query selected by its dynamic fragments.
+#[doc(hidden)]
+pub fn query_with_result<'q, DB, A>(sql: Result<&'q str, Error>, arguments: A) -> Query<'q, DB, A>
+where
+    DB: Database,
+    A: IntoArguments<'q, DB>,
+{
+    let (sql, error) = match sql {
+        Ok(sql) => (sql, None),
+        Err(error) => ("", Some(error)),
+    };
+
+    Query {
+        database: PhantomData,
+        arguments: Some(arguments),
+        statement: Either::Left(sql),
+        persistent: true,
+        error,
     }
 }
@@ -10,6 +10,7 @@ use crate::encode::Encode;
 use crate::from_row::FromRow;
 use crate::query::Query;
 use crate::query_as::QueryAs;
+use crate::quote::QuoteError;
 use crate::types::Type;
 use crate::Either;
 
@@ -92,6 +93,38 @@ where
         self
     }
 
+    /// Push an identifier, e.g. the name of a table, quoted with the rules of the database by
+    /// [`Database::quote_identifier`].
+    ///
+    /// Unlike [`.push()`][Self::push], this is safe to use with untrusted input, which can't
+    /// escape the quotes:
+    ///
+    /// ```rust
+    /// # #[cfg(feature = "mysql")] {
+    /// use sqlx::{MySql, QueryBuilder};
+    ///
+    /// let mut query_builder: QueryBuilder<MySql> = QueryBuilder::new("SELECT * FROM ");
+    /// query_builder.push_identifier("tenant`42").unwrap();
+    ///
+    /// assert_eq!(query_builder.sql(), "SELECT * FROM `tenant``42`");
+    /// # }
+    /// ```
+    ///
+    /// Returns an error, without pushing anything, if the identifier can't be quoted, e.g. as it
+    /// is longer than the database allows.
+    ///
+    /// Note that with [`Any`](crate::any::Any), the identifier is quoted as in standard SQL,
+    /// which MySQL doesn't accept unless the `ANSI_QUOTES` SQL mode is enabled; use
+    /// [`AnyKind::quote_identifier`](crate::any::AnyKind::quote_identifier) with
+    /// [`.push()`][Self::push] instead.
+    pub fn push_identifier(&mut self, identifier: &str) -> Result<&mut Self, QuoteError> {
+        self.sanity_check();
+
+        self.query.push_str(&DB::quote_identifier(identifier)?);
+
+        Ok(self)
+    }
+
     /// Push a bind argument placeholder (`?` or `$N` for Postgres) and bind a value to it.
     ///
     /// ### Note: Database-specific Limits
@@ -109,10 +142,11 @@ where
     ///       asserts that the number of parameters is in the range `[0, 65535)`.
     /// * SQLite: 32766 (configurable by [`SQLITE_LIMIT_VARIABLE_NUMBER`])
     ///     * SQLite prior to 3.32.0: 999
-    /// * MSSQL: 2100
+    /// * MSSQL: 2100, two of which are taken by the driver
     ///
     /// Exceeding these limits may panic (as a sanity check) or trigger a database error at runtime
-    /// depending on the implementation.
+    /// depending on the implementation. [`Database::MAX_BIND_PARAMETERS`] has the default for
+    /// each database, which [`insert()`](crate::insert::insert) splits bulk inserts with.
     ///
     /// [`SQLITE_LIMIT_VARIABLE_NUMBER`]: https://www.sqlite.org/limits.html#max_variable_number
     /// [postgres-limit-issue]: https://github.com/launchbadge/sqlx/issues/671#issuecomment-687043510
@@ -423,6 +457,7 @@ where
             arguments: self.arguments.take(),
             database: PhantomData,
             persistent: true,
+            error: None,
         }
     }
 
@@ -447,6 +482,17 @@ where
         }
     }
 
+    /// Deconstruct this `QueryBuilder` into the built SQL and its arguments, which unlike
+    /// [`.build()`][Self::build] leaves the arguments free to outlive a borrow of the builder.
+    pub(crate) fn into_parts(self) -> (String, <DB as HasArguments<'args>>::Arguments) {
+        self.sanity_check();
+
+        (
+            self.query,
+            self.arguments.expect("BUG: Arguments taken already"),
+        )
+    }
+
     /// Reset this `QueryBuilder` back to its initial state.
     ///
     /// The query is truncated to the initial fragment provided to [`new()`][Self::new] and
@@ -588,6 +634,27 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_push_identifier() {
+        let mut qb: QueryBuilder<'_, Postgres> = QueryBuilder::new("SELECT * FROM ");
+
+        qb.push_identifier(r#"tenant "42""#).unwrap();
+
+        assert_eq!(qb.query, r#"SELECT * FROM "tenant ""42""""#);
+
+        assert_eq!(
+            qb.push_identifier(&"a".repeat(64)).err(),
+            Some(QuoteError::IdentifierTooLong { len: 64, max: 63 })
+        );
+        assert_eq!(
+            qb.push_identifier("").err(),
+            Some(QuoteError::EmptyIdentifier)
+        );
+        assert_eq!(qb.push_identifier("a\0b").err(), Some(QuoteError::Nul));
+
+        assert_eq!(qb.query, r#"SELECT * FROM "tenant ""42""""#);
+    }
+
     #[test]
     fn test_build() {
         let mut qb: QueryBuilder<'_, Postgres> = QueryBuilder::new("SELECT * FROM users");
@@ -1,3 +1,5 @@
+use std::marker::PhantomData;
+
 use either::Either;
 use futures_core::stream::BoxStream;
 use futures_util::{StreamExt, TryFutureExt, TryStreamExt};
@@ -8,6 +10,7 @@ use crate::encode::Encode;
 use crate::error::Error;
 use crate::executor::{Execute, Executor};
 use crate::from_row::FromRow;
+use crate::query::query_with_result;
 use crate::query_as::{
     query_as, query_as_with, query_statement_as, query_statement_as_with, QueryAs,
 };
@@ -17,7 +20,7 @@ use crate::types::Type;
 /// Returned from [`query_scalar`].
 #[must_use = "query must be executed to affect database"]
 pub struct QueryScalar<'q, DB: Database, O, A> {
-    inner: QueryAs<'q, DB, (O,), A>,
+    pub(crate) inner: QueryAs<'q, DB, (O,), A>,
 }
 
 impl<'q, DB: Database, O: Send, A: Send> Execute<'q, DB> for QueryScalar<'q, DB, O, A>
@@ -42,6 +45,11 @@ where
     fn persistent(&self) -> bool {
         self.inner.persistent()
     }
+
+    #[inline]
+    fn take_error(&mut self) -> Option<Error> {
+        self.inner.take_error()
+    }
 }
 
 impl<'q, DB: Database, O> QueryScalar<'q, DB, O, <DB as HasArguments<'q>>::Arguments> {
@@ -187,6 +195,29 @@ where
     }
 }
 
+/// Make a SQL query, with the given arguments, that is mapped to a single concrete type using
+/// [`FromRow`], and returns the error of `sql` instead of being executed if it has one.
+///
+/// Used by the `query_scalar!()` macro for a query selected by its dynamic fragments.
+#[doc(hidden)]
+#[inline]
+pub fn query_scalar_with_result<'q, DB, O, A>(
+    sql: Result<&'q str, Error>,
+    arguments: A,
+) -> QueryScalar<'q, DB, O, A>
+where
+    DB: Database,
+    A: IntoArguments<'q, DB>,
+    (O,): for<'r> FromRow<'r, DB::Row>,
+{
+    QueryScalar {
+        inner: QueryAs {
+            inner: query_with_result(sql, arguments),
+            output: PhantomData,
+        },
+    }
+}
+
 // Make a SQL query from a statement, that is mapped to a concrete value.
 pub(crate) fn query_statement_scalar<'q, DB, O>(
     statement: &'q <DB as HasStatement<'q>>::Statement,
@@ -0,0 +1,67 @@
+//! Quoting identifiers and literals to embed them in SQL, for the parts of a statement which
+//! can't be bound as arguments, e.g. the name of a table created at runtime.
+//!
+//! The rules of each database are implemented by [`Database::quote_identifier`] and
+//! [`Database::quote_literal`], which [`QueryBuilder::push_identifier`] uses:
+//!
+//! ```rust
+//! # #[cfg(all(feature = "mysql", feature = "postgres"))] {
+//! use sqlx::{Database, MySql, Postgres};
+//!
+//! assert_eq!(Postgres::quote_identifier("tenant \"42\"").unwrap(), r#""tenant ""42""""#);
+//! assert_eq!(MySql::quote_identifier("tenant `42`").unwrap(), "`tenant ``42```");
+//! # }
+//! ```
+//!
+//! [`Database::quote_identifier`]: crate::database::Database::quote_identifier
+//! [`Database::quote_literal`]: crate::database::Database::quote_literal
+//! [`QueryBuilder::push_identifier`]: crate::query_builder::QueryBuilder::push_identifier
+
+/// An identifier or literal which can't be quoted for a database.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[non_exhaustive]
+pub enum QuoteError {
+    /// The identifier is empty, which no database accepts even when quoted.
+    #[error("identifier is empty")]
+    EmptyIdentifier,
+
+    /// The identifier or literal contains a NUL character, which can't be embedded in SQL.
+    #[error("identifier or literal contains a NUL character")]
+    Nul,
+
+    /// The identifier is longer than the database allows, in bytes for PostgreSQL and in
+    /// characters otherwise.
+    #[error("identifier has a length of {len}, more than the maximum of {max}")]
+    IdentifierTooLong { len: usize, max: usize },
+}
+
+/// Checks the length of an identifier, which is at most `max`.
+pub(crate) fn check_identifier_len(len: usize, max: usize) -> Result<(), QuoteError> {
+    match len {
+        0 => Err(QuoteError::EmptyIdentifier),
+        len if len > max => Err(QuoteError::IdentifierTooLong { len, max }),
+        _ => Ok(()),
+    }
+}
+
+/// Wraps `value` in `open` and `close`, escaping `close` by doubling it.
+pub(crate) fn quote(value: &str, open: char, close: char) -> Result<String, QuoteError> {
+    if value.contains('\0') {
+        return Err(QuoteError::Nul);
+    }
+
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push(open);
+
+    for c in value.chars() {
+        if c == close {
+            quoted.push(c);
+        }
+
+        quoted.push(c);
+    }
+
+    quoted.push(close);
+
+    Ok(quoted)
+}
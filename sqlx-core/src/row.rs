@@ -1,10 +1,12 @@
-use crate::column::ColumnIndex;
+use crate::column::{Column, ColumnIndex};
 use crate::database::{Database, HasValueRef};
 use crate::decode::Decode;
-use crate::error::{mismatched_types, Error};
+use crate::error::{mismatched_types, ColumnContext, Error, ErrorContext};
 use crate::type_info::TypeInfo;
 use crate::types::Type;
 use crate::value::ValueRef;
+use std::any::type_name;
+use std::sync::Arc;
 
 /// Represents a single row from the database.
 ///
@@ -119,16 +121,22 @@ pub trait Row: private_row::Sealed + Unpin + Send + Sync + 'static {
             let ty = value.type_info();
 
             if !ty.is_null() && !T::compatible(&ty) {
-                return Err(Error::ColumnDecode {
+                let error = Error::ColumnDecode {
                     index: format!("{:?}", index),
                     source: mismatched_types::<Self::Database, T>(&ty),
-                });
+                };
+
+                return Err(with_column_context::<Self, T, I>(self, &index, error));
             }
         }
 
-        T::decode(value).map_err(|source| Error::ColumnDecode {
-            index: format!("{:?}", index),
-            source,
+        T::decode(value).map_err(|source| {
+            let error = Error::ColumnDecode {
+                index: format!("{:?}", index),
+                source,
+            };
+
+            with_column_context::<Self, T, I>(self, &index, error)
         })
     }
 
@@ -156,9 +164,13 @@ pub trait Row: private_row::Sealed + Unpin + Send + Sync + 'static {
     {
         let value = self.try_get_raw(&index)?;
 
-        T::decode(value).map_err(|source| Error::ColumnDecode {
-            index: format!("{:?}", index),
-            source,
+        T::decode(value).map_err(|source| {
+            let error = Error::ColumnDecode {
+                index: format!("{:?}", index),
+                source,
+            };
+
+            with_column_context::<Self, T, I>(self, &index, error)
         })
     }
 
@@ -178,6 +190,42 @@ pub trait Row: private_row::Sealed + Unpin + Send + Sync + 'static {
     ) -> Result<<Self::Database as HasValueRef<'_>>::ValueRef, Error>
     where
         I: ColumnIndex<Self>;
+
+    /// The context of the statement which returned this row, if it was captured.
+    #[doc(hidden)]
+    fn error_context(&self) -> Option<&Arc<ErrorContext>> {
+        None
+    }
+}
+
+/// Wraps an error decoding the column `index` of a row as `T` with the context of the statement
+/// which returned the row, if it was captured.
+pub(crate) fn with_column_context<R, T, I>(row: &R, index: &I, error: Error) -> Error
+where
+    R: Row + ?Sized,
+    I: ColumnIndex<R> + ?Sized,
+{
+    let context = match row.error_context() {
+        Some(context) => context,
+        None => return error,
+    };
+
+    let column = match index.index(row) {
+        Ok(i) => &row.columns()[i],
+        Err(_) => return error,
+    };
+
+    let column = ColumnContext::new(
+        column.ordinal(),
+        column.name(),
+        column.type_info().name(),
+        type_name::<T>(),
+    );
+
+    Error::WithContext {
+        error: Box::new(error),
+        context: Box::new(context.with_column(column)),
+    }
 }
 
 // Prevent users from implementing the `Row` trait.
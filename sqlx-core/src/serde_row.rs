@@ -0,0 +1,453 @@
+//! Deserializing rows into types implementing [`serde::Deserialize`], for results whose shape
+//! isn't known in advance, e.g. ad-hoc queries captured as JSON.
+//!
+//! A row deserializes as a map from the names of its columns to their values, or as a sequence
+//! of its values, e.g. into a tuple. The values are converted based on the type of their column:
+//!
+//!  * integers, floats, booleans, text and bytes map to their serde counterparts;
+//!  * `JSON` values deserialize as the structures they contain, not as strings;
+//!  * types without a serde counterpart of their own, such as `NUMERIC` and the date and time
+//!    types, are strings formatted like the database formats them;
+//!  * PostgreSQL arrays are sequences.
+//!
+//! A column whose type has none of these mappings fails to deserialize with an
+//! [`Error::ColumnDecode`] naming its type, unless the value is ignored.
+//!
+//! ```rust,no_run
+//! # #[cfg(feature = "postgres")]
+//! # async fn example(conn: &mut sqlx_core::postgres::PgConnection) -> sqlx_core::error::Result<()> {
+//! use sqlx_core::serde_row::DeserializeRow;
+//!
+//! let row = sqlx_core::query::query("SELECT 1 AS id, '{\"tags\": [\"a\"]}'::jsonb AS data")
+//!     .fetch_one(&mut *conn)
+//!     .await?;
+//!
+//! let value: serde_json::Value = row.deserialize()?;
+//!
+//! assert_eq!(value, serde_json::json!({ "id": 1, "data": { "tags": ["a"] } }));
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Supported for PostgreSQL, MySQL, SQLite and [`Any`](crate::any::Any), but not MSSQL.
+
+use std::fmt::{self, Debug, Display, Formatter};
+
+use serde::de::value::{SeqDeserializer, StrDeserializer};
+use serde::de::{
+    self, DeserializeOwned, DeserializeSeed, IntoDeserializer, MapAccess, SeqAccess, Visitor,
+};
+use serde::forward_to_deserialize_any;
+
+use crate::column::Column;
+use crate::error::{BoxDynError, Error};
+use crate::row::Row;
+
+pub(crate) use private_serde_row::{ColumnValue, ColumnValues, DeserializeError};
+
+/// Deserializes rows with serde; implemented by the rows of the databases which support it.
+pub trait DeserializeRow: Row + ColumnValues {
+    /// Deserializes this row into `T`, e.g. a [`serde_json::Value`] or a struct deriving
+    /// [`Deserialize`](serde::Deserialize) whose fields are named after the columns.
+    ///
+    /// # Errors
+    ///
+    ///  * [`ColumnDecode`](Error::ColumnDecode) if the value of a column couldn't be converted,
+    ///    e.g. if its type has no serde mapping or doesn't match the field it's deserialized to.
+    ///  * [`Decode`](Error::Decode) if the row doesn't match `T`, e.g. if a field is missing.
+    fn deserialize<T: DeserializeOwned>(&self) -> Result<T, Error>
+    where
+        Self: Sized,
+    {
+        T::deserialize(RowDeserializer { row: self }).map_err(|e| e.0)
+    }
+}
+
+impl<R: Row + ColumnValues> DeserializeRow for R {}
+
+mod private_serde_row {
+    use crate::error::Error;
+
+    /// The conversion of the values of a row for serde, implemented by each driver.
+    pub trait ColumnValues {
+        fn column_value(&self, index: usize) -> Result<ColumnValue, Error>;
+    }
+
+    /// A value converted for serde.
+    #[derive(Debug)]
+    pub enum ColumnValue {
+        Null,
+        Bool(bool),
+        I64(i64),
+        U64(u64),
+        F64(f64),
+        String(String),
+        Bytes(Vec<u8>),
+        Json(serde_json::Value),
+        Seq(Vec<ColumnValue>),
+    }
+
+    /// The error of the deserializers, wrapping the [`Error`] returned by `DeserializeRow`.
+    pub struct DeserializeError(pub(super) Error);
+}
+
+/// An error converting the value of the column named `name`.
+pub(crate) fn column_error(name: &str, source: impl Into<BoxDynError>) -> Error {
+    Error::ColumnDecode {
+        index: format!("{:?}", name),
+        source: source.into(),
+    }
+}
+
+/// An error for the column named `name`, whose type has no serde mapping.
+pub(crate) fn unsupported_type(name: &str, type_name: &str) -> Error {
+    column_error(
+        name,
+        format!("type {} has no serde representation", type_name),
+    )
+}
+
+impl de::Error for DeserializeError {
+    fn custom<T: Display>(msg: T) -> Self {
+        DeserializeError(Error::Decode(msg.to_string().into()))
+    }
+}
+
+impl Debug for DeserializeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Debug::fmt(&self.0, f)
+    }
+}
+
+impl Display for DeserializeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Display::fmt(&self.0, f)
+    }
+}
+
+impl std::error::Error for DeserializeError {}
+
+struct RowDeserializer<'a, R> {
+    row: &'a R,
+}
+
+impl<'de, R: Row + ColumnValues> de::Deserializer<'de> for RowDeserializer<'_, R> {
+    type Error = DeserializeError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_map(Columns {
+            row: self.row,
+            index: 0,
+        })
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_seq(Columns {
+            row: self.row,
+            index: 0,
+        })
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(
+        self,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_some(self)
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes byte_buf unit
+        unit_struct map struct enum identifier ignored_any
+    }
+}
+
+/// The columns of a row, as a map from their names to their values or as a sequence of values.
+struct Columns<'a, R> {
+    row: &'a R,
+    index: usize,
+}
+
+impl<R: Row + ColumnValues> Columns<'_, R> {
+    fn next_value<'de, T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<T::Value, DeserializeError> {
+        let index = self.index;
+        self.index += 1;
+
+        seed.deserialize(ColumnDeserializer {
+            row: self.row,
+            index,
+        })
+        .map_err(|e| match e.0 {
+            // name the column whose value doesn't match
+            Error::Decode(source) => {
+                DeserializeError(column_error(self.row.columns()[index].name(), source))
+            }
+
+            error => DeserializeError(error),
+        })
+    }
+}
+
+impl<'de, R: Row + ColumnValues> MapAccess<'de> for Columns<'_, R> {
+    type Error = DeserializeError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Self::Error> {
+        match self.row.columns().get(self.index) {
+            Some(column) => {
+                let name: StrDeserializer<'_, DeserializeError> = column.name().into_deserializer();
+
+                seed.deserialize(name).map(Some)
+            }
+
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(
+        &mut self,
+        seed: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.next_value(seed)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.row.len() - self.index)
+    }
+}
+
+impl<'de, R: Row + ColumnValues> SeqAccess<'de> for Columns<'_, R> {
+    type Error = DeserializeError;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, Self::Error> {
+        if self.index < self.row.len() {
+            self.next_value(seed).map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.row.len() - self.index)
+    }
+}
+
+/// The value of a column, converted only when it isn't ignored.
+struct ColumnDeserializer<'a, R> {
+    row: &'a R,
+    index: usize,
+}
+
+impl<R: ColumnValues> ColumnDeserializer<'_, R> {
+    fn value(&self) -> Result<ColumnValue, DeserializeError> {
+        self.row.column_value(self.index).map_err(DeserializeError)
+    }
+}
+
+macro_rules! forward_to_value {
+    ($($method:ident)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+                self.value()?.$method(visitor)
+            }
+        )*
+    };
+}
+
+impl<'de, R: ColumnValues> de::Deserializer<'de> for ColumnDeserializer<'_, R> {
+    type Error = DeserializeError;
+
+    forward_to_value! {
+        deserialize_any deserialize_bool deserialize_i8 deserialize_i16 deserialize_i32
+        deserialize_i64 deserialize_u8 deserialize_u16 deserialize_u32 deserialize_u64
+        deserialize_f32 deserialize_f64 deserialize_char deserialize_str deserialize_string
+        deserialize_bytes deserialize_byte_buf deserialize_option deserialize_unit
+        deserialize_seq deserialize_map deserialize_identifier
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.value()?.deserialize_unit_struct(name, visitor)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.value()?.deserialize_newtype_struct(name, visitor)
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(
+        self,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.value()?.deserialize_tuple(len, visitor)
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.value()?.deserialize_tuple_struct(name, len, visitor)
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.value()?.deserialize_struct(name, fields, visitor)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.value()?.deserialize_enum(name, variants, visitor)
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        // an ignored value isn't converted, so that its type doesn't need a serde mapping
+        visitor.visit_unit()
+    }
+}
+
+impl<'de> IntoDeserializer<'de, DeserializeError> for ColumnValue {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
+
+fn json_error(error: serde_json::Error) -> DeserializeError {
+    de::Error::custom(error)
+}
+
+impl<'de> de::Deserializer<'de> for ColumnValue {
+    type Error = DeserializeError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self {
+            ColumnValue::Null => visitor.visit_unit(),
+            ColumnValue::Bool(value) => visitor.visit_bool(value),
+            ColumnValue::I64(value) => visitor.visit_i64(value),
+            ColumnValue::U64(value) => visitor.visit_u64(value),
+            ColumnValue::F64(value) => visitor.visit_f64(value),
+            ColumnValue::String(value) => visitor.visit_string(value),
+            ColumnValue::Json(value) => value.deserialize_any(visitor).map_err(json_error),
+
+            // bytes are a sequence of numbers unless bytes are expected, as for `serde_bytes`
+            ColumnValue::Bytes(value) => visit_seq(value, visitor),
+            ColumnValue::Seq(values) => visit_seq(values, visitor),
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self {
+            ColumnValue::Null => visitor.visit_none(),
+            ColumnValue::Json(value) => value.deserialize_option(visitor).map_err(json_error),
+            value => visitor.visit_some(value),
+        }
+    }
+
+    fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self {
+            ColumnValue::Bytes(value) => visitor.visit_byte_buf(value),
+            value => value.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        self.deserialize_bytes(visitor)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        match self {
+            ColumnValue::Json(value) => value
+                .deserialize_newtype_struct(name, visitor)
+                .map_err(json_error),
+
+            value => visitor.visit_newtype_struct(value),
+        }
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        match self {
+            // e.g. the label of a PostgreSQL enum or a MySQL `ENUM`
+            ColumnValue::String(value) => visitor.visit_enum(value.into_deserializer()),
+
+            ColumnValue::Json(value) => value
+                .deserialize_enum(name, variants, visitor)
+                .map_err(json_error),
+
+            value => value.deserialize_any(visitor),
+        }
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string unit unit_struct seq
+        tuple tuple_struct map struct identifier ignored_any
+    }
+}
+
+fn visit_seq<'de, T, V>(values: Vec<T>, visitor: V) -> Result<V::Value, DeserializeError>
+where
+    T: IntoDeserializer<'de, DeserializeError>,
+    V: Visitor<'de>,
+{
+    let mut seq = SeqDeserializer::new(values.into_iter());
+    let value = visitor.visit_seq(&mut seq)?;
+
+    seq.end()?;
+
+    Ok(value)
+}
@@ -0,0 +1,208 @@
+use std::ffi::{CStr, CString};
+use std::io;
+use std::os::raw::c_int;
+use std::path::{Path, PathBuf};
+use std::ptr::{null, null_mut};
+
+use futures_core::stream::BoxStream;
+use futures_util::{TryFutureExt, TryStreamExt};
+use libsqlite3_sys::{
+    sqlite3_backup_finish, sqlite3_backup_init, sqlite3_backup_pagecount, sqlite3_backup_remaining,
+    sqlite3_backup_step, sqlite3_errstr, sqlite3_open_v2, SQLITE_DONE, SQLITE_OK,
+    SQLITE_OPEN_CREATE, SQLITE_OPEN_READONLY, SQLITE_OPEN_READWRITE,
+};
+
+use crate::error::Error;
+use crate::sqlite::connection::handle::ConnectionHandle;
+use crate::sqlite::{SqliteConnection, SqliteError};
+
+// https://www.sqlite.org/backup.html
+// https://www.sqlite.org/c3ref/backup_finish.html
+
+/// A backup of the main database of a [`SqliteConnection`] to a file, or a restore of it from
+/// one, using the [online backup API](https://www.sqlite.org/backup.html) of SQLite.
+///
+/// Returned by [`SqliteConnection::backup()`] and [`SqliteConnection::restore()`]. Nothing is
+/// copied until it is [run](Self::run) or its [progress](Self::progress) is polled.
+#[must_use = "a backup does nothing unless it is run or its progress is polled"]
+pub struct SqliteBackup<'c> {
+    conn: &'c mut SqliteConnection,
+    path: PathBuf,
+    restore: bool,
+    pages_per_step: c_int,
+}
+
+/// The progress of a [`SqliteBackup`], reported after each step.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SqliteBackupProgress {
+    remaining: u32,
+    total: u32,
+}
+
+impl SqliteBackupProgress {
+    /// The number of pages still to be copied.
+    pub fn remaining(&self) -> u32 {
+        self.remaining
+    }
+
+    /// The number of pages of the database being copied.
+    ///
+    /// This can change between steps, as the database is written to by other connections.
+    pub fn total(&self) -> u32 {
+        self.total
+    }
+}
+
+impl<'c> SqliteBackup<'c> {
+    pub(crate) fn new(conn: &'c mut SqliteConnection, path: &Path, restore: bool) -> Self {
+        Self {
+            conn,
+            path: path.to_owned(),
+            restore,
+            pages_per_step: -1,
+        }
+    }
+
+    /// Copy up to `pages` pages in each step, instead of all of them at once.
+    ///
+    /// The database is only locked during a step, so other connections can use it in between.
+    /// If another connection writes to the database being copied, the backup starts over.
+    pub fn with_pages_per_step(mut self, pages: u32) -> Self {
+        // a negative number would copy all pages at once, and zero none
+        self.pages_per_step = match c_int::try_from(pages) {
+            Ok(0) => 1,
+            Ok(pages) => pages,
+            Err(_) => c_int::MAX,
+        };
+        self
+    }
+
+    /// Run the backup, yielding its progress after each step.
+    ///
+    /// The next step only runs once the previous progress has been received, so the backup can
+    /// be throttled by waiting in between. Dropping the stream stops the backup, leaving the
+    /// destination partially written.
+    pub fn progress(self) -> BoxStream<'c, Result<SqliteBackupProgress, Error>> {
+        Box::pin(
+            self.conn
+                .worker
+                .backup(self.path, self.restore, self.pages_per_step)
+                .map_ok(flume::Receiver::into_stream)
+                .try_flatten_stream(),
+        )
+    }
+
+    /// Run the backup to completion.
+    pub async fn run(self) -> Result<(), Error> {
+        let mut progress = self.progress();
+
+        while progress.try_next().await?.is_some() {}
+
+        Ok(())
+    }
+}
+
+/// Copy the main database of `handle` to the file at `path`, or the other way around when
+/// restoring, calling `progress` after each step until it returns `false`.
+pub(crate) fn backup(
+    handle: &mut ConnectionHandle,
+    path: &Path,
+    restore: bool,
+    pages_per_step: c_int,
+    mut progress: impl FnMut(SqliteBackupProgress) -> bool,
+) -> Result<(), Error> {
+    let filename = path
+        .to_str()
+        .and_then(|path| CString::new(path).ok())
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                "filename passed to SQLite must be valid UTF-8 without nul bytes",
+            )
+        })?;
+
+    let flags = if restore {
+        SQLITE_OPEN_READONLY
+    } else {
+        SQLITE_OPEN_READWRITE | SQLITE_OPEN_CREATE
+    };
+
+    let mut file = null_mut();
+
+    // <https://www.sqlite.org/c3ref/open.html>
+    let status = unsafe { sqlite3_open_v2(filename.as_ptr(), &mut file, flags, null()) };
+
+    if file.is_null() {
+        return Err(Error::Io(io::Error::new(
+            io::ErrorKind::OutOfMemory,
+            "SQLite is unable to allocate memory to hold the sqlite3 object",
+        )));
+    }
+
+    // SAFE: tested for NULL just above, and closed when dropped
+    let file = unsafe { ConnectionHandle::new(file) };
+
+    if status != SQLITE_OK {
+        return Err(Error::Database(Box::new(SqliteError::new(file.as_ptr()))));
+    }
+
+    let (source, destination) = if restore {
+        (file.as_ptr(), handle.as_ptr())
+    } else {
+        (handle.as_ptr(), file.as_ptr())
+    };
+
+    let main = c"main";
+
+    // SAFE: both connections are open and only used from this thread until the backup is
+    // finished, below
+    let backup = unsafe { sqlite3_backup_init(destination, main.as_ptr(), source, main.as_ptr()) };
+
+    if backup.is_null() {
+        // the error is set on the destination connection
+        return Err(Error::Database(Box::new(SqliteError::new(destination))));
+    }
+
+    let mut failed = None;
+
+    loop {
+        let status = unsafe { sqlite3_backup_step(backup, pages_per_step) };
+
+        if status != SQLITE_OK && status != SQLITE_DONE {
+            // e.g. SQLITE_BUSY or SQLITE_LOCKED for a locked database, which aren't set on the
+            // destination connection
+            let message = unsafe { CStr::from_ptr(sqlite3_errstr(status)) };
+
+            failed = Some(SqliteError::with_message(
+                status,
+                message.to_string_lossy().into_owned(),
+            ));
+
+            break;
+        }
+
+        let current = unsafe {
+            SqliteBackupProgress {
+                remaining: sqlite3_backup_remaining(backup) as u32,
+                total: sqlite3_backup_pagecount(backup) as u32,
+            }
+        };
+
+        if !progress(current) || status == SQLITE_DONE {
+            break;
+        }
+    }
+
+    // releases the backup, reporting errors such as I/O or out-of-memory errors of any step
+    let status = unsafe { sqlite3_backup_finish(backup) };
+
+    if let Some(error) = failed {
+        return Err(Error::Database(Box::new(error)));
+    }
+
+    if status != SQLITE_OK {
+        return Err(Error::Database(Box::new(SqliteError::new(destination))));
+    }
+
+    Ok(())
+}
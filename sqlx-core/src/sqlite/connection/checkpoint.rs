@@ -0,0 +1,149 @@
+use std::os::raw::c_int;
+use std::time::Duration;
+
+use libsqlite3_sys::{
+    sqlite3_wal_checkpoint_v2, SQLITE_BUSY, SQLITE_CHECKPOINT_FULL, SQLITE_CHECKPOINT_PASSIVE,
+    SQLITE_CHECKPOINT_RESTART, SQLITE_CHECKPOINT_TRUNCATE, SQLITE_OK,
+};
+
+use crate::error::Error;
+use crate::pool::Pool;
+use crate::sqlite::connection::handle::ConnectionHandle;
+use crate::sqlite::{Sqlite, SqliteError};
+
+// https://www.sqlite.org/wal.html#ckpt
+// https://www.sqlite.org/c3ref/wal_checkpoint_v2.html
+
+/// Refer to [SQLite documentation] for the meaning of the checkpoint modes.
+///
+/// [SQLite documentation]: https://www.sqlite.org/c3ref/wal_checkpoint_v2.html
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SqliteCheckpointMode {
+    /// Copy as much of the WAL as possible to the database without waiting for readers or
+    /// writers to finish, which is what the automatic checkpoints do.
+    Passive,
+
+    /// Wait for the writers to finish and for the readers to see the latest changes, then copy
+    /// the whole WAL to the database.
+    Full,
+
+    /// Like [`Full`](Self::Full), then wait for the readers to finish reading from the WAL so
+    /// that the next writer starts it over.
+    Restart,
+
+    /// Like [`Restart`](Self::Restart), then truncate the WAL file to zero bytes.
+    Truncate,
+}
+
+impl SqliteCheckpointMode {
+    fn as_c_int(&self) -> c_int {
+        match self {
+            SqliteCheckpointMode::Passive => SQLITE_CHECKPOINT_PASSIVE,
+            SqliteCheckpointMode::Full => SQLITE_CHECKPOINT_FULL,
+            SqliteCheckpointMode::Restart => SQLITE_CHECKPOINT_RESTART,
+            SqliteCheckpointMode::Truncate => SQLITE_CHECKPOINT_TRUNCATE,
+        }
+    }
+}
+
+/// The outcome of [`SqliteConnection::checkpoint()`](super::SqliteConnection::checkpoint).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SqliteCheckpointResult {
+    busy: bool,
+    wal_pages: u32,
+    checkpointed_pages: u32,
+}
+
+impl SqliteCheckpointResult {
+    /// Whether the checkpoint didn't complete, as other connections were still reading or
+    /// writing after the [busy timeout](crate::sqlite::SqliteConnectOptions::busy_timeout).
+    ///
+    /// Only checkpoints in a mode other than [`Passive`](SqliteCheckpointMode::Passive) wait,
+    /// and can be busy. A busy checkpoint may still have copied some pages to the database.
+    pub fn is_busy(&self) -> bool {
+        self.busy
+    }
+
+    /// The number of pages in the WAL, or zero if the database isn't in WAL mode.
+    pub fn wal_pages(&self) -> u32 {
+        self.wal_pages
+    }
+
+    /// The number of pages of the WAL now copied to the database, including those copied by
+    /// previous checkpoints.
+    pub fn checkpointed_pages(&self) -> u32 {
+        self.checkpointed_pages
+    }
+}
+
+/// Checkpoint the WAL of the main database of `handle`.
+pub(crate) fn checkpoint(
+    handle: &mut ConnectionHandle,
+    mode: SqliteCheckpointMode,
+) -> Result<SqliteCheckpointResult, Error> {
+    let main = c"main";
+    let mut wal_pages: c_int = 0;
+    let mut checkpointed_pages: c_int = 0;
+
+    let status = unsafe {
+        sqlite3_wal_checkpoint_v2(
+            handle.as_ptr(),
+            main.as_ptr(),
+            mode.as_c_int(),
+            &mut wal_pages,
+            &mut checkpointed_pages,
+        )
+    };
+
+    if status != SQLITE_OK && status != SQLITE_BUSY {
+        return Err(Error::Database(Box::new(SqliteError::new(handle.as_ptr()))));
+    }
+
+    // both are -1 if the database isn't in WAL mode
+    Ok(SqliteCheckpointResult {
+        busy: status == SQLITE_BUSY,
+        wal_pages: u32::try_from(wal_pages).unwrap_or(0),
+        checkpointed_pages: u32::try_from(checkpointed_pages).unwrap_or(0),
+    })
+}
+
+impl Pool<Sqlite> {
+    /// Spawn a task checkpointing the WAL with a connection of this pool every `period`, until
+    /// the pool is closed.
+    ///
+    /// The automatic checkpoints of SQLite are [passive](SqliteCheckpointMode::Passive), so they
+    /// can't keep the WAL from growing while it is always being read from. Checkpointing in
+    /// another mode from time to time, ideally with automatic checkpoints turned off by
+    /// [`wal_autocheckpoint(0)`](crate::sqlite::SqliteConnectOptions::wal_autocheckpoint),
+    /// waits for a moment without readers instead.
+    ///
+    /// Errors, as well as busy checkpoints, are logged and the task carries on.
+    pub fn spawn_checkpointer(&self, period: Duration, mode: SqliteCheckpointMode) {
+        let pool = self.clone();
+
+        sqlx_rt::spawn(async move {
+            // Immediately cancel this task if the pool is closed.
+            let _ = pool
+                .close_event()
+                .do_until(async {
+                    loop {
+                        sqlx_rt::sleep(period).await;
+
+                        let result = match pool.acquire().await {
+                            Ok(mut conn) => conn.checkpoint(mode).await,
+                            Err(e) => Err(e),
+                        };
+
+                        match result {
+                            Ok(result) if result.is_busy() => {
+                                log::debug!("checkpoint was busy: {:?}", result)
+                            }
+                            Ok(_) => {}
+                            Err(e) => log::warn!("error while checkpointing: {}", e),
+                        }
+                    }
+                })
+                .await;
+        });
+    }
+}
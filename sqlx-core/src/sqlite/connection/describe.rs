@@ -90,5 +90,6 @@ pub(crate) fn describe(conn: &mut ConnectionState, query: &str) -> Result<Descri
         columns,
         parameters: Some(Either::Right(num_params)),
         nullable,
+        domains: Vec::new(),
     })
 }
@@ -1,17 +1,25 @@
 use crate::connection::LogSettings;
 use crate::error::Error;
 use crate::sqlite::connection::handle::ConnectionHandle;
+use crate::sqlite::connection::interrupt::{ProgressFn, ProgressHandler, SqliteInterruptHandle};
 use crate::sqlite::connection::{ConnectionState, Statements};
-use crate::sqlite::{SqliteConnectOptions, SqliteError};
+use crate::sqlite::{
+    SqliteConnectOptions, SqliteError, SqliteHardening, SqliteTransactionBehavior,
+};
 use libsqlite3_sys::{
-    sqlite3_busy_timeout, sqlite3_extended_result_codes, sqlite3_open_v2, SQLITE_OK,
+    sqlite3, sqlite3_busy_timeout, sqlite3_db_config, sqlite3_extended_result_codes, sqlite3_free,
+    sqlite3_limit, sqlite3_load_extension, sqlite3_open_v2, SQLITE_DBCONFIG_DEFENSIVE,
+    SQLITE_DBCONFIG_ENABLE_LOAD_EXTENSION, SQLITE_DBCONFIG_TRUSTED_SCHEMA, SQLITE_LIMIT_ATTACHED,
+    SQLITE_LIMIT_EXPR_DEPTH, SQLITE_LIMIT_LENGTH, SQLITE_LIMIT_SQL_LENGTH, SQLITE_OK,
     SQLITE_OPEN_CREATE, SQLITE_OPEN_FULLMUTEX, SQLITE_OPEN_MEMORY, SQLITE_OPEN_NOMUTEX,
     SQLITE_OPEN_PRIVATECACHE, SQLITE_OPEN_READONLY, SQLITE_OPEN_READWRITE, SQLITE_OPEN_SHAREDCACHE,
 };
-use std::ffi::CString;
+use std::ffi::{CStr, CString};
 use std::io;
+use std::os::raw::{c_int, c_void};
 use std::ptr::{null, null_mut};
 use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
 use std::time::Duration;
 
 static THREAD_ID: AtomicU64 = AtomicU64::new(0);
@@ -20,8 +28,13 @@ pub struct EstablishParams {
     filename: CString,
     open_flags: i32,
     busy_timeout: Duration,
+    transaction_behavior: SqliteTransactionBehavior,
+    hardening: SqliteHardening,
+    extensions: Vec<(CString, Option<CString>)>,
+    progress_handler: Option<(u32, Arc<ProgressFn>)>,
+    interrupt_on_drop: bool,
     statement_cache_capacity: usize,
-    log_settings: LogSettings,
+    pub(crate) log_settings: LogSettings,
     pub(crate) thread_name: String,
     pub(crate) command_channel_size: usize,
 }
@@ -49,7 +62,8 @@ impl EstablishParams {
             SQLITE_OPEN_NOMUTEX
         };
 
-        flags |= if options.read_only {
+        // an immutable database can only be opened for reading
+        flags |= if options.read_only || options.immutable {
             SQLITE_OPEN_READONLY
         } else if options.create_if_missing {
             SQLITE_OPEN_CREATE | SQLITE_OPEN_READWRITE
@@ -70,7 +84,7 @@ impl EstablishParams {
         let mut query_params: Vec<String> = vec![];
 
         if options.immutable {
-            query_params.push("immutable=true".into())
+            query_params.push("immutable=1".into())
         }
 
         if let Some(vfs) = &options.vfs {
@@ -89,22 +103,48 @@ impl EstablishParams {
             )
         })?;
 
+        let extensions = options
+            .extensions
+            .iter()
+            .map(|(name, entry_point)| {
+                let to_cstring = |s: &str| {
+                    CString::new(s).map_err(|_| {
+                        io::Error::new(
+                            io::ErrorKind::InvalidData,
+                            "extension names passed to SQLite must not contain nul bytes",
+                        )
+                    })
+                };
+
+                Ok((
+                    to_cstring(name)?,
+                    entry_point.as_deref().map(to_cstring).transpose()?,
+                ))
+            })
+            .collect::<Result<_, io::Error>>()?;
+
         Ok(Self {
             filename,
             open_flags: flags,
             busy_timeout: options.busy_timeout,
+            transaction_behavior: options.transaction_behavior,
+            hardening: options.hardening,
+            extensions,
+            progress_handler: options.progress_handler.clone(),
+            interrupt_on_drop: options.interrupt_on_drop,
             statement_cache_capacity: options.statement_cache_capacity,
-            log_settings: options.log_settings.clone(),
+            log_settings: log_settings(options),
             thread_name: (options.thread_name)(THREAD_ID.fetch_add(1, Ordering::AcqRel)),
             command_channel_size: options.command_channel_size,
         })
     }
 
-    pub(crate) fn establish(&self) -> Result<ConnectionState, Error> {
+    /// Open a connection, without configuring it.
+    pub(crate) fn open(&self) -> Result<ConnectionHandle, Error> {
         let mut handle = null_mut();
 
         // <https://www.sqlite.org/c3ref/open.html>
-        let mut status = unsafe {
+        let status = unsafe {
             sqlite3_open_v2(self.filename.as_ptr(), &mut handle, self.open_flags, null())
         };
 
@@ -124,6 +164,12 @@ impl EstablishParams {
             return Err(Error::Database(Box::new(SqliteError::new(handle.as_ptr()))));
         }
 
+        Ok(handle)
+    }
+
+    pub(crate) fn establish(&self) -> Result<ConnectionState, Error> {
+        let mut handle = self.open()?;
+
         // Enable extended result codes
         // https://www.sqlite.org/c3ref/extended_result_codes.html
         unsafe {
@@ -135,21 +181,163 @@ impl EstablishParams {
         // This causes SQLite to automatically sleep in increasing intervals until the time
         // when there is something locked during [sqlite3_step].
         //
-        // We also need to convert the u128 value to i32, checking we're not overflowing.
-        let ms = i32::try_from(self.busy_timeout.as_millis())
-            .expect("Given busy timeout value is too big.");
+        // We also need to convert the u128 value to i32, waiting as long as we can (about 24 days)
+        // for a longer timeout.
+        let ms = i32::try_from(self.busy_timeout.as_millis()).unwrap_or(i32::MAX);
 
-        status = unsafe { sqlite3_busy_timeout(handle.as_ptr(), ms) };
+        let status = unsafe { sqlite3_busy_timeout(handle.as_ptr(), ms) };
 
         if status != SQLITE_OK {
             return Err(Error::Database(Box::new(SqliteError::new(handle.as_ptr()))));
         }
 
+        // Harden the connection before anything is read from the database
+        if self.hardening != SqliteHardening::default() {
+            harden(&mut handle, &self.hardening)?;
+        }
+
+        if !self.extensions.is_empty() {
+            load_extensions(handle.as_ptr(), &self.extensions)?;
+        }
+
+        // The progress handler also checks whether the results of a statement are still received
+        let progress_handler = match (&self.progress_handler, self.interrupt_on_drop) {
+            (Some((n_ops, handler)), _) => Some(ProgressHandler::register(
+                &mut handle,
+                *n_ops,
+                Some(Arc::clone(handler)),
+            )),
+            (None, true) => Some(ProgressHandler::register(&mut handle, 1000, None)),
+            (None, false) => None,
+        };
+
         Ok(ConnectionState {
+            interrupt_handle: SqliteInterruptHandle::new(&handle),
             handle,
             statements: Statements::new(self.statement_cache_capacity),
             transaction_depth: 0,
+            transaction_behavior: self.transaction_behavior,
             log_settings: self.log_settings.clone(),
+            read_only: false,
+            interrupt_on_drop: self.interrupt_on_drop,
+            progress_handler,
+            update_hooks: None,
         })
     }
 }
+
+// <https://www.sqlite.org/c3ref/c_dbconfig_defensive.html>
+// <https://www.sqlite.org/c3ref/limit.html>
+fn harden(handle: &mut ConnectionHandle, hardening: &SqliteHardening) -> Result<(), Error> {
+    for (op, enable) in [
+        (SQLITE_DBCONFIG_DEFENSIVE, hardening.defensive),
+        (SQLITE_DBCONFIG_TRUSTED_SCHEMA, hardening.trusted_schema),
+    ] {
+        let status = unsafe {
+            sqlite3_db_config(
+                handle.as_ptr(),
+                op,
+                enable as c_int,
+                std::ptr::null_mut::<c_int>(),
+            )
+        };
+
+        if status != SQLITE_OK {
+            return Err(Error::Database(Box::new(SqliteError::new(handle.as_ptr()))));
+        }
+    }
+
+    let limits = &hardening.limits;
+
+    for (id, limit) in [
+        (SQLITE_LIMIT_LENGTH, limits.length),
+        (SQLITE_LIMIT_SQL_LENGTH, limits.sql_length),
+        (SQLITE_LIMIT_EXPR_DEPTH, limits.expr_depth),
+        (SQLITE_LIMIT_ATTACHED, limits.attached),
+    ] {
+        if let Some(limit) = limit {
+            // SQLite only ever lowers a limit, to the one it was built with at most
+            let limit = c_int::try_from(limit).unwrap_or(c_int::MAX);
+
+            unsafe {
+                sqlite3_limit(handle.as_ptr(), id, limit);
+            }
+        }
+    }
+
+    if hardening.cell_size_check {
+        handle.exec("PRAGMA cell_size_check = ON")?;
+    }
+
+    Ok(())
+}
+
+// <https://www.sqlite.org/c3ref/load_extension.html>
+fn load_extensions(
+    handle: *mut sqlite3,
+    extensions: &[(CString, Option<CString>)],
+) -> Result<(), Error> {
+    // Enable loading through the C API only, leaving the `load_extension()` SQL function
+    // disabled, and disable it again once done
+    // <https://www.sqlite.org/c3ref/c_dbconfig_defensive.html#sqlitedbconfigenableloadextension>
+    let enable_loading = |enable: c_int| {
+        let status = unsafe {
+            sqlite3_db_config(
+                handle,
+                SQLITE_DBCONFIG_ENABLE_LOAD_EXTENSION,
+                enable,
+                std::ptr::null_mut::<c_int>(),
+            )
+        };
+
+        if status == SQLITE_OK {
+            Ok(())
+        } else {
+            Err(Error::Database(Box::new(SqliteError::new(handle))))
+        }
+    };
+
+    enable_loading(1)?;
+
+    for (name, entry_point) in extensions {
+        let mut message = null_mut();
+
+        let status = unsafe {
+            sqlite3_load_extension(
+                handle,
+                name.as_ptr(),
+                entry_point.as_ref().map_or(null(), |e| e.as_ptr()),
+                &mut message,
+            )
+        };
+
+        if status != SQLITE_OK {
+            // the message is allocated by SQLite, and includes the error of `dlopen()`
+            let message = if message.is_null() {
+                format!("failed to load extension {:?}", name)
+            } else {
+                unsafe {
+                    let m = CStr::from_ptr(message).to_string_lossy().into_owned();
+                    sqlite3_free(message as *mut c_void);
+                    m
+                }
+            };
+
+            return Err(Error::Database(Box::new(SqliteError::with_message(
+                status, message,
+            ))));
+        }
+    }
+
+    enable_loading(0)
+}
+
+fn log_settings(options: &SqliteConnectOptions) -> LogSettings {
+    #[allow(unused_mut)]
+    let mut settings = options.log_settings.for_connection();
+
+    #[cfg(feature = "tracing")]
+    settings.trace_server("sqlite", None, None);
+
+    settings
+}
@@ -1,9 +1,10 @@
 use crate::error::Error;
-use crate::logger::QueryLogger;
+use crate::logger::{QueryLogger, QuerySpan};
 use crate::sqlite::connection::{ConnectionHandle, ConnectionState};
 use crate::sqlite::statement::{StatementHandle, VirtualStatement};
-use crate::sqlite::{SqliteArguments, SqliteQueryResult, SqliteRow};
+use crate::sqlite::{SqliteArguments, SqliteError, SqliteQueryResult, SqliteRow};
 use either::Either;
+use libsqlite3_sys::SQLITE_READONLY;
 
 pub struct ExecuteIter<'a> {
     handle: &'a mut ConnectionHandle,
@@ -16,6 +17,8 @@ pub struct ExecuteIter<'a> {
     args_used: usize,
 
     goto_next: bool,
+
+    read_only: bool,
 }
 
 pub(crate) fn iter<'a>(
@@ -23,11 +26,33 @@ pub(crate) fn iter<'a>(
     query: &'a str,
     args: Option<SqliteArguments<'a>>,
     persistent: bool,
+) -> Result<ExecuteIter<'a>, Error> {
+    let span = QuerySpan::new(query, &conn.log_settings);
+
+    iter_in(conn, query, args, persistent, span)
+}
+
+/// Like [`iter`], recording the statement on a span created by the caller, e.g. on the thread
+/// which sent the statement to the worker.
+pub(crate) fn iter_in<'a>(
+    conn: &'a mut ConnectionState,
+    query: &'a str,
+    args: Option<SqliteArguments<'a>>,
+    persistent: bool,
+    span: QuerySpan,
 ) -> Result<ExecuteIter<'a>, Error> {
     // fetch the cached statement or allocate a new one
     let statement = conn.statements.get(query, persistent)?;
 
-    let logger = QueryLogger::new(query, conn.log_settings.clone());
+    let parameters = args.as_ref().map_or(0, |args| args.values.len());
+    let logger = QueryLogger::new(
+        query,
+        parameters,
+        persistent,
+        conn.log_settings.clone(),
+        span,
+    );
+    let read_only = conn.read_only;
 
     Ok(ExecuteIter {
         handle: &mut conn.handle,
@@ -36,6 +61,7 @@ pub(crate) fn iter<'a>(
         args,
         args_used: 0,
         goto_next: true,
+        read_only,
     })
 }
 
@@ -61,12 +87,8 @@ impl ExecuteIter<'_> {
 
         Ok(())
     }
-}
 
-impl Iterator for ExecuteIter<'_> {
-    type Item = Result<Either<SqliteQueryResult, SqliteRow>, Error>;
-
-    fn next(&mut self) -> Option<Self::Item> {
+    fn step(&mut self) -> Option<Result<Either<SqliteQueryResult, SqliteRow>, Error>> {
         let statement = if self.goto_next {
             let mut statement = match self.statement.prepare_next(self.handle) {
                 Ok(Some(statement)) => statement,
@@ -101,6 +123,7 @@ impl Iterator for ExecuteIter<'_> {
                     &statement.handle,
                     &statement.columns,
                     &statement.column_names,
+                    self.logger.context(),
                 ))))
             }
             Ok(false) => {
@@ -118,11 +141,29 @@ impl Iterator for ExecuteIter<'_> {
 
                 Some(Ok(Either::Left(done)))
             }
+            // with a clearer message than SQLite's, which doesn't tell the connection is read-only;
+            // the statement may still write to the `temp` schema, so only SQLite can tell
+            Err(e) if self.read_only && e.result_code() == SQLITE_READONLY => {
+                Some(Err(Error::Database(Box::new(SqliteError::with_message(
+                    SQLITE_READONLY,
+                    "attempt to write to the database with a read-only connection".into(),
+                )))))
+            }
             Err(e) => Some(Err(e.into())),
         }
     }
 }
 
+impl Iterator for ExecuteIter<'_> {
+    type Item = Result<Either<SqliteQueryResult, SqliteRow>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.step()?;
+
+        Some(item.map_err(|error| self.logger.errors().record(error)))
+    }
+}
+
 impl Drop for ExecuteIter<'_> {
     fn drop(&mut self) {
         self.statement.reset().ok();
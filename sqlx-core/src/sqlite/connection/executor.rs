@@ -1,6 +1,7 @@
 use crate::describe::Describe;
 use crate::error::Error;
 use crate::executor::{Execute, Executor};
+use crate::logger::QuerySpan;
 use crate::sqlite::{
     Sqlite, SqliteConnection, SqliteQueryResult, SqliteRow, SqliteStatement, SqliteTypeInfo,
 };
@@ -20,15 +21,26 @@ impl<'c> Executor<'c> for &'c mut SqliteConnection {
         'c: 'e,
         E: Execute<'q, Self::Database>,
     {
+        if let Some(error) = query.take_error() {
+            return Box::pin(futures_util::stream::once(async move { Err(error) }));
+        }
+
         let sql = query.sql();
+        let prepared_by = query.statement().and_then(|s| s.connection_id);
         let arguments = query.take_arguments();
         let persistent = query.persistent() && arguments.is_some();
+        let span = QuerySpan::new(sql, &self.log_settings);
 
         Box::pin(
-            self.worker
-                .execute(sql, arguments, self.row_channel_size, persistent)
-                .map_ok(flume::Receiver::into_stream)
-                .try_flatten_stream(),
+            async move {
+                self.check_prepared_by(prepared_by)?;
+
+                self.worker
+                    .execute(sql, arguments, self.row_channel_size, persistent, span)
+                    .await
+            }
+            .map_ok(flume::Receiver::into_stream)
+            .try_flatten_stream(),
         )
     }
 
@@ -40,14 +52,22 @@ impl<'c> Executor<'c> for &'c mut SqliteConnection {
         'c: 'e,
         E: Execute<'q, Self::Database>,
     {
+        if let Some(error) = query.take_error() {
+            return Box::pin(async move { Err(error) });
+        }
+
         let sql = query.sql();
+        let prepared_by = query.statement().and_then(|s| s.connection_id);
         let arguments = query.take_arguments();
         let persistent = query.persistent() && arguments.is_some();
+        let span = QuerySpan::new(sql, &self.log_settings);
 
         Box::pin(async move {
+            self.check_prepared_by(prepared_by)?;
+
             let stream = self
                 .worker
-                .execute(sql, arguments, self.row_channel_size, persistent)
+                .execute(sql, arguments, self.row_channel_size, persistent, span)
                 .map_ok(flume::Receiver::into_stream)
                 .try_flatten_stream();
 
@@ -76,6 +96,7 @@ impl<'c> Executor<'c> for &'c mut SqliteConnection {
 
             Ok(SqliteStatement {
                 sql: sql.into(),
+                connection_id: Some(self.log_settings.connection_id),
                 ..statement
             })
         })
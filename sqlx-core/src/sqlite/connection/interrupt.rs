@@ -0,0 +1,130 @@
+use std::cell::RefCell;
+use std::ops::ControlFlow;
+use std::os::raw::{c_int, c_void};
+use std::sync::{Arc, Mutex};
+
+use either::Either;
+use libsqlite3_sys::{sqlite3_interrupt, sqlite3_progress_handler};
+
+use crate::common::DebugFn;
+use crate::error::Error;
+use crate::sqlite::connection::handle::{ConnectionHandle, ConnectionHandleRaw};
+use crate::sqlite::{SqliteQueryResult, SqliteRow};
+
+// https://www.sqlite.org/c3ref/interrupt.html
+// https://www.sqlite.org/c3ref/progress_handler.html
+
+pub(crate) type ProgressFn = DebugFn<dyn Fn() -> ControlFlow<()> + Send + Sync + 'static>;
+
+type ResultsSender = flume::Sender<Result<Either<SqliteQueryResult, SqliteRow>, Error>>;
+
+/// A handle to interrupt the statement executed by a
+/// [`SqliteConnection`](super::SqliteConnection), from any thread.
+///
+/// Returned by [`SqliteConnection::interrupt_handle()`](super::SqliteConnection::interrupt_handle).
+#[derive(Debug, Clone)]
+pub struct SqliteInterruptHandle {
+    // `None` once the connection is closed
+    handle: Arc<Mutex<Option<ConnectionHandleRaw>>>,
+}
+
+impl SqliteInterruptHandle {
+    pub(crate) fn new(handle: &ConnectionHandle) -> Self {
+        Self {
+            handle: Arc::new(Mutex::new(Some(handle.to_raw()))),
+        }
+    }
+
+    /// Make the statement being executed by the connection fail with an error of the kind
+    /// [`ErrorKind::Interrupted`](crate::error::ErrorKind::Interrupted).
+    ///
+    /// This does nothing if no statement is being executed, or once the connection is closed.
+    /// A transaction which was open remains so, unless the statement was a `COMMIT`, in which
+    /// case it is rolled back.
+    pub fn interrupt(&self) {
+        let handle = self.handle.lock().unwrap_or_else(|e| e.into_inner());
+
+        if let Some(handle) = &*handle {
+            // SAFE: the connection can't be closed while the lock is held, see `close()`
+            unsafe { sqlite3_interrupt(handle.as_ptr()) }
+        }
+    }
+
+    /// Called before the connection is closed, as it must not be interrupted afterwards.
+    pub(crate) fn close(&self) {
+        *self.handle.lock().unwrap_or_else(|e| e.into_inner()) = None;
+    }
+}
+
+/// The data passed to the progress handler, owned by the [`ConnectionState`].
+///
+/// [`ConnectionState`]: super::ConnectionState
+pub(crate) struct ProgressHandler {
+    handler: Option<Arc<ProgressFn>>,
+
+    // the channel of the results of the statement being executed, when it is interrupted once
+    // they can no longer be received
+    results: RefCell<Option<ResultsSender>>,
+}
+
+impl ProgressHandler {
+    /// Register the progress handler on the connection, to be called every `n_ops` virtual
+    /// machine instructions.
+    pub(crate) fn register(
+        handle: &mut ConnectionHandle,
+        n_ops: u32,
+        handler: Option<Arc<ProgressFn>>,
+    ) -> Box<Self> {
+        let progress = Box::new(ProgressHandler {
+            handler,
+            results: RefCell::new(None),
+        });
+
+        // zero or less would unregister the handler
+        let n_ops = match c_int::try_from(n_ops) {
+            Ok(0) => 1,
+            Ok(n_ops) => n_ops,
+            Err(_) => c_int::MAX,
+        };
+
+        // SAFE: the data is kept alive by the connection state, and the handler is only called
+        // from the thread using the connection
+        unsafe {
+            sqlite3_progress_handler(
+                handle.as_ptr(),
+                n_ops,
+                Some(progress_handler),
+                &*progress as *const ProgressHandler as *mut c_void,
+            );
+        }
+
+        progress
+    }
+
+    /// Interrupt the statement being executed if the receiver of `results` is dropped, until
+    /// [`unwatch()`](Self::unwatch) is called.
+    pub(crate) fn watch(&self, results: &ResultsSender) {
+        *self.results.borrow_mut() = Some(results.clone());
+    }
+
+    pub(crate) fn unwatch(&self) {
+        // releases the sender, so that the receiver sees the end of the results
+        self.results.borrow_mut().take();
+    }
+}
+
+unsafe extern "C" fn progress_handler(data: *mut c_void) -> c_int {
+    let progress = &*(data as *const ProgressHandler);
+
+    let abandoned =
+        matches!(&*progress.results.borrow(), Some(results) if results.is_disconnected());
+
+    let interrupt = abandoned
+        || match &progress.handler {
+            Some(handler) => handler().is_break(),
+            None => false,
+        };
+
+    // non-zero interrupts the statement
+    interrupt as c_int
+}
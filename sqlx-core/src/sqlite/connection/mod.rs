@@ -1,5 +1,6 @@
 use std::cmp::Ordering;
 use std::fmt::{self, Debug, Formatter};
+use std::path::Path;
 use std::ptr::NonNull;
 
 use futures_core::future::BoxFuture;
@@ -7,24 +8,42 @@ use futures_intrusive::sync::MutexGuard;
 use futures_util::future;
 use libsqlite3_sys::sqlite3;
 
+pub use backup::{SqliteBackup, SqliteBackupProgress};
+pub use checkpoint::{SqliteCheckpointMode, SqliteCheckpointResult};
 pub(crate) use handle::{ConnectionHandle, ConnectionHandleRaw};
+pub use interrupt::SqliteInterruptHandle;
+pub use query_plan::{SqliteQueryPlan, SqliteQueryPlanNode};
+#[cfg(feature = "sqlite-snapshot")]
+pub use snapshot::SqliteSnapshot;
+pub use updates::{SqliteOperation, SqliteUpdate, SqliteUpdates, SqliteUpdatesOptions};
 
 use crate::common::StatementCache;
 use crate::connection::{Connection, LogSettings};
 use crate::error::Error;
 use crate::sqlite::connection::establish::EstablishParams;
+use crate::sqlite::connection::interrupt::ProgressHandler;
+use crate::sqlite::connection::updates::UpdateHooks;
 use crate::sqlite::connection::worker::ConnectionWorker;
 use crate::sqlite::statement::VirtualStatement;
-use crate::sqlite::{Sqlite, SqliteConnectOptions};
-use crate::transaction::Transaction;
+use crate::sqlite::{Sqlite, SqliteConnectOptions, SqliteStatement, SqliteTransactionBehavior};
+use crate::statement::PreparedStatementInfo;
+use crate::transaction::{Transaction, TransactionOptions};
 
+mod backup;
+mod checkpoint;
 pub(crate) mod collation;
 pub(crate) mod describe;
 pub(crate) mod establish;
 pub(crate) mod execute;
 mod executor;
 mod explain;
-mod handle;
+pub(crate) mod handle;
+pub(crate) mod interrupt;
+mod query_plan;
+mod serialize;
+#[cfg(feature = "sqlite-snapshot")]
+mod snapshot;
+mod updates;
 
 mod worker;
 
@@ -41,6 +60,7 @@ mod worker;
 pub struct SqliteConnection {
     pub(crate) worker: ConnectionWorker,
     pub(crate) row_channel_size: usize,
+    pub(crate) log_settings: LogSettings,
 }
 
 pub struct LockedSqliteHandle<'a> {
@@ -52,15 +72,27 @@ pub(crate) struct ConnectionState {
 
     // transaction status
     pub(crate) transaction_depth: usize,
+    // how the transactions begin, unless overridden by their options
+    pub(crate) transaction_behavior: SqliteTransactionBehavior,
 
     pub(crate) statements: Statements,
 
     log_settings: LogSettings,
+
+    // report the writes SQLite refuses as made with a read-only connection, once it is set up
+    pub(crate) read_only: bool,
+
+    pub(crate) interrupt_handle: SqliteInterruptHandle,
+    pub(crate) interrupt_on_drop: bool,
+
+    // must be dropped after `handle`, see `UpdateHooks`
+    pub(crate) progress_handler: Option<Box<ProgressHandler>>,
+    pub(crate) update_hooks: Option<Box<UpdateHooks>>,
 }
 
 pub(crate) struct Statements {
-    // cache of semi-persistent statements
-    cached: StatementCache<VirtualStatement>,
+    // cache of semi-persistent statements, with the number of times each was reused
+    cached: StatementCache<(VirtualStatement, u64)>,
     // most recent non-persistent statement
     temp: Option<VirtualStatement>,
 }
@@ -68,10 +100,12 @@ pub(crate) struct Statements {
 impl SqliteConnection {
     pub(crate) async fn establish(options: &SqliteConnectOptions) -> Result<Self, Error> {
         let params = EstablishParams::from_options(options)?;
+        let log_settings = params.log_settings.clone();
         let worker = ConnectionWorker::establish(params).await?;
         Ok(Self {
             worker,
             row_channel_size: options.row_channel_size,
+            log_settings,
         })
     }
 
@@ -88,6 +122,15 @@ impl SqliteConnection {
         self.worker.handle_raw.as_ptr()
     }
 
+    /// Returns a handle to interrupt the statement this connection is executing, which can be
+    /// used from any thread, e.g. to give up on a long query.
+    ///
+    /// See also [`SqliteConnectOptions::interrupt_on_drop()`] to interrupt queries which are no
+    /// longer awaited.
+    pub fn interrupt_handle(&self) -> SqliteInterruptHandle {
+        self.worker.interrupt_handle.clone()
+    }
+
     /// Apply a collation to the open database.
     ///
     /// See [`SqliteConnectOptions::collation()`] for details.
@@ -119,6 +162,235 @@ impl SqliteConnection {
         self.worker.create_collation(name, compare)
     }
 
+    /// Copy the main database of this connection to the database file at `destination`,
+    /// creating it if it doesn't exist.
+    ///
+    /// The copy is consistent even while the database is written to, so this can back up a
+    /// live database, or save an in-memory one to disk. An existing destination is replaced.
+    ///
+    /// See [`backup()`](Self::backup) to copy the database in steps.
+    pub async fn backup_to(&mut self, destination: impl AsRef<Path>) -> Result<(), Error> {
+        self.backup(destination).run().await
+    }
+
+    /// Prepare a backup of the main database of this connection to the database file at
+    /// `destination`, which can be run in steps with progress reported after each of them.
+    ///
+    /// ```rust,no_run
+    /// # use sqlx_core::error::Error;
+    /// # use sqlx_core::sqlite::SqliteConnection;
+    /// # use futures_util::TryStreamExt;
+    /// # async fn example(conn: &mut SqliteConnection) -> Result<(), Error> {
+    /// let mut progress = conn.backup("backup.db").with_pages_per_step(100).progress();
+    ///
+    /// while let Some(step) = progress.try_next().await? {
+    ///     println!("{} of {} pages remaining", step.remaining(), step.total());
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// If a database is locked, the backup fails with an [`Error::Database`] with the code
+    /// `SQLITE_BUSY` or `SQLITE_LOCKED`.
+    pub fn backup(&mut self, destination: impl AsRef<Path>) -> SqliteBackup<'_> {
+        SqliteBackup::new(self, destination.as_ref(), false)
+    }
+
+    /// Replace the main database of this connection with a copy of the database file at
+    /// `source`.
+    ///
+    /// See [`restore()`](Self::restore) to copy the database in steps.
+    pub async fn backup_from(&mut self, source: impl AsRef<Path>) -> Result<(), Error> {
+        self.restore(source).run().await
+    }
+
+    /// Prepare to replace the main database of this connection with a copy of the database
+    /// file at `source`, which can be run in steps like a [`backup()`](Self::backup).
+    pub fn restore(&mut self, source: impl AsRef<Path>) -> SqliteBackup<'_> {
+        SqliteBackup::new(self, source.as_ref(), true)
+    }
+
+    /// Copy a database of this connection into the bytes of a database file.
+    ///
+    /// The `schema` is the name of the database, `main` when `None`, or that of a database added
+    /// with `ATTACH DATABASE`, or else [`Error::Configuration`] is returned. The image can be
+    /// loaded into another connection with [`deserialize()`](Self::deserialize), or written to
+    /// disk and opened as a file.
+    ///
+    /// See [`sqlite3_serialize()`](https://www.sqlite.org/c3ref/serialize.html) for details.
+    pub async fn serialize(&mut self, schema: Option<&str>) -> Result<Vec<u8>, Error> {
+        self.worker.serialize(schema.unwrap_or("main")).await
+    }
+
+    /// Replace a database of this connection with an in-memory database loaded from `image`,
+    /// as returned by [`serialize()`](Self::serialize).
+    ///
+    /// The `schema` is the name of the database, `main` when `None`. The database can grow as it
+    /// is written to, unless `read_only` is set, which makes writing to it fail instead. If the
+    /// image was of a database in WAL mode, the in-memory database uses a rollback journal, as
+    /// WAL is not supported in memory.
+    ///
+    /// The image is copied, so it can be reused afterwards.
+    ///
+    /// See [`sqlite3_deserialize()`](https://www.sqlite.org/c3ref/deserialize.html) for details.
+    pub async fn deserialize(
+        &mut self,
+        schema: Option<&str>,
+        image: &[u8],
+        read_only: bool,
+    ) -> Result<(), Error> {
+        self.worker
+            .deserialize(schema.unwrap_or("main"), image.to_vec(), read_only)
+            .await
+    }
+
+    /// Copy the changes in the WAL of the main database back to the database file.
+    ///
+    /// SQLite checkpoints automatically once the WAL exceeds the
+    /// [`wal_autocheckpoint`](crate::sqlite::SqliteConnectOptions::wal_autocheckpoint) size, but
+    /// only as far as the oldest reader allows. A checkpoint which didn't complete, because other
+    /// connections were still using the WAL, isn't an error but a result which
+    /// [is busy](SqliteCheckpointResult::is_busy).
+    ///
+    /// Does nothing for a database that isn't in WAL mode. See also
+    /// [`Pool::spawn_checkpointer()`](crate::pool::Pool::spawn_checkpointer) to checkpoint in
+    /// the background.
+    ///
+    /// See [`sqlite3_wal_checkpoint_v2()`](https://www.sqlite.org/c3ref/wal_checkpoint_v2.html)
+    /// for details.
+    pub async fn checkpoint(
+        &mut self,
+        mode: SqliteCheckpointMode,
+    ) -> Result<SqliteCheckpointResult, Error> {
+        self.worker.checkpoint(mode).await
+    }
+
+    /// Ask SQLite how it would run the statement `query`, without running it.
+    ///
+    /// Any parameters of the statement are left unbound, which doesn't change the plan. Only a
+    /// single statement can be explained; [`Error::Configuration`] is returned for any other
+    /// number of statements.
+    ///
+    /// ```rust,no_run
+    /// # use sqlx_core::error::Error;
+    /// # use sqlx_core::sqlite::SqliteConnection;
+    /// # async fn example(conn: &mut SqliteConnection) -> Result<(), Error> {
+    /// let plan = conn
+    ///     .explain_query_plan("SELECT * FROM users WHERE email = ?")
+    ///     .await?;
+    ///
+    /// assert!(plan.uses_index_scan_only(), "full table scan:\n{}", plan);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn explain_query_plan(&mut self, query: &str) -> Result<SqliteQueryPlan, Error> {
+        self.worker.explain_query_plan(query).await
+    }
+
+    /// Take a snapshot of the committed state of the main database, which other connections to
+    /// the same database file can read with [`begin_snapshot_read()`](Self::begin_snapshot_read).
+    ///
+    /// Within a transaction, this is the state the transaction reads, which can't be taken once
+    /// it has written. The database must be in [WAL mode](crate::sqlite::SqliteJournalMode::Wal),
+    /// and a transaction must have been written to its WAL since it was created, e.g. the WAL
+    /// mustn't have just been truncated.
+    ///
+    /// Requires SQLite to be built with `SQLITE_ENABLE_SNAPSHOT`, see the `sqlite-snapshot`
+    /// feature. See [`sqlite3_snapshot_get()`](https://www.sqlite.org/c3ref/snapshot_get.html)
+    /// for details.
+    #[cfg(feature = "sqlite-snapshot")]
+    pub async fn snapshot(&mut self) -> Result<SqliteSnapshot, Error> {
+        self.worker.snapshot().await
+    }
+
+    /// Begin a transaction which reads the main database as it was when `snapshot` was taken,
+    /// whatever has been committed since.
+    ///
+    /// This way, several connections can read the exact same state, e.g. to export it in
+    /// parallel. Writing in this transaction fails with `SQLITE_BUSY_SNAPSHOT` if the database
+    /// was written to since. It can't be nested in another transaction.
+    ///
+    /// If the snapshot is no longer available, as its WAL was checkpointed over, this fails with
+    /// an error of the kind [`ErrorKind::SnapshotUnavailable`](crate::error::ErrorKind::SnapshotUnavailable).
+    ///
+    /// ```rust,no_run
+    /// # use sqlx_core::error::Error;
+    /// # use sqlx_core::sqlite::SqliteConnection;
+    /// # async fn example(
+    /// #     conn: &mut SqliteConnection,
+    /// #     reader: &mut SqliteConnection,
+    /// # ) -> Result<(), Error> {
+    /// let snapshot = conn.snapshot().await?;
+    ///
+    /// let mut tx = reader.begin_snapshot_read(&snapshot).await?;
+    /// let rows: Vec<(i64, String)> = sqlx_core::query_as::query_as("SELECT id, name FROM users")
+    ///     .fetch_all(&mut tx)
+    ///     .await?;
+    /// tx.commit().await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// See [`sqlite3_snapshot_open()`](https://www.sqlite.org/c3ref/snapshot_open.html) for
+    /// details.
+    #[cfg(feature = "sqlite-snapshot")]
+    pub async fn begin_snapshot_read(
+        &mut self,
+        snapshot: &SqliteSnapshot,
+    ) -> Result<Transaction<'_, Sqlite>, Error> {
+        self.worker.begin_snapshot_read(snapshot.clone()).await?;
+
+        Ok(Transaction::begun(self))
+    }
+
+    /// Subscribe to the rows inserted, updated and deleted through this connection, delivered
+    /// once their transaction is committed.
+    ///
+    /// Changes made by other connections, or by SQLite itself such as dropping a table, are not
+    /// reported. Neither are changes to `WITHOUT ROWID` tables, nor rows deleted all at once by
+    /// a `DELETE` without a `WHERE` clause (the truncate optimization).
+    ///
+    /// A rollback discards the changes of the transaction, but rolling back a nested transaction
+    /// (a savepoint) does not, so its changes are still delivered when the outer transaction is
+    /// committed.
+    ///
+    /// The connection doesn't wait for the changes to be received: see
+    /// [`SqliteUpdatesOptions::capacity()`] for what happens once the stream is full. Only one
+    /// subscription is active at a time, so this ends the stream of a previous one.
+    ///
+    /// See [`sqlite3_update_hook()`](https://www.sqlite.org/c3ref/update_hook.html) for details.
+    ///
+    /// ```rust,no_run
+    /// # use sqlx_core::error::Error;
+    /// # use sqlx_core::executor::Executor;
+    /// # use sqlx_core::sqlite::SqliteConnection;
+    /// # use futures_util::StreamExt;
+    /// # async fn example(conn: &mut SqliteConnection) -> Result<(), Error> {
+    /// let mut updates = conn.updates().await?;
+    ///
+    /// conn.execute("INSERT INTO users (name) VALUES ('alice')").await?;
+    ///
+    /// let update = updates.next().await.unwrap();
+    /// assert_eq!(update.table, "users");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn updates(&mut self) -> Result<SqliteUpdates, Error> {
+        self.updates_with(SqliteUpdatesOptions::new()).await
+    }
+
+    /// Subscribe to the changes made through this connection, with the given options.
+    ///
+    /// See [`updates()`](Self::updates) for details.
+    pub async fn updates_with(
+        &mut self,
+        options: SqliteUpdatesOptions,
+    ) -> Result<SqliteUpdates, Error> {
+        let mut locked = self.lock_handle().await?;
+
+        Ok(updates::subscribe(&mut locked.guard, &options))
+    }
+
     /// Lock the SQLite database handle out from the worker thread so direct SQLite API calls can
     /// be made safely.
     ///
@@ -128,6 +400,20 @@ impl SqliteConnection {
 
         Ok(LockedSqliteHandle { guard })
     }
+
+    // a statement can only be executed by the connection which prepared it, unless detached
+    fn check_prepared_by(&self, prepared_by: Option<u64>) -> Result<(), Error> {
+        match prepared_by {
+            Some(prepared_by) if prepared_by != self.log_settings.connection_id => {
+                Err(Error::StatementConnectionMismatch {
+                    prepared_by,
+                    used_by: self.log_settings.connection_id,
+                })
+            }
+
+            _ => Ok(()),
+        }
+    }
 }
 
 impl Debug for SqliteConnection {
@@ -174,6 +460,16 @@ impl Connection for SqliteConnection {
         Transaction::begin(self)
     }
 
+    fn begin_with(
+        &mut self,
+        options: TransactionOptions,
+    ) -> BoxFuture<'_, Result<Transaction<'_, Self::Database>, Error>>
+    where
+        Self: Sized,
+    {
+        Transaction::begin_with(self, options)
+    }
+
     fn cached_statements_size(&self) -> usize {
         self.worker
             .shared
@@ -188,6 +484,33 @@ impl Connection for SqliteConnection {
         })
     }
 
+    fn close_statement<'c, 'q: 'c>(
+        &'c mut self,
+        statement: SqliteStatement<'q>,
+    ) -> BoxFuture<'c, Result<(), Error>> {
+        Box::pin(async move {
+            self.check_prepared_by(statement.connection_id)?;
+
+            let mut conn = self.worker.unlock_db().await?;
+
+            if conn.statements.remove(&statement.sql) {
+                let size = conn.statements.len();
+                drop(conn);
+
+                self.worker
+                    .shared
+                    .cached_statements_size
+                    .store(size, std::sync::atomic::Ordering::Release);
+            }
+
+            Ok(())
+        })
+    }
+
+    fn prepared_statements(&mut self) -> BoxFuture<'_, Result<Vec<PreparedStatementInfo>, Error>> {
+        Box::pin(async move { Ok(self.worker.unlock_db().await?.statements.info()) })
+    }
+
     #[doc(hidden)]
     fn flush(&mut self) -> BoxFuture<'_, Result<(), Error>> {
         // For SQLite, FLUSH does effectively nothing...
@@ -228,6 +551,9 @@ impl Drop for ConnectionState {
     fn drop(&mut self) {
         // explicitly drop statements before the connection handle is dropped
         self.statements.clear();
+
+        // no longer interrupt the connection, as the handle is about to be closed
+        self.interrupt_handle.close();
     }
 }
 
@@ -248,19 +574,34 @@ impl Statements {
 
         if !exists {
             let statement = VirtualStatement::new(query, true)?;
-            self.cached.insert(query, statement);
+            self.cached.insert(query, (statement, 0));
         }
 
-        let statement = self.cached.get_mut(query).unwrap();
+        let (statement, hits) = self.cached.get_mut(query).unwrap();
 
         if exists {
             // as this statement has been executed before, we reset before continuing
             statement.reset()?;
+            *hits += 1;
         }
 
         Ok(statement)
     }
 
+    fn remove(&mut self, query: &str) -> bool {
+        self.cached.remove(query).is_some()
+    }
+
+    fn info(&self) -> Vec<PreparedStatementInfo> {
+        self.cached
+            .iter()
+            .map(|(sql, (_, hits))| PreparedStatementInfo {
+                sql: sql.to_owned(),
+                hits: *hits,
+            })
+            .collect()
+    }
+
     fn len(&self) -> usize {
         self.cached.len()
     }
@@ -0,0 +1,165 @@
+use std::fmt::{self, Display, Formatter};
+
+use crate::error::Error;
+use crate::from_row::FromRow;
+use crate::sqlite::connection::{execute, ConnectionState};
+use crate::sqlite::statement::VirtualStatement;
+
+// https://www.sqlite.org/eqp.html
+
+/// The plan chosen by SQLite to run a statement, as reported by `EXPLAIN QUERY PLAN`.
+///
+/// Returned by [`SqliteConnection::explain_query_plan()`](super::SqliteConnection::explain_query_plan).
+/// The [`Display`] implementation prints it as a tree, like the `sqlite3` shell does.
+///
+/// The wording of the steps isn't part of the stable API of SQLite, and may change between
+/// versions; it is meant to be read by humans, and only loosely checked by programs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SqliteQueryPlan {
+    nodes: Vec<SqliteQueryPlanNode>,
+}
+
+/// A step of a [`SqliteQueryPlan`], e.g. `SCAN users` or `SEARCH users USING INDEX
+/// users_email (email=?)`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SqliteQueryPlanNode {
+    pub id: i64,
+
+    /// The `id` of the step this one is part of, or zero for a step at the top of the plan.
+    pub parent: i64,
+
+    pub detail: String,
+
+    pub children: Vec<SqliteQueryPlanNode>,
+}
+
+impl SqliteQueryPlan {
+    /// The steps at the top of the plan, in order.
+    pub fn nodes(&self) -> &[SqliteQueryPlanNode] {
+        &self.nodes
+    }
+
+    /// All the steps of the plan, depth-first.
+    pub fn iter(&self) -> impl Iterator<Item = &SqliteQueryPlanNode> {
+        let mut stack: Vec<&SqliteQueryPlanNode> = self.nodes.iter().rev().collect();
+
+        std::iter::from_fn(move || {
+            let node = stack.pop()?;
+            stack.extend(node.children.iter().rev());
+            Some(node)
+        })
+    }
+
+    /// Whether no table is read in full without an index: each table is either searched with an
+    /// index or its primary key, or scanned through an index.
+    ///
+    /// Scans of a constant row, a subquery, a common table expression or a virtual table aren't
+    /// counted as scans of a table.
+    pub fn uses_index_scan_only(&self) -> bool {
+        // the subqueries and common table expressions which are scanned by name
+        let subqueries: Vec<&str> = self
+            .iter()
+            .filter_map(|node| {
+                node.detail
+                    .strip_prefix("MATERIALIZE ")
+                    .or_else(|| node.detail.strip_prefix("CO-ROUTINE "))
+            })
+            .collect();
+
+        !self.iter().any(|node| {
+            // `SCAN TABLE users` before SQLite 3.36
+            let scanned = match node.detail.strip_prefix("SCAN ") {
+                Some(scanned) => scanned.strip_prefix("TABLE ").unwrap_or(scanned),
+                None => return false,
+            };
+
+            let table = scanned.split(' ').next().unwrap_or_default();
+
+            !(scanned.contains(" USING INDEX ")
+                || scanned.contains(" USING COVERING INDEX ")
+                || scanned.contains(" VIRTUAL TABLE ")
+                || scanned == "CONSTANT ROW"
+                || table.starts_with('(')
+                || table == "SUBQUERY"
+                || subqueries.contains(&table))
+        })
+    }
+}
+
+impl Display for SqliteQueryPlan {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        fn write_nodes(
+            f: &mut Formatter<'_>,
+            nodes: &[SqliteQueryPlanNode],
+            indent: &str,
+        ) -> fmt::Result {
+            for (i, node) in nodes.iter().enumerate() {
+                let last = i + 1 == nodes.len();
+
+                writeln!(
+                    f,
+                    "{}{}{}",
+                    indent,
+                    if last { "`--" } else { "|--" },
+                    node.detail
+                )?;
+
+                let indent = format!("{}{}", indent, if last { "   " } else { "|  " });
+                write_nodes(f, &node.children, &indent)?;
+            }
+
+            Ok(())
+        }
+
+        writeln!(f, "QUERY PLAN")?;
+        write_nodes(f, &self.nodes, "")
+    }
+}
+
+/// Explain the plan of `query`, which must be a single statement as any other would be
+/// executed instead of explained.
+pub(crate) fn explain_query_plan(
+    conn: &mut ConnectionState,
+    query: &str,
+) -> Result<SqliteQueryPlan, Error> {
+    let mut statement = VirtualStatement::new(query, false)?;
+    let mut statements = 0;
+
+    while statement.prepare_next(&mut conn.handle)?.is_some() {
+        statements += 1;
+    }
+
+    if statements != 1 {
+        return Err(Error::Configuration(
+            format!(
+                "EXPLAIN QUERY PLAN expects a single statement, got {}",
+                statements
+            )
+            .into(),
+        ));
+    }
+
+    // any parameters are left unbound, i.e. NULL, which doesn't change the plan
+    let rows: Vec<(i64, i64, i64, String)> =
+        execute::iter(conn, &format!("EXPLAIN QUERY PLAN {}", query), None, false)?
+            .filter_map(|res| res.map(|either| either.right()).transpose())
+            .map(|row| FromRow::from_row(&row?))
+            .collect::<Result<_, Error>>()?;
+
+    Ok(SqliteQueryPlan {
+        nodes: children(&rows, 0),
+    })
+}
+
+// the steps of a plan are listed after the one they are part of
+fn children(rows: &[(i64, i64, i64, String)], parent: i64) -> Vec<SqliteQueryPlanNode> {
+    rows.iter()
+        .filter(|(id, row_parent, _, _)| *row_parent == parent && *id > parent)
+        .map(|(id, parent, _, detail)| SqliteQueryPlanNode {
+            id: *id,
+            parent: *parent,
+            detail: detail.clone(),
+            children: children(rows, *id),
+        })
+        .collect()
+}
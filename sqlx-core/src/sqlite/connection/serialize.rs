@@ -0,0 +1,120 @@
+use std::ffi::CString;
+use std::os::raw::c_void;
+use std::ptr;
+
+use libsqlite3_sys::{
+    sqlite3_deserialize, sqlite3_free, sqlite3_int64, sqlite3_malloc64, sqlite3_serialize,
+    SQLITE_DESERIALIZE_FREEONCLOSE, SQLITE_DESERIALIZE_READONLY, SQLITE_DESERIALIZE_RESIZEABLE,
+    SQLITE_OK,
+};
+
+use crate::error::Error;
+use crate::sqlite::connection::handle::ConnectionHandle;
+use crate::sqlite::SqliteError;
+
+// https://www.sqlite.org/c3ref/serialize.html
+// https://www.sqlite.org/c3ref/deserialize.html
+
+/// Copy the image of the database `schema`, e.g. `main`, into a `Vec`.
+pub(crate) fn serialize(handle: &mut ConnectionHandle, schema: &str) -> Result<Vec<u8>, Error> {
+    let c_schema = schema_name(schema)?;
+    let mut size: sqlite3_int64 = 0;
+
+    // without SQLITE_SERIALIZE_NOCOPY, the image is a copy which we're responsible for freeing
+    let data = unsafe { sqlite3_serialize(handle.as_ptr(), c_schema.as_ptr(), &mut size, 0) };
+
+    if data.is_null() {
+        return match size {
+            // a database without any pages, for which nothing is allocated
+            0 => Ok(Vec::new()),
+
+            -1 => Err(Error::Configuration(
+                format!("unknown database schema: {:?}", schema).into(),
+            )),
+
+            _ => Err(Error::Io(std::io::Error::new(
+                std::io::ErrorKind::OutOfMemory,
+                "SQLite is unable to allocate memory to serialize the database",
+            ))),
+        };
+    }
+
+    // SAFE: `data` points to `size` bytes, until freed
+    let image = unsafe {
+        let image = std::slice::from_raw_parts(data, size as usize).to_vec();
+        sqlite3_free(data as *mut c_void);
+        image
+    };
+
+    Ok(image)
+}
+
+/// Replace the database `schema` with an in-memory database loaded from a copy of `image`.
+pub(crate) fn deserialize(
+    handle: &mut ConnectionHandle,
+    schema: &str,
+    image: &[u8],
+    read_only: bool,
+) -> Result<(), Error> {
+    let c_schema = schema_name(schema)?;
+    let size = image.len();
+
+    // SQLite takes ownership of the buffer with SQLITE_DESERIALIZE_FREEONCLOSE, so it must
+    // be allocated by SQLite; note that it may not allocate 0 bytes
+    let data = unsafe { sqlite3_malloc64(if size == 0 { 1 } else { size as u64 }) } as *mut u8;
+
+    if data.is_null() {
+        return Err(Error::Io(std::io::Error::new(
+            std::io::ErrorKind::OutOfMemory,
+            "SQLite is unable to allocate memory to deserialize the database",
+        )));
+    }
+
+    // SAFE: `data` was just allocated with room for `size` bytes
+    unsafe {
+        ptr::copy_nonoverlapping(image.as_ptr(), data, size);
+    }
+
+    // An in-memory database can't be in WAL mode, so an image of a database that was is
+    // changed back to the rollback journal, which is what `PRAGMA journal_mode` would do.
+    // The file format version numbers are at offsets 18 and 19, of 2 for WAL.
+    // <https://www.sqlite.org/fileformat2.html#database_header>
+    if size >= 20 {
+        unsafe {
+            if *data.add(18) == 2 && *data.add(19) == 2 {
+                *data.add(18) = 1;
+                *data.add(19) = 1;
+            }
+        }
+    }
+
+    let flags = SQLITE_DESERIALIZE_FREEONCLOSE
+        | if read_only {
+            SQLITE_DESERIALIZE_READONLY
+        } else {
+            SQLITE_DESERIALIZE_RESIZEABLE
+        };
+
+    // SAFE: the buffer is freed by SQLite, even when this fails
+    let status = unsafe {
+        sqlite3_deserialize(
+            handle.as_ptr(),
+            c_schema.as_ptr(),
+            data,
+            size as sqlite3_int64,
+            size as sqlite3_int64,
+            flags as u32,
+        )
+    };
+
+    if status != SQLITE_OK {
+        return Err(Error::Database(Box::new(SqliteError::new(handle.as_ptr()))));
+    }
+
+    Ok(())
+}
+
+fn schema_name(schema: &str) -> Result<CString, Error> {
+    CString::new(schema)
+        .map_err(|_| Error::Configuration(format!("invalid database schema: {:?}", schema).into()))
+}
@@ -0,0 +1,167 @@
+use std::ffi::CStr;
+use std::fmt::{self, Debug, Formatter};
+use std::os::raw::c_int;
+use std::ptr::{self, NonNull};
+use std::sync::Arc;
+
+use libsqlite3_sys::{
+    sqlite3_errstr, sqlite3_snapshot, sqlite3_snapshot_free, sqlite3_snapshot_get,
+    sqlite3_snapshot_open, SQLITE_ERROR, SQLITE_ERROR_SNAPSHOT, SQLITE_OK,
+};
+
+use crate::error::Error;
+use crate::from_row::FromRow;
+use crate::sqlite::connection::{execute, ConnectionState};
+use crate::sqlite::SqliteError;
+
+// https://www.sqlite.org/c3ref/snapshot.html
+// https://www.sqlite.org/c3ref/snapshot_get.html
+// https://www.sqlite.org/c3ref/snapshot_open.html
+
+/// A committed state of the main database of a [`SqliteConnection`](super::SqliteConnection),
+/// which other connections to the same database file can read from while it is written to.
+///
+/// Returned by [`SqliteConnection::snapshot()`](super::SqliteConnection::snapshot), and read
+/// from with [`SqliteConnection::begin_snapshot_read()`](super::SqliteConnection::begin_snapshot_read).
+/// Cloning a snapshot is cheap, and all the clones refer to the same state.
+///
+/// A snapshot only refers to the WAL, so it can be read from as long as the frames of the WAL it
+/// was taken from haven't been written over: a checkpoint which restarts or truncates the WAL,
+/// followed by a write, makes it unavailable. An open read transaction on any snapshot keeps
+/// checkpoints from doing so, as does a read transaction started before the snapshot was taken.
+pub struct SqliteSnapshot {
+    handle: Arc<SnapshotHandle>,
+}
+
+// the `sqlite3_snapshot` object is a plain copy of the WAL header, owned by the snapshot and not
+// tied to the connection it was taken from
+struct SnapshotHandle(NonNull<sqlite3_snapshot>);
+
+unsafe impl Send for SnapshotHandle {}
+unsafe impl Sync for SnapshotHandle {}
+
+impl Clone for SqliteSnapshot {
+    fn clone(&self) -> Self {
+        Self {
+            handle: Arc::clone(&self.handle),
+        }
+    }
+}
+
+impl Debug for SqliteSnapshot {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SqliteSnapshot").finish_non_exhaustive()
+    }
+}
+
+impl Drop for SnapshotHandle {
+    fn drop(&mut self) {
+        // SAFE: the snapshot was allocated by `sqlite3_snapshot_get()` and is freed once
+        unsafe { sqlite3_snapshot_free(self.0.as_ptr()) }
+    }
+}
+
+fn main_schema() -> &'static CStr {
+    c"main"
+}
+
+// the snapshot functions return their result code without setting it on the connection
+fn snapshot_error(status: c_int) -> Error {
+    let message = match status {
+        SQLITE_ERROR_SNAPSHOT => "the snapshot is no longer available".to_owned(),
+
+        // SAFE: the message is a static string
+        _ => unsafe { CStr::from_ptr(sqlite3_errstr(status)) }
+            .to_string_lossy()
+            .into_owned(),
+    };
+
+    Error::Database(Box::new(SqliteError::with_message(status, message)))
+}
+
+/// Fail with a clearer message than SQLite's if the main database isn't in WAL mode.
+fn ensure_wal(conn: &mut ConnectionState) -> Result<(), Error> {
+    let journal_mode: Option<(String,)> =
+        execute::iter(conn, "PRAGMA main.journal_mode", None, false)?
+            .filter_map(|res| res.map(|either| either.right()).transpose())
+            .map(|row| FromRow::from_row(&row?))
+            .next()
+            .transpose()?;
+
+    match journal_mode {
+        Some((mode,)) if mode.eq_ignore_ascii_case("wal") => Ok(()),
+
+        _ => Err(Error::Database(Box::new(SqliteError::with_message(
+            SQLITE_ERROR,
+            "snapshots require the database to be in WAL mode".into(),
+        )))),
+    }
+}
+
+/// Take a snapshot of the state of the main database read by the current transaction, or of the
+/// latest committed state outside of a transaction.
+pub(crate) fn snapshot(conn: &mut ConnectionState) -> Result<SqliteSnapshot, Error> {
+    ensure_wal(conn)?;
+
+    // a snapshot can only be taken within a transaction, which reads the state it's of
+    let in_transaction = conn.transaction_depth > 0;
+
+    if !in_transaction {
+        conn.handle.exec("BEGIN")?;
+    }
+
+    let mut snapshot = ptr::null_mut();
+
+    let status = unsafe {
+        sqlite3_snapshot_get(conn.handle.as_ptr(), main_schema().as_ptr(), &mut snapshot)
+    };
+
+    let res = match NonNull::new(snapshot) {
+        Some(snapshot) if status == SQLITE_OK => Ok(SqliteSnapshot {
+            handle: Arc::new(SnapshotHandle(snapshot)),
+        }),
+
+        _ => Err(snapshot_error(status)),
+    };
+
+    if !in_transaction {
+        conn.handle.exec("ROLLBACK")?;
+    }
+
+    res
+}
+
+/// Begin a transaction which reads the main database as it was when `snapshot` was taken.
+pub(crate) fn begin_snapshot_read(
+    conn: &mut ConnectionState,
+    snapshot: &SqliteSnapshot,
+) -> Result<(), Error> {
+    // the snapshot must be opened before the transaction reads anything
+    if conn.transaction_depth > 0 {
+        return Err(err_protocol!(
+            "cannot begin reading from a snapshot within a transaction"
+        ));
+    }
+
+    ensure_wal(conn)?;
+
+    conn.handle.exec("BEGIN")?;
+
+    let status = unsafe {
+        sqlite3_snapshot_open(
+            conn.handle.as_ptr(),
+            main_schema().as_ptr(),
+            snapshot.handle.0.as_ptr(),
+        )
+    };
+
+    if status != SQLITE_OK {
+        conn.handle.exec("ROLLBACK")?;
+
+        return Err(snapshot_error(status));
+    }
+
+    conn.transaction_depth += 1;
+
+    Ok(())
+}
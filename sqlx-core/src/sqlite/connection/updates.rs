@@ -0,0 +1,221 @@
+use std::ffi::CStr;
+use std::os::raw::{c_char, c_int, c_void};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use futures_core::Stream;
+use libsqlite3_sys::{
+    sqlite3_commit_hook, sqlite3_int64, sqlite3_rollback_hook, sqlite3_update_hook, SQLITE_DELETE,
+    SQLITE_INSERT, SQLITE_UPDATE,
+};
+
+use crate::sqlite::connection::ConnectionState;
+
+// https://www.sqlite.org/c3ref/update_hook.html
+// https://www.sqlite.org/c3ref/commit_hook.html
+
+/// The kind of change of a [`SqliteUpdate`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SqliteOperation {
+    Insert,
+    Update,
+    Delete,
+}
+
+/// A row inserted, updated or deleted through a connection, as reported by
+/// [`SqliteConnection::updates()`](super::SqliteConnection::updates).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SqliteUpdate {
+    pub operation: SqliteOperation,
+
+    /// The name of the database of the table, e.g. `main` or one added with `ATTACH DATABASE`.
+    pub database: String,
+
+    pub table: String,
+
+    /// The `rowid` of the row; after an `UPDATE` of the `rowid` itself, this is the new one.
+    pub rowid: i64,
+}
+
+/// Options for [`SqliteConnection::updates_with()`](super::SqliteConnection::updates_with).
+#[derive(Debug, Clone)]
+pub struct SqliteUpdatesOptions {
+    immediate: bool,
+    capacity: usize,
+}
+
+impl Default for SqliteUpdatesOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SqliteUpdatesOptions {
+    /// Construct `Self` with default options: changes delivered once committed, with room for
+    /// 1024 of them.
+    pub fn new() -> Self {
+        Self {
+            immediate: false,
+            capacity: 1024,
+        }
+    }
+
+    /// Deliver changes as they are made, instead of once their transaction is committed.
+    ///
+    /// Changes which are later rolled back are delivered as well.
+    pub fn immediate(mut self, immediate: bool) -> Self {
+        self.immediate = immediate;
+        self
+    }
+
+    /// Sets the number of changes which can wait to be received.
+    ///
+    /// The connection never waits for changes to be received; once this many are waiting, any
+    /// further changes are dropped and counted by [`SqliteUpdates::dropped()`]. Unless
+    /// [`immediate`](Self::immediate), the changes of a transaction count from the time they
+    /// are made, so at most the first `capacity` changes of a transaction are kept.
+    pub fn capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity;
+        self
+    }
+}
+
+/// A stream of the changes made through a connection, returned by
+/// [`SqliteConnection::updates()`](super::SqliteConnection::updates).
+///
+/// The stream ends once the connection is closed or its changes are subscribed to again.
+pub struct SqliteUpdates {
+    stream: flume::r#async::RecvStream<'static, SqliteUpdate>,
+    dropped: Arc<AtomicU64>,
+}
+
+impl SqliteUpdates {
+    /// The number of changes dropped so far, as they were made while the stream was full.
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Acquire)
+    }
+}
+
+impl Stream for SqliteUpdates {
+    type Item = SqliteUpdate;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Pin::new(&mut self.stream).poll_next(cx)
+    }
+}
+
+/// The data passed to the hooks, owned by the [`ConnectionState`].
+///
+/// It is dropped after the connection handle is closed, as closing the handle with a
+/// transaction open calls the rollback hook.
+pub(crate) struct UpdateHooks {
+    tx: flume::Sender<SqliteUpdate>,
+    immediate: bool,
+    capacity: usize,
+    // changes of the current transaction, when not immediate; no more than the channel can hold
+    pending: Vec<SqliteUpdate>,
+    dropped: Arc<AtomicU64>,
+}
+
+impl UpdateHooks {
+    fn send(&mut self, update: SqliteUpdate) {
+        // a disconnected receiver has stopped listening, so only a full one counts as dropped
+        if let Err(flume::TrySendError::Full(_)) = self.tx.try_send(update) {
+            self.dropped.fetch_add(1, Ordering::AcqRel);
+        }
+    }
+
+    fn push_pending(&mut self, update: SqliteUpdate) {
+        // the changes past the capacity would be dropped at commit anyway
+        if self.pending.len() < self.capacity {
+            self.pending.push(update);
+        } else {
+            self.dropped.fetch_add(1, Ordering::AcqRel);
+        }
+    }
+}
+
+/// Register the hooks on the connection, replacing those of a previous subscription.
+pub(crate) fn subscribe(
+    conn: &mut ConnectionState,
+    options: &SqliteUpdatesOptions,
+) -> SqliteUpdates {
+    let (tx, rx) = flume::bounded(options.capacity);
+    let dropped = Arc::new(AtomicU64::new(0));
+
+    let mut hooks = Box::new(UpdateHooks {
+        tx,
+        immediate: options.immediate,
+        capacity: options.capacity,
+        pending: Vec::new(),
+        dropped: Arc::clone(&dropped),
+    });
+
+    let data = &mut *hooks as *mut UpdateHooks as *mut c_void;
+    let handle = conn.handle.as_ptr();
+
+    // SAFE: the data is kept alive by the connection state until the hooks are replaced, and
+    // the hooks are only called from the thread using the connection
+    unsafe {
+        sqlite3_update_hook(handle, Some(update_hook), data);
+        sqlite3_commit_hook(handle, Some(commit_hook), data);
+        sqlite3_rollback_hook(handle, Some(rollback_hook), data);
+    }
+
+    // ends the stream of a previous subscription
+    conn.update_hooks = Some(hooks);
+
+    SqliteUpdates {
+        stream: rx.into_stream(),
+        dropped,
+    }
+}
+
+unsafe extern "C" fn update_hook(
+    data: *mut c_void,
+    operation: c_int,
+    database: *const c_char,
+    table: *const c_char,
+    rowid: sqlite3_int64,
+) {
+    let hooks = &mut *(data as *mut UpdateHooks);
+
+    let operation = match operation {
+        SQLITE_INSERT => SqliteOperation::Insert,
+        SQLITE_UPDATE => SqliteOperation::Update,
+        SQLITE_DELETE => SqliteOperation::Delete,
+        _ => return,
+    };
+
+    let update = SqliteUpdate {
+        operation,
+        database: CStr::from_ptr(database).to_string_lossy().into_owned(),
+        table: CStr::from_ptr(table).to_string_lossy().into_owned(),
+        rowid,
+    };
+
+    if hooks.immediate {
+        hooks.send(update);
+    } else {
+        hooks.push_pending(update);
+    }
+}
+
+unsafe extern "C" fn commit_hook(data: *mut c_void) -> c_int {
+    let hooks = &mut *(data as *mut UpdateHooks);
+
+    for update in std::mem::take(&mut hooks.pending) {
+        hooks.send(update);
+    }
+
+    // zero lets the commit go ahead
+    0
+}
+
+unsafe extern "C" fn rollback_hook(data: *mut c_void) {
+    let hooks = &mut *(data as *mut UpdateHooks);
+
+    hooks.pending.clear();
+}
@@ -1,5 +1,7 @@
 use std::borrow::Cow;
 use std::future::Future;
+use std::os::raw::c_int;
+use std::path::PathBuf;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::thread;
@@ -10,14 +12,28 @@ use futures_intrusive::sync::{Mutex, MutexGuard};
 
 use crate::describe::Describe;
 use crate::error::Error;
+use crate::logger::QuerySpan;
+use crate::sqlite::connection::backup::{backup, SqliteBackupProgress};
+use crate::sqlite::connection::checkpoint::checkpoint;
 use crate::sqlite::connection::collation::create_collation;
 use crate::sqlite::connection::describe::describe;
 use crate::sqlite::connection::establish::EstablishParams;
+use crate::sqlite::connection::interrupt::SqliteInterruptHandle;
+use crate::sqlite::connection::query_plan::explain_query_plan;
+use crate::sqlite::connection::serialize::{deserialize, serialize};
+#[cfg(feature = "sqlite-snapshot")]
+use crate::sqlite::connection::snapshot::{begin_snapshot_read, snapshot};
 use crate::sqlite::connection::ConnectionState;
 use crate::sqlite::connection::{execute, ConnectionHandleRaw};
-use crate::sqlite::{Sqlite, SqliteArguments, SqliteQueryResult, SqliteRow, SqliteStatement};
+#[cfg(feature = "sqlite-snapshot")]
+use crate::sqlite::SqliteSnapshot;
+use crate::sqlite::{
+    Sqlite, SqliteArguments, SqliteCheckpointMode, SqliteCheckpointResult, SqliteQueryPlan,
+    SqliteQueryResult, SqliteRow, SqliteStatement,
+};
 use crate::transaction::{
     begin_ansi_transaction_sql, commit_ansi_transaction_sql, rollback_ansi_transaction_sql,
+    TransactionOptions,
 };
 
 // Each SQLite connection has a dedicated thread.
@@ -30,6 +46,7 @@ pub(crate) struct ConnectionWorker {
     command_tx: flume::Sender<Command>,
     /// The `sqlite3` pointer. NOTE: access is unsynchronized!
     pub(crate) handle_raw: ConnectionHandleRaw,
+    pub(crate) interrupt_handle: SqliteInterruptHandle,
     /// Mutex for locking access to the database.
     pub(crate) shared: Arc<WorkerSharedState>,
 }
@@ -52,9 +69,11 @@ enum Command {
         query: Box<str>,
         arguments: Option<SqliteArguments<'static>>,
         persistent: bool,
+        span: QuerySpan,
         tx: flume::Sender<Result<Either<SqliteQueryResult, SqliteRow>, Error>>,
     },
     Begin {
+        options: TransactionOptions,
         tx: oneshot::Sender<Result<(), Error>>,
     },
     Commit {
@@ -67,6 +86,40 @@ enum Command {
         create_collation:
             Box<dyn FnOnce(&mut ConnectionState) -> Result<(), Error> + Send + Sync + 'static>,
     },
+    Backup {
+        path: PathBuf,
+        restore: bool,
+        pages_per_step: c_int,
+        tx: flume::Sender<Result<SqliteBackupProgress, Error>>,
+    },
+    Checkpoint {
+        mode: SqliteCheckpointMode,
+        tx: oneshot::Sender<Result<SqliteCheckpointResult, Error>>,
+    },
+    ExplainQueryPlan {
+        query: Box<str>,
+        tx: oneshot::Sender<Result<SqliteQueryPlan, Error>>,
+    },
+    Serialize {
+        schema: Box<str>,
+        tx: oneshot::Sender<Result<Vec<u8>, Error>>,
+    },
+    Deserialize {
+        schema: Box<str>,
+        image: Vec<u8>,
+        read_only: bool,
+        tx: oneshot::Sender<Result<(), Error>>,
+    },
+    #[cfg(feature = "sqlite-snapshot")]
+    Snapshot {
+        tx: oneshot::Sender<Result<SqliteSnapshot, Error>>,
+    },
+    #[cfg(feature = "sqlite-snapshot")]
+    BeginSnapshotRead {
+        // owned, so that the snapshot outlives the command even if it's no longer awaited
+        snapshot: SqliteSnapshot,
+        tx: oneshot::Sender<Result<(), Error>>,
+    },
     UnlockDb,
     ClearCache {
         tx: oneshot::Sender<()>,
@@ -109,6 +162,7 @@ impl ConnectionWorker {
                     .send(Ok(Self {
                         command_tx,
                         handle_raw: conn.handle.to_raw(),
+                        interrupt_handle: conn.interrupt_handle.clone(),
                         shared: Arc::clone(&shared),
                     }))
                     .is_err()
@@ -135,33 +189,42 @@ impl ConnectionWorker {
                             query,
                             arguments,
                             persistent,
+                            span,
                             tx,
                         } => {
-                            let iter = match execute::iter(&mut conn, &query, arguments, persistent)
-                            {
-                                Ok(iter) => iter,
+                            if let Some(progress) = &conn.progress_handler {
+                                if conn.interrupt_on_drop {
+                                    progress.watch(&tx);
+                                }
+                            }
+
+                            match execute::iter_in(&mut conn, &query, arguments, persistent, span) {
+                                Ok(iter) => {
+                                    for res in iter {
+                                        // stepping again after an error would run the
+                                        // statement over, e.g. an interrupted one
+                                        let has_error = res.is_err();
+
+                                        if tx.send(res).is_err() || has_error {
+                                            break;
+                                        }
+                                    }
+                                }
                                 Err(e) => {
                                     tx.send(Err(e)).ok();
-                                    continue;
                                 }
-                            };
+                            }
 
-                            for res in iter {
-                                if tx.send(res).is_err() {
-                                    break;
-                                }
+                            if let Some(progress) = &conn.progress_handler {
+                                progress.unwatch();
                             }
 
                             update_cached_statements_size(&conn, &shared.cached_statements_size);
                         }
-                        Command::Begin { tx } => {
-                            let depth = conn.transaction_depth;
-                            let res =
-                                conn.handle
-                                    .exec(begin_ansi_transaction_sql(depth))
-                                    .map(|_| {
-                                        conn.transaction_depth += 1;
-                                    });
+                        Command::Begin { options, tx } => {
+                            let res = begin(&mut conn, options).map(|_| {
+                                conn.transaction_depth += 1;
+                            });
 
                             tx.send(res).ok();
                         }
@@ -202,6 +265,51 @@ impl ConnectionWorker {
                                 log::warn!("error applying collation in background worker: {}", e);
                             }
                         }
+                        Command::Backup {
+                            path,
+                            restore,
+                            pages_per_step,
+                            tx,
+                        } => {
+                            let res = backup(
+                                &mut conn.handle,
+                                &path,
+                                restore,
+                                pages_per_step,
+                                // stops the backup once the progress is no longer received
+                                |progress| tx.send(Ok(progress)).is_ok(),
+                            );
+
+                            if let Err(e) = res {
+                                tx.send(Err(e)).ok();
+                            }
+                        }
+                        Command::Checkpoint { mode, tx } => {
+                            tx.send(checkpoint(&mut conn.handle, mode)).ok();
+                        }
+                        Command::ExplainQueryPlan { query, tx } => {
+                            tx.send(explain_query_plan(&mut conn, &query)).ok();
+                        }
+                        Command::Serialize { schema, tx } => {
+                            tx.send(serialize(&mut conn.handle, &schema)).ok();
+                        }
+                        Command::Deserialize {
+                            schema,
+                            image,
+                            read_only,
+                            tx,
+                        } => {
+                            tx.send(deserialize(&mut conn.handle, &schema, &image, read_only))
+                                .ok();
+                        }
+                        #[cfg(feature = "sqlite-snapshot")]
+                        Command::Snapshot { tx } => {
+                            tx.send(snapshot(&mut conn)).ok();
+                        }
+                        #[cfg(feature = "sqlite-snapshot")]
+                        Command::BeginSnapshotRead { snapshot, tx } => {
+                            tx.send(begin_snapshot_read(&mut conn, &snapshot)).ok();
+                        }
                         Command::ClearCache { tx } => {
                             conn.statements.clear();
                             update_cached_statements_size(&conn, &shared.cached_statements_size);
@@ -251,6 +359,7 @@ impl ConnectionWorker {
         args: Option<SqliteArguments<'_>>,
         chan_size: usize,
         persistent: bool,
+        span: QuerySpan,
     ) -> Result<flume::Receiver<Result<Either<SqliteQueryResult, SqliteRow>, Error>>, Error> {
         let (tx, rx) = flume::bounded(chan_size);
 
@@ -259,6 +368,7 @@ impl ConnectionWorker {
                 query: query.into(),
                 arguments: args.map(SqliteArguments::into_static),
                 persistent,
+                span,
                 tx,
             })
             .await
@@ -267,8 +377,9 @@ impl ConnectionWorker {
         Ok(rx)
     }
 
-    pub(crate) async fn begin(&mut self) -> Result<(), Error> {
-        self.oneshot_cmd(|tx| Command::Begin { tx }).await?
+    pub(crate) async fn begin(&mut self, options: TransactionOptions) -> Result<(), Error> {
+        self.oneshot_cmd(|tx| Command::Begin { options, tx })
+            .await?
     }
 
     pub(crate) async fn commit(&mut self) -> Result<(), Error> {
@@ -321,6 +432,84 @@ impl ConnectionWorker {
         Ok(())
     }
 
+    pub(crate) async fn backup(
+        &mut self,
+        path: PathBuf,
+        restore: bool,
+        pages_per_step: c_int,
+    ) -> Result<flume::Receiver<Result<SqliteBackupProgress, Error>>, Error> {
+        // a single slot, so that the next step waits for the previous progress to be received
+        let (tx, rx) = flume::bounded(1);
+
+        self.command_tx
+            .send_async(Command::Backup {
+                path,
+                restore,
+                pages_per_step,
+                tx,
+            })
+            .await
+            .map_err(|_| Error::WorkerCrashed)?;
+
+        Ok(rx)
+    }
+
+    pub(crate) async fn checkpoint(
+        &mut self,
+        mode: SqliteCheckpointMode,
+    ) -> Result<SqliteCheckpointResult, Error> {
+        self.oneshot_cmd(|tx| Command::Checkpoint { mode, tx })
+            .await?
+    }
+
+    pub(crate) async fn explain_query_plan(
+        &mut self,
+        query: &str,
+    ) -> Result<SqliteQueryPlan, Error> {
+        self.oneshot_cmd(|tx| Command::ExplainQueryPlan {
+            query: query.into(),
+            tx,
+        })
+        .await?
+    }
+
+    pub(crate) async fn serialize(&mut self, schema: &str) -> Result<Vec<u8>, Error> {
+        self.oneshot_cmd(|tx| Command::Serialize {
+            schema: schema.into(),
+            tx,
+        })
+        .await?
+    }
+
+    pub(crate) async fn deserialize(
+        &mut self,
+        schema: &str,
+        image: Vec<u8>,
+        read_only: bool,
+    ) -> Result<(), Error> {
+        self.oneshot_cmd(|tx| Command::Deserialize {
+            schema: schema.into(),
+            image,
+            read_only,
+            tx,
+        })
+        .await?
+    }
+
+    #[cfg(feature = "sqlite-snapshot")]
+    pub(crate) async fn snapshot(&mut self) -> Result<SqliteSnapshot, Error> {
+        self.oneshot_cmd(|tx| Command::Snapshot { tx }).await?
+    }
+
+    #[cfg(feature = "sqlite-snapshot")]
+    pub(crate) async fn begin_snapshot_read(
+        &mut self,
+        snapshot: SqliteSnapshot,
+    ) -> Result<(), Error> {
+        self.oneshot_cmd(|tx| Command::BeginSnapshotRead { snapshot, tx })
+            .await?
+    }
+
     pub(crate) async fn clear_cache(&mut self) -> Result<(), Error> {
         self.oneshot_cmd(|tx| Command::ClearCache { tx }).await
     }
@@ -358,6 +547,28 @@ impl ConnectionWorker {
     }
 }
 
+fn begin(conn: &mut ConnectionState, options: TransactionOptions) -> Result<(), Error> {
+    let depth = conn.transaction_depth;
+
+    options.check_savepoint(depth)?;
+
+    // every transaction is serializable, which satisfies any isolation level; there are no
+    // read-only transactions, only read-only connections
+    if options.is_read_only() {
+        return Err(options.unsupported("SQLite"));
+    }
+
+    if depth > 0 {
+        return conn.handle.exec(begin_ansi_transaction_sql(depth));
+    }
+
+    let behavior = options
+        .get_sqlite_behavior()
+        .unwrap_or(conn.transaction_behavior);
+
+    conn.handle.exec(behavior.begin_sql())
+}
+
 fn prepare(conn: &mut ConnectionState, query: &str) -> Result<SqliteStatement<'static>, Error> {
     // prepare statement object (or checkout from cache)
     let statement = conn.statements.get(query, true)?;
@@ -365,9 +576,11 @@ fn prepare(conn: &mut ConnectionState, query: &str) -> Result<SqliteStatement<'s
     let mut parameters = 0;
     let mut columns = None;
     let mut column_names = None;
+    let mut read_only = true;
 
     while let Some(statement) = statement.prepare_next(&mut conn.handle)? {
         parameters += statement.handle.bind_parameter_count();
+        read_only &= statement.handle.read_only();
 
         // the first non-empty statement is chosen as the statement we pull columns from
         if !statement.columns.is_empty() && columns.is_none() {
@@ -381,6 +594,8 @@ fn prepare(conn: &mut ConnectionState, query: &str) -> Result<SqliteStatement<'s
         columns: columns.unwrap_or_default(),
         column_names: column_names.unwrap_or_default(),
         parameters,
+        read_only,
+        connection_id: None,
     })
 }
 
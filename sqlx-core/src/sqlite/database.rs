@@ -23,6 +23,9 @@ impl Database for Sqlite {
     type TypeInfo = SqliteTypeInfo;
 
     type Value = SqliteValue;
+
+    /// `SQLITE_MAX_VARIABLE_NUMBER` since SQLite 3.32.0, which was 999 before.
+    const MAX_BIND_PARAMETERS: usize = 32766;
 }
 
 impl<'r> HasValueRef<'r> for Sqlite {
@@ -0,0 +1,91 @@
+use crate::column::ColumnIndex;
+use crate::error::{BoxDynError, Error};
+use crate::row::Row;
+use crate::sqlite::type_info::DataType;
+use crate::sqlite::{SqliteRow, SqliteValueRef};
+use crate::value::ValueRef;
+
+/// A value of any type, decoded without knowing its Rust type in advance.
+///
+/// This is returned by [`SqliteRow::try_get_dynamic`] and [`SqliteValueRef::to_dynamic`], for
+/// tools that handle the results of arbitrary queries, e.g. to export them as JSON or CSV.
+///
+/// The variant is chosen by the [storage class] of the value, not by the declared type of its
+/// column, so e.g. a `DATETIME` column holds [`Text`] or [`Integer`] values depending on how they
+/// were inserted.
+///
+/// [storage class]: https://www.sqlite.org/datatype3.html#storage_classes_and_datatypes
+/// [`Text`]: SqliteDynamicValue::Text
+/// [`Integer`]: SqliteDynamicValue::Integer
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum SqliteDynamicValue {
+    Null,
+    Integer(i64),
+    Real(f64),
+    Text(String),
+    Blob(Vec<u8>),
+}
+
+impl SqliteValueRef<'_> {
+    /// Decode this value into a [`SqliteDynamicValue`], based on its storage class.
+    pub fn to_dynamic(&self) -> Result<SqliteDynamicValue, BoxDynError> {
+        if self.is_null() {
+            return Ok(SqliteDynamicValue::Null);
+        }
+
+        // the type info of a value that isn't NULL is its storage class
+        Ok(match self.type_info().0 {
+            DataType::Float => SqliteDynamicValue::Real(self.double()),
+            DataType::Text => SqliteDynamicValue::Text(self.text()?.to_owned()),
+            DataType::Blob => SqliteDynamicValue::Blob(self.blob().to_owned()),
+            _ => SqliteDynamicValue::Integer(self.int64()),
+        })
+    }
+}
+
+impl SqliteRow {
+    /// Index into the database row and decode a single value into a [`SqliteDynamicValue`],
+    /// based on its storage class.
+    ///
+    /// # Errors
+    ///
+    ///  * [`ColumnNotFound`] if the column by the given name was not found.
+    ///  * [`ColumnIndexOutOfBounds`] if the `usize` index was greater than the number of columns in the row.
+    ///  * [`ColumnDecode`] if the value could not be decoded.
+    ///
+    /// [`ColumnDecode`]: Error::ColumnDecode
+    /// [`ColumnNotFound`]: Error::ColumnNotFound
+    /// [`ColumnIndexOutOfBounds`]: Error::ColumnIndexOutOfBounds
+    pub fn try_get_dynamic<I>(&self, index: I) -> Result<SqliteDynamicValue, Error>
+    where
+        I: ColumnIndex<Self>,
+    {
+        self.try_get_raw(&index)?
+            .to_dynamic()
+            .map_err(|source| Error::ColumnDecode {
+                index: format!("{:?}", index),
+                source,
+            })
+    }
+}
+
+#[cfg(feature = "json")]
+impl From<SqliteDynamicValue> for serde_json::Value {
+    /// Convert the value to JSON.
+    ///
+    /// Numbers that JSON can't represent are converted to strings. Blobs are converted to a
+    /// string in the format of a blob literal, e.g. `"X'deadbeef'"`.
+    fn from(value: SqliteDynamicValue) -> Self {
+        use serde_json::Value;
+
+        match value {
+            SqliteDynamicValue::Null => Value::Null,
+            SqliteDynamicValue::Integer(value) => value.into(),
+            SqliteDynamicValue::Real(value) => serde_json::Number::from_f64(value)
+                .map_or_else(|| Value::String(value.to_string()), Value::Number),
+            SqliteDynamicValue::Text(s) => Value::String(s),
+            SqliteDynamicValue::Blob(bytes) => Value::String(format!("X'{}'", hex::encode(bytes))),
+        }
+    }
+}
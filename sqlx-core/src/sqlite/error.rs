@@ -4,9 +4,13 @@ use std::fmt::{self, Display, Formatter};
 use std::os::raw::c_int;
 use std::{borrow::Cow, str::from_utf8_unchecked};
 
-use libsqlite3_sys::{sqlite3, sqlite3_errmsg, sqlite3_extended_errcode};
+use libsqlite3_sys::{
+    sqlite3, sqlite3_errmsg, sqlite3_extended_errcode, SQLITE_BUSY, SQLITE_CONSTRAINT_CHECK,
+    SQLITE_CONSTRAINT_FOREIGNKEY, SQLITE_CONSTRAINT_NOTNULL, SQLITE_CONSTRAINT_PRIMARYKEY,
+    SQLITE_CONSTRAINT_UNIQUE, SQLITE_ERROR_SNAPSHOT, SQLITE_INTERRUPT, SQLITE_LOCKED,
+};
 
-use crate::error::DatabaseError;
+use crate::error::{DatabaseError, ErrorKind};
 
 // Error Codes And Messages
 // https://www.sqlite.org/c3ref/errcode.html
@@ -35,6 +39,91 @@ impl SqliteError {
             message: message.to_owned(),
         }
     }
+
+    /// An error for a result code of SQLite and the message returned along with it, which
+    /// functions such as [sqlite3_load_extension] give instead of setting it on the connection.
+    ///
+    /// [sqlite3_load_extension]: https://www.sqlite.org/c3ref/load_extension.html
+    pub(crate) fn with_message(code: c_int, message: String) -> Self {
+        Self { code, message }
+    }
+
+    /// The extended result code.
+    pub(crate) fn result_code(&self) -> c_int {
+        self.code
+    }
+
+    /// The objects of a constraint violation, which SQLite only reports in the message, e.g.
+    /// `UNIQUE constraint failed: users.email` or `CHECK constraint failed: price_positive`.
+    fn violation(&self) -> Violation<'_> {
+        match self.code {
+            SQLITE_CONSTRAINT_UNIQUE | SQLITE_CONSTRAINT_PRIMARYKEY => {
+                let objects = match self.message.strip_prefix("UNIQUE constraint failed: ") {
+                    Some(objects) => objects,
+                    None => return Violation::default(),
+                };
+
+                // an index on expressions is reported by its name
+                if let Some(index) = objects
+                    .strip_prefix("index '")
+                    .and_then(|index| index.strip_suffix('\''))
+                {
+                    return Violation {
+                        constraint: Some(index),
+                        ..Violation::default()
+                    };
+                }
+
+                // otherwise, as the columns of the index: `table.a, table.b`
+                let mut columns = objects.split(", ").filter_map(|c| c.split_once('.'));
+
+                match (columns.next(), columns.next()) {
+                    (Some((table, column)), None) => Violation {
+                        table: Some(table),
+                        column: Some(column),
+                        ..Violation::default()
+                    },
+
+                    (Some((table, _)), Some(_)) => Violation {
+                        table: Some(table),
+                        ..Violation::default()
+                    },
+
+                    _ => Violation::default(),
+                }
+            }
+
+            SQLITE_CONSTRAINT_NOTNULL => {
+                match self
+                    .message
+                    .strip_prefix("NOT NULL constraint failed: ")
+                    .and_then(|objects| objects.split_once('.'))
+                {
+                    Some((table, column)) => Violation {
+                        table: Some(table),
+                        column: Some(column),
+                        ..Violation::default()
+                    },
+
+                    None => Violation::default(),
+                }
+            }
+
+            SQLITE_CONSTRAINT_CHECK => Violation {
+                constraint: self.message.strip_prefix("CHECK constraint failed: "),
+                ..Violation::default()
+            },
+
+            _ => Violation::default(),
+        }
+    }
+}
+
+#[derive(Default)]
+struct Violation<'a> {
+    table: Option<&'a str>,
+    column: Option<&'a str>,
+    constraint: Option<&'a str>,
 }
 
 impl Display for SqliteError {
@@ -61,6 +150,38 @@ impl DatabaseError for SqliteError {
         &self.message
     }
 
+    fn constraint(&self) -> Option<&str> {
+        self.violation().constraint
+    }
+
+    fn table(&self) -> Option<&str> {
+        self.violation().table
+    }
+
+    fn column(&self) -> Option<&str> {
+        self.violation().column
+    }
+
+    fn kind(&self) -> ErrorKind {
+        match self.code {
+            SQLITE_ERROR_SNAPSHOT => return ErrorKind::SnapshotUnavailable,
+            SQLITE_CONSTRAINT_UNIQUE | SQLITE_CONSTRAINT_PRIMARYKEY => {
+                return ErrorKind::UniqueViolation
+            }
+            SQLITE_CONSTRAINT_FOREIGNKEY => return ErrorKind::ForeignKeyViolation,
+            SQLITE_CONSTRAINT_NOTNULL => return ErrorKind::NotNullViolation,
+            SQLITE_CONSTRAINT_CHECK => return ErrorKind::CheckViolation,
+            _ => {}
+        }
+
+        // the primary result code is the least significant byte of the extended one
+        match self.code & 0xff {
+            SQLITE_BUSY | SQLITE_LOCKED => ErrorKind::Locked,
+            SQLITE_INTERRUPT => ErrorKind::Interrupted,
+            _ => ErrorKind::Other,
+        }
+    }
+
     #[doc(hidden)]
     fn as_error(&self) -> &(dyn StdError + Send + Sync + 'static) {
         self
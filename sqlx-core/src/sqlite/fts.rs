@@ -0,0 +1,388 @@
+//! Helpers for [full-text search] with the FTS5 extension of SQLite, which is built into the
+//! bundled SQLite.
+//!
+//! # Matching
+//!
+//! The right-hand side of `MATCH` is an expression in the [query syntax] of FTS5, in which
+//! quotes, `*`, `:`, parentheses, `AND`, `OR`, `NOT` and `NEAR` are all meaningful. Binding text
+//! typed by an end user as is will either fail with a syntax error or match something else than
+//! what was typed. [`Fts5Query`] builds an expression where the text is always quoted, and is
+//! bound as a parameter:
+//!
+//! ```rust,no_run
+//! # use sqlx_core::error::Error;
+//! # use sqlx_core::query_as::query_as;
+//! # use sqlx_core::sqlite::SqliteConnection;
+//! # async fn example(conn: &mut SqliteConnection) -> Result<(), Error> {
+//! use sqlx_core::sqlite::fts::Fts5Query;
+//!
+//! let search = "\"rust\" OR sql*";
+//!
+//! // matches rows containing the words `rust`, `or` and `sql`, in any order
+//! let query = Fts5Query::words(search);
+//!
+//! let titles: Vec<(String, f64)> = query_as(
+//!     "SELECT title, rank FROM posts_fts WHERE posts_fts MATCH ? ORDER BY rank",
+//! )
+//! .bind(query)
+//! .fetch_all(conn)
+//! .await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! # Ranking
+//!
+//! The `rank` column of an FTS5 table is the [BM25] score of a row for the query, which is
+//! decoded as an `f64`. Better matches have a *lower* score, so `ORDER BY rank` gives the best
+//! ones first. The score is a negative number with the default ranking function, and `NULL`
+//! without a `MATCH`.
+//!
+//! The macros can't infer the type of `rank`, as it has none in the table definition; override it
+//! with `SELECT rank AS "rank: f64"`.
+//!
+//! # External content
+//!
+//! An FTS5 table can index the columns of another table instead of storing them a second time,
+//! as long as it is kept up to date with triggers. [`Fts5ExternalContent`] generates the SQL
+//! for both, to be added to a migration.
+//!
+//! [full-text search]: https://www.sqlite.org/fts5.html
+//! [query syntax]: https://www.sqlite.org/fts5.html#full_text_query_syntax
+//! [BM25]: https://www.sqlite.org/fts5.html#the_bm25_function
+
+use std::borrow::Cow;
+use std::fmt::{self, Display, Formatter, Write};
+
+use crate::database::Database;
+use crate::encode::{Encode, IsNull};
+use crate::quote::QuoteError;
+use crate::sqlite::type_info::DataType;
+use crate::sqlite::{Sqlite, SqliteArgumentValue, SqliteTypeInfo};
+use crate::types::Type;
+
+/// A full-text query for the `MATCH` operator of an FTS5 table.
+///
+/// All text is quoted, so it never changes the meaning of the expression whatever it contains.
+/// The expression is bound as `TEXT`, and can be seen with [`Display`].
+///
+/// ```rust
+/// use sqlx_core::sqlite::fts::Fts5Query;
+///
+/// let query = Fts5Query::phrase("new york")
+///     .or(Fts5Query::prefix("nyc"))
+///     .not(Fts5Query::phrase("new jersey"))
+///     .columns(&["title", "body"]);
+///
+/// assert_eq!(
+///     query.to_string(),
+///     r#"{"title" "body"} : ((("new york") OR ("nyc" *)) NOT ("new jersey"))"#,
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fts5Query {
+    expr: String,
+}
+
+impl Fts5Query {
+    /// Match the words of `text` next to each other, in that order.
+    ///
+    /// The text is split into words by the tokenizer of the table; e.g. `it's` is the two words
+    /// `it` and `s` with the default tokenizer.
+    pub fn phrase(text: &str) -> Self {
+        let mut expr = String::with_capacity(text.len() + 2);
+        push_quoted(&mut expr, text);
+
+        Self { expr }
+    }
+
+    /// Match the words of `text` next to each other, the last one being the start of a word.
+    ///
+    /// Suited to searching as the user types.
+    pub fn prefix(text: &str) -> Self {
+        let mut query = Self::phrase(text);
+        query.expr.push_str(" *");
+        query
+    }
+
+    /// Match all the whitespace-separated words of `text`, in any order.
+    ///
+    /// This is what's usually expected from a search box. Operators in the text match as
+    /// ordinary words, and words made only of punctuation are skipped, as the tokenizers of
+    /// SQLite ignore it and an empty phrase would match nothing.
+    ///
+    /// Text without any words gives a query which matches nothing.
+    pub fn words(text: &str) -> Self {
+        let mut words = text
+            .split_whitespace()
+            .filter(|word| word.chars().any(char::is_alphanumeric))
+            .map(Self::phrase);
+
+        let first = match words.next() {
+            Some(first) => first,
+            None => return Self::phrase(""),
+        };
+
+        words.fold(first, Self::and)
+    }
+
+    /// Match rows matching both `self` and `other`.
+    pub fn and(self, other: Self) -> Self {
+        self.binary("AND", other)
+    }
+
+    /// Match rows matching `self`, `other`, or both.
+    pub fn or(self, other: Self) -> Self {
+        self.binary("OR", other)
+    }
+
+    /// Match rows matching `self` but not `other`.
+    ///
+    /// FTS5 has no unary `NOT`, so there must be something to exclude the rows from.
+    pub fn not(self, other: Self) -> Self {
+        self.binary("NOT", other)
+    }
+
+    /// Only match `self` in the column `column` of the table.
+    pub fn column(self, column: &str) -> Self {
+        self.columns(&[column])
+    }
+
+    /// Only match `self` in the columns `columns` of the table.
+    pub fn columns(self, columns: &[&str]) -> Self {
+        let mut expr = String::with_capacity(self.expr.len() + 8);
+
+        expr.push('{');
+
+        for (i, column) in columns.iter().enumerate() {
+            if i > 0 {
+                expr.push(' ');
+            }
+
+            push_quoted(&mut expr, column);
+        }
+
+        let _ = write!(expr, "}} : ({})", self.expr);
+
+        Self { expr }
+    }
+
+    /// The expression, as bound to `MATCH`.
+    pub fn as_str(&self) -> &str {
+        &self.expr
+    }
+
+    fn binary(self, operator: &str, other: Self) -> Self {
+        Self {
+            expr: format!("({}) {} ({})", self.expr, operator, other.expr),
+        }
+    }
+}
+
+impl Display for Fts5Query {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.expr)
+    }
+}
+
+impl Type<Sqlite> for Fts5Query {
+    fn type_info() -> SqliteTypeInfo {
+        SqliteTypeInfo(DataType::Text)
+    }
+}
+
+impl<'q> Encode<'q, Sqlite> for Fts5Query {
+    fn encode(self, args: &mut Vec<SqliteArgumentValue<'q>>) -> IsNull {
+        args.push(SqliteArgumentValue::Text(Cow::Owned(self.expr)));
+
+        IsNull::No
+    }
+
+    fn encode_by_ref(&self, args: &mut Vec<SqliteArgumentValue<'q>>) -> IsNull {
+        args.push(SqliteArgumentValue::Text(Cow::Owned(self.expr.clone())));
+
+        IsNull::No
+    }
+}
+
+/// The definition of an FTS5 table indexing the columns of another table, the *content* table,
+/// and of the triggers keeping it up to date.
+///
+/// ```rust
+/// use sqlx_core::sqlite::fts::Fts5ExternalContent;
+///
+/// let sql = Fts5ExternalContent::new("posts_fts", "posts", &["title", "body"])
+///     .content_rowid("id")
+///     .tokenize("porter unicode61")
+///     .to_sql()?;
+///
+/// // e.g. copied into a migration, or run as is with `Executor::execute()`
+/// assert!(sql.starts_with(r#"CREATE VIRTUAL TABLE "posts_fts" USING fts5("title", "body""#));
+/// # Ok::<(), sqlx_core::quote::QuoteError>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct Fts5ExternalContent {
+    table: String,
+    content: String,
+    columns: Vec<String>,
+    content_rowid: Option<String>,
+    tokenize: Option<String>,
+}
+
+impl Fts5ExternalContent {
+    /// Index the columns `columns` of the table `content` in the FTS5 table `table`.
+    pub fn new(table: &str, content: &str, columns: &[&str]) -> Self {
+        Self {
+            table: table.to_owned(),
+            content: content.to_owned(),
+            columns: columns.iter().map(|&column| column.to_owned()).collect(),
+            content_rowid: None,
+            tokenize: None,
+        }
+    }
+
+    /// Sets the `INTEGER PRIMARY KEY` column of the content table, if it has one that isn't
+    /// named `rowid`.
+    ///
+    /// The rows of the FTS5 table have the same `rowid` as those of the content table, so it
+    /// must never change once a row is inserted, e.g. with `VACUUM`.
+    pub fn content_rowid(mut self, column: &str) -> Self {
+        self.content_rowid = Some(column.to_owned());
+        self
+    }
+
+    /// Sets the [tokenizer](https://www.sqlite.org/fts5.html#tokenizers) of the FTS5 table and
+    /// its arguments, e.g. `porter unicode61 remove_diacritics 2`.
+    pub fn tokenize(mut self, tokenize: &str) -> Self {
+        self.tokenize = Some(tokenize.to_owned());
+        self
+    }
+
+    /// The SQL creating the FTS5 table and the triggers, then indexing the existing rows of the
+    /// content table.
+    ///
+    /// The triggers are named after the FTS5 table, with the suffixes `_ai`, `_ad` and `_au`.
+    ///
+    /// Returns an error if an option of the FTS5 table contains a NUL character.
+    pub fn to_sql(&self) -> Result<String, QuoteError> {
+        let table = quote(&self.table);
+        let content = quote(&self.content);
+        let rowid = self.content_rowid.as_deref().unwrap_or("rowid");
+        let columns = self
+            .columns
+            .iter()
+            .map(|column| quote(column))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        let values = |row: &str| {
+            let mut values = format!("{}.{}", row, quote(rowid));
+
+            for column in &self.columns {
+                let _ = write!(values, ", {}.{}", row, quote(column));
+            }
+
+            values
+        };
+
+        let new = values("new");
+        let old = values("old");
+
+        let mut sql = format!("CREATE VIRTUAL TABLE {} USING fts5({}", table, columns);
+
+        let _ = write!(sql, ", content={}", Sqlite::quote_literal(&self.content)?);
+
+        if let Some(content_rowid) = &self.content_rowid {
+            let _ = write!(
+                sql,
+                ", content_rowid={}",
+                Sqlite::quote_literal(content_rowid)?
+            );
+        }
+
+        if let Some(tokenize) = &self.tokenize {
+            let _ = write!(sql, ", tokenize={}", Sqlite::quote_literal(tokenize)?);
+        }
+
+        sql.push_str(");\n");
+
+        let _ = write!(
+            sql,
+            "CREATE TRIGGER {trigger} AFTER INSERT ON {content} BEGIN\n  \
+             INSERT INTO {table}(rowid, {columns}) VALUES ({new});\nEND;\n",
+            trigger = quote(&format!("{}_ai", self.table)),
+            content = content,
+            table = table,
+            columns = columns,
+            new = new,
+        );
+
+        let _ = write!(
+            sql,
+            "CREATE TRIGGER {trigger} AFTER DELETE ON {content} BEGIN\n  \
+             INSERT INTO {table}({table}, rowid, {columns}) VALUES ('delete', {old});\nEND;\n",
+            trigger = quote(&format!("{}_ad", self.table)),
+            content = content,
+            table = table,
+            columns = columns,
+            old = old,
+        );
+
+        let _ = write!(
+            sql,
+            "CREATE TRIGGER {trigger} AFTER UPDATE ON {content} BEGIN\n  \
+             INSERT INTO {table}({table}, rowid, {columns}) VALUES ('delete', {old});\n  \
+             INSERT INTO {table}(rowid, {columns}) VALUES ({new});\nEND;\n",
+            trigger = quote(&format!("{}_au", self.table)),
+            content = content,
+            table = table,
+            columns = columns,
+            old = old,
+            new = new,
+        );
+
+        let _ = writeln!(
+            sql,
+            "INSERT INTO {table}({table}) VALUES ('rebuild');",
+            table = table
+        );
+
+        Ok(sql)
+    }
+}
+
+// a string in the FTS5 query syntax, or an identifier in SQL
+fn push_quoted(buf: &mut String, text: &str) {
+    buf.push('"');
+
+    for c in text.chars() {
+        if c == '"' {
+            buf.push('"');
+        }
+
+        buf.push(c);
+    }
+
+    buf.push('"');
+}
+
+fn quote(identifier: &str) -> String {
+    let mut quoted = String::with_capacity(identifier.len() + 2);
+    push_quoted(&mut quoted, identifier);
+    quoted
+}
+
+#[test]
+fn test_quotes_user_input() {
+    assert_eq!(Fts5Query::phrase(r#"say "hi""#).as_str(), r#""say ""hi""""#);
+    assert_eq!(Fts5Query::prefix("NOT").as_str(), r#""NOT" *"#);
+    assert_eq!(
+        Fts5Query::words("  a OR\tb* ( ").as_str(),
+        r#"(("a") AND ("OR")) AND ("b*")"#
+    );
+    assert_eq!(Fts5Query::words(" * ").as_str(), r#""""#);
+    assert_eq!(
+        Fts5Query::phrase("x").column(r#"we"ird"#).as_str(),
+        r#"{"we""ird"} : ("x")"#
+    );
+}
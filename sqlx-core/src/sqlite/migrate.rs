@@ -1,8 +1,10 @@
 use crate::connection::{ConnectOptions, Connection};
 use crate::error::Error;
 use crate::executor::Executor;
-use crate::migrate::MigrateError;
-use crate::migrate::{AppliedMigration, Migration};
+use crate::migrate::{applied_on, warn_missing_audit_columns};
+use crate::migrate::{quote_table_name, repeatable_table_name, split_table_name};
+use crate::migrate::{AppliedMigration, AppliedRepeatableMigration, Migration, Seed};
+use crate::migrate::{CreateDatabaseOptions, MigrateError};
 use crate::migrate::{Migrate, MigrateDatabase};
 use crate::query::query;
 use crate::query_as::query_as;
@@ -17,22 +19,55 @@ use std::time::Instant;
 
 impl MigrateDatabase for Sqlite {
     fn create_database(url: &str) -> BoxFuture<'_, Result<(), Error>> {
+        Box::pin(
+            async move { Self::create_database_with(url, &CreateDatabaseOptions::default()).await },
+        )
+    }
+
+    fn create_database_with<'a>(
+        url: &'a str,
+        create_options: &'a CreateDatabaseOptions,
+    ) -> BoxFuture<'a, Result<(), Error>> {
         Box::pin(async move {
+            create_options.check_supported("SQLite", &["encoding"])?;
+
+            let encoding = match create_options.encoding.as_deref() {
+                Some(encoding) => Some(encoding_name(encoding)?),
+                None => None,
+            };
+
             let mut opts = SqliteConnectOptions::from_str(url)?.create_if_missing(true);
 
             // Since it doesn't make sense to include this flag in the connection URL,
             // we just use an `AtomicBool` to pass it.
-            if super::CREATE_DB_WAL.load(Ordering::Acquire) {
+            let wal = super::CREATE_DB_WAL.load(Ordering::Acquire);
+
+            // the encoding must be set before anything is written to the database, which
+            // switching it to WAL mode does, so that is done after
+            if wal && encoding.is_none() {
                 opts = opts.journal_mode(SqliteJournalMode::Wal);
             }
 
             // Opening a connection to sqlite creates the database
-            let _ = opts
-                .connect()
-                .await?
-                // Ensure WAL mode tempfiles are cleaned up
-                .close()
-                .await?;
+            let mut conn = opts.connect().await?;
+
+            if let Some(encoding) = encoding {
+                let _ = conn
+                    .execute(&*format!("PRAGMA encoding = '{}'", encoding))
+                    .await?;
+
+                // the encoding is only recorded once the schema is written to
+                let _ = conn
+                    .execute("CREATE TABLE _sqlx_encoding (x); DROP TABLE _sqlx_encoding;")
+                    .await?;
+
+                if wal {
+                    let _ = conn.execute("PRAGMA journal_mode = WAL").await?;
+                }
+            }
+
+            // Ensure WAL mode tempfiles are cleaned up
+            conn.close().await?;
 
             Ok(())
         })
@@ -63,34 +98,98 @@ impl MigrateDatabase for Sqlite {
     }
 }
 
+/// The name of one of the text encodings SQLite supports, whatever its case.
+fn encoding_name(encoding: &str) -> Result<&'static str, Error> {
+    ["UTF-8", "UTF-16", "UTF-16le", "UTF-16be"]
+        .into_iter()
+        .find(|name| name.eq_ignore_ascii_case(encoding))
+        .ok_or_else(|| {
+            Error::Configuration(
+                format!(
+                    "unknown SQLite encoding `{}`; expected `UTF-8`, `UTF-16`, `UTF-16le` or `UTF-16be`",
+                    encoding
+                )
+                .into(),
+            )
+        })
+}
+
 impl Migrate for SqliteConnection {
-    fn ensure_migrations_table(&mut self) -> BoxFuture<'_, Result<(), MigrateError>> {
+    fn ensure_migrations_table<'e>(
+        &'e mut self,
+        table_name: &'e str,
+    ) -> BoxFuture<'e, Result<(), MigrateError>> {
         Box::pin(async move {
             // language=SQLite
-            self.execute(
+            self.execute(&*format!(
                 r#"
-CREATE TABLE IF NOT EXISTS _sqlx_migrations (
+CREATE TABLE IF NOT EXISTS {} (
     version BIGINT PRIMARY KEY,
     description TEXT NOT NULL,
     installed_on TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
     success BOOLEAN NOT NULL,
     checksum BLOB NOT NULL,
-    execution_time BIGINT NOT NULL
+    execution_time BIGINT NOT NULL,
+    applied_by TEXT,
+    sqlx_version TEXT,
+    hostname TEXT
 );
                 "#,
-            )
+                quote_table_name(table_name, '"')
+            ))
             .await?;
 
+            if !has_audit_columns(self, table_name).await? {
+                let table = quote_table_name(table_name, '"');
+
+                // SQLite adds a single column at a time
+                let added = async {
+                    let mut tx = self.begin().await?;
+
+                    for column in ["applied_by", "sqlx_version", "hostname"] {
+                        // language=SQLite
+                        let _ = tx
+                            .execute(&*format!(
+                                "ALTER TABLE {} ADD COLUMN {} TEXT",
+                                table, column
+                            ))
+                            .await?;
+                    }
+
+                    tx.commit().await
+                }
+                .await;
+
+                match added {
+                    Ok(()) => {}
+                    Err(e) if e.as_database_error().is_some() => {
+                        warn_missing_audit_columns(table_name, &e)
+                    }
+                    Err(e) => return Err(e.into()),
+                }
+            }
+
             Ok(())
         })
     }
 
-    fn version(&mut self) -> BoxFuture<'_, Result<Option<(i64, bool)>, MigrateError>> {
+    fn migrations_table_exists<'e>(
+        &'e mut self,
+        table_name: &'e str,
+    ) -> BoxFuture<'e, Result<bool, MigrateError>> {
+        Box::pin(async move { table_exists(self, table_name).await })
+    }
+
+    fn version<'e>(
+        &'e mut self,
+        table_name: &'e str,
+    ) -> BoxFuture<'e, Result<Option<(i64, bool)>, MigrateError>> {
         Box::pin(async move {
             // language=SQLite
-            let row = query_as(
-                "SELECT version, NOT success FROM _sqlx_migrations ORDER BY version DESC LIMIT 1",
-            )
+            let row = query_as(&format!(
+                "SELECT version, NOT success FROM {} ORDER BY version DESC LIMIT 1",
+                quote_table_name(table_name, '"')
+            ))
             .fetch_optional(self)
             .await?;
 
@@ -98,12 +197,16 @@ CREATE TABLE IF NOT EXISTS _sqlx_migrations (
         })
     }
 
-    fn dirty_version(&mut self) -> BoxFuture<'_, Result<Option<i64>, MigrateError>> {
+    fn dirty_version<'e>(
+        &'e mut self,
+        table_name: &'e str,
+    ) -> BoxFuture<'e, Result<Option<i64>, MigrateError>> {
         Box::pin(async move {
             // language=SQLite
-            let row: Option<(i64,)> = query_as(
-                "SELECT version FROM _sqlx_migrations WHERE success = false ORDER BY version LIMIT 1",
-            )
+            let row: Option<(i64,)> = query_as(&format!(
+                "SELECT version FROM {} WHERE success = false ORDER BY version LIMIT 1",
+                quote_table_name(table_name, '"')
+            ))
             .fetch_optional(self)
             .await?;
 
@@ -111,20 +214,66 @@ CREATE TABLE IF NOT EXISTS _sqlx_migrations (
         })
     }
 
-    fn list_applied_migrations(
-        &mut self,
-    ) -> BoxFuture<'_, Result<Vec<AppliedMigration>, MigrateError>> {
+    fn list_applied_migrations<'e>(
+        &'e mut self,
+        table_name: &'e str,
+    ) -> BoxFuture<'e, Result<Vec<AppliedMigration>, MigrateError>> {
         Box::pin(async move {
+            let columns = if has_audit_columns(self, table_name).await? {
+                "version, checksum, applied_by, sqlx_version, hostname"
+            } else {
+                "version, checksum, NULL, NULL, NULL"
+            };
+
             // language=SQLite
-            let rows: Vec<(i64, Vec<u8>)> =
-                query_as("SELECT version, checksum FROM _sqlx_migrations ORDER BY version")
-                    .fetch_all(self)
-                    .await?;
+            let rows: Vec<AppliedRow> = query_as(&format!(
+                "SELECT {} FROM {} ORDER BY version",
+                columns,
+                quote_table_name(table_name, '"')
+            ))
+            .fetch_all(self)
+            .await?;
 
             let migrations = rows
                 .into_iter()
-                .map(|(version, checksum)| AppliedMigration {
-                    version,
+                .map(
+                    |(version, checksum, applied_by, sqlx_version, hostname)| AppliedMigration {
+                        version,
+                        checksum: checksum.into(),
+                        applied_by,
+                        sqlx_version,
+                        hostname,
+                    },
+                )
+                .collect();
+
+            Ok(migrations)
+        })
+    }
+
+    fn list_applied_repeatable_migrations<'e>(
+        &'e mut self,
+        table_name: &'e str,
+    ) -> BoxFuture<'e, Result<Vec<AppliedRepeatableMigration>, MigrateError>> {
+        Box::pin(async move {
+            let repeatable_table = repeatable_table_name(table_name);
+
+            if !table_exists(self, &repeatable_table).await? {
+                return Ok(Vec::new());
+            }
+
+            // language=SQLite
+            let rows: Vec<(String, Vec<u8>)> = query_as(&format!(
+                "SELECT description, checksum FROM {} WHERE success = true ORDER BY description",
+                quote_table_name(&repeatable_table, '"')
+            ))
+            .fetch_all(self)
+            .await?;
+
+            let migrations = rows
+                .into_iter()
+                .map(|(description, checksum)| AppliedRepeatableMigration {
+                    description: description.into(),
                     checksum: checksum.into(),
                 })
                 .collect();
@@ -133,25 +282,34 @@ CREATE TABLE IF NOT EXISTS _sqlx_migrations (
         })
     }
 
-    fn lock(&mut self) -> BoxFuture<'_, Result<(), MigrateError>> {
+    // each migration is applied in a transaction, which SQLite runs one at a time, so there's
+    // no lock to wait for
+    fn lock<'e>(
+        &'e mut self,
+        _table_name: &'e str,
+        _timeout: Option<Duration>,
+    ) -> BoxFuture<'e, Result<(), MigrateError>> {
         Box::pin(async move { Ok(()) })
     }
 
-    fn unlock(&mut self) -> BoxFuture<'_, Result<(), MigrateError>> {
+    fn unlock<'e>(&'e mut self, _table_name: &'e str) -> BoxFuture<'e, Result<(), MigrateError>> {
         Box::pin(async move { Ok(()) })
     }
 
     fn validate<'e: 'm, 'm>(
         &'e mut self,
+        table_name: &'m str,
         migration: &'m Migration,
     ) -> BoxFuture<'m, Result<(), MigrateError>> {
         Box::pin(async move {
             // language=SQL
-            let checksum: Option<Vec<u8>> =
-                query_scalar("SELECT checksum FROM _sqlx_migrations WHERE version = ?1")
-                    .bind(migration.version)
-                    .fetch_optional(self)
-                    .await?;
+            let checksum: Option<Vec<u8>> = query_scalar(&format!(
+                "SELECT checksum FROM {} WHERE version = ?1",
+                quote_table_name(table_name, '"')
+            ))
+            .bind(migration.version)
+            .fetch_optional(self)
+            .await?;
 
             if let Some(checksum) = checksum {
                 if checksum == &*migration.checksum {
@@ -167,57 +325,263 @@ CREATE TABLE IF NOT EXISTS _sqlx_migrations (
 
     fn apply<'e: 'm, 'm>(
         &'e mut self,
+        table_name: &'m str,
         migration: &'m Migration,
+        applied_by: Option<&'m str>,
     ) -> BoxFuture<'m, Result<Duration, MigrateError>> {
         Box::pin(async move {
-            let mut tx = self.begin().await?;
             let start = Instant::now();
 
-            let _ = tx.execute(&*migration.sql).await?;
-
-            tx.commit().await?;
+            if migration.no_tx {
+                // each statement is run in a transaction of its own
+                let _ = self.execute(&*migration.sql).await?;
+            } else {
+                let mut tx = self.begin().await?;
+                let _ = tx.execute(&*migration.sql).await?;
+                tx.commit().await?;
+            }
 
             let elapsed = start.elapsed();
 
-            // language=SQL
-            let _ = query(
-                r#"
-    INSERT INTO _sqlx_migrations ( version, description, success, checksum, execution_time )
-    VALUES ( ?1, ?2, TRUE, ?3, ?4 )
-                "#,
-            )
-            .bind(migration.version)
-            .bind(&*migration.description)
+            if migration.migration_type.is_repeatable() {
+                record_applied_repeatable(
+                    self,
+                    table_name,
+                    migration,
+                    elapsed.as_nanos() as i64,
+                    applied_by,
+                )
+                .await?;
+            } else {
+                record_applied(
+                    self,
+                    table_name,
+                    migration,
+                    elapsed.as_nanos() as i64,
+                    applied_by,
+                )
+                .await?;
+            }
+
+            Ok(elapsed)
+        })
+    }
+
+    fn set_checksum<'e: 'm, 'm>(
+        &'e mut self,
+        table_name: &'m str,
+        migration: &'m Migration,
+    ) -> BoxFuture<'m, Result<(), MigrateError>> {
+        Box::pin(async move {
+            // language=SQLite
+            let _ = query(&format!(
+                r#"UPDATE {} SET checksum = ?1 WHERE version = ?2"#,
+                quote_table_name(table_name, '"')
+            ))
             .bind(&*migration.checksum)
-            .bind(elapsed.as_nanos() as i64)
+            .bind(migration.version)
             .execute(self)
             .await?;
 
-            Ok(elapsed)
+            Ok(())
         })
     }
 
+    fn mark_applied<'e: 'm, 'm>(
+        &'e mut self,
+        table_name: &'m str,
+        migration: &'m Migration,
+        applied_by: Option<&'m str>,
+    ) -> BoxFuture<'m, Result<(), MigrateError>> {
+        Box::pin(async move { record_applied(self, table_name, migration, 0, applied_by).await })
+    }
+
     fn revert<'e: 'm, 'm>(
         &'e mut self,
+        table_name: &'m str,
         migration: &'m Migration,
     ) -> BoxFuture<'m, Result<Duration, MigrateError>> {
         Box::pin(async move {
-            let mut tx = self.begin().await?;
             let start = Instant::now();
 
-            let _ = tx.execute(&*migration.sql).await?;
-
-            tx.commit().await?;
+            if migration.no_tx {
+                // each statement is run in a transaction of its own
+                let _ = self.execute(&*migration.sql).await?;
+            } else {
+                let mut tx = self.begin().await?;
+                let _ = tx.execute(&*migration.sql).await?;
+                tx.commit().await?;
+            }
 
             let elapsed = start.elapsed();
 
             // language=SQL
-            let _ = query(r#"DELETE FROM _sqlx_migrations WHERE version = ?1"#)
-                .bind(migration.version)
-                .execute(self)
-                .await?;
+            let _ = query(&format!(
+                r#"DELETE FROM {} WHERE version = ?1"#,
+                quote_table_name(table_name, '"')
+            ))
+            .bind(migration.version)
+            .execute(self)
+            .await?;
 
             Ok(elapsed)
         })
     }
+
+    fn seed<'e: 'm, 'm>(
+        &'e mut self,
+        seed: &'m Seed,
+    ) -> BoxFuture<'m, Result<Duration, MigrateError>> {
+        Box::pin(async move {
+            let start = Instant::now();
+
+            let run = async {
+                let mut tx = self.begin().await?;
+                let _ = tx.execute(&*seed.sql).await?;
+                tx.commit().await
+            };
+
+            run.await
+                .map_err(|e| MigrateError::Seed(seed.name.to_string(), e))?;
+
+            Ok(start.elapsed())
+        })
+    }
+}
+
+// version, checksum, applied_by, sqlx_version, hostname
+type AppliedRow = (i64, Vec<u8>, Option<String>, Option<String>, Option<String>);
+
+// the columns for audits are missing from migrations tables created before them, if they
+// couldn't be added
+async fn has_audit_columns(
+    conn: &mut SqliteConnection,
+    table_name: &str,
+) -> Result<bool, MigrateError> {
+    let (schema, name) = split_table_name(table_name);
+
+    // language=SQLite
+    let exists = query_scalar(
+        "SELECT EXISTS (SELECT 1 FROM pragma_table_info(?1, ?2) WHERE name = 'applied_by')",
+    )
+    .bind(name)
+    .bind(schema.unwrap_or("main"))
+    .fetch_one(conn)
+    .await?;
+
+    Ok(exists)
+}
+
+// a table always has a column, so it exists if it has any
+async fn table_exists(conn: &mut SqliteConnection, table_name: &str) -> Result<bool, MigrateError> {
+    let (schema, name) = split_table_name(table_name);
+
+    // language=SQLite
+    let exists = query_scalar("SELECT EXISTS (SELECT 1 FROM pragma_table_info(?1, ?2))")
+        .bind(name)
+        .bind(schema.unwrap_or("main"))
+        .fetch_one(conn)
+        .await?;
+
+    Ok(exists)
+}
+
+// SQLite has no users, so `applied_by` is only recorded if it is set
+async fn record_applied(
+    conn: &mut SqliteConnection,
+    table_name: &str,
+    migration: &Migration,
+    execution_time: i64,
+    applied_by: Option<&str>,
+) -> Result<(), MigrateError> {
+    let table = quote_table_name(table_name, '"');
+
+    if has_audit_columns(conn, table_name).await? {
+        let (sqlx_version, hostname) = applied_on();
+
+        // language=SQLite
+        let _ = query(&format!(
+            r#"
+    INSERT INTO {} ( version, description, success, checksum, execution_time, applied_by, sqlx_version, hostname )
+    VALUES ( ?1, ?2, TRUE, ?3, ?4, ?5, ?6, ?7 )
+            "#,
+            table
+        ))
+        .bind(migration.version)
+        .bind(&*migration.description)
+        .bind(&*migration.checksum)
+        .bind(execution_time)
+        .bind(applied_by)
+        .bind(sqlx_version)
+        .bind(hostname)
+        .execute(conn)
+        .await?;
+    } else {
+        // language=SQLite
+        let _ = query(&format!(
+            r#"
+    INSERT INTO {} ( version, description, success, checksum, execution_time )
+    VALUES ( ?1, ?2, TRUE, ?3, ?4 )
+            "#,
+            table
+        ))
+        .bind(migration.version)
+        .bind(&*migration.description)
+        .bind(&*migration.checksum)
+        .bind(execution_time)
+        .execute(conn)
+        .await?;
+    }
+
+    Ok(())
+}
+
+// the table of repeatable migrations is created with the first one, in the schema of the
+// migrations table
+async fn record_applied_repeatable(
+    conn: &mut SqliteConnection,
+    table_name: &str,
+    migration: &Migration,
+    execution_time: i64,
+    applied_by: Option<&str>,
+) -> Result<(), MigrateError> {
+    let table = quote_table_name(&repeatable_table_name(table_name), '"');
+    let (sqlx_version, hostname) = applied_on();
+
+    // language=SQLite
+    conn.execute(&*format!(
+        r#"
+CREATE TABLE IF NOT EXISTS {} (
+    description TEXT PRIMARY KEY,
+    installed_on TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    success BOOLEAN NOT NULL,
+    checksum BLOB NOT NULL,
+    execution_time BIGINT NOT NULL,
+    applied_by TEXT,
+    sqlx_version TEXT,
+    hostname TEXT
+);
+        "#,
+        table
+    ))
+    .await?;
+
+    // language=SQLite
+    let _ = query(&format!(
+        r#"
+    INSERT OR REPLACE INTO {} ( description, success, checksum, execution_time, applied_by, sqlx_version, hostname )
+    VALUES ( ?1, TRUE, ?2, ?3, ?4, ?5, ?6 )
+        "#,
+        table
+    ))
+    .bind(&*migration.description)
+    .bind(&*migration.checksum)
+    .bind(execution_time)
+    .bind(applied_by)
+    .bind(sqlx_version)
+    .bind(hostname)
+    .execute(conn)
+    .await?;
+
+    Ok(())
 }
@@ -7,11 +7,19 @@
 
 pub use arguments::{SqliteArgumentValue, SqliteArguments};
 pub use column::SqliteColumn;
-pub use connection::{LockedSqliteHandle, SqliteConnection};
+#[cfg(feature = "sqlite-snapshot")]
+pub use connection::SqliteSnapshot;
+pub use connection::{
+    LockedSqliteHandle, SqliteBackup, SqliteBackupProgress, SqliteCheckpointMode,
+    SqliteCheckpointResult, SqliteConnection, SqliteInterruptHandle, SqliteOperation,
+    SqliteQueryPlan, SqliteQueryPlanNode, SqliteUpdate, SqliteUpdates, SqliteUpdatesOptions,
+};
 pub use database::Sqlite;
+pub use dynamic_value::SqliteDynamicValue;
 pub use error::SqliteError;
 pub use options::{
-    SqliteAutoVacuum, SqliteConnectOptions, SqliteJournalMode, SqliteLockingMode, SqliteSynchronous,
+    SqliteAutoVacuum, SqliteConnectOptions, SqliteHardening, SqliteJournalMode, SqliteLimits,
+    SqliteLockingMode, SqliteSynchronous, SqliteTransactionBehavior,
 };
 pub use query_result::SqliteQueryResult;
 pub use row::SqliteRow;
@@ -30,7 +38,9 @@ mod arguments;
 mod column;
 mod connection;
 mod database;
+mod dynamic_value;
 mod error;
+pub mod fts;
 mod options;
 mod query_result;
 mod row;
@@ -46,6 +56,9 @@ mod migrate;
 #[cfg(feature = "migrate")]
 mod testing;
 
+#[cfg(feature = "serde_row")]
+mod serde_row;
+
 /// An alias for [`Pool`][crate::pool::Pool], specialized for SQLite.
 pub type SqlitePool = crate::pool::Pool<Sqlite>;
 
@@ -82,7 +95,7 @@ pub fn describe_blocking(
     let mut conn = params.establish()?;
 
     // Execute any ancillary `PRAGMA`s
-    connection::execute::iter(&mut conn, &opts.pragma_string(), None, false)?.finish()?;
+    connection::execute::iter(&mut conn, &opts.pragma_string()?, None, false)?.finish()?;
 
     connection::describe::describe(&mut conn, query)
 
@@ -1,11 +1,20 @@
-use crate::connection::ConnectOptions;
+use crate::connection::{redact_query, ConnectOptions, REDACTED};
 use crate::error::Error;
 use crate::executor::Executor;
+use crate::logger::StatementLogger;
+use crate::observer::QueryObserver;
+use crate::sqlite::connection::establish::EstablishParams;
+use crate::sqlite::connection::handle::ConnectionHandle;
+use crate::sqlite::options::parse::is_key_pragma;
+use crate::sqlite::options::pragma::check_pragma;
 use crate::sqlite::{SqliteConnectOptions, SqliteConnection};
 use futures_core::future::BoxFuture;
 use log::LevelFilter;
-use std::fmt::Write;
+use std::fmt::{self, Debug, Formatter, Write};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
+use url::Url;
 
 impl ConnectOptions for SqliteConnectOptions {
     type Connection = SqliteConnection;
@@ -15,11 +24,14 @@ impl ConnectOptions for SqliteConnectOptions {
         Self::Connection: Sized,
     {
         Box::pin(async move {
-            let mut conn = SqliteConnection::establish(self).await?;
+            if let Some(keep_alive) = &self.keep_alive {
+                keep_alive.open(self)?;
+            }
 
-            // Execute PRAGMAs
-            conn.execute(&*self.pragma_string()).await?;
+            let mut conn = SqliteConnection::establish(self).await?;
 
+            // Collations come first, as a PRAGMA such as `optimize` may already need
+            // the ones used by indexes
             if !self.collations.is_empty() {
                 let mut locked = conn.lock_handle().await?;
 
@@ -28,10 +40,38 @@ impl ConnectOptions for SqliteConnectOptions {
                 }
             }
 
+            // Execute PRAGMAs
+            conn.execute(&*self.pragma_string()?).await?;
+
+            // Attach databases once the main one is set up, e.g. decrypted with the `key` PRAGMA
+            if !self.attached.is_empty() {
+                conn.execute(&*self.attach_string()?).await?;
+            }
+
+            // Only report failed writes as those of a read-only connection from here on
+            if self.read_only || self.immutable || self.query_only {
+                conn.lock_handle().await?.guard.read_only = true;
+            }
+
             Ok(conn)
         })
     }
 
+    fn to_url_lossless(&self) -> Result<Url, Error> {
+        self.build_url()
+    }
+
+    fn to_url_redacted(&self) -> Result<Url, Error> {
+        let mut url = self.build_url()?;
+
+        redact_query(&mut url, |key, value| {
+            let (name, _) = value.split_once('=').filter(|_| key == "pragma")?;
+            is_key_pragma(name).then(|| format!("{}={}", name, REDACTED))
+        });
+
+        Ok(url)
+    }
+
     fn log_statements(&mut self, level: LevelFilter) -> &mut Self {
         self.log_settings.log_statements(level);
         self
@@ -41,19 +81,111 @@ impl ConnectOptions for SqliteConnectOptions {
         self.log_settings.log_slow_statements(level, duration);
         self
     }
+
+    fn capture_statement_on_error(&mut self, capture: bool) -> &mut Self {
+        self.log_settings.capture_statement_on_error(capture);
+        self
+    }
+
+    fn log_with(&mut self, logger: Arc<dyn StatementLogger>) -> &mut Self {
+        self.log_settings.log_with(logger);
+        self
+    }
+
+    fn query_observer(&mut self, observer: Arc<dyn QueryObserver>) -> &mut Self {
+        self.log_settings.query_observer(observer);
+        self
+    }
+
+    #[cfg(feature = "tracing")]
+    fn trace_statement_limit(&mut self, limit: usize) -> &mut Self {
+        self.log_settings.trace_statement_limit(limit);
+        self
+    }
+
+    fn check_pool_size(&self, max_connections: u32) {
+        let private_memory =
+            !self.shared_cache && (self.in_memory || self.filename == Path::new(":memory:"));
+
+        if private_memory && max_connections > 1 {
+            log::warn!(
+                "each connection of a pool opened with the SQLite database `{}` opens a \
+                 separate, empty in-memory database; use `SqliteConnectOptions::shared_memory()` \
+                 for the connections to share one",
+                self.filename.display()
+            );
+        }
+    }
 }
 
 impl SqliteConnectOptions {
-    /// Collect all `PRAMGA` commands into a single string
-    pub(crate) fn pragma_string(&self) -> String {
+    /// Collect all `PRAMGA` commands into a single string, failing on an invalid value which
+    /// SQLite would ignore.
+    pub(crate) fn pragma_string(&self) -> Result<String, Error> {
         let mut string = String::new();
 
-        for (key, opt_value) in &self.pragmas {
-            if let Some(value) = opt_value {
-                write!(string, "PRAGMA {} = {}; ", key, value).ok();
+        for (key, value) in self.pragmas() {
+            check_pragma(key, value)?;
+
+            write!(string, "PRAGMA {} = {}; ", key, value).ok();
+        }
+
+        Ok(string)
+    }
+
+    /// Collect the `ATTACH DATABASE` statements into a single string, each followed by the
+    /// PRAGMAs which are set per database, as they only applied to the main one.
+    pub(crate) fn attach_string(&self) -> Result<String, Error> {
+        let mut string = String::new();
+
+        for (name, path) in &self.attached {
+            let path = path.to_str().ok_or_else(|| {
+                Error::Configuration(
+                    format!("path of attached database `{}` must be valid UTF-8", name).into(),
+                )
+            })?;
+
+            let name = name.replace('"', "\"\"");
+
+            write!(
+                string,
+                "ATTACH DATABASE '{}' AS \"{}\"; ",
+                path.replace('\'', "''"),
+                name
+            )
+            .ok();
+
+            for key in ["page_size", "auto_vacuum", "journal_mode", "synchronous"] {
+                if let Some(Some(value)) = self.pragmas.get(key) {
+                    write!(string, "PRAGMA \"{}\".{} = {}; ", name, key, value).ok();
+                }
             }
         }
 
-        string
+        Ok(string)
+    }
+}
+
+/// A connection to a shared in-memory database, which exists for as long as a connection to it
+/// is open. It is opened along with the first one, and never used.
+#[derive(Default)]
+pub(crate) struct KeepAlive(Mutex<Option<ConnectionHandle>>);
+
+impl KeepAlive {
+    fn open(&self, options: &SqliteConnectOptions) -> Result<(), Error> {
+        let mut conn = self.0.lock().unwrap_or_else(|e| e.into_inner());
+
+        if conn.is_none() {
+            // opening an in-memory database is quick enough not to need the worker thread
+            *conn = Some(EstablishParams::from_options(options)?.open()?);
+        }
+
+        Ok(())
+    }
+}
+
+impl Debug for KeepAlive {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("KeepAlive").finish_non_exhaustive()
     }
 }
@@ -0,0 +1,92 @@
+/// Settings which limit what a database file, or the statements executed on it, can do, for
+/// opening files which can't be trusted.
+///
+/// Set with [`SqliteConnectOptions::hardening()`](super::SqliteConnectOptions::hardening). The
+/// [`Default`] is how SQLite behaves without any of them, and [`strict()`](Self::strict) turns
+/// them all on. See [Defense Against The Dark Arts](https://www.sqlite.org/security.html) for
+/// details.
+///
+/// ```rust,no_run
+/// # use sqlx_core::sqlite::{SqliteHardening, SqliteLimits};
+/// // allow a single attached database on top of the strict settings
+/// let hardening = SqliteHardening {
+///     limits: SqliteLimits {
+///         attached: Some(1),
+///         ..SqliteHardening::strict().limits
+///     },
+///     ..SqliteHardening::strict()
+/// };
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SqliteHardening {
+    /// Set [`SQLITE_DBCONFIG_DEFENSIVE`], which keeps statements from corrupting the database,
+    /// e.g. by writing to `sqlite_schema` or to the shadow tables of a virtual table.
+    ///
+    /// [`SQLITE_DBCONFIG_DEFENSIVE`]: https://www.sqlite.org/c3ref/c_dbconfig_defensive.html#sqlitedbconfigdefensive
+    pub defensive: bool,
+
+    /// Whether the views and triggers of the database may use functions and virtual tables
+    /// which have side effects. This is [`SQLITE_DBCONFIG_TRUSTED_SCHEMA`], on by default.
+    ///
+    /// [`SQLITE_DBCONFIG_TRUSTED_SCHEMA`]: https://www.sqlite.org/c3ref/c_dbconfig_defensive.html#sqlitedbconfigtrustedschema
+    pub trusted_schema: bool,
+
+    /// Set [`PRAGMA cell_size_check`](https://www.sqlite.org/pragma.html#pragma_cell_size_check),
+    /// which checks the pages of the database for corruption as they are read.
+    pub cell_size_check: bool,
+
+    pub limits: SqliteLimits,
+}
+
+/// Caps on the size of the values and statements of a connection, lowering those SQLite was
+/// built with. `None` keeps the cap SQLite was built with.
+///
+/// Going over a cap is an error of the statement. See
+/// [`sqlite3_limit()`](https://www.sqlite.org/c3ref/limit.html) for details.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SqliteLimits {
+    /// The largest string or blob, or row, in bytes: `SQLITE_LIMIT_LENGTH`.
+    pub length: Option<u32>,
+
+    /// The longest statement, in bytes: `SQLITE_LIMIT_SQL_LENGTH`.
+    pub sql_length: Option<u32>,
+
+    /// The deepest expression tree: `SQLITE_LIMIT_EXPR_DEPTH`.
+    pub expr_depth: Option<u32>,
+
+    /// The most databases which can be attached: `SQLITE_LIMIT_ATTACHED`.
+    ///
+    /// This includes those attached with
+    /// [`SqliteConnectOptions::attach()`](super::SqliteConnectOptions::attach).
+    pub attached: Option<u32>,
+}
+
+impl Default for SqliteHardening {
+    fn default() -> Self {
+        Self {
+            defensive: false,
+            trusted_schema: true,
+            cell_size_check: false,
+            limits: SqliteLimits::default(),
+        }
+    }
+}
+
+impl SqliteHardening {
+    /// Settings for opening database files which can't be trusted: defensive, with an untrusted
+    /// schema and cell size checks, no attached database, statements of up to 100 KB, and
+    /// expressions up to 100 deep. Strings and blobs keep the default cap of 1 GB.
+    pub fn strict() -> Self {
+        Self {
+            defensive: true,
+            trusted_schema: false,
+            cell_size_check: true,
+            limits: SqliteLimits {
+                length: None,
+                sql_length: Some(100_000),
+                expr_depth: Some(100),
+                attached: Some(0),
+            },
+        }
+    }
+}
@@ -2,24 +2,44 @@ use std::path::Path;
 
 mod auto_vacuum;
 mod connect;
+mod hardening;
 mod journal_mode;
 mod locking_mode;
 mod parse;
+mod pragma;
 mod synchronous;
+mod transaction_behavior;
 
-use crate::connection::LogSettings;
+use crate::connection::{LogSettings, REDACTED};
 pub use auto_vacuum::SqliteAutoVacuum;
+pub use hardening::{SqliteHardening, SqliteLimits};
 pub use journal_mode::SqliteJournalMode;
 pub use locking_mode::SqliteLockingMode;
 use std::cmp::Ordering;
+use std::fmt::{self, Debug, Formatter};
+use std::ops::ControlFlow;
+use std::path::PathBuf;
+use std::sync::atomic::{self, AtomicUsize};
 use std::sync::Arc;
 use std::{borrow::Cow, time::Duration};
 pub use synchronous::SqliteSynchronous;
+pub use transaction_behavior::SqliteTransactionBehavior;
 
 use crate::common::DebugFn;
 use crate::sqlite::connection::collation::Collation;
+use crate::sqlite::connection::interrupt::ProgressFn;
+use crate::sqlite::options::connect::KeepAlive;
 use indexmap::IndexMap;
 
+// https://www.sqlite.org/inmemorydb.html
+
+static IN_MEMORY_DB_SEQ: AtomicUsize = AtomicUsize::new(0);
+
+fn in_memory_filename(name: &str) -> PathBuf {
+    let seqno = IN_MEMORY_DB_SEQ.fetch_add(1, atomic::Ordering::Relaxed);
+    PathBuf::from(format!("file:{}-{}", name, seqno))
+}
+
 /// Options and flags which can be used to configure a SQLite connection.
 ///
 /// A value of `SqliteConnectOptions` can be parsed from a connection URL,
@@ -52,7 +72,7 @@ use indexmap::IndexMap;
 /// # }).unwrap();
 /// # }
 /// ```
-#[derive(Clone, Debug)]
+#[derive(Clone)]
 pub struct SqliteConnectOptions {
     pub(crate) filename: Cow<'static, Path>,
     pub(crate) in_memory: bool,
@@ -61,9 +81,12 @@ pub struct SqliteConnectOptions {
     pub(crate) shared_cache: bool,
     pub(crate) statement_cache_capacity: usize,
     pub(crate) busy_timeout: Duration,
+    pub(crate) transaction_behavior: SqliteTransactionBehavior,
     pub(crate) log_settings: LogSettings,
     pub(crate) immutable: bool,
+    pub(crate) query_only: bool,
     pub(crate) vfs: Option<Cow<'static, str>>,
+    pub(crate) hardening: SqliteHardening,
 
     pub(crate) pragmas: IndexMap<Cow<'static, str>, Option<Cow<'static, str>>>,
 
@@ -72,10 +95,68 @@ pub struct SqliteConnectOptions {
 
     pub(crate) collations: Vec<Collation>,
 
+    // extensions, with their entry points, loaded in order when the connection is opened
+    pub(crate) extensions: IndexMap<Cow<'static, str>, Option<Cow<'static, str>>>,
+
+    // called every so many virtual machine instructions while a statement is executed
+    pub(crate) progress_handler: Option<(u32, Arc<ProgressFn>)>,
+    pub(crate) interrupt_on_drop: bool,
+
+    // databases attached in order after the PRAGMAs, by schema name
+    pub(crate) attached: IndexMap<Cow<'static, str>, Cow<'static, Path>>,
+
+    // held by the options of a shared in-memory database, and their clones
+    pub(crate) keep_alive: Option<Arc<KeepAlive>>,
+
     pub(crate) serialized: bool,
     pub(crate) thread_name: Arc<DebugFn<dyn Fn(u64) -> String + Send + Sync + 'static>>,
 }
 
+impl Debug for SqliteConnectOptions {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        // the values of the PRAGMAs of SQLCipher which set the encryption key are secrets
+        let pragmas: IndexMap<&str, Option<&str>> = self
+            .pragmas
+            .iter()
+            .map(|(key, value)| {
+                let value = match value {
+                    Some(_) if parse::is_key_pragma(key) => Some(REDACTED),
+                    value => value.as_deref(),
+                };
+
+                (&**key, value)
+            })
+            .collect();
+
+        f.debug_struct("SqliteConnectOptions")
+            .field("filename", &self.filename)
+            .field("in_memory", &self.in_memory)
+            .field("read_only", &self.read_only)
+            .field("create_if_missing", &self.create_if_missing)
+            .field("shared_cache", &self.shared_cache)
+            .field("statement_cache_capacity", &self.statement_cache_capacity)
+            .field("busy_timeout", &self.busy_timeout)
+            .field("transaction_behavior", &self.transaction_behavior)
+            .field("log_settings", &self.log_settings)
+            .field("immutable", &self.immutable)
+            .field("query_only", &self.query_only)
+            .field("vfs", &self.vfs)
+            .field("hardening", &self.hardening)
+            .field("pragmas", &pragmas)
+            .field("command_channel_size", &self.command_channel_size)
+            .field("row_channel_size", &self.row_channel_size)
+            .field("collations", &self.collations)
+            .field("extensions", &self.extensions)
+            .field("progress_handler", &self.progress_handler)
+            .field("interrupt_on_drop", &self.interrupt_on_drop)
+            .field("attached", &self.attached)
+            .field("keep_alive", &self.keep_alive)
+            .field("serialized", &self.serialized)
+            .field("thread_name", &self.thread_name)
+            .finish()
+    }
+}
+
 impl Default for SqliteConnectOptions {
     fn default() -> Self {
         Self::new()
@@ -162,6 +243,11 @@ impl SqliteConnectOptions {
 
         pragmas.insert("auto_vacuum".into(), None);
 
+        // These only tune the connection, and can be set in any order after the above.
+        pragmas.insert("temp_store".into(), None);
+        pragmas.insert("cache_size".into(), None);
+        pragmas.insert("mmap_size".into(), None);
+
         Self {
             filename: Cow::Borrowed(Path::new(":memory:")),
             in_memory: false,
@@ -170,11 +256,19 @@ impl SqliteConnectOptions {
             shared_cache: false,
             statement_cache_capacity: 100,
             busy_timeout: Duration::from_secs(5),
+            transaction_behavior: SqliteTransactionBehavior::Deferred,
             log_settings: Default::default(),
             immutable: false,
+            query_only: false,
             vfs: None,
+            hardening: SqliteHardening::default(),
             pragmas,
             collations: Default::default(),
+            extensions: Default::default(),
+            attached: Default::default(),
+            keep_alive: None,
+            progress_handler: None,
+            interrupt_on_drop: false,
             serialized: false,
             thread_name: Arc::new(DebugFn(|id| format!("sqlx-sqlite-worker-{}", id))),
             command_channel_size: 50,
@@ -183,8 +277,43 @@ impl SqliteConnectOptions {
     }
 
     /// Sets the name of the database file.
+    ///
+    /// The default, `:memory:`, opens a new in-memory database for each connection, which is
+    /// likely not what is wanted for a pool; see [`shared_memory()`](Self::shared_memory).
     pub fn filename(mut self, filename: impl AsRef<Path>) -> Self {
         self.filename = Cow::Owned(filename.as_ref().to_owned());
+        self.keep_alive = None;
+        self
+    }
+
+    /// Open an in-memory database shared by all the connections opened with these options or
+    /// their clones, such as those of a pool.
+    ///
+    /// The database is named `file:<name>-<N>`, where `N` makes it unique to these options, so
+    /// that each pool gets its own. It lives until the options and all their clones are dropped,
+    /// even once all the connections to it are closed, as another connection to it is kept open
+    /// in the meantime, from when the first one is opened.
+    ///
+    /// A `sqlite::memory:` URL also opens a database shared by the connections of a pool, but
+    /// it is deleted along with its data whenever the pool happens to have no connection open,
+    /// e.g. once they all reached their [idle timeout](crate::pool::PoolOptions::idle_timeout).
+    ///
+    /// ```rust,no_run
+    /// # use sqlx_core::error::Error;
+    /// # use sqlx_core::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+    /// # async fn example() -> Result<(), Error> {
+    /// let pool = SqlitePoolOptions::new()
+    ///     .max_connections(4)
+    ///     .connect_with(SqliteConnectOptions::new().shared_memory("cache"))
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn shared_memory(mut self, name: &str) -> Self {
+        self.filename = Cow::Owned(in_memory_filename(name));
+        self.in_memory = true;
+        self.shared_cache = true;
+        self.keep_alive = Some(Arc::new(KeepAlive::default()));
         self
     }
 
@@ -238,6 +367,14 @@ impl SqliteConnectOptions {
 
     /// Sets the [access mode](https://www.sqlite.org/c3ref/open.html) to open the database
     /// for read-only access.
+    ///
+    /// Statements which write to the database then fail with an
+    /// [`Error::Database`](crate::error::Error::Database) with the code `SQLITE_READONLY`. The
+    /// `temp` schema is still written to, e.g. by `CREATE TEMP TABLE`.
+    ///
+    /// A file in a read-only directory, or on read-only media, may still not be opened this way
+    /// as SQLite may need to create its journal or the shared-memory file of the WAL; see
+    /// [`immutable`](Self::immutable) for those.
     pub fn read_only(mut self, read_only: bool) -> Self {
         self.read_only = read_only;
         self
@@ -266,12 +403,53 @@ impl SqliteConnectOptions {
     /// Sets a timeout value to wait when the database is locked, before
     /// returning a busy timeout error.
     ///
-    /// The default busy timeout is 5 seconds.
+    /// The default busy timeout is 5 seconds. A timeout of zero returns the error right away.
+    ///
+    /// SQLite doesn't wait in every case: a transaction which has read from a database in WAL
+    /// mode and then writes to it fails right away if another connection wrote in between, as
+    /// it would write over changes it hasn't seen. Such errors, like the ones returned after the
+    /// timeout, are of the kind [`ErrorKind::Locked`][crate::error::ErrorKind::Locked] and the
+    /// transaction can be retried; starting it with `BEGIN IMMEDIATE` avoids them, see
+    /// [`transaction_behavior`](Self::transaction_behavior).
+    ///
+    /// See [`sqlite3_busy_timeout()`](https://www.sqlite.org/c3ref/busy_timeout.html) for details.
     pub fn busy_timeout(mut self, timeout: Duration) -> Self {
         self.busy_timeout = timeout;
         self
     }
 
+    /// Sets how the transactions begun on the connection take the lock of the database, i.e.
+    /// whether they begin with `BEGIN`, `BEGIN IMMEDIATE` or `BEGIN EXCLUSIVE`.
+    ///
+    /// The default, [`Deferred`](SqliteTransactionBehavior::Deferred), takes the lock on the
+    /// first write, which may then fail with `SQLITE_BUSY` in the middle of the transaction
+    /// under write contention; [`Immediate`](SqliteTransactionBehavior::Immediate) waits for the
+    /// lock when the transaction begins instead. Savepoints are part of their transaction, so
+    /// this doesn't apply to them. A transaction can override it with
+    /// [`TransactionOptions::sqlite_behavior`](crate::transaction::TransactionOptions::sqlite_behavior).
+    ///
+    /// Also settable with the `transaction_behavior` URL parameter, e.g.
+    /// `sqlite://data.db?transaction_behavior=immediate`.
+    pub fn transaction_behavior(mut self, behavior: SqliteTransactionBehavior) -> Self {
+        self.transaction_behavior = behavior;
+        self
+    }
+
+    /// Sets the size of the WAL, in pages, after which a transaction is followed by a
+    /// [passive checkpoint](crate::sqlite::SqliteCheckpointMode::Passive).
+    ///
+    /// The default is 1000 pages. Zero turns automatic checkpoints off, so that the WAL is only
+    /// checkpointed with [`SqliteConnection::checkpoint()`] or when the last connection to the
+    /// database is closed.
+    ///
+    /// See [`PRAGMA wal_autocheckpoint`](https://www.sqlite.org/pragma.html#pragma_wal_autocheckpoint)
+    /// for details.
+    ///
+    /// [`SqliteConnection::checkpoint()`]: crate::sqlite::SqliteConnection::checkpoint
+    pub fn wal_autocheckpoint(self, pages: u32) -> Self {
+        self.pragma("wal_autocheckpoint", pages.to_string())
+    }
+
     /// Sets the [synchronous](https://www.sqlite.org/pragma.html#pragma_synchronous) setting for the database connection.
     ///
     /// The default synchronous settings is FULL. However, if durability is not a concern,
@@ -301,20 +479,84 @@ impl SqliteConnectOptions {
         self.pragma("page_size", page_size.to_string())
     }
 
-    /// Sets custom initial pragma for the database connection.
+    /// Sets a [PRAGMA](https://www.sqlite.org/pragma.html) to execute when a connection is
+    /// opened, e.g. `pragma("temp_store", "MEMORY")`.
+    ///
+    /// PRAGMAs are executed in a fixed order: those which must come first, such as `key`,
+    /// `page_size`, `locking_mode` and `journal_mode`, are executed in that order whenever they
+    /// are set, by their dedicated methods or by this one. They are followed by `foreign_keys`,
+    /// `synchronous`, `auto_vacuum`, `temp_store`, `cache_size` and `mmap_size`, then by the
+    /// other PRAGMAs in the order they were first set. Setting a PRAGMA again replaces its value
+    /// in place. See [`pragmas()`](Self::pragmas) for the resulting list.
+    ///
+    /// SQLite ignores an invalid value for most PRAGMAs, so the values of those it is known to
+    /// take are checked when the connection is opened, which fails with an
+    /// [`Error::Configuration`](crate::error::Error::Configuration) if one is invalid. The values
+    /// of other PRAGMAs are executed as they are, so they must not come from untrusted input.
+    ///
+    /// In a connection URL, a PRAGMA can be set with the `pragma=<key>=<value>` query parameter,
+    /// with the `=` escaped as `%3D`, or with the PRAGMA as a parameter of its own for
+    /// `journal_mode`, `synchronous`, `foreign_keys`, `locking_mode`, `auto_vacuum`, `page_size`,
+    /// `temp_store`, `cache_size` and `mmap_size`, e.g.
+    /// `sqlite://data.db?journal_mode=WAL&pragma=busy_timeout%3D1000`.
     pub fn pragma<K, V>(mut self, key: K, value: V) -> Self
     where
         K: Into<Cow<'static, str>>,
         V: Into<Cow<'static, str>>,
     {
-        self.pragmas.insert(key.into(), Some(value.into()));
+        let mut key = key.into();
+
+        // PRAGMA names are case-insensitive, but the keys of the map are not
+        if key.bytes().any(|b| b.is_ascii_uppercase()) {
+            key = Cow::Owned(key.to_ascii_lowercase());
+        }
+
+        self.pragmas.insert(key, Some(value.into()));
         self
     }
 
+    /// The PRAGMAs executed when a connection is opened, as `(key, value)` pairs in the order
+    /// they are executed.
+    ///
+    /// ```rust
+    /// # use sqlx_core::sqlite::{SqliteConnectOptions, SqliteJournalMode};
+    /// let options = SqliteConnectOptions::new()
+    ///     .pragma("cache_size", "-64000")
+    ///     .journal_mode(SqliteJournalMode::Wal);
+    ///
+    /// assert_eq!(
+    ///     options.pragmas().collect::<Vec<_>>(),
+    ///     [
+    ///         ("journal_mode", "WAL"),
+    ///         ("foreign_keys", "ON"),
+    ///         ("cache_size", "-64000"),
+    ///     ]
+    /// );
+    /// ```
+    pub fn pragmas(&self) -> impl Iterator<Item = (&str, &str)> + '_ {
+        self.pragmas
+            .iter()
+            .filter_map(|(key, value)| Some((&**key, value.as_deref()?)))
+    }
+
     /// Add a custom collation for comparing strings in SQL.
     ///
     /// If a collation with the same name already exists, it will be replaced.
     ///
+    /// The collation is created on every connection opened with these options, before any
+    /// statement is executed, so it is available to all connections of a pool. Collations belong
+    /// to the connection rather than to a database file, so they also apply to databases added
+    /// with `ATTACH DATABASE`. The function is shared by all of these connections, and is only
+    /// dropped once the options and every connection using it are.
+    ///
+    /// ```rust,no_run
+    /// # use sqlx_core::sqlite::SqliteConnectOptions;
+    /// // ORDER BY name COLLATE unicode_ci
+    /// let options = SqliteConnectOptions::new().collation("unicode_ci", |a, b| {
+    ///     a.to_lowercase().cmp(&b.to_lowercase())
+    /// });
+    /// ```
+    ///
     /// See [`sqlite3_create_collation()`](https://www.sqlite.org/c3ref/create_collation.html) for details.
     ///
     /// Note this excerpt:
@@ -343,9 +585,9 @@ impl SqliteConnectOptions {
     /// to improve performance but can produce incorrect query results or errors if the file
     /// _does_ change.
     ///
-    /// Note that this is different from the `SQLITE_OPEN_READONLY` flag set by
-    /// [`.read_only()`][Self::read_only], though the documentation suggests that this
-    /// does _imply_ `SQLITE_OPEN_READONLY`.
+    /// This implies the `SQLITE_OPEN_READONLY` flag set by [`.read_only()`][Self::read_only],
+    /// so statements which write to the database fail, but not those which only write to the
+    /// `temp` schema.
     ///
     /// See [`sqlite3_open`](https://www.sqlite.org/capi3ref.html#sqlite3_open) (subheading
     /// "URI Filenames") for details.
@@ -354,6 +596,38 @@ impl SqliteConnectOptions {
         self
     }
 
+    /// Sets [`PRAGMA query_only`](https://www.sqlite.org/pragma.html#pragma_query_only), which
+    /// prevents writing to the database with a connection opened for writing.
+    ///
+    /// Unlike with [`.read_only()`][Self::read_only], the connection still takes part in the
+    /// locking of the database and WAL, so a database in a read-only directory can't be opened
+    /// this way. Statements which write to the database fail as with `read_only`, and so do those
+    /// which write to the `temp` schema.
+    pub fn query_only(mut self, query_only: bool) -> Self {
+        self.query_only = query_only;
+        self.pragma("query_only", if query_only { "ON" } else { "OFF" })
+    }
+
+    /// Limit what the database file, and the statements executed on it, can do, e.g. with
+    /// [`SqliteHardening::strict()`] to open files which can't be trusted.
+    ///
+    /// The settings are applied as soon as the connection is opened, before any statement is
+    /// executed, including the PRAGMAs of these options. Statements which go against them fail
+    /// with an [`Error::Database`](crate::error::Error::Database), as does reading a page found
+    /// to be corrupt with [`cell_size_check`](SqliteHardening::cell_size_check).
+    ///
+    /// ```rust,no_run
+    /// # use sqlx_core::sqlite::{SqliteConnectOptions, SqliteHardening};
+    /// let options = SqliteConnectOptions::new()
+    ///     .filename("upload.db")
+    ///     .read_only(true)
+    ///     .hardening(SqliteHardening::strict());
+    /// ```
+    pub fn hardening(mut self, hardening: SqliteHardening) -> Self {
+        self.hardening = hardening;
+        self
+    }
+
     /// Sets the [threading mode](https://www.sqlite.org/threadsafe.html) for the database connection.
     ///
     /// The default setting is `false` corresponding to using `OPEN_NOMUTEX`.
@@ -414,4 +688,125 @@ impl SqliteConnectOptions {
         self.vfs = Some(vfs_name.into());
         self
     }
+
+    /// Load an [extension](https://www.sqlite.org/loadext.html) when a connection is opened,
+    /// e.g. one of [`sqlean`](https://github.com/nalgeon/sqlean) or `mod_spatialite`.
+    ///
+    /// Extensions are loaded in the order they are added, before any statement is executed and
+    /// before the [`after_connect`](crate::pool::PoolOptions::after_connect) callback of a pool.
+    /// The `extension_name` is a path to the shared library, which SQLite also tries with the
+    /// extension of the platform (`.so`, `.dylib` or `.dll`) appended. The entry point is derived
+    /// from the file name; see [`extension_with_entrypoint`](Self::extension_with_entrypoint) to
+    /// choose it.
+    ///
+    /// Loading is only enabled for this, through the C API, so the `load_extension()` SQL function
+    /// remains unavailable to queries. If an extension can't be loaded, the connection fails with
+    /// the error given by SQLite, which includes the reason of the OS, e.g. the file not being
+    /// found.
+    ///
+    /// Note that an extension runs native code within the process, so only load ones you trust.
+    ///
+    /// ```rust,no_run
+    /// # use sqlx_core::sqlite::SqliteConnectOptions;
+    /// let options = SqliteConnectOptions::new()
+    ///     .extension("vsv")
+    ///     .extension("mod_spatialite");
+    /// ```
+    pub fn extension(mut self, extension_name: impl Into<Cow<'static, str>>) -> Self {
+        self.extensions.insert(extension_name.into(), None);
+        self
+    }
+
+    /// Load an extension with a custom entry point, the name of the function that initializes it.
+    ///
+    /// See [`extension`](Self::extension) for details.
+    pub fn extension_with_entrypoint(
+        mut self,
+        extension_name: impl Into<Cow<'static, str>>,
+        entry_point: impl Into<Cow<'static, str>>,
+    ) -> Self {
+        self.extensions
+            .insert(extension_name.into(), Some(entry_point.into()));
+        self
+    }
+
+    /// Call `handler` every `n_ops` virtual machine instructions while a statement is executed,
+    /// interrupting the statement when it returns [`ControlFlow::Break`].
+    ///
+    /// This lets a long query be given up on, e.g. once it ran for too long or a flag is set.
+    /// The statement then fails with an error of the kind
+    /// [`ErrorKind::Interrupted`](crate::error::ErrorKind::Interrupted).
+    ///
+    /// The handler runs on the worker thread of the connection, in the middle of the statement,
+    /// so it must be quick and must not use the connection. See
+    /// [`sqlite3_progress_handler()`](https://www.sqlite.org/c3ref/progress_handler.html) for
+    /// details.
+    ///
+    /// ```rust,no_run
+    /// # use sqlx_core::sqlite::SqliteConnectOptions;
+    /// use std::ops::ControlFlow;
+    /// use std::sync::atomic::{AtomicBool, Ordering};
+    /// use std::sync::Arc;
+    ///
+    /// let cancelled = Arc::new(AtomicBool::new(false));
+    ///
+    /// let options = SqliteConnectOptions::new().progress_handler(1000, {
+    ///     let cancelled = cancelled.clone();
+    ///
+    ///     move || match cancelled.load(Ordering::Relaxed) {
+    ///         true => ControlFlow::Break(()),
+    ///         false => ControlFlow::Continue(()),
+    ///     }
+    /// });
+    /// ```
+    pub fn progress_handler<F>(mut self, n_ops: u32, handler: F) -> Self
+    where
+        F: Fn() -> ControlFlow<()> + Send + Sync + 'static,
+    {
+        self.progress_handler = Some((n_ops, Arc::new(DebugFn(handler))));
+        self
+    }
+
+    /// Interrupt a statement once its results can no longer be received, as the future or stream
+    /// of the query was dropped, e.g. by a timeout.
+    ///
+    /// By default, the worker thread of the connection runs the statement to completion, which
+    /// for a long query keeps the connection busy long after it was given up on. When enabled,
+    /// this is checked every 1000 virtual machine instructions, or as often as the
+    /// [`progress_handler`](Self::progress_handler) is called if one is set.
+    pub fn interrupt_on_drop(mut self, interrupt: bool) -> Self {
+        self.interrupt_on_drop = interrupt;
+        self
+    }
+
+    /// [Attach](https://www.sqlite.org/lang_attach.html) the database file at `path` under the
+    /// schema `name` when a connection is opened, so that its tables can be queried as
+    /// `name.table`.
+    ///
+    /// Databases are attached in the order they are added, after the main database is set up
+    /// with the PRAGMAs of these options and before any other statement is executed, including
+    /// the [`after_connect`](crate::pool::PoolOptions::after_connect) callback of a pool. If a
+    /// database can't be attached, the connection fails. Attaching another database under the
+    /// same name replaces it.
+    ///
+    /// An attached database is opened like the main one, e.g. read-only with
+    /// [`read_only`](Self::read_only) and created if missing with
+    /// [`create_if_missing`](Self::create_if_missing). The `page_size`, `auto_vacuum`,
+    /// `journal_mode` and `synchronous` PRAGMAs set on these options apply to attached databases
+    /// as well.
+    ///
+    /// In a connection URL, a database can be attached with the `attach.<name>=<path>` query
+    /// parameter, e.g. `sqlite://data.db?attach.archive=archive.db`.
+    ///
+    /// ```rust,no_run
+    /// # use sqlx_core::sqlite::SqliteConnectOptions;
+    /// let options = SqliteConnectOptions::new()
+    ///     .filename("data.db")
+    ///     .attach("archive", "archive.db");
+    /// ```
+    pub fn attach(mut self, name: impl Into<Cow<'static, str>>, path: impl AsRef<Path>) -> Self {
+        self.attached
+            .insert(name.into(), Cow::Owned(path.as_ref().to_owned()));
+        self
+    }
 }
@@ -1,15 +1,16 @@
+use crate::connection::{path_in_url, unrepresentable_in_url};
 use crate::error::Error;
+use crate::sqlite::options::in_memory_filename;
+use crate::sqlite::options::pragma::{check_pragma, URL_PRAGMAS};
 use crate::sqlite::SqliteConnectOptions;
-use percent_encoding::percent_decode_str;
+use percent_encoding::{percent_decode_str, utf8_percent_encode, AsciiSet, CONTROLS};
 use std::borrow::Cow;
-use std::path::{Path, PathBuf};
+use std::path::Path;
 use std::str::FromStr;
-use std::sync::atomic::{AtomicUsize, Ordering};
+use url::Url;
 
 // https://www.sqlite.org/uri.html
 
-static IN_MEMORY_DB_SEQ: AtomicUsize = AtomicUsize::new(0);
-
 impl FromStr for SqliteConnectOptions {
     type Err = Error;
 
@@ -28,8 +29,7 @@ impl FromStr for SqliteConnectOptions {
         if database == ":memory:" {
             options.in_memory = true;
             options.shared_cache = true;
-            let seqno = IN_MEMORY_DB_SEQ.fetch_add(1, Ordering::Relaxed);
-            options.filename = Cow::Owned(PathBuf::from(format!("file:sqlx-in-memory-{}", seqno)));
+            options.filename = Cow::Owned(in_memory_filename("sqlx-in-memory"));
         } else {
             // % decode to allow for `?` or `#` in the filename
             options.filename = Cow::Owned(
@@ -110,6 +110,30 @@ impl FromStr for SqliteConnectOptions {
 
                     "vfs" => options.vfs = Some(Cow::Owned(value.into_owned())),
 
+                    "transaction_behavior" => options.transaction_behavior = value.parse()?,
+
+                    // `pragma=cache_size%3D-64000`
+                    "pragma" => {
+                        let (key, value) = value.split_once('=').ok_or_else(|| {
+                            Error::Configuration(
+                                format!("expected `<key>=<value>` for `pragma`, got {:?}", value)
+                                    .into(),
+                            )
+                        })?;
+
+                        check_pragma(key, value)?;
+                        options = options.pragma(key.to_owned(), value.to_owned());
+                    }
+
+                    _ if URL_PRAGMAS.contains(&&*key) => {
+                        check_pragma(&key, &value)?;
+                        options = options.pragma(key.into_owned(), value.into_owned());
+                    }
+
+                    _ if key.starts_with("attach.") => {
+                        options = options.attach(key["attach.".len()..].to_owned(), &*value);
+                    }
+
                     _ => {
                         return Err(Error::Configuration(
                             format!(
@@ -127,6 +151,158 @@ impl FromStr for SqliteConnectOptions {
     }
 }
 
+// the characters of a file name to escape in a URL, where it is decoded as a whole
+const FILENAME: &AsciiSet = &CONTROLS
+    .add(b' ')
+    .add(b'"')
+    .add(b'#')
+    .add(b'%')
+    .add(b':')
+    .add(b'<')
+    .add(b'>')
+    .add(b'?')
+    .add(b'`')
+    .add(b'{')
+    .add(b'}');
+
+// the PRAGMAs of SQLCipher which set the encryption key
+const KEY_PRAGMAS: &[&str] = &["key", "hexkey", "rekey", "hexrekey"];
+
+/// Whether the value of the PRAGMA `key` is a secret, which `Debug` and redacted URLs hide.
+pub(crate) fn is_key_pragma(key: &str) -> bool {
+    let name = key.rsplit('.').next().unwrap_or(key);
+    KEY_PRAGMAS.iter().any(|k| k.eq_ignore_ascii_case(name))
+}
+
+impl SqliteConnectOptions {
+    /// The URL which parses to these options, see [`ConnectOptions::to_url_lossless`].
+    ///
+    /// [`ConnectOptions::to_url_lossless`]: crate::connection::ConnectOptions::to_url_lossless
+    pub(crate) fn build_url(&self) -> Result<Url, Error> {
+        let default = SqliteConnectOptions::new();
+
+        if self.in_memory || self.filename == default.filename {
+            return Err(unrepresentable_in_url(
+                "filename",
+                "as an in-memory database only exists in the process which opened it",
+            ));
+        }
+
+        let unsupported = [
+            (
+                "statement_cache_capacity",
+                self.statement_cache_capacity != default.statement_cache_capacity,
+            ),
+            ("busy_timeout", self.busy_timeout != default.busy_timeout),
+            ("query_only", self.query_only),
+            ("hardening", self.hardening != default.hardening),
+            ("serialized", self.serialized),
+            (
+                "command_buffer_size",
+                self.command_channel_size != default.command_channel_size,
+            ),
+            (
+                "row_buffer_size",
+                self.row_channel_size != default.row_channel_size,
+            ),
+            ("collation", !self.collations.is_empty()),
+            ("extension", !self.extensions.is_empty()),
+            ("progress_handler", self.progress_handler.is_some()),
+            ("interrupt_on_drop", self.interrupt_on_drop),
+        ];
+
+        if let Some((option, _)) = unsupported.iter().find(|(_, set)| *set) {
+            return Err(unrepresentable_in_url(
+                option,
+                "as a SQLite URL has no parameter for it",
+            ));
+        }
+
+        let filename = path_in_url("filename", &self.filename)?;
+        let mut filename = utf8_percent_encode(filename, FILENAME).to_string();
+
+        // `sqlite://` is a prefix of the URL, rather than part of the file name
+        if filename.starts_with("//") {
+            filename.replace_range(..1, "%2F");
+        }
+
+        let prefix = format!("sqlite:{}", filename);
+        let mut url = Url::parse(&prefix).map_err(Error::config)?;
+
+        // e.g. `/a/../b.db`, which is normalized to `/b.db`
+        if url.as_str() != prefix {
+            return Err(unrepresentable_in_url(
+                "filename",
+                "as it would be normalized by the URL",
+            ));
+        }
+
+        let mut params: Vec<(Cow<'_, str>, Cow<'_, str>)> = Vec::new();
+
+        if self.read_only {
+            params.push(("mode".into(), "ro".into()));
+        }
+
+        if self.create_if_missing {
+            params.push(("mode".into(), "rwc".into()));
+        }
+
+        if self.shared_cache {
+            params.push(("cache".into(), "shared".into()));
+        }
+
+        if self.immutable {
+            params.push(("immutable".into(), "true".into()));
+        }
+
+        if let Some(vfs) = &self.vfs {
+            params.push(("vfs".into(), (**vfs).into()));
+        }
+
+        if self.transaction_behavior != default.transaction_behavior {
+            params.push((
+                "transaction_behavior".into(),
+                self.transaction_behavior.as_str().into(),
+            ));
+        }
+
+        for (key, value) in self.pragmas() {
+            if default.pragmas.get(key).and_then(|v| v.as_deref()) == Some(value) {
+                continue;
+            }
+
+            if key.contains('=') {
+                return Err(unrepresentable_in_url(
+                    "pragma",
+                    "as the name of the PRAGMA has a `=`",
+                ));
+            }
+
+            check_pragma(key, value)?;
+
+            if URL_PRAGMAS.contains(&key) {
+                params.push((key.into(), value.into()));
+            } else {
+                params.push(("pragma".into(), format!("{}={}", key, value).into()));
+            }
+        }
+
+        for (name, path) in &self.attached {
+            params.push((
+                format!("attach.{}", name).into(),
+                path_in_url("attach", path)?.into(),
+            ));
+        }
+
+        if !params.is_empty() {
+            url.query_pairs_mut()
+                .extend_pairs(params.iter().map(|(key, value)| (&**key, &**value)));
+        }
+
+        Ok(url)
+    }
+}
+
 #[test]
 fn test_parse_in_memory() -> Result<(), Error> {
     let options: SqliteConnectOptions = "sqlite::memory:".parse()?;
@@ -165,3 +341,174 @@ fn test_parse_shared_in_memory() -> Result<(), Error> {
 
     Ok(())
 }
+
+#[test]
+fn test_parse_transaction_behavior() -> Result<(), Error> {
+    use crate::sqlite::SqliteTransactionBehavior;
+
+    let options: SqliteConnectOptions = "sqlite://a.db?transaction_behavior=IMMEDIATE".parse()?;
+    assert_eq!(
+        options.transaction_behavior,
+        SqliteTransactionBehavior::Immediate
+    );
+
+    assert!("sqlite://a.db?transaction_behavior=eventually"
+        .parse::<SqliteConnectOptions>()
+        .is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_parse_attach() -> Result<(), Error> {
+    let options: SqliteConnectOptions =
+        "sqlite://a.db?attach.archive=archive%20old.db&attach.b=b.db".parse()?;
+
+    let attached: Vec<_> = options
+        .attached
+        .iter()
+        .map(|(name, path)| (&**name, path.to_string_lossy().into_owned()))
+        .collect();
+
+    assert_eq!(
+        attached,
+        [
+            ("archive", "archive old.db".to_owned()),
+            ("b", "b.db".to_owned())
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_parse_pragmas() -> Result<(), Error> {
+    let options: SqliteConnectOptions =
+        "sqlite://a.db?pragma=busy_timeout%3D1000&journal_mode=wal&pragma=Temp_Store%3DMEMORY"
+            .parse()?;
+
+    assert_eq!(
+        options.pragmas().collect::<Vec<_>>(),
+        [
+            ("journal_mode", "wal"),
+            ("foreign_keys", "ON"),
+            ("temp_store", "MEMORY"),
+            ("busy_timeout", "1000")
+        ]
+    );
+
+    assert!("sqlite://a.db?synchronous=sometimes"
+        .parse::<SqliteConnectOptions>()
+        .is_err());
+    assert!("sqlite://a.db?pragma=cache_size"
+        .parse::<SqliteConnectOptions>()
+        .is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_url_round_trip() -> Result<(), Error> {
+    use crate::connection::ConnectOptions;
+    use crate::sqlite::{SqliteJournalMode, SqliteSynchronous, SqliteTransactionBehavior};
+
+    let filenames = [
+        "a.db",
+        "/var/lib/app/a.db",
+        "with space.db",
+        "what?#100%.db",
+        "c:/a.db",
+        "//x.db",
+        "sqlite:x.db",
+        "café.db",
+    ];
+
+    for (i, filename) in filenames.iter().enumerate() {
+        for flags in 0..32 {
+            let flag = |n: u32| flags & (1 << n) != 0;
+
+            let mut options = SqliteConnectOptions::new()
+                .filename(filename)
+                .read_only(flag(0))
+                .create_if_missing(flag(1))
+                .shared_cache(flag(2))
+                .immutable(flag(3));
+
+            if flag(4) {
+                options = options
+                    .vfs("unix-dotfile")
+                    .transaction_behavior(SqliteTransactionBehavior::Immediate)
+                    .journal_mode(SqliteJournalMode::Delete)
+                    .synchronous(SqliteSynchronous::Normal)
+                    .pragma("key", "it's a secret")
+                    .pragma("cache_size", "-64000")
+                    .attach("archive", "archive old.db");
+            }
+
+            if i % 2 == 1 {
+                options = options.foreign_keys(false).page_size(8192);
+            }
+
+            let url = options.to_url_lossless()?;
+            let parsed: SqliteConnectOptions = url.as_str().parse()?;
+
+            assert_eq!(parsed.filename, options.filename, "{}", url);
+            assert_eq!(
+                parsed.pragmas().collect::<Vec<_>>(),
+                options.pragmas().collect::<Vec<_>>(),
+                "{}",
+                url
+            );
+            assert_eq!(format!("{:?}", parsed), format!("{:?}", options), "{}", url);
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_url_unrepresentable_options() {
+    use crate::connection::ConnectOptions;
+    use std::time::Duration;
+
+    for options in [
+        SqliteConnectOptions::new(),
+        SqliteConnectOptions::new().shared_memory("a"),
+        SqliteConnectOptions::new()
+            .filename("a.db")
+            .busy_timeout(Duration::from_secs(1)),
+        SqliteConnectOptions::new().filename("/a/../b.db"),
+        SqliteConnectOptions::new()
+            .filename("a.db")
+            .pragma("a=b", "c"),
+    ] {
+        assert!(
+            matches!(options.to_url_lossless(), Err(Error::Configuration(_))),
+            "{:?}",
+            options
+        );
+    }
+}
+
+#[test]
+fn test_url_redacted_key() -> Result<(), Error> {
+    use crate::connection::ConnectOptions;
+
+    let options = SqliteConnectOptions::new()
+        .filename("a.db")
+        .pragma("key", "hunter2")
+        .pragma("hexrekey", "2dd29ca851e7b56e");
+
+    let debug = format!("{:?}", options);
+    assert!(!debug.contains("hunter2"), "{}", debug);
+    assert!(!debug.contains("2dd29ca851e7b56e"), "{}", debug);
+
+    assert!(options.to_url_lossless()?.as_str().contains("hunter2"));
+
+    let url = options.to_url_redacted()?;
+    assert!(!url.as_str().contains("hunter2"), "{}", url);
+    assert!(!url.as_str().contains("2dd29ca851e7b56e"), "{}", url);
+    assert!(url.as_str().contains("****"), "{}", url);
+
+    Ok(())
+}
@@ -0,0 +1,89 @@
+use crate::error::Error;
+use crate::sqlite::{SqliteAutoVacuum, SqliteJournalMode, SqliteLockingMode, SqliteSynchronous};
+
+// https://www.sqlite.org/pragma.html
+
+// PRAGMAs which can be set in a connection URL by their own query parameter
+pub(crate) const URL_PRAGMAS: &[&str] = &[
+    "journal_mode",
+    "synchronous",
+    "foreign_keys",
+    "locking_mode",
+    "auto_vacuum",
+    "page_size",
+    "temp_store",
+    "cache_size",
+    "mmap_size",
+];
+
+/// Check the value of a PRAGMA SQLite is known to take, as SQLite ignores an invalid value
+/// rather than failing, or sets it to zero.
+///
+/// The values of other PRAGMAs are passed through as they are.
+pub(crate) fn check_pragma(key: &str, value: &str) -> Result<(), Error> {
+    // the PRAGMA may be set on another schema than `main`, e.g. `archive.journal_mode`
+    let name = key.rsplit('.').next().unwrap_or(key).to_ascii_lowercase();
+
+    let valid = match &*name {
+        "journal_mode" => value.parse::<SqliteJournalMode>().is_ok(),
+        "locking_mode" => value.parse::<SqliteLockingMode>().is_ok(),
+
+        "synchronous" => {
+            value.parse::<SqliteSynchronous>().is_ok() || matches!(value, "0" | "1" | "2" | "3")
+        }
+
+        "auto_vacuum" => {
+            value.parse::<SqliteAutoVacuum>().is_ok() || matches!(value, "0" | "1" | "2")
+        }
+
+        "temp_store" => {
+            matches!(
+                &*value.to_ascii_lowercase(),
+                "default" | "file" | "memory" | "0" | "1" | "2"
+            )
+        }
+
+        "foreign_keys"
+        | "query_only"
+        | "recursive_triggers"
+        | "cell_size_check"
+        | "trusted_schema"
+        | "case_sensitive_like"
+        | "ignore_check_constraints"
+        | "defer_foreign_keys"
+        | "reverse_unordered_selects" => matches!(
+            &*value.to_ascii_lowercase(),
+            "on" | "off" | "true" | "false" | "yes" | "no" | "1" | "0"
+        ),
+
+        "page_size" | "cache_size" | "mmap_size" | "wal_autocheckpoint" | "journal_size_limit"
+        | "busy_timeout" | "user_version" | "application_id" | "analysis_limit" | "threads"
+        | "soft_heap_limit" | "hard_heap_limit" => value.parse::<i64>().is_ok(),
+
+        _ => true,
+    };
+
+    if valid {
+        Ok(())
+    } else {
+        Err(Error::Configuration(
+            format!("invalid value {:?} for PRAGMA `{}`", value, key).into(),
+        ))
+    }
+}
+
+#[test]
+fn test_check_pragma() {
+    assert!(check_pragma("journal_mode", "wal").is_ok());
+    assert!(check_pragma("archive.journal_mode", "WAL").is_ok());
+    assert!(check_pragma("synchronous", "2").is_ok());
+    assert!(check_pragma("foreign_keys", "Yes").is_ok());
+    assert!(check_pragma("cache_size", "-2000").is_ok());
+    assert!(check_pragma("optimize", "0x10002").is_ok());
+
+    assert!(check_pragma("journal_mode", "wall").is_err());
+    assert!(check_pragma("synchronous", "4").is_err());
+    assert!(check_pragma("foreign_keys", "maybe").is_err());
+    assert!(check_pragma("temp_store", "disk").is_err());
+    assert!(check_pragma("mmap_size", "1GB").is_err());
+}
@@ -0,0 +1,56 @@
+use crate::error::Error;
+use std::str::FromStr;
+
+/// Refer to [SQLite documentation] for the meaning of the behaviors of a transaction.
+///
+/// A `Deferred` transaction takes the write lock of the database when it first writes to it, so
+/// that it may fail with `SQLITE_BUSY` in the middle if another connection holds the lock, or
+/// wrote to a database in WAL mode since the transaction started reading. An `Immediate` or
+/// `Exclusive` transaction takes the lock when it begins, waiting for it up to the
+/// [busy timeout](super::SqliteConnectOptions::busy_timeout), so that only `BEGIN` may fail.
+///
+/// [SQLite documentation]: https://www.sqlite.org/lang_transaction.html#deferred_immediate_and_exclusive_transactions
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SqliteTransactionBehavior {
+    #[default]
+    Deferred,
+    Immediate,
+    Exclusive,
+}
+
+impl SqliteTransactionBehavior {
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            SqliteTransactionBehavior::Deferred => "deferred",
+            SqliteTransactionBehavior::Immediate => "immediate",
+            SqliteTransactionBehavior::Exclusive => "exclusive",
+        }
+    }
+
+    pub(crate) fn begin_sql(&self) -> &'static str {
+        match self {
+            // the same as `BEGIN DEFERRED`
+            SqliteTransactionBehavior::Deferred => "BEGIN",
+            SqliteTransactionBehavior::Immediate => "BEGIN IMMEDIATE",
+            SqliteTransactionBehavior::Exclusive => "BEGIN EXCLUSIVE",
+        }
+    }
+}
+
+impl FromStr for SqliteTransactionBehavior {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Error> {
+        Ok(match &*s.to_ascii_lowercase() {
+            "deferred" => SqliteTransactionBehavior::Deferred,
+            "immediate" => SqliteTransactionBehavior::Immediate,
+            "exclusive" => SqliteTransactionBehavior::Exclusive,
+
+            _ => {
+                return Err(Error::Configuration(
+                    format!("unknown value {:?} for `transaction_behavior`", s).into(),
+                ));
+            }
+        })
+    }
+}
@@ -16,6 +16,12 @@ impl SqliteQueryResult {
     }
 }
 
+impl crate::dyn_connection::DynQueryResult for SqliteQueryResult {
+    fn rows_affected(&self) -> u64 {
+        self.changes
+    }
+}
+
 impl Extend<SqliteQueryResult> for SqliteQueryResult {
     fn extend<T: IntoIterator<Item = SqliteQueryResult>>(&mut self, iter: T) {
         for elem in iter {
@@ -5,7 +5,7 @@ use std::sync::Arc;
 use crate::HashMap;
 
 use crate::column::ColumnIndex;
-use crate::error::Error;
+use crate::error::{Error, ErrorContext};
 use crate::ext::ustr::UStr;
 use crate::row::Row;
 use crate::sqlite::statement::StatementHandle;
@@ -16,6 +16,7 @@ pub struct SqliteRow {
     pub(crate) values: Box<[SqliteValue]>,
     pub(crate) columns: Arc<Vec<SqliteColumn>>,
     pub(crate) column_names: Arc<HashMap<UStr, usize>>,
+    pub(crate) context: Option<Arc<ErrorContext>>,
 }
 
 impl crate::row::private_row::Sealed for SqliteRow {}
@@ -34,6 +35,7 @@ impl SqliteRow {
         statement: &StatementHandle,
         columns: &Arc<Vec<SqliteColumn>>,
         column_names: &Arc<HashMap<UStr, usize>>,
+        context: &Option<Arc<ErrorContext>>,
     ) -> Self {
         let size = statement.column_count();
         let mut values = Vec::with_capacity(size);
@@ -50,6 +52,7 @@ impl SqliteRow {
             values: values.into_boxed_slice(),
             columns: Arc::clone(columns),
             column_names: Arc::clone(column_names),
+            context: context.clone(),
         }
     }
 }
@@ -68,6 +71,10 @@ impl Row for SqliteRow {
         let index = index.index(self)?;
         Ok(SqliteValueRef::value(&self.values[index]))
     }
+
+    fn error_context(&self) -> Option<&Arc<ErrorContext>> {
+        self.context.as_ref()
+    }
 }
 
 impl ColumnIndex<SqliteRow> for &'_ str {
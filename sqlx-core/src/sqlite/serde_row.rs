@@ -0,0 +1,48 @@
+use crate::column::Column;
+use crate::decode::Decode;
+use crate::error::{BoxDynError, Error};
+use crate::row::Row;
+use crate::serde_row::{column_error, ColumnValue, ColumnValues};
+use crate::sqlite::type_info::DataType;
+use crate::sqlite::{Sqlite, SqliteRow, SqliteValueRef};
+use crate::value::ValueRef;
+
+impl ColumnValues for SqliteRow {
+    fn column_value(&self, index: usize) -> Result<ColumnValue, Error> {
+        let column = &self.columns()[index];
+        let value = self.try_get_raw(index)?;
+
+        if value.is_null() {
+            return Ok(ColumnValue::Null);
+        }
+
+        // the type of a value is its storage class, except that an integer in a column declared
+        // as `BOOLEAN` is a boolean
+        let declared_bool = column.type_info().0 == DataType::Bool;
+
+        convert(value, declared_bool).map_err(|source| column_error(column.name(), source))
+    }
+}
+
+fn convert(value: SqliteValueRef<'_>, declared_bool: bool) -> Result<ColumnValue, BoxDynError> {
+    let data_type = value.type_info().0;
+
+    Ok(match data_type {
+        DataType::Int | DataType::Int64 | DataType::Bool if declared_bool => {
+            ColumnValue::Bool(decode(value)?)
+        }
+
+        DataType::Int | DataType::Int64 | DataType::Bool => ColumnValue::I64(decode(value)?),
+        DataType::Float | DataType::Numeric => ColumnValue::F64(decode(value)?),
+        DataType::Blob => ColumnValue::Bytes(decode(value)?),
+        DataType::Null => ColumnValue::Null,
+
+        DataType::Text | DataType::Date | DataType::Time | DataType::Datetime => {
+            ColumnValue::String(decode(value)?)
+        }
+    })
+}
+
+fn decode<'r, T: Decode<'r, Sqlite>>(value: SqliteValueRef<'r>) -> Result<T, BoxDynError> {
+    T::decode(value)
+}
@@ -21,6 +21,21 @@ pub struct SqliteStatement<'q> {
     pub(crate) parameters: usize,
     pub(crate) columns: Arc<Vec<SqliteColumn>>,
     pub(crate) column_names: Arc<HashMap<UStr, usize>>,
+    pub(crate) read_only: bool,
+    // the connection which prepared the statement, unless the statement was detached from it
+    pub(crate) connection_id: Option<u64>,
+}
+
+impl SqliteStatement<'_> {
+    /// Whether the statement doesn't write to the database, as reported by
+    /// [`sqlite3_stmt_readonly()`](https://www.sqlite.org/c3ref/stmt_readonly.html).
+    ///
+    /// For a query made of several statements, this is whether none of them writes. Note that
+    /// transaction statements such as `BEGIN` count as read-only, as they don't write by
+    /// themselves.
+    pub fn is_read_only(&self) -> bool {
+        self.read_only
+    }
 }
 
 impl<'q> Statement<'q> for SqliteStatement<'q> {
@@ -32,6 +47,8 @@ impl<'q> Statement<'q> for SqliteStatement<'q> {
             parameters: self.parameters,
             columns: Arc::clone(&self.columns),
             column_names: Arc::clone(&self.column_names),
+            read_only: self.read_only,
+            connection_id: self.connection_id,
         }
     }
 
@@ -39,6 +56,10 @@ impl<'q> Statement<'q> for SqliteStatement<'q> {
         &self.sql
     }
 
+    fn detach(&mut self) {
+        self.connection_id = None;
+    }
+
     fn parameters(&self) -> Option<Either<&[SqliteTypeInfo], usize>> {
         Some(Either::Right(self.parameters))
     }
@@ -1,6 +1,9 @@
 use crate::error::Error;
+use crate::executor::Executor;
 use crate::pool::PoolOptions;
-use crate::sqlite::{Sqlite, SqliteConnectOptions};
+use crate::sqlite::{
+    Sqlite, SqliteConnectOptions, SqliteConnection, SqliteJournalMode, SqliteSynchronous,
+};
 use crate::testing::{FixtureSnapshot, TestArgs, TestContext, TestSupport};
 use futures_core::future::BoxFuture;
 use std::path::{Path, PathBuf};
@@ -16,7 +19,18 @@ impl TestSupport for Sqlite {
     }
 
     fn cleanup_test(db_name: &str) -> BoxFuture<'_, Result<(), Error>> {
-        Box::pin(async move { Ok(sqlx_rt::fs::remove_file(db_name).await?) })
+        Box::pin(async move { Ok(remove_db_files(db_name).await?) })
+    }
+
+    fn execute_without_transaction<'c>(
+        conn: &'c mut SqliteConnection,
+        sql: &'c str,
+    ) -> BoxFuture<'c, Result<(), Error>> {
+        // each statement is run in a transaction of its own
+        Box::pin(async move {
+            let _ = conn.execute(sql).await?;
+            Ok(())
+        })
     }
 
     fn cleanup_test_dbs() -> BoxFuture<'static, Result<Option<usize>, Error>> {
@@ -42,23 +56,39 @@ async fn test_context(args: &TestArgs) -> Result<TestContext<Sqlite>, Error> {
             .expect("failed to create folders");
     }
 
-    if Path::exists(db_path.as_ref()) {
-        sqlx_rt::fs::remove_file(&db_path)
-            .await
-            .expect("failed to remove database from previous test run");
-    }
+    remove_db_files(&db_path)
+        .await
+        .expect("failed to remove database from previous test run");
 
     Ok(TestContext {
         connect_opts: SqliteConnectOptions::new()
             .filename(&db_path)
-            .create_if_missing(true),
+            .create_if_missing(true)
+            // Let the connections of the test pool read while another one writes. Syncing less
+            // often is safe in WAL mode, and the database of a test doesn't need to be durable.
+            .journal_mode(SqliteJournalMode::Wal)
+            .synchronous(SqliteSynchronous::Normal),
         // This doesn't really matter for SQLite as the databases are independent of each other.
         // The main limitation is going to be the number of concurrent running tests.
         pool_opts: PoolOptions::new().max_connections(1000),
         db_name: db_path,
+        guard: None,
     })
 }
 
+/// Remove a database, with the WAL files which are left behind if it wasn't closed.
+async fn remove_db_files(db_path: &str) -> std::io::Result<()> {
+    for suffix in ["", "-wal", "-shm"] {
+        let path = format!("{}{}", db_path, suffix);
+
+        if Path::exists(path.as_ref()) {
+            sqlx_rt::fs::remove_file(&path).await?;
+        }
+    }
+
+    Ok(())
+}
+
 fn convert_path(test_path: &str) -> String {
     let mut path = PathBuf::from(BASE_PATH);
 
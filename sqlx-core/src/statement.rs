@@ -14,8 +14,14 @@ use either::Either;
 /// look at that cache in-between the statement being prepared and it being executed. This contains
 /// the expected columns to be returned and the expected parameter types (if available).
 ///
-/// Statements can be re-used with any connection and on first-use it will be re-prepared and
-/// cached within the connection.
+/// A statement prepared by a connection refers to the statement it prepared on the server, so
+/// executing it with [`query`](Self::query) on that connection binds the arguments to the
+/// prepared statement directly, without looking it up in the cache, unless it was evicted from the
+/// cache or closed with [`Connection::close_statement`] in the meantime, in which case it's
+/// prepared again. Executing it on another connection returns
+/// [`Error::StatementConnectionMismatch`], unless it's [detached](Self::detach).
+///
+/// [`Connection::close_statement`]: crate::connection::Connection::close_statement
 pub trait Statement<'q>: Send + Sync {
     type Database: Database;
 
@@ -26,6 +32,13 @@ pub trait Statement<'q>: Send + Sync {
     /// Get the original SQL text used to create this statement.
     fn sql(&self) -> &str;
 
+    /// Detaches this statement from the connection which prepared it, so that it can be executed
+    /// on any connection, which prepares it again and caches it on first use.
+    ///
+    /// The statements prepared by a [`Pool`](crate::pool::Pool) are already detached, as well as
+    /// those of drivers which don't refer to the statements on the server.
+    fn detach(&mut self) {}
+
     /// Get the expected parameters for this statement.
     ///
     /// The information returned depends on what is available from the driver. SQLite can
@@ -88,6 +101,18 @@ pub trait Statement<'q>: Send + Sync {
         A: IntoArguments<'s, Self::Database>;
 }
 
+/// A statement cached by a connection, as listed by
+/// [`Connection::prepared_statements`](crate::connection::Connection::prepared_statements).
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct PreparedStatementInfo {
+    /// The SQL of the statement.
+    pub sql: String,
+
+    /// The number of times the statement was executed again since it was prepared.
+    pub hits: u64,
+}
+
 macro_rules! impl_statement_query {
     ($A:ty) => {
         #[inline]
@@ -1,7 +1,11 @@
+use std::any::Any;
+use std::fmt::Display;
 use std::future::Future;
+use std::panic::{self, AssertUnwindSafe};
 use std::time::Duration;
 
 use futures_core::future::BoxFuture;
+use futures_util::FutureExt;
 
 pub use fixtures::FixtureSnapshot;
 use sqlx_rt::test_block_on;
@@ -10,7 +14,7 @@ use crate::connection::{ConnectOptions, Connection};
 use crate::database::Database;
 use crate::error::Error;
 use crate::executor::Executor;
-use crate::migrate::{Migrate, Migrator};
+use crate::migrate::{Migrate, Migrator, NO_TRANSACTION};
 use crate::pool::{Pool, PoolConnection, PoolOptions};
 
 mod fixtures;
@@ -29,6 +33,13 @@ pub trait TestSupport: Database {
 
     fn cleanup_test(db_name: &str) -> BoxFuture<'_, Result<(), Error>>;
 
+    /// Execute the statements of a fixture which starts with [`NO_TRANSACTION`], each on its own
+    /// rather than in a transaction.
+    fn execute_without_transaction<'c>(
+        conn: &'c mut Self::Connection,
+        sql: &'c str,
+    ) -> BoxFuture<'c, Result<(), Error>>;
+
     /// Cleanup any test databases that are no longer in-use.
     ///
     /// Returns a count of the databases deleted, if possible.
@@ -49,12 +60,25 @@ pub struct TestFixture {
     pub contents: &'static str,
 }
 
+impl TestFixture {
+    /// Whether the fixture starts with [`NO_TRANSACTION`], like a migration, to be applied
+    /// outside of a transaction.
+    pub fn no_tx(&self) -> bool {
+        self.contents.lines().next().map(str::trim_end) == Some(NO_TRANSACTION)
+    }
+}
+
 pub struct TestArgs {
     pub test_path: &'static str,
     pub migrator: Option<&'static Migrator>,
     pub fixtures: &'static [TestFixture],
+    // a `Teardown<DB>`, for the database of the test
+    teardown: Option<Box<dyn Any + Send + Sync>>,
 }
 
+type Teardown<DB> =
+    Box<dyn FnOnce(Pool<DB>) -> BoxFuture<'static, Result<(), String>> + Send + Sync>;
+
 pub trait TestFn {
     type Output;
 
@@ -69,6 +93,10 @@ pub struct TestContext<DB: Database> {
     pub pool_opts: PoolOptions<DB>,
     pub connect_opts: <DB::Connection as Connection>::Options,
     pub db_name: String,
+    /// A connection holding a lock which marks the test database as in use until it's cleaned
+    /// up, so that the test processes running alongside, e.g. with `cargo nextest`, don't clean
+    /// it up as a database left behind by a previous run.
+    pub(crate) guard: Option<DB::Connection>,
 }
 
 impl<DB, Fut> TestFn for fn(Pool<DB>) -> Fut
@@ -135,6 +163,10 @@ where
             args.fixtures.is_empty(),
             "fixtures cannot be applied for a bare function"
         );
+        assert!(
+            args.teardown.is_none(),
+            "a teardown cannot be run for a bare function"
+        );
         test_block_on(self())
     }
 }
@@ -145,6 +177,7 @@ impl TestArgs {
             test_path,
             migrator: None,
             fixtures: &[],
+            teardown: None,
         }
     }
 
@@ -155,6 +188,40 @@ impl TestArgs {
     pub fn fixtures(&mut self, fixtures: &'static [TestFixture]) {
         self.fixtures = fixtures;
     }
+
+    /// Run `teardown` with a `Pool` of the test database once the test finished, even if it
+    /// failed or panicked, before the database is deleted.
+    pub fn teardown<DB, F, Fut, E>(&mut self, teardown: F)
+    where
+        DB: Database,
+        F: FnOnce(Pool<DB>) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<(), E>> + Send + 'static,
+        E: Display,
+    {
+        let teardown: Teardown<DB> = Box::new(move |pool| {
+            Box::pin(async move { teardown(pool).await.map_err(|e| e.to_string()) })
+        });
+
+        self.teardown = Some(Box::new(teardown));
+    }
+
+    fn take_teardown<DB: Database>(&mut self) -> Option<Teardown<DB>> {
+        let teardown =
+            self.teardown.take()?.downcast::<Teardown<DB>>().expect(
+                "the teardown of a test must take a `Pool` of the same database as the test",
+            );
+
+        Some(*teardown)
+    }
+}
+
+/// Whether to keep the databases of failed tests, rather than deleting them, as set by the
+/// `SQLX_TEST_KEEP_ON_FAILURE` environment variable.
+fn keep_on_failure() -> bool {
+    matches!(
+        dotenvy::var("SQLX_TEST_KEEP_ON_FAILURE").as_deref(),
+        Ok("1" | "true")
+    )
 }
 
 impl TestTermination for () {
@@ -199,7 +266,7 @@ where
     })
 }
 
-fn run_test<DB, F, Fut>(args: TestArgs, test_fn: F) -> Fut::Output
+fn run_test<DB, F, Fut>(mut args: TestArgs, test_fn: F) -> Fut::Output
 where
     DB: TestSupport,
     DB::Connection: Migrate,
@@ -209,28 +276,85 @@ where
     Fut::Output: TestTermination,
 {
     test_block_on(async move {
+        let teardown = args.take_teardown::<DB>();
+
         let test_context = DB::test_context(&args)
             .await
             .expect("failed to connect to setup test database");
 
         setup_test_db::<DB>(&test_context.connect_opts, &args).await;
 
-        let res = test_fn(test_context.pool_opts, test_context.connect_opts).await;
+        let res = AssertUnwindSafe(test_fn(
+            test_context.pool_opts,
+            test_context.connect_opts.clone(),
+        ))
+        .catch_unwind()
+        .await;
+
+        let success = matches!(&res, Ok(res) if res.is_success());
+
+        // the database is deleted even if the teardown panics
+        let teardown_res = match teardown {
+            Some(teardown) => {
+                AssertUnwindSafe(run_teardown(teardown, test_context.connect_opts))
+                    .catch_unwind()
+                    .await
+            }
+            None => Ok(Ok(())),
+        };
 
-        if res.is_success() {
+        if success || !keep_on_failure() {
             if let Err(e) = DB::cleanup_test(&test_context.db_name).await {
                 eprintln!(
                     "failed to delete database {:?}: {}",
                     test_context.db_name, e
                 );
             }
+        } else {
+            eprintln!(
+                "keeping database {:?} of failed test {}",
+                test_context.db_name, args.test_path
+            );
+        }
+
+        if let Some(guard) = test_context.guard {
+            let _ = guard.close().await;
+        }
+
+        // a panic of the teardown takes precedence over one of the test, which was already
+        // reported by the panic hook
+        let teardown_res = teardown_res.unwrap_or_else(|payload| panic::resume_unwind(payload));
+        let res = res.unwrap_or_else(|payload| panic::resume_unwind(payload));
+
+        if let Err(e) = teardown_res {
+            if success {
+                panic!("failed to tear down test {}: {}", args.test_path, e);
+            }
+
+            eprintln!("failed to tear down test {}: {}", args.test_path, e);
         }
 
         res
     })
 }
 
-async fn setup_test_db<DB: Database>(
+async fn run_teardown<DB: Database>(
+    teardown: Teardown<DB>,
+    connect_opts: <DB::Connection as Connection>::Options,
+) -> Result<(), String> {
+    let pool = PoolOptions::<DB>::new()
+        .max_connections(1)
+        .connect_with(connect_opts)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let res = teardown(pool.clone()).await;
+    pool.close().await;
+
+    res
+}
+
+async fn setup_test_db<DB: TestSupport>(
     copts: &<DB::Connection as Connection>::Options,
     args: &TestArgs,
 ) where
@@ -249,14 +373,27 @@ async fn setup_test_db<DB: Database>(
             .expect("failed to apply migrations");
     }
 
+    // fixtures are applied in the order they were given, each in a transaction of its own
     for fixture in args.fixtures {
-        (&mut conn)
-            .execute(fixture.contents)
-            .await
-            .unwrap_or_else(|e| panic!("failed to apply test fixture {:?}: {:?}", fixture.path, e));
+        let res = if fixture.no_tx() {
+            DB::execute_without_transaction(&mut conn, fixture.contents).await
+        } else {
+            apply_fixture(&mut conn, fixture.contents).await
+        };
+
+        res.unwrap_or_else(|e| panic!("failed to apply test fixture {:?}: {:?}", fixture.path, e));
     }
 
     conn.close()
         .await
         .expect("failed to close setup connection");
 }
+
+async fn apply_fixture<DB: Database>(conn: &mut DB::Connection, sql: &str) -> Result<(), Error>
+where
+    for<'c> &'c mut DB::Connection: Executor<'c, Database = DB>,
+{
+    let mut tx = conn.begin().await?;
+    let _ = (&mut *tx).execute(sql).await?;
+    tx.commit().await
+}
@@ -20,6 +20,22 @@ pub trait TransactionManager {
         conn: &mut <Self::Database as Database>::Connection,
     ) -> BoxFuture<'_, Result<(), Error>>;
 
+    /// Begin a new transaction with the given options, or establish a savepoint within the
+    /// active transaction if the options are the default ones.
+    ///
+    /// Databases which don't support any of the options return an error for all but the default
+    /// ones.
+    fn begin_with(
+        conn: &mut <Self::Database as Database>::Connection,
+        options: TransactionOptions,
+    ) -> BoxFuture<'_, Result<(), Error>> {
+        if options.is_default() {
+            Self::begin(conn)
+        } else {
+            Box::pin(async move { Err(options.unsupported("this database")) })
+        }
+    }
+
     /// Commit the active transaction or release the most recent savepoint.
     fn commit(
         conn: &mut <Self::Database as Database>::Connection,
@@ -34,6 +50,137 @@ pub trait TransactionManager {
     fn start_rollback(conn: &mut <Self::Database as Database>::Connection);
 }
 
+/// The isolation level of a transaction, which is a level of the SQL standard.
+///
+/// A database may run a transaction at a stricter level than the requested one, which the SQL
+/// standard allows; e.g. PostgreSQL runs `ReadUncommitted` as `ReadCommitted`, and every SQLite
+/// transaction is serializable.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IsolationLevel {
+    ReadUncommitted,
+    ReadCommitted,
+    RepeatableRead,
+    Serializable,
+}
+
+impl IsolationLevel {
+    #[allow(dead_code)]
+    pub(crate) fn sql(self) -> &'static str {
+        match self {
+            IsolationLevel::ReadUncommitted => "READ UNCOMMITTED",
+            IsolationLevel::ReadCommitted => "READ COMMITTED",
+            IsolationLevel::RepeatableRead => "REPEATABLE READ",
+            IsolationLevel::Serializable => "SERIALIZABLE",
+        }
+    }
+}
+
+/// Options for beginning a transaction with [`Connection::begin_with`] or [`Pool::begin_with`].
+///
+/// The options apply to a transaction as a whole, so they can't be given for a savepoint; the
+/// savepoint is part of the transaction it is established in. Beginning a savepoint with options
+/// other than the default ones returns an error, as does beginning a transaction with an option
+/// which the database doesn't support.
+///
+/// | Option                | PostgreSQL | MySQL | SQLite                         | MSSQL |
+/// |-----------------------|------------|-------|--------------------------------|-------|
+/// | `isolation_level`     | Yes        | Yes   | Yes (always serializable)      | No    |
+/// | `read_only`           | Yes        | Yes   | No                             | No    |
+/// | `sqlite_behavior`     | No         | No    | Yes                            | No    |
+///
+/// [`Connection::begin_with`]: crate::connection::Connection::begin_with()
+/// [`Pool::begin_with`]: crate::pool::Pool::begin_with()
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TransactionOptions {
+    isolation_level: Option<IsolationLevel>,
+    read_only: bool,
+    #[cfg(feature = "sqlite")]
+    sqlite_behavior: Option<crate::sqlite::SqliteTransactionBehavior>,
+}
+
+impl TransactionOptions {
+    /// Creates the default options, which begin a transaction like [`Connection::begin`].
+    ///
+    /// [`Connection::begin`]: crate::connection::Connection::begin()
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the isolation level of the transaction.
+    ///
+    /// Defaults to the default level of the connection.
+    pub fn isolation_level(mut self, level: IsolationLevel) -> Self {
+        self.isolation_level = Some(level);
+        self
+    }
+
+    /// Sets whether the transaction is read-only, in which case writing to a table fails.
+    ///
+    /// Defaults to `false`.
+    pub fn read_only(mut self, read_only: bool) -> Self {
+        self.read_only = read_only;
+        self
+    }
+
+    /// Sets how a SQLite transaction takes the lock of the database, overriding the
+    /// [`transaction_behavior`] of the connection.
+    ///
+    /// [`transaction_behavior`]: crate::sqlite::SqliteConnectOptions::transaction_behavior
+    #[cfg(feature = "sqlite")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "sqlite")))]
+    pub fn sqlite_behavior(mut self, behavior: crate::sqlite::SqliteTransactionBehavior) -> Self {
+        self.sqlite_behavior = Some(behavior);
+        self
+    }
+
+    /// Returns the isolation level of the transaction, if one was set.
+    pub fn get_isolation_level(&self) -> Option<IsolationLevel> {
+        self.isolation_level
+    }
+
+    /// Returns whether the transaction is read-only.
+    pub fn is_read_only(&self) -> bool {
+        self.read_only
+    }
+
+    /// Returns how the SQLite transaction takes the lock of the database, if it was set.
+    #[cfg(feature = "sqlite")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "sqlite")))]
+    pub fn get_sqlite_behavior(&self) -> Option<crate::sqlite::SqliteTransactionBehavior> {
+        self.sqlite_behavior
+    }
+
+    /// Returns whether these are the default options.
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Returns an error if these options can't be given for the savepoint at `depth`, i.e. a
+    /// savepoint within an active transaction.
+    #[allow(dead_code)]
+    pub(crate) fn check_savepoint(&self, depth: usize) -> Result<(), Error> {
+        if depth > 0 && !self.is_default() {
+            return Err(Error::Configuration(
+                "transaction options can't be given for a savepoint within an active transaction"
+                    .into(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Returns the error for these options not being supported by `database`.
+    pub(crate) fn unsupported(&self, database: &str) -> Error {
+        Error::Configuration(
+            format!(
+                "the transaction options {:?} are not supported by {}",
+                self, database
+            )
+            .into(),
+        )
+    }
+}
+
 /// An in-progress database transaction or savepoint.
 ///
 /// A transaction starts with a call to [`Pool::begin`] or [`Connection::begin`].
@@ -77,6 +224,31 @@ where
         })
     }
 
+    pub(crate) fn begin_with(
+        conn: impl Into<MaybePoolConnection<'c, DB>>,
+        options: TransactionOptions,
+    ) -> BoxFuture<'c, Result<Self, Error>> {
+        let mut conn = conn.into();
+
+        Box::pin(async move {
+            DB::TransactionManager::begin_with(&mut conn, options).await?;
+
+            Ok(Self {
+                connection: conn,
+                open: true,
+            })
+        })
+    }
+
+    /// Wrap a transaction which `conn` has already begun, in a way specific to its database.
+    #[allow(dead_code)]
+    pub(crate) fn begun(conn: impl Into<MaybePoolConnection<'c, DB>>) -> Self {
+        Self {
+            connection: conn.into(),
+            open: true,
+        }
+    }
+
     /// Commits this transaction or savepoint.
     pub async fn commit(mut self) -> Result<(), Error> {
         DB::TransactionManager::commit(&mut self.connection).await?;
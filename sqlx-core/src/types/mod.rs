@@ -32,6 +32,9 @@ pub mod git2;
 #[cfg_attr(docsrs, doc(cfg(feature = "json")))]
 mod json;
 
+#[doc(no_inline)]
+pub use bytes::Bytes;
+
 #[cfg(feature = "uuid")]
 #[cfg_attr(docsrs, doc(cfg(feature = "uuid")))]
 #[doc(no_inline)]
@@ -42,7 +45,7 @@ pub use uuid::{self, Uuid};
 pub mod chrono {
     #[doc(no_inline)]
     pub use chrono::{
-        DateTime, FixedOffset, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc,
+        DateTime, Duration, FixedOffset, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc,
     };
 }
 
@@ -55,7 +58,7 @@ pub use bit_vec::BitVec;
 #[cfg_attr(docsrs, doc(cfg(feature = "time")))]
 pub mod time {
     #[doc(no_inline)]
-    pub use time::{Date, OffsetDateTime, PrimitiveDateTime, Time, UtcOffset};
+    pub use time::{Date, Duration, OffsetDateTime, PrimitiveDateTime, Time, UtcOffset};
 }
 
 #[cfg(feature = "bigdecimal")]
@@ -164,6 +167,13 @@ pub use json::{Json, JsonRawValue, JsonValue};
 /// }
 /// ```
 ///
+/// ##### Attributes
+///
+/// * `#[sqlx(no_pg_array)]` on enum (without `#[repr(_)]`) or struct definition: don't implement
+///   `PgHasArrayType`, which otherwise resolves the array type
+///   of `<SQL type name>` so that `Vec<T>` and `&[T]` can be used for its arrays. Use this to
+///   provide your own implementation. Affects Postgres only.
+///
 pub trait Type<DB: Database> {
     /// Returns the canonical SQL type for this Rust type.
     ///
@@ -1,3 +1,5 @@
+#[cfg(any(feature = "offline", feature = "migrate"))]
+use once_cell::sync::OnceCell;
 use proc_macro2::Span;
 use std::env;
 use std::path::{Path, PathBuf};
@@ -35,3 +37,35 @@ pub(crate) fn resolve_path(path: impl AsRef<Path>, err_span: Span) -> syn::Resul
 
     Ok(base_dir_path.join(path))
 }
+
+/// Get the root of the workspace of the crate being built from `cargo metadata`, once.
+#[cfg(any(feature = "offline", feature = "migrate"))]
+pub(crate) fn workspace_root(manifest_dir: &Path) -> Result<PathBuf, String> {
+    static WORKSPACE_ROOT: OnceCell<PathBuf> = OnceCell::new();
+
+    WORKSPACE_ROOT
+        .get_or_try_init(|| {
+            use serde::Deserialize;
+            use std::process::Command;
+
+            let cargo = env::var("CARGO").map_err(|_| "`CARGO` must be set".to_owned())?;
+
+            let output = Command::new(&cargo)
+                .args(["metadata", "--format-version=1", "--no-deps"])
+                .current_dir(manifest_dir)
+                .env_remove("__CARGO_FIX_PLZ")
+                .output()
+                .map_err(|e| format!("could not fetch metadata: {}", e))?;
+
+            #[derive(Deserialize)]
+            struct CargoMetadata {
+                workspace_root: PathBuf,
+            }
+
+            let metadata: CargoMetadata = serde_json::from_slice(&output.stdout)
+                .map_err(|e| format!("invalid `cargo metadata` output: {}", e))?;
+
+            Ok(metadata.workspace_root)
+        })
+        .cloned()
+}
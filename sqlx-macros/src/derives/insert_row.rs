@@ -0,0 +1,119 @@
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use syn::{
+    parse_quote, punctuated::Punctuated, token::Comma, Data, DataStruct, DeriveInput, Field,
+    Fields, FieldsNamed, Lifetime, Stmt,
+};
+
+use super::{
+    attributes::{parse_child_attributes, parse_container_attributes},
+    rename_all,
+};
+
+pub fn expand_derive_insert_row(input: &DeriveInput) -> syn::Result<TokenStream> {
+    match &input.data {
+        Data::Struct(DataStruct {
+            fields: Fields::Named(FieldsNamed { named, .. }),
+            ..
+        }) => expand_derive_insert_row_struct(input, named),
+
+        Data::Struct(DataStruct {
+            fields: Fields::Unnamed(_),
+            ..
+        }) => Err(syn::Error::new_spanned(
+            input,
+            "tuple structs are not supported, as their fields don't name the columns",
+        )),
+
+        Data::Struct(DataStruct {
+            fields: Fields::Unit,
+            ..
+        }) => Err(syn::Error::new_spanned(
+            input,
+            "unit structs are not supported",
+        )),
+
+        Data::Enum(_) => Err(syn::Error::new_spanned(input, "enums are not supported")),
+
+        Data::Union(_) => Err(syn::Error::new_spanned(input, "unions are not supported")),
+    }
+}
+
+fn expand_derive_insert_row_struct(
+    input: &DeriveInput,
+    fields: &Punctuated<Field, Comma>,
+) -> syn::Result<TokenStream> {
+    let ident = &input.ident;
+
+    let generics = &input.generics;
+
+    let lifetime = Lifetime::new("'args", Span::call_site());
+
+    let (_, ty_generics, _) = generics.split_for_impl();
+
+    let mut generics = generics.clone();
+    generics
+        .params
+        .insert(0, parse_quote!(DB: ::sqlx::Database));
+    generics.params.insert(0, parse_quote!(#lifetime));
+
+    let predicates = &mut generics.make_where_clause().predicates;
+
+    let container_attributes = parse_container_attributes(&input.attrs)?;
+
+    let mut columns: Vec<Stmt> = Vec::with_capacity(fields.len());
+    let mut binds: Vec<Stmt> = Vec::with_capacity(fields.len());
+
+    for field in fields {
+        let id = field.ident.as_ref().unwrap();
+        let attributes = parse_child_attributes(&field.attrs)?;
+        let ty = &field.ty;
+
+        if attributes.flatten {
+            predicates.push(parse_quote!(#ty: ::sqlx::InsertRow<#lifetime, DB>));
+
+            columns.push(parse_quote!(
+                columns.extend(<#ty as ::sqlx::InsertRow<#lifetime, DB>>::columns());
+            ));
+            binds.push(parse_quote!(
+                <#ty as ::sqlx::InsertRow<#lifetime, DB>>::push_values(self.#id, values);
+            ));
+        } else {
+            predicates.push(parse_quote!(#ty: #lifetime));
+            predicates.push(parse_quote!(#ty: ::sqlx::encode::Encode<#lifetime, DB>));
+            predicates.push(parse_quote!(#ty: ::sqlx::types::Type<DB>));
+            predicates.push(parse_quote!(#ty: ::std::marker::Send));
+
+            let id_s = attributes
+                .rename
+                .unwrap_or_else(|| id.to_string().trim_start_matches("r#").to_owned());
+            let id_s = match container_attributes.rename_all {
+                Some(pattern) => rename_all(&id_s, pattern),
+                None => id_s,
+            };
+
+            columns.push(parse_quote!(columns.push(#id_s);));
+            binds.push(parse_quote!(values.push_bind(self.#id);));
+        }
+    }
+
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+
+    Ok(quote!(
+        #[automatically_derived]
+        impl #impl_generics ::sqlx::InsertRow<#lifetime, DB> for #ident #ty_generics #where_clause {
+            fn columns() -> ::std::vec::Vec<&'static ::std::primitive::str> {
+                let mut columns = ::std::vec::Vec::new();
+                #(#columns)*
+                columns
+            }
+
+            fn push_values(
+                self,
+                values: &mut ::sqlx::query_builder::Separated<'_, #lifetime, DB, &'static ::std::primitive::str>,
+            ) {
+                #(#binds)*
+            }
+        }
+    ))
+}
@@ -81,13 +81,21 @@ pub fn derive_from_row(input: TokenStream) -> TokenStream {
     }
 }
 
+#[proc_macro_derive(InsertRow, attributes(sqlx))]
+pub fn derive_insert_row(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as syn::DeriveInput);
+
+    match derives::expand_derive_insert_row(&input) {
+        Ok(ts) => ts.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
 #[cfg(feature = "migrate")]
 #[proc_macro]
 pub fn migrate(input: TokenStream) -> TokenStream {
-    use syn::LitStr;
-
-    let input = syn::parse_macro_input!(input as LitStr);
-    match migrate::expand_migrator_from_lit_dir(input) {
+    let input = syn::parse_macro_input!(input as migrate::MigrateInput);
+    match migrate::expand_migrator_from_input(input) {
         Ok(ts) => ts.into(),
         Err(e) => {
             if let Some(parse_err) = e.downcast_ref::<syn::Error>() {
@@ -1,11 +1,87 @@
+use proc_macro2::Ident;
 use proc_macro2::TokenStream;
 use quote::{quote, ToTokens, TokenStreamExt};
-use sha2::{Digest, Sha384};
-use sqlx_core::migrate::MigrationType;
-use std::fs;
-use std::path::Path;
+use sqlx_core::migrate::{parse_file_name, Migration, MigrationType, DEFAULT_MIGRATIONS_TABLE};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::{env, fs};
+use syn::parse::{Parse, ParseStream};
 use syn::LitStr;
 
+/// Input of `migrate!()`: the migrations directories, then `key = value` settings.
+pub struct MigrateInput {
+    dirs: Vec<MigrationsDir>,
+    table_name: Option<LitStr>,
+}
+
+/// A migrations directory given to `migrate!()`.
+enum MigrationsDir {
+    /// `"migrations"`, relative to `CARGO_MANIFEST_DIR`, or `"workspace:migrations"`, relative
+    /// to the root of the workspace.
+    Path(LitStr),
+
+    /// `env!("MIGRATIONS_DIR")`: a path as above, or an absolute one, read from the environment
+    /// variable at compile time.
+    Env(LitStr),
+}
+
+impl Parse for MigrationsDir {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        if input.peek(LitStr) {
+            return Ok(MigrationsDir::Path(input.parse()?));
+        }
+
+        let mac: syn::Macro = input.parse()?;
+
+        if !mac.path.is_ident("env") {
+            return Err(syn::Error::new_spanned(
+                mac.path,
+                "expected a migrations directory: a string literal or `env!(\"VAR\")`",
+            ));
+        }
+
+        Ok(MigrationsDir::Env(mac.parse_body()?))
+    }
+}
+
+impl Parse for MigrateInput {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut dirs = vec![input.parse::<MigrationsDir>()?];
+        let mut table_name = None;
+
+        while !input.is_empty() {
+            let _ = input.parse::<syn::token::Comma>()?;
+
+            if input.is_empty() {
+                break;
+            }
+
+            if input.peek(LitStr) || input.peek2(syn::token::Bang) {
+                if table_name.is_some() {
+                    return Err(input.error("migrations directories must come before settings"));
+                }
+
+                dirs.push(input.parse::<MigrationsDir>()?);
+                continue;
+            }
+
+            let key: Ident = input.parse()?;
+
+            let _ = input.parse::<syn::token::Eq>()?;
+
+            if key == "table_name" {
+                table_name = Some(input.parse::<LitStr>()?);
+            } else {
+                let message = format!("unexpected input key: {}", key);
+                return Err(syn::Error::new_spanned(key, message));
+            }
+        }
+
+        Ok(MigrateInput { dirs, table_name })
+    }
+}
+
 pub struct QuotedMigrationType(MigrationType);
 
 impl ToTokens for QuotedMigrationType {
@@ -16,6 +92,7 @@ impl ToTokens for QuotedMigrationType {
             MigrationType::ReversibleDown => {
                 quote! { ::sqlx::migrate::MigrationType::ReversibleDown }
             }
+            MigrationType::Repeatable => quote! { ::sqlx::migrate::MigrationType::Repeatable },
         };
         tokens.append_all(ts.into_iter());
     }
@@ -27,6 +104,7 @@ struct QuotedMigration {
     migration_type: QuotedMigrationType,
     path: String,
     checksum: Vec<u8>,
+    no_tx: bool,
 }
 
 impl ToTokens for QuotedMigration {
@@ -37,6 +115,7 @@ impl ToTokens for QuotedMigration {
             migration_type,
             path,
             checksum,
+            no_tx,
         } = &self;
 
         let ts = quote! {
@@ -49,6 +128,8 @@ impl ToTokens for QuotedMigration {
                 checksum: ::std::borrow::Cow::Borrowed(&[
                     #(#checksum),*
                 ]),
+                no_tx: #no_tx,
+                path: ::std::option::Option::Some(::std::borrow::Cow::Borrowed(#path)),
             }
         };
 
@@ -56,7 +137,103 @@ impl ToTokens for QuotedMigration {
     }
 }
 
-// mostly copied from sqlx-core/src/migrate/source.rs
+pub(crate) fn expand_migrator_from_input(input: MigrateInput) -> crate::Result<TokenStream> {
+    let paths = input
+        .dirs
+        .iter()
+        .map(MigrationsDir::resolve)
+        .collect::<syn::Result<Vec<_>>>()?;
+    let table_name = input.table_name.map(|table_name| table_name.value());
+
+    let migrator = expand_migrator(
+        &paths,
+        table_name.as_deref().unwrap_or(DEFAULT_MIGRATIONS_TABLE),
+    )?;
+
+    // `env!()` tells the compiler to rebuild when the variable changes
+    let vars = input.dirs.iter().filter_map(|dir| match dir {
+        MigrationsDir::Env(var) => Some(var),
+        MigrationsDir::Path(_) => None,
+    });
+
+    Ok(quote! {{
+        #(const _: &str = ::std::env!(#vars);)*
+        #migrator
+    }})
+}
+
+impl MigrationsDir {
+    fn resolve(&self) -> syn::Result<PathBuf> {
+        match self {
+            MigrationsDir::Path(dir) => resolve_dir_path(&dir.value(), dir.span(), None),
+
+            MigrationsDir::Env(var) => {
+                let dir = env::var(var.value()).map_err(|_| {
+                    syn::Error::new(
+                        var.span(),
+                        format!("environment variable `{}` is not set", var.value()),
+                    )
+                })?;
+
+                resolve_dir_path(&dir, var.span(), Some(&var.value()))
+            }
+        }
+    }
+}
+
+fn resolve_dir_path(
+    dir: &str,
+    err_span: proc_macro2::Span,
+    var: Option<&str>,
+) -> syn::Result<PathBuf> {
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").map_err(|_| {
+        syn::Error::new(
+            err_span,
+            "CARGO_MANIFEST_DIR is not set; please use Cargo to build",
+        )
+    })?;
+    let workspace_root = || {
+        crate::common::workspace_root(Path::new(&manifest_dir))
+            .map_err(|e| syn::Error::new(err_span, e))
+    };
+
+    let path = if let Some(workspace_dir) = dir.strip_prefix("workspace:") {
+        workspace_root()?.join(workspace_dir)
+    } else if var.is_some() && Path::new(dir).is_absolute() {
+        // the environment is of the current machine anyways
+        PathBuf::from(dir)
+    } else {
+        crate::common::resolve_path(dir, err_span)?
+    };
+
+    if path.is_dir() {
+        return Ok(path);
+    }
+
+    let mut message = format!("migrations directory not found; tried {}", path.display());
+
+    if let Some(var) = var {
+        message += &format!(" (`{}` from the environment variable `{}`)", dir, var);
+    }
+
+    // a path meant to be relative to the workspace, in a crate of the workspace
+    if !dir.starts_with("workspace:") && Path::new(dir).is_relative() {
+        if let Ok(root) = workspace_root() {
+            let workspace_path = root.join(dir);
+
+            if workspace_path.is_dir() && workspace_path != path {
+                message += &format!(
+                    "; `workspace:{}` would resolve to {}",
+                    dir.trim_start_matches("./"),
+                    workspace_path.display()
+                );
+            }
+        }
+    }
+
+    Err(syn::Error::new(err_span, message))
+}
+
 pub(crate) fn expand_migrator_from_lit_dir(dir: LitStr) -> crate::Result<TokenStream> {
     expand_migrator_from_dir(&dir.value(), dir.span())
 }
@@ -67,41 +244,106 @@ pub(crate) fn expand_migrator_from_dir(
 ) -> crate::Result<TokenStream> {
     let path = crate::common::resolve_path(dir, err_span)?;
 
-    expand_migrator(&path)
+    expand_migrator(&[path], DEFAULT_MIGRATIONS_TABLE)
 }
 
-pub(crate) fn expand_migrator(path: &Path) -> crate::Result<TokenStream> {
+pub(crate) fn expand_migrator(paths: &[PathBuf], table_name: &str) -> crate::Result<TokenStream> {
     let mut migrations = Vec::new();
 
-    for entry in fs::read_dir(&path)? {
-        let entry = entry?;
-        if !fs::metadata(entry.path())?.is_file() {
-            // not a file; ignore
+    for path in paths {
+        resolve_dir(path, &mut migrations)?;
+    }
+
+    // ensure that we are sorted by `VERSION ASC`, then repeatable migrations by `DESCRIPTION ASC`
+    migrations.sort_by(|a, b| {
+        let key = |m: &QuotedMigration| {
+            (
+                m.migration_type.0.is_repeatable(),
+                m.version,
+                m.description.clone(),
+            )
+        };
+
+        key(a).cmp(&key(b))
+    });
+
+    // the up and down migrations of a reversible migration share their version
+    let mut seen = HashMap::new();
+
+    for migration in &migrations {
+        if migration.migration_type.0.is_repeatable() {
             continue;
         }
 
-        let file_name = entry.file_name();
-        let file_name = file_name.to_string_lossy();
+        let key = (
+            migration.version,
+            migration.migration_type.0.is_down_migration(),
+        );
+
+        if let Some(other) = seen.insert(key, &migration.path) {
+            return Err(format!(
+                "migration {} is defined twice, by {} and {}",
+                migration.version, other, migration.path
+            )
+            .into());
+        }
+    }
 
-        let parts = file_name.splitn(2, '_').collect::<Vec<_>>();
+    #[cfg(any(sqlx_macros_unstable, procmacro2_semver_exempt))]
+    for path in paths {
+        let path = path.canonicalize()?;
+        let path = path.to_str().ok_or_else(|| {
+            format!(
+                "migration directory path cannot be represented as a string: {:?}",
+                path
+            )
+        })?;
+
+        proc_macro::tracked_path::path(path);
+    }
+
+    Ok(quote! {
+        ::sqlx::migrate::Migrator {
+            migrations: ::std::borrow::Cow::Borrowed(&[
+                #(#migrations),*
+            ]),
+            ignore_missing: false,
+            allow_out_of_order: false,
+            table_name: ::std::borrow::Cow::Borrowed(#table_name),
+            locking: ::sqlx::migrate::Locking::Wait { timeout: None },
+            applied_by: ::std::option::Option::None,
+            seeds: ::std::borrow::Cow::Borrowed(&[]),
+            after_run: ::std::borrow::Cow::Borrowed(&[]),
+        }
+    })
+}
 
-        if parts.len() != 2 || !parts[1].ends_with(".sql") {
-            // not of the format: <VERSION>_<DESCRIPTION>.sql; ignore
+fn resolve_dir(path: &Path, migrations: &mut Vec<QuotedMigration>) -> crate::Result<()> {
+    for entry in fs::read_dir(&path)? {
+        let entry = entry?;
+        if !fs::metadata(entry.path())?.is_file() {
+            // not a file; ignore
             continue;
         }
 
-        let version: i64 = parts[0].parse()?;
+        let file_name = entry.file_name();
 
-        let migration_type = MigrationType::from_filename(parts[1]);
-        // remove the `.sql` and replace `_` with ` `
-        let description = parts[1]
-            .trim_end_matches(migration_type.suffix())
-            .replace('_', " ")
-            .to_owned();
+        // resolved like the migrations of a `Migrator` at runtime
+        let (version, migration_type, description) =
+            match parse_file_name(&file_name.to_string_lossy()).map_err(|e| e as crate::Error)? {
+                Some(parsed) => parsed,
+                // not of the format: <VERSION>_<DESCRIPTION>.sql; ignore
+                None => continue,
+            };
 
         let sql = fs::read_to_string(&entry.path())?;
 
-        let checksum = Vec::from(Sha384::digest(sql.as_bytes()).as_slice());
+        let migration = Migration::new(
+            version,
+            Cow::Owned(description.clone()),
+            migration_type,
+            Cow::Owned(sql),
+        );
 
         // canonicalize the path so we can pass it to `include_str!()`
         let path = entry.path().canonicalize()?;
@@ -120,32 +362,10 @@ pub(crate) fn expand_migrator(path: &Path) -> crate::Result<TokenStream> {
             description,
             migration_type: QuotedMigrationType(migration_type),
             path,
-            checksum,
+            checksum: migration.checksum.into_owned(),
+            no_tx: migration.no_tx,
         })
     }
 
-    // ensure that we are sorted by `VERSION ASC`
-    migrations.sort_by_key(|m| m.version);
-
-    #[cfg(any(sqlx_macros_unstable, procmacro2_semver_exempt))]
-    {
-        let path = path.canonicalize()?;
-        let path = path.to_str().ok_or_else(|| {
-            format!(
-                "migration directory path cannot be represented as a string: {:?}",
-                path
-            )
-        })?;
-
-        proc_macro::tracked_path::path(path);
-    }
-
-    Ok(quote! {
-        ::sqlx::migrate::Migrator {
-            migrations: ::std::borrow::Cow::Borrowed(&[
-                #(#migrations),*
-            ]),
-            ignore_missing: false,
-        }
-    })
+    Ok(())
 }
@@ -76,11 +76,14 @@ pub fn quote_args<DB: DatabaseExt>(
                     Ok(quote_spanned!(expr.span() =>
                         // this shouldn't actually run
                         if false {
-                            use ::sqlx::ty_match::{WrapSameExt as _, MatchBorrowExt as _};
+                            use ::sqlx::ty_match::{OptionRefExt as _, WrapSameExt as _, MatchBorrowExt as _};
 
                             // evaluate the expression only once in case it contains moves
                             let expr = ::sqlx::ty_match::dupe_value(#name);
 
+                            // if `expr` is `&Option<T>`, get `Option<&T>`
+                            let expr = ::sqlx::ty_match::OptionRef::new(&expr).option_ref();
+
                             // if `expr` is `Option<T>`, get `Option<$ty>`, otherwise `$ty`
                             let ty_check = ::sqlx::ty_match::WrapSame::<#param_ty, _>::new(&expr).wrap_same();
 
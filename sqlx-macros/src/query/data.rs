@@ -1,5 +1,6 @@
+use crate::database::DatabaseExt;
 use sqlx_core::database::Database;
-use sqlx_core::describe::Describe;
+use sqlx_core::describe::{self, Describe};
 use sqlx_core::executor::Executor;
 
 #[cfg_attr(feature = "offline", derive(serde::Deserialize, serde::Serialize))]
@@ -19,12 +20,15 @@ pub struct QueryData<DB: Database> {
     pub(super) hash: String,
 }
 
-impl<DB: Database> QueryData<DB> {
+impl<DB: DatabaseExt> QueryData<DB> {
     pub async fn from_db(
         conn: impl Executor<'_, Database = DB>,
         query: &str,
     ) -> crate::Result<Self> {
-        Ok(Self::from_describe(query, conn.describe(query).await?))
+        Ok(Self::from_describe(
+            query,
+            describe::describe(conn, query).await?,
+        ))
     }
 
     pub fn from_describe(query: &str, describe: Describe<DB>) -> Self {
@@ -32,7 +36,7 @@ impl<DB: Database> QueryData<DB> {
             query: query.into(),
             describe,
             #[cfg(feature = "offline")]
-            hash: offline::hash_string(query),
+            hash: offline::hash_query(DB::NAME, query),
         }
     }
 }
@@ -61,24 +65,70 @@ pub mod offline {
         describe: serde_json::Value,
     }
 
+    /// `sqlx-data.json`: the query data of each database, by the name of its driver.
     #[derive(serde::Deserialize)]
-    struct OfflineData {
-        db: String,
+    #[serde(untagged)]
+    enum OfflineData {
+        /// The data of a single database, as saved before `sqlx-data.json` held the data of
+        /// several, whose queries are hashed without the name of the database.
+        Legacy {
+            db: String,
+            #[serde(flatten)]
+            data: DatabaseData,
+        },
+        Databases(BTreeMap<String, DatabaseData>),
+    }
+
+    #[derive(serde::Deserialize)]
+    struct DatabaseData {
+        // the cargo configuration the data was prepared with, checked by `cargo sqlx prepare`
+        #[serde(default, rename = "build")]
+        _build: Option<serde::de::IgnoredAny>,
         #[serde(flatten)]
         hash_to_query: BTreeMap<String, BaseQuery>,
     }
 
     impl OfflineData {
-        fn get_query_from_hash(&self, hash: &str) -> Option<DynQueryData> {
-            self.hash_to_query.get(hash).map(|base_query| DynQueryData {
-                db_name: self.db.clone(),
-                query: base_query.query.to_owned(),
-                describe: base_query.describe.to_owned(),
-                hash: hash.to_owned(),
+        fn get_query(&self, db_name: &str, query: &str) -> Option<DynQueryData> {
+            let (data, hashes) = match self {
+                OfflineData::Legacy { db, data } if db == db_name => {
+                    (data, vec![hash_string(query)])
+                }
+                OfflineData::Legacy { .. } => return None,
+                OfflineData::Databases(databases) => (
+                    databases.get(db_name)?,
+                    // the data prepared by sqlx 0.6.1 and earlier is hashed without normalizing
+                    // the query, which is accepted until the next release
+                    vec![
+                        hash_query(db_name, query),
+                        legacy_hash_query(db_name, query),
+                    ],
+                ),
+            };
+
+            hashes.into_iter().find_map(|hash| {
+                let base_query = data.hash_to_query.get(&hash)?;
+
+                Some(DynQueryData {
+                    db_name: db_name.to_owned(),
+                    query: base_query.query.to_owned(),
+                    describe: base_query.describe.to_owned(),
+                    hash,
+                })
             })
         }
     }
 
+    /// The databases whose features are enabled, which the query data is looked up for.
+    const DATABASES: &[&str] = &[
+        #[cfg(feature = "postgres")]
+        <sqlx_core::postgres::Postgres as DatabaseExt>::NAME,
+        #[cfg(feature = "mysql")]
+        <sqlx_core::mysql::MySql as DatabaseExt>::NAME,
+        #[cfg(feature = "sqlite")]
+        <sqlx_core::sqlite::Sqlite as DatabaseExt>::NAME,
+    ];
+
     #[derive(serde::Deserialize)]
     pub struct DynQueryData {
         #[serde(skip)]
@@ -91,8 +141,16 @@ pub mod offline {
 
     impl DynQueryData {
         /// Find and deserialize the data table for this query from a shared `sqlx-data.json`
-        /// file. The expected structure is a JSON map keyed by the SHA-256 hash of queries in hex.
-        pub fn from_data_file(path: impl AsRef<Path>, query: &str) -> crate::Result<Self> {
+        /// file. The expected structure is a JSON map keyed by the name of each database, of
+        /// maps keyed by the SHA-256 hash of the name of the database and the query, in hex.
+        ///
+        /// The data is looked up for each database whose feature is enabled, or only for
+        /// `db_name` if given, which must be unambiguous.
+        pub fn from_data_file(
+            path: impl AsRef<Path>,
+            query: &str,
+            db_name: Option<&str>,
+        ) -> crate::Result<Self> {
             let path = path.as_ref();
 
             let query_data = {
@@ -116,12 +174,45 @@ pub mod offline {
                     .get(path)
                     .expect("Missing data should have just been added");
 
-                let query_hash = hash_string(query);
-                let query_data = offline_data
-                    .get_query_from_hash(&query_hash)
-                    .ok_or_else(|| format!("failed to find data for query {}", query))?;
+                let databases = match db_name {
+                    Some(db_name) if DATABASES.contains(&db_name) => vec![db_name],
+                    Some(db_name) => {
+                        return Err(format!(
+                            "`SQLX_OFFLINE_DB` is {}, but the feature for that database was \
+                             not enabled; expected one of: {}",
+                            db_name,
+                            DATABASES.join(", ")
+                        )
+                        .into())
+                    }
+                    None => DATABASES.to_vec(),
+                };
+
+                let mut found: Vec<DynQueryData> = databases
+                    .into_iter()
+                    .filter_map(|db_name| offline_data.get_query(db_name, query))
+                    .collect();
+
+                let query_data = match found.len() {
+                    0 => return Err(format!("failed to find data for query {}", query).into()),
+                    1 => found.remove(0),
+                    _ => {
+                        return Err(format!(
+                            "found query data for {}; set `SQLX_OFFLINE_DB` to the database to \
+                             check the query against\nquery: {}",
+                            found
+                                .iter()
+                                .map(|data| &*data.db_name)
+                                .collect::<Vec<_>>()
+                                .join(" and "),
+                            query
+                        )
+                        .into())
+                    }
+                };
 
-                if query != query_data.query {
+                // the saved query may differ from `query` in whitespace only
+                if normalize_query(query) != normalize_query(&query_data.query) {
                     return Err(format!(
                         "hash collision for stored queries:\n{:?}\n{:?}",
                         query, query_data.query
@@ -193,6 +284,73 @@ pub mod offline {
         }
     }
 
+    /// The key of the data of `query` for the database `db_name`, which `cargo sqlx prepare`
+    /// computes the same way.
+    ///
+    /// The query is normalized first, so that reformatting it doesn't change its key.
+    pub fn hash_query(db_name: &str, query: &str) -> String {
+        hash_string(&format!("{}\n{}", db_name, normalize_query(query)))
+    }
+
+    /// The key of the data of `query` as computed by sqlx 0.6.1 and earlier, without normalizing
+    /// the query.
+    fn legacy_hash_query(db_name: &str, query: &str) -> String {
+        hash_string(&format!("{}\n{}", db_name, query))
+    }
+
+    /// Collapses each run of whitespace in `query` into a single space, except within string
+    /// literals, quoted identifiers and line comments, and the newline ending a line comment.
+    pub fn normalize_query(query: &str) -> String {
+        let mut normalized = String::with_capacity(query.len());
+        let mut rest = query.trim();
+        let mut in_line_comment = false;
+
+        while let Some(c) = rest.chars().next() {
+            let len = match c {
+                // a doubled quote escaping the quote ends the literal and starts another one
+                '\'' | '"' | '`' => rest[1..].find(c).map_or(rest.len(), |end| end + 2),
+                '-' if rest.starts_with("--") => {
+                    in_line_comment = true;
+                    rest.find('\n').unwrap_or(rest.len())
+                }
+                '$' => dollar_quoted_len(rest).unwrap_or(1),
+                c if c.is_whitespace() => {
+                    let len = rest
+                        .find(|c: char| !c.is_whitespace())
+                        .unwrap_or(rest.len());
+
+                    normalized.push(if in_line_comment { '\n' } else { ' ' });
+                    in_line_comment = false;
+                    rest = &rest[len..];
+                    continue;
+                }
+                c => c.len_utf8(),
+            };
+
+            normalized.push_str(&rest[..len]);
+            rest = &rest[len..];
+        }
+
+        normalized
+    }
+
+    /// The length of the dollar-quoted string of PostgreSQL which `query` starts with, e.g.
+    /// `$$text$$` or `$tag$text$tag$`, as opposed to a parameter like `$1`.
+    fn dollar_quoted_len(query: &str) -> Option<usize> {
+        let tag_end = query[1..].find(|c: char| !c.is_alphanumeric() && c != '_')? + 1;
+
+        if !query[tag_end..].starts_with('$')
+            || query[1..].starts_with(|c: char| c.is_ascii_digit())
+        {
+            return None;
+        }
+
+        let tag = &query[..=tag_end];
+        let end = query[tag.len()..].find(tag)?;
+
+        Some(tag.len() + end + tag.len())
+    }
+
     pub fn hash_string(query: &str) -> String {
         // picked `sha2` because it's already in the dependency tree for both MySQL and Postgres
         use sha2::{Digest, Sha256};
@@ -0,0 +1,112 @@
+//! The database URL the queries of a crate are checked against, so that the crates of a workspace
+//! can each be checked against their own database. It's looked up, in order, in:
+//!
+//! 1. the `SQLX_DATABASE_URL_<CRATE>` environment variable, e.g. `SQLX_DATABASE_URL_MY_APP` for
+//!    the package `my-app`;
+//! 2. the `.env` files from the manifest directory of the crate up to the root of its workspace,
+//!    for `SQLX_DATABASE_URL_<CRATE>` and then `DATABASE_URL`;
+//! 3. the `DATABASE_URL` environment variable.
+//!
+//! `cargo sqlx prepare` looks the URL of each crate up the same way.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub struct DatabaseUrl {
+    pub url: Option<String>,
+    /// The places looked in, in order, for errors.
+    pub consulted: Vec<String>,
+}
+
+impl DatabaseUrl {
+    /// Lists the places looked in, for errors.
+    pub fn describe_consulted(&self) -> String {
+        format!("looked for a database URL in {}", self.consulted.join(", "))
+    }
+}
+
+/// The environment variable of the database URL of `package`.
+pub fn package_var(package: &str) -> String {
+    format!(
+        "SQLX_DATABASE_URL_{}",
+        package.to_uppercase().replace('-', "_")
+    )
+}
+
+/// Looks the database URL of `package` up, with `env` reading the environment variables as they
+/// were before any `.env` file was loaded.
+pub fn resolve(
+    package: &str,
+    manifest_dir: &Path,
+    env: impl Fn(&str) -> Option<String>,
+) -> DatabaseUrl {
+    let package_var = package_var(package);
+    let mut consulted = Vec::new();
+
+    consulted.push(format!("`{}`", package_var));
+    if let Some(url) = env(&package_var) {
+        return DatabaseUrl {
+            url: Some(url),
+            consulted,
+        };
+    }
+
+    for dir in env_dirs(manifest_dir) {
+        let path = dir.join(".env");
+        consulted.push(format!("`{}`", path.display()));
+
+        if let Some(url) = read_env_file(&path, &package_var) {
+            return DatabaseUrl {
+                url: Some(url),
+                consulted,
+            };
+        }
+    }
+
+    consulted.push("`DATABASE_URL`".to_owned());
+    DatabaseUrl {
+        url: env("DATABASE_URL"),
+        consulted,
+    }
+}
+
+/// The directories from `manifest_dir` up to the root of its workspace, the first one whose
+/// `Cargo.toml` has a `[workspace]` table, or only `manifest_dir` if there's none.
+fn env_dirs(manifest_dir: &Path) -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+
+    for dir in manifest_dir.ancestors() {
+        dirs.push(dir.to_owned());
+
+        let is_workspace_root = fs::read_to_string(dir.join("Cargo.toml")).is_ok_and(|toml| {
+            toml.lines()
+                .any(|line| line.trim_start().starts_with("[workspace"))
+        });
+
+        if is_workspace_root {
+            return dirs;
+        }
+    }
+
+    vec![manifest_dir.to_owned()]
+}
+
+/// Reads the URL of `package_var`, or else `DATABASE_URL`, from the `.env` file at `path`.
+fn read_env_file(path: &Path, package_var: &str) -> Option<String> {
+    let vars: Vec<(String, String)> = dotenvy::from_path_iter(path)
+        .ok()?
+        .filter_map(Result::ok)
+        .collect();
+
+    // tell the compiler to watch the `.env` for changes
+    #[cfg(procmacro2_semver_exempt)]
+    if let Some(path) = path.to_str() {
+        proc_macro::tracked_path::path(path);
+    }
+
+    [package_var, "DATABASE_URL"].iter().find_map(|name| {
+        vars.iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, url)| url.clone())
+    })
+}
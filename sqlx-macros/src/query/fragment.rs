@@ -0,0 +1,185 @@
+//! The dynamic fragments of a query, e.g. `ORDER BY {order}` with
+//! `order = sort in ["name ASC", "name DESC"]`: the query is checked with every combination of
+//! their values, and the one given is selected at runtime.
+
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+use syn::{Expr, Ident, LitStr, Token};
+
+/// The most combinations of values a query is checked with.
+const MAX_VARIANTS: usize = 64;
+
+#[derive(Clone)]
+pub struct Fragment {
+    pub name: Ident,
+    expr: Expr,
+    values: Vec<LitStr>,
+}
+
+/// The query with a combination of the values of its fragments.
+pub struct Variant {
+    pub sql: String,
+    /// The values of the fragments, in order.
+    values: Vec<String>,
+}
+
+impl Variant {
+    /// Describes the values of the fragments of this variant, for errors.
+    pub fn describe(&self, fragments: &[Fragment]) -> String {
+        fragments
+            .iter()
+            .zip(&self.values)
+            .map(|(fragment, value)| format!("{} = {:?}", fragment.name, value))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+impl Parse for Fragment {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let name = input.parse()?;
+        input.parse::<Token![=]>()?;
+        let expr = input.parse()?;
+        input.parse::<Token![in]>()?;
+
+        let content;
+        let bracket = syn::bracketed!(content in input);
+        let values: Vec<LitStr> = Punctuated::<LitStr, Token![,]>::parse_terminated(&content)?
+            .into_iter()
+            .collect();
+
+        if values.is_empty() {
+            return Err(syn::Error::new(
+                bracket.span,
+                "expected at least one value of the fragment",
+            ));
+        }
+
+        for (i, value) in values.iter().enumerate() {
+            if values[..i]
+                .iter()
+                .any(|other| other.value() == value.value())
+            {
+                return Err(syn::Error::new(
+                    value.span(),
+                    "duplicate value of the fragment",
+                ));
+            }
+        }
+
+        Ok(Fragment { name, expr, values })
+    }
+}
+
+/// The query with every combination of the values of `fragments`, each of which is replaced in the
+/// query where its name is in braces, e.g. `{order}`.
+pub fn variants(sql: &str, fragments: &[Fragment]) -> syn::Result<Vec<Variant>> {
+    let mut count = 1usize;
+
+    for (i, fragment) in fragments.iter().enumerate() {
+        if fragments[..i]
+            .iter()
+            .any(|other| other.name == fragment.name)
+        {
+            return Err(syn::Error::new(
+                fragment.name.span(),
+                format!("duplicate fragment `{}`", fragment.name),
+            ));
+        }
+
+        if !sql.contains(&placeholder(fragment)) {
+            return Err(syn::Error::new(
+                fragment.name.span(),
+                format!(
+                    "fragment `{}` isn't in the query; expected `{}` where it goes",
+                    fragment.name,
+                    placeholder(fragment)
+                ),
+            ));
+        }
+
+        count = count.saturating_mul(fragment.values.len());
+    }
+
+    if count > MAX_VARIANTS {
+        return Err(syn::Error::new(
+            fragments[0].name.span(),
+            format!(
+                "the fragments have {} combinations of values, but at most {} are checked",
+                count, MAX_VARIANTS
+            ),
+        ));
+    }
+
+    let mut variants = vec![Variant {
+        sql: sql.to_owned(),
+        values: Vec::new(),
+    }];
+
+    for fragment in fragments {
+        variants = variants
+            .into_iter()
+            .flat_map(|variant| {
+                fragment.values.iter().map(move |value| {
+                    let value = value.value();
+
+                    Variant {
+                        sql: variant.sql.replace(&placeholder(fragment), &value),
+                        values: variant.values.iter().cloned().chain([value]).collect(),
+                    }
+                })
+            })
+            .collect();
+    }
+
+    Ok(variants)
+}
+
+/// Quotes the expression selecting the query of the values of the fragments at runtime, a
+/// `Result` with a configuration error for any other value.
+pub fn quote_select(fragments: &[Fragment], variants: &[Variant]) -> TokenStream {
+    let exprs = fragments.iter().map(|fragment| {
+        let expr = &fragment.expr;
+        quote! { <_ as ::std::convert::AsRef<str>>::as_ref(&(#expr)) }
+    });
+
+    let arms = variants.iter().map(|variant| {
+        let values = &variant.values;
+        let sql = &variant.sql;
+
+        quote! { (#(#values,)*) => ::std::result::Result::Ok(#sql), }
+    });
+
+    // not the names of the fragments, which could be those of constants in scope
+    let bindings: Vec<_> = (0..fragments.len())
+        .map(|i| format_ident!("sqlx_fragment_{}", i))
+        .collect();
+    let expected = fragments.iter().map(|fragment| {
+        let values: Vec<_> = fragment
+            .values
+            .iter()
+            .map(|value| format!("{:?}", value.value()))
+            .collect();
+
+        format!("`{}` must be one of {}", fragment.name, values.join(", "))
+    });
+
+    quote! {
+        match (#(#exprs,)*) {
+            #(#arms)*
+            (#(#bindings,)*) => ::std::result::Result::Err(::sqlx::Error::Configuration(
+                ::std::format!(
+                    "unexpected value of the query fragments: {}",
+                    [#(::std::format!("{}, got {:?}", #expected, #bindings)),*].join("; ")
+                )
+                .into(),
+            )),
+        }
+    }
+}
+
+fn placeholder(fragment: &Fragment) -> String {
+    format!("{{{}}}", fragment.name)
+}
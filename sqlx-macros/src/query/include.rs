@@ -0,0 +1,149 @@
+//! The `-- sqlx:include "path"` directives of query files, each replaced by the contents of the
+//! file at `path`, relative to the including file, when the query is read. Included files may
+//! include others, but not themselves.
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub struct QueryFile {
+    /// The query, with its includes expanded.
+    pub sql: String,
+    /// The files read, the query file first.
+    pub paths: Vec<PathBuf>,
+}
+
+/// Reads the query file at `path`, expanding its includes.
+pub fn read(path: &Path) -> Result<QueryFile, String> {
+    let mut reader = Reader::default();
+    let sql = reader.expand(path)?;
+
+    Ok(QueryFile {
+        sql,
+        paths: reader.paths,
+    })
+}
+
+#[derive(Default)]
+struct Reader {
+    paths: Vec<PathBuf>,
+    /// The files being expanded, each with the line of the include being expanded.
+    includes: Vec<(PathBuf, usize)>,
+}
+
+impl Reader {
+    fn expand(&mut self, path: &Path) -> Result<String, String> {
+        let contents = fs::read_to_string(path).map_err(|e| {
+            format!(
+                "failed to read query file at {}: {}{}",
+                display(path),
+                e,
+                self.describe_includes()
+            )
+        })?;
+
+        let path = path.canonicalize().map_err(|e| e.to_string())?;
+
+        if self.includes.iter().any(|(file, _)| *file == path) {
+            let cycle: Vec<_> = self
+                .includes
+                .iter()
+                .skip_while(|(file, _)| *file != path)
+                .map(|(file, _)| display(file))
+                .chain([display(&path)])
+                .collect();
+
+            return Err(format!(
+                "query file {} includes itself: {}",
+                display(&path),
+                cycle.join(" -> ")
+            ));
+        }
+
+        if !self.paths.contains(&path) {
+            self.paths.push(path.clone());
+        }
+
+        let mut sql = String::with_capacity(contents.len());
+
+        for (i, line) in contents.split_inclusive('\n').enumerate() {
+            let include = match parse_include(line) {
+                None => {
+                    sql.push_str(line);
+                    continue;
+                }
+                Some(Some(include)) => Path::new(include),
+                Some(None) => {
+                    return Err(format!(
+                        "invalid include at {}:{}; expected `-- sqlx:include \"path\"`{}",
+                        display(&path),
+                        i + 1,
+                        self.describe_includes()
+                    ))
+                }
+            };
+
+            if include.is_absolute() {
+                return Err(format!(
+                    "include at {}:{}: absolute paths will only work on the current machine",
+                    display(&path),
+                    i + 1
+                ));
+            }
+
+            let dir = path.parent().unwrap_or(&path).to_owned();
+
+            self.includes.push((path.clone(), i + 1));
+            let included = self.expand(&dir.join(include))?;
+            self.includes.pop();
+
+            // the included file takes the place of the line of the include
+            sql.push_str(included.trim_end_matches(&['\r', '\n'][..]));
+            if line.ends_with('\n') {
+                sql.push('\n');
+            }
+        }
+
+        Ok(sql)
+    }
+
+    /// The chain of includes of the file being read, for errors.
+    fn describe_includes(&self) -> String {
+        self.includes
+            .iter()
+            .rev()
+            .map(|(file, line)| format!("\n    included from {}:{}", display(file), line))
+            .collect()
+    }
+}
+
+/// Parses the include of `line`: `None` if it's not an include, `Some(None)` if it's invalid.
+fn parse_include(line: &str) -> Option<Option<&str>> {
+    let include = line
+        .trim()
+        .strip_prefix("--")?
+        .trim_start()
+        .strip_prefix("sqlx:include")?;
+
+    Some(
+        include
+            .trim()
+            .strip_prefix('"')
+            .and_then(|path| path.strip_suffix('"'))
+            .filter(|path| !path.is_empty()),
+    )
+}
+
+/// Displays `path` relative to the manifest directory, if it's in it.
+fn display(path: &Path) -> String {
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR")
+        .ok()
+        .and_then(|dir| Path::new(&dir).canonicalize().ok());
+
+    let path = manifest_dir
+        .as_deref()
+        .and_then(|dir| path.strip_prefix(dir).ok())
+        .unwrap_or(path);
+
+    format!("`{}`", path.display())
+}
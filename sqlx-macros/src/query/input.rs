@@ -1,12 +1,18 @@
-use std::fs;
+use std::path::{Path, PathBuf};
 
-use proc_macro2::{Ident, Span};
+use proc_macro2::{Ident, Span, TokenStream};
+use quote::{format_ident, quote, quote_spanned};
 use syn::parse::{Parse, ParseStream};
 use syn::punctuated::Punctuated;
+use syn::Type;
 use syn::{Expr, LitBool, LitStr, Token};
-use syn::{ExprArray, Type};
+
+use super::fragment::Fragment;
+use super::include;
+use super::output::ColumnDecl;
 
 /// Macro input shared by `query!()` and `query_file!()`
+#[derive(Clone)]
 pub struct QueryMacroInput {
     pub(super) sql: String,
 
@@ -19,7 +25,27 @@ pub struct QueryMacroInput {
 
     pub(super) checked: bool,
 
+    /// The query file, if it includes no other file, so that it's its own `include_str!()`.
     pub(super) file_path: Option<String>,
+
+    /// The files the query was read from otherwise, to rebuild when they change.
+    pub(super) dependencies: Vec<String>,
+
+    /// Overrides of output columns by name, for columns which can't be renamed in the query,
+    /// e.g. those of `SELECT *`.
+    pub(super) column_overrides: Vec<ColumnDecl>,
+
+    /// The dynamic fragments of the query, e.g. `order = sort in ["name ASC", "name DESC"]`.
+    pub(super) fragments: Vec<Fragment>,
+
+    /// The expression selecting the variant of the query at runtime, if it has fragments.
+    pub(super) select: Option<TokenStream>,
+}
+
+/// A bind argument, or a dynamic fragment of the query.
+enum QueryArg {
+    Expr(Expr),
+    Fragment(Fragment),
 }
 
 enum QuerySrc {
@@ -27,6 +53,7 @@ enum QuerySrc {
     File(String),
 }
 
+#[derive(Clone)]
 pub enum RecordType {
     Given(Type),
     Scalar,
@@ -37,8 +64,10 @@ impl Parse for QueryMacroInput {
     fn parse(input: ParseStream) -> syn::Result<Self> {
         let mut query_src: Option<(QuerySrc, Span)> = None;
         let mut args: Option<Vec<Expr>> = None;
+        let mut fragments = Vec::new();
         let mut record_type = RecordType::Generated;
         let mut checked = true;
+        let mut column_overrides = Vec::new();
 
         let mut expect_comma = false;
 
@@ -62,8 +91,25 @@ impl Parse for QueryMacroInput {
                 let lit_str = input.parse::<LitStr>()?;
                 query_src = Some((QuerySrc::File(lit_str.value()), lit_str.span()));
             } else if key == "args" {
-                let exprs = input.parse::<ExprArray>()?;
-                args = Some(exprs.elems.into_iter().collect())
+                let content;
+                syn::bracketed!(content in input);
+
+                let mut exprs = Vec::new();
+
+                for arg in Punctuated::<QueryArg, Token![,]>::parse_terminated(&content)? {
+                    match arg {
+                        QueryArg::Expr(expr) if !fragments.is_empty() => {
+                            return Err(syn::Error::new_spanned(
+                                expr,
+                                "bind arguments must come before the fragments of the query",
+                            ))
+                        }
+                        QueryArg::Expr(expr) => exprs.push(expr),
+                        QueryArg::Fragment(fragment) => fragments.push(fragment),
+                    }
+                }
+
+                args = Some(exprs)
             } else if key == "record" {
                 if !matches!(record_type, RecordType::Generated) {
                     return Err(input.error("colliding `scalar` or `record` key"));
@@ -80,6 +126,12 @@ impl Parse for QueryMacroInput {
                 // of the column in SQL
                 input.parse::<syn::Token![_]>()?;
                 record_type = RecordType::Scalar;
+            } else if key == "overrides" {
+                let content;
+                syn::braced!(content in input);
+                column_overrides = Punctuated::<ColumnDecl, Token![,]>::parse_terminated(&content)?
+                    .into_iter()
+                    .collect();
             } else if key == "checked" {
                 let lit_bool = input.parse::<LitBool>()?;
                 checked = lit_bool.value;
@@ -96,25 +148,78 @@ impl Parse for QueryMacroInput {
 
         let arg_exprs = args.unwrap_or_default();
 
-        let file_path = src.file_path(src_span)?;
+        let (sql, paths) = src.resolve(src_span)?;
+
+        // the query of a file with fragments is selected at runtime, so it's not the file
+        let (file_path, dependencies) = if paths.len() > 1 || !fragments.is_empty() {
+            let dependencies = paths
+                .iter()
+                .map(|path| path_to_string(path, src_span))
+                .collect::<syn::Result<_>>()?;
+
+            (None, dependencies)
+        } else {
+            (src.file_path(src_span)?, Vec::new())
+        };
 
         Ok(QueryMacroInput {
-            sql: src.resolve(src_span)?,
+            sql,
             src_span,
             record_type,
             arg_exprs,
             checked,
             file_path,
+            dependencies,
+            column_overrides,
+            fragments,
+            select: None,
         })
     }
 }
 
+impl Parse for QueryArg {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        // `name = expr in [...]`, unlike `name == expr`
+        if input.peek(syn::Ident) && input.peek2(Token![=]) {
+            input.parse().map(QueryArg::Fragment)
+        } else {
+            input.parse().map(QueryArg::Expr)
+        }
+    }
+}
+
+impl QueryMacroInput {
+    /// Quotes the query: the expression selecting its variant, its file, or the query itself.
+    pub(super) fn quote_sql(&self) -> TokenStream {
+        if let Some(select) = &self.select {
+            select.clone()
+        } else if let Some(path) = &self.file_path {
+            // tell the compiler where the query came from
+            quote_spanned! { self.src_span => include_str!(#path) }
+        } else {
+            let sql = &self.sql;
+            quote! { #sql }
+        }
+    }
+
+    /// The function of `sqlx` making the query of its SQL and arguments, e.g. `query_with`, or
+    /// its `_result` variant for the result of selecting the variant of the query.
+    pub(super) fn query_fn(&self, name: &str) -> Ident {
+        if self.select.is_some() {
+            format_ident!("{}_result", name)
+        } else {
+            format_ident!("{}", name)
+        }
+    }
+}
+
 impl QuerySrc {
-    /// If the query source is a file, read it to a string. Otherwise return the query string.
-    fn resolve(self, source_span: Span) -> syn::Result<String> {
+    /// If the query source is a file, read it to a string, expanding its includes, with the paths
+    /// of the files read. Otherwise return the query string.
+    fn resolve(&self, source_span: Span) -> syn::Result<(String, Vec<PathBuf>)> {
         match self {
-            QuerySrc::String(string) => Ok(string),
-            QuerySrc::File(file) => read_file_src(&file, source_span),
+            QuerySrc::String(string) => Ok((string.clone(), Vec::new())),
+            QuerySrc::File(file) => read_file_src(file, source_span),
         }
     }
 
@@ -124,33 +229,26 @@ impl QuerySrc {
                 .canonicalize()
                 .map_err(|e| syn::Error::new(source_span, e))?;
 
-            Ok(Some(
-                path.to_str()
-                    .ok_or_else(|| {
-                        syn::Error::new(
-                            source_span,
-                            "query file path cannot be represented as a string",
-                        )
-                    })?
-                    .to_string(),
-            ))
+            Ok(Some(path_to_string(&path, source_span)?))
         } else {
             Ok(None)
         }
     }
 }
 
-fn read_file_src(source: &str, source_span: Span) -> syn::Result<String> {
+fn read_file_src(source: &str, source_span: Span) -> syn::Result<(String, Vec<PathBuf>)> {
     let file_path = crate::common::resolve_path(source, source_span)?;
 
-    fs::read_to_string(&file_path).map_err(|e| {
+    let file = include::read(&file_path).map_err(|e| syn::Error::new(source_span, e))?;
+
+    Ok((file.sql, file.paths))
+}
+
+fn path_to_string(path: &Path, source_span: Span) -> syn::Result<String> {
+    path.to_str().map(str::to_owned).ok_or_else(|| {
         syn::Error::new(
             source_span,
-            format!(
-                "failed to read query file at {}: {}",
-                file_path.display(),
-                e
-            ),
+            "query file path cannot be represented as a string",
         )
     })
 }
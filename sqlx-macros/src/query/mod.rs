@@ -1,8 +1,6 @@
 use std::collections::BTreeMap;
 use std::path::PathBuf;
 use std::str::FromStr;
-#[cfg(feature = "offline")]
-use std::sync::{Arc, Mutex};
 
 use once_cell::sync::Lazy;
 use proc_macro2::TokenStream;
@@ -18,52 +16,38 @@ use sqlx_rt::{block_on, AsyncMutex};
 use crate::database::DatabaseExt;
 use crate::query::data::QueryData;
 use crate::query::input::RecordType;
+use crate::query::type_overrides::TypeOverrides;
 use either::Either;
 
 mod args;
 mod data;
+mod database_url;
+mod fragment;
+mod include;
 mod input;
 mod output;
+mod type_overrides;
 
 struct Metadata {
     #[allow(unused)]
     manifest_dir: PathBuf,
     offline: bool,
     database_url: Option<String>,
+    /// Where the database URL was looked for, for errors.
+    database_url_consulted: String,
     #[cfg(feature = "offline")]
     target_dir: PathBuf,
     #[cfg(feature = "offline")]
-    workspace_root: Arc<Mutex<Option<PathBuf>>>,
+    package_name: String,
+    /// The database to look the query data up for, if the data of several databases is saved.
+    #[cfg(feature = "offline")]
+    offline_db: Option<String>,
 }
 
 #[cfg(feature = "offline")]
 impl Metadata {
     pub fn workspace_root(&self) -> PathBuf {
-        let mut root = self.workspace_root.lock().unwrap();
-        if root.is_none() {
-            use serde::Deserialize;
-            use std::process::Command;
-
-            let cargo = env("CARGO").expect("`CARGO` must be set");
-
-            let output = Command::new(&cargo)
-                .args(&["metadata", "--format-version=1", "--no-deps"])
-                .current_dir(&self.manifest_dir)
-                .env_remove("__CARGO_FIX_PLZ")
-                .output()
-                .expect("Could not fetch metadata");
-
-            #[derive(Deserialize)]
-            struct CargoMetadata {
-                workspace_root: PathBuf,
-            }
-
-            let metadata: CargoMetadata =
-                serde_json::from_slice(&output.stdout).expect("Invalid `cargo metadata` output");
-
-            *root = Some(metadata.workspace_root);
-        }
-        root.clone().unwrap()
+        crate::common::workspace_root(&self.manifest_dir).unwrap_or_else(|e| panic!("{}", e))
     }
 }
 
@@ -77,6 +61,13 @@ static METADATA: Lazy<Metadata> = Lazy::new(|| {
     #[cfg(feature = "offline")]
     let target_dir = env("CARGO_TARGET_DIR").map_or_else(|_| "target".into(), |dir| dir.into());
 
+    // query data is saved per package, so `cargo sqlx prepare --workspace` can tell them apart,
+    // and each package may have its own database
+    let package_name = env("CARGO_PKG_NAME").expect("`CARGO_PKG_NAME` must be set");
+
+    // looked up before the `.env` files are loaded into the environment below
+    let database_url = database_url::resolve(&package_name, &manifest_dir, |name| env(name).ok());
+
     // If a .env file exists at CARGO_MANIFEST_DIR, load environment variables from this,
     // otherwise fallback to default dotenv behaviour.
     let env_path = manifest_dir.join(".env");
@@ -103,20 +94,68 @@ static METADATA: Lazy<Metadata> = Lazy::new(|| {
         .map(|s| s.eq_ignore_ascii_case("true") || s == "1")
         .unwrap_or(false);
 
-    let database_url = env("DATABASE_URL").ok();
+    let database_url_consulted = database_url.describe_consulted();
+    let database_url = database_url.url;
+
+    #[cfg(feature = "offline")]
+    let offline_db = env("SQLX_OFFLINE_DB").ok().filter(|db| !db.is_empty());
 
     Metadata {
         manifest_dir,
         offline,
         database_url,
+        database_url_consulted,
         #[cfg(feature = "offline")]
         target_dir,
         #[cfg(feature = "offline")]
-        workspace_root: Arc::new(Mutex::new(None)),
+        package_name,
+        #[cfg(feature = "offline")]
+        offline_db,
     }
 });
 
-pub fn expand_input(input: QueryMacroInput) -> crate::Result<TokenStream> {
+pub fn expand_input(mut input: QueryMacroInput) -> crate::Result<TokenStream> {
+    if input.fragments.is_empty() {
+        return expand_variant(input);
+    }
+
+    // every variant is checked, and saved for offline builds, but they must expand the same, to
+    // the query selected at runtime
+    let variants = fragment::variants(&input.sql, &input.fragments)?;
+    input.select = Some(fragment::quote_select(&input.fragments, &variants));
+
+    let mut expansion: Option<(&fragment::Variant, String, TokenStream)> = None;
+
+    for variant in &variants {
+        let mut variant_input = input.clone();
+        variant_input.sql = variant.sql.clone();
+
+        let tokens = expand_variant(variant_input).map_err(|e| {
+            format!(
+                "with the fragments {}: {}",
+                variant.describe(&input.fragments),
+                e
+            )
+        })?;
+
+        match &expansion {
+            None => expansion = Some((variant, tokens.to_string(), tokens)),
+            Some((first, expected, _)) if *expected != tokens.to_string() => {
+                return Err(format!(
+                    "the query has other parameters or columns with the fragments {} than with {}",
+                    variant.describe(&input.fragments),
+                    first.describe(&input.fragments)
+                )
+                .into())
+            }
+            Some(_) => {}
+        }
+    }
+
+    Ok(expansion.map(|(_, _, tokens)| tokens).unwrap_or_default())
+}
+
+fn expand_variant(input: QueryMacroInput) -> crate::Result<TokenStream> {
     match &*METADATA {
         #[cfg(not(any(
             feature = "postgres",
@@ -157,11 +196,12 @@ pub fn expand_input(input: QueryMacroInput) -> crate::Result<TokenStream> {
                 if workspace_data_file_path.exists() {
                     expand_from_file(input, workspace_data_file_path)
                 } else {
-                    Err(
+                    Err(format!(
                         "`DATABASE_URL` must be set, or `cargo sqlx prepare` must have been run \
-                     and sqlx-data.json must exist, to use query macros"
-                            .into(),
+                         and sqlx-data.json must exist, to use query macros; {}",
+                        METADATA.database_url_consulted
                     )
+                    .into())
                 }
             }
         }
@@ -176,7 +216,11 @@ pub fn expand_input(input: QueryMacroInput) -> crate::Result<TokenStream> {
             offline: false,
             database_url: None,
             ..
-        } => Err("`DATABASE_URL` must be set to use query macros".into()),
+        } => Err(format!(
+            "`DATABASE_URL` must be set to use query macros; {}",
+            METADATA.database_url_consulted
+        )
+        .into()),
     }
 }
 
@@ -247,7 +291,8 @@ fn expand_from_db(input: QueryMacroInput, db_url: &str) -> crate::Result<TokenSt
 pub fn expand_from_file(input: QueryMacroInput, file: PathBuf) -> crate::Result<TokenStream> {
     use data::offline::DynQueryData;
 
-    let query_data = DynQueryData::from_data_file(file, &input.sql)?;
+    let query_data =
+        DynQueryData::from_data_file(file, &input.sql, METADATA.offline_db.as_deref())?;
     assert!(!query_data.db_name.is_empty());
 
     match &*query_data.db_name {
@@ -319,6 +364,8 @@ where
 
     let args_tokens = args::quote_args(&input, &data.describe)?;
 
+    let type_overrides = TypeOverrides::load()?;
+
     let query_args = format_ident!("query_args");
 
     let output = if data
@@ -328,15 +375,20 @@ where
         .all(|it| it.type_info().is_void())
     {
         let db_path = DB::db_path();
-        let sql = &input.sql;
+        let sql = input.quote_sql();
+        let query_with = input.query_fn("query_with");
 
         quote! {
-            ::sqlx::query_with::<#db_path, _>(#sql, #query_args)
+            ::sqlx::#query_with::<#db_path, _>(#sql, #query_args)
         }
     } else {
         match input.record_type {
             RecordType::Generated => {
-                let columns = output::columns_to_rust::<DB>(&data.describe)?;
+                let columns = output::columns_to_rust::<DB>(
+                    &data.describe,
+                    &input.column_overrides,
+                    &type_overrides,
+                )?;
 
                 let record_name: Type = syn::parse_str("Record").unwrap();
 
@@ -375,22 +427,34 @@ where
                 record_tokens
             }
             RecordType::Given(ref out_ty) => {
-                let columns = output::columns_to_rust::<DB>(&data.describe)?;
+                let columns = output::columns_to_rust::<DB>(
+                    &data.describe,
+                    &input.column_overrides,
+                    &type_overrides,
+                )?;
 
                 output::quote_query_as::<DB>(&input, out_ty, &query_args, &columns)
             }
-            RecordType::Scalar => {
-                output::quote_query_scalar::<DB>(&input, &query_args, &data.describe)?
-            }
+            RecordType::Scalar => output::quote_query_scalar::<DB>(
+                &input,
+                &query_args,
+                &data.describe,
+                &type_overrides,
+            )?,
         }
     };
 
+    // rebuild when a file the query was read from, or `sqlx.toml`, changes
+    let dependencies = input.dependencies.iter().chain(&type_overrides.path);
+
     let ret_tokens = quote! {
         {
             #[allow(clippy::all)]
             {
                 use ::sqlx::Arguments as _;
 
+                #(const _: &str = include_str!(#dependencies);)*
+
                 #args_tokens
 
                 #output
@@ -402,7 +466,10 @@ where
     // If the build is offline, the cache is our input so it's pointless to also write data for it.
     #[cfg(feature = "offline")]
     if !offline {
-        let save_dir = METADATA.target_dir.join("sqlx");
+        let save_dir = METADATA
+            .target_dir
+            .join("sqlx")
+            .join(&METADATA.package_name);
         std::fs::create_dir_all(&save_dir)?;
         data.save_in(save_dir, input.src_span)?;
     }
@@ -4,13 +4,16 @@ use syn::Type;
 
 use sqlx_core::column::Column;
 use sqlx_core::describe::Describe;
+use sqlx_core::type_info::TypeInfo;
 
 use crate::database::DatabaseExt;
 
+use crate::query::type_overrides::TypeOverrides;
 use crate::query::QueryMacroInput;
 use std::fmt::{self, Display, Formatter};
+use syn::ext::IdentExt;
 use syn::parse::{Parse, ParseStream};
-use syn::Token;
+use syn::{PathArguments, Token, TypePath};
 
 pub struct RustColumn {
     pub(super) ident: Ident,
@@ -46,49 +49,129 @@ struct DisplayColumn<'a> {
     name: &'a str,
 }
 
-struct ColumnDecl {
+/// A column name with its overrides, e.g. `id!: UserId`, given in the query or in the
+/// `overrides` of `query_as!()`.
+#[derive(Clone)]
+pub(super) struct ColumnDecl {
     ident: Ident,
     r#override: ColumnOverride,
 }
 
+#[derive(Clone)]
 struct ColumnOverride {
     nullability: ColumnNullabilityOverride,
     type_: ColumnTypeOverride,
 }
 
-#[derive(PartialEq)]
+#[derive(Clone, PartialEq)]
 enum ColumnNullabilityOverride {
     NonNull,
     Nullable,
     None,
 }
 
+#[derive(Clone)]
 enum ColumnTypeOverride {
     Exact(Type),
     Wildcard,
     None,
 }
 
+impl ColumnOverride {
+    fn is_none(&self) -> bool {
+        self.nullability == ColumnNullabilityOverride::None
+            && matches!(self.type_, ColumnTypeOverride::None)
+    }
+}
+
+/// Listed in the errors of invalid overrides.
+const OVERRIDE_SYNTAX: &str = "expected one of `name`, `name!`, `name?`, `name: T`, `name!: T`, \
+     `name?: T` or `name?: Option<T>`, with `T` a Rust type or `_`";
+
 impl Display for DisplayColumn<'_> {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         write!(f, "column #{} ({:?})", self.idx + 1, self.name)
     }
 }
 
-pub fn columns_to_rust<DB: DatabaseExt>(describe: &Describe<DB>) -> crate::Result<Vec<RustColumn>> {
-    (0..describe.columns().len())
-        .map(|i| column_to_rust(describe, i))
-        .collect::<crate::Result<Vec<_>>>()
+pub fn columns_to_rust<DB: DatabaseExt>(
+    describe: &Describe<DB>,
+    overrides: &[ColumnDecl],
+    type_overrides: &TypeOverrides,
+) -> crate::Result<Vec<RustColumn>> {
+    let columns = (0..describe.columns().len())
+        .map(|i| column_to_rust(describe, i, overrides, type_overrides))
+        .collect::<crate::Result<Vec<_>>>()?;
+
+    // an override of a column which isn't in the output is likely a typo
+    for decl in overrides {
+        if !columns
+            .iter()
+            .any(|column| column.ident.unraw() == decl.ident.unraw())
+        {
+            return Err(syn::Error::new(
+                decl.ident.span(),
+                format!("no column named `{}` to override", decl.ident.unraw()),
+            )
+            .into());
+        }
+    }
+
+    Ok(columns)
 }
 
-fn column_to_rust<DB: DatabaseExt>(describe: &Describe<DB>, i: usize) -> crate::Result<RustColumn> {
+fn column_to_rust<DB: DatabaseExt>(
+    describe: &Describe<DB>,
+    i: usize,
+    overrides: &[ColumnDecl],
+    type_overrides: &TypeOverrides,
+) -> crate::Result<RustColumn> {
     let column = &describe.columns()[i];
 
     // add raw prefix to all identifiers
-    let decl = ColumnDecl::parse(&column.name())
-        .map_err(|e| format!("column name {:?} is invalid: {}", column.name(), e))?;
+    let mut decl = ColumnDecl::parse(column.name()).map_err(|e| {
+        format!(
+            "column name {:?} is invalid: {}; {}",
+            column.name(),
+            e,
+            OVERRIDE_SYNTAX
+        )
+    })?;
+
+    // columns can also be overridden by name, e.g. those of `SELECT *`
+    if let Some(named) = overrides
+        .iter()
+        .find(|named| named.ident.unraw() == decl.ident.unraw())
+    {
+        if !decl.r#override.is_none() {
+            return Err(syn::Error::new(
+                named.ident.span(),
+                format!(
+                    "column `{}` is overridden both in the query and in `overrides`",
+                    decl.ident.unraw()
+                ),
+            )
+            .into());
+        }
 
-    let ColumnOverride { nullability, type_ } = decl.r#override;
+        decl.r#override = named.r#override.clone();
+    }
+
+    let ColumnOverride {
+        nullability,
+        mut type_,
+    } = decl.r#override;
+
+    // then by `sqlx.toml`, by the types and the names of the columns
+    if let ColumnTypeOverride::None = type_ {
+        let name = decl.ident.unraw().to_string();
+
+        if let Some(rust) =
+            type_overrides.find(&name, column.type_info().name(), describe.domain(i))
+        {
+            type_ = ColumnTypeOverride::Exact(rust.clone());
+        }
+    }
 
     let nullable = match nullability {
         ColumnNullabilityOverride::NonNull => false,
@@ -97,6 +180,10 @@ fn column_to_rust<DB: DatabaseExt>(describe: &Describe<DB>, i: usize) -> crate::
     };
     let type_ = match (type_, nullable) {
         (ColumnTypeOverride::Exact(type_), false) => ColumnType::Exact(type_.to_token_stream()),
+        // `foo?: Option<T>` is the same as `foo?: T`
+        (ColumnTypeOverride::Exact(type_), true) if is_option(&type_) => {
+            ColumnType::Exact(type_.to_token_stream())
+        }
         (ColumnTypeOverride::Exact(type_), true) => {
             ColumnType::Exact(quote! { ::std::option::Option<#type_> })
         }
@@ -123,6 +210,20 @@ fn column_to_rust<DB: DatabaseExt>(describe: &Describe<DB>, i: usize) -> crate::
     })
 }
 
+/// Whether `type_` is `Option<T>`, under any path.
+fn is_option(type_: &Type) -> bool {
+    match type_ {
+        Type::Path(TypePath { qself: None, path }) => matches!(
+            path.segments.last(),
+            Some(last) if last.ident == "Option"
+                && matches!(last.arguments, PathArguments::AngleBracketed(_))
+        ),
+        Type::Group(group) => is_option(&group.elem),
+        Type::Paren(paren) => is_option(&paren.elem),
+        _ => false,
+    }
+}
+
 pub fn quote_query_as<DB: DatabaseExt>(
     input: &QueryMacroInput,
     out_ty: &Type,
@@ -163,16 +264,11 @@ pub fn quote_query_as<DB: DatabaseExt>(
     let db_path = DB::db_path();
     let row_path = DB::row_path();
 
-    // if this query came from a file, use `include_str!()` to tell the compiler where it came from
-    let sql = if let Some(ref path) = &input.file_path {
-        quote::quote_spanned! { input.src_span => include_str!(#path) }
-    } else {
-        let sql = &input.sql;
-        quote! { #sql }
-    };
+    let sql = input.quote_sql();
+    let query_with = input.query_fn("query_with");
 
     quote! {
-        ::sqlx::query_with::<#db_path, _>(#sql, #bind_args).try_map(|row: #row_path| {
+        ::sqlx::#query_with::<#db_path, _>(#sql, #bind_args).try_map(|row: #row_path| {
             use ::sqlx::Row as _;
 
             #(#instantiations)*
@@ -186,6 +282,7 @@ pub fn quote_query_scalar<DB: DatabaseExt>(
     input: &QueryMacroInput,
     bind_args: &Ident,
     describe: &Describe<DB>,
+    type_overrides: &TypeOverrides,
 ) -> crate::Result<TokenStream> {
     let columns = describe.columns();
 
@@ -198,7 +295,7 @@ pub fn quote_query_scalar<DB: DatabaseExt>(
     }
 
     // attempt to parse a column override, otherwise fall back to the inferred type of the column
-    let ty = if let Ok(rust_col) = column_to_rust(describe, 0) {
+    let ty = if let Ok(rust_col) = column_to_rust(describe, 0, &[], type_overrides) {
         rust_col.type_.to_token_stream()
     } else if input.checked {
         let ty = get_column_type::<DB>(0, &columns[0]);
@@ -212,10 +309,11 @@ pub fn quote_query_scalar<DB: DatabaseExt>(
     };
 
     let db = DB::db_path();
-    let query = &input.sql;
+    let query = input.quote_sql();
+    let query_scalar_with = input.query_fn("query_scalar_with");
 
     Ok(quote! {
-        ::sqlx::query_scalar_with::<#db, #ty, _>(#query, #bind_args)
+        ::sqlx::#query_scalar_with::<#db, #ty, _>(#query, #bind_args)
     })
 }
 
@@ -278,6 +376,22 @@ impl ColumnDecl {
     }
 }
 
+impl Parse for ColumnDecl {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        // keywords are allowed, e.g. `type`, as the fields of the record are raw identifiers
+        let ident = Ident::parse_any(input)?;
+        let ident = parse_ident(&ident.unraw().to_string())
+            .map(|parsed| Ident::new_raw(&parsed.unraw().to_string(), ident.span()))
+            .map_err(|e| syn::Error::new(ident.span(), e))?;
+
+        let r#override = input
+            .parse()
+            .map_err(|e| syn::Error::new(e.span(), format!("{}; {}", e, OVERRIDE_SYNTAX)))?;
+
+        Ok(ColumnDecl { ident, r#override })
+    }
+}
+
 impl Parse for ColumnOverride {
     fn parse(input: ParseStream) -> syn::Result<Self> {
         let lookahead = input.lookahead1();
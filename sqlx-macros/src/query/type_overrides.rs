@@ -0,0 +1,164 @@
+//! The types of output columns overridden in `sqlx.toml`, by the name of their database type or
+//! domain and of the column, e.g. to a newtype of the ids of a table:
+//!
+//! ```toml
+//! [[macros.type-overrides]]
+//! type = "INT8"
+//! column = "*user_id"
+//! rust = "crate::ids::UserId"
+//! ```
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use syn::Type;
+use toml::Spanned;
+
+/// The name of the configuration file, as read by `sqlx-cli`.
+const CONFIG_FILE: &str = "sqlx.toml";
+
+#[derive(Default)]
+pub struct TypeOverrides {
+    /// The configuration file the overrides were read from, for the compiler to track.
+    pub path: Option<String>,
+    overrides: Vec<TypeOverride>,
+}
+
+struct TypeOverride {
+    type_: Option<String>,
+    column: Option<String>,
+    rust: Type,
+}
+
+/// The keys of the other sections are those of `sqlx-cli`.
+#[derive(Default, serde::Deserialize)]
+#[serde(default)]
+struct ConfigFile {
+    macros: MacrosConfig,
+}
+
+#[derive(Default, serde::Deserialize)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+struct MacrosConfig {
+    type_overrides: Vec<TypeOverrideConfig>,
+}
+
+#[derive(serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TypeOverrideConfig {
+    #[serde(rename = "type")]
+    type_: Option<String>,
+    column: Option<String>,
+    rust: Spanned<String>,
+}
+
+impl TypeOverrides {
+    /// Reads the type overrides of `sqlx.toml` in the directory of the crate or its closest
+    /// ancestor which has one, if any.
+    pub fn load() -> crate::Result<TypeOverrides> {
+        let manifest_dir =
+            env::var("CARGO_MANIFEST_DIR").map_err(|_| "`CARGO_MANIFEST_DIR` must be set")?;
+
+        match Path::new(&manifest_dir)
+            .ancestors()
+            .map(|dir| dir.join(CONFIG_FILE))
+            .find(|path| path.is_file())
+        {
+            Some(path) => TypeOverrides::read(path),
+            None => Ok(TypeOverrides::default()),
+        }
+    }
+
+    fn read(path: PathBuf) -> crate::Result<TypeOverrides> {
+        let contents = fs::read_to_string(&path)
+            .map_err(|e| format!("failed to read {}: {}", path.display(), e))?;
+
+        let config: ConfigFile =
+            toml::from_str(&contents).map_err(|e| format!("invalid {}: {}", path.display(), e))?;
+
+        let overrides = config
+            .macros
+            .type_overrides
+            .into_iter()
+            .map(|config| {
+                let rust = config.rust.get_ref();
+                // errors point at the entry, as the path can't be spanned in the file
+                let line = contents[..config.rust.start()].matches('\n').count() + 1;
+
+                let type_ = syn::parse_str::<Type>(rust).map_err(|e| {
+                    format!(
+                        "invalid Rust type `{}` of the type override at {}:{}: {}",
+                        rust,
+                        path.display(),
+                        line,
+                        e
+                    )
+                })?;
+
+                if config.type_.is_none() && config.column.is_none() {
+                    return Err(format!(
+                        "the type override at {}:{} would override every column; \
+                         expected `type`, `column` or both",
+                        path.display(),
+                        line
+                    ));
+                }
+
+                Ok(TypeOverride {
+                    type_: config.type_,
+                    column: config.column,
+                    rust: type_,
+                })
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(TypeOverrides {
+            path: path.to_str().map(str::to_owned),
+            overrides,
+        })
+    }
+
+    /// The Rust type of the column `column`, of the database type `type_name` or declared with
+    /// the domain `domain`, if it's overridden. The first override which matches is used.
+    pub fn find(&self, column: &str, type_name: &str, domain: Option<&str>) -> Option<&Type> {
+        self.overrides
+            .iter()
+            .find(|r#override| {
+                let type_matches = match &r#override.type_ {
+                    Some(type_) => {
+                        type_.eq_ignore_ascii_case(type_name)
+                            || matches!(domain, Some(domain) if type_.eq_ignore_ascii_case(domain))
+                    }
+                    None => true,
+                };
+
+                let column_matches = match &r#override.column {
+                    Some(pattern) => matches_pattern(pattern, column),
+                    None => true,
+                };
+
+                type_matches && column_matches
+            })
+            .map(|r#override| &r#override.rust)
+    }
+}
+
+/// Whether `name` matches `pattern`, in which `*` matches any characters.
+fn matches_pattern(pattern: &str, name: &str) -> bool {
+    match pattern.split_once('*') {
+        None => pattern == name,
+        Some((prefix, rest)) => {
+            let name = match name.strip_prefix(prefix) {
+                Some(name) => name,
+                None => return false,
+            };
+
+            // the rest of the pattern matches the end of `name` after any characters
+            name.char_indices()
+                .map(|(i, _)| i)
+                .chain([name.len()])
+                .any(|i| matches_pattern(rest, &name[i..]))
+        }
+    }
+}
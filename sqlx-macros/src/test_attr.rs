@@ -1,15 +1,25 @@
 use proc_macro2::{Span, TokenStream};
 use quote::quote;
-use syn::LitStr;
 
+#[cfg(feature = "migrate")]
 struct Args {
-    fixtures: Vec<LitStr>,
+    fixtures: Vec<Fixture>,
     migrations: MigrationsOpt,
+    teardown: Option<syn::Path>,
+}
+
+/// A fixture, in the order it was given.
+#[cfg(feature = "migrate")]
+struct Fixture {
+    // relative to the test file, like `include_str!()`, or absolute
+    path: String,
+    span: Span,
 }
 
+#[cfg(feature = "migrate")]
 enum MigrationsOpt {
     InferredPath,
-    ExplicitPath(LitStr),
+    ExplicitPath(syn::LitStr),
     ExplicitMigrator(syn::Path),
     Disabled,
 }
@@ -72,10 +82,8 @@ fn expand_advanced(args: syn::AttributeArgs, input: syn::ItemFn) -> crate::Resul
 
     let fn_arg_types = inputs.iter().map(|_| quote! { _ });
 
-    let fixtures = args.fixtures.into_iter().map(|fixture| {
-        let path = format!("fixtures/{}.sql", fixture.value());
-
-        quote! {
+    let fixtures = args.fixtures.into_iter().map(|Fixture { path, span }| {
+        quote::quote_spanned! {span=>
             ::sqlx::testing::TestFixture {
                 path: #path,
                 contents: include_str!(#path),
@@ -83,6 +91,8 @@ fn expand_advanced(args: syn::AttributeArgs, input: syn::ItemFn) -> crate::Resul
         }
     });
 
+    let teardown = args.teardown.map(|path| quote! { args.teardown(#path); });
+
     let migrations = match args.migrations {
         MigrationsOpt::ExplicitPath(path) => {
             let migrator = crate::migrate::expand_migrator_from_lit_dir(path)?;
@@ -92,7 +102,10 @@ fn expand_advanced(args: syn::AttributeArgs, input: syn::ItemFn) -> crate::Resul
             let migrations_path = crate::common::resolve_path("./migrations", Span::call_site())?;
 
             if migrations_path.is_dir() {
-                let migrator = crate::migrate::expand_migrator(&migrations_path)?;
+                let migrator = crate::migrate::expand_migrator(
+                    &[migrations_path],
+                    sqlx_core::migrate::DEFAULT_MIGRATIONS_TABLE,
+                )?;
                 quote! { args.migrator(&#migrator); }
             } else {
                 quote! {}
@@ -118,6 +131,8 @@ fn expand_advanced(args: syn::AttributeArgs, input: syn::ItemFn) -> crate::Resul
 
             args.fixtures(&[#(#fixtures),*]);
 
+            #teardown
+
             // We need to give a coercion site or else we get "unimplemented trait" errors.
             let f: fn(#(#fn_arg_types),*) -> _ = inner;
 
@@ -130,22 +145,26 @@ fn expand_advanced(args: syn::AttributeArgs, input: syn::ItemFn) -> crate::Resul
 fn parse_args(attr_args: syn::AttributeArgs) -> syn::Result<Args> {
     let mut fixtures = vec![];
     let mut migrations = MigrationsOpt::InferredPath;
+    let mut teardown = None;
 
     for arg in attr_args {
         match arg {
+            // `fixtures(...)` may be given more than once, the fixtures being applied in order
             syn::NestedMeta::Meta(syn::Meta::List(list)) if list.path.is_ident("fixtures") => {
-                if !fixtures.is_empty() {
-                    return Err(syn::Error::new_spanned(list, "duplicate `fixtures` arg"));
+                parse_fixtures(list, &mut fixtures)?;
+            }
+            syn::NestedMeta::Meta(syn::Meta::NameValue(namevalue))
+                if namevalue.path.is_ident("teardown") =>
+            {
+                if teardown.is_some() {
+                    return Err(syn::Error::new_spanned(namevalue, "duplicate `teardown` arg"));
                 }
 
-                for nested in list.nested {
-                    match nested {
-                        syn::NestedMeta::Lit(syn::Lit::Str(litstr)) => fixtures.push(litstr),
-                        other => {
-                            return Err(syn::Error::new_spanned(other, "expected string literal"))
-                        }
-                    }
-                }
+                teardown = match namevalue.lit {
+                    // teardown = "<rust path>"
+                    syn::Lit::Str(litstr) => Some(litstr.parse()?),
+                    _ => return Err(syn::Error::new_spanned(namevalue, "expected string")),
+                };
             }
             syn::NestedMeta::Meta(syn::Meta::NameValue(namevalue))
                 if namevalue.path.is_ident("migrations") =>
@@ -204,7 +223,7 @@ fn parse_args(attr_args: syn::AttributeArgs) -> syn::Result<Args> {
             other => {
                 return Err(syn::Error::new_spanned(
                     other,
-                    "expected `fixtures(\"<filename>\", ...)` or `migrations = \"<path>\" | false` or `migrator = \"<rust path>\"`",
+                    "expected `fixtures(\"<filename>\", ...)` or `fixtures(path = \"<path>\", scripts(\"<filename>\", ...))` or `migrations = \"<path>\" | false` or `migrator = \"<rust path>\"` or `teardown = \"<rust path>\"`",
                 ))
             }
         }
@@ -213,5 +232,100 @@ fn parse_args(attr_args: syn::AttributeArgs) -> syn::Result<Args> {
     Ok(Args {
         fixtures,
         migrations,
+        teardown,
     })
 }
+
+/// Parse `fixtures("<name>" | "<path>.sql", ...)` or
+/// `fixtures(path = "<dir>", scripts("<name>" | "<path>.sql", ...))`.
+///
+/// A name is resolved to `<dir>/<name>.sql`, where the directory defaults to `fixtures`, while
+/// a path ending in `.sql` is resolved relative to the directory, if given.
+#[cfg(feature = "migrate")]
+fn parse_fixtures(list: syn::MetaList, fixtures: &mut Vec<Fixture>) -> syn::Result<()> {
+    let mut dir = None;
+    let mut scripts = None;
+    let mut names = vec![];
+
+    for nested in list.nested {
+        match nested {
+            syn::NestedMeta::Lit(syn::Lit::Str(litstr)) => names.push(litstr),
+            syn::NestedMeta::Meta(syn::Meta::NameValue(namevalue))
+                if namevalue.path.is_ident("path") =>
+            {
+                if dir.is_some() {
+                    return Err(syn::Error::new_spanned(namevalue, "duplicate `path` arg"));
+                }
+
+                match namevalue.lit {
+                    syn::Lit::Str(litstr) => dir = Some(litstr.value()),
+                    _ => return Err(syn::Error::new_spanned(namevalue, "expected string")),
+                }
+            }
+            syn::NestedMeta::Meta(syn::Meta::List(list)) if list.path.is_ident("scripts") => {
+                if scripts.is_some() {
+                    return Err(syn::Error::new_spanned(list, "duplicate `scripts` arg"));
+                }
+
+                let mut litstrs = vec![];
+
+                for nested in list.nested {
+                    match nested {
+                        syn::NestedMeta::Lit(syn::Lit::Str(litstr)) => litstrs.push(litstr),
+                        other => {
+                            return Err(syn::Error::new_spanned(other, "expected string literal"))
+                        }
+                    }
+                }
+
+                scripts = Some((list.path, litstrs));
+            }
+            other => {
+                return Err(syn::Error::new_spanned(
+                    other,
+                    "expected string literal or `path = \"<path>\"` or `scripts(...)`",
+                ))
+            }
+        }
+    }
+
+    let names = match (dir.is_some(), scripts) {
+        (false, None) => names,
+        (true, Some((_, scripts))) if names.is_empty() => scripts,
+        (true, Some(_)) => {
+            return Err(syn::Error::new_spanned(
+                &names[0],
+                "fixtures in a `path` must be listed in `scripts(...)`",
+            ))
+        }
+        (true, None) => {
+            return Err(syn::Error::new_spanned(
+                list.path,
+                "`path` requires `scripts(\"<filename>\", ...)`",
+            ))
+        }
+        (false, Some((path, _))) => {
+            return Err(syn::Error::new_spanned(path, "`scripts` requires `path`"))
+        }
+    };
+
+    let dir = dir.as_deref().map(|dir| dir.trim_end_matches('/'));
+
+    fixtures.extend(names.into_iter().map(|name| {
+        let value = name.value();
+
+        let path = match dir {
+            _ if std::path::Path::new(&value).is_absolute() => value,
+            Some(dir) if value.ends_with(".sql") => format!("{}/{}", dir, value),
+            None if value.ends_with(".sql") => value,
+            dir => format!("{}/{}.sql", dir.unwrap_or("fixtures"), value),
+        };
+
+        Fixture {
+            path,
+            span: name.span(),
+        }
+    }));
+
+    Ok(())
+}
@@ -15,6 +15,12 @@ pub use futures_rustls::{client::TlsStream, TlsConnector};
 
 pub use async_std::task::{block_on, block_on as test_block_on};
 
+pub async fn lookup_host(
+    host: (&str, u16),
+) -> std::io::Result<impl Iterator<Item = std::net::SocketAddr>> {
+    async_std::net::ToSocketAddrs::to_socket_addrs(&host).await
+}
+
 pub fn enter_runtime<F, R>(f: F) -> R
 where
     F: FnOnce() -> R,
@@ -11,19 +11,26 @@ pub use sqlx_core::acquire::Acquire;
 pub use sqlx_core::arguments::{Arguments, IntoArguments};
 pub use sqlx_core::column::Column;
 pub use sqlx_core::column::ColumnIndex;
-pub use sqlx_core::connection::{ConnectOptions, Connection};
+pub use sqlx_core::connection::{ConnectOptions, Connection, PingResult};
 pub use sqlx_core::database::{self, Database};
-pub use sqlx_core::describe::Describe;
+pub use sqlx_core::describe::{describe, Describe};
+pub use sqlx_core::dyn_connection;
 pub use sqlx_core::executor::{Execute, Executor};
 pub use sqlx_core::from_row::FromRow;
+pub use sqlx_core::insert::{self, insert, InsertRow};
+pub use sqlx_core::logger;
+pub use sqlx_core::observer;
 pub use sqlx_core::pool::{self, Pool};
 pub use sqlx_core::query::{query, query_with};
 pub use sqlx_core::query_as::{query_as, query_as_with};
 pub use sqlx_core::query_builder::{self, QueryBuilder};
 pub use sqlx_core::query_scalar::{query_scalar, query_scalar_with};
+pub use sqlx_core::quote;
 pub use sqlx_core::row::Row;
-pub use sqlx_core::statement::Statement;
-pub use sqlx_core::transaction::{Transaction, TransactionManager};
+pub use sqlx_core::statement::{PreparedStatementInfo, Statement};
+pub use sqlx_core::transaction::{
+    IsolationLevel, Transaction, TransactionManager, TransactionOptions,
+};
 pub use sqlx_core::type_info::TypeInfo;
 pub use sqlx_core::types::Type;
 pub use sqlx_core::value::{Value, ValueRef};
@@ -35,6 +42,10 @@ pub use sqlx_core::error::{self, Error, Result};
 #[cfg(feature = "migrate")]
 pub use sqlx_core::migrate;
 
+#[cfg(feature = "serde_row")]
+#[cfg_attr(docsrs, doc(cfg(feature = "serde_row")))]
+pub use sqlx_core::serde_row;
+
 #[cfg(all(
     any(
         feature = "mysql",
@@ -69,7 +80,7 @@ pub extern crate sqlx_macros;
 // derives
 #[cfg(feature = "macros")]
 #[doc(hidden)]
-pub use sqlx_macros::{FromRow, Type};
+pub use sqlx_macros::{FromRow, InsertRow, Type};
 
 // We can't do our normal facade approach with an attribute, but thankfully we can now
 // have docs out-of-line quite easily.
@@ -83,6 +94,10 @@ pub use sqlx_core::testing;
 #[doc(hidden)]
 pub use sqlx_core::test_block_on;
 
+// the queries of the macros selected by their dynamic fragments
+#[doc(hidden)]
+pub use sqlx_core::{query::query_with_result, query_scalar::query_scalar_with_result};
+
 #[cfg(feature = "macros")]
 mod macros;
 
@@ -156,6 +171,9 @@ pub mod prelude {
     pub use super::Row;
     pub use super::Statement;
     pub use super::Type;
+
+    #[cfg(feature = "serde_row")]
+    pub use super::serde_row::DeserializeRow;
 }
 
 #[doc(hidden)]
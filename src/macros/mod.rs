@@ -45,6 +45,10 @@
 /// server with the schema that the query string will be checked against. All variants of `query!()`
 /// use [dotenv]<sup>1</sup> so this can be in a `.env` file instead.
 ///
+///     * In a workspace, a crate can have its own database: `SQLX_DATABASE_URL_<CRATE>` (e.g.
+///       `SQLX_DATABASE_URL_MY_APP` for the package `my-app`) comes first, then the `.env` files
+///       from the directory of the crate up to the workspace root, then `DATABASE_URL`.
+///
 ///     * Or, `sqlx-data.json` must exist at the workspace root. See [Offline Mode](#offline-mode-requires-the-offline-feature)
 ///       below.
 ///
@@ -105,6 +109,11 @@
 /// behind references). `Option::None` will be bound as `NULL`, so if binding a type behind `Option`
 /// be sure your query can support it.
 ///
+/// Where the database tells the expected types of the parameters (Postgres), the borrowed and
+/// owned forms of a type are interchangeable: `String`, `&String` and `&str` for `text`;
+/// `Vec<T>`, `[T; N]` and `&[T]` for an array, and `Vec<&str>` for `text[]`; and an `Option` of
+/// any of these, or a reference to one, e.g. `&Option<String>`, without `.as_deref()`.
+///
 /// Note, however, if binding in a `where` clause, that equality comparisons with `NULL` may not
 /// work as expected; instead you must use `IS NOT NULL` or `IS NULL` to check if a column is not
 /// null or is null, respectively.
@@ -266,15 +275,117 @@
 /// # }
 /// ```
 ///
+/// As a nullable column is already wrapped in `Option`, `foo?: Option<T>` is the same as
+/// `foo?: T`, and so is `foo: Option<T>` for a column inferred to be nullable.
+///
 /// ##### Overrides cheatsheet
 ///
-/// | Syntax    | Nullability     | Type       |
-/// | --------- | --------------- | ---------- |
-/// | `foo!`    | Forced not-null | Inferred   |
-/// | `foo?`    | Forced nullable | Inferred   |
-/// | `foo: T`  | Inferred        | Overridden |
-/// | `foo!: T` | Forced not-null | Overridden |
-/// | `foo?: T` | Forced nullable | Overridden |
+/// | Syntax            | Nullability     | Type       |
+/// | ----------------- | --------------- | ---------- |
+/// | `foo!`            | Forced not-null | Inferred   |
+/// | `foo?`            | Forced nullable | Inferred   |
+/// | `foo: T`          | Inferred        | Overridden |
+/// | `foo!: T`         | Forced not-null | Overridden |
+/// | `foo?: T`         | Forced nullable | Overridden |
+/// | `foo?: Option<T>` | Forced nullable | Overridden |
+/// | `foo: _`          | Inferred        | Checked at runtime ([query_as!] only) |
+///
+/// A column name which isn't a valid override is a compile error listing these forms.
+///
+/// ##### Inference by driver
+///
+/// | Column                             | Postgres                | MySQL            | SQLite                     |
+/// | ---------------------------------- | ----------------------- | ---------------- | -------------------------- |
+/// | Table column, `NOT NULL`           | `T`                     | `T`              | `T`                        |
+/// | Table column, nullable             | `Option<T>`             | `Option<T>`      | `Option<T>`                |
+/// | `NOT NULL` column of a `LEFT JOIN` | `Option<T>` (`EXPLAIN`) | `Option<T>`      | `T`: use `?`               |
+/// | Aggregate, e.g. `COUNT(*)`         | `Option<T>`: use `!`    | per the function | `T`, even `MAX()`: use `?` |
+/// | Other expressions                  | `Option<T>`: use `!`    | per the function | per `EXPLAIN`              |
+///
+/// Postgres can't tell the nullability of expressions, so `!` is often needed for them. The
+/// columns of its functions returning a table type may however be inferred `NOT NULL` and still be
+/// `NULL`, e.g. through an outer join in their body; use `?` for those. SQLite only sees the
+/// `NOT NULL` constraints of the tables, so a column brought in by a `LEFT JOIN`, or `MAX()` over
+/// no rows, needs `?`.
+///
+/// ##### Overriding columns by name
+/// The columns of `SELECT *`, or of a query in a file shared with other code, can't be renamed in
+/// the query. [query_as!] and [query_file_as!] take the overrides of these columns by name before
+/// the bind arguments instead, in the same syntax:
+///
+/// ```rust,ignore
+/// # async fn main() {
+/// # let mut conn = panic!();
+/// struct TweetWithOwner {
+///     id: i64,
+///     text: String,
+///     is_sent: bool,
+///     owner_id: i64,
+///     owner: Option<i64>,
+///     name: Option<String>,
+/// }
+///
+/// // SQLite: `owner` and `name` would be inferred `NOT NULL`, and `owner_id` nullable
+/// let tweets = sqlx::query_as!(
+///     TweetWithOwner,
+///     "SELECT * FROM tweet LEFT JOIN (SELECT id AS owner, name FROM accounts) ON owner = owner_id
+///      WHERE owner_id IS NOT NULL AND id > ?",
+///     overrides = { owner_id!, owner?, name?: Option<String> },
+///     1
+/// )
+/// .fetch_all(&mut conn)
+/// .await?;
+/// # }
+/// ```
+///
+/// A column overridden both in the query and by name, or an override naming no column, is an error.
+///
+/// ##### Overriding types in `sqlx.toml`
+/// Newtypes used across a project, such as the ids of a table, can be mapped once in the
+/// `sqlx.toml` of the crate or its closest ancestor which has one, the file read by `sqlx-cli`.
+/// A column of the database type `type`, whose name matches `column`, in which `*` matches any
+/// characters, is then of the Rust type `rust`, wrapped in `Option` if it's nullable:
+///
+/// ```toml
+/// [[macros.type-overrides]]
+/// type = "INT8"
+/// column = "*user_id"
+/// rust = "crate::ids::UserId"
+///
+/// # a Postgres domain, or custom type
+/// [[macros.type-overrides]]
+/// type = "email"
+/// rust = "crate::Email"
+/// ```
+///
+/// `type` is the name of the type as in the errors of the macros, in any case, and either it or
+/// `column` may be left out. The first entry matching a column is used, unless the column is
+/// overridden in the query or with `overrides`. As with `foo: T`, the compatibility of the Rust
+/// types with the columns isn't checked. An invalid entry is an error naming its line in
+/// `sqlx.toml`, while a path which doesn't resolve is an error where the query is.
+///
+/// ## Dynamic Fragments
+/// Parts of a query which can't be bind parameters, such as the sort order, can be chosen at
+/// runtime from a fixed set of string literals. A fragment `name = expr in ["..", ..]` after the
+/// bind arguments is replaced where `{name}` is in the query by the value of `expr`, anything
+/// implementing `AsRef<str>`, which must be one of the literals listed:
+///
+/// ```rust,ignore
+/// let sort = if descending { "name DESC" } else { "name ASC" };
+///
+/// let accounts = sqlx::query!(
+///     "SELECT id, name FROM accounts WHERE id > ? ORDER BY {order}",
+///     1i32,
+///     order = sort in ["name ASC", "name DESC"],
+/// )
+/// .fetch_all(&mut conn)
+/// .await?;
+/// ```
+///
+/// The query is checked with every combination of the values of its fragments, at most 64, each of
+/// which must have the same bind parameters and output columns. With any other value, the query
+/// returns [`Error::Configuration`](crate::Error::Configuration) instead of being executed.
+/// `cargo sqlx prepare` saves the data of every combination for offline builds.
 ///
 /// ## Offline Mode (requires the `offline` feature)
 /// The macros can be configured to not require a live database connection for compilation,
@@ -294,6 +405,11 @@
 /// project and your database schema itself, run
 /// `cargo install sqlx-cli && cargo sqlx prepare --check` in your Continuous Integration script.
 ///
+/// The data of several databases can be kept in `sqlx-data.json` by running `cargo sqlx prepare`
+/// against each. The macros look a query up for each database whose feature is enabled; if the
+/// query has data for several of them, set `SQLX_OFFLINE_DB` to the name of the database to use,
+/// e.g. `PostgreSQL`.
+///
 /// See [the README for `sqlx-cli`](https://crates.io/crates/sqlx-cli) for more information.
 ///
 /// ## See Also
@@ -375,6 +491,26 @@ macro_rules! query_unchecked (
 /// # #[cfg(any(not(feature = "mysql"), not(feature = "_rt-async-std")))]
 /// # fn main() {}
 /// ```
+///
+/// ## Includes
+/// A line `-- sqlx:include "path"` of a query file is replaced by the contents of the file at
+/// `path`, relative to the including file, e.g. to share fragments between queries. Included files
+/// can include others, but not themselves. The macro is expanded again when any of these files
+/// changes, and offline, the query data is looked up by the expanded query.
+///
+/// `queries/fragments/tenant-filter.sql`:
+/// ```text
+/// tenant_id = $1 and deleted_at is null
+/// ```
+///
+/// `queries/invoices.sql`:
+/// ```text
+/// select id, total from invoices
+/// where
+/// -- sqlx:include "fragments/tenant-filter.sql"
+/// ```
+///
+/// If a file can't be read, the error lists the includes which led to it.
 #[macro_export]
 #[cfg_attr(docsrs, doc(cfg(feature = "macros")))]
 macro_rules! query_file (
@@ -557,6 +693,12 @@ macro_rules! query_as (
     ($out_struct:path, $query:expr) => ( {
         $crate::sqlx_macros::expand_query!(record = $out_struct, source = $query)
     });
+    ($out_struct:path, $query:expr, overrides = { $($overrides:tt)* }) => ( {
+        $crate::sqlx_macros::expand_query!(record = $out_struct, source = $query, overrides = { $($overrides)* })
+    });
+    ($out_struct:path, $query:expr, overrides = { $($overrides:tt)* }, $($args:tt)*) => ( {
+        $crate::sqlx_macros::expand_query!(record = $out_struct, source = $query, overrides = { $($overrides)* }, args = [$($args)*])
+    });
     ($out_struct:path, $query:expr, $($args:tt)*) => ( {
         $crate::sqlx_macros::expand_query!(record = $out_struct, source = $query, args = [$($args)*])
     })
@@ -601,6 +743,12 @@ macro_rules! query_file_as (
     ($out_struct:path, $path:literal) => ( {
         $crate::sqlx_macros::expand_query!(record = $out_struct, source_file = $path)
     });
+    ($out_struct:path, $path:literal, overrides = { $($overrides:tt)* }) => ( {
+        $crate::sqlx_macros::expand_query!(record = $out_struct, source_file = $path, overrides = { $($overrides)* })
+    });
+    ($out_struct:path, $path:literal, overrides = { $($overrides:tt)* }, $($args:tt)*) => ( {
+        $crate::sqlx_macros::expand_query!(record = $out_struct, source_file = $path, overrides = { $($overrides)* }, args = [$($args)*])
+    });
     ($out_struct:path, $path:literal, $($args:tt)*) => ( {
         $crate::sqlx_macros::expand_query!(record = $out_struct, source_file = $path, args = [$($args)*])
     })
@@ -726,6 +874,35 @@ macro_rules! query_file_scalar_unchecked (
 ///
 /// See [MigrationSource][crate::migrate::MigrationSource] for details on structure of the ./migrations directory.
 ///
+/// The table applied migrations are recorded in can be set with `table_name`, and qualified with
+/// a schema (see [Migrator::set_table_name()][crate::migrate::Migrator::set_table_name]):
+///
+/// ```rust,ignore
+/// static MIGRATOR: Migrator = sqlx::migrate!("migrations", table_name = "ops.billing_migrations");
+/// ```
+///
+/// Several directories can be given, e.g. migrations shared by all databases and those of a
+/// single one. Their migrations are sorted together by version, and two migrations with the same
+/// version are a compile error naming both files:
+///
+/// ```rust,ignore
+/// static MIGRATOR: Migrator = sqlx::migrate!("migrations/common", "migrations/postgres");
+/// ```
+///
+/// A directory prefixed with `workspace:` is relative to the root of the workspace, found with
+/// `cargo metadata`, e.g. for migrations shared by several crates of a workspace. A directory may
+/// also be read from an environment variable at compile time with `env!()`, as a path relative to
+/// the project root, prefixed with `workspace:`, or absolute; the crate is rebuilt when the
+/// variable changes:
+///
+/// ```rust,ignore
+/// static MIGRATOR: Migrator = sqlx::migrate!("workspace:migrations");
+///
+/// static MIGRATOR: Migrator = sqlx::migrate!(env!("MIGRATIONS_DIR"));
+/// ```
+///
+/// A directory which doesn't exist is a compile error naming the path tried.
+///
 /// ## Triggering Recompilation on Migration Changes
 /// In some cases when making changes to embedded migrations, such as adding a new migration without
 /// changing any Rust source files, you might find that `cargo build` doesn't actually do anything,
@@ -757,6 +934,10 @@ macro_rules! query_file_scalar_unchecked (
 ///
 /// You can run `sqlx migrate build-script` to generate this file automatically.
 ///
+/// For a `workspace:` directory, print its path relative to the project root instead, e.g.
+/// `cargo:rerun-if-changed=../migrations`. The variable of an `env!()` directory is tracked
+/// already, but not the directory it names.
+///
 /// See: [The Cargo Book: 3.8 Build Scripts; Outputs of the Build Script](https://doc.rust-lang.org/stable/cargo/reference/build-scripts.html#outputs-of-the-build-script)
 ///
 /// #### Nightly Rust: `cfg` Flag
@@ -790,11 +971,16 @@ macro_rules! query_file_scalar_unchecked (
 #[cfg(feature = "migrate")]
 #[macro_export]
 macro_rules! migrate {
-    ($dir:literal) => {{
-        $crate::sqlx_macros::migrate!($dir)
+    (table_name = $table_name:literal $(,)?) => {{
+        $crate::sqlx_macros::migrate!("./migrations", table_name = $table_name)
     }};
 
     () => {{
         $crate::sqlx_macros::migrate!("./migrations")
     }};
+
+    // the directories, then `table_name`
+    ($($args:tt)+) => {{
+        $crate::sqlx_macros::migrate!($($args)+)
+    }};
 }
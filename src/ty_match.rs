@@ -18,6 +18,34 @@ use std::marker::PhantomData;
 #[allow(clippy::just_underscores_and_digits)]
 pub fn same_type<T>(_1: &T, _2: &T) {}
 
+pub struct OptionRef<T>(PhantomData<T>);
+
+impl<T> OptionRef<T> {
+    pub fn new(_arg: &T) -> Self {
+        OptionRef(PhantomData)
+    }
+}
+
+pub trait OptionRefExt: Sized {
+    type Ref;
+
+    fn option_ref(self) -> Self::Ref {
+        panic!("only for type resolution")
+    }
+}
+
+impl<'a, T> OptionRefExt for OptionRef<&'a Option<T>> {
+    type Ref = Option<&'a T>;
+}
+
+impl<'a, T> OptionRefExt for OptionRef<&'a &'a Option<T>> {
+    type Ref = Option<&'a T>;
+}
+
+impl<T> OptionRefExt for &'_ OptionRef<T> {
+    type Ref = T;
+}
+
 pub struct WrapSame<T, U>(PhantomData<T>, PhantomData<U>);
 
 impl<T, U> WrapSame<T, U> {
@@ -62,24 +90,49 @@ impl<'a> MatchBorrowExt for MatchBorrow<Option<&'a str>, Option<String>> {
     type Matched = Option<&'a str>;
 }
 
-impl<'a> MatchBorrowExt for MatchBorrow<Option<&'a [u8]>, Option<Vec<u8>>> {
-    type Matched = Option<&'a [u8]>;
+impl<'a, T> MatchBorrowExt for MatchBorrow<Option<&'a [T]>, Option<Vec<T>>> {
+    type Matched = Option<&'a [T]>;
 }
 
 impl<'a> MatchBorrowExt for MatchBorrow<Option<&'a str>, Option<&'a String>> {
     type Matched = Option<&'a str>;
 }
 
-impl<'a> MatchBorrowExt for MatchBorrow<Option<&'a [u8]>, Option<&'a Vec<u8>>> {
-    type Matched = Option<&'a [u8]>;
+impl<'a> MatchBorrowExt for MatchBorrow<Option<&'a str>, Option<&'a &'a String>> {
+    type Matched = Option<&'a str>;
+}
+
+impl<'a, T> MatchBorrowExt for MatchBorrow<Option<&'a [T]>, Option<&'a Vec<T>>> {
+    type Matched = Option<&'a [T]>;
+}
+
+impl<'a, T> MatchBorrowExt for MatchBorrow<Option<&'a [T]>, Option<&'a &'a Vec<T>>> {
+    type Matched = Option<&'a [T]>;
 }
 
 impl<'a> MatchBorrowExt for MatchBorrow<&'a str, String> {
     type Matched = &'a str;
 }
 
-impl<'a> MatchBorrowExt for MatchBorrow<&'a [u8], Vec<u8>> {
-    type Matched = &'a [u8];
+impl<'a, T> MatchBorrowExt for MatchBorrow<&'a [T], Vec<T>> {
+    type Matched = &'a [T];
+}
+
+impl<'a, T, const N: usize> MatchBorrowExt for MatchBorrow<&'a [T], [T; N]> {
+    type Matched = &'a [T];
+}
+
+// arrays of `text` are expected as `&[String]`, but `&str` is also encoded as `text`
+impl<'a> MatchBorrowExt for MatchBorrow<&'a [String], Vec<&'_ str>> {
+    type Matched = &'a [String];
+}
+
+impl<'a> MatchBorrowExt for MatchBorrow<&'a [String], &'_ [&'_ str]> {
+    type Matched = &'a [String];
+}
+
+impl<'a> MatchBorrowExt for MatchBorrow<&'a [String], &'_ Vec<&'_ str>> {
+    type Matched = &'a [String];
 }
 
 impl<T> MatchBorrowExt for MatchBorrow<&'_ T, T> {
@@ -146,6 +199,15 @@ fn test_wrap_same() {
     }
 }
 
+#[test]
+fn test_option_ref() {
+    if false {
+        let _: Option<&i32> = OptionRef::new(&&Some(0i32)).option_ref();
+        let _: Option<i32> = OptionRef::new(&Some(0i32)).option_ref();
+        let _: &i32 = OptionRef::new(&&0i32).option_ref();
+    }
+}
+
 #[test]
 fn test_match_borrow() {
     if false {
@@ -160,5 +222,12 @@ fn test_match_borrow() {
 
         let (_, match_borrow) = MatchBorrow::new(0i64, &0i64);
         let _: i64 = match_borrow.match_borrow();
+
+        let (_, match_borrow) = MatchBorrow::new(&[0i64][..], &vec![0i64]);
+        let _: &[i64] = match_borrow.match_borrow();
+
+        let names = [String::new()];
+        let (_, match_borrow) = MatchBorrow::new(&names[..], &vec![""]);
+        let _: &[String] = match_borrow.match_borrow();
     }
 }
@@ -1,7 +1,28 @@
+#[cfg(any(feature = "postgres", feature = "mysql", feature = "sqlite"))]
+use sqlx::any::AnyKind;
 use sqlx::any::AnyRow;
-use sqlx::{Any, Connection, Executor, Row};
+use sqlx::{Any, AnyConnection, Connection, Decode, Encode, Executor, Row, Type};
 use sqlx_test::new;
 
+// binds the value as the parameter of a query which selects it back; the parameter is cast to
+// the type for PostgreSQL, which would otherwise reuse the types of a cached statement
+#[allow(dead_code, unused_variables)]
+async fn round_trip<T>(conn: &mut AnyConnection, pg_type: &str, value: T) -> anyhow::Result<T>
+where
+    T: for<'q> Encode<'q, Any> + for<'r> Decode<'r, Any> + Type<Any> + Send + Unpin,
+{
+    #[cfg(feature = "postgres")]
+    let sql = if conn.kind() == AnyKind::Postgres {
+        format!("SELECT $1::{}", pg_type)
+    } else {
+        "SELECT ?".to_owned()
+    };
+    #[cfg(not(feature = "postgres"))]
+    let sql = "SELECT ?".to_owned();
+
+    Ok(sqlx::query_scalar(&sql).bind(value).fetch_one(conn).await?)
+}
+
 #[sqlx_macros::test]
 async fn it_connects() -> anyhow::Result<()> {
     let mut conn = new::<Any>().await?;
@@ -126,3 +147,166 @@ async fn it_can_fail_and_recover_with_pool() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[cfg(all(feature = "uuid", not(feature = "mssql")))]
+#[sqlx_macros::test]
+async fn it_encodes_and_decodes_uuid() -> anyhow::Result<()> {
+    use sqlx::types::Uuid;
+
+    let mut conn = new::<Any>().await?;
+
+    let uuid = Uuid::parse_str("a6c8cbb8-14ef-4bd2-8d5d-ea0e3ab6f7b5")?;
+    assert_eq!(round_trip(&mut conn, "UUID", uuid).await?, uuid);
+
+    let none: Option<Uuid> = round_trip(&mut conn, "UUID", None::<Uuid>).await?;
+    assert_eq!(none, None);
+
+    Ok(())
+}
+
+#[cfg(all(feature = "json", not(feature = "mssql")))]
+#[sqlx_macros::test]
+async fn it_encodes_and_decodes_json() -> anyhow::Result<()> {
+    use serde::{Deserialize, Serialize};
+    use sqlx::types::{Json, JsonValue};
+
+    #[derive(Serialize, Deserialize)]
+    struct Friend {
+        name: String,
+        age: u32,
+    }
+
+    let mut conn = new::<Any>().await?;
+
+    let friend = Friend {
+        name: "Joe".to_owned(),
+        age: 33,
+    };
+    let Json(decoded) = round_trip(&mut conn, "JSONB", Json(friend)).await?;
+    assert_eq!(decoded.name, "Joe");
+    assert_eq!(decoded.age, 33);
+
+    let value = serde_json::json!({ "numbers": [1, 2, 3], "empty": null });
+    let decoded: JsonValue = round_trip(&mut conn, "JSONB", value.clone()).await?;
+    assert_eq!(decoded, value);
+
+    Ok(())
+}
+
+#[cfg(all(feature = "chrono", not(feature = "mssql")))]
+#[sqlx_macros::test]
+async fn it_encodes_and_decodes_chrono() -> anyhow::Result<()> {
+    use sqlx::types::chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
+
+    let mut conn = new::<Any>().await?;
+
+    let date = NaiveDate::from_ymd(2022, 2, 28);
+    assert_eq!(round_trip(&mut conn, "DATE", date).await?, date);
+
+    let time = NaiveTime::from_hms_micro(13, 5, 0, 500_000);
+    assert_eq!(round_trip(&mut conn, "TIME", time).await?, time);
+
+    let timestamp: NaiveDateTime = date.and_time(time);
+    assert_eq!(
+        round_trip(&mut conn, "TIMESTAMP", timestamp).await?,
+        timestamp
+    );
+
+    let timestamp_tz: DateTime<Utc> = Utc.from_utc_datetime(&timestamp);
+    assert_eq!(
+        round_trip(&mut conn, "TIMESTAMPTZ", timestamp_tz).await?,
+        timestamp_tz
+    );
+
+    Ok(())
+}
+
+#[cfg(feature = "postgres")]
+#[sqlx_macros::test]
+async fn it_downcasts_to_postgres() -> anyhow::Result<()> {
+    use sqlx::postgres::{PgConnection, PgRow};
+
+    let mut conn = new::<Any>().await?;
+
+    if conn.kind() != AnyKind::Postgres {
+        assert!(conn.downcast_ref::<PgConnection>().is_none());
+        return Ok(());
+    }
+
+    let options: sqlx::any::AnyConnectOptions = dotenvy::var("DATABASE_URL")?.parse()?;
+    assert!(options.as_postgres().is_some());
+
+    let pg = conn.downcast_mut::<PgConnection>().unwrap();
+    assert!(pg.server_version_num().is_some());
+
+    // the reference can be held across an await
+    pg.execute("LISTEN _sqlx_any_downcast").await?;
+    pg.execute("UNLISTEN _sqlx_any_downcast").await?;
+
+    let row = conn.fetch_one("SELECT 1::int4").await?;
+    let pg_row = row.downcast_ref::<PgRow>().unwrap();
+    assert_eq!(pg_row.try_get::<i32, _>(0)?, 1);
+
+    #[cfg(feature = "sqlite")]
+    assert!(conn
+        .downcast_ref::<sqlx::sqlite::SqliteConnection>()
+        .is_none());
+
+    Ok(())
+}
+
+#[cfg(feature = "mysql")]
+#[sqlx_macros::test]
+async fn it_downcasts_to_mysql() -> anyhow::Result<()> {
+    use sqlx::mysql::{MySqlConnection, MySqlRow};
+
+    let mut conn = new::<Any>().await?;
+
+    if conn.kind() != AnyKind::MySql {
+        assert!(conn.downcast_ref::<MySqlConnection>().is_none());
+        return Ok(());
+    }
+
+    let options: sqlx::any::AnyConnectOptions = dotenvy::var("DATABASE_URL")?.parse()?;
+    assert!(options.as_mysql().is_some());
+
+    let mysql = conn.downcast_mut::<MySqlConnection>().unwrap();
+    assert!(mysql.server_version().0 > 0);
+
+    let row = conn.fetch_one("SELECT 1").await?;
+    let mysql_row = row.downcast_ref::<MySqlRow>().unwrap();
+    assert_eq!(mysql_row.try_get::<i64, _>(0)?, 1);
+
+    Ok(())
+}
+
+#[cfg(feature = "sqlite")]
+#[sqlx_macros::test]
+async fn it_downcasts_to_sqlite() -> anyhow::Result<()> {
+    use sqlx::sqlite::{SqliteConnection, SqliteRow};
+
+    let mut conn = new::<Any>().await?;
+
+    if conn.kind() != AnyKind::Sqlite {
+        assert!(conn.downcast_ref::<SqliteConnection>().is_none());
+        return Ok(());
+    }
+
+    let options: sqlx::any::AnyConnectOptions = dotenvy::var("DATABASE_URL")?.parse()?;
+    assert!(options.as_sqlite().is_some());
+
+    let sqlite = conn.downcast_mut::<SqliteConnection>().unwrap();
+
+    // the reference can be held across an await
+    let plan = sqlite.explain_query_plan("SELECT * FROM tweet").await?;
+    assert!(!plan.nodes().is_empty());
+
+    let row = conn.fetch_one("SELECT 1").await?;
+    let sqlite_row = row.downcast_ref::<SqliteRow>().unwrap();
+    assert_eq!(sqlite_row.try_get::<i32, _>(0)?, 1);
+
+    #[cfg(feature = "postgres")]
+    assert!(row.downcast_ref::<sqlx::postgres::PgRow>().is_none());
+
+    Ok(())
+}
@@ -0,0 +1,214 @@
+use std::collections::HashMap;
+use std::env;
+use std::sync::{Arc, Mutex};
+
+use sqlx::any::AnyConnectOptions;
+use sqlx::{AnyConnection, ConnectOptions, Executor};
+use sqlx_test::new;
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Metadata, Subscriber};
+
+/// A span recorded by [`Spans`], with its fields formatted as strings.
+#[derive(Debug, Clone)]
+struct RecordedSpan {
+    name: &'static str,
+    parent: Option<u64>,
+    fields: HashMap<&'static str, String>,
+}
+
+impl RecordedSpan {
+    fn field(&self, name: &str) -> Option<&str> {
+        self.fields.get(name).map(String::as_str)
+    }
+}
+
+/// Records every span, identified by its index plus one.
+#[derive(Clone, Default)]
+struct Spans {
+    spans: Arc<Mutex<Vec<RecordedSpan>>>,
+    entered: Arc<Mutex<Vec<u64>>>,
+}
+
+impl Spans {
+    fn queries(&self) -> Vec<RecordedSpan> {
+        self.spans
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|span| span.name == "sqlx.query")
+            .cloned()
+            .collect()
+    }
+}
+
+struct Fields<'a>(&'a mut HashMap<&'static str, String>);
+
+impl Visit for Fields<'_> {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.insert(field.name(), value.to_owned());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        self.0.insert(field.name(), format!("{:?}", value));
+    }
+}
+
+impl Subscriber for Spans {
+    fn enabled(&self, _metadata: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, span: &Attributes<'_>) -> Id {
+        let parent = if span.is_contextual() {
+            self.entered.lock().unwrap().last().copied()
+        } else {
+            span.parent().map(Id::into_u64)
+        };
+
+        let mut fields = HashMap::new();
+        span.record(&mut Fields(&mut fields));
+
+        let mut spans = self.spans.lock().unwrap();
+
+        spans.push(RecordedSpan {
+            name: span.metadata().name(),
+            parent,
+            fields,
+        });
+
+        Id::from_u64(spans.len() as u64)
+    }
+
+    fn record(&self, span: &Id, values: &Record<'_>) {
+        let mut spans = self.spans.lock().unwrap();
+        let span = &mut spans[span.into_u64() as usize - 1];
+
+        values.record(&mut Fields(&mut span.fields));
+    }
+
+    fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+
+    fn event(&self, _event: &Event<'_>) {}
+
+    fn enter(&self, span: &Id) {
+        self.entered.lock().unwrap().push(span.into_u64());
+    }
+
+    fn exit(&self, _span: &Id) {
+        self.entered.lock().unwrap().pop();
+    }
+}
+
+async fn connect(configure: impl FnOnce(&mut AnyConnectOptions)) -> anyhow::Result<AnyConnection> {
+    sqlx_test::setup_if_needed();
+
+    let mut options: AnyConnectOptions = env::var("DATABASE_URL")?.parse()?;
+    configure(&mut options);
+
+    Ok(options.connect().await?)
+}
+
+#[sqlx_macros::test]
+async fn it_records_statements_on_spans() -> anyhow::Result<()> {
+    let mut conn = new::<sqlx::Any>().await?;
+
+    let spans = Spans::default();
+    let _guard = tracing::subscriber::set_default(spans.clone());
+
+    let parent = tracing::info_span!("parent");
+
+    let rows = {
+        let _entered = parent.enter();
+        conn.fetch_all("SELECT 1 + 1")
+    }
+    .await?;
+
+    assert_eq!(rows.len(), 1);
+
+    let queries = spans.queries();
+    assert_eq!(queries.len(), 1);
+
+    let span = &queries[0];
+
+    // the span of the statement is a child of the one it was executed in
+    assert_eq!(span.parent, parent.id().map(|id| id.into_u64()));
+    assert!(matches!(
+        span.field("db.system"),
+        Some("postgresql" | "mysql" | "sqlite" | "mssql")
+    ));
+    assert_eq!(span.field("otel.kind"), Some("client"));
+    assert_eq!(span.field("otel.name"), Some("SELECT"));
+    assert_eq!(span.field("db.operation"), Some("SELECT"));
+    assert_eq!(span.field("db.statement"), Some("SELECT 1 + 1"));
+    assert_eq!(span.field("db.rows_returned"), Some("1"));
+    assert!(span.field("duration_ms").is_some());
+    assert_eq!(span.field("otel.status_code"), None);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_records_errors_on_spans() -> anyhow::Result<()> {
+    let mut conn = new::<sqlx::Any>().await?;
+
+    let spans = Spans::default();
+    let _guard = tracing::subscriber::set_default(spans.clone());
+
+    let error = conn
+        .execute("SELECT * FROM table_that_does_not_exist")
+        .await
+        .unwrap_err();
+
+    let queries = spans.queries();
+    assert_eq!(queries.len(), 1);
+
+    let span = &queries[0];
+
+    assert_eq!(span.field("otel.status_code"), Some("ERROR"));
+    assert_eq!(span.field("otel.status_message"), Some(&*error.to_string()));
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_truncates_statements_on_spans() -> anyhow::Result<()> {
+    let mut conn = connect(|options| {
+        options.trace_statement_limit(11);
+    })
+    .await?;
+
+    let spans = Spans::default();
+    let _guard = tracing::subscriber::set_default(spans.clone());
+
+    conn.execute("SELECT 'àáâ'").await?;
+
+    let queries = spans.queries();
+    assert_eq!(queries.len(), 1);
+
+    // truncated at the end of `à`, rather than within `á`
+    assert_eq!(queries[0].field("db.statement"), Some("SELECT 'à…"));
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_omits_statements_which_are_not_logged_from_spans() -> anyhow::Result<()> {
+    let mut conn = connect(|options| {
+        options.disable_statement_logging();
+    })
+    .await?;
+
+    let spans = Spans::default();
+    let _guard = tracing::subscriber::set_default(spans.clone());
+
+    conn.execute("SELECT 1").await?;
+
+    let queries = spans.queries();
+    assert_eq!(queries.len(), 1);
+
+    assert_eq!(queries[0].field("db.statement"), None);
+    assert_eq!(queries[0].field("db.operation"), Some("SELECT"));
+
+    Ok(())
+}
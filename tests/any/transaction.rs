@@ -0,0 +1,128 @@
+#[cfg(any(feature = "postgres", feature = "sqlite"))]
+use sqlx::any::AnyKind;
+use sqlx::{Any, Connection, Error, Executor, IsolationLevel, TransactionOptions};
+use sqlx_test::new;
+
+#[sqlx_macros::test]
+async fn it_begins_with_an_isolation_level() -> anyhow::Result<()> {
+    let mut conn = new::<Any>().await?;
+
+    let options = TransactionOptions::new().isolation_level(IsolationLevel::Serializable);
+    let mut tx = conn.begin_with(options).await?;
+
+    #[cfg(feature = "postgres")]
+    if tx.kind() == AnyKind::Postgres {
+        let level: String = sqlx::query_scalar("SHOW transaction_isolation")
+            .fetch_one(&mut *tx)
+            .await?;
+
+        assert_eq!(level, "serializable");
+    }
+
+    let value: i32 = sqlx::query_scalar("SELECT 1").fetch_one(&mut *tx).await?;
+    assert_eq!(value, 1);
+
+    tx.commit().await?;
+
+    // the level only applies to the transaction
+    #[cfg(feature = "postgres")]
+    if conn.kind() == AnyKind::Postgres {
+        let mut tx = conn.begin().await?;
+
+        let level: String = sqlx::query_scalar("SHOW transaction_isolation")
+            .fetch_one(&mut *tx)
+            .await?;
+
+        assert_eq!(level, "read committed");
+    }
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_begins_a_read_only_transaction() -> anyhow::Result<()> {
+    let mut conn = new::<Any>().await?;
+
+    let options = TransactionOptions::new().read_only(true);
+
+    // SQLite only has read-only connections
+    #[cfg(feature = "sqlite")]
+    if conn.kind() == AnyKind::Sqlite {
+        let res = conn.begin_with(options).await;
+        assert!(matches!(res, Err(Error::Configuration(_))));
+
+        return Ok(());
+    }
+
+    let mut tx = conn.begin_with(options).await?;
+
+    let res = tx.execute("DELETE FROM tweet WHERE id = -1").await;
+    assert!(matches!(res, Err(Error::Database(_))));
+
+    tx.rollback().await?;
+
+    // the connection can write again
+    let mut tx = conn.begin().await?;
+    tx.execute("DELETE FROM tweet WHERE id = -1").await?;
+    tx.rollback().await?;
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_nests_savepoints_in_a_transaction_with_options() -> anyhow::Result<()> {
+    let mut conn = new::<Any>().await?;
+
+    conn.execute("CREATE TEMPORARY TABLE _sqlx_any_transactions (id INTEGER)")
+        .await?;
+
+    let options = TransactionOptions::new().isolation_level(IsolationLevel::ReadCommitted);
+    let mut tx = conn.begin_with(options).await?;
+
+    tx.execute("INSERT INTO _sqlx_any_transactions (id) VALUES (1)")
+        .await?;
+
+    let mut savepoint = tx.begin().await?;
+    savepoint
+        .execute("INSERT INTO _sqlx_any_transactions (id) VALUES (2)")
+        .await?;
+    savepoint.rollback().await?;
+
+    // the default options establish a savepoint too
+    let mut savepoint = tx.begin_with(TransactionOptions::new()).await?;
+    savepoint
+        .execute("INSERT INTO _sqlx_any_transactions (id) VALUES (3)")
+        .await?;
+    savepoint.commit().await?;
+
+    // but options can't be given for a savepoint
+    assert!(matches!(
+        tx.begin_with(options).await,
+        Err(Error::Configuration(_))
+    ));
+
+    tx.commit().await?;
+
+    let ids: Vec<i32> = sqlx::query_scalar("SELECT id FROM _sqlx_any_transactions ORDER BY id")
+        .fetch_all(&mut conn)
+        .await?;
+
+    assert_eq!(ids, [1, 3]);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_begins_with_options_from_a_pool() -> anyhow::Result<()> {
+    let pool = sqlx_test::pool::<Any>().await?;
+
+    let options = TransactionOptions::new().isolation_level(IsolationLevel::RepeatableRead);
+    let mut tx = pool.begin_with(options).await?;
+
+    let value: i32 = sqlx::query_scalar("SELECT 1").fetch_one(&mut tx).await?;
+    assert_eq!(value, 1);
+
+    tx.commit().await?;
+
+    Ok(())
+}
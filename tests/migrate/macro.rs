@@ -1,8 +1,25 @@
-use sqlx::migrate::Migrator;
+use futures::future::BoxFuture;
+use sqlx::error::BoxDynError;
+use sqlx::migrate::{MigrateError, Migration, MigrationSource, Migrator, DEFAULT_MIGRATIONS_TABLE};
 use std::path::Path;
 
 static EMBEDDED: Migrator = sqlx::migrate!("tests/migrate/migrations");
 
+static EMBEDDED_OPS: Migrator =
+    sqlx::migrate!("tests/migrate/migrations", table_name = "ops.migrations");
+
+static EMBEDDED_POSTGRES: Migrator = sqlx::migrate!(
+    "tests/migrate/migrations",
+    "tests/migrate/migrations_postgres",
+    table_name = "ops.migrations",
+);
+
+// this crate is the root of its workspace
+static EMBEDDED_WORKSPACE: Migrator = sqlx::migrate!("workspace:tests/migrate/migrations");
+
+// an absolute path, without migrations
+static EMBEDDED_ENV: Migrator = sqlx::migrate!(env!("CARGO_MANIFEST_DIR"));
+
 #[sqlx_macros::test]
 async fn same_output() -> anyhow::Result<()> {
     let runtime = Migrator::new(Path::new("tests/migrate/migrations")).await?;
@@ -14,7 +31,158 @@ async fn same_output() -> anyhow::Result<()> {
         assert_eq!(e.description, r.description);
         assert_eq!(e.sql, r.sql);
         assert_eq!(e.checksum, r.checksum);
+        assert_eq!(e.no_tx, r.no_tx);
+        assert_eq!(e.path, r.path);
+    }
+
+    assert!(EMBEDDED.iter().any(|m| m.no_tx));
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn multiple_dirs() -> anyhow::Result<()> {
+    let dirs = [
+        Path::new("tests/migrate/migrations"),
+        Path::new("tests/migrate/migrations_postgres"),
+    ];
+    let runtime = Migrator::new(&dirs[..]).await?;
+
+    // the migrations of both directories are sorted together by version
+    let versions: Vec<_> = EMBEDDED_POSTGRES.iter().map(|m| m.version).collect();
+    assert_eq!(
+        versions,
+        [
+            20200723212833,
+            20200723212837,
+            20200723212841,
+            20220721115250,
+            0
+        ]
+    );
+
+    let search = &EMBEDDED_POSTGRES.migrations[1];
+    assert!(search
+        .path
+        .as_deref()
+        .unwrap()
+        .ends_with("20200723212837_tweet_search.sql"));
+
+    assert_eq!(runtime.migrations.len(), EMBEDDED_POSTGRES.migrations.len());
+
+    for (e, r) in EMBEDDED_POSTGRES.iter().zip(runtime.iter()) {
+        assert_eq!(e.version, r.version);
+        assert_eq!(e.checksum, r.checksum);
+        assert_eq!(e.path, r.path);
+    }
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn duplicate_versions() -> anyhow::Result<()> {
+    let dirs = [
+        Path::new("tests/migrate/migrations"),
+        Path::new("tests/migrate/migrations_postgres"),
+        Path::new("tests/migrate/migrations"),
+    ];
+
+    match Migrator::new(&dirs[..]).await {
+        Err(MigrateError::DuplicateVersion(version, first, second)) => {
+            assert_eq!(version, 20200723212833);
+            assert!(first.ends_with("20200723212833_tweet.sql"), "{}", first);
+            assert!(second.ends_with("20200723212833_tweet.sql"), "{}", second);
+        }
+        res => panic!("expected a duplicate version, got {:?}", res),
+    }
+
+    Ok(())
+}
+
+/// Migrations kept in memory, named like files, e.g. loaded from an object store.
+struct InMemory(Vec<(&'static str, &'static str)>);
+
+impl MigrationSource<'static> for InMemory {
+    fn resolve(self) -> BoxFuture<'static, Result<Vec<Migration>, BoxDynError>> {
+        Box::pin(async move {
+            let mut migrations = Vec::new();
+
+            for (file_name, sql) in self.0 {
+                migrations.extend(Migration::from_file_name(file_name, sql.into())?);
+            }
+
+            Ok(migrations)
+        })
+    }
+}
+
+#[sqlx_macros::test]
+async fn custom_source() -> anyhow::Result<()> {
+    let in_memory = Migrator::new(InMemory(vec![
+        (
+            "20200723212837_tweet_search.sql",
+            include_str!("migrations_postgres/20200723212837_tweet_search.sql"),
+        ),
+        ("README.md", "not a migration"),
+    ]))
+    .await?;
+
+    // resolved like the same file of a directory
+    let from_dir = Migrator::new(Path::new("tests/migrate/migrations_postgres")).await?;
+
+    assert_eq!(in_memory.migrations.len(), 1);
+    assert_eq!(
+        in_memory.migrations[0].version,
+        from_dir.migrations[0].version
+    );
+    assert_eq!(
+        in_memory.migrations[0].description,
+        from_dir.migrations[0].description
+    );
+    assert_eq!(
+        in_memory.migrations[0].checksum,
+        from_dir.migrations[0].checksum
+    );
+
+    // mixed with embedded migrations
+    let mixed = Migrator::from_iter(EMBEDDED.iter().chain(in_memory.iter()).cloned())?;
+
+    assert_eq!(mixed.migrations.len(), EMBEDDED_POSTGRES.migrations.len());
+
+    for (m, e) in mixed.iter().zip(EMBEDDED_POSTGRES.iter()) {
+        assert_eq!(m.version, e.version);
+        assert_eq!(m.checksum, e.checksum);
     }
 
+    assert!(matches!(
+        Migrator::from_iter(EMBEDDED.iter().chain(EMBEDDED.iter()).cloned()),
+        Err(MigrateError::DuplicateVersion(..))
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn table_name() {
+    assert_eq!(EMBEDDED.table_name, DEFAULT_MIGRATIONS_TABLE);
+    assert_eq!(EMBEDDED_OPS.table_name, "ops.migrations");
+    assert_eq!(EMBEDDED_OPS.migrations.len(), EMBEDDED.migrations.len());
+    assert_eq!(EMBEDDED_POSTGRES.table_name, "ops.migrations");
+}
+
+#[sqlx_macros::test]
+async fn resolved_dirs() -> anyhow::Result<()> {
+    assert_eq!(
+        EMBEDDED_WORKSPACE.migrations.len(),
+        EMBEDDED.migrations.len()
+    );
+
+    for (w, e) in EMBEDDED_WORKSPACE.iter().zip(EMBEDDED.iter()) {
+        assert_eq!(w.version, e.version);
+        assert_eq!(w.path, e.path);
+    }
+
+    assert!(EMBEDDED_ENV.migrations.is_empty());
+
     Ok(())
 }
@@ -0,0 +1,2 @@
+-- sqlx:no-transaction
+CREATE INDEX CONCURRENTLY tweet_owner_id ON tweet (owner_id);
@@ -0,0 +1,2 @@
+CREATE VIEW IF NOT EXISTS active_accounts AS
+SELECT id, name FROM accounts WHERE is_active;
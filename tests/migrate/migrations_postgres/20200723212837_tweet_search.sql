@@ -0,0 +1 @@
+ALTER TABLE tweet ADD COLUMN search TSVECTOR GENERATED ALWAYS AS (to_tsvector('english', text)) STORED;
@@ -159,6 +159,56 @@ async fn test_query_bytes() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[sqlx_macros::test]
+async fn test_query_unsigned() -> anyhow::Result<()> {
+    let mut conn = new::<MySql>().await?;
+    let mut tx = conn.begin().await?;
+
+    let id = sqlx::query!(
+        "INSERT INTO unsigned_ints (tiny, medium, medium_signed) VALUES (?, ?, ?)",
+        u8::MAX,
+        16777215_u32,
+        -8388608_i32
+    )
+    .execute(&mut tx)
+    .await?
+    .last_insert_id();
+
+    let rec = sqlx::query!(
+        "SELECT id, tiny, medium, medium_signed FROM unsigned_ints WHERE id = ?",
+        id
+    )
+    .fetch_one(&mut tx)
+    .await?;
+
+    // the types are spelled out, as the assertions would pass with other integer types
+    let (tiny, medium, medium_signed): (u8, u32, i32) = (rec.tiny, rec.medium, rec.medium_signed);
+    let rec_id: u64 = rec.id;
+
+    assert_eq!(rec_id, id);
+    assert_eq!(tiny, u8::MAX);
+    assert_eq!(medium, 16777215);
+    assert_eq!(medium_signed, -8388608);
+
+    // `AUTO_INCREMENT` values as large as they get
+    sqlx::query!(
+        "INSERT INTO unsigned_ints (id, tiny, medium, medium_signed) VALUES (?, 0, 0, 0)",
+        u64::MAX
+    )
+    .execute(&mut tx)
+    .await?;
+
+    let max_id: u64 = sqlx::query_scalar!("SELECT MAX(id) as `id!` FROM unsigned_ints")
+        .fetch_one(&mut tx)
+        .await?;
+
+    assert_eq!(max_id, u64::MAX);
+
+    tx.rollback().await?;
+
+    Ok(())
+}
+
 #[sqlx_macros::test]
 async fn test_column_override_not_null() -> anyhow::Result<()> {
     let mut conn = new::<MySql>().await?;
@@ -186,6 +236,69 @@ async fn test_column_override_nullable() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[cfg(feature = "json")]
+#[sqlx_macros::test]
+async fn test_column_override_json() -> anyhow::Result<()> {
+    use sqlx::types::Json;
+
+    #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+    struct Friend {
+        name: String,
+        age: u32,
+    }
+
+    #[derive(Debug)]
+    struct Document {
+        id: i64,
+        document: Json<Friend>,
+        extra: Option<Json<Friend>>,
+    }
+
+    let mut conn = new::<MySql>().await?;
+    let mut tx = conn.begin().await?;
+
+    let joe = Friend {
+        name: "Joe".to_owned(),
+        age: 33,
+    };
+
+    let id = sqlx::query!(
+        "INSERT INTO json_documents (document, extra) VALUES (?, ?)",
+        Json(&joe),
+        Option::<Json<Friend>>::None
+    )
+    .execute(&mut tx)
+    .await?
+    .last_insert_id();
+
+    // without an override, MySQL maps `JSON` to `JsonValue` but MariaDB maps it to `String`
+    let record = sqlx::query!(
+        "SELECT document as `document: Json<Friend>`, extra as `extra: Json<Friend>` FROM json_documents WHERE id = ?",
+        id
+    )
+    .fetch_one(&mut tx)
+    .await?;
+
+    assert_eq!(record.document.0, joe);
+    assert!(record.extra.is_none());
+
+    let document = sqlx::query_as!(
+        Document,
+        "SELECT id, document as `document: _`, extra as `extra: _` FROM json_documents WHERE id = ?",
+        id
+    )
+    .fetch_one(&mut tx)
+    .await?;
+
+    assert_eq!(document.id as u64, id);
+    assert_eq!(document.document.0, joe);
+    assert!(document.extra.is_none());
+
+    tx.rollback().await?;
+
+    Ok(())
+}
+
 async fn with_test_row<'a>(
     conn: &'a mut MySqlConnection,
 ) -> anyhow::Result<(Transaction<'a, MySql>, MyInt)> {
@@ -1,5 +1,8 @@
 use futures::TryStreamExt;
-use sqlx::mysql::{MySql, MySqlConnection, MySqlPool, MySqlPoolOptions, MySqlRow};
+use sqlx::mysql::{
+    MySql, MySqlConnectOptions, MySqlConnection, MySqlPool, MySqlPoolOptions, MySqlRow,
+    MySqlServerFlavor, MySqlSslMode,
+};
 use sqlx::{Column, Connection, Executor, Row, Statement, TypeInfo};
 use sqlx_test::{new, setup_if_needed};
 use std::env;
@@ -446,3 +449,1026 @@ async fn it_can_work_with_transactions() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[sqlx_macros::test]
+async fn it_authenticates_with_caching_sha2_password_without_tls() -> anyhow::Result<()> {
+    let mut conn = new::<MySql>().await?;
+
+    let version: String = sqlx::query_scalar("SELECT VERSION()")
+        .fetch_one(&mut conn)
+        .await?;
+
+    // `caching_sha2_password` is only available from MySQL 8
+    if version.contains("MariaDB") || !version.starts_with('8') {
+        return Ok(());
+    }
+
+    conn.execute(
+        r#"
+CREATE USER IF NOT EXISTS 'sqlx_sha2'@'%' IDENTIFIED WITH caching_sha2_password BY 'sha2-password';
+CREATE USER IF NOT EXISTS 'sqlx_native'@'%' IDENTIFIED WITH mysql_native_password BY 'native-password';
+GRANT SELECT ON sqlx.* TO 'sqlx_sha2'@'%', 'sqlx_native'@'%';
+        "#,
+    )
+    .await?;
+
+    let (_, public_key): (String, String) =
+        sqlx::query_as("SHOW STATUS LIKE 'Caching_sha2_password_rsa_public_key'")
+            .fetch_one(&mut conn)
+            .await?;
+
+    let options = env::var("DATABASE_URL")?
+        .parse::<MySqlConnectOptions>()?
+        .ssl_mode(MySqlSslMode::Disabled)
+        .username("sqlx_sha2")
+        .password("sha2-password");
+
+    async fn check(options: &MySqlConnectOptions) -> anyhow::Result<()> {
+        let mut conn = MySqlConnection::connect_with(options).await?;
+
+        let value: i32 = sqlx::query_scalar("SELECT 1").fetch_one(&mut conn).await?;
+        assert_eq!(value, 1);
+
+        conn.close().await?;
+
+        Ok(())
+    }
+
+    // empty the authentication cache so that the server asks for the full password,
+    // which is encrypted with the public key it sends us
+    conn.execute("FLUSH PRIVILEGES").await?;
+    check(&options).await?;
+
+    // the password is now cached, so the fast path is taken
+    check(&options).await?;
+
+    // with the public key pinned, it is never requested from the server
+    let key_path = env::temp_dir().join("sqlx-mysql-server-public-key.pem");
+    std::fs::write(&key_path, public_key)?;
+
+    conn.execute("FLUSH PRIVILEGES").await?;
+    check(&options.clone().server_rsa_public_key_path(&key_path)).await?;
+
+    // the server switches to `mysql_native_password` for this user after the handshake
+    check(
+        &options
+            .clone()
+            .username("sqlx_native")
+            .password("native-password"),
+    )
+    .await?;
+
+    conn.execute("DROP USER 'sqlx_sha2'@'%', 'sqlx_native'@'%'")
+        .await?;
+
+    Ok(())
+}
+
+#[cfg(feature = "mysql-compression")]
+#[sqlx_macros::test]
+async fn it_compresses_the_connection() -> anyhow::Result<()> {
+    use sqlx::mysql::MySqlCompression;
+
+    let options = env::var("DATABASE_URL")?.parse::<MySqlConnectOptions>()?;
+
+    // 100 rows of 40 KB each, some of which is not trivially compressible
+    let query = r#"
+SELECT CONCAT(a.n, b.n, REPEAT(MD5(CONCAT(a.n, b.n)), 1250)) AS text
+FROM (SELECT 0 AS n UNION ALL SELECT 1 UNION ALL SELECT 2 UNION ALL SELECT 3 UNION ALL SELECT 4
+      UNION ALL SELECT 5 UNION ALL SELECT 6 UNION ALL SELECT 7 UNION ALL SELECT 8 UNION ALL SELECT 9) a
+CROSS JOIN (SELECT 0 AS n UNION ALL SELECT 1 UNION ALL SELECT 2 UNION ALL SELECT 3 UNION ALL SELECT 4
+      UNION ALL SELECT 5 UNION ALL SELECT 6 UNION ALL SELECT 7 UNION ALL SELECT 8 UNION ALL SELECT 9) b
+ORDER BY text
+    "#;
+
+    // sent to the server as a single packet of a few MB
+    let param = "sqlx".repeat(512 * 1024);
+
+    async fn fetch(
+        options: &MySqlConnectOptions,
+        query: &str,
+        param: &str,
+    ) -> anyhow::Result<(Vec<String>, String)> {
+        let mut conn = MySqlConnection::connect_with(options).await?;
+
+        let rows: Vec<String> = sqlx::query_scalar(query).fetch_all(&mut conn).await?;
+        let echoed: String = sqlx::query_scalar("SELECT CAST(? AS CHAR)")
+            .bind(param)
+            .fetch_one(&mut conn)
+            .await?;
+
+        conn.close().await?;
+
+        Ok((rows, echoed))
+    }
+
+    let (expected, echoed) = fetch(&options, query, &param).await?;
+    assert_eq!(expected.len(), 100);
+    assert_eq!(echoed, param);
+
+    for compression in [MySqlCompression::Zlib, MySqlCompression::Zstd { level: 3 }] {
+        let (rows, echoed) =
+            fetch(&options.clone().compression(compression), query, &param).await?;
+
+        assert_eq!(rows, expected, "{:?}", compression);
+        assert_eq!(echoed, param, "{:?}", compression);
+    }
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_can_load_data_local_infile() -> anyhow::Result<()> {
+    use futures::FutureExt;
+    use sqlx::mysql::MySqlLocalInfileHandler;
+    use std::io;
+    use std::sync::Arc;
+
+    let mut conn = new::<MySql>().await?;
+
+    // from MySQL 8, the server refuses `LOAD DATA LOCAL INFILE` by default
+    conn.execute("SET GLOBAL local_infile = 1").await?;
+
+    // a few MB, sent as many packets
+    let csv: String = (0..100_000)
+        .map(|i| format!("{},user {}\n", i, i))
+        .collect();
+
+    let path = env::temp_dir().join("sqlx-mysql-local-infile.csv");
+    std::fs::write(&path, csv)?;
+
+    let handler: MySqlLocalInfileHandler = Arc::new(move |filename| {
+        let path = (filename == "users.csv").then(|| path.clone());
+
+        async move {
+            match path {
+                Some(path) => Ok(Box::new(sqlx_rt::fs::File::open(path).await?) as _),
+                None => Err(io::ErrorKind::PermissionDenied.into()),
+            }
+        }
+        .boxed()
+    });
+
+    let options = env::var("DATABASE_URL")?.parse::<MySqlConnectOptions>()?;
+
+    let mut conn = MySqlConnection::connect_with(
+        &options
+            .clone()
+            .enable_local_infile(true)
+            .local_infile_handler(handler),
+    )
+    .await?;
+
+    conn.execute("CREATE TEMPORARY TABLE users (id INTEGER PRIMARY KEY, name TEXT NOT NULL)")
+        .await?;
+
+    let load = "LOAD DATA LOCAL INFILE 'users.csv' INTO TABLE users FIELDS TERMINATED BY ','";
+
+    let done = conn.execute(load).await?;
+    assert_eq!(done.rows_affected(), 100_000);
+
+    let (count, last): (i64, String) =
+        sqlx::query_as("SELECT COUNT(*), MAX(CONCAT(LPAD(id, 6, '0'), name)) FROM users")
+            .fetch_one(&mut conn)
+            .await?;
+
+    assert_eq!(count, 100_000);
+    assert_eq!(last, "099999user 99999");
+
+    // the handler decides which files are served
+    let error = conn
+        .execute("LOAD DATA LOCAL INFILE '/etc/passwd' INTO TABLE users")
+        .await
+        .unwrap_err();
+
+    assert!(
+        matches!(&error, sqlx::Error::Io(e) if e.kind() == io::ErrorKind::PermissionDenied),
+        "{:?}",
+        error
+    );
+
+    // the connection is still usable afterwards
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM users")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(count, 100_000);
+
+    conn.close().await?;
+
+    // without enabling it, no file is served
+    let mut conn = MySqlConnection::connect_with(&options).await?;
+
+    conn.execute("CREATE TEMPORARY TABLE users (id INTEGER PRIMARY KEY, name TEXT NOT NULL)")
+        .await?;
+
+    assert!(conn.execute(load).await.is_err());
+
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM users")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(count, 0);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_can_fetch_multiple_result_sets() -> anyhow::Result<()> {
+    let mut conn = new::<MySql>().await?;
+
+    // as a simple query, and as a prepared statement
+    for n in [0_i64, 10] {
+        let mut results = if n == 0 {
+            conn.fetch_multi("CALL multi_result_sets(0)")
+        } else {
+            conn.fetch_multi(sqlx::query("CALL multi_result_sets(?)").bind(n))
+        };
+
+        let result = results.next().await?.unwrap();
+        assert_eq!(result.columns().len(), 1);
+        assert_eq!(result.columns()[0].name(), "value");
+
+        let rows: Vec<MySqlRow> = result.try_collect().await?;
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].try_get::<i64, _>("value")?, n);
+
+        let result = results.next().await?.unwrap();
+        assert_eq!(result.columns().len(), 2);
+        assert_eq!(result.columns()[1].name(), "name");
+
+        let rows: Vec<(i64, String)> = result
+            .map_ok(|row| (row.get("value"), row.get("name")))
+            .try_collect()
+            .await?;
+        assert_eq!(rows, vec![(n + 1, "b".to_owned()), (n + 2, "c".to_owned())]);
+
+        // the status of the `CALL` itself
+        let result = results.next().await?.unwrap();
+        assert!(result.columns().is_empty());
+
+        assert!(results.next().await?.is_none());
+    }
+
+    // rows left unread are skipped
+    {
+        let mut results = conn.fetch_multi("SELECT 1; SELECT 2, 3; SELECT 4");
+
+        results.next().await?.unwrap();
+
+        let result = results.next().await?.unwrap();
+        assert_eq!(result.columns().len(), 2);
+
+        let mut result = results.next().await?.unwrap();
+        let row = result.try_next().await?.unwrap();
+        assert_eq!(row.try_get::<i64, _>(0)?, 4);
+        assert!(result.try_next().await?.is_none());
+
+        assert!(results.next().await?.is_none());
+    }
+
+    // dropping the cursor early leaves the connection usable
+    let mut results = conn.fetch_multi("CALL multi_result_sets(20)");
+    let mut result = results.next().await?.unwrap();
+    assert!(result.try_next().await?.is_some());
+    drop(results);
+
+    let value: i64 = sqlx::query_scalar("SELECT 5").fetch_one(&mut conn).await?;
+    assert_eq!(value, 5);
+
+    // as do other ways of executing a `CALL`
+    let rows = conn.fetch_all("CALL multi_result_sets(30)").await?;
+    assert_eq!(rows.len(), 3);
+
+    conn.execute("CALL multi_result_sets(40)").await?;
+
+    let value: i64 = sqlx::query_scalar("SELECT 6").fetch_one(&mut conn).await?;
+    assert_eq!(value, 6);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_sends_connect_attributes() -> anyhow::Result<()> {
+    let options: MySqlConnectOptions = env::var("DATABASE_URL")?.parse()?;
+    let options = options
+        .connect_attribute("program_name", "sqlx-test")
+        .connect_attribute("program_name", "sqlx-tests");
+
+    let mut conn = MySqlConnection::connect_with(&options).await?;
+
+    let attrs: Vec<(String, String)> = sqlx::query_as(
+        "SELECT ATTR_NAME, ATTR_VALUE FROM performance_schema.session_connect_attrs \
+         WHERE PROCESSLIST_ID = CONNECTION_ID() ORDER BY ATTR_NAME",
+    )
+    .fetch_all(&mut conn)
+    .await?;
+
+    // the performance schema is disabled by default in MariaDB
+    if attrs.is_empty() {
+        return Ok(());
+    }
+
+    let attr = |name: &str| {
+        attrs
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| &**value)
+    };
+
+    assert_eq!(attr("_client_name"), Some("sqlx"));
+    assert_eq!(attr("_client_version"), Some(env!("CARGO_PKG_VERSION")));
+    assert_eq!(attr("_os"), Some(env::consts::OS));
+    assert_eq!(attr("_pid"), Some(&*std::process::id().to_string()));
+    assert_eq!(attr("program_name"), Some("sqlx-tests"));
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_sends_query_attributes() -> anyhow::Result<()> {
+    let mut conn = new::<MySql>().await?;
+
+    // servers without support for query attributes ignore them
+    let (value,): (i64,) = sqlx::query_as("SELECT ?")
+        .bind(1_i64)
+        .attribute("trace_id", "ignored")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(value, 1);
+
+    // query attributes are read through a component, which only MySQL 8.0.23+ has
+    let installed = conn
+        .fetch_optional(
+            "SELECT component_urn FROM mysql.component \
+             WHERE component_urn = 'file://component_query_attributes'",
+        )
+        .await;
+
+    match installed {
+        Ok(Some(_)) => {}
+
+        Ok(None) => {
+            if conn
+                .execute("INSTALL COMPONENT 'file://component_query_attributes'")
+                .await
+                .is_err()
+            {
+                return Ok(());
+            }
+        }
+
+        Err(_) => return Ok(()),
+    }
+
+    let (trace_id, span_id, missing, value): (Option<String>, Option<String>, Option<String>, i64) =
+        sqlx::query_as(
+            "SELECT mysql_query_attribute_string('trace_id'), \
+             mysql_query_attribute_string('span_id'), \
+             mysql_query_attribute_string('missing'), ?",
+        )
+        .bind(2_i64)
+        .attribute("trace_id", "5c8a9d")
+        .attribute("span_id", 42_i32)
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(trace_id.as_deref(), Some("5c8a9d"));
+    assert_eq!(span_id.as_deref(), Some("42"));
+    assert_eq!(missing, None);
+    assert_eq!(value, 2);
+
+    // and without any arguments
+    let trace_id: Option<String> =
+        sqlx::query_scalar("SELECT mysql_query_attribute_string('trace_id')")
+            .attribute("trace_id", "7f3e21")
+            .fetch_one(&mut conn)
+            .await?;
+
+    assert_eq!(trace_id.as_deref(), Some("7f3e21"));
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_detects_the_server_flavor() -> anyhow::Result<()> {
+    let mut conn = new::<MySql>().await?;
+
+    let version: String = sqlx::query_scalar("SELECT VERSION()")
+        .fetch_one(&mut conn)
+        .await?;
+
+    let flavor = if version.contains("MariaDB") {
+        MySqlServerFlavor::MariaDb
+    } else {
+        MySqlServerFlavor::MySql
+    };
+
+    assert_eq!(conn.server_flavor(), flavor);
+
+    // unlike the handshake, `VERSION()` isn't prefixed with `5.5.5-` by MariaDB
+    let (major, minor, patch) = conn.server_version();
+    let prefix = format!("{}.{}.{}", major, minor, patch);
+    assert!(version.starts_with(&prefix), "{} in {}", prefix, version);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_returns_rows_from_dml_on_mariadb() -> anyhow::Result<()> {
+    let mut conn = new::<MySql>().await?;
+
+    // `INSERT ... RETURNING` was added in MariaDB 10.5
+    if conn.server_flavor() != MySqlServerFlavor::MariaDb || conn.server_version() < (10, 5, 0) {
+        return Ok(());
+    }
+
+    conn.execute(
+        "CREATE TEMPORARY TABLE returning_rows (id INT PRIMARY KEY AUTO_INCREMENT, name TEXT NOT NULL);",
+    )
+    .await?;
+
+    let inserted: Vec<(i32, String)> =
+        sqlx::query_as("INSERT INTO returning_rows (name) VALUES (?), (?) RETURNING id, name")
+            .bind("a")
+            .bind("b")
+            .fetch_all(&mut conn)
+            .await?;
+
+    assert_eq!(inserted, vec![(1, "a".to_owned()), (2, "b".to_owned())]);
+
+    let deleted: Vec<i32> =
+        sqlx::query_scalar("DELETE FROM returning_rows WHERE name = ? RETURNING id")
+            .bind("a")
+            .fetch_all(&mut conn)
+            .await?;
+
+    assert_eq!(deleted, vec![1]);
+
+    let row = conn
+        .fetch_one("DELETE FROM returning_rows RETURNING id")
+        .await?;
+
+    assert_eq!(row.try_get::<i32, _>(0)?, 2);
+
+    Ok(())
+}
+
+#[cfg(feature = "mysql-ed25519")]
+#[sqlx_macros::test]
+async fn it_authenticates_with_ed25519() -> anyhow::Result<()> {
+    let mut conn = new::<MySql>().await?;
+
+    // `USING PASSWORD(..)` for ed25519 was added in MariaDB 10.4
+    if conn.server_flavor() != MySqlServerFlavor::MariaDb || conn.server_version() < (10, 4, 0) {
+        return Ok(());
+    }
+
+    // fails if the plugin is already installed
+    let _ = conn.execute("INSTALL SONAME 'auth_ed25519'").await;
+
+    conn.execute(
+        r#"
+DROP USER IF EXISTS sqlx_ed25519;
+CREATE USER sqlx_ed25519 IDENTIFIED VIA ed25519 USING PASSWORD('s3cr3t!');
+GRANT SELECT ON sqlx.* TO sqlx_ed25519;
+        "#,
+    )
+    .await?;
+
+    let options: MySqlConnectOptions = env::var("DATABASE_URL")?.parse()?;
+    let options = options.username("sqlx_ed25519");
+
+    let result = async {
+        let mut user = MySqlConnection::connect_with(&options.clone().password("s3cr3t!")).await?;
+
+        let current: String = sqlx::query_scalar("SELECT CURRENT_USER()")
+            .fetch_one(&mut user)
+            .await?;
+
+        assert!(current.starts_with("sqlx_ed25519@"), "{}", current);
+
+        assert!(MySqlConnection::connect_with(&options.password("wrong"))
+            .await
+            .is_err());
+
+        anyhow::Ok(())
+    }
+    .await;
+
+    conn.execute("DROP USER sqlx_ed25519").await?;
+
+    result
+}
+
+#[cfg(feature = "migrate")]
+#[sqlx_macros::test]
+async fn it_locks_migrations_with_a_timeout() -> anyhow::Result<()> {
+    use sqlx::migrate::{Locking, Migrate, MigrateError, Migrator};
+    use std::borrow::Cow;
+    use std::time::{Duration, Instant};
+
+    let mut conn = new::<MySql>().await?;
+    let mut holder = new::<MySql>().await?;
+
+    let mut migrator = Migrator {
+        migrations: Cow::Owned(Vec::new()),
+        ignore_missing: false,
+        allow_out_of_order: false,
+        table_name: "lock_test_migrations".into(),
+        locking: Locking::default(),
+        applied_by: None,
+        seeds: Cow::Borrowed(&[]),
+        after_run: Cow::Borrowed(&[]),
+    };
+
+    let id: u64 = sqlx::query_scalar("SELECT CONNECTION_ID()")
+        .fetch_one(&mut holder)
+        .await?;
+
+    holder.lock("lock_test_migrations", None).await?;
+
+    migrator.set_locking(Locking::Fail);
+    match migrator.run(&mut conn).await {
+        Err(MigrateError::LockTimeout(Some(holder))) => {
+            assert!(holder.contains(&id.to_string()), "{}", holder)
+        }
+        res => panic!("expected a lock timeout, got {:?}", res),
+    }
+
+    migrator.set_locking(Locking::Wait {
+        timeout: Some(Duration::from_millis(200)),
+    });
+    let started = Instant::now();
+    assert!(matches!(
+        migrator.run(&mut conn).await,
+        Err(MigrateError::LockTimeout(_))
+    ));
+    assert!(started.elapsed() >= Duration::from_millis(200));
+
+    migrator.set_locking(Locking::None);
+    migrator.run(&mut conn).await?;
+
+    holder.unlock("lock_test_migrations").await?;
+
+    migrator.set_locking(Locking::default());
+    migrator.run(&mut conn).await?;
+
+    conn.execute("DROP TABLE lock_test_migrations").await?;
+
+    Ok(())
+}
+
+#[cfg(feature = "migrate")]
+#[sqlx_macros::test]
+async fn it_force_drops_a_database_in_use() -> anyhow::Result<()> {
+    use sqlx::migrate::MigrateDatabase;
+
+    // needs quoting, and is left in the URL as is
+    let base = dotenvy::var("DATABASE_URL")?;
+    let (base, params) = base.split_once('?').unwrap_or((&base, ""));
+    let url = format!(
+        "{}/sqlx-Force_Drop?{}",
+        base.rsplit_once('/').unwrap().0,
+        params
+    );
+
+    MySql::force_drop_database(&url).await?;
+    MySql::create_database(&url).await?;
+    assert!(MySql::database_exists(&url).await?);
+
+    let mut conn = MySqlConnection::connect(&url).await?;
+    conn.ping().await?;
+
+    MySql::force_drop_database(&url).await?;
+    assert!(!MySql::database_exists(&url).await?);
+
+    // the connection was terminated
+    assert!(conn.ping().await.is_err());
+
+    // and dropping a database which doesn't exist succeeds
+    MySql::force_drop_database(&url).await?;
+
+    Ok(())
+}
+
+#[cfg(feature = "migrate")]
+#[sqlx_macros::test]
+async fn it_creates_a_database_with_options() -> anyhow::Result<()> {
+    use sqlx::migrate::{CreateDatabaseOptions, MigrateDatabase};
+
+    let base = dotenvy::var("DATABASE_URL")?;
+    let (base, params) = base.split_once('?').unwrap_or((&base, ""));
+    let url = format!(
+        "{}/sqlx-Create_Options?{}",
+        base.rsplit_once('/').unwrap().0,
+        params
+    );
+
+    MySql::force_drop_database(&url).await?;
+    MySql::create_database_with(
+        &url,
+        &CreateDatabaseOptions::new()
+            .charset("latin1")
+            .collation("latin1_bin"),
+    )
+    .await?;
+
+    let mut conn = new::<MySql>().await?;
+    let (charset, collation): (String, String) = sqlx::query_as(
+        "SELECT DEFAULT_CHARACTER_SET_NAME, DEFAULT_COLLATION_NAME \
+         FROM information_schema.SCHEMATA WHERE SCHEMA_NAME = ?",
+    )
+    .bind("sqlx-Create_Options")
+    .fetch_one(&mut conn)
+    .await?;
+
+    assert_eq!(charset, "latin1");
+    assert_eq!(collation, "latin1_bin");
+
+    MySql::force_drop_database(&url).await?;
+
+    // PostgreSQL's options don't apply
+    let res =
+        MySql::create_database_with(&url, &CreateDatabaseOptions::new().template("base")).await;
+    assert!(matches!(res, Err(sqlx::Error::Configuration(_))));
+    assert!(!MySql::database_exists(&url).await?);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_inspects_constraint_violations() -> anyhow::Result<()> {
+    use sqlx::error::{DatabaseError, ErrorKind};
+
+    async fn violate(conn: &mut MySqlConnection, sql: &str) -> Box<dyn DatabaseError> {
+        conn.execute(sql)
+            .await
+            .unwrap_err()
+            .into_database_error()
+            .unwrap()
+    }
+
+    let mut conn = new::<MySql>().await?;
+
+    // temporary tables can't have foreign keys
+    conn.execute(
+        r#"
+DROP TABLE IF EXISTS _sqlx_children, _sqlx_parents;
+CREATE TABLE _sqlx_parents (id INTEGER PRIMARY KEY);
+CREATE TABLE _sqlx_children (
+    id INTEGER PRIMARY KEY,
+    parent_id INTEGER,
+    name TEXT NOT NULL,
+    age INTEGER,
+    CONSTRAINT _sqlx_children_parent_fk FOREIGN KEY (parent_id) REFERENCES _sqlx_parents (id),
+    CONSTRAINT _sqlx_children_age_check CHECK (age >= 0)
+);
+INSERT INTO _sqlx_parents (id) VALUES (1);
+INSERT INTO _sqlx_children (id, parent_id, name, age) VALUES (1, 1, 'first', 1);
+        "#,
+    )
+    .await?;
+
+    let err = violate(
+        &mut conn,
+        "INSERT INTO _sqlx_children (id, name) VALUES (1, 'again')",
+    )
+    .await;
+    assert_eq!(err.kind(), ErrorKind::UniqueViolation);
+    assert_eq!(err.constraint(), Some("PRIMARY"));
+
+    let err = violate(
+        &mut conn,
+        "INSERT INTO _sqlx_children (id, parent_id, name) VALUES (2, 2, 'orphan')",
+    )
+    .await;
+    assert_eq!(err.kind(), ErrorKind::ForeignKeyViolation);
+    assert_eq!(err.constraint(), Some("_sqlx_children_parent_fk"));
+    assert_eq!(err.table(), Some("_sqlx_children"));
+    assert_eq!(err.column(), Some("parent_id"));
+    assert!(err.schema().is_some());
+
+    let err = violate(
+        &mut conn,
+        "INSERT INTO _sqlx_children (id, name) VALUES (3, NULL)",
+    )
+    .await;
+    assert_eq!(err.kind(), ErrorKind::NotNullViolation);
+    assert_eq!(err.column(), Some("name"));
+
+    // MySQL only enforces `CHECK` constraints as of 8.0.16
+    let res = conn
+        .execute("INSERT INTO _sqlx_children (id, name, age) VALUES (4, 'unborn', -1)")
+        .await;
+
+    if let Err(err) = res {
+        let err = err.into_database_error().unwrap();
+        assert_eq!(err.kind(), ErrorKind::CheckViolation);
+        assert_eq!(err.constraint(), Some("_sqlx_children_age_check"));
+    }
+
+    conn.execute("DROP TABLE _sqlx_children, _sqlx_parents")
+        .await?;
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_captures_the_statement_on_error() -> anyhow::Result<()> {
+    use sqlx::ConnectOptions;
+
+    setup_if_needed();
+
+    let mut options: MySqlConnectOptions = env::var("DATABASE_URL")?.parse()?;
+    options.capture_statement_on_error(true);
+
+    let mut conn = MySqlConnection::connect_with(&options).await?;
+
+    let err = sqlx::query("SELECT * FROM missing WHERE name = ?")
+        .bind("secret")
+        .fetch_one(&mut conn)
+        .await
+        .unwrap_err();
+
+    let context = err.context().unwrap();
+    assert_eq!(context.sql(), "SELECT * FROM missing WHERE name = ?");
+    assert_eq!(context.parameters(), 1);
+    assert!(err.as_database_error().is_some());
+    assert!(!err.to_string().contains("secret"));
+
+    let err = sqlx::query_scalar::<_, i64>("SELECT 'text' AS name")
+        .fetch_one(&mut conn)
+        .await
+        .unwrap_err();
+
+    let column = err.context().unwrap().column().unwrap();
+    assert_eq!(column.index(), 0);
+    assert_eq!(column.name(), "name");
+    assert_eq!(column.rust_type(), "i64");
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_round_trips_quoted_identifiers_and_literals() -> anyhow::Result<()> {
+    use rand::{Rng, SeedableRng};
+    use rand_xoshiro::Xoshiro256PlusPlus;
+    use sqlx::Database;
+
+    let mut conn = new::<MySql>().await?;
+
+    // names can't end with a space in MySQL
+    let alphabet = [
+        '"', '\'', '`', '\\', '[', ']', ';', '-', '$', '?', 'a', 'Z', '0', 'é', '✓',
+    ];
+    let mut rng = Xoshiro256PlusPlus::seed_from_u64(0xC0FFEE);
+
+    let mut names: Vec<String> = vec![
+        r#"with "double" quotes"#.into(),
+        "with `backticks`".into(),
+        "with 'single' quotes".into(),
+        "with [brackets]]".into(),
+        r"back\slash\".into(),
+        "'; DROP TABLE users; --".into(),
+        "$1 ?2 :name @p3".into(),
+        "ünïcødé ✓".into(),
+    ];
+
+    names.extend((0..32).map(|_| {
+        let len = rng.gen_range(1..=16);
+
+        (0..len)
+            .map(|_| alphabet[rng.gen_range(0..alphabet.len())])
+            .collect::<String>()
+    }));
+
+    for name in &names {
+        let table = MySql::quote_identifier(name)?;
+
+        conn.execute(&*format!(
+            "CREATE TEMPORARY TABLE {} ({} TEXT)",
+            table, table
+        ))
+        .await?;
+
+        conn.execute(&*format!(
+            "INSERT INTO {} VALUES ({})",
+            table,
+            MySql::quote_literal(name)?
+        ))
+        .await?;
+
+        let row = conn
+            .fetch_one(&*format!("SELECT {} FROM {}", table, table))
+            .await?;
+
+        assert_eq!(row.column(0).name(), name);
+        assert_eq!(row.try_get::<String, _>(0)?, *name);
+
+        conn.execute(&*format!("DROP TABLE {}", table)).await?;
+    }
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_pings_inside_and_outside_transactions() -> anyhow::Result<()> {
+    use std::time::Duration;
+
+    let mut conn = new::<MySql>().await?;
+
+    conn.ping().await?;
+    conn.ping_ex().await?;
+
+    let mut tx = conn.begin().await?;
+
+    tx.ping().await?;
+    let result = tx.ping_ex().await?;
+    assert!(result.round_trip < Duration::from_secs(10));
+
+    tx.rollback().await?;
+
+    conn.ping_ex().await?;
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_gets_the_server_time() -> anyhow::Result<()> {
+    use std::time::{Duration, SystemTime};
+
+    let mut conn = new::<MySql>().await?;
+
+    let before = SystemTime::now();
+    let server_time = conn.server_time().await?;
+    let after = SystemTime::now();
+
+    // the server runs on the same host in CI, so its clock shouldn't be skewed by much
+    let skew = Duration::from_secs(60);
+    assert!(server_time > before - skew && server_time < after + skew);
+
+    // unlike `now()`, it isn't frozen at the start of a transaction
+    let mut tx = conn.begin().await?;
+    let first = tx.server_time().await?;
+    sqlx_rt::sleep(Duration::from_millis(10)).await;
+    assert!(tx.server_time().await? > first);
+    tx.rollback().await?;
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_executes_and_closes_prepared_statements() -> anyhow::Result<()> {
+    let mut conn = new::<MySql>().await?;
+
+    let stmt = conn.prepare("SELECT CAST(? AS SIGNED) + 1").await?;
+
+    for i in 0..3_i64 {
+        let value: i64 = stmt.query_scalar().bind(i).fetch_one(&mut conn).await?;
+        assert_eq!(value, i + 1);
+    }
+
+    let prepared = conn.prepared_statements().await?;
+
+    assert_eq!(prepared.len(), 1);
+    assert_eq!(prepared[0].sql, "SELECT CAST(? AS SIGNED) + 1");
+    assert_eq!(prepared[0].hits, 3);
+
+    // the statement is bound to the connection which prepared it, unless detached
+    let mut other = new::<MySql>().await?;
+    let res = stmt.query().bind(1_i64).execute(&mut other).await;
+
+    assert!(matches!(
+        res,
+        Err(sqlx::Error::StatementConnectionMismatch { .. })
+    ));
+
+    let mut detached = stmt.clone();
+    detached.detach();
+
+    let value: i64 = detached
+        .query_scalar()
+        .bind(1_i64)
+        .fetch_one(&mut other)
+        .await?;
+
+    assert_eq!(value, 2);
+
+    conn.close_statement(stmt.clone()).await?;
+
+    assert!(conn.prepared_statements().await?.is_empty());
+
+    // a closed statement is prepared again
+    let value: i64 = stmt
+        .query_scalar()
+        .bind(41_i64)
+        .fetch_one(&mut conn)
+        .await?;
+    assert_eq!(value, 42);
+
+    Ok(())
+}
+
+#[cfg(feature = "serde_row")]
+#[sqlx_macros::test]
+async fn it_deserializes_rows_with_serde() -> anyhow::Result<()> {
+    use sqlx::serde_row::DeserializeRow;
+
+    let mut conn = new::<MySql>().await?;
+
+    let row = sqlx::query(
+        "SELECT CAST(1 AS SIGNED) AS id, CAST(2 AS UNSIGNED) AS count, 'hello' AS body, \
+         CAST('{\"tags\": [\"a\"]}' AS JSON) AS data, CAST('2022-02-28 13:05:00.5' AS DATETIME(1)) AS at, \
+         NULL AS missing",
+    )
+    .fetch_one(&mut conn)
+    .await?;
+
+    let value: serde_json::Value = row.deserialize()?;
+
+    assert_eq!(
+        value,
+        serde_json::json!({
+            "id": 1,
+            "count": 2,
+            "body": "hello",
+            "data": { "tags": ["a"] },
+            "at": "2022-02-28 13:05:00.5",
+            "missing": null,
+        })
+    );
+
+    // in the binary format, the fractional seconds of a value are always formatted as microseconds
+    let value: serde_json::Value =
+        sqlx::query("SELECT CAST('2022-02-28 13:05:00.5' AS DATETIME(1)) AS at, ? AS body")
+            .bind("hello")
+            .fetch_one_deserialized(&mut conn)
+            .await?;
+
+    assert_eq!(
+        value,
+        serde_json::json!({ "at": "2022-02-28 13:05:00.500000", "body": "hello" })
+    );
+
+    Ok(())
+}
+
+#[cfg(feature = "json")]
+#[sqlx_macros::test]
+async fn it_decodes_dynamic_values() -> anyhow::Result<()> {
+    use serde_json::{json, Map, Value};
+    use sqlx::mysql::MySqlDynamicValue;
+
+    let mut conn = new::<MySql>().await?;
+
+    let sql = r#"
+SELECT NULL                                        AS `null`,
+       CAST(-4 AS SIGNED)                          AS `int`,
+       CAST(8 AS UNSIGNED)                         AS `uint`,
+       CAST(1.5 AS DOUBLE)                         AS `double`,
+       CAST('-12.3400' AS DECIMAL(10, 4))          AS `decimal`,
+       'hello'                                     AS `text`,
+       X'deadbeef'                                 AS `bytes`,
+       CAST('2022-02-28' AS DATE)                  AS `date`,
+       CAST('-838:59:59' AS TIME)                  AS `time`,
+       CAST('2022-02-28 13:05:00' AS DATETIME)     AS `datetime`
+"#;
+
+    fn to_json(row: &MySqlRow) -> Result<Value, sqlx::Error> {
+        let mut object = Map::new();
+
+        for column in row.columns() {
+            let value = row.try_get_dynamic(column.ordinal())?;
+            object.insert(column.name().to_owned(), value.into());
+        }
+
+        Ok(Value::Object(object))
+    }
+
+    let expected = json!({
+        "null": null,
+        "int": -4,
+        "uint": 8,
+        "double": 1.5,
+        "decimal": "-12.3400",
+        "text": "hello",
+        "bytes": "0xdeadbeef",
+        "date": "2022-02-28",
+        "time": "-838:59:59",
+        "datetime": "2022-02-28 13:05:00",
+    });
+
+    // prepared queries return values in the binary protocol
+    let row = sqlx::query(sql).fetch_one(&mut conn).await?;
+    assert_eq!(to_json(&row)?, expected);
+
+    assert_eq!(row.try_get_dynamic("int")?, MySqlDynamicValue::Int(-4));
+
+    // simple queries return values in the text protocol, which are formatted the same
+    let row = conn.fetch_one(sql).await?;
+    assert_eq!(to_json(&row)?, expected);
+
+    Ok(())
+}
@@ -6,3 +6,29 @@ CREATE TABLE tweet
     text       TEXT      NOT NULL,
     owner_id   BIGINT
 );
+
+-- returns two result sets, followed by the status of the `CALL`
+DELIMITER //
+CREATE PROCEDURE multi_result_sets(IN n BIGINT)
+BEGIN
+    SELECT n AS value;
+    SELECT n + 1 AS value, 'b' AS name
+    UNION ALL
+    SELECT n + 2, 'c';
+END //
+DELIMITER ;
+
+CREATE TABLE unsigned_ints
+(
+    id            BIGINT UNSIGNED PRIMARY KEY AUTO_INCREMENT,
+    tiny          TINYINT UNSIGNED NOT NULL,
+    medium        MEDIUMINT UNSIGNED NOT NULL,
+    medium_signed MEDIUMINT NOT NULL
+);
+
+CREATE TABLE json_documents
+(
+    id       BIGINT PRIMARY KEY AUTO_INCREMENT,
+    document JSON NOT NULL,
+    extra    JSON NULL
+);
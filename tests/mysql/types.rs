@@ -9,17 +9,63 @@ use sqlx_test::{new, test_type};
 
 test_type!(bool(MySql, "false" == false, "true" == true));
 
-test_type!(u8(MySql, "CAST(253 AS UNSIGNED)" == 253_u8));
+test_type!(u8(
+    MySql,
+    "CAST(253 AS UNSIGNED)" == 253_u8,
+    "CAST(255 AS UNSIGNED)" == u8::MAX
+));
 test_type!(i8(MySql, "5" == 5_i8, "0" == 0_i8));
 
-test_type!(u16(MySql, "CAST(21415 AS UNSIGNED)" == 21415_u16));
+test_type!(u16(
+    MySql,
+    "CAST(21415 AS UNSIGNED)" == 21415_u16,
+    "CAST(65535 AS UNSIGNED)" == u16::MAX
+));
 test_type!(i16(MySql, "21415" == 21415_i16));
 
-test_type!(u32(MySql, "CAST(2141512 AS UNSIGNED)" == 2141512_u32));
+test_type!(u32(
+    MySql,
+    "CAST(2141512 AS UNSIGNED)" == 2141512_u32,
+    "CAST(4294967295 AS UNSIGNED)" == u32::MAX
+));
 test_type!(i32(MySql, "2141512" == 2141512_i32));
 
-test_type!(u64(MySql, "CAST(2141512 AS UNSIGNED)" == 2141512_u64));
-test_type!(i64(MySql, "2141512" == 2141512_i64));
+test_type!(u64(
+    MySql,
+    "CAST(2141512 AS UNSIGNED)" == 2141512_u64,
+    "CAST(18446744073709551615 AS UNSIGNED)" == u64::MAX
+));
+test_type!(i64(
+    MySql,
+    "2141512" == 2141512_i64,
+    "CAST(9223372036854775807 AS SIGNED)" == i64::MAX,
+    "CAST(-9223372036854775808 AS SIGNED)" == i64::MIN
+));
+
+#[sqlx_macros::test]
+async fn test_unsigned_out_of_range_for_signed() -> anyhow::Result<()> {
+    let mut conn = new::<MySql>().await?;
+
+    let sql = "SELECT CAST(18446744073709551615 AS UNSIGNED), CAST(9223372036854775807 AS UNSIGNED), CAST(255 AS UNSIGNED)";
+
+    let text = conn.fetch_one(sql).await?;
+    let binary = sqlx::query(sql).fetch_one(&mut conn).await?;
+
+    for row in [text, binary] {
+        assert_eq!(row.try_get::<u64, _>(0)?, u64::MAX);
+
+        // rather than wrapping around to -1
+        let error = row.try_get::<i64, _>(0).unwrap_err().to_string();
+        assert!(error.contains("18446744073709551615"), "{}", error);
+
+        assert_eq!(row.try_get::<i64, _>(1)?, i64::MAX);
+
+        assert_eq!(row.try_get::<i16, _>(2)?, 255);
+        assert!(row.try_get::<i8, _>(2).is_err());
+    }
+
+    Ok(())
+}
 
 test_type!(f64(MySql, "3.14159265e0" == 3.14159265_f64));
 
@@ -42,6 +88,40 @@ test_type!(bytes<Vec<u8>>(MySql,
         == vec![0_u8, 0, 0, 0, 0x52]
 ));
 
+test_type!(bytes_shared<sqlx::types::Bytes>(MySql,
+    "X'DEADBEEF'"
+        == sqlx::types::Bytes::from_static(&[0xDE, 0xAD, 0xBE, 0xEF]),
+    "X''"
+        == sqlx::types::Bytes::new()
+));
+
+#[sqlx_macros::test]
+async fn test_bytes_shares_row_buffer() -> anyhow::Result<()> {
+    use sqlx::types::Bytes;
+
+    let mut conn = new::<MySql>().await?;
+
+    // text and binary protocols
+    for row in [
+        conn.fetch_one("SELECT REPEAT(X'AB', 1048576)").await?,
+        conn.fetch_one(sqlx::query("SELECT REPEAT(X'AB', 1048576)"))
+            .await?,
+    ] {
+        let slice: &[u8] = row.try_get(0)?;
+        let bytes: Bytes = row.try_get(0)?;
+
+        // the value is a view of the row, not a copy of it
+        assert_eq!(bytes.as_ptr(), slice.as_ptr());
+        assert_eq!(bytes.len(), 1048576);
+
+        // and outlives the row
+        drop(row);
+        assert!(bytes.iter().all(|b| *b == 0xAB));
+    }
+
+    Ok(())
+}
+
 #[cfg(feature = "uuid")]
 test_type!(uuid<sqlx::types::Uuid>(MySql,
     "x'b731678f636f4135bc6f19440c13bd19'"
@@ -125,6 +205,162 @@ mod chrono {
 
         Ok(())
     }
+
+    #[sqlx_macros::test]
+    async fn test_type_chrono_zero_date_behavior() -> anyhow::Result<()> {
+        use sqlx::mysql::{MySqlConnectOptions, MySqlConnection, MySqlZeroDateBehavior};
+        use sqlx::Connection;
+
+        let options: MySqlConnectOptions = std::env::var("DATABASE_URL")?.parse()?;
+        let min = NaiveDate::from_ymd(1000, 1, 1);
+
+        for behavior in [
+            MySqlZeroDateBehavior::Error,
+            MySqlZeroDateBehavior::ConvertToNone,
+            MySqlZeroDateBehavior::ConvertToMin,
+        ] {
+            let mut conn =
+                MySqlConnection::connect_with(&options.clone().zero_dates(behavior)).await?;
+
+            conn.execute(
+                r#"
+SET SESSION sql_mode = '';
+CREATE TEMPORARY TABLE zero_dates (d DATE, dt DATETIME, ts TIMESTAMP NULL DEFAULT NULL, bad DATE);
+INSERT INTO zero_dates VALUES ('0000-00-00', '0000-00-00 00:00:00', '0000-00-00 00:00:00', '2020-00-15');
+            "#,
+            )
+            .await?;
+
+            let text = conn.fetch_one("SELECT * FROM zero_dates").await?;
+            let binary = sqlx::query("SELECT * FROM zero_dates")
+                .fetch_one(&mut conn)
+                .await?;
+
+            for row in [text, binary] {
+                match behavior {
+                    MySqlZeroDateBehavior::Error => {
+                        let errors = [
+                            row.try_get::<Option<NaiveDate>, _>("d").unwrap_err(),
+                            row.try_get::<Option<NaiveDateTime>, _>("dt").unwrap_err(),
+                            row.try_get::<Option<DateTime<Utc>>, _>("ts").unwrap_err(),
+                        ];
+
+                        for (column, error) in ["d", "dt", "ts"].into_iter().zip(errors) {
+                            let error = error.to_string();
+
+                            assert!(error.contains("`0000-00-00"), "{}", error);
+                            assert!(
+                                error.contains(&format!("in column `{}`", column)),
+                                "{}",
+                                error
+                            );
+                        }
+                    }
+
+                    MySqlZeroDateBehavior::ConvertToNone => {
+                        assert_eq!(row.try_get::<Option<NaiveDate>, _>("d")?, None);
+                        assert_eq!(row.try_get::<Option<NaiveDateTime>, _>("dt")?, None);
+                        assert_eq!(row.try_get::<Option<DateTime<Utc>>, _>("ts")?, None);
+
+                        let error = row.try_get::<NaiveDate, _>("d").unwrap_err().to_string();
+                        assert!(error.contains("`0000-00-00` in column `d`"), "{}", error);
+                    }
+
+                    MySqlZeroDateBehavior::ConvertToMin => {
+                        assert_eq!(row.try_get::<NaiveDate, _>("d")?, min);
+                        assert_eq!(row.try_get::<NaiveDateTime, _>("dt")?, min.and_hms(0, 0, 0));
+                        assert_eq!(
+                            row.try_get::<Option<DateTime<Utc>>, _>("ts")?,
+                            Some(DateTime::<Utc>::from_utc(min.and_hms(0, 0, 0), Utc))
+                        );
+                    }
+                }
+
+                // a date with a zero month is invalid whatever the behavior
+                let error = row.try_get::<NaiveDate, _>("bad").unwrap_err().to_string();
+                assert!(error.contains("`2020-00-15` in column `bad`"), "{}", error);
+            }
+        }
+
+        Ok(())
+    }
+
+    #[sqlx_macros::test]
+    async fn test_type_chrono_timestamp_session_timezone() -> anyhow::Result<()> {
+        use sqlx::mysql::{MySqlConnectOptions, MySqlConnection};
+        use sqlx::Connection;
+
+        let options: MySqlConnectOptions = std::env::var("DATABASE_URL")?.parse()?;
+        let value =
+            DateTime::<Utc>::from_utc(NaiveDate::from_ymd(2019, 1, 2).and_hms(5, 10, 20), Utc);
+
+        // make the server default a zone other than UTC for the connections opened below
+        let mut admin = MySqlConnection::connect_with(&options).await?;
+        let (global,): (String,) = sqlx::query_as("SELECT @@GLOBAL.time_zone")
+            .fetch_one(&mut admin)
+            .await?;
+
+        admin
+            .execute(
+                r#"
+SET GLOBAL time_zone = '+05:00';
+DROP TABLE IF EXISTS timestamp_session_timezone;
+CREATE TABLE timestamp_session_timezone (ts TIMESTAMP NOT NULL);
+            "#,
+            )
+            .await?;
+
+        let result: anyhow::Result<()> = async {
+            let mut conn = MySqlConnection::connect_with(&options).await?;
+            let mut system =
+                MySqlConnection::connect_with(&options.clone().timezone_system()).await?;
+            let mut named =
+                MySqlConnection::connect_with(&options.clone().timezone("-03:00")).await?;
+
+            let (zone,): (String,) = sqlx::query_as("SELECT @@SESSION.time_zone")
+                .fetch_one(&mut system)
+                .await?;
+            assert_eq!(zone, "+05:00");
+
+            sqlx::query("INSERT INTO timestamp_session_timezone (ts) VALUES (?)")
+                .bind(value)
+                .execute(&mut conn)
+                .await?;
+
+            // the default session is UTC whatever the server default is, so the value round-trips
+            let (ts,): (DateTime<Utc>,) =
+                sqlx::query_as("SELECT ts FROM timestamp_session_timezone")
+                    .fetch_one(&mut conn)
+                    .await?;
+            assert_eq!(ts, value);
+
+            // other sessions see the same instant in their own zone
+            let (ts,): (NaiveDateTime,) =
+                sqlx::query_as("SELECT ts FROM timestamp_session_timezone")
+                    .fetch_one(&mut system)
+                    .await?;
+            assert_eq!(ts, NaiveDate::from_ymd(2019, 1, 2).and_hms(10, 10, 20));
+
+            let (ts,): (NaiveDateTime,) =
+                sqlx::query_as("SELECT ts FROM timestamp_session_timezone")
+                    .fetch_one(&mut named)
+                    .await?;
+            assert_eq!(ts, NaiveDate::from_ymd(2019, 1, 2).and_hms(2, 10, 20));
+
+            Ok(())
+        }
+        .await;
+
+        sqlx::query("SET GLOBAL time_zone = ?")
+            .bind(global)
+            .execute(&mut admin)
+            .await?;
+        admin
+            .execute("DROP TABLE timestamp_session_timezone")
+            .await?;
+
+        result
+    }
 }
 
 #[cfg(feature = "time")]
@@ -268,6 +504,63 @@ mod json_tests {
         MySql,
         "\'{\"json_column\":[1,2]}\'" == Json(Customer { json_column: Json(vec![1, 2]) })
     ));
+
+    #[sqlx_macros::test]
+    async fn test_json_null_and_raw_value() -> anyhow::Result<()> {
+        use serde_json::value::RawValue;
+
+        let mut conn = new::<MySql>().await?;
+
+        // MariaDB creates a `LONGTEXT` column with a `JSON_VALID` check instead
+        conn.execute(
+            "CREATE TEMPORARY TABLE json_values (id INT PRIMARY KEY, document JSON NULL);",
+        )
+        .await?;
+
+        let raw = RawValue::from_string(r#"{"name":"Joe","age":33}"#.to_owned())?;
+
+        sqlx::query("INSERT INTO json_values (id, document) VALUES (1, ?), (2, ?), (3, ?)")
+            .bind(Option::<JsonValue>::None)
+            .bind(Some(JsonValue::Null))
+            .bind(&*raw)
+            .execute(&mut conn)
+            .await?;
+
+        let text = conn
+            .fetch_all("SELECT document FROM json_values ORDER BY id")
+            .await?;
+        let binary = sqlx::query("SELECT document FROM json_values ORDER BY id")
+            .fetch_all(&mut conn)
+            .await?;
+
+        for rows in [text, binary] {
+            // a SQL `NULL` and a JSON `null` are told apart
+            assert_eq!(rows[0].try_get::<Option<JsonValue>, _>(0)?, None);
+            assert_eq!(
+                rows[1].try_get::<Option<JsonValue>, _>(0)?,
+                Some(JsonValue::Null)
+            );
+            assert!(rows[0].try_get::<JsonValue, _>(0).is_err());
+
+            let friend: Json<Friend> = rows[2].try_get(0)?;
+            assert_eq!(
+                friend.0,
+                Friend {
+                    name: "Joe".to_owned(),
+                    age: 33
+                }
+            );
+
+            // MySQL normalizes the document, so only compare what it holds
+            let document: &RawValue = rows[2].try_get(0)?;
+            assert_eq!(
+                serde_json::from_str::<JsonValue>(document.get())?,
+                json!({ "name": "Joe", "age": 33 })
+            );
+        }
+
+        Ok(())
+    }
 }
 
 #[sqlx_macros::test]
@@ -124,6 +124,23 @@ struct InventoryItem {
     price: Option<i64>,
 }
 
+// Enums and records implement `PgHasArrayType` so they can be used in arrays and records
+#[derive(PartialEq, Debug, sqlx::Type)]
+#[sqlx(type_name = "weather")]
+#[sqlx(rename_all = "lowercase")]
+enum Weather {
+    Sunny,
+    Cloudy,
+    Rainy,
+}
+
+#[derive(PartialEq, Debug, sqlx::Type)]
+#[sqlx(type_name = "forecast")]
+struct Forecast {
+    today: Weather,
+    week: Vec<Weather>,
+}
+
 // Custom range type
 #[derive(sqlx::Type, Debug, PartialEq)]
 #[sqlx(type_name = "float_range")]
@@ -374,6 +391,87 @@ SELECT $1 = ROW('fuzzy dice', 42, 199)::inventory_item, $1
     Ok(())
 }
 
+#[sqlx_macros::test]
+async fn test_enum_array_and_record_type() -> anyhow::Result<()> {
+    let mut conn = new::<Postgres>().await?;
+
+    conn.execute(
+        r#"
+DROP TYPE IF EXISTS forecast CASCADE;
+DROP TYPE IF EXISTS weather CASCADE;
+
+CREATE TYPE weather AS ENUM ( 'sunny', 'cloudy', 'rainy' );
+CREATE TYPE forecast AS ( today weather, week weather[] );
+    "#,
+    )
+    .await?;
+
+    // Drop and re-acquire the connection
+    conn.close().await?;
+    let mut conn = new::<Postgres>().await?;
+
+    let values = vec![Weather::Sunny, Weather::Rainy];
+
+    let rec: (bool, Vec<Weather>) = sqlx::query_as(
+        "
+SELECT $1 = '{sunny,rainy}'::weather[], $1
+        ",
+    )
+    .bind(&values)
+    .fetch_one(&mut conn)
+    .await?;
+
+    assert!(rec.0);
+    assert_eq!(rec.1, values);
+
+    let values = vec![Some(Weather::Cloudy), None];
+
+    let rec: (bool, Vec<Option<Weather>>) = sqlx::query_as(
+        "
+SELECT $1 = '{cloudy,NULL}'::weather[], $1
+        ",
+    )
+    .bind(&values)
+    .fetch_one(&mut conn)
+    .await?;
+
+    assert!(rec.0);
+    assert_eq!(rec.1, values);
+
+    let value = Forecast {
+        today: Weather::Cloudy,
+        week: vec![Weather::Sunny, Weather::Cloudy, Weather::Rainy],
+    };
+
+    let rec: (bool, Forecast) = sqlx::query_as(
+        "
+SELECT $1 = ROW('cloudy', '{sunny,cloudy,rainy}')::forecast, $1
+        ",
+    )
+    .bind(&value)
+    .fetch_one(&mut conn)
+    .await?;
+
+    assert!(rec.0);
+    assert_eq!(rec.1, value);
+
+    let values = vec![value];
+
+    let rec: (bool, Vec<Forecast>) = sqlx::query_as(
+        "
+SELECT $1 = ARRAY[ROW('cloudy', '{sunny,cloudy,rainy}')::forecast], $1
+        ",
+    )
+    .bind(&values)
+    .fetch_one(&mut conn)
+    .await?;
+
+    assert!(rec.0);
+    assert_eq!(rec.1, values);
+
+    Ok(())
+}
+
 #[cfg(feature = "macros")]
 #[sqlx_macros::test]
 async fn test_from_row() -> anyhow::Result<()> {
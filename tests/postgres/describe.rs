@@ -1,4 +1,4 @@
-use sqlx::{postgres::Postgres, Column, Executor, TypeInfo};
+use sqlx::{postgres::Postgres, Column, Either, Executor, TypeInfo};
 use sqlx_test::new;
 
 #[sqlx_macros::test]
@@ -93,3 +93,35 @@ async fn it_describes_composite() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[sqlx_macros::test]
+async fn it_describes_with_any_executor() -> anyhow::Result<()> {
+    let pool = sqlx_test::pool::<Postgres>().await?;
+
+    let d = sqlx::describe(
+        &pool,
+        "SELECT tweet.id, owner.id AS owner_id, tweet.id + 1 AS next_id \
+         FROM tweet LEFT JOIN tweet owner ON owner.id = tweet.owner_id",
+    )
+    .await?;
+
+    assert_eq!(d.columns()[1].name(), "owner_id");
+
+    assert_eq!(d.nullable(0), Some(false));
+    // on the nullable side of the join, from `EXPLAIN`
+    assert_eq!(d.nullable(1), Some(true));
+    // an expression
+    assert_eq!(d.nullable(2), None);
+
+    let d = sqlx::describe(&pool, "SELECT id FROM tweet WHERE text = $1").await?;
+
+    match d.parameters() {
+        Some(Either::Left(params)) => {
+            assert_eq!(params.len(), 1);
+            assert_eq!(params[0].name(), "TEXT");
+        }
+        params => panic!("unexpected parameters {:?}", params),
+    }
+
+    Ok(())
+}
@@ -0,0 +1,3 @@
+-- sqlx:no-transaction
+create index concurrently post_user_id on post(user_id);
+create index concurrently comment_post_id on comment(post_id);
@@ -0,0 +1,16 @@
+#!/bin/sh
+set -e
+
+# The principal of the server, `postgres/localhost`, and of the `postgres` user, whose password
+# is `password`
+if [ ! -f /etc/postgresql.keytab ]; then
+    kdb5_util create -s -r SQLX.TEST -P password
+    kadmin.local -q "addprinc -pw password postgres@SQLX.TEST"
+    kadmin.local -q "addprinc -randkey postgres/localhost@SQLX.TEST"
+    kadmin.local -q "ktadd -k /etc/postgresql.keytab postgres/localhost@SQLX.TEST"
+    chown postgres:postgres /etc/postgresql.keytab
+fi
+
+krb5kdc
+
+exec docker-entrypoint.sh "$@"
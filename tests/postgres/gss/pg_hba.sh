@@ -0,0 +1,9 @@
+#!/bin/sh
+set -e
+
+# Only accept connections authenticated with Kerberos over TCP, encrypted with GSSAPI or not
+cat > "$PGDATA/pg_hba.conf" <<HBA
+local all all trust
+hostgssenc all all all gss include_realm=0
+hostnogssenc all all all gss include_realm=0
+HBA
@@ -0,0 +1,63 @@
+use std::env;
+use std::str::FromStr;
+
+use sqlx::postgres::{PgConnectOptions, PgConnection, PgGssEncMode};
+use sqlx::Connection;
+
+// These need credentials from `kinit` for the realm of the `postgres_14_gss` service of
+// tests/docker-compose.yml, with `KRB5_CONFIG=tests/postgres/gss/krb5.conf`.
+
+async fn gss_state(mode: PgGssEncMode) -> anyhow::Result<(bool, bool, String)> {
+    let options = PgConnectOptions::from_str(&env::var("DATABASE_URL")?)?.gss_enc_mode(mode);
+    let mut conn = PgConnection::connect_with(&options).await?;
+
+    let state = sqlx::query_as(
+        "SELECT gss_authenticated, encrypted, principal FROM pg_stat_gssapi \
+         WHERE pid = pg_backend_pid()",
+    )
+    .fetch_one(&mut conn)
+    .await?;
+
+    Ok(state)
+}
+
+// run against `postgres_14_gss`, which only authenticates with Kerberos
+#[sqlx_macros::test]
+async fn it_encrypts_with_gssapi() -> anyhow::Result<()> {
+    for mode in [PgGssEncMode::Prefer, PgGssEncMode::Require] {
+        let (authenticated, encrypted, principal) = gss_state(mode).await?;
+
+        assert!(authenticated);
+        assert!(encrypted, "{:?}", mode);
+        assert_eq!(principal, "postgres@SQLX.TEST");
+    }
+
+    Ok(())
+}
+
+// run against `postgres_14_gss`, which only authenticates with Kerberos
+#[sqlx_macros::test]
+async fn it_authenticates_with_gssapi_without_encryption() -> anyhow::Result<()> {
+    let (authenticated, encrypted, _) = gss_state(PgGssEncMode::Disable).await?;
+
+    assert!(authenticated);
+    assert!(!encrypted);
+
+    Ok(())
+}
+
+// run against a server which refuses GSSAPI encryption (14, without the principal of the server)
+// or doesn't know it (10), with the credentials anyway, so that it is attempted
+#[sqlx_macros::test]
+async fn it_falls_back_without_gssapi_encryption() -> anyhow::Result<()> {
+    let options = PgConnectOptions::from_str(&env::var("DATABASE_URL")?)?;
+    let mut conn = PgConnection::connect_with(&options).await?;
+
+    conn.ping().await?;
+    conn.close().await?;
+
+    let options = options.gss_enc_mode(PgGssEncMode::Require);
+    assert!(PgConnection::connect_with(&options).await.is_err());
+
+    Ok(())
+}
@@ -244,6 +244,37 @@ async fn test_query_scalar() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[sqlx_macros::test]
+async fn test_column_override_enum_array() -> anyhow::Result<()> {
+    #[derive(PartialEq, Debug, sqlx::Type)]
+    #[sqlx(type_name = "status", rename_all = "lowercase")]
+    enum Status {
+        New,
+        Open,
+        Closed,
+    }
+
+    let mut conn = new::<Postgres>().await?;
+
+    let statuses = sqlx::query_scalar!(
+        r#"select $1::status[] as "statuses!: Vec<Option<Status>>""#,
+        &[Some(Status::New), None, Some(Status::Closed)] as &[Option<Status>]
+    )
+    .fetch_one(&mut conn)
+    .await?;
+
+    assert_eq!(statuses, [Some(Status::New), None, Some(Status::Closed)]);
+
+    let statuses: Vec<Status> =
+        sqlx::query_scalar!(r#"select '{open,new}'::status[] as "statuses!: _""#)
+            .fetch_one(&mut conn)
+            .await?;
+
+    assert_eq!(statuses, [Status::Open, Status::New]);
+
+    Ok(())
+}
+
 #[sqlx_macros::test]
 async fn query_by_string() -> anyhow::Result<()> {
     let mut conn = new::<Postgres>().await?;
@@ -581,6 +612,50 @@ async fn test_bind_arg_override_exact() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[sqlx_macros::test]
+async fn test_bind_arg_borrowed() -> anyhow::Result<()> {
+    let mut conn = new::<Postgres>().await?;
+
+    let name = Some(String::from("Herp Derpinson"));
+
+    let record = sqlx::query!("select $1::text as name", &name)
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(record.name, name);
+
+    let record = sqlx::query!("select $1::text as name", &name.as_deref())
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(record.name, name);
+
+    let ids = vec![1i64, 2];
+
+    let record = sqlx::query!("select $1::int8[] as ids", ids.clone())
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(record.ids, Some(vec![1, 2]));
+
+    let record = sqlx::query!("select $1::int8[] as ids", &Some(ids))
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(record.ids, Some(vec![1, 2]));
+
+    let record = sqlx::query!("select $1::text[] as names", vec!["Herp", "Derpinson"])
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(
+        record.names,
+        Some(vec![String::from("Herp"), String::from("Derpinson")])
+    );
+
+    Ok(())
+}
+
 #[sqlx_macros::test]
 async fn test_bind_arg_override_wildcard() -> anyhow::Result<()> {
     let mut conn = new::<Postgres>().await?;
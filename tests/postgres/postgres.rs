@@ -1,8 +1,8 @@
 use futures::{StreamExt, TryStreamExt};
 use sqlx::postgres::types::Oid;
 use sqlx::postgres::{
-    PgAdvisoryLock, PgConnectOptions, PgConnection, PgDatabaseError, PgErrorPosition, PgListener,
-    PgPoolOptions, PgRow, PgSeverity, Postgres,
+    PgAdvisoryLock, PgConnectOptions, PgConnection, PgDatabaseError, PgErrorPosition,
+    PgExplainOptions, PgListener, PgPoolOptions, PgRow, PgSeverity, PgTargetSessionAttrs, Postgres,
 };
 use sqlx::{Column, Connection, Executor, Row, Statement, TypeInfo};
 use sqlx_test::{new, pool, setup_if_needed};
@@ -154,6 +154,125 @@ async fn it_can_inspect_constraint_errors() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[sqlx_macros::test]
+async fn it_inspects_constraint_violations() -> anyhow::Result<()> {
+    use sqlx::error::{DatabaseError, ErrorKind};
+
+    let mut conn = new::<Postgres>().await?;
+
+    conn.execute(
+        r#"
+CREATE TEMPORARY TABLE _sqlx_parents (id INTEGER PRIMARY KEY);
+CREATE TEMPORARY TABLE _sqlx_children (
+    id INTEGER PRIMARY KEY,
+    parent_id INTEGER CONSTRAINT _sqlx_children_parent_fk REFERENCES _sqlx_parents (id),
+    name TEXT NOT NULL,
+    age INTEGER CONSTRAINT _sqlx_children_age_check CHECK (age >= 0)
+);
+INSERT INTO _sqlx_parents (id) VALUES (1);
+INSERT INTO _sqlx_children (id, parent_id, name, age) VALUES (1, 1, 'first', 1);
+        "#,
+    )
+    .await?;
+
+    async fn violate(conn: &mut PgConnection, sql: &str) -> Box<dyn DatabaseError> {
+        conn.execute(sql)
+            .await
+            .unwrap_err()
+            .into_database_error()
+            .unwrap()
+    }
+
+    let err = violate(
+        &mut conn,
+        "INSERT INTO _sqlx_children (id, name) VALUES (1, 'again')",
+    )
+    .await;
+    assert_eq!(err.kind(), ErrorKind::UniqueViolation);
+    assert_eq!(err.constraint(), Some("_sqlx_children_pkey"));
+    assert_eq!(err.table(), Some("_sqlx_children"));
+    assert!(err.schema().unwrap().starts_with("pg_temp"));
+
+    let err = violate(
+        &mut conn,
+        "INSERT INTO _sqlx_children (id, parent_id, name) VALUES (2, 2, 'orphan')",
+    )
+    .await;
+    assert_eq!(err.kind(), ErrorKind::ForeignKeyViolation);
+    assert_eq!(err.constraint(), Some("_sqlx_children_parent_fk"));
+    assert_eq!(err.table(), Some("_sqlx_children"));
+
+    let err = violate(&mut conn, "INSERT INTO _sqlx_children (id) VALUES (3)").await;
+    assert_eq!(err.kind(), ErrorKind::NotNullViolation);
+    assert_eq!(err.table(), Some("_sqlx_children"));
+    assert_eq!(err.column(), Some("name"));
+    assert_eq!(DatabaseError::routine(&*err), Some("ExecConstraints"));
+
+    let err = violate(
+        &mut conn,
+        "INSERT INTO _sqlx_children (id, name, age) VALUES (4, 'unborn', -1)",
+    )
+    .await;
+    assert_eq!(err.kind(), ErrorKind::CheckViolation);
+    assert_eq!(err.constraint(), Some("_sqlx_children_age_check"));
+    assert_eq!(err.table(), Some("_sqlx_children"));
+    assert_eq!(err.column(), None);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_captures_the_statement_on_error() -> anyhow::Result<()> {
+    use sqlx::ConnectOptions;
+
+    sqlx_test::setup_if_needed();
+
+    let mut options: PgConnectOptions = env::var("DATABASE_URL")?.parse().unwrap();
+    options.capture_statement_on_error(true);
+
+    let mut conn = PgConnection::connect_with(&options).await?;
+
+    let err = sqlx::query("SELECT * FROM missing WHERE name = $1")
+        .bind("secret")
+        .execute(&mut conn)
+        .await
+        .unwrap_err();
+
+    let context = err.context().unwrap();
+    assert_eq!(context.sql(), "SELECT * FROM missing WHERE name = $1");
+    assert_eq!(context.parameters(), 1);
+    assert!(context.column().is_none());
+
+    assert_eq!(
+        err.as_database_error().unwrap().code().as_deref(),
+        Some("42P01")
+    );
+    assert!(!err.to_string().contains("secret"));
+
+    // the errors of `fetch_optional` and those decoding the rows of the statement
+    let err = sqlx::query_scalar::<_, i64>("SELECT 'text'::TEXT AS name, $1::INT8 AS id")
+        .bind(1_i64)
+        .fetch_optional(&mut conn)
+        .await
+        .unwrap_err();
+
+    let column = err.context().unwrap().column().unwrap();
+    assert_eq!(column.index(), 0);
+    assert_eq!(column.name(), "name");
+    assert_eq!(column.type_name(), "TEXT");
+    assert_eq!(column.rust_type(), "i64");
+
+    let message = err.to_string();
+    assert!(message.contains("decoding column 0 (`name`) of SQL type `TEXT` as `i64`"));
+    assert!(message.contains("in statement (parameters: 1): SELECT 'text'::TEXT AS name"));
+
+    // the connection is still usable
+    let value: i32 = sqlx::query_scalar("SELECT 1").fetch_one(&mut conn).await?;
+    assert_eq!(value, 1);
+
+    Ok(())
+}
+
 #[sqlx_macros::test]
 async fn it_executes() -> anyhow::Result<()> {
     let mut conn = new::<Postgres>().await?;
@@ -753,6 +872,161 @@ async fn it_closes_statement_from_cache_issue_470() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[sqlx_macros::test]
+async fn it_bounds_prepared_statements_by_cache_capacity() -> anyhow::Result<()> {
+    sqlx_test::setup_if_needed();
+
+    let options: PgConnectOptions = env::var("DATABASE_URL")?.parse().unwrap();
+    let mut conn = PgConnection::connect_with(&options.statement_cache_capacity(5)).await?;
+
+    // a plain string is sent as a simple query, so it doesn't prepare a statement itself
+    let count_prepared = "SELECT COUNT(*) FROM pg_prepared_statements";
+
+    for i in 0..50 {
+        let val: i32 = sqlx::query_scalar(&format!("SELECT {}::int4 + $1", i))
+            .bind(1_i32)
+            .fetch_one(&mut conn)
+            .await?;
+
+        assert_eq!(i + 1, val);
+
+        let prepared: i64 = conn.fetch_one(count_prepared).await?.get(0);
+        assert!(
+            prepared <= 5,
+            "{} statements prepared on the server",
+            prepared
+        );
+    }
+
+    let stats = conn.cache_statistics();
+
+    assert_eq!(stats.prepared, 50);
+    assert_eq!(stats.evictions, 45);
+    assert_eq!(stats.hits, 0);
+
+    // the most recently used statement is still cached
+    let _: i32 = sqlx::query_scalar("SELECT 49::int4 + $1")
+        .bind(1_i32)
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(conn.cache_statistics().hits, 1);
+
+    // statements that aren't persistent use the unnamed statement and are never cached
+    for i in 0..10 {
+        let val: i32 = sqlx::query_scalar(&format!("SELECT {}::int4 * $1", i))
+            .bind(2_i32)
+            .persistent(false)
+            .fetch_one(&mut conn)
+            .await?;
+
+        assert_eq!(i * 2, val);
+    }
+
+    let prepared: i64 = conn.fetch_one(count_prepared).await?.get(0);
+    assert_eq!(prepared, 5);
+
+    let stats = conn.cache_statistics();
+
+    assert_eq!(stats.prepared, 60);
+    assert_eq!(stats.evictions, 45);
+    assert_eq!(conn.cached_statements_size(), 5);
+
+    Ok(())
+}
+
+// CI also runs this test through PgBouncer in transaction pooling mode
+#[sqlx_macros::test]
+async fn it_prefers_simple_protocol() -> anyhow::Result<()> {
+    sqlx_test::setup_if_needed();
+
+    let options: PgConnectOptions = env::var("DATABASE_URL")?.parse().unwrap();
+    let mut conn = PgConnection::connect_with(&options.prefer_simple_protocol(true)).await?;
+
+    // without bind parameters, results are decoded from the text format
+    let row = sqlx::query("SELECT 1::int4, 'hello'::text, 1.5::float8, NULL::int8")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(row.try_get::<i32, _>(0)?, 1);
+    assert_eq!(row.try_get::<String, _>(1)?, "hello");
+    assert_eq!(row.try_get::<f64, _>(2)?, 1.5);
+    assert_eq!(row.try_get::<Option<i64>, _>(3)?, None);
+
+    for i in 0..5 {
+        let (sum, text): (i32, String) = sqlx::query_as("SELECT $1::int4 + 1, $2")
+            .bind(i)
+            .bind("world")
+            .fetch_one(&mut conn)
+            .await?;
+
+        assert_eq!(sum, i + 1);
+        assert_eq!(text, "world");
+    }
+
+    let values: Vec<i64> = sqlx::query_scalar("SELECT generate_series(1, $1)")
+        .bind(3_i64)
+        .fetch_all(&mut conn)
+        .await?;
+
+    assert_eq!(values, [1, 2, 3]);
+
+    let value: String = sqlx::query_scalar("SELECT $1::json->>'key'")
+        .bind(sqlx::types::Json(serde_json::json!({ "key": "value" })))
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(value, "value");
+
+    // an error doesn't leave an unfinished query behind
+    let res = sqlx::query("SELECT 1 / $1")
+        .bind(0_i32)
+        .execute(&mut conn)
+        .await;
+    assert!(res.is_err());
+
+    let mut tx = conn.begin().await?;
+
+    tx.execute("CREATE TEMPORARY TABLE simple_protocol (id INT4) ON COMMIT DROP")
+        .await?;
+
+    for id in 0..3_i32 {
+        sqlx::query("INSERT INTO simple_protocol (id) VALUES ($1)")
+            .bind(id)
+            .execute(&mut tx)
+            .await?;
+    }
+
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM simple_protocol")
+        .fetch_one(&mut tx)
+        .await?;
+
+    assert_eq!(count, 3);
+
+    tx.commit().await?;
+
+    // preparing a statement doesn't leave it on the server
+    let stmt = conn.prepare("SELECT $1::int4 * 2").await?;
+    let value: i32 = stmt
+        .query_scalar()
+        .bind(21_i32)
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(value, 42);
+
+    let prepared: i64 = conn
+        .fetch_one("SELECT COUNT(*) FROM pg_prepared_statements")
+        .await?
+        .get(0);
+
+    assert_eq!(prepared, 0);
+    assert_eq!(conn.cached_statements_size(), 0);
+    assert_eq!(conn.cache_statistics().hits, 0);
+
+    Ok(())
+}
+
 #[sqlx_macros::test]
 async fn it_sets_application_name() -> anyhow::Result<()> {
     sqlx_test::setup_if_needed();
@@ -773,6 +1047,133 @@ async fn it_sets_application_name() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[sqlx_macros::test]
+async fn it_checks_target_session_attrs() -> anyhow::Result<()> {
+    sqlx_test::setup_if_needed();
+
+    let options: PgConnectOptions = env::var("DATABASE_URL")?.parse().unwrap();
+
+    // the test server is a primary, so only sessions that allow a primary are accepted
+    for attrs in ["any", "read-write", "primary", "prefer-standby"] {
+        let options = options.clone().target_session_attrs(attrs.parse().unwrap());
+
+        PgConnection::connect_with(&options).await?.close().await?;
+    }
+
+    for attrs in ["read-only", "standby"] {
+        let options = options.clone().target_session_attrs(attrs.parse().unwrap());
+
+        let res = PgConnection::connect_with(&options).await;
+        assert!(
+            matches!(res, Err(sqlx::Error::Configuration(_))),
+            "connected with target_session_attrs={}",
+            attrs
+        );
+    }
+
+    // a read-only default makes the session read-only, even on a primary
+    let read_only = options
+        .clone()
+        .options([("default_transaction_read_only", "on")]);
+
+    let res = PgConnection::connect_with(
+        &read_only
+            .clone()
+            .target_session_attrs(PgTargetSessionAttrs::ReadWrite),
+    )
+    .await;
+    assert!(res.is_err());
+
+    PgConnection::connect_with(&read_only.target_session_attrs(PgTargetSessionAttrs::ReadOnly))
+        .await?
+        .close()
+        .await?;
+
+    Ok(())
+}
+
+// run against `postgres_14_primary` and `postgres_14_standby` in `tests/docker-compose.yml`,
+// through a host name that resolves to both of them, with
+// `cargo test --features postgres -- --ignored it_finds_target_session_attrs_in_a_cluster`
+#[ignore]
+#[sqlx_macros::test]
+async fn it_finds_target_session_attrs_in_a_cluster() -> anyhow::Result<()> {
+    sqlx_test::setup_if_needed();
+
+    let options: PgConnectOptions = env::var("DATABASE_URL")?.parse().unwrap();
+
+    // whichever of the addresses is tried first, the server that matches is connected to
+    for (attrs, standby) in [
+        ("read-write", false),
+        ("primary", false),
+        ("read-only", true),
+        ("standby", true),
+        ("prefer-standby", true),
+    ] {
+        let options = options.clone().target_session_attrs(attrs.parse().unwrap());
+        let mut conn = PgConnection::connect_with(&options).await?;
+
+        let in_recovery: bool = sqlx::query_scalar("SELECT pg_is_in_recovery()")
+            .fetch_one(&mut conn)
+            .await?;
+        assert_eq!(in_recovery, standby, "target_session_attrs={}", attrs);
+
+        conn.close().await?;
+    }
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_sends_startup_parameters() -> anyhow::Result<()> {
+    sqlx_test::setup_if_needed();
+
+    let options: PgConnectOptions = env::var("DATABASE_URL")?.parse().unwrap();
+
+    let mut conn = PgConnection::connect_with(
+        &options
+            .clone()
+            .application_name("sqlx-startup-test")
+            .statement_timeout(Duration::from_secs(5))
+            .startup_parameter("lock_timeout", "1500ms")
+            .startup_parameter("search_path", "pg_catalog"),
+    )
+    .await?;
+
+    let app_name: String = sqlx::query_scalar(
+        "SELECT application_name FROM pg_stat_activity WHERE pid = pg_backend_pid()",
+    )
+    .fetch_one(&mut conn)
+    .await?;
+
+    assert_eq!(app_name, "sqlx-startup-test");
+
+    let statement_timeout: String = conn.fetch_one("SHOW statement_timeout").await?.get(0);
+    assert_eq!(statement_timeout, "5s");
+
+    let lock_timeout: String = conn.fetch_one("SHOW lock_timeout").await?.get(0);
+    assert_eq!(lock_timeout, "1500ms");
+
+    let search_path: String = conn.fetch_one("SHOW search_path").await?.get(0);
+    assert_eq!(search_path, "pg_catalog");
+
+    conn.close().await?;
+
+    // the server rejects unknown parameters when the connection starts
+    let res =
+        PgConnection::connect_with(&options.startup_parameter("sqlx_no_such_parameter", "on"))
+            .await;
+
+    let err = res.unwrap_err();
+    let err = err.into_database_error().unwrap();
+    assert_eq!(
+        err.message(),
+        "unrecognized configuration parameter \"sqlx_no_such_parameter\""
+    );
+
+    Ok(())
+}
+
 #[sqlx_macros::test]
 async fn it_can_handle_parameter_status_message_issue_484() -> anyhow::Result<()> {
     new::<Postgres>().await?.execute("SET NAMES 'UTF8'").await?;
@@ -1397,6 +1798,43 @@ async fn test_pg_server_num() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[sqlx_macros::test]
+async fn it_reports_backend_pid_and_parameters() -> anyhow::Result<()> {
+    let pool = pool::<Postgres>().await?;
+    let mut conn = pool.acquire().await?;
+
+    let pid: i32 = sqlx::query_scalar("SELECT pg_backend_pid()")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(conn.backend_pid(), pid as u32);
+
+    let version_num: String = sqlx::query_scalar("SHOW server_version_num")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(conn.server_version_num(), Some(version_num.parse()?));
+
+    let version: String = sqlx::query_scalar("SHOW server_version")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(conn.parameter("server_version"), Some(&*version));
+    assert_eq!(conn.parameter("TimeZone"), Some("UTC"));
+    assert_eq!(conn.parameter("statement_timeout"), None);
+
+    // changes during the session are reported by the server
+    conn.execute("SET application_name = 'sqlx-parameter-test'")
+        .await?;
+
+    assert_eq!(
+        conn.parameter("application_name"),
+        Some("sqlx-parameter-test")
+    );
+
+    Ok(())
+}
+
 #[sqlx_macros::test]
 async fn it_can_copy_in() -> anyhow::Result<()> {
     let mut conn = new::<Postgres>().await?;
@@ -1726,3 +2164,1086 @@ async fn test_postgres_bytea_hex_deserialization_errors() -> anyhow::Result<()>
     }
     Ok(())
 }
+
+#[sqlx_macros::test]
+async fn it_shares_resolved_type_oids_across_pooled_connections() -> anyhow::Result<()> {
+    #[derive(Debug)]
+    struct Mood(&'static str);
+
+    impl sqlx::Type<Postgres> for Mood {
+        fn type_info() -> sqlx::postgres::PgTypeInfo {
+            sqlx::postgres::PgTypeInfo::with_name("shared_cache_mood")
+        }
+    }
+
+    impl<'q> sqlx::Encode<'q, Postgres> for Mood {
+        fn encode_by_ref(
+            &self,
+            buf: &mut sqlx::postgres::PgArgumentBuffer,
+        ) -> sqlx::encode::IsNull {
+            <&str as sqlx::Encode<Postgres>>::encode(self.0, buf)
+        }
+    }
+
+    let mut conn = new::<Postgres>().await?;
+
+    conn.execute(
+        r#"
+DROP TYPE IF EXISTS shared_cache_mood;
+DROP TYPE IF EXISTS shared_cache_mood_renamed;
+
+CREATE TYPE shared_cache_mood AS ENUM ('ok', 'happy');
+    "#,
+    )
+    .await?;
+
+    let pool = PgPoolOptions::new()
+        .max_connections(3)
+        .connect(&env::var("DATABASE_URL")?)
+        .await?;
+
+    let mut conn1 = pool.acquire().await?;
+
+    let mood: String = sqlx::query_scalar("SELECT $1::text")
+        .bind(Mood("happy"))
+        .fetch_one(&mut conn1)
+        .await?;
+
+    assert_eq!(mood, "happy");
+
+    // the OID survives a rename but a lookup by the old name would now fail,
+    // so this only succeeds on a new connection if the OID was not looked up again
+    conn.execute("ALTER TYPE shared_cache_mood RENAME TO shared_cache_mood_renamed")
+        .await?;
+
+    let mut conn2 = pool.acquire().await?;
+
+    let mood: String = sqlx::query_scalar("SELECT $1::text")
+        .bind(Mood("ok"))
+        .fetch_one(&mut conn2)
+        .await?;
+
+    assert_eq!(mood, "ok");
+
+    // once cleared, every connection must look the type up by name again
+    // (the SQL differs from above so the statement is not already prepared)
+    pool.clear_type_cache();
+
+    let res = sqlx::query_scalar::<_, String>("SELECT $1::text AS mood")
+        .bind(Mood("ok"))
+        .fetch_one(&mut conn1)
+        .await;
+
+    assert!(matches!(res, Err(sqlx::Error::TypeNotFound { .. })));
+
+    // re-create the type under its original name, with a new OID
+    conn.execute(
+        r#"
+DROP TYPE shared_cache_mood_renamed;
+CREATE TYPE shared_cache_mood AS ENUM ('ok', 'happy');
+    "#,
+    )
+    .await?;
+
+    let mood: String = sqlx::query_scalar("SELECT $1::text AS mood")
+        .bind(Mood("happy"))
+        .fetch_one(&mut conn1)
+        .await?;
+
+    assert_eq!(mood, "happy");
+
+    // re-create the type again without telling the pool, leaving a stale OID in the caches
+    conn.execute(
+        r#"
+DROP TYPE shared_cache_mood;
+CREATE TYPE shared_cache_mood AS ENUM ('ok', 'happy');
+    "#,
+    )
+    .await?;
+
+    // the server rejects the stale OID, which clears the caches...
+    let res = sqlx::query_scalar::<_, String>("SELECT $1::text AS stale_mood")
+        .bind(Mood("ok"))
+        .fetch_one(&mut conn2)
+        .await;
+
+    assert!(matches!(res, Err(sqlx::Error::Database(_))));
+
+    // ...so the type is looked up again on the next query
+    let mood: String = sqlx::query_scalar("SELECT $1::text AS fresh_mood")
+        .bind(Mood("ok"))
+        .fetch_one(&mut conn2)
+        .await?;
+
+    assert_eq!(mood, "ok");
+
+    conn.execute("DROP TYPE shared_cache_mood").await?;
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_streams_large_objects() -> anyhow::Result<()> {
+    use sqlx::postgres::PgLargeObjectMode;
+    use std::io::SeekFrom;
+
+    let mut conn = new::<Postgres>().await?;
+
+    // large objects cannot be used in autocommit mode
+    assert!(matches!(
+        conn.create_large_object().await,
+        Err(sqlx::Error::Configuration(_))
+    ));
+
+    // several times larger than the internal buffer, and not a multiple of it
+    let data: Vec<u8> = (0..100_003u32).map(|i| (i % 251) as u8).collect();
+
+    let mut tx = conn.begin().await?;
+
+    let oid = tx.create_large_object().await?;
+
+    let mut lo = tx.open_large_object(oid, PgLargeObjectMode::Write).await?;
+    assert_eq!(lo.oid(), oid);
+    assert_eq!(lo.write_from(&data[..]).await?, data.len() as u64);
+    assert_eq!(lo.tell().await?, data.len() as u64);
+    lo.close().await?;
+
+    let mut lo = tx
+        .open_large_object(oid, PgLargeObjectMode::ReadWrite)
+        .await?;
+
+    let mut read = Vec::new();
+    assert_eq!(lo.read_to(&mut read).await?, data.len() as u64);
+    assert_eq!(read, data);
+
+    // seek relative to the end and overwrite the tail
+    assert_eq!(lo.seek(SeekFrom::End(-3)).await?, data.len() as u64 - 3);
+    lo.write(b"xyz").await?;
+
+    assert_eq!(
+        lo.seek(SeekFrom::Start(data.len() as u64 - 4)).await?,
+        99_999
+    );
+    let mut buf = [0u8; 16];
+    assert_eq!(lo.read(&mut buf).await?, 4);
+    assert_eq!(&buf[..4], &[data[99_999], b'x', b'y', b'z']);
+
+    // positions past 2^31 require 64-bit offsets
+    let far = 3 * (1u64 << 30);
+    assert_eq!(lo.seek(SeekFrom::Start(far)).await?, far);
+    lo.write(b"!").await?;
+    assert_eq!(lo.seek(SeekFrom::End(0)).await?, far + 1);
+
+    lo.truncate(10).await?;
+    assert_eq!(lo.seek(SeekFrom::End(0)).await?, 10);
+    assert_eq!(lo.seek(SeekFrom::Current(-10)).await?, 0);
+    assert_eq!(lo.read(&mut buf).await?, 10);
+    assert_eq!(&buf[..10], &data[..10]);
+
+    lo.close().await?;
+
+    tx.unlink_large_object(oid).await?;
+
+    assert!(tx
+        .open_large_object(oid, PgLargeObjectMode::Read)
+        .await
+        .is_err());
+
+    tx.rollback().await?;
+
+    Ok(())
+}
+
+#[cfg(feature = "json")]
+#[sqlx_macros::test]
+async fn it_decodes_dynamic_values() -> anyhow::Result<()> {
+    use serde_json::{json, Map, Value};
+
+    let mut conn = new::<Postgres>().await?;
+
+    // language=PostgreSQL
+    let sql = r#"
+SELECT NULL::int4                                      AS "null",
+       TRUE                                            AS "bool",
+       2::int2                                         AS int2,
+       4::int4                                         AS int4,
+       8::int8                                         AS int8,
+       1.5::float4                                     AS float4,
+       'NaN'::float8                                   AS float8,
+       '-12.3400'::numeric                             AS numeric,
+       '0.00050'::numeric                              AS numeric_small,
+       '100000000.0001'::numeric                       AS numeric_large,
+       'hello'::text                                   AS text,
+       '\xdeadbeef'::bytea                             AS bytea,
+       '2022-02-28 13:05:00.5'::timestamp              AS timestamp,
+       '2022-02-28 13:05:00.5+02'::timestamptz         AS timestamptz,
+       '0044-03-15 BC'::date                           AS date,
+       '13:05:00'::time                                AS time,
+       'a2b0d3c6-33e2-4b0a-9e49-fd4a9dc2b4a2'::uuid    AS uuid,
+       '{"a": [1, null]}'::jsonb                       AS jsonb,
+       'open'::status                                  AS status,
+       ARRAY[[1, 2], [3, NULL]]::int4[]                AS int4_array,
+       ARRAY['a', NULL]::text[]                        AS text_array,
+       '1 day'::interval                               AS interval
+"#;
+
+    fn to_json(row: &PgRow) -> Result<Value, sqlx::Error> {
+        let mut object = Map::new();
+
+        for column in row.columns() {
+            let value = row.try_get_dynamic(column.ordinal())?;
+            object.insert(column.name().to_owned(), value.into());
+        }
+
+        Ok(Value::Object(object))
+    }
+
+    let mut expected = json!({
+        "null": null,
+        "bool": true,
+        "int2": 2,
+        "int4": 4,
+        "int8": 8,
+        "float4": 1.5,
+        "float8": "NaN",
+        "numeric": "-12.3400",
+        "numeric_small": "0.00050",
+        "numeric_large": "100000000.0001",
+        "text": "hello",
+        "bytea": "\\xdeadbeef",
+        "timestamp": "2022-02-28 13:05:00.5",
+        "timestamptz": "2022-02-28 11:05:00.5+00",
+        "date": "0044-03-15 BC",
+        "time": "13:05:00",
+        "uuid": "a2b0d3c6-33e2-4b0a-9e49-fd4a9dc2b4a2",
+        "jsonb": { "a": [1, null] },
+        "status": "open",
+        "int4_array": [[1, 2], [3, null]],
+        "text_array": ["a", null],
+        // an `Other` value in the binary format: 0 microseconds, 1 day, 0 months
+        "interval": "\\x00000000000000000000000100000000",
+    });
+
+    // prepared queries return values in the binary format
+    let row = sqlx::query(sql).fetch_one(&mut conn).await?;
+    assert_eq!(to_json(&row)?, expected);
+
+    assert_eq!(
+        row.try_get_dynamic("int4")?,
+        sqlx::postgres::PgDynamicValue::Int4(4)
+    );
+
+    // simple queries return values in the text format, which are formatted the same,
+    // except for arrays and other types that are passed through as text
+    let row = conn.fetch_one(sql).await?;
+
+    expected["int4_array"] = json!("{{1,2},{3,NULL}}");
+    expected["text_array"] = json!("{a,NULL}");
+    expected["interval"] = json!("1 day");
+
+    assert_eq!(to_json(&row)?, expected);
+
+    Ok(())
+}
+
+#[cfg(feature = "migrate")]
+#[sqlx_macros::test]
+async fn it_locks_migrations_with_a_timeout() -> anyhow::Result<()> {
+    use sqlx::migrate::{Locking, Migrate, MigrateError, Migrator};
+    use std::borrow::Cow;
+    use std::time::Instant;
+
+    let mut conn = new::<Postgres>().await?;
+    let mut holder = new::<Postgres>().await?;
+
+    let mut migrator = Migrator {
+        migrations: Cow::Owned(Vec::new()),
+        ignore_missing: false,
+        allow_out_of_order: false,
+        table_name: "lock_test_migrations".into(),
+        locking: Locking::default(),
+        applied_by: None,
+        seeds: Cow::Borrowed(&[]),
+        after_run: Cow::Borrowed(&[]),
+    };
+
+    let pid: i32 = sqlx::query_scalar("SELECT pg_backend_pid()")
+        .fetch_one(&mut holder)
+        .await?;
+
+    holder.lock("lock_test_migrations", None).await?;
+
+    migrator.set_locking(Locking::Fail);
+    match migrator.run(&mut conn).await {
+        Err(MigrateError::LockTimeout(Some(holder))) => {
+            assert!(holder.contains(&pid.to_string()), "{}", holder)
+        }
+        res => panic!("expected a lock timeout, got {:?}", res),
+    }
+
+    migrator.set_locking(Locking::Wait {
+        timeout: Some(Duration::from_millis(200)),
+    });
+    let started = Instant::now();
+    assert!(matches!(
+        migrator.run(&mut conn).await,
+        Err(MigrateError::LockTimeout(_))
+    ));
+    assert!(started.elapsed() >= Duration::from_millis(200));
+
+    migrator.set_locking(Locking::None);
+    migrator.run(&mut conn).await?;
+
+    holder.unlock("lock_test_migrations").await?;
+
+    migrator.set_locking(Locking::default());
+    migrator.run(&mut conn).await?;
+
+    conn.execute("DROP TABLE lock_test_migrations").await?;
+
+    Ok(())
+}
+
+#[cfg(feature = "migrate")]
+#[sqlx_macros::test]
+async fn it_force_drops_a_database_in_use() -> anyhow::Result<()> {
+    use sqlx::migrate::MigrateDatabase;
+
+    // needs quoting, and is left in the URL as is
+    let base = dotenvy::var("DATABASE_URL")?;
+    let (base, params) = base.split_once('?').unwrap_or((&base, ""));
+    let url = format!(
+        "{}/sqlx-Force_Drop?{}",
+        base.rsplit_once('/').unwrap().0,
+        params
+    );
+
+    Postgres::force_drop_database(&url).await?;
+    Postgres::create_database(&url).await?;
+    assert!(Postgres::database_exists(&url).await?);
+
+    let mut conn = PgConnection::connect(&url).await?;
+    conn.ping().await?;
+
+    Postgres::force_drop_database(&url).await?;
+    assert!(!Postgres::database_exists(&url).await?);
+
+    // the connection was terminated
+    assert!(conn.ping().await.is_err());
+
+    // and dropping a database which doesn't exist succeeds
+    Postgres::force_drop_database(&url).await?;
+
+    Ok(())
+}
+
+#[cfg(feature = "migrate")]
+#[sqlx_macros::test]
+async fn it_creates_a_database_with_options() -> anyhow::Result<()> {
+    use sqlx::migrate::{CreateDatabaseOptions, MigrateDatabase};
+
+    let base = dotenvy::var("DATABASE_URL")?;
+    let (base, params) = base.split_once('?').unwrap_or((&base, ""));
+    let url = format!(
+        "{}/sqlx-Create_Options?{}",
+        base.rsplit_once('/').unwrap().0,
+        params
+    );
+
+    let mut conn = new::<Postgres>().await?;
+    let owner: String = sqlx::query_scalar("SELECT current_user::text")
+        .fetch_one(&mut conn)
+        .await?;
+
+    Postgres::force_drop_database(&url).await?;
+    Postgres::create_database_with(
+        &url,
+        &CreateDatabaseOptions::new()
+            .owner(&owner)
+            .encoding("LATIN1")
+            .locale("C")
+            .collation("POSIX")
+            .template("template0"),
+    )
+    .await?;
+
+    let (encoding, collate, ctype, dba): (String, String, String, String) = sqlx::query_as(
+        "SELECT pg_encoding_to_char(encoding)::text, datcollate::text, datctype::text, \
+         pg_get_userbyid(datdba)::text FROM pg_database WHERE datname = $1",
+    )
+    .bind("sqlx-Create_Options")
+    .fetch_one(&mut conn)
+    .await?;
+
+    assert_eq!(encoding, "LATIN1");
+    // `POSIX` is an alias of `C`, which PostgreSQL 15 and later record in its place
+    assert!(matches!(&*collate, "C" | "POSIX"), "{}", collate);
+    assert_eq!(ctype, "C");
+    assert_eq!(dba, owner);
+
+    Postgres::force_drop_database(&url).await?;
+
+    // MySQL's options don't apply
+    let res =
+        Postgres::create_database_with(&url, &CreateDatabaseOptions::new().charset("utf8mb4"))
+            .await;
+    assert!(matches!(res, Err(sqlx::Error::Configuration(_))));
+    assert!(!Postgres::database_exists(&url).await?);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_observes_queries_of_a_pool() -> anyhow::Result<()> {
+    use sqlx::observer::{Fingerprint, QueryObserver, QueryOutcome};
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct Observer {
+        started: Mutex<Vec<String>>,
+        ended: Mutex<Vec<(String, QueryOutcome, u64)>>,
+    }
+
+    impl QueryObserver for Observer {
+        fn on_query_start(&self, fingerprint: &Fingerprint) {
+            self.started.lock().unwrap().push(fingerprint.to_string());
+        }
+
+        fn on_query_end(
+            &self,
+            fingerprint: &Fingerprint,
+            outcome: QueryOutcome,
+            rows: u64,
+            _elapsed: Duration,
+        ) {
+            self.ended
+                .lock()
+                .unwrap()
+                .push((fingerprint.to_string(), outcome, rows));
+        }
+    }
+
+    setup_if_needed();
+
+    let observer = Arc::new(Observer::default());
+
+    let pool = PgPoolOptions::new()
+        .max_connections(1)
+        .query_observer(observer.clone())
+        .connect(&env::var("DATABASE_URL")?)
+        .await?;
+
+    for value in [1_i32, 2] {
+        sqlx::query("SELECT $1::int4, 'a'   UNION ALL SELECT 3, 'b'")
+            .bind(value)
+            .fetch_all(&pool)
+            .await?;
+    }
+
+    pool.execute("SELECT * FROM missing").await.unwrap_err();
+
+    assert_eq!(
+        *observer.started.lock().unwrap(),
+        [
+            "SELECT $1::int4, ? UNION ALL SELECT ?, ?",
+            "SELECT $1::int4, ? UNION ALL SELECT ?, ?",
+            "SELECT * FROM missing",
+        ]
+    );
+
+    let fingerprint = String::from("SELECT $1::int4, ? UNION ALL SELECT ?, ?");
+
+    assert_eq!(
+        *observer.ended.lock().unwrap(),
+        [
+            (fingerprint.clone(), QueryOutcome::Success, 2),
+            (fingerprint, QueryOutcome::Success, 2),
+            ("SELECT * FROM missing".into(), QueryOutcome::Error, 0),
+        ]
+    );
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_round_trips_quoted_identifiers_and_literals() -> anyhow::Result<()> {
+    use rand::{Rng, SeedableRng};
+    use rand_xoshiro::Xoshiro256PlusPlus;
+    use sqlx::Database;
+
+    let mut conn = new::<Postgres>().await?;
+
+    let alphabet = [
+        '"', '\'', '`', '\\', '[', ']', ';', '-', ' ', '$', '?', 'a', 'Z', '0', 'é', '✓',
+    ];
+    let mut rng = Xoshiro256PlusPlus::seed_from_u64(0xC0FFEE);
+
+    let mut names: Vec<String> = vec![
+        r#"with "double" quotes"#.into(),
+        "with `backticks`".into(),
+        "with 'single' quotes".into(),
+        "with [brackets]]".into(),
+        r"back\slash\".into(),
+        "'; DROP TABLE users; --".into(),
+        "$1 ?2 :name @p3".into(),
+        "ünïcødé ✓".into(),
+    ];
+
+    names.extend((0..32).map(|_| {
+        let len = rng.gen_range(1..=16);
+
+        (0..len)
+            .map(|_| alphabet[rng.gen_range(0..alphabet.len())])
+            .collect::<String>()
+    }));
+
+    for name in &names {
+        let table = Postgres::quote_identifier(name)?;
+
+        conn.execute(&*format!(
+            "CREATE TEMPORARY TABLE {} ({} TEXT)",
+            table, table
+        ))
+        .await?;
+
+        conn.execute(&*format!(
+            "INSERT INTO {} VALUES ({})",
+            table,
+            Postgres::quote_literal(name)?
+        ))
+        .await?;
+
+        let row = conn
+            .fetch_one(&*format!("SELECT {} FROM {}", table, table))
+            .await?;
+
+        assert_eq!(row.column(0).name(), name);
+        assert_eq!(row.try_get::<String, _>(0)?, *name);
+
+        conn.execute(&*format!("DROP TABLE {}", table)).await?;
+    }
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_works_with_a_type_erased_connection() -> anyhow::Result<()> {
+    let mut conn: sqlx::dyn_connection::BoxDynConnection = Box::new(new::<Postgres>().await?);
+
+    let visits = count_visits(&mut *conn, &["home", "it's", "home"]).await?;
+
+    assert_eq!(
+        visits,
+        [
+            ("home".to_owned(), 2, 1.0, true),
+            ("it's".to_owned(), 1, 0.5, false),
+        ]
+    );
+
+    assert!(conn.downcast_mut::<PgConnection>().is_some());
+    assert!(conn.is::<PgConnection>());
+
+    let conn = conn.downcast::<PgConnection>().ok().unwrap();
+    conn.close().await?;
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_pings_inside_and_outside_transactions() -> anyhow::Result<()> {
+    let mut conn = new::<Postgres>().await?;
+
+    conn.ping().await?;
+    conn.ping_ex().await?;
+
+    let mut tx = conn.begin().await?;
+
+    tx.ping().await?;
+    let result = tx.ping_ex().await?;
+    assert!(result.round_trip < Duration::from_secs(10));
+
+    // the transaction is aborted by the error, which the ping doesn't mind
+    assert!(tx.execute("SELECT 1 / 0").await.is_err());
+    tx.ping().await?;
+
+    tx.rollback().await?;
+
+    conn.ping_ex().await?;
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_gets_the_server_time() -> anyhow::Result<()> {
+    use std::time::SystemTime;
+
+    let mut conn = new::<Postgres>().await?;
+
+    let before = SystemTime::now();
+    let server_time = conn.server_time().await?;
+    let after = SystemTime::now();
+
+    // the server runs on the same host in CI, so its clock shouldn't be skewed by much
+    let skew = Duration::from_secs(60);
+    assert!(server_time > before - skew && server_time < after + skew);
+
+    // unlike `now()`, it isn't frozen at the start of a transaction
+    let mut tx = conn.begin().await?;
+    let first = tx.server_time().await?;
+    sqlx_rt::sleep(Duration::from_millis(10)).await;
+    assert!(tx.server_time().await? > first);
+    tx.rollback().await?;
+
+    Ok(())
+}
+
+/// A plugin which is handed a connection without knowing its database.
+async fn count_visits(
+    conn: &mut dyn sqlx::dyn_connection::DynConnection,
+    pages: &[&str],
+) -> anyhow::Result<Vec<(String, i64, f64, bool)>> {
+    conn.execute(
+        "CREATE TEMPORARY TABLE plugin_visits (page TEXT NOT NULL, hits INTEGER NOT NULL)",
+    )
+    .await?;
+
+    conn.begin().await?;
+
+    for page in pages {
+        let page = conn.quote_literal(page)?;
+
+        let updated = conn
+            .execute(&format!(
+                "UPDATE plugin_visits SET hits = hits + 1 WHERE page = {}",
+                page
+            ))
+            .await?;
+
+        if updated == 0 {
+            conn.execute(&format!(
+                "INSERT INTO plugin_visits (page, hits) VALUES ({}, 1)",
+                page
+            ))
+            .await?;
+        }
+    }
+
+    conn.commit().await?;
+
+    conn.begin().await?;
+    assert_eq!(conn.execute("DELETE FROM plugin_visits").await?, 2);
+    conn.rollback().await?;
+
+    let rows = conn
+        .fetch_all(
+            "SELECT page, hits, CAST(hits AS REAL) / 2, hits > 1, NULL AS missing \
+             FROM plugin_visits ORDER BY page",
+        )
+        .await?;
+
+    let mut visits = Vec::new();
+
+    for row in rows {
+        assert_eq!(row.len(), 5);
+        assert_eq!(row.column_name(0), Some("page"));
+        assert_eq!(row.try_column_index("missing")?, 4);
+        assert!(row.try_is_null(4)?);
+        assert!(row.try_get_i64(0).is_err());
+
+        visits.push((
+            row.try_get_string(0)?.unwrap(),
+            row.try_get_i64(1)?.unwrap(),
+            row.try_get_f64(2)?.unwrap(),
+            row.try_get_bool(3)?.unwrap(),
+        ));
+    }
+
+    Ok(visits)
+}
+
+#[sqlx_macros::test]
+async fn it_executes_and_closes_prepared_statements() -> anyhow::Result<()> {
+    let mut conn = new::<Postgres>().await?;
+
+    let stmt = conn.prepare("SELECT $1::int4 + 1").await?;
+
+    for i in 0..3_i32 {
+        let value: i32 = stmt.query_scalar().bind(i).fetch_one(&mut conn).await?;
+        assert_eq!(value, i + 1);
+    }
+
+    let prepared = conn.prepared_statements().await?;
+
+    assert_eq!(prepared.len(), 1);
+    assert_eq!(prepared[0].sql, "SELECT $1::int4 + 1");
+    assert_eq!(prepared[0].hits, 3);
+
+    // the statement is bound to the connection which prepared it, unless detached
+    let mut other = new::<Postgres>().await?;
+    let res = stmt.query().bind(1_i32).execute(&mut other).await;
+
+    assert!(matches!(
+        res,
+        Err(sqlx::Error::StatementConnectionMismatch { .. })
+    ));
+
+    let mut detached = stmt.clone();
+    detached.detach();
+
+    let value: i32 = detached
+        .query_scalar()
+        .bind(1_i32)
+        .fetch_one(&mut other)
+        .await?;
+
+    assert_eq!(value, 2);
+
+    conn.close_statement(stmt.clone()).await?;
+
+    assert!(conn.prepared_statements().await?.is_empty());
+
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM pg_prepared_statements")
+        .persistent(false)
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(count, 0);
+
+    // a closed statement is prepared again
+    let value: i32 = stmt
+        .query_scalar()
+        .bind(41_i32)
+        .fetch_one(&mut conn)
+        .await?;
+    assert_eq!(value, 42);
+
+    Ok(())
+}
+
+#[cfg(feature = "serde_row")]
+#[sqlx_macros::test]
+async fn it_deserializes_rows_with_serde() -> anyhow::Result<()> {
+    use sqlx::serde_row::DeserializeRow;
+
+    #[derive(Debug, PartialEq, serde::Deserialize)]
+    struct Tags {
+        tags: Vec<String>,
+    }
+
+    #[derive(Debug, PartialEq, serde::Deserialize)]
+    struct Record {
+        id: i32,
+        data: Tags,
+        ids: Vec<i64>,
+    }
+
+    let mut conn = new::<Postgres>().await?;
+
+    let row = sqlx::query(
+        r#"SELECT 1 AS id, '{"tags": ["a", "b"]}'::jsonb AS data, ARRAY[1, 2]::int8[] AS ids,
+           12.50::numeric AS price, 'x'::bytea AS raw, point(1, 2) AS location"#,
+    )
+    .fetch_one(&mut conn)
+    .await?;
+
+    // a JSON column deserializes as the structure it contains
+    let record: Record = row.deserialize()?;
+
+    assert_eq!(
+        record,
+        Record {
+            id: 1,
+            data: Tags {
+                tags: vec!["a".into(), "b".into()]
+            },
+            ids: vec![1, 2],
+        }
+    );
+
+    // `POINT` has no serde mapping
+    let res = row.deserialize::<serde_json::Value>();
+
+    assert!(
+        matches!(res, Err(sqlx::Error::ColumnDecode { ref index, .. }) if index == "\"location\""),
+        "{:?}",
+        res
+    );
+
+    let value: serde_json::Value = sqlx::query(
+        r#"SELECT '{"a": {"b": null}}'::json AS data, 12.50::numeric AS price, NULL::text AS note"#,
+    )
+    .fetch_one_deserialized(&mut conn)
+    .await?;
+
+    assert_eq!(
+        value,
+        serde_json::json!({ "data": { "a": { "b": null } }, "price": "12.50", "note": null })
+    );
+
+    let missing: Option<Record> = sqlx::query("SELECT 1 AS id WHERE false")
+        .fetch_optional_deserialized(&mut conn)
+        .await?;
+
+    assert_eq!(missing, None);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_explains_queries() -> anyhow::Result<()> {
+    let mut conn = new::<Postgres>().await?;
+
+    conn.execute(
+        "CREATE TEMPORARY TABLE explained (id INT PRIMARY KEY, name TEXT);
+         INSERT INTO explained SELECT n, 'name ' || n FROM generate_series(1, 100) n",
+    )
+    .await?;
+
+    // the arguments are bound to `EXPLAIN`
+    let plan = conn
+        .explain(
+            sqlx::query("SELECT name FROM explained WHERE name = $1").bind("name 7"),
+            PgExplainOptions::new().analyze(true).buffers(true),
+        )
+        .await?;
+
+    let scan = plan
+        .iter()
+        .find(|node| node.relation_name.as_deref() == Some("explained"))
+        .unwrap();
+
+    assert_eq!(scan.node_type, "Seq Scan");
+    assert_eq!(scan.actual_rows, Some(1.0));
+    assert_eq!(scan.properties["Rows Removed by Filter"], 99);
+    assert!(scan.buffers.is_some());
+    assert!(plan.execution_time.is_some());
+    assert!(
+        plan.to_string().starts_with("Seq Scan on explained"),
+        "{}",
+        plan
+    );
+
+    // without arguments, the generic plan is explained
+    let plan = conn
+        .explain(
+            "SELECT name FROM explained WHERE id = $1",
+            PgExplainOptions::new(),
+        )
+        .await?;
+
+    assert_eq!(plan.root.index_name.as_deref(), Some("explained_pkey"));
+    assert_eq!(plan.root.actual_rows, None);
+
+    let res = conn
+        .explain(
+            "SELECT name FROM explained WHERE id = $1",
+            PgExplainOptions::new().analyze(true),
+        )
+        .await;
+
+    assert!(
+        matches!(res, Err(sqlx::Error::Configuration(_))),
+        "{:?}",
+        res
+    );
+
+    // analyzing runs the statement
+    let mut tx = conn.begin().await?;
+
+    let plan = tx
+        .explain(
+            "DELETE FROM explained WHERE id > 50",
+            PgExplainOptions::new().analyze(true),
+        )
+        .await?;
+
+    assert_eq!(plan.root.node_type, "ModifyTable");
+    assert_eq!(plan.root.properties["Operation"], "Delete");
+
+    let count: i64 = sqlx::query_scalar("SELECT count(*) FROM explained")
+        .fetch_one(&mut tx)
+        .await?;
+    assert_eq!(count, 50);
+
+    tx.rollback().await?;
+
+    // a statement which can't be explained fails like it would without `EXPLAIN`
+    assert!(conn
+        .explain("SELECT * FROM not_explained", PgExplainOptions::new())
+        .await
+        .is_err());
+
+    // the statements prepared to be explained were closed
+    let prepared: i64 = sqlx::query_scalar("SELECT count(*) FROM pg_prepared_statements")
+        .fetch_one(&mut conn)
+        .await?;
+    assert_eq!(prepared as usize, conn.prepared_statements().await?.len());
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_explains_slow_statements() -> anyhow::Result<()> {
+    use sqlx::logger::{LoggedStatement, StatementLogger};
+    use sqlx::ConnectOptions;
+    use std::sync::Mutex;
+
+    // the plans are logged at the level of slow statements, which must be enabled
+    setup_if_needed();
+
+    #[derive(Default)]
+    struct Logger(Mutex<Vec<String>>);
+
+    impl StatementLogger for Logger {
+        fn log(&self, statement: &LoggedStatement<'_>) {
+            self.0.lock().unwrap().push(statement.sql.to_owned());
+        }
+    }
+
+    let logger = Arc::new(Logger::default());
+
+    // the statements run to explain another one, e.g. `EXPLAIN … EXECUTE _sqlx_explain_1(NULL)`
+    let explained = || -> Vec<String> {
+        std::mem::take(&mut *logger.0.lock().unwrap())
+            .into_iter()
+            .filter(|sql| sql.starts_with("EXPLAIN"))
+            .collect()
+    };
+
+    // every statement is slow
+    let mut options: PgConnectOptions = env::var("DATABASE_URL")?.parse().unwrap();
+    options
+        .log_with(logger.clone())
+        .log_slow_statements(log::LevelFilter::Error, Duration::ZERO);
+
+    let mut conn = options
+        .clone()
+        .explain_slow_statements(Duration::ZERO)
+        .connect()
+        .await?;
+
+    conn.execute("CREATE TEMPORARY TABLE slow (id INT PRIMARY KEY)")
+        .await?;
+    assert!(explained().is_empty());
+
+    let id: Option<i32> = sqlx::query_scalar("SELECT id FROM slow WHERE id = $1")
+        .bind(1)
+        .fetch_optional(&mut conn)
+        .await?;
+    assert_eq!(id, None);
+    assert_eq!(explained().len(), 1);
+
+    // in a transaction, the statement is explained in a savepoint
+    let mut tx = conn.begin().await?;
+
+    sqlx::query("INSERT INTO slow VALUES ($1)")
+        .bind(1)
+        .execute(&mut tx)
+        .await?;
+    assert_eq!(explained().len(), 1);
+
+    // a statement which fails to be explained doesn't abort the transaction
+    tx.execute("SELECT * FROM slow; SELECT 1").await?;
+    assert!(explained().is_empty());
+
+    let count: i64 = sqlx::query_scalar("SELECT count(*) FROM slow")
+        .fetch_one(&mut tx)
+        .await?;
+    assert_eq!(count, 1);
+
+    tx.rollback().await?;
+
+    // the statements prepared to be explained were closed
+    let prepared: i64 = sqlx::query_scalar("SELECT count(*) FROM pg_prepared_statements")
+        .fetch_one(&mut conn)
+        .await?;
+    assert_eq!(prepared as usize, conn.prepared_statements().await?.len());
+
+    explained();
+
+    // the connections opened with the same options share the interval
+    let options = options.explain_slow_statements(Duration::from_secs(3600));
+    let mut a = options.connect().await?;
+    let mut b = options.connect().await?;
+
+    a.execute("SELECT 1").await?;
+    b.execute("SELECT 1").await?;
+    a.execute("SELECT 1").await?;
+
+    assert_eq!(explained().len(), 1);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_inserts_rows_in_bulk() -> anyhow::Result<()> {
+    #[derive(sqlx::InsertRow)]
+    #[sqlx(rename_all = "camelCase")]
+    struct Position {
+        pos_x: f64,
+        pos_y: f64,
+    }
+
+    #[derive(sqlx::InsertRow)]
+    struct Marker {
+        id: i32,
+        #[sqlx(flatten)]
+        position: Position,
+    }
+
+    #[derive(sqlx::InsertRow)]
+    struct Id {
+        id: i32,
+    }
+
+    fn markers(ids: std::ops::Range<i32>) -> impl Iterator<Item = Marker> {
+        ids.map(|id| Marker {
+            id,
+            position: Position {
+                pos_x: id as f64,
+                pos_y: -id as f64,
+            },
+        })
+    }
+
+    assert_eq!(
+        <Marker as sqlx::InsertRow<'_, Postgres>>::columns(),
+        ["id", "posX", "posY"]
+    );
+
+    let mut conn = new::<Postgres>().await?;
+
+    conn.execute(
+        "CREATE TEMPORARY TABLE bulk (id INT PRIMARY KEY, posX FLOAT8 NOT NULL, posY FLOAT8 NOT NULL)",
+    )
+    .await?;
+
+    // 3 statements of up to 21845 rows
+    let result = sqlx::insert("bulk", markers(0..50_000))
+        .transaction(true)
+        .execute(&mut conn)
+        .await?;
+    assert_eq!(result.rows_affected(), 50_000);
+
+    let (x, y): (f64, f64) = sqlx::query_as("SELECT posX, posY FROM bulk WHERE id = 43690")
+        .fetch_one(&mut conn)
+        .await?;
+    assert_eq!((x, y), (43690.0, -43690.0));
+
+    let result = sqlx::insert("bulk", markers(49_990..50_010))
+        .suffix("ON CONFLICT (id) DO UPDATE SET posY = 0")
+        .execute(&mut conn)
+        .await?;
+    assert_eq!(result.rows_affected(), 20);
+
+    // 65535 rows of a single column in the first statement, at the very limit
+    conn.execute("CREATE TEMPORARY TABLE bulk_ids (id INT PRIMARY KEY)")
+        .await?;
+
+    let result = sqlx::insert("bulk_ids", (0..70_000).map(|id| Id { id }))
+        .execute(&mut conn)
+        .await?;
+    assert_eq!(result.rows_affected(), 70_000);
+
+    Ok(())
+}
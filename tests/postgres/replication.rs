@@ -0,0 +1,222 @@
+use std::env;
+use std::str::FromStr;
+
+use sqlx::postgres::replication::{
+    PgOutputMessage, PgOutputOldRow, PgOutputValue, PgReplicationMessage,
+};
+use sqlx::postgres::{PgConnectOptions, PgConnection, Postgres};
+use sqlx::{Connection, Executor};
+use sqlx_test::{new, setup_if_needed};
+
+async fn connect_replication() -> anyhow::Result<PgConnection> {
+    setup_if_needed();
+
+    let options = PgConnectOptions::from_str(&env::var("DATABASE_URL")?)?.replication(true);
+
+    Ok(PgConnection::connect_with(&options).await?)
+}
+
+async fn next_change(
+    stream: &mut sqlx::postgres::replication::PgReplicationStream<&mut PgConnection>,
+) -> anyhow::Result<PgOutputMessage> {
+    loop {
+        match stream.recv().await? {
+            Some(PgReplicationMessage::XLogData(data)) => {
+                return Ok(PgOutputMessage::decode(data.data)?)
+            }
+            Some(PgReplicationMessage::PrimaryKeepalive(_)) => continue,
+            None => anyhow::bail!("replication stream ended unexpectedly"),
+        }
+    }
+}
+
+#[sqlx_macros::test]
+async fn it_streams_pgoutput_changes() -> anyhow::Result<()> {
+    let mut conn = new::<Postgres>().await?;
+    let mut repl = connect_replication().await?;
+
+    conn.execute(
+        r#"
+DROP PUBLICATION IF EXISTS sqlx_replication_test;
+DROP TABLE IF EXISTS sqlx_replication_test;
+
+CREATE TABLE sqlx_replication_test (id INT PRIMARY KEY, name TEXT);
+CREATE PUBLICATION sqlx_replication_test FOR TABLE sqlx_replication_test;
+"#,
+    )
+    .await?;
+
+    let slot = repl
+        .create_replication_slot("sqlx_replication_test", "pgoutput", true)
+        .await?;
+
+    assert_eq!(slot.slot_name, "sqlx_replication_test");
+    assert_eq!(slot.output_plugin, "pgoutput");
+
+    conn.execute(
+        r#"
+INSERT INTO sqlx_replication_test (id, name) VALUES (1, 'alice');
+UPDATE sqlx_replication_test SET name = NULL, id = 2 WHERE id = 1;
+DELETE FROM sqlx_replication_test;
+"#,
+    )
+    .await?;
+
+    let mut stream = repl
+        .start_replication(
+            "sqlx_replication_test",
+            slot.consistent_point,
+            [
+                ("proto_version", "1"),
+                ("publication_names", "sqlx_replication_test"),
+            ],
+        )
+        .await?;
+
+    // the statements were sent in a single query, so they are replicated as one transaction
+    let relation_id = match next_change(&mut stream).await? {
+        PgOutputMessage::Begin(_) => match next_change(&mut stream).await? {
+            PgOutputMessage::Relation(relation) => {
+                assert_eq!(relation.namespace, "public");
+                assert_eq!(relation.name, "sqlx_replication_test");
+                assert_eq!(relation.columns.len(), 2);
+                assert_eq!(relation.columns[0].name, "id");
+                assert!(relation.columns[0].is_key);
+                assert!(!relation.columns[1].is_key);
+
+                relation.id
+            }
+            other => anyhow::bail!("expected Relation, got {:?}", other),
+        },
+        other => anyhow::bail!("expected Begin, got {:?}", other),
+    };
+
+    match next_change(&mut stream).await? {
+        PgOutputMessage::Insert(insert) => {
+            assert_eq!(insert.relation_id, relation_id);
+            assert_eq!(
+                insert.new,
+                [
+                    PgOutputValue::Text("1".into()),
+                    PgOutputValue::Text("alice".into())
+                ]
+            );
+        }
+        other => anyhow::bail!("expected Insert, got {:?}", other),
+    }
+
+    match next_change(&mut stream).await? {
+        PgOutputMessage::Update(update) => {
+            assert_eq!(update.relation_id, relation_id);
+            // the key changed, so the old key is included
+            assert_eq!(
+                update.old,
+                Some(PgOutputOldRow::Key(vec![
+                    PgOutputValue::Text("1".into()),
+                    PgOutputValue::Null
+                ]))
+            );
+            assert_eq!(
+                update.new,
+                [PgOutputValue::Text("2".into()), PgOutputValue::Null]
+            );
+        }
+        other => anyhow::bail!("expected Update, got {:?}", other),
+    }
+
+    match next_change(&mut stream).await? {
+        PgOutputMessage::Delete(delete) => {
+            assert_eq!(delete.relation_id, relation_id);
+            assert_eq!(
+                delete.old,
+                PgOutputOldRow::Key(vec![PgOutputValue::Text("2".into()), PgOutputValue::Null])
+            );
+        }
+        other => anyhow::bail!("expected Delete, got {:?}", other),
+    }
+
+    let end_lsn = match next_change(&mut stream).await? {
+        PgOutputMessage::Commit(commit) => commit.end_lsn,
+        other => anyhow::bail!("expected Commit, got {:?}", other),
+    };
+
+    stream.set_flushed_lsn(end_lsn);
+    stream.set_applied_lsn(end_lsn);
+
+    // asking for a reply makes the server send a keepalive right away
+    stream.send_status_update(true).await?;
+
+    loop {
+        match stream.recv().await? {
+            Some(PgReplicationMessage::PrimaryKeepalive(keepalive)) => {
+                assert!(keepalive.wal_end >= end_lsn);
+                break;
+            }
+            Some(PgReplicationMessage::XLogData(_)) => continue,
+            None => anyhow::bail!("replication stream ended unexpectedly"),
+        }
+    }
+
+    stream.finish().await?;
+
+    let confirmed: String = sqlx::query_scalar(
+        "SELECT confirmed_flush_lsn::text FROM pg_replication_slots WHERE slot_name = $1",
+    )
+    .bind("sqlx_replication_test")
+    .fetch_one(&mut conn)
+    .await?;
+
+    assert_eq!(
+        confirmed.parse::<sqlx::postgres::replication::PgLsn>()?,
+        end_lsn
+    );
+
+    // dropping a stream ends it the next time the connection is used
+    let stream = repl
+        .start_replication(
+            "sqlx_replication_test",
+            end_lsn,
+            [
+                ("proto_version", "1"),
+                ("publication_names", "sqlx_replication_test"),
+            ],
+        )
+        .await?;
+
+    drop(stream);
+
+    repl.drop_replication_slot("sqlx_replication_test", false)
+        .await?;
+    repl.close().await?;
+
+    conn.execute(
+        r#"
+DROP PUBLICATION sqlx_replication_test;
+DROP TABLE sqlx_replication_test;
+"#,
+    )
+    .await?;
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_fails_to_stream_from_missing_slot() -> anyhow::Result<()> {
+    let mut repl = connect_replication().await?;
+
+    let res = repl
+        .start_replication(
+            "sqlx_replication_missing",
+            "0/0".parse()?,
+            std::iter::empty::<(&str, &str)>(),
+        )
+        .await;
+
+    assert!(res.is_err());
+    drop(res);
+
+    // the connection is still usable afterwards
+    repl.execute("SELECT 1").await?;
+
+    Ok(())
+}
@@ -0,0 +1,13 @@
+#!/bin/sh
+set -e
+
+# Clone the primary at `$PRIMARY_HOST` on the first start, and follow it as a hot standby
+if [ ! -s "$PGDATA/PG_VERSION" ]; then
+    until pg_isready -h "$PRIMARY_HOST" -U postgres; do
+        sleep 1
+    done
+
+    pg_basebackup -h "$PRIMARY_HOST" -U postgres -D "$PGDATA" -R -X stream
+fi
+
+exec docker-entrypoint.sh "$@"
@@ -0,0 +1,5 @@
+#!/bin/sh
+set -e
+
+# Let the standby stream the WAL of the primary, authenticated with the password of `postgres`
+echo "host replication all all scram-sha-256" >> "$PGDATA/pg_hba.conf"
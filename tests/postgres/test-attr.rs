@@ -92,3 +92,61 @@ async fn it_gets_comments(pool: PgPool) -> sqlx::Result<()> {
 
     Ok(())
 }
+
+// Fixtures from explicit paths and from the scripts of a directory are applied in order
+#[sqlx::test(
+    migrator = "MIGRATOR",
+    fixtures("fixtures/users.sql"),
+    fixtures(path = "../postgres/fixtures", scripts("posts", "comments.sql"))
+)]
+async fn it_gets_fixtures_from_paths(pool: PgPool) -> sqlx::Result<()> {
+    let comment_count: i64 = sqlx::query_scalar("SELECT count(*) FROM comment")
+        .fetch_one(&pool)
+        .await?;
+
+    assert_eq!(comment_count, 3);
+
+    Ok(())
+}
+
+// `CREATE INDEX CONCURRENTLY` fails within a transaction
+#[sqlx::test(migrator = "MIGRATOR", fixtures("indexes"))]
+async fn it_applies_fixtures_without_a_transaction(pool: PgPool) -> sqlx::Result<()> {
+    let indexes: Vec<String> = sqlx::query_scalar(
+        "SELECT indexname::text FROM pg_indexes WHERE indexname IN ('post_user_id', 'comment_post_id') ORDER BY indexname",
+    )
+    .fetch_all(&pool)
+    .await?;
+
+    assert_eq!(indexes, ["comment_post_id", "post_user_id"]);
+
+    Ok(())
+}
+
+async fn failing_teardown(_pool: PgPool) -> sqlx::Result<()> {
+    Err(sqlx::Error::RowNotFound)
+}
+
+#[sqlx::test(teardown = "failing_teardown")]
+#[should_panic(expected = "failed to tear down")]
+async fn it_fails_when_the_teardown_fails(_pool: PgPool) {}
+
+async fn panicking_teardown(pool: PgPool) -> sqlx::Result<()> {
+    // the test database is still there
+    let user_count: i64 = sqlx::query_scalar(r#"SELECT count(*) FROM "user""#)
+        .fetch_one(&pool)
+        .await?;
+
+    panic!("tore down {} users", user_count);
+}
+
+#[sqlx::test(migrator = "MIGRATOR", teardown = "panicking_teardown")]
+#[should_panic(expected = "tore down 1 users")]
+async fn it_tears_down_after_a_panic(pool: PgPool) {
+    sqlx::query(r#"INSERT INTO "user"(username) VALUES ('alice')"#)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    panic!("the test failed");
+}
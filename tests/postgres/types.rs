@@ -49,6 +49,38 @@ test_prepared_type!(byte_slice<&[u8]>(Postgres,
         == &[0_u8, 0, 0, 0, 0x52][..]
 ));
 
+test_type!(byte_bytes<sqlx::types::Bytes>(Postgres,
+    "E'\\\\xDEADBEEF'::bytea"
+        == sqlx::types::Bytes::from_static(&[0xDE, 0xAD, 0xBE, 0xEF]),
+    "E'\\\\x'::bytea"
+        == sqlx::types::Bytes::new()
+));
+
+#[sqlx_macros::test]
+async fn test_bytes_shares_row_buffer() -> anyhow::Result<()> {
+    use sqlx::types::Bytes;
+    use sqlx::{Executor, Row};
+
+    let mut conn = sqlx_test::new::<Postgres>().await?;
+
+    let row = conn
+        .fetch_one(sqlx::query("SELECT repeat('x', 1048576)::bytea"))
+        .await?;
+
+    let slice: &[u8] = row.try_get(0)?;
+    let bytes: Bytes = row.try_get(0)?;
+
+    // the value is a view of the row, not a copy of it
+    assert_eq!(bytes.as_ptr(), slice.as_ptr());
+    assert_eq!(bytes.len(), 1048576);
+
+    // and outlives the row
+    drop(row);
+    assert!(bytes.iter().all(|b| *b == b'x'));
+
+    Ok(())
+}
+
 test_type!(byte_array_empty<[u8; 0]>(Postgres,
     "E'\\\\x'::bytea" == [0_u8; 0],
 ));
@@ -301,6 +333,13 @@ mod chrono {
         "TIMETZ '05:10:20.115100-05'" == PgTimeTz { time: NaiveTime::from_hms_micro(5, 10, 20, 115100), offset: FixedOffset::west(60 * 60 * 5) },
         "TIMETZ '05:10:20+02'" == PgTimeTz { time: NaiveTime::from_hms(5, 10, 20), offset: FixedOffset::east(60 * 60 * 2 )}
     ));
+
+    test_prepared_type!(chrono_duration<sqlx::types::chrono::Duration>(Postgres,
+        "INTERVAL '1h'" == sqlx::types::chrono::Duration::hours(1),
+        "INTERVAL '-1 hours'" == sqlx::types::chrono::Duration::hours(-1),
+        "INTERVAL '2 days -1 hours 10.000001 seconds'"
+            == sqlx::types::chrono::Duration::microseconds((47 * 3_600 + 10) * 1_000_000 + 1),
+    ));
 }
 
 #[cfg(feature = "time")]
@@ -343,6 +382,13 @@ mod time_tests {
         "TIMETZ '05:10:20.115100-05'" == PgTimeTz { time: time!(5:10:20.115100), offset: UtcOffset::from_whole_seconds(-(60 * 60 * 5)).unwrap() },
         "TIMETZ '05:10:20+02'" == PgTimeTz { time: time!(5:10:20), offset: UtcOffset::from_whole_seconds(60 * 60 * 2 ).unwrap() }
     ));
+
+    test_prepared_type!(time_duration<time::Duration>(Postgres,
+        "INTERVAL '1h'" == time::Duration::hours(1),
+        "INTERVAL '-1 hours'" == time::Duration::hours(-1),
+        "INTERVAL '2 days -1 hours 10.000001 seconds'"
+            == time::Duration::microseconds((47 * 3_600 + 10) * 1_000_000 + 1),
+    ));
 }
 
 #[cfg(feature = "json")]
@@ -541,6 +587,12 @@ test_prepared_type!(interval<PgInterval>(
         },
 ));
 
+test_prepared_type!(std_duration<std::time::Duration>(
+    Postgres,
+    "INTERVAL '1h'" == std::time::Duration::from_secs(3_600),
+    "INTERVAL '1 day 10.000001 seconds'" == std::time::Duration::from_micros(86_410_000_001),
+));
+
 test_prepared_type!(money<PgMoney>(Postgres, "123.45::money" == PgMoney(12345)));
 
 test_prepared_type!(money_vec<Vec<PgMoney>>(Postgres,
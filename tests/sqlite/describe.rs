@@ -2,7 +2,7 @@ use sqlx::error::DatabaseError;
 use sqlx::sqlite::{SqliteConnectOptions, SqliteError};
 use sqlx::ConnectOptions;
 use sqlx::TypeInfo;
-use sqlx::{sqlite::Sqlite, Column, Executor};
+use sqlx::{sqlite::Sqlite, Column, Either, Executor};
 use sqlx_test::new;
 use std::env;
 
@@ -380,3 +380,28 @@ async fn it_describes_union() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[sqlx_macros::test]
+async fn it_describes_with_any_executor() -> anyhow::Result<()> {
+    let pool = sqlx_test::pool::<Sqlite>().await?;
+
+    let d = sqlx::describe(&pool, "SELECT id, owner_id FROM tweet WHERE text = ?").await?;
+
+    assert_eq!(d.columns()[0].name(), "id");
+    assert_eq!(d.columns()[1].name(), "owner_id");
+
+    assert_eq!(d.nullable(0), Some(false));
+    assert_eq!(d.nullable(1), Some(true));
+
+    assert!(matches!(d.parameters(), Some(Either::Right(1))));
+
+    let mut conn = pool.acquire().await?;
+
+    // the same as the query macros infer
+    let d = sqlx::describe(&mut conn, "SELECT COUNT(*), MAX(owner_id) FROM tweet").await?;
+
+    assert_eq!(d.nullable(0), Some(false));
+    assert_eq!(d.nullable(1), None);
+
+    Ok(())
+}
@@ -0,0 +1,2 @@
+-- sqlx:no-transaction
+VACUUM;
@@ -1,4 +1,4 @@
-use sqlx::Sqlite;
+use sqlx::{Connection, Sqlite};
 use sqlx_test::new;
 
 #[sqlx_macros::test]
@@ -110,6 +110,83 @@ async fn test_query_as_raw() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[sqlx_macros::test]
+async fn test_query_file_include() -> anyhow::Result<()> {
+    let mut conn = new::<Sqlite>().await?;
+
+    let tweet = sqlx::query_file!("tests/sqlite/queries/tweet-with-owner.sql", 1)
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(tweet.id, 1);
+    assert_eq!(tweet.owner_name, "Herp Derpinson");
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn test_query_fragments() -> anyhow::Result<()> {
+    let mut conn = new::<Sqlite>().await?;
+    let mut tx = conn.begin().await?;
+
+    sqlx::query!("INSERT INTO tweet (id, text, owner_id) VALUES (2, 'b', 1), (3, 'a', NULL)")
+        .execute(&mut tx)
+        .await?;
+
+    for (order, owned, expected) in [
+        ("id DESC", "owner_id IS NOT NULL", vec![2, 1]),
+        ("text ASC", "owner_id IS NOT NULL", vec![1, 2]),
+        ("text ASC", "1", vec![1, 3, 2]),
+    ] {
+        let tweets = sqlx::query!(
+            "SELECT id FROM tweet WHERE id >= ? AND {owned} ORDER BY {order}",
+            1,
+            order = order in ["id DESC", "text ASC"],
+            owned = owned in ["owner_id IS NOT NULL", "1"],
+        )
+        .fetch_all(&mut tx)
+        .await?;
+
+        let ids: Vec<_> = tweets.iter().map(|tweet| tweet.id).collect();
+        assert_eq!(ids, expected, "{}, {}", order, owned);
+    }
+
+    // other values are an error instead of being executed
+    let order = String::from("rowid; DELETE FROM tweet");
+    let res = sqlx::query!(
+        "SELECT id FROM tweet ORDER BY {order}",
+        order = order in ["id DESC", "text ASC"]
+    )
+    .fetch_all(&mut tx)
+    .await;
+
+    match res {
+        Err(sqlx::Error::Configuration(e)) => assert_eq!(
+            e.to_string(),
+            "unexpected value of the query fragments: \
+             `order` must be one of \"id DESC\", \"text ASC\", got \"rowid; DELETE FROM tweet\""
+        ),
+        res => panic!("expected a configuration error, got {:?}", res),
+    }
+
+    let res = sqlx::query_scalar!(
+        "SELECT COUNT(*) FROM tweet WHERE {owned}",
+        owned = "owner_id = 1; DELETE FROM tweet" in ["owner_id IS NOT NULL", "1"]
+    )
+    .fetch_one(&mut tx)
+    .await;
+    assert!(matches!(res, Err(sqlx::Error::Configuration(_))));
+
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM tweet")
+        .fetch_one(&mut tx)
+        .await?;
+    assert_eq!(count, 3);
+
+    tx.rollback().await?;
+
+    Ok(())
+}
+
 #[sqlx_macros::test]
 async fn test_query_scalar() -> anyhow::Result<()> {
     let mut conn = new::<Sqlite>().await?;
@@ -330,6 +407,52 @@ async fn test_column_override_exact_nullable() -> anyhow::Result<()> {
 
     assert_eq!(record.id, Some(MyInt(1)));
 
+    // `Option` isn't added twice
+    let record = sqlx::query!(r#"select id as "id?: Option<MyInt>" from tweet"#)
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(record.id, Some(MyInt(1)));
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn test_column_override_by_name() -> anyhow::Result<()> {
+    let mut conn = new::<Sqlite>().await?;
+
+    #[derive(Debug, PartialEq)]
+    struct TweetWithOwner {
+        id: MyInt,
+        text: String,
+        is_sent: Option<bool>,
+        owner_id: i64,
+        owner: Option<i64>,
+        name: Option<String>,
+    }
+
+    let tweet = sqlx::query_as!(
+        TweetWithOwner,
+        "SELECT * FROM tweet LEFT JOIN (SELECT id AS owner, name FROM accounts) ON owner = owner_id
+         WHERE owner_id IS NOT NULL AND id = ?",
+        overrides = { id: MyInt, is_sent?, owner_id!, owner?, name?: Option<String> },
+        1
+    )
+    .fetch_one(&mut conn)
+    .await?;
+
+    assert_eq!(
+        tweet,
+        TweetWithOwner {
+            id: MyInt(1),
+            text: "#sqlx is pretty cool!".to_owned(),
+            is_sent: Some(true),
+            owner_id: 1,
+            owner: Some(1),
+            name: Some("Herp Derpinson".to_owned()),
+        }
+    );
+
     Ok(())
 }
 
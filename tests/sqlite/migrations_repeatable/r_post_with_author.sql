@@ -0,0 +1,5 @@
+DROP VIEW IF EXISTS post_with_author;
+
+CREATE VIEW post_with_author AS
+SELECT post_id, username, content
+FROM post JOIN user USING (user_id);
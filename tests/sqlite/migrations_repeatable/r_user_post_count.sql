@@ -0,0 +1,6 @@
+DROP VIEW IF EXISTS user_post_count;
+
+CREATE VIEW user_post_count AS
+SELECT user.user_id, username, COUNT(post_id) AS posts
+FROM user LEFT JOIN post USING (user_id)
+GROUP BY user.user_id;
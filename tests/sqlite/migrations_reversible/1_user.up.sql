@@ -0,0 +1,4 @@
+CREATE TABLE user (
+    user_id INTEGER PRIMARY KEY,
+    username TEXT NOT NULL
+);
@@ -0,0 +1,5 @@
+CREATE TABLE post (
+    post_id INTEGER PRIMARY KEY,
+    user_id INTEGER NOT NULL REFERENCES user (user_id),
+    content TEXT NOT NULL
+);
@@ -0,0 +1,5 @@
+CREATE TABLE comment (
+    comment_id INTEGER PRIMARY KEY,
+    post_id INTEGER NOT NULL REFERENCES post (post_id),
+    content TEXT NOT NULL
+);
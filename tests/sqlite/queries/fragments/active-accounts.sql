@@ -0,0 +1 @@
+SELECT id, name FROM accounts WHERE is_active
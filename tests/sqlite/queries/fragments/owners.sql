@@ -0,0 +1,3 @@
+WITH owners AS (
+    -- sqlx:include "active-accounts.sql"
+)
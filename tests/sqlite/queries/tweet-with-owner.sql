@@ -0,0 +1,5 @@
+-- sqlx:include "fragments/owners.sql"
+SELECT tweet.id, owners.name AS owner_name
+FROM tweet
+JOIN owners ON owners.id = tweet.owner_id
+WHERE tweet.id = ?
@@ -424,298 +424,3491 @@ CREATE TEMPORARY TABLE users (id INTEGER PRIMARY KEY, name TEXT NOT NULL COLLATE
 }
 
 #[sqlx_macros::test]
-async fn it_caches_statements() -> anyhow::Result<()> {
-    let mut conn = new::<Sqlite>().await?;
+async fn it_supports_collations_from_options() -> anyhow::Result<()> {
+    let dir = tempdir::TempDir::new("sqlite_collation_test")?;
 
-    // Initial PRAGMAs are not cached as we are not going to execute
-    // them more than once.
-    assert_eq!(0, conn.cached_statements_size());
+    let options = SqliteConnectOptions::new()
+        .filename(dir.path().join("collation.db"))
+        .create_if_missing(true)
+        .collation("unicode_ci", |l, r| l.to_lowercase().cmp(&r.to_lowercase()));
 
-    // `&str` queries are not persistent.
-    let row = conn.fetch_one("SELECT 100 AS val").await?;
-    let val: i32 = row.get("val");
-    assert_eq!(val, 100);
-    assert_eq!(0, conn.cached_statements_size());
+    let pool = SqlitePoolOptions::new()
+        .max_connections(2)
+        .connect_with(options)
+        .await?;
 
-    // `Query` is persistent by default.
-    let mut conn = new::<Sqlite>().await?;
-    for i in 0..2 {
-        let row = sqlx::query("SELECT ? AS val")
-            .bind(i)
-            .fetch_one(&mut conn)
-            .await?;
+    pool.execute(
+        r#"
+CREATE TABLE names (name TEXT NOT NULL);
+CREATE INDEX idx_names_name ON names (name COLLATE unicode_ci);
+INSERT INTO names (name) VALUES ('éclair'), ('Zoë'), ('apple'), ('Émile'), ('zebra');
+        "#,
+    )
+    .await?;
 
-        let val: i32 = row.get("val");
+    // the collation is created on every connection of the pool
+    let mut first = pool.acquire().await?;
+    let mut second = pool.acquire().await?;
 
-        assert_eq!(i, val);
-    }
-    assert_eq!(1, conn.cached_statements_size());
+    for conn in [&mut first, &mut second] {
+        let names: Vec<String> =
+            sqlx::query_scalar("SELECT name FROM names ORDER BY name COLLATE unicode_ci")
+                .fetch_all(&mut *conn)
+                .await?;
 
-    // Cache can be cleared.
-    conn.clear_cached_statements().await?;
-    assert_eq!(0, conn.cached_statements_size());
+        assert_eq!(names, ["apple", "zebra", "Zoë", "éclair", "Émile"]);
 
-    // `Query` is not persistent if `.persistent(false)` is used
-    // explicitly.
-    let mut conn = new::<Sqlite>().await?;
-    for i in 0..2 {
-        let row = sqlx::query("SELECT ? AS val")
-            .bind(i)
-            .persistent(false)
-            .fetch_one(&mut conn)
-            .await?;
+        let name: String =
+            sqlx::query_scalar("SELECT name FROM names WHERE name = 'ÉMILE' COLLATE unicode_ci")
+                .fetch_one(&mut *conn)
+                .await?;
 
-        let val: i32 = row.get("val");
+        assert_eq!(name, "Émile");
 
-        assert_eq!(i, val);
+        // the index is usable for lookups with the collation
+        let plan: Vec<SqliteRow> = conn
+            .fetch_all(
+                "EXPLAIN QUERY PLAN SELECT name FROM names WHERE name = 'ZOË' COLLATE unicode_ci",
+            )
+            .await?;
+        let detail: String = plan[0].try_get("detail")?;
+
+        assert!(detail.contains("idx_names_name"), "{}", detail);
     }
-    assert_eq!(0, conn.cached_statements_size());
+
+    // and applies to attached databases as well
+    first
+        .execute(
+            r#"
+ATTACH DATABASE ':memory:' AS other;
+CREATE TABLE other.names (name TEXT NOT NULL COLLATE unicode_ci);
+INSERT INTO other.names (name) VALUES ('b'), ('A'), ('c');
+            "#,
+        )
+        .await?;
+
+    let names: Vec<String> = sqlx::query_scalar("SELECT name FROM other.names ORDER BY name")
+        .fetch_all(&mut first)
+        .await?;
+
+    assert_eq!(names, ["A", "b", "c"]);
 
     Ok(())
 }
 
+#[cfg(unix)]
 #[sqlx_macros::test]
-async fn it_can_prepare_then_execute() -> anyhow::Result<()> {
-    let mut conn = new::<Sqlite>().await?;
-    let mut tx = conn.begin().await?;
+async fn it_loads_extensions() -> anyhow::Result<()> {
+    let dir = tempdir::TempDir::new("sqlite_extension_test")?;
+    let source = dir.path().join("noop.c");
+    let library = dir.path().join("libnoop.so");
+
+    // the entry point of `libnoop.so` is found by its name, `sqlite3_noop_init`
+    std::fs::write(
+        &source,
+        r#"
+int sqlite3_noop_init(void *db, char **error, const void *api) { return 0; }
+int failing_init(void *db, char **error, const void *api) { return 1; }
+        "#,
+    )?;
+
+    let built = std::process::Command::new("cc")
+        .args(["-shared", "-fPIC", "-o"])
+        .arg(&library)
+        .arg(&source)
+        .status();
+
+    if !matches!(built, Ok(status) if status.success()) {
+        // no C compiler to build the extension with
+        return Ok(());
+    }
 
-    let _ = sqlx::query("INSERT INTO tweet ( id, text ) VALUES ( 2, 'Hello, World' )")
-        .execute(&mut tx)
+    let library = library.to_str().unwrap().to_owned();
+
+    let pool = SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect_with(SqliteConnectOptions::new().extension(library.clone()))
         .await?;
 
-    let tweet_id: i32 = 2;
+    // the SQL function stays disabled
+    let res = sqlx::query("SELECT load_extension(?)")
+        .bind(&library)
+        .execute(&pool)
+        .await;
 
-    let statement = tx.prepare("SELECT * FROM tweet WHERE id = ?1").await?;
+    assert!(res.unwrap_err().to_string().contains("not authorized"));
 
-    assert_eq!(statement.column(0).name(), "id");
-    assert_eq!(statement.column(1).name(), "text");
-    assert_eq!(statement.column(2).name(), "is_sent");
-    assert_eq!(statement.column(3).name(), "owner_id");
+    // the custom entry point is called
+    let err = SqliteConnectOptions::new()
+        .extension_with_entrypoint(library.clone(), "failing_init")
+        .connect()
+        .await
+        .unwrap_err();
 
-    assert_eq!(statement.column(0).type_info().name(), "INTEGER");
-    assert_eq!(statement.column(1).type_info().name(), "TEXT");
-    assert_eq!(statement.column(2).type_info().name(), "BOOLEAN");
-    assert_eq!(statement.column(3).type_info().name(), "INTEGER");
+    assert!(
+        err.to_string().contains("error during initialization"),
+        "{}",
+        err
+    );
 
-    let row = statement.query().bind(tweet_id).fetch_one(&mut tx).await?;
-    let tweet_text: &str = row.try_get("text")?;
+    // the error of the OS is included
+    let missing = dir.path().join("missing.so");
+    let err = SqliteConnectOptions::new()
+        .extension(missing.to_str().unwrap().to_owned())
+        .connect()
+        .await
+        .unwrap_err();
 
-    assert_eq!(tweet_text, "Hello, World");
+    assert!(err.to_string().contains("missing.so"), "{}", err);
 
     Ok(())
 }
 
 #[sqlx_macros::test]
-async fn it_resets_prepared_statement_after_fetch_one() -> anyhow::Result<()> {
-    let mut conn = new::<Sqlite>().await?;
+async fn it_backs_up_and_restores() -> anyhow::Result<()> {
+    let dir = tempdir::TempDir::new("sqlite_backup_test")?;
+    let path = dir.path().join("backup.db");
 
-    conn.execute("CREATE TEMPORARY TABLE foobar (id INTEGER)")
+    // an in-memory database, large enough to take several pages
+    let mut conn = SqliteConnection::connect(":memory:").await?;
+
+    conn.execute(
+        r#"
+CREATE TABLE items (id INTEGER PRIMARY KEY, data TEXT NOT NULL);
+WITH RECURSIVE n(i) AS (SELECT 1 UNION ALL SELECT i + 1 FROM n WHERE i < 1000)
+INSERT INTO items (data) SELECT printf('%.100c', 'x') FROM n;
+        "#,
+    )
+    .await?;
+
+    let steps: Vec<_> = conn
+        .backup(&path)
+        .with_pages_per_step(10)
+        .progress()
+        .try_collect()
         .await?;
-    conn.execute("INSERT INTO foobar VALUES (42)").await?;
 
-    let r = sqlx::query("SELECT id FROM foobar")
-        .fetch_one(&mut conn)
+    assert!(steps.len() > 1);
+    assert!(steps.iter().all(|step| step.total() == steps[0].total()));
+    assert!(steps
+        .windows(2)
+        .all(|w| w[0].remaining() > w[1].remaining()));
+    assert_eq!(steps.last().unwrap().remaining(), 0);
+
+    let mut backup = SqliteConnection::connect(path.to_str().unwrap()).await?;
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM items")
+        .fetch_one(&mut backup)
         .await?;
-    let x: i32 = r.try_get("id")?;
-    assert_eq!(x, 42);
 
-    conn.execute("DROP TABLE foobar").await?;
+    assert_eq!(count, 1000);
 
-    Ok(())
-}
+    backup.execute("DELETE FROM items WHERE id > 10").await?;
+    backup.close().await?;
 
-#[sqlx_macros::test]
-async fn it_resets_prepared_statement_after_fetch_many() -> anyhow::Result<()> {
-    let mut conn = new::<Sqlite>().await?;
+    // the whole database is replaced by the backup
+    conn.backup_from(&path).await?;
 
-    conn.execute("CREATE TEMPORARY TABLE foobar (id INTEGER)")
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM items")
+        .fetch_one(&mut conn)
         .await?;
-    conn.execute("INSERT INTO foobar VALUES (42)").await?;
-    conn.execute("INSERT INTO foobar VALUES (43)").await?;
 
-    let mut rows = sqlx::query("SELECT id FROM foobar").fetch(&mut conn);
-    let row = rows.try_next().await?.unwrap();
-    let x: i32 = row.try_get("id")?;
-    assert_eq!(x, 42);
-    drop(rows);
+    assert_eq!(count, 10);
 
-    conn.execute("DROP TABLE foobar").await?;
+    // and can be written to disk again in one go
+    conn.backup_to(&path).await?;
 
     Ok(())
 }
 
-// https://github.com/launchbadge/sqlx/issues/1300
 #[sqlx_macros::test]
-async fn concurrent_resets_dont_segfault() {
-    use sqlx::{sqlite::SqliteConnectOptions, ConnectOptions};
-    use std::{str::FromStr, time::Duration};
+async fn it_fails_to_back_up_to_a_locked_database() -> anyhow::Result<()> {
+    let dir = tempdir::TempDir::new("sqlite_backup_test")?;
+    let path = dir.path().join("locked.db");
 
-    let mut conn = SqliteConnectOptions::from_str(":memory:")
-        .unwrap()
+    let mut conn = SqliteConnection::connect(":memory:").await?;
+    conn.execute("CREATE TABLE items (id INTEGER PRIMARY KEY)")
+        .await?;
+
+    let mut locked = SqliteConnectOptions::new()
+        .filename(&path)
+        .create_if_missing(true)
         .connect()
-        .await
-        .unwrap();
+        .await?;
+    locked
+        .execute("CREATE TABLE other (id INTEGER PRIMARY KEY); BEGIN EXCLUSIVE")
+        .await?;
 
-    sqlx::query("CREATE TABLE stuff (name INTEGER, value INTEGER)")
-        .execute(&mut conn)
-        .await
-        .unwrap();
+    let err = conn.backup_to(&path).await.unwrap_err();
+    let err = err.as_database_error().expect("a database error");
 
-    sqlx_rt::spawn(async move {
-        for i in 0..1000 {
-            sqlx::query("INSERT INTO stuff (name, value) VALUES (?, ?)")
-                .bind(i)
-                .bind(0)
-                .execute(&mut conn)
-                .await
-                .unwrap();
-        }
-    });
+    // SQLITE_BUSY
+    assert_eq!(err.code().as_deref(), Some("5"));
 
-    sqlx_rt::sleep(Duration::from_millis(1)).await;
+    Ok(())
 }
 
-// https://github.com/launchbadge/sqlx/issues/1419
-// note: this passes before and after the fix; you need to run it with `--nocapture`
-// to see the panic from the worker thread, which doesn't happen after the fix
 #[sqlx_macros::test]
-async fn row_dropped_after_connection_doesnt_panic() {
-    let mut conn = SqliteConnection::connect(":memory:").await.unwrap();
-
-    let books = sqlx::query("SELECT 'hello' AS title")
-        .fetch_all(&mut conn)
-        .await
-        .unwrap();
-
-    for book in &books {
-        // force the row to be inflated
-        let _title: String = book.get("title");
-    }
-
-    // hold `books` past the lifetime of `conn`
-    drop(conn);
-    sqlx_rt::sleep(std::time::Duration::from_secs(1)).await;
-    drop(books);
-}
+async fn it_serializes_and_deserializes() -> anyhow::Result<()> {
+    let dir = tempdir::TempDir::new("sqlite_serialize_test")?;
 
-// note: to repro issue #1467 this should be run in release mode
-// May spuriously fail with UNIQUE constraint failures (which aren't relevant to the original issue)
-// which I have tried to reproduce using the same seed as printed from CI but to no avail.
-// It may be due to some nondeterminism in SQLite itself for all I know.
-#[sqlx_macros::test]
-#[ignore]
-async fn issue_1467() -> anyhow::Result<()> {
+    // a database in WAL mode, which in-memory databases don't support
     let mut conn = SqliteConnectOptions::new()
-        .filename(":memory:")
+        .filename(dir.path().join("wal.db"))
+        .create_if_missing(true)
+        .journal_mode(sqlx::sqlite::SqliteJournalMode::Wal)
         .connect()
         .await?;
 
-    sqlx::query(
+    conn.execute(
         r#"
-    CREATE TABLE kv (k PRIMARY KEY, v);
-    CREATE INDEX idx_kv ON kv (v);
-    "#,
+CREATE TABLE items (id INTEGER PRIMARY KEY, name TEXT NOT NULL);
+INSERT INTO items (name) VALUES ('a'), ('b');
+        "#,
     )
-    .execute(&mut conn)
     .await?;
 
-    // Random seed:
-    let seed: [u8; 32] = rand::random();
-    println!("RNG seed: {}", hex::encode(&seed));
+    let image = conn.serialize(None).await?;
 
-    // Pre-determined seed:
-    // let mut seed: [u8; 32] = [0u8; 32];
-    // hex::decode_to_slice(
-    //     "135234871d03fc0479e22f2f06395b6074761bac5fe7dcf205dbe01eef9f7794",
-    //     &mut seed,
-    // )?;
+    assert!(image.starts_with(b"SQLite format 3\0"));
 
-    // reproducible RNG for testing
-    let mut rng = Xoshiro256PlusPlus::from_seed(seed);
+    let mut copy = SqliteConnection::connect(":memory:").await?;
+    copy.deserialize(None, &image, false).await?;
 
-    for i in 0..1_000_000 {
-        if i % 1_000 == 0 {
-            println!("{}", i);
-        }
-        let key = rng.gen_range(0..1_000);
-        let value = rng.gen_range(0..1_000);
-        let mut tx = conn.begin().await?;
+    sqlx::query("INSERT INTO items (name) VALUES ('c')")
+        .execute(&mut copy)
+        .await?;
+
+    let names: Vec<String> = sqlx::query_scalar("SELECT name FROM items ORDER BY id")
+        .fetch_all(&mut copy)
+        .await?;
+
+    assert_eq!(names, ["a", "b", "c"]);
+
+    // the changed image round-trips
+    let image = copy.serialize(Some("main")).await?;
+    let mut read_only = SqliteConnection::connect(":memory:").await?;
+    read_only.deserialize(None, &image, true).await?;
+
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM items")
+        .fetch_one(&mut read_only)
+        .await?;
+
+    assert_eq!(count, 3);
+
+    let err = read_only.execute("DELETE FROM items").await.unwrap_err();
+
+    // SQLITE_READONLY
+    assert_eq!(
+        err.as_database_error().and_then(|e| e.code()).as_deref(),
+        Some("8")
+    );
+
+    assert!(matches!(
+        conn.serialize(Some("missing")).await,
+        Err(sqlx::Error::Configuration(_))
+    ));
 
-        let exists = sqlx::query("SELECT 1 FROM kv WHERE k = ?")
-            .bind(key)
-            .fetch_optional(&mut tx)
-            .await?;
-        if exists.is_some() {
-            sqlx::query("UPDATE kv SET v = ? WHERE k = ?")
-                .bind(value)
-                .bind(key)
-                .execute(&mut tx)
-                .await?;
-        } else {
-            sqlx::query("INSERT INTO kv(k, v) VALUES (?, ?)")
-                .bind(key)
-                .bind(value)
-                .execute(&mut tx)
-                .await?;
-        }
-        tx.commit().await?;
-    }
     Ok(())
 }
 
 #[sqlx_macros::test]
-async fn concurrent_read_and_write() {
-    let pool: SqlitePool = SqlitePoolOptions::new()
-        .min_connections(2)
-        .connect(":memory:")
-        .await
-        .unwrap();
+async fn it_streams_committed_updates() -> anyhow::Result<()> {
+    use futures::{FutureExt, StreamExt};
+    use sqlx::sqlite::{SqliteOperation, SqliteUpdate};
 
-    sqlx::query("CREATE TABLE kv (k PRIMARY KEY, v)")
-        .execute(&pool)
-        .await
-        .unwrap();
+    let mut conn = SqliteConnection::connect(":memory:").await?;
+    conn.execute("CREATE TABLE items (id INTEGER PRIMARY KEY, name TEXT NOT NULL)")
+        .await?;
 
-    let n = 100;
+    let mut updates = conn.updates().await?;
 
-    let read = sqlx_rt::spawn({
-        let mut conn = pool.acquire().await.unwrap();
+    let mut tx = conn.begin().await?;
+    tx.execute("INSERT INTO items (name) VALUES ('a')").await?;
 
-        async move {
-            for i in 0u32..n {
-                sqlx::query("SELECT v FROM kv")
-                    .bind(i)
-                    .fetch_all(&mut conn)
-                    .await
-                    .unwrap();
-            }
-        }
-    });
+    // nothing is delivered before the commit
+    assert!(updates.next().now_or_never().is_none());
 
-    let write = sqlx_rt::spawn({
-        let mut conn = pool.acquire().await.unwrap();
+    tx.commit().await?;
 
-        async move {
-            for i in 0u32..n {
-                sqlx::query("INSERT INTO kv (k, v) VALUES (?, ?)")
-                    .bind(i)
-                    .bind(i * i)
+    assert_eq!(
+        updates.next().await,
+        Some(SqliteUpdate {
+            operation: SqliteOperation::Insert,
+            database: "main".into(),
+            table: "items".into(),
+            rowid: 1,
+        })
+    );
+
+    let mut tx = conn.begin().await?;
+    tx.execute("INSERT INTO items (name) VALUES ('b')").await?;
+    tx.rollback().await?;
+
+    conn.execute("UPDATE items SET name = 'c' WHERE id = 1; DELETE FROM items WHERE id = 1")
+        .await?;
+
+    let operations: Vec<_> = updates
+        .by_ref()
+        .take(2)
+        .map(|update| (update.operation, update.rowid))
+        .collect()
+        .await;
+
+    // the rolled back insert isn't delivered
+    assert_eq!(
+        operations,
+        [(SqliteOperation::Update, 1), (SqliteOperation::Delete, 1)]
+    );
+    assert!(updates.next().now_or_never().is_none());
+
+    // closing the connection ends the stream
+    conn.close().await?;
+    assert_eq!(updates.next().await, None);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_streams_immediate_updates_and_counts_dropped() -> anyhow::Result<()> {
+    use futures::{FutureExt, StreamExt};
+    use sqlx::sqlite::SqliteUpdatesOptions;
+
+    let mut conn = SqliteConnection::connect(":memory:").await?;
+    conn.execute("CREATE TABLE items (id INTEGER PRIMARY KEY)")
+        .await?;
+
+    let mut updates = conn
+        .updates_with(SqliteUpdatesOptions::new().immediate(true))
+        .await?;
+
+    let mut tx = conn.begin().await?;
+    tx.execute("INSERT INTO items (id) VALUES (7)").await?;
+
+    assert_eq!(updates.next().await.map(|update| update.rowid), Some(7));
+
+    tx.rollback().await?;
+
+    // subscribing again ends the previous stream
+    let mut updates_2 = conn
+        .updates_with(SqliteUpdatesOptions::new().capacity(1))
+        .await?;
+    assert_eq!(updates.next().await, None);
+
+    conn.execute("INSERT INTO items (id) VALUES (1), (2), (3)")
+        .await?;
+
+    assert_eq!(updates_2.next().await.map(|update| update.rowid), Some(1));
+    assert!(updates_2.next().now_or_never().is_none());
+    assert_eq!(updates_2.dropped(), 2);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_counts_dropped_updates_of_large_transactions() -> anyhow::Result<()> {
+    use futures::{FutureExt, StreamExt};
+    use sqlx::sqlite::SqliteUpdatesOptions;
+
+    let mut conn = SqliteConnection::connect(":memory:").await?;
+    conn.execute("CREATE TABLE items (id INTEGER PRIMARY KEY)")
+        .await?;
+
+    let mut updates = conn
+        .updates_with(SqliteUpdatesOptions::new().capacity(2))
+        .await?;
+
+    // no more changes than the capacity are kept until the commit
+    let mut tx = conn.begin().await?;
+    tx.execute(
+        "WITH RECURSIVE n(id) AS (SELECT 1 UNION ALL SELECT id + 1 FROM n WHERE id < 1000) \
+         INSERT INTO items (id) SELECT id FROM n",
+    )
+    .await?;
+    assert_eq!(updates.dropped(), 998);
+    tx.commit().await?;
+
+    assert_eq!(updates.next().await.map(|update| update.rowid), Some(1));
+    assert_eq!(updates.next().await.map(|update| update.rowid), Some(2));
+    assert!(updates.next().now_or_never().is_none());
+    assert_eq!(updates.dropped(), 998);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_streams_updates_of_concurrent_writers() -> anyhow::Result<()> {
+    use futures::StreamExt;
+
+    let dir = tempdir::TempDir::new("sqlite_updates_test")?;
+    let options = SqliteConnectOptions::new()
+        .filename(dir.path().join("updates.db"))
+        .create_if_missing(true)
+        .journal_mode(sqlx::sqlite::SqliteJournalMode::Wal);
+
+    let mut conn = options.connect().await?;
+    let mut other = options.connect().await?;
+
+    conn.execute("CREATE TABLE items (id INTEGER PRIMARY KEY, writer TEXT NOT NULL)")
+        .await?;
+
+    let mut updates = conn.updates().await?;
+
+    async fn write(conn: &mut SqliteConnection, writer: &str) -> anyhow::Result<()> {
+        for _ in 0..50 {
+            let mut tx = conn.begin().await?;
+
+            sqlx::query("INSERT INTO items (writer) VALUES (?)")
+                .bind(writer)
+                .execute(&mut tx)
+                .await?;
+
+            tx.commit().await?;
+        }
+
+        Ok(())
+    }
+
+    let (a, b) = futures::join!(write(&mut conn, "conn"), write(&mut other, "other"));
+    a?;
+    b?;
+
+    let rowids: Vec<i64> =
+        sqlx::query_scalar("SELECT id FROM items WHERE writer = 'conn' ORDER BY id")
+            .fetch_all(&mut conn)
+            .await?;
+
+    assert_eq!(rowids.len(), 50);
+
+    // only the changes made through the subscribed connection are delivered
+    conn.close().await?;
+    let delivered: Vec<i64> = updates.map(|update| update.rowid).collect().await;
+
+    assert_eq!(delivered, rowids);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_waits_for_concurrent_writers() -> anyhow::Result<()> {
+    let dir = tempdir::TempDir::new("sqlite_busy_test")?;
+    let options = SqliteConnectOptions::new()
+        .filename(dir.path().join("busy.db"))
+        .create_if_missing(true)
+        .journal_mode(sqlx::sqlite::SqliteJournalMode::Wal);
+
+    let mut conn = options.connect().await?;
+    conn.execute("CREATE TABLE counter (n INTEGER NOT NULL); INSERT INTO counter VALUES (0)")
+        .await?;
+
+    async fn increment(options: &SqliteConnectOptions) -> anyhow::Result<()> {
+        let mut conn = options.connect().await?;
+
+        for _ in 0..100 {
+            // the default busy timeout waits for the other writer to commit
+            conn.execute("UPDATE counter SET n = n + 1").await?;
+        }
+
+        Ok(())
+    }
+
+    let (a, b) = futures::join!(increment(&options), increment(&options));
+    a?;
+    b?;
+
+    let n: i64 = sqlx::query_scalar("SELECT n FROM counter")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(n, 200);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_begins_immediate_transactions() -> anyhow::Result<()> {
+    use sqlx::error::ErrorKind;
+    use sqlx::sqlite::SqliteTransactionBehavior;
+    use sqlx::TransactionOptions;
+
+    let dir = tempdir::TempDir::new("sqlite_busy_test")?;
+    let options = SqliteConnectOptions::new()
+        .filename(dir.path().join("busy.db"))
+        .create_if_missing(true)
+        .journal_mode(sqlx::sqlite::SqliteJournalMode::Wal);
+
+    let mut conn = options.connect().await?;
+    conn.execute("CREATE TABLE counter (n INTEGER NOT NULL); INSERT INTO counter VALUES (0)")
+        .await?;
+
+    // a deferred transaction which read before another connection wrote can't write anymore
+    let mut other = options.connect().await?;
+    let mut tx = other.begin().await?;
+    let _: i64 = sqlx::query_scalar("SELECT n FROM counter")
+        .fetch_one(&mut *tx)
+        .await?;
+
+    conn.execute("UPDATE counter SET n = n + 1").await?;
+
+    let err = tx
+        .execute("UPDATE counter SET n = n + 1")
+        .await
+        .unwrap_err();
+    assert_eq!(err.as_database_error().unwrap().kind(), ErrorKind::Locked);
+    tx.rollback().await?;
+
+    // immediate transactions wait for the lock when they begin instead, by the options of the
+    // connection or of the transaction
+    async fn increment(
+        options: SqliteConnectOptions,
+        transaction: TransactionOptions,
+    ) -> anyhow::Result<()> {
+        let mut conn = options.connect().await?;
+
+        for _ in 0..50 {
+            let mut tx = conn.begin_with(transaction).await?;
+            let n: i64 = sqlx::query_scalar("SELECT n FROM counter")
+                .fetch_one(&mut *tx)
+                .await?;
+
+            // savepoints are unchanged
+            let mut savepoint = tx.begin().await?;
+            sqlx::query("UPDATE counter SET n = ?")
+                .bind(n + 1)
+                .execute(&mut *savepoint)
+                .await?;
+            savepoint.commit().await?;
+
+            tx.commit().await?;
+        }
+
+        Ok(())
+    }
+
+    let (a, b) = futures::join!(
+        increment(
+            options
+                .clone()
+                .transaction_behavior(SqliteTransactionBehavior::Immediate),
+            TransactionOptions::new(),
+        ),
+        increment(
+            options.clone(),
+            TransactionOptions::new().sqlite_behavior(SqliteTransactionBehavior::Exclusive),
+        )
+    );
+    a?;
+    b?;
+
+    let n: i64 = sqlx::query_scalar("SELECT n FROM counter")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(n, 101);
+
+    // the behavior of a transaction can't be given for a savepoint
+    let mut tx = conn.begin().await?;
+    assert!(tx
+        .begin_with(TransactionOptions::new().sqlite_behavior(SqliteTransactionBehavior::Immediate))
+        .await
+        .is_err());
+    tx.rollback().await?;
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_reports_a_locked_database() -> anyhow::Result<()> {
+    use sqlx::error::ErrorKind;
+    use std::time::Duration;
+
+    let dir = tempdir::TempDir::new("sqlite_busy_test")?;
+    let options = SqliteConnectOptions::new()
+        .filename(dir.path().join("busy.db"))
+        .create_if_missing(true);
+
+    let mut conn = options.connect().await?;
+    conn.execute("CREATE TABLE items (id INTEGER PRIMARY KEY)")
+        .await?;
+
+    let mut other = options
+        .clone()
+        .busy_timeout(Duration::ZERO)
+        .connect()
+        .await?;
+
+    let mut tx = conn.begin().await?;
+    tx.execute("INSERT INTO items (id) VALUES (1)").await?;
+
+    let err = other
+        .execute("INSERT INTO items (id) VALUES (2)")
+        .await
+        .unwrap_err();
+    let err = err.as_database_error().unwrap();
+
+    assert_eq!(err.kind(), ErrorKind::Locked);
+    // SQLITE_BUSY
+    assert_eq!(err.code().as_deref(), Some("5"));
+
+    tx.commit().await?;
+
+    let err = sqlx::query("SELECT * FROM missing")
+        .execute(&mut other)
+        .await
+        .unwrap_err();
+
+    assert_eq!(err.as_database_error().unwrap().kind(), ErrorKind::Other);
+
+    other.execute("INSERT INTO items (id) VALUES (2)").await?;
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_inspects_constraint_violations() -> anyhow::Result<()> {
+    use sqlx::error::{DatabaseError, ErrorKind};
+
+    async fn violate(conn: &mut SqliteConnection, sql: &str) -> Box<dyn DatabaseError> {
+        conn.execute(sql)
+            .await
+            .unwrap_err()
+            .into_database_error()
+            .unwrap()
+    }
+
+    let mut conn = new::<Sqlite>().await?;
+
+    conn.execute(
+        r#"
+PRAGMA foreign_keys = ON;
+CREATE TEMPORARY TABLE parents (id INTEGER PRIMARY KEY);
+CREATE TEMPORARY TABLE children (
+    id INTEGER PRIMARY KEY,
+    parent_id INTEGER REFERENCES parents (id),
+    name TEXT NOT NULL,
+    nickname TEXT,
+    code TEXT,
+    age INTEGER CONSTRAINT children_age_check CHECK (age >= 0),
+    UNIQUE (name, nickname)
+);
+CREATE UNIQUE INDEX temp.children_lower_code ON children (lower(code));
+INSERT INTO parents (id) VALUES (1);
+INSERT INTO children (id, parent_id, name, nickname, code, age) VALUES (1, 1, 'first', 'one', 'A', 1);
+        "#,
+    )
+    .await?;
+
+    let err = violate(
+        &mut conn,
+        "INSERT INTO children (id, name) VALUES (1, 'again')",
+    )
+    .await;
+    assert_eq!(err.kind(), ErrorKind::UniqueViolation);
+    assert_eq!(err.table(), Some("children"));
+    assert_eq!(err.column(), Some("id"));
+
+    // the columns of an index are only reported by the table
+    let err = violate(
+        &mut conn,
+        "INSERT INTO children (id, name, nickname) VALUES (2, 'first', 'one')",
+    )
+    .await;
+    assert_eq!(err.kind(), ErrorKind::UniqueViolation);
+    assert_eq!(err.table(), Some("children"));
+    assert_eq!(err.column(), None);
+
+    // and an index on expressions by its name
+    let err = violate(
+        &mut conn,
+        "INSERT INTO children (id, name, code) VALUES (2, 'second', 'a')",
+    )
+    .await;
+    assert_eq!(err.kind(), ErrorKind::UniqueViolation);
+    assert_eq!(err.constraint(), Some("children_lower_code"));
+
+    let err = violate(
+        &mut conn,
+        "INSERT INTO children (id, parent_id, name) VALUES (2, 2, 'orphan')",
+    )
+    .await;
+    assert_eq!(err.kind(), ErrorKind::ForeignKeyViolation);
+
+    let err = violate(&mut conn, "INSERT INTO children (id) VALUES (3)").await;
+    assert_eq!(err.kind(), ErrorKind::NotNullViolation);
+    assert_eq!(err.table(), Some("children"));
+    assert_eq!(err.column(), Some("name"));
+
+    let err = violate(
+        &mut conn,
+        "INSERT INTO children (id, name, age) VALUES (4, 'unborn', -1)",
+    )
+    .await;
+    assert_eq!(err.kind(), ErrorKind::CheckViolation);
+    assert_eq!(err.constraint(), Some("children_age_check"));
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_captures_the_statement_on_error() -> anyhow::Result<()> {
+    use std::str::FromStr;
+
+    let mut options = SqliteConnectOptions::from_str(":memory:")?;
+    options.capture_statement_on_error(true);
+
+    let mut conn = options.connect().await?;
+
+    let err = sqlx::query("SELECT * FROM missing WHERE name = ?")
+        .bind("secret")
+        .execute(&mut conn)
+        .await
+        .unwrap_err();
+
+    let context = err.context().unwrap();
+    assert_eq!(context.sql(), "SELECT * FROM missing WHERE name = ?");
+    assert_eq!(context.parameters(), 1);
+    assert!(context.column().is_none());
+
+    assert!(err.as_database_error().is_some());
+    assert!(matches!(err.without_context(), sqlx::Error::Database(_)));
+
+    let message = err.to_string();
+    assert!(message.contains("no such table: missing"));
+    assert!(message.contains("SELECT * FROM missing WHERE name = ?"));
+    assert!(!message.contains("secret"));
+
+    // the errors decoding the rows of the statement
+    let err = sqlx::query_scalar::<_, i64>("SELECT 'text' AS name, ? AS id")
+        .bind(1_i64)
+        .fetch_one(&mut conn)
+        .await
+        .unwrap_err();
+
+    let context = err.context().unwrap();
+    assert_eq!(context.sql(), "SELECT 'text' AS name, ? AS id");
+    assert_eq!(context.parameters(), 1);
+
+    let column = context.column().unwrap();
+    assert_eq!(column.index(), 0);
+    assert_eq!(column.name(), "name");
+    assert_eq!(column.rust_type(), "i64");
+
+    assert!(matches!(
+        err.without_context(),
+        sqlx::Error::ColumnDecode { .. }
+    ));
+
+    // the statement isn't captured by default
+    let mut conn = new::<Sqlite>().await?;
+
+    let err = sqlx::query("SELECT * FROM missing")
+        .execute(&mut conn)
+        .await
+        .unwrap_err();
+
+    assert!(err.context().is_none());
+    assert!(matches!(err, sqlx::Error::Database(_)));
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_checkpoints_the_wal() -> anyhow::Result<()> {
+    use sqlx::sqlite::SqliteCheckpointMode;
+    use std::time::Duration;
+
+    let dir = tempdir::TempDir::new("sqlite_checkpoint_test")?;
+    let wal = dir.path().join("wal.db-wal");
+    let options = SqliteConnectOptions::new()
+        .filename(dir.path().join("wal.db"))
+        .create_if_missing(true)
+        .journal_mode(sqlx::sqlite::SqliteJournalMode::Wal)
+        .wal_autocheckpoint(0)
+        .busy_timeout(Duration::from_millis(100));
+
+    let mut conn = options.connect().await?;
+    let mut reader = options.connect().await?;
+
+    conn.execute("CREATE TABLE items (id INTEGER PRIMARY KEY); INSERT INTO items DEFAULT VALUES")
+        .await?;
+
+    let result = conn.checkpoint(SqliteCheckpointMode::Passive).await?;
+
+    assert!(!result.is_busy());
+    assert!(result.wal_pages() > 0);
+    assert_eq!(result.checkpointed_pages(), result.wal_pages());
+
+    // a reader of the WAL keeps it from being truncated
+    conn.execute("INSERT INTO items DEFAULT VALUES").await?;
+
+    let mut tx = reader.begin().await?;
+    let _: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM items")
+        .fetch_one(&mut tx)
+        .await?;
+
+    let result = conn.checkpoint(SqliteCheckpointMode::Truncate).await?;
+
+    assert!(result.is_busy());
+    assert!(std::fs::metadata(&wal)?.len() > 0);
+
+    tx.rollback().await?;
+
+    let result = conn.checkpoint(SqliteCheckpointMode::Truncate).await?;
+
+    assert!(!result.is_busy());
+    assert_eq!(result.wal_pages(), 0);
+    assert_eq!(std::fs::metadata(&wal)?.len(), 0);
+
+    // without WAL, there's nothing to do
+    let mut memory = SqliteConnection::connect(":memory:").await?;
+    let result = memory.checkpoint(SqliteCheckpointMode::Full).await?;
+
+    assert_eq!((result.wal_pages(), result.is_busy()), (0, false));
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_checkpoints_in_the_background() -> anyhow::Result<()> {
+    use sqlx::sqlite::SqliteCheckpointMode;
+    use std::time::{Duration, Instant};
+
+    let dir = tempdir::TempDir::new("sqlite_checkpoint_test")?;
+    let wal = dir.path().join("wal.db-wal");
+    let pool = SqlitePoolOptions::new()
+        .max_connections(2)
+        .connect_with(
+            SqliteConnectOptions::new()
+                .filename(dir.path().join("wal.db"))
+                .create_if_missing(true)
+                .journal_mode(sqlx::sqlite::SqliteJournalMode::Wal)
+                .wal_autocheckpoint(0),
+        )
+        .await?;
+
+    pool.execute("CREATE TABLE items (id INTEGER PRIMARY KEY); INSERT INTO items DEFAULT VALUES")
+        .await?;
+
+    assert!(std::fs::metadata(&wal)?.len() > 0);
+
+    pool.spawn_checkpointer(Duration::from_millis(10), SqliteCheckpointMode::Truncate);
+
+    let deadline = Instant::now() + Duration::from_secs(5);
+
+    while std::fs::metadata(&wal)?.len() > 0 {
+        assert!(Instant::now() < deadline, "the WAL was not checkpointed");
+        sqlx_rt::sleep(Duration::from_millis(10)).await;
+    }
+
+    pool.close().await;
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_attaches_databases_from_options() -> anyhow::Result<()> {
+    use sqlx::sqlite::{SqliteJournalMode, SqliteSynchronous};
+
+    let dir = tempdir::TempDir::new("sqlite_attach_test")?;
+    let pool = SqlitePoolOptions::new()
+        .max_connections(2)
+        .connect_with(
+            SqliteConnectOptions::new()
+                .filename(dir.path().join("main.db"))
+                .create_if_missing(true)
+                .journal_mode(SqliteJournalMode::Wal)
+                .synchronous(SqliteSynchronous::Normal)
+                .attach("archive", dir.path().join("archive.db"))
+                .attach("other one", dir.path().join("other.db")),
+        )
+        .await?;
+
+    pool.execute("CREATE TABLE archive.items (id INTEGER PRIMARY KEY)")
+        .await?;
+
+    // every connection of the pool has the databases attached
+    let (mut a, mut b) = (pool.acquire().await?, pool.acquire().await?);
+
+    for conn in [&mut a, &mut b] {
+        let schemas: Vec<String> = sqlx::query_scalar("SELECT name FROM pragma_database_list")
+            .fetch_all(&mut *conn)
+            .await?;
+
+        assert_eq!(schemas, ["main", "archive", "other one"]);
+
+        let journal_mode: String = sqlx::query_scalar("PRAGMA archive.journal_mode")
+            .fetch_one(&mut *conn)
+            .await?;
+        let synchronous: i64 = sqlx::query_scalar(r#"PRAGMA "other one".synchronous"#)
+            .fetch_one(&mut *conn)
+            .await?;
+
+        assert_eq!((&*journal_mode, synchronous), ("wal", 1));
+
+        sqlx::query("INSERT INTO archive.items DEFAULT VALUES")
+            .execute(&mut *conn)
+            .await?;
+    }
+
+    drop((a, b));
+    pool.close().await;
+
+    // a database which can't be attached fails the connection
+    let err = SqliteConnectOptions::new()
+        .filename(dir.path().join("main.db"))
+        .attach("missing", dir.path().join("missing.db"))
+        .connect()
+        .await
+        .unwrap_err();
+
+    assert!(err.as_database_error().is_some(), "{:?}", err);
+    assert!(!dir.path().join("missing.db").exists());
+
+    let mut conn = SqliteConnectOptions::new()
+        .filename(dir.path().join("main.db"))
+        .read_only(true)
+        .attach("archive", dir.path().join("archive.db"))
+        .connect()
+        .await?;
+
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM archive.items")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(count, 2);
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[sqlx_macros::test]
+async fn it_opens_read_only_databases() -> anyhow::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = tempdir::TempDir::new("sqlite_read_only_test")?;
+    let filename = dir.path().join("data.db");
+
+    let mut conn = SqliteConnectOptions::new()
+        .filename(&filename)
+        .create_if_missing(true)
+        .journal_mode(sqlx::sqlite::SqliteJournalMode::Wal)
+        .connect()
+        .await?;
+
+    conn.execute("CREATE TABLE items (id INTEGER PRIMARY KEY); INSERT INTO items DEFAULT VALUES")
+        .await?;
+    conn.close().await?;
+
+    let assert_rejects_writes = |err: sqlx::Error| {
+        let err = err.into_database_error().unwrap();
+
+        // SQLITE_READONLY
+        assert_eq!(err.code().as_deref(), Some("8"));
+        assert!(err.message().contains("read-only connection"), "{}", err);
+    };
+
+    std::fs::set_permissions(dir.path(), std::fs::Permissions::from_mode(0o555))?;
+
+    // the WAL needs its shared-memory file, which can't be created in the directory, unless
+    // running with the privileges to do so anyway
+    if std::fs::File::create(dir.path().join("probe")).is_err() {
+        let result = async {
+            let mut conn = SqliteConnectOptions::new()
+                .filename(&filename)
+                .read_only(true)
+                .connect()
+                .await?;
+
+            conn.execute("SELECT * FROM items").await
+        }
+        .await;
+
+        assert!(result.is_err());
+    }
+
+    let pool = SqlitePoolOptions::new()
+        .max_connections(2)
+        .connect_with(
+            SqliteConnectOptions::new()
+                .filename(&filename)
+                .immutable(true),
+        )
+        .await?;
+
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM items")
+        .fetch_one(&pool)
+        .await?;
+
+    assert_eq!(count, 1);
+    assert_rejects_writes(
+        pool.execute("INSERT INTO items DEFAULT VALUES")
+            .await
+            .unwrap_err(),
+    );
+
+    // the `temp` schema isn't the database file
+    let mut conn = pool.acquire().await?;
+    conn.execute("CREATE TEMP TABLE scratch (id INTEGER); INSERT INTO scratch VALUES (1)")
+        .await?;
+    drop(conn);
+
+    pool.close().await;
+    std::fs::set_permissions(dir.path(), std::fs::Permissions::from_mode(0o755))?;
+
+    let mut conn = SqliteConnectOptions::new()
+        .filename(&filename)
+        .query_only(true)
+        .connect()
+        .await?;
+
+    let query_only: bool = sqlx::query_scalar("PRAGMA query_only")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert!(query_only);
+
+    let mut tx = conn.begin().await?;
+
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM items")
+        .fetch_one(&mut tx)
+        .await?;
+
+    assert_eq!(count, 1);
+    assert_rejects_writes(
+        sqlx::query("DELETE FROM items WHERE id = ?")
+            .bind(1)
+            .execute(&mut tx)
+            .await
+            .unwrap_err(),
+    );
+
+    tx.rollback().await?;
+
+    Ok(())
+}
+
+// a query which never completes on its own
+const ENDLESS_QUERY: &str =
+    "WITH RECURSIVE c(x) AS (SELECT 1 UNION ALL SELECT x + 1 FROM c) SELECT COUNT(*) FROM c";
+
+#[sqlx_macros::test]
+async fn it_interrupts_queries_from_the_progress_handler() -> anyhow::Result<()> {
+    use sqlx::error::ErrorKind;
+    use std::ops::ControlFlow;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    let cancelled = Arc::new(AtomicBool::new(false));
+
+    let mut conn = SqliteConnectOptions::new()
+        .progress_handler(100, {
+            let cancelled = cancelled.clone();
+
+            move || match cancelled.load(Ordering::Relaxed) {
+                true => ControlFlow::Break(()),
+                false => ControlFlow::Continue(()),
+            }
+        })
+        .connect()
+        .await?;
+
+    std::thread::spawn({
+        let cancelled = cancelled.clone();
+
+        move || {
+            std::thread::sleep(Duration::from_millis(50));
+            cancelled.store(true, Ordering::Relaxed);
+        }
+    });
+
+    let err = conn.execute(ENDLESS_QUERY).await.unwrap_err();
+
+    assert_eq!(
+        err.as_database_error().unwrap().kind(),
+        ErrorKind::Interrupted
+    );
+
+    cancelled.store(false, Ordering::Relaxed);
+
+    let one: i64 = sqlx::query_scalar("SELECT 1").fetch_one(&mut conn).await?;
+    assert_eq!(one, 1);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_interrupts_queries_with_a_handle() -> anyhow::Result<()> {
+    use sqlx::error::ErrorKind;
+    use std::time::Duration;
+
+    let mut conn = SqliteConnection::connect(":memory:").await?;
+    let handle = conn.interrupt_handle();
+
+    // nothing to interrupt
+    handle.interrupt();
+
+    let one: i64 = sqlx::query_scalar("SELECT 1").fetch_one(&mut conn).await?;
+    assert_eq!(one, 1);
+
+    let mut tx = conn.begin().await?;
+
+    std::thread::spawn({
+        let handle = handle.clone();
+
+        move || {
+            std::thread::sleep(Duration::from_millis(50));
+            handle.interrupt();
+        }
+    });
+
+    let err = tx.execute(ENDLESS_QUERY).await.unwrap_err();
+
+    assert_eq!(
+        err.as_database_error().unwrap().kind(),
+        ErrorKind::Interrupted
+    );
+    // SQLITE_INTERRUPT
+    assert_eq!(
+        err.as_database_error().unwrap().code().as_deref(),
+        Some("9")
+    );
+
+    // the transaction is still usable
+    tx.execute("CREATE TABLE items (id INTEGER PRIMARY KEY)")
+        .await?;
+    tx.commit().await?;
+
+    conn.close().await?;
+
+    // does nothing once the connection is closed
+    handle.interrupt();
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_interrupts_dropped_queries() -> anyhow::Result<()> {
+    use std::time::Duration;
+
+    let mut conn = SqliteConnectOptions::new()
+        .interrupt_on_drop(true)
+        .connect()
+        .await?;
+
+    let res = sqlx_rt::timeout(
+        Duration::from_millis(50),
+        sqlx::query(ENDLESS_QUERY).fetch_one(&mut conn),
+    )
+    .await;
+
+    assert!(res.is_err());
+
+    // the worker is free for the next query, instead of running the dropped one forever
+    let one: i64 = sqlx_rt::timeout(
+        Duration::from_secs(5),
+        sqlx::query_scalar("SELECT 1").fetch_one(&mut conn),
+    )
+    .await??;
+
+    assert_eq!(one, 1);
+
+    Ok(())
+}
+
+async fn search(
+    conn: &mut SqliteConnection,
+    query: sqlx::sqlite::fts::Fts5Query,
+) -> sqlx::Result<Vec<i64>> {
+    sqlx::query_scalar("SELECT rowid FROM posts_fts WHERE posts_fts MATCH ? ORDER BY rowid")
+        .bind(query)
+        .fetch_all(conn)
+        .await
+}
+
+#[sqlx_macros::test]
+async fn it_searches_full_text_with_user_input() -> anyhow::Result<()> {
+    use sqlx::sqlite::fts::{Fts5ExternalContent, Fts5Query};
+
+    let mut conn = SqliteConnection::connect("sqlite::memory:").await?;
+
+    conn.execute(
+        r#"
+CREATE TABLE posts (id INTEGER PRIMARY KEY, title TEXT NOT NULL, body TEXT NOT NULL);
+INSERT INTO posts (id, title, body) VALUES (1, 'Rust or SQL', 'Say "hello" to NOT NULL');
+        "#,
+    )
+    .await?;
+
+    // the existing row is indexed as well
+    let fts = Fts5ExternalContent::new("posts_fts", "posts", &["title", "body"])
+        .content_rowid("id")
+        .to_sql()?;
+
+    conn.execute(&*fts).await?;
+
+    conn.execute(
+        r#"
+INSERT INTO posts (id, title, body) VALUES (2, 'Hello world', 'Nothing to see here');
+INSERT INTO posts (id, title, body) VALUES (3, 'Deleted', 'hello');
+UPDATE posts SET body = 'rusty' WHERE id = 2;
+DELETE FROM posts WHERE id = 3;
+        "#,
+    )
+    .await?;
+
+    // operators and quotes from the user are plain words
+    assert_eq!(
+        search(&mut conn, Fts5Query::words(r#""hello" NOT NULL"#)).await?,
+        [1]
+    );
+    assert_eq!(
+        search(&mut conn, Fts5Query::words("rust OR sql")).await?,
+        [1]
+    );
+    assert_eq!(
+        search(&mut conn, Fts5Query::words("rust OR world")).await?,
+        Vec::<i64>::new()
+    );
+    assert_eq!(
+        search(&mut conn, Fts5Query::words(r#"sql) OR (": *"#)).await?,
+        [1]
+    );
+    assert_eq!(
+        search(&mut conn, Fts5Query::words(r#"say "hello""#)).await?,
+        [1]
+    );
+    assert_eq!(
+        search(&mut conn, Fts5Query::words(" ")).await?,
+        Vec::<i64>::new()
+    );
+
+    assert_eq!(
+        search(&mut conn, Fts5Query::phrase("say hello")).await?,
+        [1]
+    );
+    assert_eq!(
+        search(&mut conn, Fts5Query::phrase("hello say")).await?,
+        Vec::<i64>::new()
+    );
+
+    assert_eq!(search(&mut conn, Fts5Query::prefix("rus")).await?, [1, 2]);
+    assert_eq!(
+        search(&mut conn, Fts5Query::prefix("rus").column("body")).await?,
+        [2]
+    );
+
+    assert_eq!(
+        search(
+            &mut conn,
+            Fts5Query::phrase("hello").or(Fts5Query::phrase("rust"))
+        )
+        .await?,
+        [1, 2]
+    );
+    assert_eq!(
+        search(
+            &mut conn,
+            Fts5Query::phrase("hello").not(Fts5Query::phrase("rust"))
+        )
+        .await?,
+        [2]
+    );
+    assert_eq!(
+        search(
+            &mut conn,
+            Fts5Query::phrase("hello").columns(&["title", "body"])
+        )
+        .await?,
+        [1, 2]
+    );
+
+    // the better match has the lower rank
+    let ranks: Vec<(i64, f64)> =
+        sqlx::query_as("SELECT rowid, rank FROM posts_fts WHERE posts_fts MATCH ? ORDER BY rank")
+            .bind(Fts5Query::words("hello"))
+            .fetch_all(&mut conn)
+            .await?;
+
+    assert_eq!(ranks.len(), 2);
+    assert!(ranks[0].1 < 0.0 && ranks[0].1 <= ranks[1].1);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_shares_an_in_memory_database_in_a_pool() -> anyhow::Result<()> {
+    let pool = SqlitePoolOptions::new()
+        .max_connections(2)
+        .connect_with(SqliteConnectOptions::new().shared_memory("it_shares"))
+        .await?;
+
+    // two connections at once, so that they can't be the same one
+    let mut writer = pool.acquire().await?;
+    let mut reader = pool.acquire().await?;
+
+    writer
+        .execute("CREATE TABLE items (id INTEGER PRIMARY KEY); INSERT INTO items VALUES (1)")
+        .await?;
+
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM items")
+        .fetch_one(&mut reader)
+        .await?;
+
+    assert_eq!(count, 1);
+
+    // another pool gets another database
+    let other = SqlitePoolOptions::new()
+        .connect_with(SqliteConnectOptions::new().shared_memory("it_shares"))
+        .await?;
+
+    assert!(sqlx::query("SELECT * FROM items")
+        .fetch_all(&other)
+        .await
+        .is_err());
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_keeps_a_shared_in_memory_database_without_connections() -> anyhow::Result<()> {
+    let options = SqliteConnectOptions::new().shared_memory("it_keeps");
+
+    let mut conn = options.connect().await?;
+    conn.execute("CREATE TABLE items (id INTEGER PRIMARY KEY); INSERT INTO items VALUES (1)")
+        .await?;
+    conn.close().await?;
+
+    let mut conn = options.clone().connect().await?;
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM items")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(count, 1);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_explains_query_plans() -> anyhow::Result<()> {
+    let mut conn = new::<Sqlite>().await?;
+
+    conn.execute(
+        r#"
+CREATE TEMPORARY TABLE accounts (id INTEGER PRIMARY KEY, email TEXT NOT NULL, name TEXT);
+CREATE INDEX temp.accounts_email ON accounts (email);
+        "#,
+    )
+    .await?;
+
+    let plan = conn
+        .explain_query_plan("SELECT id FROM accounts WHERE name = ?")
+        .await?;
+
+    assert!(!plan.uses_index_scan_only());
+    assert_eq!(plan.nodes().len(), 1);
+    assert_eq!(plan.nodes()[0].detail, "SCAN accounts");
+    assert_eq!(plan.to_string(), "QUERY PLAN\n`--SCAN accounts\n");
+
+    for query in [
+        "SELECT id FROM accounts WHERE email = ?",
+        "SELECT name FROM accounts WHERE id = ?",
+        "SELECT email FROM accounts ORDER BY email",
+        "WITH c AS MATERIALIZED (SELECT id FROM accounts WHERE id > ?) SELECT * FROM c",
+        "SELECT 1",
+    ] {
+        let plan = conn.explain_query_plan(query).await?;
+        assert!(plan.uses_index_scan_only(), "{}\n{}", query, plan);
+    }
+
+    // the unindexed scan is part of a subquery
+    let plan = conn
+        .explain_query_plan(
+            "SELECT id FROM accounts WHERE email IN (SELECT email FROM accounts WHERE name = ?)",
+        )
+        .await?;
+
+    assert!(!plan.uses_index_scan_only(), "{}", plan);
+    assert!(plan.iter().any(|node| node.detail == "SCAN accounts"));
+
+    // nothing but the first statement would be explained
+    assert!(matches!(
+        conn.explain_query_plan("SELECT 1; DELETE FROM accounts")
+            .await,
+        Err(sqlx::Error::Configuration(_))
+    ));
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_reports_read_only_statements() -> anyhow::Result<()> {
+    let mut conn = new::<Sqlite>().await?;
+
+    conn.execute("CREATE TEMPORARY TABLE notes (id INTEGER PRIMARY KEY, body TEXT)")
+        .await?;
+
+    assert!(conn.prepare("SELECT * FROM notes").await?.is_read_only());
+    assert!(!conn
+        .prepare("INSERT INTO notes (body) VALUES (?)")
+        .await?
+        .is_read_only());
+    assert!(!conn
+        .prepare("SELECT 1; DELETE FROM notes")
+        .await?
+        .is_read_only());
+
+    Ok(())
+}
+
+#[cfg(feature = "sqlite-snapshot")]
+#[sqlx_macros::test]
+async fn it_reads_from_snapshots() -> anyhow::Result<()> {
+    use sqlx::error::ErrorKind;
+    use sqlx::sqlite::SqliteCheckpointMode;
+
+    let dir = tempdir::TempDir::new("sqlite_snapshot_test")?;
+    let options = SqliteConnectOptions::new()
+        .filename(dir.path().join("snapshot.db"))
+        .create_if_missing(true)
+        .journal_mode(sqlx::sqlite::SqliteJournalMode::Wal)
+        .wal_autocheckpoint(0);
+
+    let mut writer = options.connect().await?;
+    let mut exporter = options.connect().await?;
+    let mut reader = options.connect().await?;
+
+    writer
+        .execute("CREATE TABLE items (id INTEGER PRIMARY KEY); INSERT INTO items DEFAULT VALUES")
+        .await?;
+
+    let snapshot = exporter.snapshot().await?;
+
+    writer.execute("INSERT INTO items DEFAULT VALUES").await?;
+
+    // both readers see the state of the snapshot, as a writer commits mid-export
+    let mut export = exporter.begin_snapshot_read(&snapshot).await?;
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM items")
+        .fetch_one(&mut export)
+        .await?;
+    assert_eq!(count, 1);
+
+    writer.execute("INSERT INTO items DEFAULT VALUES").await?;
+
+    let mut read = reader.begin_snapshot_read(&snapshot).await?;
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM items")
+        .fetch_one(&mut read)
+        .await?;
+    assert_eq!(count, 1);
+
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM items")
+        .fetch_one(&mut export)
+        .await?;
+    assert_eq!(count, 1);
+
+    // a snapshot can't be opened within another transaction
+    assert!(read.begin_snapshot_read(&snapshot).await.is_err());
+
+    export.commit().await?;
+    read.commit().await?;
+
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM items")
+        .fetch_one(&mut reader)
+        .await?;
+    assert_eq!(count, 3);
+
+    // once the WAL is truncated and written over, the snapshot is no longer available
+    let result = writer.checkpoint(SqliteCheckpointMode::Truncate).await?;
+    assert!(!result.is_busy());
+
+    writer.execute("INSERT INTO items DEFAULT VALUES").await?;
+
+    let err = reader
+        .begin_snapshot_read(&snapshot)
+        .await
+        .unwrap_err()
+        .into_database_error()
+        .unwrap();
+    assert_eq!(err.kind(), ErrorKind::SnapshotUnavailable);
+
+    // the connection isn't left in a transaction
+    reader.execute("INSERT INTO items DEFAULT VALUES").await?;
+
+    // snapshots require WAL
+    let mut memory = SqliteConnection::connect(":memory:").await?;
+    assert!(memory.snapshot().await.is_err());
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_hardens_connections_to_untrusted_databases() -> anyhow::Result<()> {
+    use sqlx::sqlite::SqliteHardening;
+
+    let dir = tempdir::TempDir::new("sqlite_hardening_test")?;
+    let options = SqliteConnectOptions::new()
+        .filename(dir.path().join("untrusted.db"))
+        .create_if_missing(true);
+
+    // a database which corrupts its full-text index, and uses it from a view
+    let mut conn = options.connect().await?;
+    conn.execute(
+        r#"
+CREATE TABLE items (id INTEGER PRIMARY KEY, body TEXT);
+CREATE VIRTUAL TABLE docs USING fts5(body);
+CREATE VIEW matches AS SELECT rowid FROM docs WHERE docs MATCH 'sqlx';
+CREATE TRIGGER poison AFTER INSERT ON items BEGIN
+    INSERT INTO docs_data VALUES (1000, x'deadbeef');
+END;
+        "#,
+    )
+    .await?;
+    conn.close().await?;
+
+    let mut hardened = options
+        .clone()
+        .hardening(SqliteHardening::strict())
+        .connect()
+        .await?;
+
+    // defensive: the shadow tables of `docs` can't be written to
+    let err = hardened
+        .execute("INSERT INTO items (body) VALUES ('sqlx')")
+        .await
+        .unwrap_err()
+        .into_database_error()
+        .unwrap();
+    assert!(err.message().contains("docs_data"), "{}", err);
+
+    let err = hardened
+        .execute("PRAGMA writable_schema = ON; DELETE FROM sqlite_master WHERE name = 'poison'")
+        .await
+        .unwrap_err();
+    assert!(err.as_database_error().is_some(), "{}", err);
+
+    // untrusted schema: the view can't use the virtual table
+    let err = hardened
+        .execute("SELECT * FROM matches")
+        .await
+        .unwrap_err()
+        .into_database_error()
+        .unwrap();
+    assert!(err.message().contains("unsafe use"), "{}", err);
+
+    // limits
+    let deep = format!("SELECT 1{}", " + 1".repeat(200));
+    assert!(hardened.execute(&*deep).await.is_err());
+    assert!(hardened
+        .execute("ATTACH DATABASE ':memory:' AS other")
+        .await
+        .is_err());
+
+    // the connection remains usable
+    hardened.execute("SELECT * FROM items").await?;
+
+    // without hardening, all of the above goes through
+    let mut conn = options.connect().await?;
+    conn.execute("INSERT INTO items (body) VALUES ('sqlx')")
+        .await?;
+    conn.execute(&*deep).await?;
+    conn.execute("ATTACH DATABASE ':memory:' AS other").await?;
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_applies_pragmas_from_options() -> anyhow::Result<()> {
+    let mut conn = "sqlite::memory:?temp_store=memory"
+        .parse::<SqliteConnectOptions>()?
+        .pragma("cache_size", "-4000")
+        .pragma("Recursive_Triggers", "ON")
+        .connect()
+        .await?;
+
+    let (temp_store, cache_size, recursive_triggers): (i64, i64, bool) = sqlx::query_as(
+        "SELECT * FROM pragma_temp_store, pragma_cache_size, pragma_recursive_triggers",
+    )
+    .fetch_one(&mut conn)
+    .await?;
+
+    assert_eq!(
+        (temp_store, cache_size, recursive_triggers),
+        (2, -4000, true)
+    );
+
+    // SQLite would ignore these values
+    for (key, value) in [("journal_mode", "wall"), ("foreign_keys", "maybe")] {
+        let res = SqliteConnectOptions::new()
+            .pragma(key, value)
+            .connect()
+            .await;
+
+        assert!(matches!(res, Err(sqlx::Error::Configuration(_))), "{}", key);
+    }
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_caches_statements() -> anyhow::Result<()> {
+    let mut conn = new::<Sqlite>().await?;
+
+    // Initial PRAGMAs are not cached as we are not going to execute
+    // them more than once.
+    assert_eq!(0, conn.cached_statements_size());
+
+    // `&str` queries are not persistent.
+    let row = conn.fetch_one("SELECT 100 AS val").await?;
+    let val: i32 = row.get("val");
+    assert_eq!(val, 100);
+    assert_eq!(0, conn.cached_statements_size());
+
+    // `Query` is persistent by default.
+    let mut conn = new::<Sqlite>().await?;
+    for i in 0..2 {
+        let row = sqlx::query("SELECT ? AS val")
+            .bind(i)
+            .fetch_one(&mut conn)
+            .await?;
+
+        let val: i32 = row.get("val");
+
+        assert_eq!(i, val);
+    }
+    assert_eq!(1, conn.cached_statements_size());
+
+    // Cache can be cleared.
+    conn.clear_cached_statements().await?;
+    assert_eq!(0, conn.cached_statements_size());
+
+    // `Query` is not persistent if `.persistent(false)` is used
+    // explicitly.
+    let mut conn = new::<Sqlite>().await?;
+    for i in 0..2 {
+        let row = sqlx::query("SELECT ? AS val")
+            .bind(i)
+            .persistent(false)
+            .fetch_one(&mut conn)
+            .await?;
+
+        let val: i32 = row.get("val");
+
+        assert_eq!(i, val);
+    }
+    assert_eq!(0, conn.cached_statements_size());
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_can_prepare_then_execute() -> anyhow::Result<()> {
+    let mut conn = new::<Sqlite>().await?;
+    let mut tx = conn.begin().await?;
+
+    let _ = sqlx::query("INSERT INTO tweet ( id, text ) VALUES ( 2, 'Hello, World' )")
+        .execute(&mut tx)
+        .await?;
+
+    let tweet_id: i32 = 2;
+
+    let statement = tx.prepare("SELECT * FROM tweet WHERE id = ?1").await?;
+
+    assert_eq!(statement.column(0).name(), "id");
+    assert_eq!(statement.column(1).name(), "text");
+    assert_eq!(statement.column(2).name(), "is_sent");
+    assert_eq!(statement.column(3).name(), "owner_id");
+
+    assert_eq!(statement.column(0).type_info().name(), "INTEGER");
+    assert_eq!(statement.column(1).type_info().name(), "TEXT");
+    assert_eq!(statement.column(2).type_info().name(), "BOOLEAN");
+    assert_eq!(statement.column(3).type_info().name(), "INTEGER");
+
+    let row = statement.query().bind(tweet_id).fetch_one(&mut tx).await?;
+    let tweet_text: &str = row.try_get("text")?;
+
+    assert_eq!(tweet_text, "Hello, World");
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_resets_prepared_statement_after_fetch_one() -> anyhow::Result<()> {
+    let mut conn = new::<Sqlite>().await?;
+
+    conn.execute("CREATE TEMPORARY TABLE foobar (id INTEGER)")
+        .await?;
+    conn.execute("INSERT INTO foobar VALUES (42)").await?;
+
+    let r = sqlx::query("SELECT id FROM foobar")
+        .fetch_one(&mut conn)
+        .await?;
+    let x: i32 = r.try_get("id")?;
+    assert_eq!(x, 42);
+
+    conn.execute("DROP TABLE foobar").await?;
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_resets_prepared_statement_after_fetch_many() -> anyhow::Result<()> {
+    let mut conn = new::<Sqlite>().await?;
+
+    conn.execute("CREATE TEMPORARY TABLE foobar (id INTEGER)")
+        .await?;
+    conn.execute("INSERT INTO foobar VALUES (42)").await?;
+    conn.execute("INSERT INTO foobar VALUES (43)").await?;
+
+    let mut rows = sqlx::query("SELECT id FROM foobar").fetch(&mut conn);
+    let row = rows.try_next().await?.unwrap();
+    let x: i32 = row.try_get("id")?;
+    assert_eq!(x, 42);
+    drop(rows);
+
+    conn.execute("DROP TABLE foobar").await?;
+
+    Ok(())
+}
+
+// https://github.com/launchbadge/sqlx/issues/1300
+#[sqlx_macros::test]
+async fn concurrent_resets_dont_segfault() {
+    use sqlx::{sqlite::SqliteConnectOptions, ConnectOptions};
+    use std::{str::FromStr, time::Duration};
+
+    let mut conn = SqliteConnectOptions::from_str(":memory:")
+        .unwrap()
+        .connect()
+        .await
+        .unwrap();
+
+    sqlx::query("CREATE TABLE stuff (name INTEGER, value INTEGER)")
+        .execute(&mut conn)
+        .await
+        .unwrap();
+
+    sqlx_rt::spawn(async move {
+        for i in 0..1000 {
+            sqlx::query("INSERT INTO stuff (name, value) VALUES (?, ?)")
+                .bind(i)
+                .bind(0)
+                .execute(&mut conn)
+                .await
+                .unwrap();
+        }
+    });
+
+    sqlx_rt::sleep(Duration::from_millis(1)).await;
+}
+
+// https://github.com/launchbadge/sqlx/issues/1419
+// note: this passes before and after the fix; you need to run it with `--nocapture`
+// to see the panic from the worker thread, which doesn't happen after the fix
+#[sqlx_macros::test]
+async fn row_dropped_after_connection_doesnt_panic() {
+    let mut conn = SqliteConnection::connect(":memory:").await.unwrap();
+
+    let books = sqlx::query("SELECT 'hello' AS title")
+        .fetch_all(&mut conn)
+        .await
+        .unwrap();
+
+    for book in &books {
+        // force the row to be inflated
+        let _title: String = book.get("title");
+    }
+
+    // hold `books` past the lifetime of `conn`
+    drop(conn);
+    sqlx_rt::sleep(std::time::Duration::from_secs(1)).await;
+    drop(books);
+}
+
+// note: to repro issue #1467 this should be run in release mode
+// May spuriously fail with UNIQUE constraint failures (which aren't relevant to the original issue)
+// which I have tried to reproduce using the same seed as printed from CI but to no avail.
+// It may be due to some nondeterminism in SQLite itself for all I know.
+#[sqlx_macros::test]
+#[ignore]
+async fn issue_1467() -> anyhow::Result<()> {
+    let mut conn = SqliteConnectOptions::new()
+        .filename(":memory:")
+        .connect()
+        .await?;
+
+    sqlx::query(
+        r#"
+    CREATE TABLE kv (k PRIMARY KEY, v);
+    CREATE INDEX idx_kv ON kv (v);
+    "#,
+    )
+    .execute(&mut conn)
+    .await?;
+
+    // Random seed:
+    let seed: [u8; 32] = rand::random();
+    println!("RNG seed: {}", hex::encode(&seed));
+
+    // Pre-determined seed:
+    // let mut seed: [u8; 32] = [0u8; 32];
+    // hex::decode_to_slice(
+    //     "135234871d03fc0479e22f2f06395b6074761bac5fe7dcf205dbe01eef9f7794",
+    //     &mut seed,
+    // )?;
+
+    // reproducible RNG for testing
+    let mut rng = Xoshiro256PlusPlus::from_seed(seed);
+
+    for i in 0..1_000_000 {
+        if i % 1_000 == 0 {
+            println!("{}", i);
+        }
+        let key = rng.gen_range(0..1_000);
+        let value = rng.gen_range(0..1_000);
+        let mut tx = conn.begin().await?;
+
+        let exists = sqlx::query("SELECT 1 FROM kv WHERE k = ?")
+            .bind(key)
+            .fetch_optional(&mut tx)
+            .await?;
+        if exists.is_some() {
+            sqlx::query("UPDATE kv SET v = ? WHERE k = ?")
+                .bind(value)
+                .bind(key)
+                .execute(&mut tx)
+                .await?;
+        } else {
+            sqlx::query("INSERT INTO kv(k, v) VALUES (?, ?)")
+                .bind(key)
+                .bind(value)
+                .execute(&mut tx)
+                .await?;
+        }
+        tx.commit().await?;
+    }
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn concurrent_read_and_write() {
+    let pool: SqlitePool = SqlitePoolOptions::new()
+        .min_connections(2)
+        .connect(":memory:")
+        .await
+        .unwrap();
+
+    sqlx::query("CREATE TABLE kv (k PRIMARY KEY, v)")
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let n = 100;
+
+    let read = sqlx_rt::spawn({
+        let mut conn = pool.acquire().await.unwrap();
+
+        async move {
+            for i in 0u32..n {
+                sqlx::query("SELECT v FROM kv")
+                    .bind(i)
+                    .fetch_all(&mut conn)
+                    .await
+                    .unwrap();
+            }
+        }
+    });
+
+    let write = sqlx_rt::spawn({
+        let mut conn = pool.acquire().await.unwrap();
+
+        async move {
+            for i in 0u32..n {
+                sqlx::query("INSERT INTO kv (k, v) VALUES (?, ?)")
+                    .bind(i)
+                    .bind(i * i)
                     .execute(&mut conn)
                     .await
                     .unwrap();
             }
         }
-    });
+    });
+
+    #[cfg(any(feature = "_rt-tokio", feature = "_rt-actix"))]
+    {
+        read.await.unwrap();
+        write.await.unwrap();
+    }
+
+    #[cfg(feature = "_rt-async-std")]
+    {
+        read.await;
+        write.await;
+    }
+}
+
+#[cfg(feature = "migrate")]
+#[sqlx_macros::test]
+async fn it_reverts_migrations_down_to_a_target_version() -> anyhow::Result<()> {
+    use sqlx::migrate::{MigrateError, Migrator};
+    use std::path::Path;
+
+    let migrator = Migrator::new(Path::new("tests/sqlite/migrations_reversible")).await?;
+    let mut conn = SqliteConnection::connect("sqlite::memory:").await?;
+
+    async fn tables(conn: &mut SqliteConnection) -> anyhow::Result<Vec<String>> {
+        Ok(sqlx::query_scalar(
+            "SELECT name FROM sqlite_schema WHERE type = 'table' AND name NOT LIKE '\\_%' ESCAPE '\\' ORDER BY name",
+        )
+        .fetch_all(conn)
+        .await?)
+    }
+
+    migrator.run(&mut conn).await?;
+    assert_eq!(tables(&mut conn).await?, ["comment", "post", "user"]);
+
+    migrator.undo(&mut conn, 1).await?;
+    assert_eq!(tables(&mut conn).await?, ["user"]);
+
+    migrator.run(&mut conn).await?;
+    migrator.undo(&mut conn, 0).await?;
+    assert!(tables(&mut conn).await?.is_empty());
+
+    // a migration without a down migration keeps any of them from being reverted
+    migrator.run(&mut conn).await?;
+    let mut partial = Migrator::new(Path::new("tests/sqlite/migrations_reversible")).await?;
+    partial.migrations = partial
+        .iter()
+        .filter(|m| !(m.version > 1 && m.migration_type.is_down_migration()))
+        .cloned()
+        .collect();
+
+    match partial.undo(&mut conn, 0).await {
+        Err(MigrateError::MissingDownMigrations(versions)) => assert_eq!(versions, [3, 2]),
+        res => panic!("expected missing down migrations, got {:?}", res),
+    }
+
+    assert_eq!(tables(&mut conn).await?, ["comment", "post", "user"]);
+
+    Ok(())
+}
+
+#[cfg(feature = "migrate")]
+#[sqlx_macros::test]
+async fn it_plans_migrations_without_creating_the_migrations_table() -> anyhow::Result<()> {
+    use sqlx::migrate::Migrator;
+    use std::path::Path;
+
+    let migrator = Migrator::new(Path::new("tests/sqlite/migrations_reversible")).await?;
+    let mut conn = SqliteConnection::connect("sqlite::memory:").await?;
+
+    let plan = migrator.plan(&mut conn).await?;
+
+    assert!(plan.applied.is_empty());
+    assert_eq!(
+        plan.pending.iter().map(|m| m.version).collect::<Vec<_>>(),
+        [1, 2, 3]
+    );
+
+    let tables: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM sqlite_schema")
+        .fetch_one(&mut conn)
+        .await?;
+    assert_eq!(tables, 0);
+
+    Ok(())
+}
+
+#[cfg(feature = "migrate")]
+#[sqlx_macros::test]
+async fn it_plans_migrations_without_applying_them() -> anyhow::Result<()> {
+    use sqlx::migrate::{MigrateError, MigrationProblem, Migrator};
+    use std::path::Path;
+
+    let migrator = Migrator::new(Path::new("tests/sqlite/migrations_reversible")).await?;
+    let mut conn = SqliteConnection::connect("sqlite::memory:").await?;
+
+    // apply everything but the second migration
+    let mut partial = Migrator::new(Path::new("tests/sqlite/migrations_reversible")).await?;
+    partial.migrations = partial.iter().filter(|m| m.version != 2).cloned().collect();
+
+    partial.run(&mut conn).await?;
+
+    let plan = migrator.plan(&mut conn).await?;
+
+    assert_eq!(
+        plan.applied.iter().map(|m| m.version).collect::<Vec<_>>(),
+        [1, 3]
+    );
+    assert_eq!(
+        plan.pending.iter().map(|m| m.version).collect::<Vec<_>>(),
+        [2]
+    );
+    assert_eq!(plan.problems, [MigrationProblem::OutOfOrder(2)]);
+    assert!(matches!(plan.check(), Err(MigrateError::OutOfOrder(2))));
+
+    // nothing was applied
+    let post: Option<String> =
+        sqlx::query_scalar("SELECT name FROM sqlite_schema WHERE name = 'post'")
+            .fetch_optional(&mut conn)
+            .await?;
+    assert!(post.is_none());
+
+    // a migration which isn't resolved any more, and one which was modified
+    let mut changed = Migrator::new(Path::new("tests/sqlite/migrations_reversible")).await?;
+    changed.migrations = changed
+        .iter()
+        .filter(|m| m.version != 3)
+        .cloned()
+        .map(|mut m| {
+            if m.version == 1 {
+                m.checksum = vec![0; 48].into();
+            }
+            m
+        })
+        .collect();
+
+    let plan = changed.plan(&mut conn).await?;
+
+    assert_eq!(
+        plan.problems,
+        [
+            MigrationProblem::MissingLocally(3),
+            MigrationProblem::ChecksumMismatch(1),
+            MigrationProblem::OutOfOrder(2),
+        ]
+    );
+    assert!(matches!(plan.check(), Err(MigrateError::VersionMissing(3))));
+    assert!(matches!(
+        changed.run(&mut conn).await,
+        Err(MigrateError::VersionMissing(3))
+    ));
+
+    Ok(())
+}
+
+#[cfg(feature = "migrate")]
+#[sqlx_macros::test]
+async fn it_applies_migrations_out_of_order_when_allowed() -> anyhow::Result<()> {
+    use sqlx::migrate::{
+        Locking, Migrate, MigrateError, Migration, MigrationType, Migrator,
+        DEFAULT_MIGRATIONS_TABLE,
+    };
+    use std::borrow::Cow;
+
+    fn migrator(versions: &[i64]) -> Migrator {
+        let migrations: Vec<_> = versions
+            .iter()
+            .map(|&version| {
+                Migration::new(
+                    version,
+                    Cow::Owned(format!("t{}", version)),
+                    MigrationType::Simple,
+                    Cow::Owned(format!("CREATE TABLE t{} (x INTEGER);", version)),
+                )
+            })
+            .collect();
+
+        Migrator {
+            migrations: migrations.into(),
+            ignore_missing: false,
+            allow_out_of_order: false,
+            table_name: DEFAULT_MIGRATIONS_TABLE.into(),
+            locking: Locking::default(),
+            applied_by: None,
+            seeds: Cow::Borrowed(&[]),
+            after_run: Cow::Borrowed(&[]),
+        }
+    }
+
+    async fn applied(conn: &mut SqliteConnection) -> anyhow::Result<Vec<i64>> {
+        Ok(conn
+            .list_applied_migrations(DEFAULT_MIGRATIONS_TABLE)
+            .await?
+            .into_iter()
+            .map(|m| m.version)
+            .collect())
+    }
+
+    let mut conn = SqliteConnection::connect("sqlite::memory:").await?;
+
+    // one branch deploys 1, 3 and 5, then another merges 2 and 4
+    migrator(&[1, 3, 5]).run(&mut conn).await?;
+
+    let mut merged = migrator(&[1, 2, 3, 4, 5]);
+
+    assert!(matches!(
+        merged.run(&mut conn).await,
+        Err(MigrateError::OutOfOrder(2))
+    ));
+    assert_eq!(applied(&mut conn).await?, [1, 3, 5]);
+
+    merged.set_allow_out_of_order(true);
+    assert!(merged.plan(&mut conn).await?.problems.is_empty());
+
+    merged.run(&mut conn).await?;
+    assert_eq!(applied(&mut conn).await?, [1, 2, 3, 4, 5]);
+
+    // later migrations are applied as usual, in strict mode too
+    migrator(&[1, 2, 3, 4, 5, 6]).run(&mut conn).await?;
+    assert_eq!(applied(&mut conn).await?, [1, 2, 3, 4, 5, 6]);
+
+    // and a migration older than all of them still isn't, unless allowed
+    let mut older = migrator(&[0, 1, 2, 3, 4, 5, 6]);
+
+    assert!(matches!(
+        older.run(&mut conn).await,
+        Err(MigrateError::OutOfOrder(0))
+    ));
+
+    older.set_allow_out_of_order(true);
+    older.run(&mut conn).await?;
+    assert_eq!(applied(&mut conn).await?, [0, 1, 2, 3, 4, 5, 6]);
+
+    Ok(())
+}
+
+#[cfg(feature = "migrate")]
+#[sqlx_macros::test]
+async fn it_applies_repeatable_migrations_when_they_change() -> anyhow::Result<()> {
+    use sqlx::migrate::{Migrate, Migration, Migrator};
+    use std::path::Path;
+
+    let mut migrator = Migrator::new(Path::new("tests/sqlite/migrations_repeatable")).await?;
+    let mut conn = SqliteConnection::connect("sqlite::memory:").await?;
+
+    // versioned migrations first, then repeatable ones by description
+    assert_eq!(
+        migrator
+            .iter()
+            .map(|m| (m.migration_type.is_repeatable(), &*m.description))
+            .collect::<Vec<_>>(),
+        [
+            (false, "user"),
+            (false, "post"),
+            (true, "post with author"),
+            (true, "user post count"),
+        ]
+    );
+
+    migrator.run(&mut conn).await?;
+
+    let views: Vec<String> =
+        sqlx::query_scalar("SELECT name FROM sqlite_schema WHERE type = 'view' ORDER BY name")
+            .fetch_all(&mut conn)
+            .await?;
+    assert_eq!(views, ["post_with_author", "user_post_count"]);
+
+    // repeatable migrations aren't versions
+    assert_eq!(
+        conn.list_applied_migrations(&migrator.table_name)
+            .await?
+            .iter()
+            .map(|m| m.version)
+            .collect::<Vec<_>>(),
+        [1, 2]
+    );
+
+    // nothing changed, so nothing is pending
+    assert!(migrator.plan(&mut conn).await?.pending.is_empty());
+
+    // change one of them
+    let mut migrations = migrator.migrations.to_vec();
+    let changed = migrations
+        .iter_mut()
+        .find(|m| m.description == "user post count")
+        .unwrap();
+
+    *changed = Migration::new(
+        changed.version,
+        changed.description.clone(),
+        changed.migration_type,
+        "DROP VIEW IF EXISTS user_post_count; \
+         CREATE VIEW user_post_count AS SELECT user_id, 0 AS posts FROM user;"
+            .into(),
+    );
+
+    migrator.migrations = migrations.into();
+
+    let plan = migrator.plan(&mut conn).await?;
+    assert_eq!(
+        plan.pending
+            .iter()
+            .map(|m| &*m.description)
+            .collect::<Vec<_>>(),
+        ["user post count"]
+    );
+
+    migrator.run(&mut conn).await?;
+
+    let columns: Vec<String> =
+        sqlx::query_scalar("SELECT name FROM pragma_table_info('user_post_count')")
+            .fetch_all(&mut conn)
+            .await?;
+    assert_eq!(columns, ["user_id", "posts"]);
+
+    // the previous run of a repeatable migration is replaced
+    let applied = conn
+        .list_applied_repeatable_migrations(&migrator.table_name)
+        .await?;
+    assert_eq!(applied.len(), 2);
+    assert!(migrator.plan(&mut conn).await?.pending.is_empty());
+
+    Ok(())
+}
+
+#[cfg(feature = "migrate")]
+#[sqlx_macros::test]
+async fn it_applies_migrations_outside_of_a_transaction() -> anyhow::Result<()> {
+    use sqlx::migrate::{
+        Locking, Migration, MigrationType, Migrator, DEFAULT_MIGRATIONS_TABLE, NO_TRANSACTION,
+    };
+    use std::borrow::Cow;
+
+    fn migrator(sql: String) -> Migrator {
+        Migrator {
+            migrations: vec![Migration::new(
+                1,
+                Cow::Borrowed("vacuum"),
+                MigrationType::Simple,
+                Cow::Owned(sql),
+            )]
+            .into(),
+            ignore_missing: false,
+            allow_out_of_order: false,
+            table_name: DEFAULT_MIGRATIONS_TABLE.into(),
+            locking: Locking::default(),
+            applied_by: None,
+            seeds: Cow::Borrowed(&[]),
+            after_run: Cow::Borrowed(&[]),
+        }
+    }
+
+    let sql = "CREATE TABLE kv (k TEXT); VACUUM;";
+
+    // `VACUUM` can't be run within a transaction
+    let mut conn = SqliteConnection::connect("sqlite::memory:").await?;
+    let in_tx = migrator(sql.to_owned());
+
+    assert!(!in_tx.migrations[0].no_tx);
+    assert!(in_tx.run(&mut conn).await.is_err());
+
+    let mut conn = SqliteConnection::connect("sqlite::memory:").await?;
+    let no_tx = migrator(format!("{}\n{}", NO_TRANSACTION, sql));
+
+    assert!(no_tx.migrations[0].no_tx);
+    no_tx.run(&mut conn).await?;
+    assert!(no_tx.plan(&mut conn).await?.pending.is_empty());
+
+    Ok(())
+}
+
+#[cfg(feature = "migrate")]
+#[sqlx_macros::test]
+async fn it_records_migrations_in_a_custom_table() -> anyhow::Result<()> {
+    use sqlx::migrate::{
+        Locking, Migrate, Migration, MigrationType, Migrator, DEFAULT_MIGRATIONS_TABLE,
+    };
+    use std::borrow::Cow;
+
+    // the migrations of a service, with the same versions as those of the other services
+    fn migrator(service: &str) -> Migrator {
+        let migrations: Vec<_> = [1, 2]
+            .iter()
+            .map(|&version| {
+                Migration::new(
+                    version,
+                    Cow::Owned(format!("{} {}", service, version)),
+                    MigrationType::Simple,
+                    Cow::Owned(format!("CREATE TABLE {}_{} (x INTEGER);", service, version)),
+                )
+            })
+            .collect();
+
+        Migrator {
+            migrations: migrations.into(),
+            ignore_missing: false,
+            allow_out_of_order: false,
+            table_name: DEFAULT_MIGRATIONS_TABLE.into(),
+            locking: Locking::default(),
+            applied_by: None,
+            seeds: Cow::Borrowed(&[]),
+            after_run: Cow::Borrowed(&[]),
+        }
+    }
+
+    async fn tables(conn: &mut SqliteConnection, schema: &str) -> anyhow::Result<Vec<String>> {
+        Ok(sqlx::query_scalar(&format!(
+            "SELECT name FROM {}.sqlite_schema WHERE type = 'table' ORDER BY name",
+            schema
+        ))
+        .fetch_all(conn)
+        .await?)
+    }
+
+    let mut conn = SqliteConnection::connect("sqlite::memory:").await?;
+
+    let mut user = migrator("user");
+    user.set_table_name("user_migrations");
+    user.run(&mut conn).await?;
+
+    // recorded in a table of the `temp` schema
+    let mut post = migrator("post");
+    post.set_table_name("temp.post_migrations");
+    post.run(&mut conn).await?;
+
+    assert_eq!(
+        tables(&mut conn, "main").await?,
+        ["post_1", "post_2", "user_1", "user_2", "user_migrations"]
+    );
+    assert_eq!(tables(&mut conn, "temp").await?, ["post_migrations"]);
+
+    // each service only sees its own migrations
+    for table_name in ["user_migrations", "temp.post_migrations"] {
+        let applied = conn.list_applied_migrations(table_name).await?;
+        assert_eq!(
+            applied.iter().map(|m| m.version).collect::<Vec<_>>(),
+            [1, 2]
+        );
+    }
+
+    assert!(user.plan(&mut conn).await?.pending.is_empty());
+    assert!(post.plan(&mut conn).await?.pending.is_empty());
+
+    // nothing was recorded in the default table
+    let default = migrator("comment");
+    assert_eq!(default.plan(&mut conn).await?.pending.len(), 2);
+
+    Ok(())
+}
+
+#[cfg(feature = "migrate")]
+#[sqlx_macros::test]
+async fn it_repairs_checksums_and_baselines_migrations() -> anyhow::Result<()> {
+    use sqlx::migrate::{
+        Locking, Migrate, MigrateError, Migration, MigrationType, Migrator,
+        DEFAULT_MIGRATIONS_TABLE,
+    };
+    use std::borrow::Cow;
+
+    fn migrator(sql: &[&'static str]) -> Migrator {
+        let migrations: Vec<_> = sql
+            .iter()
+            .zip(1..)
+            .map(|(&sql, version)| {
+                Migration::new(
+                    version,
+                    Cow::Owned(format!("t{}", version)),
+                    MigrationType::Simple,
+                    Cow::Borrowed(sql),
+                )
+            })
+            .collect();
+
+        Migrator {
+            migrations: migrations.into(),
+            ignore_missing: false,
+            allow_out_of_order: false,
+            table_name: DEFAULT_MIGRATIONS_TABLE.into(),
+            locking: Locking::default(),
+            applied_by: None,
+            seeds: Cow::Borrowed(&[]),
+            after_run: Cow::Borrowed(&[]),
+        }
+    }
+
+    let mut conn = SqliteConnection::connect("sqlite::memory:").await?;
+
+    // a database whose schema was created before it was managed with migrations
+    conn.execute("CREATE TABLE t1 (x INTEGER); CREATE TABLE t2 (x INTEGER);")
+        .await?;
+
+    let original = migrator(&[
+        "CREATE TABLE t1 (x INTEGER);",
+        "CREATE TABLE t2 (x INTEGER);",
+        "CREATE TABLE t3 (x INTEGER);",
+    ]);
+
+    assert!(original.run(&mut conn).await.is_err());
+
+    let plan = original.plan(&mut conn).await?;
+    assert_eq!(
+        original
+            .baselines(&plan.applied, 2)
+            .iter()
+            .map(|m| m.version)
+            .collect::<Vec<_>>(),
+        [1, 2]
+    );
+
+    let baselined = original.baseline(&mut conn, 2).await?;
+    assert_eq!(baselined.len(), 2);
+    assert!(original.baseline(&mut conn, 2).await?.is_empty());
+
+    original.run(&mut conn).await?;
+    assert_eq!(
+        conn.list_applied_migrations(DEFAULT_MIGRATIONS_TABLE)
+            .await?
+            .len(),
+        3
+    );
+
+    // the first migration is reformatted
+    let reformatted = migrator(&[
+        "CREATE TABLE t1 (\n    x INTEGER\n);",
+        "CREATE TABLE t2 (x INTEGER);",
+        "CREATE TABLE t3 (x INTEGER);",
+    ]);
+
+    assert!(matches!(
+        reformatted.run(&mut conn).await,
+        Err(MigrateError::VersionMismatch(1))
+    ));
+
+    let repairs = reformatted.repair(&mut conn).await?;
+    assert_eq!(repairs.iter().map(|r| r.version).collect::<Vec<_>>(), [1]);
+    assert_eq!(repairs[0].applied_checksum, original.migrations[0].checksum);
+    assert_eq!(repairs[0].checksum, reformatted.migrations[0].checksum);
+
+    reformatted.run(&mut conn).await?;
+    assert!(reformatted.repair(&mut conn).await?.is_empty());
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_migrates_without_waiting_for_a_lock() -> anyhow::Result<()> {
+    use sqlx::migrate::{Locking, Migration, MigrationType, Migrator};
+    use std::borrow::Cow;
+    use std::time::Duration;
+
+    let mut conn = SqliteConnection::connect("sqlite::memory:").await?;
+
+    let mut migrator = Migrator {
+        migrations: Cow::Owned(vec![Migration::new(
+            1,
+            Cow::Borrowed("create users"),
+            MigrationType::Simple,
+            Cow::Borrowed("CREATE TABLE users (id INTEGER PRIMARY KEY);"),
+        )]),
+        ignore_missing: false,
+        allow_out_of_order: false,
+        table_name: "_sqlx_migrations".into(),
+        locking: Locking::Fail,
+        applied_by: None,
+        seeds: Cow::Borrowed(&[]),
+        after_run: Cow::Borrowed(&[]),
+    };
+
+    // SQLite has no migrations lock, so none of these wait for or fail on it
+    migrator.run(&mut conn).await?;
+
+    migrator.set_locking(Locking::None);
+    migrator.run(&mut conn).await?;
+
+    migrator.set_locking(Locking::Wait {
+        timeout: Some(Duration::ZERO),
+    });
+    migrator.run(&mut conn).await?;
+
+    let users: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM users")
+        .fetch_one(&mut conn)
+        .await?;
+    assert_eq!(users, 0);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_records_who_applied_migrations() -> anyhow::Result<()> {
+    use sqlx::migrate::{Locking, Migrate, Migration, MigrationType, Migrator};
+    use std::borrow::Cow;
+
+    let mut conn = SqliteConnection::connect("sqlite::memory:").await?;
+
+    // a migrations table created before the columns for audits, with a migration applied
+    conn.execute(
+        r#"
+CREATE TABLE _sqlx_migrations (
+    version BIGINT PRIMARY KEY,
+    description TEXT NOT NULL,
+    installed_on TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    success BOOLEAN NOT NULL,
+    checksum BLOB NOT NULL,
+    execution_time BIGINT NOT NULL
+);
+        "#,
+    )
+    .await?;
+
+    let migrations: Vec<_> = [1, 2]
+        .iter()
+        .map(|&version| {
+            Migration::new(
+                version,
+                Cow::Owned(format!("create table {}", version)),
+                MigrationType::Simple,
+                Cow::Owned(format!("CREATE TABLE t{} (x INTEGER);", version)),
+            )
+        })
+        .collect();
+
+    sqlx::query(
+        "INSERT INTO _sqlx_migrations ( version, description, success, checksum, execution_time ) \
+         VALUES ( 1, 'create table 1', TRUE, ?1, 0 )",
+    )
+    .bind(&*migrations[0].checksum)
+    .execute(&mut conn)
+    .await?;
+    conn.execute("CREATE TABLE t1 (x INTEGER)").await?;
+
+    let mut migrator = Migrator {
+        migrations: migrations.into(),
+        ignore_missing: false,
+        allow_out_of_order: false,
+        table_name: "_sqlx_migrations".into(),
+        locking: Locking::default(),
+        applied_by: None,
+        seeds: Cow::Borrowed(&[]),
+        after_run: Cow::Borrowed(&[]),
+    };
+    migrator.set_applied_by("deploy-bot");
+    migrator.run(&mut conn).await?;
+
+    let applied = conn.list_applied_migrations("_sqlx_migrations").await?;
+    assert_eq!(applied.len(), 2);
+
+    // recorded before the columns were added
+    assert_eq!(applied[0].applied_by, None);
+    assert_eq!(applied[0].sqlx_version, None);
+    assert_eq!(applied[0].hostname, None);
+
+    assert_eq!(applied[1].applied_by.as_deref(), Some("deploy-bot"));
+    assert!(applied[1].sqlx_version.is_some());
+    assert!(applied[1].hostname.is_some());
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_seeds_after_migrations() -> anyhow::Result<()> {
+    use sqlx::migrate::{MigrateError, Migration, MigrationType, Migrator, Seed};
+    use std::borrow::Cow;
+
+    let mut conn = SqliteConnection::connect("sqlite::memory:").await?;
+
+    let mut migrator = Migrator::from_iter([Migration::new(
+        1,
+        Cow::Borrowed("create countries"),
+        MigrationType::Simple,
+        Cow::Borrowed("CREATE TABLE countries (code TEXT PRIMARY KEY);"),
+    )])?;
+    migrator.add_seed(Seed::new(
+        "countries.sql",
+        "INSERT INTO countries VALUES ('NZ') ON CONFLICT DO NOTHING;",
+    ));
+    migrator.after_run(|conn: &mut SqliteConnection| {
+        Box::pin(async move {
+            let countries: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM countries")
+                .fetch_one(conn)
+                .await?;
+
+            if countries != 1 {
+                return Err(format!("expected 1 country, found {}", countries).into());
+            }
+
+            Ok(())
+        })
+    });
+
+    // the seed is idempotent, so it may run on every run
+    migrator.run(&mut conn).await?;
+    migrator.run(&mut conn).await?;
+
+    // only the migration is recorded
+    let recorded: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM _sqlx_migrations")
+        .fetch_one(&mut conn)
+        .await?;
+    assert_eq!(recorded, 1);
+
+    // a failed seed is rolled back, and reported apart from the migrations
+    migrator.add_seed(Seed::new(
+        "cities.sql",
+        "INSERT INTO countries VALUES ('AU'); INSERT INTO cities VALUES ('Sydney');",
+    ));
+
+    match migrator.run(&mut conn).await {
+        Err(MigrateError::Seed(name, _)) => assert_eq!(name, "cities.sql"),
+        res => panic!("expected a seed error, got {:?}", res),
+    }
+
+    let countries: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM countries")
+        .fetch_one(&mut conn)
+        .await?;
+    assert_eq!(countries, 1);
+
+    // the hook runs after the seeds, and its failure is reported apart from them
+    migrator.seeds.to_mut().pop();
+    migrator.add_seed(Seed::new(
+        "more countries.sql",
+        "INSERT INTO countries VALUES ('AU') ON CONFLICT DO NOTHING;",
+    ));
+
+    assert!(matches!(
+        migrator.run(&mut conn).await,
+        Err(MigrateError::AfterRun(_))
+    ));
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_reports_migration_progress() -> anyhow::Result<()> {
+    use sqlx::migrate::{Migration, MigrationEvent, MigrationType, Migrator, Seed};
+    use std::borrow::Cow;
+    use std::sync::Mutex;
+    use std::time::Duration;
+
+    let mut conn = SqliteConnection::connect("sqlite::memory:").await?;
+
+    let mut migrator = Migrator::from_iter((1..=2).map(|version| {
+        Migration::new(
+            version,
+            Cow::Owned(format!("create table {}", version)),
+            MigrationType::Simple,
+            Cow::Owned(format!("CREATE TABLE t{} (x INTEGER);", version)),
+        )
+    }))?;
+    migrator.add_seed(Seed::new("t1.sql", "INSERT INTO t1 VALUES (1);"));
+
+    let events = Mutex::new(Vec::new());
+    let durations = Mutex::new(Vec::new());
+
+    migrator
+        .run_with_progress(&mut conn, |event| {
+            let name = match event {
+                MigrationEvent::LockAcquired => "lock acquired".to_owned(),
+                MigrationEvent::Resolved { pending } => format!("resolved {}", pending.len()),
+                MigrationEvent::MigrationStarted { migration } => {
+                    format!("started {}", migration.version)
+                }
+                MigrationEvent::MigrationFinished {
+                    migration,
+                    elapsed,
+                    success,
+                } => {
+                    durations.lock().unwrap().push((migration.version, elapsed));
+                    format!("finished {} {}", migration.version, success)
+                }
+                MigrationEvent::SeedFinished { seed, .. } => format!("seeded {}", seed.name),
+                MigrationEvent::Completed { applied, .. } => format!("completed {}", applied),
+                _ => "other".to_owned(),
+            };
+
+            events.lock().unwrap().push(name);
+        })
+        .await?;
+
+    assert_eq!(
+        events.into_inner().unwrap(),
+        [
+            "lock acquired",
+            "resolved 2",
+            "started 1",
+            "finished 1 true",
+            "started 2",
+            "finished 2 true",
+            "seeded t1.sql",
+            "completed 2",
+        ]
+    );
+
+    // the durations reported are those recorded
+    let recorded: Vec<(i64, i64)> =
+        sqlx::query_as("SELECT version, execution_time FROM _sqlx_migrations ORDER BY version")
+            .fetch_all(&mut conn)
+            .await?;
+
+    let reported: Vec<(i64, i64)> = durations
+        .into_inner()
+        .unwrap()
+        .into_iter()
+        .map(|(version, elapsed): (i64, Duration)| (version, elapsed.as_nanos() as i64))
+        .collect();
+
+    assert_eq!(recorded, reported);
+
+    // a failed migration is reported before the run fails
+    let mut migrator = Migrator::from_iter([Migration::new(
+        3,
+        Cow::Borrowed("create table 1 again"),
+        MigrationType::Simple,
+        Cow::Borrowed("CREATE TABLE t1 (x INTEGER);"),
+    )])?;
+    migrator.set_ignore_missing(true);
+
+    let failed = Mutex::new(None);
+
+    let res = migrator
+        .run_with_progress(&mut conn, |event| {
+            if let MigrationEvent::MigrationFinished {
+                migration, success, ..
+            } = event
+            {
+                *failed.lock().unwrap() = Some((migration.version, success));
+            }
+        })
+        .await;
+
+    assert!(res.is_err());
+    assert_eq!(failed.into_inner().unwrap(), Some((3, false)));
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_verifies_migrations() -> anyhow::Result<()> {
+    use sqlx::migrate::{DriftKind, Migration, MigrationType, Migrator};
+    use std::borrow::Cow;
+
+    let mut conn = SqliteConnection::connect("sqlite::memory:").await?;
+
+    let migration = |version: i64, sql: &str| {
+        Migration::new(
+            version,
+            Cow::Owned(format!("create table {}", version)),
+            MigrationType::Simple,
+            Cow::Owned(sql.to_owned()),
+        )
+    };
+
+    let applied = Migrator::from_iter([
+        migration(1, "CREATE TABLE t1 (x INTEGER);"),
+        migration(2, "CREATE TABLE t2 (x INTEGER);"),
+        migration(3, "CREATE TABLE t3 (x INTEGER);"),
+    ])?;
+    applied.run(&mut conn).await?;
+
+    assert!(applied.verify(&mut conn).await?.is_clean());
+
+    // 1 was modified, and 3 is missing and newer than every migration
+    let local = Migrator::from_iter([
+        migration(1, "CREATE TABLE t1 (x INTEGER, y INTEGER);"),
+        migration(2, "CREATE TABLE t2 (x INTEGER);"),
+    ])?;
+    let report = local.verify(&mut conn).await?;
+
+    let drift: Vec<_> = report
+        .drift
+        .iter()
+        .map(|drift| (drift.kind, drift.version))
+        .collect();
+    assert_eq!(
+        drift,
+        [(DriftKind::Newer, Some(3)), (DriftKind::Modified, Some(1))]
+    );
+    assert!(!report.contains(DriftKind::Missing));
+
+    // 4 is pending
+    let local = Migrator::from_iter(
+        applied
+            .iter()
+            .cloned()
+            .chain([migration(4, "CREATE TABLE t4 (x INTEGER);")]),
+    )?;
+    let report = local.verify(&mut conn).await?;
+
+    assert_eq!(report.drift.len(), 1);
+    assert_eq!(report.drift[0].kind, DriftKind::Pending);
+    assert_eq!(report.drift[0].version, Some(4));
+
+    // a migration missing below the latest one isn't newer
+    let local = Migrator::from_iter([
+        migration(1, "CREATE TABLE t1 (x INTEGER);"),
+        migration(3, "CREATE TABLE t3 (x INTEGER);"),
+    ])?;
+    let report = local.verify(&mut conn).await?;
+
+    assert_eq!(report.drift.len(), 1);
+    assert_eq!(report.drift[0].kind, DriftKind::Missing);
+    assert_eq!(report.drift[0].version, Some(2));
+
+    Ok(())
+}
+
+#[cfg(feature = "migrate")]
+#[sqlx_macros::test]
+async fn it_creates_a_database_with_an_encoding() -> anyhow::Result<()> {
+    use sqlx::migrate::{CreateDatabaseOptions, MigrateDatabase};
+
+    let dir = tempdir::TempDir::new("sqlite_create_encoding_test")?;
+    let url = format!("sqlite://{}", dir.path().join("utf16.db").display());
+
+    Sqlite::create_database_with(&url, &CreateDatabaseOptions::new().encoding("utf-16le")).await?;
+
+    let mut conn = SqliteConnection::connect(&url).await?;
+    let encoding: String = sqlx::query_scalar("PRAGMA encoding")
+        .fetch_one(&mut conn)
+        .await?;
+    assert_eq!(encoding, "UTF-16le");
+
+    // the table which made the encoding stick is gone
+    let tables: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM sqlite_master")
+        .fetch_one(&mut conn)
+        .await?;
+    assert_eq!(tables, 0);
+    conn.close().await?;
+
+    // the options which don't apply to SQLite are errors, before anything is created
+    let url = format!("sqlite://{}", dir.path().join("owned.db").display());
+    let res = Sqlite::create_database_with(&url, &CreateDatabaseOptions::new().owner("app")).await;
+    assert!(matches!(res, Err(sqlx::Error::Configuration(_))));
+    assert!(!Sqlite::database_exists(&url).await?);
+
+    let res =
+        Sqlite::create_database_with(&url, &CreateDatabaseOptions::new().encoding("latin1")).await;
+    assert!(matches!(res, Err(sqlx::Error::Configuration(_))));
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_observes_queries_of_a_pool() -> anyhow::Result<()> {
+    use sqlx::observer::{Fingerprint, QueryObserver, QueryOutcome};
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    #[derive(Default)]
+    struct Observer {
+        started: Mutex<Vec<String>>,
+        ended: Mutex<Vec<(String, QueryOutcome, u64)>>,
+    }
+
+    impl QueryObserver for Observer {
+        fn on_query_start(&self, fingerprint: &Fingerprint) {
+            self.started.lock().unwrap().push(fingerprint.to_string());
+        }
 
-    #[cfg(any(feature = "_rt-tokio", feature = "_rt-actix"))]
-    {
-        read.await.unwrap();
-        write.await.unwrap();
+        fn on_query_end(
+            &self,
+            fingerprint: &Fingerprint,
+            outcome: QueryOutcome,
+            rows: u64,
+            _elapsed: Duration,
+        ) {
+            self.ended
+                .lock()
+                .unwrap()
+                .push((fingerprint.to_string(), outcome, rows));
+        }
     }
 
-    #[cfg(feature = "_rt-async-std")]
-    {
-        read.await;
-        write.await;
+    let observer = Arc::new(Observer::default());
+
+    let pool = SqlitePoolOptions::new()
+        .max_connections(1)
+        .query_observer(observer.clone())
+        .connect("sqlite::memory:")
+        .await?;
+
+    for value in [1_i64, 2] {
+        sqlx::query("SELECT ?, 'a' UNION ALL SELECT 3, 'b'")
+            .bind(value)
+            .fetch_all(&pool)
+            .await?;
+    }
+
+    pool.execute("SELECT * FROM missing").await.unwrap_err();
+
+    // the statements of the connection are executed in order
+    pool.execute("CREATE TABLE   observed (id INTEGER DEFAULT 0)")
+        .await?;
+
+    let observed = |fingerprints: &Mutex<Vec<String>>| {
+        fingerprints
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|fingerprint| !fingerprint.starts_with("PRAGMA"))
+            .cloned()
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(
+        observed(&observer.started),
+        [
+            "SELECT ?, ? UNION ALL SELECT ?, ?",
+            "SELECT ?, ? UNION ALL SELECT ?, ?",
+            "SELECT * FROM missing",
+            "CREATE TABLE observed (id INTEGER DEFAULT ?)",
+        ]
+    );
+
+    let ended = observer
+        .ended
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|(fingerprint, ..)| !fingerprint.starts_with("PRAGMA"))
+        .cloned()
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        ended,
+        [
+            (
+                "SELECT ?, ? UNION ALL SELECT ?, ?".into(),
+                QueryOutcome::Success,
+                2
+            ),
+            (
+                "SELECT ?, ? UNION ALL SELECT ?, ?".into(),
+                QueryOutcome::Success,
+                2
+            ),
+            ("SELECT * FROM missing".into(), QueryOutcome::Error, 0),
+            (
+                "CREATE TABLE observed (id INTEGER DEFAULT ?)".into(),
+                QueryOutcome::Success,
+                0
+            ),
+        ]
+    );
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_logs_statements_with_a_custom_logger() -> anyhow::Result<()> {
+    use sqlx::logger::{LoggedStatement, StatementLogger};
+    use std::str::FromStr;
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    #[derive(Debug, PartialEq)]
+    struct Logged {
+        sql: String,
+        level: log::Level,
+        rows_affected: u64,
+        rows_returned: u64,
+        slow: bool,
+        connection_id: u64,
+    }
+
+    #[derive(Default)]
+    struct Logger(Mutex<Vec<Logged>>);
+
+    impl StatementLogger for Logger {
+        fn log(&self, statement: &LoggedStatement<'_>) {
+            self.0.lock().unwrap().push(Logged {
+                sql: statement.sql.to_owned(),
+                level: statement.level,
+                rows_affected: statement.rows_affected,
+                rows_returned: statement.rows_returned,
+                slow: statement.slow,
+                connection_id: statement.connection_id,
+            });
+        }
+    }
+
+    let logger = Arc::new(Logger::default());
+
+    let pool = SqlitePoolOptions::new()
+        .max_connections(1)
+        .log_with(logger.clone())
+        .connect_with(
+            SqliteConnectOptions::from_str(":memory:")?
+                .log_statements(log::LevelFilter::Debug)
+                .clone(),
+        )
+        .await?;
+
+    let sql = "CREATE TABLE logged (id INTEGER);\n    INSERT INTO logged VALUES (1), (2)";
+    pool.execute(sql).await?;
+    pool.fetch_all("SELECT id FROM logged").await?;
+
+    let logged = std::mem::take(&mut *logger.0.lock().unwrap());
+    let logged: Vec<_> = logged
+        .into_iter()
+        .filter(|logged| !logged.sql.starts_with("PRAGMA"))
+        .collect();
+
+    assert_eq!(logged.len(), 2);
+
+    let connection_id = logged[0].connection_id;
+    assert_ne!(connection_id, 0);
+
+    // the SQL is passed as it was executed, rather than summarized and prettified
+    assert_eq!(
+        logged[0],
+        Logged {
+            sql: sql.to_owned(),
+            level: log::Level::Debug,
+            rows_affected: 2,
+            rows_returned: 0,
+            slow: false,
+            connection_id,
+        }
+    );
+
+    assert_eq!(logged[1].sql, "SELECT id FROM logged");
+    assert_eq!(logged[1].rows_returned, 2);
+    assert_eq!(logged[1].connection_id, connection_id);
+
+    // every statement is slow from now on
+    let mut options = SqliteConnectOptions::from_str(":memory:")?;
+    options
+        .log_with(logger.clone())
+        .log_slow_statements(log::LevelFilter::Warn, Duration::ZERO);
+
+    let mut conn = options.connect().await?;
+    conn.execute("SELECT 1").await?;
+
+    let logged = logger.0.lock().unwrap().pop().unwrap();
+
+    assert_eq!(logged.sql, "SELECT 1");
+    assert_eq!(logged.level, log::Level::Warn);
+    assert!(logged.slow);
+    assert_ne!(logged.connection_id, connection_id);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_round_trips_quoted_identifiers_and_literals() -> anyhow::Result<()> {
+    use sqlx::Database;
+
+    let mut conn = new::<Sqlite>().await?;
+
+    let alphabet = [
+        '"', '\'', '`', '\\', '[', ']', ';', '-', ' ', '$', '?', 'a', 'Z', '0', 'é', '✓',
+    ];
+    let mut rng = Xoshiro256PlusPlus::seed_from_u64(0xC0FFEE);
+
+    let mut names: Vec<String> = vec![
+        r#"with "double" quotes"#.into(),
+        "with `backticks`".into(),
+        "with 'single' quotes".into(),
+        "with [brackets]]".into(),
+        r"back\slash\".into(),
+        "'; DROP TABLE users; --".into(),
+        "$1 ?2 :name @p3".into(),
+        "ünïcødé ✓".into(),
+    ];
+
+    names.extend((0..32).map(|_| {
+        let len = rng.gen_range(1..=16);
+
+        (0..len)
+            .map(|_| alphabet[rng.gen_range(0..alphabet.len())])
+            .collect::<String>()
+    }));
+
+    for name in &names {
+        let table = Sqlite::quote_identifier(name)?;
+
+        conn.execute(&*format!(
+            "CREATE TEMPORARY TABLE {} ({} TEXT)",
+            table, table
+        ))
+        .await?;
+
+        conn.execute(&*format!(
+            "INSERT INTO {} VALUES ({})",
+            table,
+            Sqlite::quote_literal(name)?
+        ))
+        .await?;
+
+        let row = conn
+            .fetch_one(&*format!("SELECT {} FROM {}", table, table))
+            .await?;
+
+        assert_eq!(row.column(0).name(), name);
+        assert_eq!(row.try_get::<String, _>(0)?, *name);
+
+        conn.execute(&*format!("DROP TABLE {}", table)).await?;
+    }
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_works_with_a_type_erased_connection() -> anyhow::Result<()> {
+    let mut conn: sqlx::dyn_connection::BoxDynConnection = Box::new(new::<Sqlite>().await?);
+
+    let visits = count_visits(&mut *conn, &["home", "it's", "home"]).await?;
+
+    assert_eq!(
+        visits,
+        [
+            ("home".to_owned(), 2, 1.0, true),
+            ("it's".to_owned(), 1, 0.5, false),
+        ]
+    );
+
+    assert!(conn.downcast_mut::<SqliteConnection>().is_some());
+    assert!(conn.is::<SqliteConnection>());
+
+    let conn = conn.downcast::<SqliteConnection>().ok().unwrap();
+    conn.close().await?;
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_pings_inside_and_outside_transactions() -> anyhow::Result<()> {
+    use std::time::Duration;
+
+    let mut conn = new::<Sqlite>().await?;
+
+    conn.ping().await?;
+    conn.ping_ex().await?;
+
+    let mut tx = conn.begin().await?;
+
+    tx.ping().await?;
+    let result = tx.ping_ex().await?;
+    assert!(result.round_trip < Duration::from_secs(10));
+
+    tx.rollback().await?;
+
+    conn.ping_ex().await?;
+
+    Ok(())
+}
+
+/// A plugin which is handed a connection without knowing its database.
+async fn count_visits(
+    conn: &mut dyn sqlx::dyn_connection::DynConnection,
+    pages: &[&str],
+) -> anyhow::Result<Vec<(String, i64, f64, bool)>> {
+    conn.execute(
+        "CREATE TEMPORARY TABLE plugin_visits (page TEXT NOT NULL, hits INTEGER NOT NULL)",
+    )
+    .await?;
+
+    conn.begin().await?;
+
+    for page in pages {
+        let page = conn.quote_literal(page)?;
+
+        let updated = conn
+            .execute(&format!(
+                "UPDATE plugin_visits SET hits = hits + 1 WHERE page = {}",
+                page
+            ))
+            .await?;
+
+        if updated == 0 {
+            conn.execute(&format!(
+                "INSERT INTO plugin_visits (page, hits) VALUES ({}, 1)",
+                page
+            ))
+            .await?;
+        }
+    }
+
+    conn.commit().await?;
+
+    conn.begin().await?;
+    assert_eq!(conn.execute("DELETE FROM plugin_visits").await?, 2);
+    conn.rollback().await?;
+
+    let rows = conn
+        .fetch_all(
+            "SELECT page, hits, CAST(hits AS REAL) / 2, hits > 1, NULL AS missing \
+             FROM plugin_visits ORDER BY page",
+        )
+        .await?;
+
+    let mut visits = Vec::new();
+
+    for row in rows {
+        assert_eq!(row.len(), 5);
+        assert_eq!(row.column_name(0), Some("page"));
+        assert_eq!(row.try_column_index("missing")?, 4);
+        assert!(row.try_is_null(4)?);
+        assert!(row.try_get_i64(0).is_err());
+
+        visits.push((
+            row.try_get_string(0)?.unwrap(),
+            row.try_get_i64(1)?.unwrap(),
+            row.try_get_f64(2)?.unwrap(),
+            row.try_get_bool(3)?.unwrap(),
+        ));
+    }
+
+    Ok(visits)
+}
+
+#[sqlx_macros::test]
+async fn it_executes_and_closes_prepared_statements() -> anyhow::Result<()> {
+    let mut conn = new::<Sqlite>().await?;
+
+    let stmt = conn.prepare("SELECT ?1 + 1").await?;
+
+    for i in 0..3_i64 {
+        let value: i64 = stmt.query_scalar().bind(i).fetch_one(&mut conn).await?;
+        assert_eq!(value, i + 1);
+    }
+
+    let prepared = conn.prepared_statements().await?;
+
+    assert_eq!(prepared.len(), 1);
+    assert_eq!(prepared[0].sql, "SELECT ?1 + 1");
+    assert_eq!(prepared[0].hits, 3);
+
+    // the statement is bound to the connection which prepared it, unless detached
+    let mut other = new::<Sqlite>().await?;
+    let res = stmt.query().bind(1_i64).execute(&mut other).await;
+
+    assert!(matches!(
+        res,
+        Err(sqlx::Error::StatementConnectionMismatch { .. })
+    ));
+
+    let mut detached = stmt.clone();
+    detached.detach();
+
+    let value: i64 = detached
+        .query_scalar()
+        .bind(1_i64)
+        .fetch_one(&mut other)
+        .await?;
+
+    assert_eq!(value, 2);
+
+    conn.close_statement(stmt.clone()).await?;
+
+    assert!(conn.prepared_statements().await?.is_empty());
+    assert_eq!(0, conn.cached_statements_size());
+
+    // a closed statement is prepared again
+    let value: i64 = stmt
+        .query_scalar()
+        .bind(41_i64)
+        .fetch_one(&mut conn)
+        .await?;
+    assert_eq!(value, 42);
+
+    Ok(())
+}
+
+#[cfg(feature = "serde_row")]
+#[sqlx_macros::test]
+async fn it_deserializes_rows_with_serde() -> anyhow::Result<()> {
+    use sqlx::serde_row::DeserializeRow;
+
+    #[derive(Debug, PartialEq, serde::Deserialize)]
+    struct Note {
+        id: i64,
+        body: String,
+        score: Option<f64>,
+    }
+
+    let mut conn = new::<Sqlite>().await?;
+
+    let row = sqlx::query(
+        "SELECT 1 AS id, 'hello' AS body, 2.5 AS score, X'0102' AS data, NULL AS missing",
+    )
+    .fetch_one(&mut conn)
+    .await?;
+
+    let value: serde_json::Value = row.deserialize()?;
+
+    assert_eq!(
+        value,
+        serde_json::json!({
+            "id": 1,
+            "body": "hello",
+            "score": 2.5,
+            "data": [1, 2],
+            "missing": null,
+        })
+    );
+
+    // the columns which aren't fields are ignored
+    let note: Note = row.deserialize()?;
+
+    assert_eq!(
+        note,
+        Note {
+            id: 1,
+            body: "hello".into(),
+            score: Some(2.5),
+        }
+    );
+
+    let (id, body): (i64, String) = row.deserialize()?;
+
+    assert_eq!(id, 1);
+    assert_eq!(body, "hello");
+
+    let notes: Vec<Note> =
+        sqlx::query("SELECT 1 AS id, 'a' AS body, NULL AS score UNION ALL SELECT 2, 'b', 0.5")
+            .fetch_all_deserialized(&mut conn)
+            .await?;
+
+    assert_eq!(notes.len(), 2);
+    assert_eq!(notes[0].score, None);
+    assert_eq!(notes[1].score, Some(0.5));
+
+    // a value which doesn't match its field names its column
+    #[derive(Debug, serde::Deserialize)]
+    #[allow(dead_code)]
+    struct Mismatched {
+        body: i64,
+    }
+
+    let res = row.deserialize::<Mismatched>();
+
+    assert!(
+        matches!(res, Err(sqlx::Error::ColumnDecode { ref index, .. }) if index == "\"body\""),
+        "{:?}",
+        res
+    );
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_inserts_rows_in_bulk() -> anyhow::Result<()> {
+    #[derive(sqlx::InsertRow, sqlx::FromRow, Debug, PartialEq)]
+    struct Tweet {
+        id: i64,
+        text: String,
+        #[sqlx(rename = "owner_id")]
+        owner: Option<i64>,
+    }
+
+    fn tweets(ids: std::ops::Range<i64>) -> impl Iterator<Item = Tweet> {
+        ids.map(|id| Tweet {
+            id,
+            text: format!("#{}", id),
+            owner: if id % 2 == 0 { Some(id) } else { None },
+        })
+    }
+
+    let mut conn = new::<Sqlite>().await?;
+
+    conn.execute(
+        "CREATE TEMPORARY TABLE bulk (id INTEGER PRIMARY KEY, text TEXT NOT NULL, owner_id INTEGER)",
+    )
+    .await?;
+
+    // 2 rows of 3 columns per statement
+    let result = sqlx::insert("bulk", tweets(0..5))
+        .max_bind_parameters(8)
+        .execute(&mut conn)
+        .await?;
+    assert_eq!(result.rows_affected(), 5);
+
+    // 10922 rows per statement
+    let result = sqlx::insert("bulk", tweets(5..30_000))
+        .execute(&mut conn)
+        .await?;
+    assert_eq!(result.rows_affected(), 29_995);
+
+    let tweet: Tweet = sqlx::query_as("SELECT * FROM bulk WHERE id = 29998")
+        .fetch_one(&mut conn)
+        .await?;
+    assert_eq!(tweet, tweets(29_998..29_999).next().unwrap());
+
+    let result = sqlx::insert("bulk", tweets(29_990..30_010))
+        .suffix("ON CONFLICT (id) DO NOTHING")
+        .execute(&mut conn)
+        .await?;
+    assert_eq!(result.rows_affected(), 10);
+
+    // the rows of the statements before the one which failed are kept, unless in a transaction
+    let res = sqlx::insert("bulk", tweets(30_010..30_020).chain(tweets(0..1)))
+        .max_rows(5)
+        .transaction(true)
+        .execute(&mut conn)
+        .await;
+    assert!(res.is_err());
+
+    let count: i64 = sqlx::query_scalar("SELECT count(*) FROM bulk")
+        .fetch_one(&mut conn)
+        .await?;
+    assert_eq!(count, 30_010);
+
+    let res = sqlx::insert("bulk", tweets(30_010..30_020).chain(tweets(0..1)))
+        .max_rows(5)
+        .execute(&mut conn)
+        .await;
+    assert!(res.is_err());
+
+    let count: i64 = sqlx::query_scalar("SELECT count(*) FROM bulk")
+        .fetch_one(&mut conn)
+        .await?;
+    assert_eq!(count, 30_020);
+
+    let res = sqlx::insert("bulk", tweets(0..1))
+        .max_bind_parameters(2)
+        .execute(&mut conn)
+        .await;
+    assert!(
+        matches!(res, Err(sqlx::Error::Configuration(_))),
+        "{:?}",
+        res
+    );
+
+    let result = sqlx::insert("bulk", tweets(0..0))
+        .execute(&mut conn)
+        .await?;
+    assert_eq!(result.rows_affected(), 0);
+
+    Ok(())
+}
+
+#[cfg(feature = "json")]
+#[sqlx_macros::test]
+async fn it_decodes_dynamic_values() -> anyhow::Result<()> {
+    use serde_json::{json, Map, Value};
+    use sqlx::sqlite::SqliteDynamicValue;
+
+    let mut conn = new::<Sqlite>().await?;
+
+    let sql = r#"
+SELECT NULL              AS "null",
+       42                AS "integer",
+       1.5               AS "real",
+       'hello'           AS "text",
+       X'deadbeef'       AS "blob",
+       '2022-02-28'      AS "date",
+       ?1                AS "bound"
+"#;
+
+    fn to_json(row: &SqliteRow) -> Result<Value, sqlx::Error> {
+        let mut object = Map::new();
+
+        for column in row.columns() {
+            let value = row.try_get_dynamic(column.ordinal())?;
+            object.insert(column.name().to_owned(), value.into());
+        }
+
+        Ok(Value::Object(object))
     }
+
+    let row = sqlx::query(sql).bind(7_i64).fetch_one(&mut conn).await?;
+
+    assert_eq!(
+        to_json(&row)?,
+        json!({
+            "null": null,
+            "integer": 42,
+            "real": 1.5,
+            "text": "hello",
+            "blob": "X'deadbeef'",
+            "date": "2022-02-28",
+            "bound": 7,
+        })
+    );
+
+    assert_eq!(row.try_get_dynamic("real")?, SqliteDynamicValue::Real(1.5));
+    assert_eq!(
+        row.try_get_dynamic("blob")?,
+        SqliteDynamicValue::Blob(vec![0xde, 0xad, 0xbe, 0xef])
+    );
+
+    Ok(())
 }
@@ -97,3 +97,62 @@ async fn it_gets_comments(pool: SqlitePool) -> sqlx::Result<()> {
 
     Ok(())
 }
+
+// Fixtures from explicit paths and from the scripts of a directory are applied in order
+#[sqlx::test(
+    migrations = "tests/sqlite/migrations",
+    fixtures("fixtures/users.sql"),
+    fixtures(path = "../sqlite/fixtures", scripts("posts", "comments.sql"))
+)]
+async fn it_gets_fixtures_from_paths(pool: SqlitePool) -> sqlx::Result<()> {
+    let comment_count: i64 = sqlx::query_scalar("SELECT count(*) FROM comment")
+        .fetch_one(&pool)
+        .await?;
+
+    assert_eq!(comment_count, 3);
+
+    Ok(())
+}
+
+// `VACUUM` fails within a transaction
+#[sqlx::test(migrations = "tests/sqlite/migrations", fixtures("users", "vacuum"))]
+async fn it_applies_fixtures_without_a_transaction(pool: SqlitePool) -> sqlx::Result<()> {
+    let user_count: i64 = sqlx::query_scalar(r#"SELECT count(*) FROM "user""#)
+        .fetch_one(&pool)
+        .await?;
+
+    assert_eq!(user_count, 2);
+
+    Ok(())
+}
+
+async fn failing_teardown(_pool: SqlitePool) -> sqlx::Result<()> {
+    Err(sqlx::Error::RowNotFound)
+}
+
+#[sqlx::test(teardown = "failing_teardown")]
+#[should_panic(expected = "failed to tear down")]
+async fn it_fails_when_the_teardown_fails(_pool: SqlitePool) {}
+
+async fn panicking_teardown(pool: SqlitePool) -> sqlx::Result<()> {
+    // the test database is still there
+    let user_count: i64 = sqlx::query_scalar(r#"SELECT count(*) FROM "user""#)
+        .fetch_one(&pool)
+        .await?;
+
+    panic!("tore down {} users", user_count);
+}
+
+#[sqlx::test(
+    migrations = "tests/sqlite/migrations",
+    teardown = "panicking_teardown"
+)]
+#[should_panic(expected = "tore down 1 users")]
+async fn it_tears_down_after_a_panic(pool: SqlitePool) {
+    sqlx::query(r#"INSERT INTO "user"(user_id, username) VALUES (1, 'alice')"#)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    panic!("the test failed");
+}
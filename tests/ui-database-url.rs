@@ -0,0 +1,27 @@
+use std::env;
+use std::path::Path;
+
+// a process of its own, as the database URL is given to the UI tests in the environment
+#[test]
+#[ignore]
+fn ui_database_url_tests() {
+    let database = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/sqlite/sqlite.db");
+
+    // the trybuild project is the package `sqlx-tests`
+    env::set_var(
+        "SQLX_DATABASE_URL_SQLX_TESTS",
+        format!("sqlite://{}", database.display()),
+    );
+    env::set_var("DATABASE_URL", "sqlite://does-not-exist.db");
+    env::set_var("SQLX_OFFLINE", "false");
+
+    trybuild::TestCases::new().compile_fail("tests/ui/database-url/per-package/*.rs");
+
+    env::remove_var("SQLX_DATABASE_URL_SQLX_TESTS");
+    env::remove_var("DATABASE_URL");
+
+    // the error lists where the URL was looked for, and differs with offline data
+    if cfg!(not(feature = "offline")) {
+        trybuild::TestCases::new().compile_fail("tests/ui/database-url/missing/*.rs");
+    }
+}
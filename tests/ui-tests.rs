@@ -7,6 +7,7 @@ fn ui_tests() {
 
     if cfg!(feature = "postgres") {
         t.compile_fail("tests/ui/postgres/*.rs");
+        t.pass("tests/ui/postgres/pass/*.rs");
 
         // UI tests for column types that require gated features
         if cfg!(not(feature = "chrono")) && cfg!(not(feature = "time")) {
@@ -40,6 +41,9 @@ fn ui_tests() {
         }
 
         t.compile_fail("tests/ui/sqlite/*.rs");
+        t.compile_fail("tests/ui/sqlite/overrides/*.rs");
+        t.pass("tests/ui/sqlite/overrides/pass/*.rs");
+        t.compile_fail("tests/ui/sqlite/fragments/*.rs");
     }
 
     t.compile_fail("tests/ui/*.rs");
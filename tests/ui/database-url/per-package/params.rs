@@ -0,0 +1,4 @@
+fn main() {
+    // `tweet` is only in the database of `SQLX_DATABASE_URL_SQLX_TESTS`, not of `DATABASE_URL`
+    let _ = sqlx::query!("select text from tweet where id = ?", 1, 2);
+}
@@ -0,0 +1,35 @@
+fn main() {
+    let name = String::new();
+    let opt_name = Some(String::new());
+    let opt_str: Option<&str> = Some("");
+    let ids = vec![1i64];
+    let opt_ids = Some(vec![1i64]);
+
+    // references to `Option`
+    let _ = sqlx::query!("select $1::text", &opt_str);
+    let _ = sqlx::query!("select $1::text", &opt_name);
+    let _ = sqlx::query!("select $1::text", &opt_name.as_ref());
+    let _ = sqlx::query!("select $1::text", &opt_name.as_deref());
+    let _ = sqlx::query!("select $1::int8", &Some(1i64));
+
+    // `String` or `&str`
+    let _ = sqlx::query!("select $1::text", &name);
+    let _ = sqlx::query!("select $1::text", name.clone());
+    let _ = sqlx::query!("select $1::text", &&*name);
+
+    // `Vec<T>`, arrays or slices
+    let _ = sqlx::query!("select $1::int8[]", ids.clone());
+    let _ = sqlx::query!("select $1::int8[]", &ids);
+    let _ = sqlx::query!("select $1::int8[]", &ids[..]);
+    let _ = sqlx::query!("select $1::int8[]", [1i64, 2]);
+    let _ = sqlx::query!("select $1::int8[]", opt_ids.clone());
+    let _ = sqlx::query!("select $1::int8[]", &opt_ids);
+    let _ = sqlx::query!("select $1::int8[]", opt_ids.as_deref());
+    let _ = sqlx::query!("select $1::int8[]", opt_ids.as_ref());
+    let _ = sqlx::query!("select $1::bytea", &Some(vec![0u8]));
+
+    // `&str` in arrays of `text`
+    let _ = sqlx::query!("select $1::text[]", vec![""]);
+    let _ = sqlx::query!("select $1::text[]", &[""][..]);
+    let _ = sqlx::query!("select $1::text[]", vec![String::new()]);
+}
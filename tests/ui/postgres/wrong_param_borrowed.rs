@@ -0,0 +1,11 @@
+fn main() {
+    let _query = sqlx::query!("select $1::text", &Some(0i32));
+
+    let _query = sqlx::query!("select $1::int8", &Some(""));
+
+    let _query = sqlx::query!("select $1::int8[]", vec![0i32]);
+
+    let _query = sqlx::query!("select $1::int8[]", &Some(vec![""]));
+
+    let _query = sqlx::query!("select $1::text[]", vec![0i32]);
+}
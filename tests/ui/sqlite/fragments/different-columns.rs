@@ -0,0 +1,4 @@
+fn main() {
+    let columns = "id";
+    let _ = sqlx::query!("select {columns} from tweet", columns = columns in ["id", "text"]);
+}
@@ -0,0 +1,4 @@
+fn main() {
+    let order = "id";
+    let _ = sqlx::query!("select id from tweet order by {order}", order = order in ["id", "foo"]);
+}
@@ -0,0 +1,3 @@
+fn main() {
+    let _ = sqlx::query!(r#"select id as "id!!" from tweet"#);
+}
@@ -0,0 +1,7 @@
+struct Tweet {
+    id: i64,
+}
+
+fn main() {
+    let _ = sqlx::query_as!(Tweet, "select id from tweet", overrides = { id: });
+}
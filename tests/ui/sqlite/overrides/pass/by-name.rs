@@ -0,0 +1,37 @@
+// every combination of overrides by name, on the columns of `SELECT *`
+struct Tweet {
+    id: i32,
+    text: Option<String>,
+    is_sent: bool,
+    owner_id: i64,
+    name: Option<String>,
+    account_id: Option<i64>,
+    r#type: String,
+}
+
+async fn _query(conn: &mut sqlx::SqliteConnection) -> sqlx::Result<Vec<Tweet>> {
+    sqlx::query_as!(
+        Tweet,
+        r#"
+        SELECT *
+        FROM tweet
+        LEFT JOIN (SELECT id AS account_id, name, name AS type FROM accounts)
+            ON account_id = owner_id
+        WHERE id = ?
+        "#,
+        overrides = {
+            id: i32,
+            text?,
+            is_sent: _,
+            owner_id!: i64,
+            name?: Option<String>,
+            account_id?: i64,
+            type!,
+        },
+        1
+    )
+    .fetch_all(conn)
+    .await
+}
+
+fn main() {}
@@ -0,0 +1,36 @@
+// every combination of overrides in the query, on the columns of a `LEFT JOIN`
+struct Tweet {
+    id: i64,
+    not_null: String,
+    nullable: Option<String>,
+    typed: i32,
+    not_null_typed: i32,
+    nullable_typed: Option<i32>,
+    nullable_option: Option<i32>,
+    wildcard: Option<String>,
+    r#type: String,
+}
+
+async fn _query(conn: &mut sqlx::SqliteConnection) -> sqlx::Result<Vec<Tweet>> {
+    sqlx::query_as!(
+        Tweet,
+        r#"
+        SELECT
+            tweet.id,
+            accounts.name AS "not_null!",
+            accounts.name AS "nullable?",
+            accounts.id AS "typed: i32",
+            accounts.id AS "not_null_typed!: i32",
+            accounts.id AS "nullable_typed?: i32",
+            accounts.id AS "nullable_option?: Option<i32>",
+            accounts.name AS "wildcard: _",
+            accounts.name AS "type!"
+        FROM tweet
+        LEFT JOIN accounts ON accounts.id = tweet.owner_id
+        "#
+    )
+    .fetch_all(conn)
+    .await
+}
+
+fn main() {}